@@ -0,0 +1,9 @@
+-- Declarative per-project execution pipelines. When an enabled row exists, starting an attempt
+-- runs the pipeline's steps instead of the default coding agent → cleanup chain.
+CREATE TABLE project_pipelines (
+    project_id  BLOB NOT NULL PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    definition  TEXT NOT NULL,               -- JSON-serialized executors::actions::pipeline::Pipeline
+    enabled     INTEGER NOT NULL DEFAULT 1,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
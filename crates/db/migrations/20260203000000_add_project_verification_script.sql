@@ -0,0 +1,4 @@
+-- Optional verification script run after each coding agent turn. A non-zero exit sends the
+-- failure output back to the agent as a follow-up, up to verification_max_retries times.
+ALTER TABLE projects ADD COLUMN verification_script TEXT;
+ALTER TABLE projects ADD COLUMN verification_max_retries INTEGER NOT NULL DEFAULT 3;
@@ -0,0 +1,21 @@
+-- Race mode: a group of sibling task attempts started from one request, each with its own
+-- worktree and executor profile, compared side by side until a winner is promoted.
+CREATE TABLE attempt_groups (
+    id                  BLOB NOT NULL PRIMARY KEY,
+    task_id             BLOB NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    winner_attempt_id   BLOB REFERENCES task_attempts(id) ON DELETE SET NULL,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_attempt_groups_task_id ON attempt_groups(task_id);
+
+CREATE TABLE attempt_group_members (
+    task_attempt_id     BLOB NOT NULL PRIMARY KEY REFERENCES task_attempts(id) ON DELETE CASCADE,
+    attempt_group_id    BLOB NOT NULL REFERENCES attempt_groups(id) ON DELETE CASCADE,
+    executor_profile_id TEXT NOT NULL,       -- JSON-serialized executors::profile::ExecutorProfileId
+    discarded           INTEGER NOT NULL DEFAULT 0,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_attempt_group_members_group_id ON attempt_group_members(attempt_group_id);
@@ -0,0 +1,16 @@
+-- Token usage and cost reported by coding agents, one row per execution process.
+-- Each write holds the process's usage so far (summed from per-message reports for agents
+-- that send those) and replaces the previous one.
+CREATE TABLE execution_process_usage (
+    execution_process_id BLOB NOT NULL PRIMARY KEY REFERENCES execution_processes(id) ON DELETE CASCADE,
+    executor             TEXT NOT NULL,       -- executors::executors::BaseCodingAgent, e.g. 'CLAUDE_CODE'
+    input_tokens         INTEGER NOT NULL DEFAULT 0,
+    cached_input_tokens  INTEGER NOT NULL DEFAULT 0,
+    output_tokens        INTEGER NOT NULL DEFAULT 0,
+    reasoning_tokens     INTEGER NOT NULL DEFAULT 0,
+    total_cost_usd       REAL,                -- NULL unless the agent reports cost
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_execution_process_usage_executor ON execution_process_usage(executor);
@@ -0,0 +1,19 @@
+-- Token and spend limits for a project or a single task. Coding agent runs are stopped once
+-- the usage recorded in execution_process_usage reaches either limit.
+CREATE TABLE usage_budgets (
+    id            BLOB NOT NULL PRIMARY KEY,
+    project_id    BLOB REFERENCES projects(id) ON DELETE CASCADE,
+    task_id       BLOB REFERENCES tasks(id) ON DELETE CASCADE,
+    max_tokens    INTEGER,
+    max_cost_usd  REAL,
+    -- NULL counts all usage; 'day' or 'week' counts only the current day or week (Monday start)
+    period        TEXT CHECK (period IN ('day', 'week')),
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    CHECK ((project_id IS NULL) != (task_id IS NULL)),
+    CHECK (max_tokens IS NOT NULL OR max_cost_usd IS NOT NULL)
+);
+
+-- NULLs are distinct, so each index only constrains the budgets of its own scope
+CREATE UNIQUE INDEX idx_usage_budgets_project_id ON usage_budgets(project_id);
+CREATE UNIQUE INDEX idx_usage_budgets_task_id ON usage_budgets(task_id);
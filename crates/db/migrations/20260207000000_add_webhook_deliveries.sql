@@ -0,0 +1,21 @@
+-- One row per webhook delivery. Retries update the same row; manual redeliveries add a new row
+-- pointing at the delivery they resend.
+CREATE TABLE webhook_deliveries (
+    id              BLOB NOT NULL PRIMARY KEY,
+    webhook_id      BLOB NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event_type      TEXT NOT NULL,
+    request_body    TEXT NOT NULL,
+    status          TEXT NOT NULL DEFAULT 'pending'
+                       CHECK (status IN ('pending', 'succeeded', 'failed', 'dropped')),
+    attempt_count   INTEGER NOT NULL DEFAULT 0,
+    -- Outcome of the latest attempt
+    response_status INTEGER,
+    latency_ms      INTEGER,
+    error           TEXT,
+    redelivery_of   BLOB REFERENCES webhook_deliveries(id) ON DELETE SET NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_webhook_deliveries_webhook_id_created_at
+    ON webhook_deliveries(webhook_id, created_at);
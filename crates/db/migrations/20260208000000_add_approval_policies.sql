@@ -0,0 +1,39 @@
+-- Per-project rules that decide tool approvals before a human is asked. Rules are evaluated
+-- in position order and the first matching rule decides; without a match the user is asked.
+CREATE TABLE approval_policy_rules (
+    id          BLOB NOT NULL PRIMARY KEY,
+    project_id  BLOB NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    position    INTEGER NOT NULL,
+    name        TEXT NOT NULL,
+    -- Wildcard pattern for the agent's tool name; NULL matches any tool
+    tool_name   TEXT,
+    -- Kind of action the tool call performs; NULL matches any action
+    action      TEXT CHECK (action IN ('file_read', 'file_edit', 'command_run', 'web_fetch', 'search', 'tool')),
+    -- Wildcard pattern for the action's subject: file path, command or domain
+    pattern     TEXT,
+    outcome     TEXT NOT NULL CHECK (outcome IN ('allow', 'deny', 'ask')),
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    CHECK (pattern IS NULL OR action IS NOT NULL)
+);
+
+CREATE INDEX idx_approval_policy_rules_project_id ON approval_policy_rules(project_id, position);
+
+-- Audit log of how each tool approval was decided: by a rule, or by the user when no rule matched
+CREATE TABLE approval_policy_decisions (
+    id                    BLOB NOT NULL PRIMARY KEY,
+    execution_process_id  BLOB NOT NULL REFERENCES execution_processes(id) ON DELETE CASCADE,
+    -- NULL when no rule matched or the rule has since been removed
+    rule_id               BLOB REFERENCES approval_policy_rules(id) ON DELETE SET NULL,
+    -- Copy of the rule name at decision time, so the audit log survives rule edits
+    rule_name             TEXT,
+    tool_name             TEXT NOT NULL,
+    tool_call_id          TEXT NOT NULL,
+    action                TEXT,
+    subject               TEXT,
+    outcome               TEXT NOT NULL CHECK (outcome IN ('allow', 'deny', 'ask')),
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_approval_policy_decisions_execution_process_id
+    ON approval_policy_decisions(execution_process_id, created_at);
@@ -0,0 +1,27 @@
+-- Tool approvals and AskUserQuestion prompts, persisted so they survive a server restart.
+-- A resumed agent that repeats the tool call picks up the stored request and its answer.
+CREATE TABLE approval_requests (
+    -- Approval id as shown to the user
+    id                    TEXT NOT NULL PRIMARY KEY,
+    execution_process_id  BLOB NOT NULL REFERENCES execution_processes(id) ON DELETE CASCADE,
+    tool_name             TEXT NOT NULL,
+    -- Latest tool call id; replaced when a resumed agent repeats the call
+    tool_call_id          TEXT NOT NULL,
+    tool_input            TEXT NOT NULL,
+    -- JSON array of questions for AskUserQuestion prompts
+    questions             TEXT,
+    status                TEXT NOT NULL DEFAULT 'pending'
+                             CHECK (status IN ('pending', 'approved', 'denied', 'timed_out')),
+    denial_reason         TEXT,
+    -- JSON object of answers keyed by question header
+    answers               TEXT,
+    -- Whether the agent has received the response; unset for answers given after a restart
+    -- until the resumed agent asks again
+    delivered             INTEGER NOT NULL DEFAULT 0,
+    created_at            TEXT NOT NULL,
+    timeout_at            TEXT NOT NULL,
+    responded_at          TEXT
+);
+
+CREATE INDEX idx_approval_requests_execution_process_id
+    ON approval_requests(execution_process_id, status);
@@ -0,0 +1,28 @@
+-- Full-text index over normalized agent log entries: user and assistant messages, tool calls
+-- (commands, file paths, URLs, tool names and command output) and errors. An execution's
+-- entries are written once it finishes, replacing any rows from an earlier indexing.
+CREATE VIRTUAL TABLE log_search USING fts5(
+    content,
+    -- File path, URL or search query the entry acts on
+    subject,
+    execution_process_id UNINDEXED,
+    entry_index UNINDEXED,
+    entry_type UNINDEXED,
+    tool_name UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- Executions whose entries are in log_search, so startup backfill can skip them
+CREATE TABLE log_search_indexed (
+    execution_process_id  BLOB NOT NULL PRIMARY KEY
+                             REFERENCES execution_processes(id) ON DELETE CASCADE,
+    entry_count           INTEGER NOT NULL,
+    indexed_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- FTS5 tables can't take foreign keys; drop an execution's entries with it
+CREATE TRIGGER log_search_delete_execution
+AFTER DELETE ON execution_processes
+BEGIN
+    DELETE FROM log_search WHERE execution_process_id = old.id;
+END;
@@ -0,0 +1,17 @@
+-- Bearer tokens for MCP clients connecting to the /mcp endpoint over HTTP.
+-- Only a hash of each token is stored; the token itself is shown once, when it is issued.
+CREATE TABLE mcp_client_tokens (
+    id            BLOB NOT NULL PRIMARY KEY,
+    name          TEXT NOT NULL,
+    -- SHA-256 of the token, hex encoded
+    token_hash    TEXT NOT NULL UNIQUE,
+    -- First characters of the token, to tell tokens apart in the UI
+    token_prefix  TEXT NOT NULL,
+    -- JSON array of project ids the client may access; NULL allows every project
+    project_ids   TEXT,
+    access        TEXT NOT NULL DEFAULT 'read_only'
+                     CHECK (access IN ('read_only', 'read_write')),
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    last_used_at  TEXT,
+    revoked_at    TEXT
+);
@@ -0,0 +1,19 @@
+-- Asciicast v2 recordings of terminal sessions, one row per recording.
+-- The cast file itself lives on disk; this table ties it to the task attempt it was made for.
+CREATE TABLE terminal_recordings (
+    id               BLOB NOT NULL PRIMARY KEY,
+    task_attempt_id  BLOB NOT NULL REFERENCES task_attempts(id) ON DELETE CASCADE,
+    -- Terminal session that was recorded; sessions are reused per worktree
+    session_id       TEXT NOT NULL,
+    file_path        TEXT NOT NULL,
+    -- Terminal size when recording started
+    cols             INTEGER NOT NULL,
+    rows             INTEGER NOT NULL,
+    -- Set when the recording is stopped or its session ends; NULL if the server stopped first
+    duration_ms      INTEGER,
+    started_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    ended_at         TEXT
+);
+
+CREATE INDEX idx_terminal_recordings_task_attempt_id
+    ON terminal_recordings(task_attempt_id, started_at);
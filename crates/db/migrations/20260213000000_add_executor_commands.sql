@@ -0,0 +1,19 @@
+-- Shell commands coding agents ran, one row per command entry in a process's normalized log.
+-- Rows are written while the agent runs, so start times are wall-clock times the log itself
+-- does not carry.
+CREATE TABLE executor_commands (
+    execution_process_id  BLOB NOT NULL REFERENCES execution_processes(id) ON DELETE CASCADE,
+    -- Index of the command's entry in the normalized conversation
+    entry_index           INTEGER NOT NULL,
+    command               TEXT NOT NULL,
+    -- running | awaiting_approval | succeeded | failed | denied
+    status                TEXT NOT NULL,
+    -- Only set when the agent reported an exit code rather than just success or failure
+    exit_code             INTEGER,
+    -- Tail of the output, cut to a fixed size
+    output                TEXT,
+    output_truncated      INTEGER NOT NULL DEFAULT 0,
+    started_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at          TEXT,
+    PRIMARY KEY (execution_process_id, entry_index)
+);
@@ -0,0 +1,4 @@
+-- Language the project's setup, dev, cleanup, verification and pipeline scripts are written
+-- in (executors::actions::script::ScriptRequestLanguage). 'Shebang' picks the interpreter
+-- from each script's #! line.
+ALTER TABLE projects ADD COLUMN script_language TEXT NOT NULL DEFAULT 'Shebang';
@@ -93,7 +93,7 @@ impl ActivityFeed {
         ON ep.task_attempt_id = ta.id
      WHERE ta.task_id       = t.id
        AND ep.status        = 'running'
-       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','pipelinestep')
      LIMIT 1
   ) THEN 1 ELSE 0 END            AS "has_running_attempt!: i64",
 
@@ -116,7 +116,7 @@ WHERE
           ON ep.task_attempt_id = ta.id
        WHERE ta.task_id = t.id
          AND ep.status = 'running'
-         AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+         AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','pipelinestep')
     ))
     OR
     -- Completed: Done status within last 24h
@@ -100,7 +100,7 @@ impl AllTasksResponse {
         ON ep.task_attempt_id = ta.id
      WHERE ta.task_id = t.id
        AND ep.status = 'running'
-       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','pipelinestep')
      LIMIT 1
   ) THEN 1 ELSE 0 END             AS "has_in_progress_attempt!: i64",
 
@@ -0,0 +1,395 @@
+//! Approval policies: per-project rules that decide tool approvals without asking the user.
+//!
+//! Rules are evaluated in `position` order and the first matching rule decides. Every decision
+//! made while an agent runs, including the ones left to the user, is recorded as an
+//! [`ApprovalPolicyDecision`].
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalPolicyOutcome {
+    /// Approve the tool call without asking
+    Allow,
+    /// Deny the tool call without asking
+    Deny,
+    /// Ask the user, skipping any later rules
+    Ask,
+}
+
+/// Kind of action a tool call performs, as recognised from the agent's logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalPolicyAction {
+    FileRead,
+    FileEdit,
+    CommandRun,
+    WebFetch,
+    Search,
+    /// Any other tool, such as MCP tools
+    Tool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ApprovalPolicyRule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub position: i64,
+    pub name: String,
+    /// Wildcard pattern for the tool name; matches any tool when unset
+    pub tool_name: Option<String>,
+    /// Matches any action when unset
+    pub action: Option<ApprovalPolicyAction>,
+    /// Wildcard pattern for the file path, command or domain of the action
+    pub pattern: Option<String>,
+    pub outcome: ApprovalPolicyOutcome,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct ApprovalPolicyRuleInput {
+    /// Id of an existing rule to keep; new rules leave it unset
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub tool_name: Option<String>,
+    pub action: Option<ApprovalPolicyAction>,
+    pub pattern: Option<String>,
+    pub outcome: ApprovalPolicyOutcome,
+}
+
+impl ApprovalPolicyRuleInput {
+    /// Build an unsaved rule, for evaluating rules that have not been stored yet
+    pub fn to_rule(&self, project_id: Uuid, position: usize) -> ApprovalPolicyRule {
+        let now = Utc::now();
+        ApprovalPolicyRule {
+            id: self.id.unwrap_or_else(Uuid::new_v4),
+            project_id,
+            position: position as i64,
+            name: self.name.trim().to_string(),
+            tool_name: self.tool_name.clone(),
+            action: self.action,
+            pattern: self.pattern.clone(),
+            outcome: self.outcome,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// The full, ordered rule list of a project
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetApprovalPolicy {
+    pub rules: Vec<ApprovalPolicyRuleInput>,
+}
+
+impl SetApprovalPolicy {
+    /// Check that every rule is named and that patterns are only used together with an action
+    pub fn validate(&self) -> Result<(), &'static str> {
+        for rule in &self.rules {
+            if rule.name.trim().is_empty() {
+                return Err("Every approval rule needs a name");
+            }
+            if rule.pattern.is_some() && rule.action.is_none() {
+                return Err("A rule with a pattern needs an action to match the pattern against");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ApprovalPolicyDecision {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    /// Rule that decided; unset when no rule matched or the rule was removed since
+    pub rule_id: Option<Uuid>,
+    pub rule_name: Option<String>,
+    pub tool_name: String,
+    pub tool_call_id: String,
+    pub action: Option<ApprovalPolicyAction>,
+    /// File path, command or URL the tool call acted on
+    pub subject: Option<String>,
+    pub outcome: ApprovalPolicyOutcome,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct CreateApprovalPolicyDecision<'a> {
+    pub execution_process_id: Uuid,
+    pub rule: Option<&'a ApprovalPolicyRule>,
+    pub tool_name: &'a str,
+    pub tool_call_id: &'a str,
+    pub action: Option<ApprovalPolicyAction>,
+    pub subject: Option<&'a str>,
+    pub outcome: ApprovalPolicyOutcome,
+}
+
+impl ApprovalPolicyRule {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalPolicyRule,
+            r#"SELECT id AS "id!: Uuid",
+                      project_id AS "project_id!: Uuid",
+                      position,
+                      name,
+                      tool_name,
+                      action AS "action: ApprovalPolicyAction",
+                      pattern,
+                      outcome AS "outcome!: ApprovalPolicyOutcome",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM approval_policy_rules
+               WHERE project_id = $1
+               ORDER BY position ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace a project's rules with `rules`, in order. Rules whose id is kept are updated in
+    /// place so past decisions still point at them.
+    pub async fn replace_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        rules: &[ApprovalPolicyRuleInput],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let kept: Vec<Uuid> = rules.iter().filter_map(|rule| rule.id).collect();
+        let existing = sqlx::query_scalar!(
+            r#"SELECT id AS "id!: Uuid" FROM approval_policy_rules WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for id in existing.iter().filter(|id| !kept.contains(id)) {
+            sqlx::query!("DELETE FROM approval_policy_rules WHERE id = $1", id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for (position, rule) in rules.iter().enumerate() {
+            // Ids of other projects' rules are not reused; such rules are created afresh
+            let id = rule
+                .id
+                .filter(|id| existing.contains(id))
+                .unwrap_or_else(Uuid::new_v4);
+            let position = position as i64;
+            let name = rule.name.trim();
+            let tool_name = rule.tool_name.as_deref();
+            let pattern = rule.pattern.as_deref();
+            sqlx::query!(
+                r#"INSERT INTO approval_policy_rules
+                       (id, project_id, position, name, tool_name, action, pattern, outcome)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                   ON CONFLICT(id) DO UPDATE SET
+                       position = excluded.position,
+                       name = excluded.name,
+                       tool_name = excluded.tool_name,
+                       action = excluded.action,
+                       pattern = excluded.pattern,
+                       outcome = excluded.outcome,
+                       updated_at = datetime('now', 'subsec')"#,
+                id,
+                project_id,
+                position,
+                name,
+                tool_name,
+                rule.action,
+                pattern,
+                rule.outcome
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Self::find_by_project_id(pool, project_id).await
+    }
+}
+
+impl ApprovalPolicyDecision {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateApprovalPolicyDecision<'_>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let rule_id = data.rule.map(|rule| rule.id);
+        let rule_name = data.rule.map(|rule| rule.name.as_str());
+        sqlx::query_as!(
+            ApprovalPolicyDecision,
+            r#"INSERT INTO approval_policy_decisions
+                   (id, execution_process_id, rule_id, rule_name, tool_name, tool_call_id,
+                    action, subject, outcome)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id AS "id!: Uuid",
+                         execution_process_id AS "execution_process_id!: Uuid",
+                         rule_id AS "rule_id: Uuid",
+                         rule_name,
+                         tool_name,
+                         tool_call_id,
+                         action AS "action: ApprovalPolicyAction",
+                         subject,
+                         outcome AS "outcome!: ApprovalPolicyOutcome",
+                         created_at AS "created_at!: DateTime<Utc>""#,
+            id,
+            data.execution_process_id,
+            rule_id,
+            rule_name,
+            data.tool_name,
+            data.tool_call_id,
+            data.action,
+            data.subject,
+            data.outcome
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Most recent decisions first, across all executions in the project
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalPolicyDecision,
+            r#"SELECT d.id AS "id!: Uuid",
+                      d.execution_process_id AS "execution_process_id!: Uuid",
+                      d.rule_id AS "rule_id: Uuid",
+                      d.rule_name,
+                      d.tool_name,
+                      d.tool_call_id,
+                      d.action AS "action: ApprovalPolicyAction",
+                      d.subject,
+                      d.outcome AS "outcome!: ApprovalPolicyOutcome",
+                      d.created_at AS "created_at!: DateTime<Utc>"
+               FROM approval_policy_decisions d
+               JOIN execution_processes ep ON ep.id = d.execution_process_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1
+               ORDER BY d.created_at DESC
+               LIMIT $2"#,
+            project_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalPolicyDecision,
+            r#"SELECT id AS "id!: Uuid",
+                      execution_process_id AS "execution_process_id!: Uuid",
+                      rule_id AS "rule_id: Uuid",
+                      rule_name,
+                      tool_name,
+                      tool_call_id,
+                      action AS "action: ApprovalPolicyAction",
+                      subject,
+                      outcome AS "outcome!: ApprovalPolicyOutcome",
+                      created_at AS "created_at!: DateTime<Utc>"
+               FROM approval_policy_decisions
+               WHERE execution_process_id = $1
+               ORDER BY created_at ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::project::{CreateProject, Project},
+        test_utils::create_test_pool,
+    };
+
+    async fn seed_project(pool: &SqlitePool) -> Uuid {
+        let project_id = Uuid::new_v4();
+        Project::create(
+            pool,
+            &CreateProject {
+                name: "Policy Project".to_string(),
+                git_repo_path: format!("/tmp/test-repo-{project_id}"),
+                use_existing_repo: true,
+                clone_url: None,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+            },
+            project_id,
+        )
+        .await
+        .unwrap();
+        project_id
+    }
+
+    fn rule(
+        id: Option<Uuid>,
+        name: &str,
+        outcome: ApprovalPolicyOutcome,
+    ) -> ApprovalPolicyRuleInput {
+        ApprovalPolicyRuleInput {
+            id,
+            name: name.to_string(),
+            tool_name: None,
+            action: Some(ApprovalPolicyAction::CommandRun),
+            pattern: Some("git push*".to_string()),
+            outcome,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_keeps_ids_and_order() {
+        let (pool, _tmp) = create_test_pool().await;
+        let project_id = seed_project(&pool).await;
+
+        let first = ApprovalPolicyRule::replace_for_project(
+            &pool,
+            project_id,
+            &[
+                rule(None, "deny push", ApprovalPolicyOutcome::Deny),
+                rule(None, "ask rest", ApprovalPolicyOutcome::Ask),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].name, "deny push");
+
+        // Reorder, keep the first rule and drop the second
+        let second = ApprovalPolicyRule::replace_for_project(
+            &pool,
+            project_id,
+            &[
+                rule(None, "allow all", ApprovalPolicyOutcome::Allow),
+                rule(Some(first[0].id), "deny push", ApprovalPolicyOutcome::Deny),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(second.len(), 2);
+        assert_eq!(second[0].name, "allow all");
+        assert_eq!(second[1].id, first[0].id);
+        assert_eq!(second[1].position, 1);
+        assert!(second.iter().all(|r| r.id != first[1].id));
+    }
+}
@@ -0,0 +1,448 @@
+//! Tool approvals and AskUserQuestion prompts persisted across server restarts.
+//!
+//! The executor waiting for an approval lives in memory, so a restart loses it. The stored
+//! request lets the user still answer after the restart, and lets the resumed agent pick up the
+//! answer, or the still-pending request, when it repeats the same tool call.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{SqlitePool, Type};
+use utils::approvals::{ApprovalRequest, ApprovalStatus, Question};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+enum StoredApprovalStatus {
+    Pending,
+    Approved,
+    Denied,
+    TimedOut,
+}
+
+struct StoredApprovalRow {
+    id: String,
+    execution_process_id: Uuid,
+    tool_name: String,
+    tool_call_id: String,
+    tool_input: String,
+    questions: Option<String>,
+    status: StoredApprovalStatus,
+    denial_reason: Option<String>,
+    answers: Option<String>,
+    delivered: bool,
+    created_at: DateTime<Utc>,
+    timeout_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredApproval {
+    pub request: ApprovalRequest,
+    pub status: ApprovalStatus,
+    pub answers: Option<HashMap<String, String>>,
+    /// Whether the agent has received the response
+    pub delivered: bool,
+}
+
+impl From<StoredApprovalRow> for StoredApproval {
+    fn from(row: StoredApprovalRow) -> Self {
+        let status = match row.status {
+            StoredApprovalStatus::Pending => ApprovalStatus::Pending,
+            StoredApprovalStatus::Approved => ApprovalStatus::Approved,
+            StoredApprovalStatus::Denied => ApprovalStatus::Denied {
+                reason: row.denial_reason,
+            },
+            StoredApprovalStatus::TimedOut => ApprovalStatus::TimedOut,
+        };
+        let questions = row
+            .questions
+            .as_deref()
+            .and_then(|s| serde_json::from_str::<Vec<Question>>(s).ok());
+        StoredApproval {
+            request: ApprovalRequest {
+                id: row.id,
+                tool_name: row.tool_name,
+                tool_input: serde_json::from_str(&row.tool_input).unwrap_or(Value::Null),
+                tool_call_id: row.tool_call_id,
+                execution_process_id: row.execution_process_id,
+                created_at: row.created_at,
+                timeout_at: row.timeout_at,
+                questions,
+            },
+            status,
+            answers: row
+                .answers
+                .as_deref()
+                .and_then(|s| serde_json::from_str(s).ok()),
+            delivered: row.delivered,
+        }
+    }
+}
+
+impl StoredApproval {
+    pub async fn create(pool: &SqlitePool, request: &ApprovalRequest) -> Result<(), sqlx::Error> {
+        let questions = request
+            .questions
+            .as_ref()
+            .map(|q| serde_json::to_string(q).unwrap_or_else(|_| "[]".to_string()));
+        let tool_input = request.tool_input.to_string();
+        sqlx::query!(
+            r#"INSERT INTO approval_requests
+                   (id, execution_process_id, tool_name, tool_call_id, tool_input, questions,
+                    created_at, timeout_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
+            request.id,
+            request.execution_process_id,
+            request.tool_name,
+            request.tool_call_id,
+            tool_input,
+            questions,
+            request.created_at,
+            request.timeout_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            StoredApprovalRow,
+            r#"SELECT id                    as "id!",
+                      execution_process_id  as "execution_process_id!: Uuid",
+                      tool_name,
+                      tool_call_id,
+                      tool_input,
+                      questions,
+                      status                as "status!: StoredApprovalStatus",
+                      denial_reason,
+                      answers,
+                      delivered             as "delivered!: bool",
+                      created_at            as "created_at!: DateTime<Utc>",
+                      timeout_at            as "timeout_at!: DateTime<Utc>"
+                 FROM approval_requests
+                WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    /// Requests still waiting for the user, oldest first, optionally for one execution
+    pub async fn find_pending(
+        pool: &SqlitePool,
+        execution_process_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let now = Utc::now();
+        let rows = sqlx::query_as!(
+            StoredApprovalRow,
+            r#"SELECT id                    as "id!",
+                      execution_process_id  as "execution_process_id!: Uuid",
+                      tool_name,
+                      tool_call_id,
+                      tool_input,
+                      questions,
+                      status                as "status!: StoredApprovalStatus",
+                      denial_reason,
+                      answers,
+                      delivered             as "delivered!: bool",
+                      created_at            as "created_at!: DateTime<Utc>",
+                      timeout_at            as "timeout_at!: DateTime<Utc>"
+                 FROM approval_requests
+                WHERE status = 'pending'
+                  AND timeout_at > $1
+                  AND ($2 IS NULL OR execution_process_id = $2)
+                ORDER BY created_at ASC"#,
+            now,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    /// A request for the same tool call that the agent never received a response for: either
+    /// still pending, or answered while no agent was waiting
+    pub async fn find_undelivered(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        tool_name: &str,
+        tool_input: &Value,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let now = Utc::now();
+        let rows = sqlx::query_as!(
+            StoredApprovalRow,
+            r#"SELECT id                    as "id!",
+                      execution_process_id  as "execution_process_id!: Uuid",
+                      tool_name,
+                      tool_call_id,
+                      tool_input,
+                      questions,
+                      status                as "status!: StoredApprovalStatus",
+                      denial_reason,
+                      answers,
+                      delivered             as "delivered!: bool",
+                      created_at            as "created_at!: DateTime<Utc>",
+                      timeout_at            as "timeout_at!: DateTime<Utc>"
+                 FROM approval_requests
+                WHERE execution_process_id = $1
+                  AND tool_name = $2
+                  AND delivered = 0
+                  AND (status != 'pending' OR timeout_at > $3)
+                ORDER BY created_at ASC"#,
+            execution_process_id,
+            tool_name,
+            now
+        )
+        .fetch_all(pool)
+        .await?;
+        // Compare parsed inputs so key order and whitespace don't matter
+        Ok(rows
+            .into_iter()
+            .map(Self::from)
+            .find(|stored| &stored.request.tool_input == tool_input))
+    }
+
+    /// Point a pending request at the tool call of a resumed agent
+    pub async fn update_tool_call_id(
+        pool: &SqlitePool,
+        id: &str,
+        tool_call_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE approval_requests SET tool_call_id = $1 WHERE id = $2",
+            tool_call_id,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the response to a pending request. Returns false if the request was already
+    /// answered or doesn't exist.
+    pub async fn record_response(
+        pool: &SqlitePool,
+        id: &str,
+        status: &ApprovalStatus,
+        answers: Option<&HashMap<String, String>>,
+        delivered: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let (status, denial_reason) = match status {
+            ApprovalStatus::Pending => return Ok(false),
+            ApprovalStatus::Approved => (StoredApprovalStatus::Approved, None),
+            ApprovalStatus::Denied { reason } => (StoredApprovalStatus::Denied, reason.as_deref()),
+            ApprovalStatus::TimedOut => (StoredApprovalStatus::TimedOut, None),
+        };
+        let answers = answers.map(|a| serde_json::to_string(a).unwrap_or_else(|_| "{}".into()));
+        let result = sqlx::query!(
+            r#"UPDATE approval_requests
+               SET status = $1, denial_reason = $2, answers = $3, delivered = $4,
+                   responded_at = datetime('now', 'subsec')
+               WHERE id = $5 AND status = 'pending'"#,
+            status,
+            denial_reason,
+            answers,
+            delivered,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn mark_delivered(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE approval_requests SET delivered = 1 WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Close requests no agent can receive anymore: those of processes that are no longer
+    /// running, and pending ones past their timeout. Returns the number of requests closed.
+    pub async fn close_stale(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let exited = sqlx::query!(
+            r#"UPDATE approval_requests
+               SET status = CASE WHEN status = 'pending' THEN 'denied' ELSE status END,
+                   denial_reason = CASE WHEN status = 'pending' THEN 'Process exited'
+                                        ELSE denial_reason END,
+                   delivered = 1
+               WHERE delivered = 0
+                 AND execution_process_id IN
+                     (SELECT id FROM execution_processes WHERE status != 'running')"#
+        )
+        .execute(pool)
+        .await?;
+        let now = Utc::now();
+        let timed_out = sqlx::query!(
+            r#"UPDATE approval_requests
+               SET status = 'timed_out', delivered = 1
+               WHERE status = 'pending' AND timeout_at <= $1"#,
+            now
+        )
+        .execute(pool)
+        .await?;
+        Ok(exited.rows_affected() + timed_out.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::{
+        actions::{
+            ExecutorAction, ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest,
+        },
+        executors::BaseCodingAgent,
+        profile::ExecutorProfileId,
+    };
+    use serde_json::json;
+    use utils::approvals::CreateApprovalRequest;
+
+    use super::*;
+    use crate::{
+        models::{
+            execution_process::{
+                CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason,
+            },
+            project::{CreateProject, Project},
+            task::{CreateTask, Task},
+            task_attempt::{CreateTaskAttempt, TaskAttempt},
+        },
+        test_utils::create_test_pool,
+    };
+
+    fn request(execution_process_id: Uuid, tool_call_id: &str) -> ApprovalRequest {
+        ApprovalRequest::from_create(
+            CreateApprovalRequest {
+                tool_name: "Bash".to_string(),
+                tool_input: json!({ "command": "git push", "description": "Push" }),
+                tool_call_id: tool_call_id.to_string(),
+            },
+            execution_process_id,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_answer_after_restart_is_picked_up_once() {
+        let (pool, _tmp) = create_test_pool().await;
+        let exec_id = seed_execution_process(&pool).await;
+
+        let original = request(exec_id, "call-1");
+        StoredApproval::create(&pool, &original).await.unwrap();
+        let pending = StoredApproval::find_pending(&pool, Some(exec_id))
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+
+        // Answered after the restart, while no agent is waiting
+        assert!(
+            StoredApproval::record_response(
+                &pool,
+                &original.id,
+                &ApprovalStatus::Approved,
+                None,
+                false
+            )
+            .await
+            .unwrap()
+        );
+        assert!(
+            !StoredApproval::record_response(
+                &pool,
+                &original.id,
+                &ApprovalStatus::TimedOut,
+                None,
+                false
+            )
+            .await
+            .unwrap()
+        );
+
+        // The resumed agent repeats the call with the keys in another order
+        let input = json!({ "description": "Push", "command": "git push" });
+        let found = StoredApproval::find_undelivered(&pool, exec_id, "Bash", &input)
+            .await
+            .unwrap()
+            .expect("stored answer should match the repeated call");
+        assert_eq!(found.request.id, original.id);
+        assert!(matches!(found.status, ApprovalStatus::Approved));
+
+        StoredApproval::mark_delivered(&pool, &found.request.id)
+            .await
+            .unwrap();
+        assert!(
+            StoredApproval::find_undelivered(&pool, exec_id, "Bash", &input)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    async fn seed_execution_process(pool: &SqlitePool) -> Uuid {
+        let project_id = Uuid::new_v4();
+        Project::create(
+            pool,
+            &CreateProject {
+                name: "Approvals Project".to_string(),
+                git_repo_path: format!("/tmp/test-repo-{project_id}"),
+                use_existing_repo: true,
+                clone_url: None,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+            },
+            project_id,
+        )
+        .await
+        .unwrap();
+        let task_id = Uuid::new_v4();
+        Task::create(
+            pool,
+            &CreateTask::from_title_description(project_id, "Task".to_string(), None),
+            task_id,
+        )
+        .await
+        .unwrap();
+        let attempt_id = Uuid::new_v4();
+        TaskAttempt::create(
+            pool,
+            &CreateTaskAttempt {
+                executor: BaseCodingAgent::ClaudeCode,
+                base_branch: "main".to_string(),
+                branch: format!("approvals-{attempt_id}"),
+                origin_node_id: None,
+            },
+            attempt_id,
+            task_id,
+        )
+        .await
+        .unwrap();
+        let exec_id = Uuid::new_v4();
+        ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                task_attempt_id: attempt_id,
+                executor_action: ExecutorAction::new(
+                    ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                        prompt: "Push the branch".to_string(),
+                        executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+                    }),
+                    None,
+                ),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+            },
+            exec_id,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        exec_id
+    }
+}
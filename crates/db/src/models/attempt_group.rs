@@ -0,0 +1,295 @@
+//! Race mode attempt groups.
+//!
+//! An attempt group ties together sibling [`TaskAttempt`](super::task_attempt::TaskAttempt)s
+//! started from a single request, one per executor profile, so their results can be compared
+//! side by side and a winner promoted.
+
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AttemptGroup {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    /// The promoted attempt, once a winner has been chosen
+    pub winner_attempt_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct AttemptGroupMemberRow {
+    task_attempt_id: Uuid,
+    attempt_group_id: Uuid,
+    executor_profile_id: String,
+    discarded: bool,
+    created_at: DateTime<Utc>,
+}
+
+/// One attempt of a race, with the exact profile it was started with
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AttemptGroupMember {
+    pub task_attempt_id: Uuid,
+    pub attempt_group_id: Uuid,
+    pub executor_profile_id: ExecutorProfileId,
+    /// True once the attempt lost and its worktree was cleaned up
+    pub discarded: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<AttemptGroupMemberRow> for AttemptGroupMember {
+    type Error = sqlx::Error;
+
+    fn try_from(row: AttemptGroupMemberRow) -> Result<Self, Self::Error> {
+        let executor_profile_id = serde_json::from_str(&row.executor_profile_id)
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        Ok(Self {
+            task_attempt_id: row.task_attempt_id,
+            attempt_group_id: row.attempt_group_id,
+            executor_profile_id,
+            discarded: row.discarded,
+            created_at: row.created_at,
+        })
+    }
+}
+
+impl AttemptGroup {
+    pub async fn create(pool: &SqlitePool, id: Uuid, task_id: Uuid) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"INSERT INTO attempt_groups (id, task_id)
+               VALUES ($1, $2)
+               RETURNING id AS "id!: Uuid",
+                         task_id AS "task_id!: Uuid",
+                         winner_attempt_id AS "winner_attempt_id: Uuid",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"SELECT id AS "id!: Uuid",
+                      task_id AS "task_id!: Uuid",
+                      winner_attempt_id AS "winner_attempt_id: Uuid",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM attempt_groups
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"SELECT id AS "id!: Uuid",
+                      task_id AS "task_id!: Uuid",
+                      winner_attempt_id AS "winner_attempt_id: Uuid",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM attempt_groups
+               WHERE task_id = $1
+               ORDER BY created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn add_member(
+        pool: &SqlitePool,
+        group_id: Uuid,
+        task_attempt_id: Uuid,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Result<AttemptGroupMember, sqlx::Error> {
+        let profile = serde_json::to_string(executor_profile_id).map_err(|e| {
+            sqlx::Error::Protocol(format!("failed to serialize executor profile: {e}"))
+        })?;
+        sqlx::query_as!(
+            AttemptGroupMemberRow,
+            r#"INSERT INTO attempt_group_members (task_attempt_id, attempt_group_id, executor_profile_id)
+               VALUES ($1, $2, $3)
+               RETURNING task_attempt_id AS "task_attempt_id!: Uuid",
+                         attempt_group_id AS "attempt_group_id!: Uuid",
+                         executor_profile_id,
+                         discarded AS "discarded!: bool",
+                         created_at AS "created_at!: DateTime<Utc>""#,
+            task_attempt_id,
+            group_id,
+            profile
+        )
+        .fetch_one(pool)
+        .await?
+        .try_into()
+    }
+
+    pub async fn members(
+        pool: &SqlitePool,
+        group_id: Uuid,
+    ) -> Result<Vec<AttemptGroupMember>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroupMemberRow,
+            r#"SELECT task_attempt_id AS "task_attempt_id!: Uuid",
+                      attempt_group_id AS "attempt_group_id!: Uuid",
+                      executor_profile_id,
+                      discarded AS "discarded!: bool",
+                      created_at AS "created_at!: DateTime<Utc>"
+               FROM attempt_group_members
+               WHERE attempt_group_id = $1
+               ORDER BY created_at ASC"#,
+            group_id
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(AttemptGroupMember::try_from)
+        .collect()
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM attempt_groups WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Record `winner_attempt_id` as the winner and mark every other member discarded.
+    pub async fn promote(
+        pool: &SqlitePool,
+        group_id: Uuid,
+        winner_attempt_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"UPDATE attempt_group_members
+               SET discarded = (task_attempt_id != $1)
+               WHERE attempt_group_id = $2"#,
+            winner_attempt_id,
+            group_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        let group = sqlx::query_as!(
+            AttemptGroup,
+            r#"UPDATE attempt_groups
+               SET winner_attempt_id = $1,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $2
+               RETURNING id AS "id!: Uuid",
+                         task_id AS "task_id!: Uuid",
+                         winner_attempt_id AS "winner_attempt_id: Uuid",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            winner_attempt_id,
+            group_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+    use crate::{
+        models::{
+            project::{CreateProject, Project},
+            task::{CreateTask, Task},
+            task_attempt::{CreateTaskAttempt, TaskAttempt},
+        },
+        test_utils::create_test_pool,
+    };
+
+    #[tokio::test]
+    async fn test_promote_discards_other_members() {
+        let (pool, _tmp) = create_test_pool().await;
+        let project_id = Uuid::new_v4();
+        Project::create(
+            &pool,
+            &CreateProject {
+                name: "Race Project".to_string(),
+                git_repo_path: format!("/tmp/test-repo-{project_id}"),
+                use_existing_repo: true,
+                clone_url: None,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+            },
+            project_id,
+        )
+        .await
+        .unwrap();
+        let task_id = Uuid::new_v4();
+        Task::create(
+            &pool,
+            &CreateTask::from_title_description(project_id, "Race".to_string(), None),
+            task_id,
+        )
+        .await
+        .unwrap();
+
+        let group = AttemptGroup::create(&pool, Uuid::new_v4(), task_id)
+            .await
+            .unwrap();
+        let mut attempt_ids = Vec::new();
+        for executor in [BaseCodingAgent::ClaudeCode, BaseCodingAgent::Codex] {
+            let attempt_id = Uuid::new_v4();
+            TaskAttempt::create(
+                &pool,
+                &CreateTaskAttempt {
+                    executor,
+                    base_branch: "main".to_string(),
+                    branch: format!("race-{attempt_id}"),
+                    origin_node_id: None,
+                },
+                attempt_id,
+                task_id,
+            )
+            .await
+            .unwrap();
+            AttemptGroup::add_member(
+                &pool,
+                group.id,
+                attempt_id,
+                &ExecutorProfileId::new(executor),
+            )
+            .await
+            .unwrap();
+            attempt_ids.push(attempt_id);
+        }
+
+        let group = AttemptGroup::promote(&pool, group.id, attempt_ids[1])
+            .await
+            .unwrap();
+        assert_eq!(group.winner_attempt_id, Some(attempt_ids[1]));
+
+        let members = AttemptGroup::members(&pool, group.id).await.unwrap();
+        assert_eq!(members.len(), 2);
+        assert!(members[0].discarded);
+        assert!(!members[1].discarded);
+        assert_eq!(
+            members[1].executor_profile_id,
+            ExecutorProfileId::new(BaseCodingAgent::Codex)
+        );
+    }
+}
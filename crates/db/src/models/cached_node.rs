@@ -97,6 +97,9 @@ pub struct CachedNodeCapabilities {
     /// Vibe Kanban version running on the node
     #[serde(default)]
     pub version: String,
+    /// Labels tasks can require of the node when dispatched by the hive
+    #[serde(default)]
+    pub labels: Vec<String>,
 }
 
 fn default_max_concurrent() -> i32 {
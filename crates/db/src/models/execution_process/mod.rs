@@ -57,6 +57,10 @@ pub enum ExecutionProcessRunReason {
     CleanupScript,
     CodingAgent,
     DevServer,
+    /// A script step of a project pipeline, or the project's verification script
+    PipelineStep,
+    /// A command from an attempt's command timeline, run again on request
+    CommandRerun,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -0,0 +1,457 @@
+//! Token usage and cost accounting for execution processes.
+//!
+//! Coding agents report token counts (and, for some agents, cost) in their normalized log
+//! stream, either for the run so far or per message. `services::usage` adds them up into the
+//! process's usage so far, which is persisted here so usage can be rolled up per task, project
+//! and executor without replaying logs.
+
+use std::ops::{Add, AddAssign};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ExecutionProcessUsage {
+    pub execution_process_id: Uuid,
+    /// Base coding agent that ran the process, e.g. `CLAUDE_CODE`
+    pub executor: String,
+    pub input_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub output_tokens: i64,
+    pub reasoning_tokens: i64,
+    /// Only reported by some agents
+    pub total_cost_usd: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Token counts of a process, or of one snapshot reported by its agent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenCounts {
+    pub input_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub output_tokens: i64,
+    pub reasoning_tokens: i64,
+}
+
+impl Add for TokenCounts {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign for TokenCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.input_tokens += other.input_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "usage_period", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum UsagePeriod {
+    Day,
+    /// Weeks start on Monday
+    Week,
+}
+
+impl UsagePeriod {
+    /// SQL expression for the first day of the bucket containing `timestamp`
+    pub(crate) fn bucket_start_sql(self, timestamp: &str) -> String {
+        match self {
+            Self::Day => format!("date({timestamp})"),
+            // 'weekday 0' moves forward to Sunday (or stays on it); six days back is Monday
+            Self::Week => format!("date({timestamp}, 'weekday 0', '-6 days')"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageGroupBy {
+    Executor,
+    Project,
+    Task,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UsageFilter {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub executor: Option<String>,
+    /// Only processes started at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only processes started before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Only tasks with at least one attempt merged directly or through a merged PR
+    pub merged_only: bool,
+}
+
+/// Usage summed over one group, optionally within one day or week.
+///
+/// Only the key matching the requested grouping is set.
+#[derive(Debug, Clone, PartialEq, Serialize, TS, FromRow)]
+pub struct UsageRollup {
+    /// First day of the bucket as `YYYY-MM-DD`; unset for all-time totals
+    pub period_start: Option<String>,
+    pub executor: Option<String>,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub process_count: i64,
+    pub input_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub output_tokens: i64,
+    pub reasoning_tokens: i64,
+    /// Sum over the processes that reported a cost; unset when none did
+    pub total_cost_usd: Option<f64>,
+}
+
+impl ExecutionProcessUsage {
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessUsage,
+            r#"SELECT execution_process_id AS "execution_process_id!: Uuid",
+                      executor,
+                      input_tokens AS "input_tokens!: i64",
+                      cached_input_tokens AS "cached_input_tokens!: i64",
+                      output_tokens AS "output_tokens!: i64",
+                      reasoning_tokens AS "reasoning_tokens!: i64",
+                      total_cost_usd,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM execution_process_usage
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store a process's token usage so far, replacing the previous figures.
+    ///
+    /// The executor is taken from the process's action, falling back to the attempt's executor.
+    pub async fn record_tokens(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        tokens: TokenCounts,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO execution_process_usage
+                   (execution_process_id, executor, input_tokens, cached_input_tokens,
+                    output_tokens, reasoning_tokens)
+               SELECT ep.id,
+                      COALESCE(json_extract(ep.executor_action, '$.typ.executor_profile_id.executor'), ta.executor),
+                      $1, $2, $3, $4
+               FROM execution_processes ep
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               WHERE ep.id = $5
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                   input_tokens = excluded.input_tokens,
+                   cached_input_tokens = excluded.cached_input_tokens,
+                   output_tokens = excluded.output_tokens,
+                   reasoning_tokens = excluded.reasoning_tokens,
+                   updated_at = datetime('now', 'subsec')"#,
+            tokens.input_tokens,
+            tokens.cached_input_tokens,
+            tokens.output_tokens,
+            tokens.reasoning_tokens,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Store the total cost the agent reported for a process.
+    pub async fn record_cost(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        total_cost_usd: f64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO execution_process_usage (execution_process_id, executor, total_cost_usd)
+               SELECT ep.id,
+                      COALESCE(json_extract(ep.executor_action, '$.typ.executor_profile_id.executor'), ta.executor),
+                      $1
+               FROM execution_processes ep
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               WHERE ep.id = $2
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                   total_cost_usd = excluded.total_cost_usd,
+                   updated_at = datetime('now', 'subsec')"#,
+            total_cost_usd,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Sum usage by `group_by`, bucketed by the day or week each process started in when
+    /// `period` is set. Buckets are returned newest first.
+    pub async fn rollup(
+        pool: &SqlitePool,
+        period: Option<UsagePeriod>,
+        group_by: UsageGroupBy,
+        filter: &UsageFilter,
+    ) -> Result<Vec<UsageRollup>, sqlx::Error> {
+        let period_expr = period
+            .map(|period| period.bucket_start_sql("ep.started_at"))
+            .unwrap_or_else(|| "NULL".to_string());
+        let (executor_expr, project_expr, task_expr, key_expr) = match group_by {
+            UsageGroupBy::Executor => ("u.executor", "NULL", "NULL", "u.executor"),
+            UsageGroupBy::Project => ("NULL", "t.project_id", "NULL", "t.project_id"),
+            UsageGroupBy::Task => ("NULL", "t.project_id", "t.id", "t.id"),
+        };
+
+        let mut query = QueryBuilder::<Sqlite>::new(format!(
+            r#"SELECT {period_expr} AS period_start,
+                      {executor_expr} AS executor,
+                      {project_expr} AS project_id,
+                      {task_expr} AS task_id,
+                      COUNT(*) AS process_count,
+                      COALESCE(SUM(u.input_tokens), 0) AS input_tokens,
+                      COALESCE(SUM(u.cached_input_tokens), 0) AS cached_input_tokens,
+                      COALESCE(SUM(u.output_tokens), 0) AS output_tokens,
+                      COALESCE(SUM(u.reasoning_tokens), 0) AS reasoning_tokens,
+                      SUM(u.total_cost_usd) AS total_cost_usd
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE 1 = 1"#
+        ));
+        if let Some(project_id) = filter.project_id {
+            query.push(" AND t.project_id = ").push_bind(project_id);
+        }
+        if let Some(task_id) = filter.task_id {
+            query.push(" AND t.id = ").push_bind(task_id);
+        }
+        if let Some(executor) = &filter.executor {
+            query.push(" AND u.executor = ").push_bind(executor.clone());
+        }
+        if let Some(since) = filter.since {
+            query
+                .push(" AND datetime(ep.started_at) >= datetime(")
+                .push_bind(since.to_rfc3339())
+                .push(")");
+        }
+        if let Some(until) = filter.until {
+            query
+                .push(" AND datetime(ep.started_at) < datetime(")
+                .push_bind(until.to_rfc3339())
+                .push(")");
+        }
+        if filter.merged_only {
+            query.push(
+                r#" AND EXISTS (
+                       SELECT 1 FROM merges m
+                       JOIN task_attempts mta ON mta.id = m.task_attempt_id
+                       WHERE mta.task_id = t.id
+                         AND (m.merge_type = 'direct' OR m.pr_status = 'merged'))"#,
+            );
+        }
+        query.push(format!(
+            " GROUP BY period_start, {key_expr} ORDER BY period_start DESC, process_count DESC"
+        ));
+
+        query.build_query_as::<UsageRollup>().fetch_all(pool).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::{
+        actions::{
+            ExecutorAction, ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest,
+        },
+        executors::BaseCodingAgent,
+        profile::ExecutorProfileId,
+    };
+
+    use super::*;
+    use crate::{
+        models::{
+            execution_process::{
+                CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason,
+            },
+            project::{CreateProject, Project},
+            task::{CreateTask, Task},
+            task_attempt::{CreateTaskAttempt, TaskAttempt},
+        },
+        test_utils::create_test_pool,
+    };
+
+    async fn create_agent_process(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        executor: BaseCodingAgent,
+    ) -> Uuid {
+        let attempt_id = Uuid::new_v4();
+        TaskAttempt::create(
+            pool,
+            &CreateTaskAttempt {
+                executor,
+                base_branch: "main".to_string(),
+                branch: format!("usage-{attempt_id}"),
+                origin_node_id: None,
+            },
+            attempt_id,
+            task_id,
+        )
+        .await
+        .unwrap();
+        let process_id = Uuid::new_v4();
+        ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                task_attempt_id: attempt_id,
+                executor_action: ExecutorAction::new(
+                    ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                        prompt: "Do the thing".to_string(),
+                        executor_profile_id: ExecutorProfileId::new(executor),
+                    }),
+                    None,
+                ),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+            },
+            process_id,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        process_id
+    }
+
+    #[tokio::test]
+    async fn test_snapshots_replace_and_roll_up_by_executor() {
+        let (pool, _tmp) = create_test_pool().await;
+        let project_id = Uuid::new_v4();
+        Project::create(
+            &pool,
+            &CreateProject {
+                name: "Usage Project".to_string(),
+                git_repo_path: format!("/tmp/test-repo-{project_id}"),
+                use_existing_repo: true,
+                clone_url: None,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+            },
+            project_id,
+        )
+        .await
+        .unwrap();
+        let task_id = Uuid::new_v4();
+        Task::create(
+            &pool,
+            &CreateTask::from_title_description(project_id, "Usage".to_string(), None),
+            task_id,
+        )
+        .await
+        .unwrap();
+
+        let claude = create_agent_process(&pool, task_id, BaseCodingAgent::ClaudeCode).await;
+        let codex = create_agent_process(&pool, task_id, BaseCodingAgent::Codex).await;
+
+        // Each call stores the usage so far, so the second one replaces the first
+        for input_tokens in [100, 250] {
+            ExecutionProcessUsage::record_tokens(
+                &pool,
+                claude,
+                TokenCounts {
+                    input_tokens,
+                    output_tokens: 40,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+        ExecutionProcessUsage::record_cost(&pool, claude, 0.5)
+            .await
+            .unwrap();
+        ExecutionProcessUsage::record_tokens(
+            &pool,
+            codex,
+            TokenCounts {
+                input_tokens: 1000,
+                cached_input_tokens: 600,
+                output_tokens: 200,
+                reasoning_tokens: 50,
+            },
+        )
+        .await
+        .unwrap();
+
+        let usage = ExecutionProcessUsage::find_by_execution_process_id(&pool, claude)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(usage.executor, "CLAUDE_CODE");
+        assert_eq!(usage.input_tokens, 250);
+        assert_eq!(usage.total_cost_usd, Some(0.5));
+
+        let by_executor = ExecutionProcessUsage::rollup(
+            &pool,
+            Some(UsagePeriod::Week),
+            UsageGroupBy::Executor,
+            &UsageFilter::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(by_executor.len(), 2);
+        let codex_row = by_executor
+            .iter()
+            .find(|row| row.executor.as_deref() == Some("CODEX"))
+            .unwrap();
+        assert_eq!(codex_row.input_tokens, 1000);
+        assert_eq!(codex_row.total_cost_usd, None);
+        assert!(codex_row.period_start.is_some());
+
+        let by_task = ExecutionProcessUsage::rollup(
+            &pool,
+            None,
+            UsageGroupBy::Task,
+            &UsageFilter {
+                project_id: Some(project_id),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(by_task.len(), 1);
+        assert_eq!(by_task[0].task_id, Some(task_id));
+        assert_eq!(by_task[0].process_count, 2);
+        assert_eq!(by_task[0].input_tokens, 1250);
+        assert_eq!(by_task[0].total_cost_usd, Some(0.5));
+
+        // No attempt of the task has been merged
+        let merged = ExecutionProcessUsage::rollup(
+            &pool,
+            None,
+            UsageGroupBy::Task,
+            &UsageFilter {
+                merged_only: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(merged.is_empty());
+    }
+}
@@ -0,0 +1,304 @@
+//! Shell commands coding agents ran, as reported by their normalized log stream.
+//!
+//! Normalized `CommandRun` entries carry the command and, once it finished, its exit status and
+//! output, but not when it ran. Rows here are written as the entries appear and change, so each
+//! command also gets a wall-clock start and completion time.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CommandRunStatus {
+    Running,
+    /// Waiting for the user to approve it or answer a question first
+    AwaitingApproval,
+    Succeeded,
+    Failed,
+    /// Rejected by the user, an approval policy or an approval timeout
+    Denied,
+}
+
+impl CommandRunStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Denied)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ExecutorCommand {
+    pub execution_process_id: Uuid,
+    /// Index of the command's entry in the process's normalized conversation
+    pub entry_index: i64,
+    pub command: String,
+    pub status: CommandRunStatus,
+    /// Only set when the agent reported an exit code rather than just success or failure
+    pub exit_code: Option<i64>,
+    /// Tail of the output, cut to a fixed size
+    pub output: Option<String>,
+    pub output_truncated: bool,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// The state of a command entry as last seen in the log stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutorCommandUpdate {
+    pub entry_index: i64,
+    pub command: String,
+    pub status: CommandRunStatus,
+    pub exit_code: Option<i64>,
+    pub output: Option<String>,
+    pub output_truncated: bool,
+}
+
+impl ExecutorCommand {
+    pub fn duration_ms(&self) -> Option<i64> {
+        self.completed_at
+            .map(|completed_at| (completed_at - self.started_at).num_milliseconds().max(0))
+    }
+
+    /// Insert or update a command entry.
+    ///
+    /// A command is timed from when it first appears, or from when it stops awaiting approval,
+    /// until the first update that finishes it.
+    pub async fn record(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        update: &ExecutorCommandUpdate,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        let completed_at = update.status.is_finished().then_some(now);
+        sqlx::query!(
+            r#"INSERT INTO executor_commands
+                   (execution_process_id, entry_index, command, status, exit_code, output,
+                    output_truncated, started_at, completed_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               ON CONFLICT(execution_process_id, entry_index) DO UPDATE SET
+                   command = excluded.command,
+                   status = excluded.status,
+                   exit_code = excluded.exit_code,
+                   output = excluded.output,
+                   output_truncated = excluded.output_truncated,
+                   started_at = CASE
+                       WHEN executor_commands.status = 'awaiting_approval'
+                            AND excluded.status != 'awaiting_approval'
+                       THEN excluded.started_at
+                       ELSE executor_commands.started_at
+                   END,
+                   completed_at = COALESCE(executor_commands.completed_at, excluded.completed_at)"#,
+            execution_process_id,
+            update.entry_index,
+            update.command,
+            update.status,
+            update.exit_code,
+            update.output,
+            update.output_truncated,
+            now,
+            completed_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        entry_index: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutorCommand,
+            r#"SELECT execution_process_id  as "execution_process_id!: Uuid",
+                      entry_index           as "entry_index!: i64",
+                      command,
+                      status                as "status!: CommandRunStatus",
+                      exit_code,
+                      output,
+                      output_truncated      as "output_truncated!: bool",
+                      started_at            as "started_at!: DateTime<Utc>",
+                      completed_at          as "completed_at: DateTime<Utc>"
+                 FROM executor_commands
+                WHERE execution_process_id = $1 AND entry_index = $2"#,
+            execution_process_id,
+            entry_index
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Commands of one process in the order the agent issued them
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutorCommand,
+            r#"SELECT execution_process_id  as "execution_process_id!: Uuid",
+                      entry_index           as "entry_index!: i64",
+                      command,
+                      status                as "status!: CommandRunStatus",
+                      exit_code,
+                      output,
+                      output_truncated      as "output_truncated!: bool",
+                      started_at            as "started_at!: DateTime<Utc>",
+                      completed_at          as "completed_at: DateTime<Utc>"
+                 FROM executor_commands
+                WHERE execution_process_id = $1
+                ORDER BY entry_index"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::{
+        actions::{
+            ExecutorAction, ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest,
+        },
+        executors::BaseCodingAgent,
+        profile::ExecutorProfileId,
+    };
+
+    use super::*;
+    use crate::{
+        models::{
+            execution_process::{
+                CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason,
+            },
+            project::{CreateProject, Project},
+            task::{CreateTask, Task},
+            task_attempt::{CreateTaskAttempt, TaskAttempt},
+        },
+        test_utils::create_test_pool,
+    };
+
+    async fn seed_process(pool: &SqlitePool) -> Uuid {
+        let project_id = Uuid::new_v4();
+        Project::create(
+            pool,
+            &CreateProject {
+                name: "Commands Project".to_string(),
+                git_repo_path: format!("/tmp/test-repo-{project_id}"),
+                use_existing_repo: true,
+                clone_url: None,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+            },
+            project_id,
+        )
+        .await
+        .unwrap();
+        let task_id = Uuid::new_v4();
+        Task::create(
+            pool,
+            &CreateTask::from_title_description(project_id, "Task".to_string(), None),
+            task_id,
+        )
+        .await
+        .unwrap();
+        let attempt_id = Uuid::new_v4();
+        TaskAttempt::create(
+            pool,
+            &CreateTaskAttempt {
+                executor: BaseCodingAgent::ClaudeCode,
+                base_branch: "main".to_string(),
+                branch: format!("commands-{attempt_id}"),
+                origin_node_id: None,
+            },
+            attempt_id,
+            task_id,
+        )
+        .await
+        .unwrap();
+        let process_id = Uuid::new_v4();
+        ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                task_attempt_id: attempt_id,
+                executor_action: ExecutorAction::new(
+                    ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                        prompt: "Run the tests".to_string(),
+                        executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+                    }),
+                    None,
+                ),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+            },
+            process_id,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        process_id
+    }
+
+    fn update(status: CommandRunStatus, exit_code: Option<i64>) -> ExecutorCommandUpdate {
+        ExecutorCommandUpdate {
+            entry_index: 3,
+            command: "cargo test".to_string(),
+            status,
+            exit_code,
+            output: exit_code.map(|_| "test result: ok".to_string()),
+            output_truncated: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_command_is_timed_until_first_finish() {
+        let (pool, _tmp) = create_test_pool().await;
+        let process_id = seed_process(&pool).await;
+
+        ExecutorCommand::record(&pool, process_id, &update(CommandRunStatus::Running, None))
+            .await
+            .unwrap();
+        let running = ExecutorCommand::find(&pool, process_id, 3)
+            .await
+            .unwrap()
+            .expect("recorded command");
+        assert_eq!(running.status, CommandRunStatus::Running);
+        assert!(running.completed_at.is_none());
+        assert_eq!(running.duration_ms(), None);
+
+        ExecutorCommand::record(
+            &pool,
+            process_id,
+            &update(CommandRunStatus::Succeeded, Some(0)),
+        )
+        .await
+        .unwrap();
+        let finished = ExecutorCommand::find(&pool, process_id, 3)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(finished.started_at, running.started_at);
+        assert_eq!(finished.exit_code, Some(0));
+        assert_eq!(finished.output.as_deref(), Some("test result: ok"));
+        let completed_at = finished.completed_at.expect("completion time");
+        assert!(finished.duration_ms().is_some_and(|ms| ms >= 0));
+
+        // Replaying the finished entry keeps the original completion time
+        ExecutorCommand::record(
+            &pool,
+            process_id,
+            &update(CommandRunStatus::Succeeded, Some(0)),
+        )
+        .await
+        .unwrap();
+        let listed = ExecutorCommand::find_by_execution_process_id(&pool, process_id)
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].completed_at, Some(completed_at));
+    }
+}
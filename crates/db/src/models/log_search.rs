@@ -0,0 +1,550 @@
+//! Full-text search over normalized agent log entries.
+//!
+//! Normalized entries are only kept as patches in the process logs, so an execution's messages,
+//! tool calls and errors are copied into the `log_search` FTS5 table once it finishes. Hits link
+//! back to the execution and the entry's index in its normalized conversation.
+
+use chrono::{DateTime, Utc};
+use executors::logs::{ActionType, NormalizedEntry, NormalizedEntryType};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcessStatus;
+
+/// Longest command output or tool result kept per entry, in characters
+const MAX_OUTPUT_CHARS: usize = 4000;
+
+/// Kinds of normalized entries that are indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LogSearchEntryType {
+    UserMessage,
+    /// Feedback given when denying a tool call
+    UserFeedback,
+    AssistantMessage,
+    ToolUse,
+    ErrorMessage,
+}
+
+/// The searchable text of one normalized entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogSearchDocument {
+    pub entry_index: i64,
+    pub entry_type: LogSearchEntryType,
+    pub tool_name: Option<String>,
+    /// File path, URL or search query the entry acts on
+    pub subject: Option<String>,
+    pub content: String,
+}
+
+impl LogSearchDocument {
+    /// Searchable text of `entry`, or `None` for entries that aren't indexed (thinking, token
+    /// usage, system messages and the like)
+    pub fn from_entry(entry_index: usize, entry: &NormalizedEntry) -> Option<Self> {
+        let entry_index = entry_index as i64;
+        let (entry_type, tool_name, subject, content) = match &entry.entry_type {
+            NormalizedEntryType::UserMessage => (
+                LogSearchEntryType::UserMessage,
+                None,
+                None,
+                entry.content.clone(),
+            ),
+            NormalizedEntryType::UserFeedback { denied_tool } => (
+                LogSearchEntryType::UserFeedback,
+                Some(denied_tool.clone()),
+                None,
+                entry.content.clone(),
+            ),
+            NormalizedEntryType::AssistantMessage => (
+                LogSearchEntryType::AssistantMessage,
+                None,
+                None,
+                entry.content.clone(),
+            ),
+            NormalizedEntryType::ErrorMessage { .. } => (
+                LogSearchEntryType::ErrorMessage,
+                None,
+                None,
+                entry.content.clone(),
+            ),
+            NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type,
+                ..
+            } => {
+                let (subject, details) = action_text(action_type);
+                let mut content = entry.content.clone();
+                for detail in details {
+                    content.push('\n');
+                    content.push_str(&detail);
+                }
+                (
+                    LogSearchEntryType::ToolUse,
+                    Some(tool_name.clone()),
+                    subject,
+                    content,
+                )
+            }
+            _ => return None,
+        };
+        if content.trim().is_empty() && subject.is_none() {
+            return None;
+        }
+        Some(Self {
+            entry_index,
+            entry_type,
+            tool_name,
+            subject,
+            content,
+        })
+    }
+}
+
+/// What a tool call acts on, and any text beyond the entry's own content worth searching
+fn action_text(action: &ActionType) -> (Option<String>, Vec<String>) {
+    match action {
+        ActionType::FileRead { path } | ActionType::FileEdit { path, .. } => {
+            (Some(path.clone()), Vec::new())
+        }
+        ActionType::Search { query } => (Some(query.clone()), Vec::new()),
+        ActionType::WebFetch { url } => (Some(url.clone()), Vec::new()),
+        ActionType::CommandRun { command, result } => {
+            let mut details = vec![command.clone()];
+            if let Some(output) = result.as_ref().and_then(|r| r.output.as_deref()) {
+                details.push(truncate_chars(output, MAX_OUTPUT_CHARS));
+            }
+            (None, details)
+        }
+        ActionType::Tool {
+            arguments, result, ..
+        } => {
+            let mut details = Vec::new();
+            if let Some(arguments) = arguments {
+                details.push(truncate_chars(&arguments.to_string(), MAX_OUTPUT_CHARS));
+            }
+            if let Some(result) = result {
+                let value = match &result.value {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                details.push(truncate_chars(&value, MAX_OUTPUT_CHARS));
+            }
+            (None, details)
+        }
+        ActionType::TaskCreate { description } | ActionType::Other { description } => {
+            (None, vec![description.clone()])
+        }
+        ActionType::PlanPresentation { plan } => (None, vec![plan.clone()]),
+        ActionType::TodoManagement { .. } => (None, Vec::new()),
+    }
+}
+
+fn truncate_chars(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some((end, _)) => s[..end].to_string(),
+        None => s.to_string(),
+    }
+}
+
+/// Turn free text into an FTS5 query: every word must match, in any column. Words are quoted
+/// so punctuation such as `migrations/` or `--force` is matched rather than parsed as query
+/// syntax; a trailing `*` keeps prefix matching.
+pub fn match_expression(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| {
+            let (word, prefix) = match word.strip_suffix('*') {
+                Some(stem) if !stem.is_empty() => (stem, "*"),
+                _ => (word, ""),
+            };
+            format!("\"{}\"{prefix}", word.replace('"', "\"\""))
+        })
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LogSearchFilter {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub task_attempt_id: Option<Uuid>,
+    pub executor: Option<String>,
+    pub entry_type: Option<LogSearchEntryType>,
+    /// Only executions started at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only executions started before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// An entry matching a search, with the task it belongs to
+#[derive(Debug, Clone, Serialize, TS, FromRow)]
+pub struct LogSearchHit {
+    pub execution_process_id: Uuid,
+    /// Index of the entry in the execution's normalized conversation
+    pub entry_index: i64,
+    pub entry_type: LogSearchEntryType,
+    pub tool_name: Option<String>,
+    pub subject: Option<String>,
+    /// Excerpt around the match, with matched words wrapped in `**`
+    pub snippet: String,
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub project_id: Uuid,
+    /// Base coding agent of the attempt, e.g. `CLAUDE_CODE`
+    pub executor: String,
+    pub execution_status: ExecutionProcessStatus,
+    pub started_at: DateTime<Utc>,
+}
+
+pub struct LogSearch;
+
+impl LogSearch {
+    /// Replace the indexed entries of an execution
+    pub async fn replace_for_execution(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        documents: &[LogSearchDocument],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM log_search WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for doc in documents {
+            sqlx::query!(
+                r#"INSERT INTO log_search
+                       (content, subject, execution_process_id, entry_index, entry_type, tool_name)
+                   VALUES ($1, $2, $3, $4, $5, $6)"#,
+                doc.content,
+                doc.subject,
+                execution_process_id,
+                doc.entry_index,
+                doc.entry_type,
+                doc.tool_name
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        let entry_count = documents.len() as i64;
+        sqlx::query!(
+            r#"INSERT INTO log_search_indexed (execution_process_id, entry_count)
+               VALUES ($1, $2)
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                   entry_count = excluded.entry_count,
+                   indexed_at = datetime('now', 'subsec')"#,
+            execution_process_id,
+            entry_count
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Finished coding agent executions that haven't been indexed, oldest first
+    pub async fn find_unindexed_executions(
+        pool: &SqlitePool,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT ep.id AS "id!: Uuid" FROM execution_processes ep
+               LEFT JOIN log_search_indexed lsi ON lsi.execution_process_id = ep.id
+               WHERE lsi.execution_process_id IS NULL
+                 AND ep.run_reason = 'codingagent'
+                 AND ep.status != 'running'
+               ORDER BY ep.started_at ASC
+               LIMIT $1"#,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Best matches first. `text` is free text, see [`match_expression`].
+    pub async fn search(
+        pool: &SqlitePool,
+        text: &str,
+        filter: &LogSearchFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<LogSearchHit>, sqlx::Error> {
+        let Some(expression) = match_expression(text) else {
+            return Ok(Vec::new());
+        };
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            r#"SELECT ep.id AS execution_process_id,
+                      CAST(log_search.entry_index AS INTEGER) AS entry_index,
+                      log_search.entry_type AS entry_type,
+                      log_search.tool_name AS tool_name,
+                      log_search.subject AS subject,
+                      COALESCE(snippet(log_search, -1, '**', '**', '…', 24), '') AS snippet,
+                      ta.id AS task_attempt_id,
+                      t.id AS task_id,
+                      t.title AS task_title,
+                      t.project_id AS project_id,
+                      ta.executor AS executor,
+                      ep.status AS execution_status,
+                      ep.started_at AS started_at
+               FROM log_search
+               JOIN execution_processes ep ON ep.id = log_search.execution_process_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE log_search MATCH "#,
+        );
+        query.push_bind(expression);
+        if let Some(project_id) = filter.project_id {
+            query.push(" AND t.project_id = ").push_bind(project_id);
+        }
+        if let Some(task_id) = filter.task_id {
+            query.push(" AND t.id = ").push_bind(task_id);
+        }
+        if let Some(task_attempt_id) = filter.task_attempt_id {
+            query.push(" AND ta.id = ").push_bind(task_attempt_id);
+        }
+        if let Some(executor) = &filter.executor {
+            query
+                .push(" AND ta.executor = ")
+                .push_bind(executor.clone());
+        }
+        if let Some(entry_type) = filter.entry_type {
+            query
+                .push(" AND log_search.entry_type = ")
+                .push_bind(entry_type);
+        }
+        if let Some(since) = filter.since {
+            query
+                .push(" AND datetime(ep.started_at) >= datetime(")
+                .push_bind(since.to_rfc3339())
+                .push(")");
+        }
+        if let Some(until) = filter.until {
+            query
+                .push(" AND datetime(ep.started_at) < datetime(")
+                .push_bind(until.to_rfc3339())
+                .push(")");
+        }
+        query
+            .push(" ORDER BY rank, ep.started_at DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+
+        query.build_query_as::<LogSearchHit>().fetch_all(pool).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::{
+        actions::{
+            ExecutorAction, ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest,
+        },
+        executors::BaseCodingAgent,
+        logs::{CommandExitStatus, CommandRunResult, ToolStatus},
+        profile::ExecutorProfileId,
+    };
+
+    use super::*;
+    use crate::{
+        models::{
+            execution_process::{
+                CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason,
+            },
+            project::{CreateProject, Project},
+            task::{CreateTask, Task},
+            task_attempt::{CreateTaskAttempt, TaskAttempt},
+        },
+        test_utils::create_test_pool,
+    };
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_match_expression_quotes_terms() {
+        assert_eq!(
+            match_expression("migrations/ \"unique\" constr*").as_deref(),
+            Some(r#""migrations/" """unique""" "constr"*"#)
+        );
+        assert_eq!(match_expression("   "), None);
+    }
+
+    #[test]
+    fn test_documents_cover_messages_and_tool_calls() {
+        let edit = entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "Edit".to_string(),
+                action_type: ActionType::FileEdit {
+                    path: "migrations/0001_init.sql".to_string(),
+                    changes: Vec::new(),
+                },
+                status: ToolStatus::Success,
+            },
+            "migrations/0001_init.sql",
+        );
+        let doc = LogSearchDocument::from_entry(3, &edit).unwrap();
+        assert_eq!(doc.entry_type, LogSearchEntryType::ToolUse);
+        assert_eq!(doc.subject.as_deref(), Some("migrations/0001_init.sql"));
+        assert_eq!(doc.tool_name.as_deref(), Some("Edit"));
+
+        let thinking = entry(NormalizedEntryType::Thinking, "Let me think");
+        assert!(LogSearchDocument::from_entry(4, &thinking).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_filters_and_replaces() {
+        let (pool, _tmp) = create_test_pool().await;
+        let project_id = Uuid::new_v4();
+        Project::create(
+            &pool,
+            &CreateProject {
+                name: "Search Project".to_string(),
+                git_repo_path: format!("/tmp/test-repo-{project_id}"),
+                use_existing_repo: true,
+                clone_url: None,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+            },
+            project_id,
+        )
+        .await
+        .unwrap();
+        let task_id = Uuid::new_v4();
+        Task::create(
+            &pool,
+            &CreateTask::from_title_description(project_id, "Add index".to_string(), None),
+            task_id,
+        )
+        .await
+        .unwrap();
+        let attempt_id = Uuid::new_v4();
+        TaskAttempt::create(
+            &pool,
+            &CreateTaskAttempt {
+                executor: BaseCodingAgent::ClaudeCode,
+                base_branch: "main".to_string(),
+                branch: format!("search-{attempt_id}"),
+                origin_node_id: None,
+            },
+            attempt_id,
+            task_id,
+        )
+        .await
+        .unwrap();
+        let process_id = Uuid::new_v4();
+        ExecutionProcess::create(
+            &pool,
+            &CreateExecutionProcess {
+                task_attempt_id: attempt_id,
+                executor_action: ExecutorAction::new(
+                    ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                        prompt: "Add an index".to_string(),
+                        executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+                    }),
+                    None,
+                ),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+            },
+            process_id,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let run = entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "Bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "sqlx migrate run".to_string(),
+                    result: Some(CommandRunResult {
+                        exit_status: Some(CommandExitStatus::ExitCode { code: 1 }),
+                        output: Some("error: UNIQUE constraint failed: tasks.slug".to_string()),
+                    }),
+                },
+                status: ToolStatus::Failed,
+            },
+            "sqlx migrate run",
+        );
+        let reply = entry(
+            NormalizedEntryType::AssistantMessage,
+            "The migration in migrations/0002_slug.sql failed",
+        );
+        let docs: Vec<_> = [run, reply]
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| LogSearchDocument::from_entry(i, e))
+            .collect();
+        LogSearch::replace_for_execution(&pool, process_id, &docs)
+            .await
+            .unwrap();
+
+        let hits = LogSearch::search(
+            &pool,
+            "unique constraint",
+            &LogSearchFilter::default(),
+            10,
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entry_index, 0);
+        assert_eq!(hits[0].task_id, task_id);
+        assert_eq!(hits[0].executor, "CLAUDE_CODE");
+        assert!(hits[0].snippet.contains("**UNIQUE**"));
+
+        let assistant_only = LogSearchFilter {
+            project_id: Some(project_id),
+            entry_type: Some(LogSearchEntryType::AssistantMessage),
+            ..Default::default()
+        };
+        let hits = LogSearch::search(&pool, "migrations/", &assistant_only, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entry_index, 1);
+
+        let other_project = LogSearchFilter {
+            project_id: Some(Uuid::new_v4()),
+            ..Default::default()
+        };
+        assert!(
+            LogSearch::search(&pool, "migrations", &other_project, 10, 0)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Re-indexing replaces the execution's rows rather than adding to them
+        LogSearch::replace_for_execution(&pool, process_id, &docs[1..])
+            .await
+            .unwrap();
+        assert!(
+            LogSearch::search(&pool, "unique", &LogSearchFilter::default(), 10, 0)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            LogSearch::find_unindexed_executions(&pool, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
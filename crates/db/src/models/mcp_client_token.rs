@@ -0,0 +1,304 @@
+//! Bearer tokens issued to MCP clients that connect over HTTP.
+//!
+//! Each token limits its client to a set of projects and to either the read-only or the full
+//! tool set. Only a hash of the token is stored, so a lost token has to be revoked and reissued.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum McpToolAccess {
+    /// Tools that only read tasks, attempts, logs and diffs
+    ReadOnly,
+    /// Every tool, including ones that start agents, merge or open pull requests
+    ReadWrite,
+}
+
+struct McpClientTokenRow {
+    id: Uuid,
+    name: String,
+    token_prefix: String,
+    project_ids: Option<String>,
+    access: McpToolAccess,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct McpClientToken {
+    pub id: Uuid,
+    pub name: String,
+    /// First characters of the token, to tell tokens apart
+    pub token_prefix: String,
+    /// Projects the client may access; `None` allows every project
+    pub project_ids: Option<Vec<Uuid>>,
+    pub access: McpToolAccess,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<McpClientTokenRow> for McpClientToken {
+    fn from(row: McpClientTokenRow) -> Self {
+        McpClientToken {
+            id: row.id,
+            name: row.name,
+            token_prefix: row.token_prefix,
+            project_ids: row.project_ids.as_deref().map(|s| {
+                // A scope that no longer parses denies every project instead of allowing them all
+                serde_json::from_str(s).unwrap_or_else(|e| {
+                    tracing::warn!(
+                        token_id = %row.id,
+                        error = %e,
+                        "Invalid MCP token project scope"
+                    );
+                    Vec::new()
+                })
+            }),
+            access: row.access,
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+            revoked_at: row.revoked_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateMcpClientToken {
+    pub name: String,
+    /// Projects the client may access; omit to allow every project
+    #[serde(default)]
+    pub project_ids: Option<Vec<Uuid>>,
+    pub access: McpToolAccess,
+}
+
+impl McpClientToken {
+    pub fn allows_project(&self, project_id: Uuid) -> bool {
+        self.project_ids
+            .as_ref()
+            .is_none_or(|ids| ids.contains(&project_id))
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateMcpClientToken,
+        token_hash: &str,
+        token_prefix: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let project_ids = data
+            .project_ids
+            .as_ref()
+            .map(|ids| serde_json::to_string(ids).unwrap_or_else(|_| "[]".to_string()));
+        let created_at = Utc::now();
+        sqlx::query!(
+            r#"INSERT INTO mcp_client_tokens
+                   (id, name, token_hash, token_prefix, project_ids, access, created_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            id,
+            data.name,
+            token_hash,
+            token_prefix,
+            project_ids,
+            data.access,
+            created_at
+        )
+        .execute(pool)
+        .await?;
+
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// All tokens, including revoked ones, newest first
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            McpClientTokenRow,
+            r#"SELECT id AS "id!: Uuid",
+                      name,
+                      token_prefix,
+                      project_ids,
+                      access AS "access!: McpToolAccess",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      last_used_at AS "last_used_at: DateTime<Utc>",
+                      revoked_at AS "revoked_at: DateTime<Utc>"
+               FROM mcp_client_tokens
+               ORDER BY created_at DESC"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            McpClientTokenRow,
+            r#"SELECT id AS "id!: Uuid",
+                      name,
+                      token_prefix,
+                      project_ids,
+                      access AS "access!: McpToolAccess",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      last_used_at AS "last_used_at: DateTime<Utc>",
+                      revoked_at AS "revoked_at: DateTime<Utc>"
+               FROM mcp_client_tokens
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    /// The unrevoked token with this hash
+    pub async fn find_active_by_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            McpClientTokenRow,
+            r#"SELECT id AS "id!: Uuid",
+                      name,
+                      token_prefix,
+                      project_ids,
+                      access AS "access!: McpToolAccess",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      last_used_at AS "last_used_at: DateTime<Utc>",
+                      revoked_at AS "revoked_at: DateTime<Utc>"
+               FROM mcp_client_tokens
+               WHERE token_hash = $1 AND revoked_at IS NULL"#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    pub async fn touch_last_used(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            "UPDATE mcp_client_tokens SET last_used_at = $1 WHERE id = $2",
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Revoke the token. Returns false if it does not exist or was already revoked.
+    pub async fn revoke(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE mcp_client_tokens SET revoked_at = $1 WHERE id = $2 AND revoked_at IS NULL",
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_test_pool;
+
+    #[tokio::test]
+    async fn test_revoked_token_no_longer_authenticates() {
+        let (pool, _tmp) = create_test_pool().await;
+        let project_id = Uuid::new_v4();
+        let token = McpClientToken::create(
+            &pool,
+            &CreateMcpClientToken {
+                name: "ide".to_string(),
+                project_ids: Some(vec![project_id]),
+                access: McpToolAccess::ReadOnly,
+            },
+            "hash-1",
+            "vkmcp_ab",
+        )
+        .await
+        .unwrap();
+        assert!(token.allows_project(project_id));
+        assert!(!token.allows_project(Uuid::new_v4()));
+
+        let found = McpClientToken::find_active_by_hash(&pool, "hash-1")
+            .await
+            .unwrap()
+            .expect("active token");
+        assert_eq!(found.id, token.id);
+        assert_eq!(found.project_ids, Some(vec![project_id]));
+        assert_eq!(found.access, McpToolAccess::ReadOnly);
+
+        assert!(McpClientToken::revoke(&pool, token.id).await.unwrap());
+        assert!(!McpClientToken::revoke(&pool, token.id).await.unwrap());
+        assert!(
+            McpClientToken::find_active_by_hash(&pool, "hash-1")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let listed = McpClientToken::find_all(&pool).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].revoked_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_token_without_projects_allows_every_project() {
+        let (pool, _tmp) = create_test_pool().await;
+        let token = McpClientToken::create(
+            &pool,
+            &CreateMcpClientToken {
+                name: "manager".to_string(),
+                project_ids: None,
+                access: McpToolAccess::ReadWrite,
+            },
+            "hash-2",
+            "vkmcp_cd",
+        )
+        .await
+        .unwrap();
+        assert_eq!(token.project_ids, None);
+        assert!(token.allows_project(Uuid::new_v4()));
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_project_scope_allows_no_project() {
+        let (pool, _tmp) = create_test_pool().await;
+        let token = McpClientToken::create(
+            &pool,
+            &CreateMcpClientToken {
+                name: "ide".to_string(),
+                project_ids: Some(vec![Uuid::new_v4()]),
+                access: McpToolAccess::ReadOnly,
+            },
+            "hash-3",
+            "vkmcp_ef",
+        )
+        .await
+        .unwrap();
+        sqlx::query!(
+            "UPDATE mcp_client_tokens SET project_ids = 'not json' WHERE id = $1",
+            token.id
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let found = McpClientToken::find_active_by_hash(&pool, "hash-3")
+            .await
+            .unwrap()
+            .expect("active token");
+        assert_eq!(found.project_ids, Some(Vec::new()));
+        assert!(!found.allows_project(Uuid::new_v4()));
+    }
+}
@@ -17,21 +17,32 @@
 pub mod activity_dismissal;
 pub mod activity_feed;
 pub mod all_tasks;
+pub mod approval_policy;
+pub mod approval_request;
+pub mod attempt_group;
 pub mod dashboard;
 pub mod draft;
 pub mod execution_process;
 pub mod execution_process_logs;
+pub mod execution_process_usage;
+pub mod executor_command;
 pub mod executor_session;
 pub mod image;
 pub mod label;
+pub mod log_search;
+pub mod mcp_client_token;
 pub mod merge;
 pub mod node_outbox;
 pub mod project;
+pub mod project_pipeline;
 pub mod task;
 pub mod task_attempt;
 pub mod task_variable;
 pub mod template;
+pub mod terminal_recording;
+pub mod usage_budget;
 pub mod webhook;
+pub mod webhook_delivery;
 pub mod workstream_state;
 
 // === Electric SQL Integration (New) ===
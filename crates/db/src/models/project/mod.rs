@@ -6,8 +6,10 @@
 
 mod github;
 mod queries;
+mod script_language;
 mod stats;
 mod sync;
+mod verification;
 
 use std::path::PathBuf;
 
@@ -17,6 +19,8 @@ use sqlx::FromRow;
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
+pub use script_language::{ProjectScriptLanguage, UpdateProjectScriptLanguage};
+pub use verification::{ProjectVerification, UpdateProjectVerification};
 
 #[derive(Debug, Error)]
 pub enum ProjectError {
@@ -0,0 +1,117 @@
+//! Script language setting for projects.
+//!
+//! Applies to every script the project runs: setup, dev server, cleanup, verification and
+//! pipeline script steps that don't name their own language.
+
+use executors::actions::script::ScriptRequestLanguage;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::Project;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ProjectScriptLanguage {
+    /// Interpreter for the project's scripts; `Shebang` reads it from each script's `#!` line
+    pub script_language: ScriptRequestLanguage,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateProjectScriptLanguage {
+    pub script_language: ScriptRequestLanguage,
+}
+
+impl Project {
+    pub async fn find_script_language(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<ProjectScriptLanguage>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectScriptLanguage,
+            r#"SELECT script_language AS "script_language!: ScriptRequestLanguage"
+               FROM projects
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The language to run the project's scripts in, defaulting when the project is gone
+    pub async fn script_language(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<ScriptRequestLanguage, sqlx::Error> {
+        Ok(Self::find_script_language(pool, id)
+            .await?
+            .map(|settings| settings.script_language)
+            .unwrap_or_default())
+    }
+
+    pub async fn update_script_language(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateProjectScriptLanguage,
+    ) -> Result<ProjectScriptLanguage, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectScriptLanguage,
+            r#"UPDATE projects
+               SET script_language = $1,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $2
+               RETURNING script_language AS "script_language!: ScriptRequestLanguage""#,
+            data.script_language,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::project::CreateProject;
+    use crate::test_utils::create_test_pool;
+
+    #[tokio::test]
+    async fn test_update_script_language() {
+        let (pool, _temp_dir) = create_test_pool().await;
+
+        let project_id = Uuid::new_v4();
+        let create_data = CreateProject {
+            name: "Script Language Test".to_string(),
+            git_repo_path: "/script-language/test".to_string(),
+            use_existing_repo: true,
+            clone_url: None,
+            setup_script: None,
+            dev_script: None,
+            cleanup_script: None,
+            copy_files: None,
+        };
+        Project::create(&pool, &create_data, project_id)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Project::script_language(&pool, project_id).await.unwrap(),
+            ScriptRequestLanguage::Shebang
+        );
+
+        let updated = Project::update_script_language(
+            &pool,
+            project_id,
+            &UpdateProjectScriptLanguage {
+                script_language: ScriptRequestLanguage::PowerShell,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.script_language, ScriptRequestLanguage::PowerShell);
+        assert_eq!(
+            Project::script_language(&pool, project_id).await.unwrap(),
+            ScriptRequestLanguage::PowerShell
+        );
+    }
+}
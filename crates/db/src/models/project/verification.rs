@@ -0,0 +1,149 @@
+//! Verification script settings for projects.
+//!
+//! A verification script runs after each coding agent turn. When it exits non-zero, its
+//! output is sent back to the agent as a follow-up, up to `max_retries` times in a row.
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::Project;
+
+/// Default number of automatic fix attempts after a failed verification
+pub const DEFAULT_VERIFICATION_MAX_RETRIES: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ProjectVerification {
+    /// Script run after each coding agent turn; verification is disabled when unset
+    pub verification_script: Option<String>,
+    /// Automatic follow-ups allowed before a failing verification is left for review
+    pub verification_max_retries: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateProjectVerification {
+    pub verification_script: Option<String>,
+    pub verification_max_retries: Option<u32>,
+}
+
+impl ProjectVerification {
+    /// The script to run, if verification is configured
+    pub fn script(&self) -> Option<&str> {
+        self.verification_script
+            .as_deref()
+            .filter(|script| !script.trim().is_empty())
+    }
+}
+
+impl Project {
+    pub async fn find_verification(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<ProjectVerification>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectVerification,
+            r#"SELECT verification_script,
+                      verification_max_retries AS "verification_max_retries!: u32"
+               FROM projects
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn update_verification(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateProjectVerification,
+    ) -> Result<ProjectVerification, sqlx::Error> {
+        let verification_script = data
+            .verification_script
+            .as_deref()
+            .map(str::trim)
+            .filter(|script| !script.is_empty());
+        let verification_max_retries = data
+            .verification_max_retries
+            .unwrap_or(DEFAULT_VERIFICATION_MAX_RETRIES);
+        sqlx::query_as!(
+            ProjectVerification,
+            r#"UPDATE projects
+               SET verification_script = $1,
+                   verification_max_retries = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $3
+               RETURNING verification_script,
+                         verification_max_retries AS "verification_max_retries!: u32""#,
+            verification_script,
+            verification_max_retries,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::project::CreateProject;
+    use crate::test_utils::create_test_pool;
+
+    #[tokio::test]
+    async fn test_update_verification() {
+        let (pool, _temp_dir) = create_test_pool().await;
+
+        let project_id = Uuid::new_v4();
+        let create_data = CreateProject {
+            name: "Verification Test".to_string(),
+            git_repo_path: "/verification/test".to_string(),
+            use_existing_repo: true,
+            clone_url: None,
+            setup_script: None,
+            dev_script: None,
+            cleanup_script: None,
+            copy_files: None,
+        };
+        Project::create(&pool, &create_data, project_id)
+            .await
+            .unwrap();
+
+        // Defaults: no script, default retry budget
+        let verification = Project::find_verification(&pool, project_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(verification.script(), None);
+        assert_eq!(
+            verification.verification_max_retries,
+            DEFAULT_VERIFICATION_MAX_RETRIES
+        );
+
+        let verification = Project::update_verification(
+            &pool,
+            project_id,
+            &UpdateProjectVerification {
+                verification_script: Some("  cargo test  ".to_string()),
+                verification_max_retries: Some(5),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(verification.script(), Some("cargo test"));
+        assert_eq!(verification.verification_max_retries, 5);
+
+        // Blank scripts disable verification
+        let verification = Project::update_verification(
+            &pool,
+            project_id,
+            &UpdateProjectVerification {
+                verification_script: Some("   ".to_string()),
+                verification_max_retries: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(verification.verification_script, None);
+    }
+}
@@ -0,0 +1,203 @@
+use chrono::{DateTime, Utc};
+use executors::actions::pipeline::Pipeline;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+struct ProjectPipelineRow {
+    project_id: Uuid,
+    definition: String,
+    enabled: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// A project's declarative execution pipeline
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectPipeline {
+    pub project_id: Uuid,
+    pub pipeline: Pipeline,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertProjectPipeline {
+    pub pipeline: Pipeline,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl TryFrom<ProjectPipelineRow> for ProjectPipeline {
+    type Error = sqlx::Error;
+
+    fn try_from(row: ProjectPipelineRow) -> Result<Self, Self::Error> {
+        let pipeline =
+            serde_json::from_str(&row.definition).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        Ok(Self {
+            project_id: row.project_id,
+            pipeline,
+            enabled: row.enabled,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+impl ProjectPipeline {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectPipelineRow,
+            r#"SELECT project_id AS "project_id!: Uuid",
+                      definition,
+                      enabled AS "enabled!: bool",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM project_pipelines
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .map(Self::try_from)
+        .transpose()
+    }
+
+    /// The pipeline to run for new attempts of a project, if one is configured and enabled
+    pub async fn find_enabled(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Pipeline>, sqlx::Error> {
+        Ok(Self::find_by_project_id(pool, project_id)
+            .await?
+            .filter(|p| p.enabled)
+            .map(|p| p.pipeline))
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectPipeline,
+    ) -> Result<Self, sqlx::Error> {
+        let definition = serde_json::to_string(&data.pipeline)
+            .map_err(|e| sqlx::Error::Protocol(format!("failed to serialize pipeline: {e}")))?;
+        let row = sqlx::query_as!(
+            ProjectPipelineRow,
+            r#"INSERT INTO project_pipelines (project_id, definition, enabled)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   definition = excluded.definition,
+                   enabled = excluded.enabled,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id AS "project_id!: Uuid",
+                         definition,
+                         enabled AS "enabled!: bool",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            project_id,
+            definition,
+            data.enabled
+        )
+        .fetch_one(pool)
+        .await?;
+        Self::try_from(row)
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_pipelines WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::actions::pipeline::{PipelineStep, PipelineStepKind};
+
+    use super::*;
+    use crate::{
+        models::project::{CreateProject, Project},
+        test_utils::create_test_pool,
+    };
+
+    #[tokio::test]
+    async fn test_upsert_replaces_and_disables() {
+        let (pool, _tmp) = create_test_pool().await;
+        let project_id = Uuid::new_v4();
+        Project::create(
+            &pool,
+            &CreateProject {
+                name: "Pipeline Project".to_string(),
+                git_repo_path: format!("/tmp/test-repo-{project_id}"),
+                use_existing_repo: true,
+                clone_url: None,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+            },
+            project_id,
+        )
+        .await
+        .unwrap();
+
+        let pipeline = Pipeline {
+            steps: vec![PipelineStep {
+                name: "agent".to_string(),
+                kind: PipelineStepKind::CodingAgent,
+                on_success: None,
+                on_failure: None,
+                max_runs: None,
+            }],
+        };
+        ProjectPipeline::upsert(
+            &pool,
+            project_id,
+            &UpsertProjectPipeline {
+                pipeline: pipeline.clone(),
+                enabled: true,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            ProjectPipeline::find_enabled(&pool, project_id)
+                .await
+                .unwrap(),
+            Some(pipeline.clone())
+        );
+
+        ProjectPipeline::upsert(
+            &pool,
+            project_id,
+            &UpsertProjectPipeline {
+                pipeline,
+                enabled: false,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(
+            ProjectPipeline::find_enabled(&pool, project_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert_eq!(ProjectPipeline::delete(&pool, project_id).await.unwrap(), 1);
+    }
+}
@@ -45,7 +45,7 @@ impl Task {
         ON ep.task_attempt_id = ta.id
      WHERE ta.task_id       = t.id
        AND ep.status        = 'running'
-       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','pipelinestep')
      LIMIT 1
   ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
 
@@ -55,7 +55,7 @@ impl Task {
       JOIN execution_processes ep
         ON ep.task_attempt_id = ta.id
      WHERE ta.task_id       = t.id
-     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','pipelinestep')
      ORDER BY ep.created_at DESC
      LIMIT 1
   ) IN ('failed','killed') THEN 1 ELSE 0 END
@@ -417,6 +417,29 @@ ORDER BY COALESCE(t.activity_at, t.created_at) DESC"#,
                 .collect()
         })
     }
+
+    /// Status and archived state of every task, used to seed task lifecycle change detection.
+    pub async fn find_lifecycle_states(
+        pool: &SqlitePool,
+    ) -> Result<Vec<TaskLifecycleRow>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskLifecycleRow,
+            r#"SELECT id AS "id!: Uuid",
+                      status AS "status!: TaskStatus",
+                      archived_at IS NOT NULL AS "archived!: bool"
+               FROM tasks"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+/// A task's lifecycle state: what task status and archive webhooks compare against.
+#[derive(Debug, Clone)]
+pub struct TaskLifecycleRow {
+    pub id: Uuid,
+    pub status: TaskStatus,
+    pub archived: bool,
 }
 
 /// A node-side anti-entropy digest row: the id-bridge key + the version the node believes the hive holds.
@@ -0,0 +1,292 @@
+//! Recordings of terminal sessions made for a task attempt.
+//!
+//! The recording itself is an asciicast v2 file on disk; a row here records where it lives,
+//! which attempt it belongs to and, once it has ended, how long it ran.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct TerminalRecording {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    /// Terminal session that was recorded
+    pub session_id: String,
+    #[serde(skip)]
+    #[ts(skip)]
+    pub file_path: String,
+    /// Terminal size when recording started
+    pub cols: i64,
+    pub rows: i64,
+    /// Unset while the recording runs, or if the server stopped before it ended
+    pub duration_ms: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+pub struct CreateTerminalRecording<'a> {
+    pub task_attempt_id: Uuid,
+    pub session_id: &'a str,
+    pub file_path: &'a str,
+    pub cols: u16,
+    pub rows: u16,
+}
+
+impl TerminalRecording {
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &CreateTerminalRecording<'_>,
+    ) -> Result<Self, sqlx::Error> {
+        let cols = i64::from(data.cols);
+        let rows = i64::from(data.rows);
+        sqlx::query_as!(
+            TerminalRecording,
+            r#"INSERT INTO terminal_recordings
+                   (id, task_attempt_id, session_id, file_path, cols, rows)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id AS "id!: Uuid",
+                         task_attempt_id AS "task_attempt_id!: Uuid",
+                         session_id,
+                         file_path,
+                         cols,
+                         rows,
+                         duration_ms,
+                         started_at AS "started_at!: DateTime<Utc>",
+                         ended_at AS "ended_at: DateTime<Utc>""#,
+            id,
+            data.task_attempt_id,
+            data.session_id,
+            data.file_path,
+            cols,
+            rows
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TerminalRecording,
+            r#"SELECT id AS "id!: Uuid",
+                      task_attempt_id AS "task_attempt_id!: Uuid",
+                      session_id,
+                      file_path,
+                      cols,
+                      rows,
+                      duration_ms,
+                      started_at AS "started_at!: DateTime<Utc>",
+                      ended_at AS "ended_at: DateTime<Utc>"
+               FROM terminal_recordings
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Recordings of an attempt, newest first
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TerminalRecording,
+            r#"SELECT id AS "id!: Uuid",
+                      task_attempt_id AS "task_attempt_id!: Uuid",
+                      session_id,
+                      file_path,
+                      cols,
+                      rows,
+                      duration_ms,
+                      started_at AS "started_at!: DateTime<Utc>",
+                      ended_at AS "ended_at: DateTime<Utc>"
+               FROM terminal_recordings
+               WHERE task_attempt_id = $1
+               ORDER BY started_at DESC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The recording of a session that has not ended yet
+    pub async fn find_active_by_session_id(
+        pool: &SqlitePool,
+        session_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TerminalRecording,
+            r#"SELECT id AS "id!: Uuid",
+                      task_attempt_id AS "task_attempt_id!: Uuid",
+                      session_id,
+                      file_path,
+                      cols,
+                      rows,
+                      duration_ms,
+                      started_at AS "started_at!: DateTime<Utc>",
+                      ended_at AS "ended_at: DateTime<Utc>"
+               FROM terminal_recordings
+               WHERE session_id = $1 AND ended_at IS NULL
+               ORDER BY started_at DESC
+               LIMIT 1"#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record that the recording has ended. Only the first call has an effect.
+    pub async fn mark_ended(
+        pool: &SqlitePool,
+        id: Uuid,
+        duration_ms: i64,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            "UPDATE terminal_recordings SET duration_ms = $1, ended_at = $2
+             WHERE id = $3 AND ended_at IS NULL",
+            duration_ms,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// End recordings left running by a previous server process, whose sessions are gone.
+    /// Their duration stays unset.
+    pub async fn end_orphaned(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE terminal_recordings SET ended_at = $1 WHERE ended_at IS NULL",
+            now
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM terminal_recordings WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+    use crate::{
+        models::{
+            project::{CreateProject, Project},
+            task::{CreateTask, Task},
+            task_attempt::{CreateTaskAttempt, TaskAttempt},
+        },
+        test_utils::create_test_pool,
+    };
+
+    async fn seed_attempt(pool: &SqlitePool) -> Uuid {
+        let project_id = Uuid::new_v4();
+        Project::create(
+            pool,
+            &CreateProject {
+                name: "Recordings Project".to_string(),
+                git_repo_path: format!("/tmp/test-repo-{project_id}"),
+                use_existing_repo: true,
+                clone_url: None,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+            },
+            project_id,
+        )
+        .await
+        .unwrap();
+        let task_id = Uuid::new_v4();
+        Task::create(
+            pool,
+            &CreateTask::from_title_description(project_id, "Task".to_string(), None),
+            task_id,
+        )
+        .await
+        .unwrap();
+        let attempt_id = Uuid::new_v4();
+        TaskAttempt::create(
+            pool,
+            &CreateTaskAttempt {
+                executor: BaseCodingAgent::ClaudeCode,
+                base_branch: "main".to_string(),
+                branch: format!("recordings-{attempt_id}"),
+                origin_node_id: None,
+            },
+            attempt_id,
+            task_id,
+        )
+        .await
+        .unwrap();
+        attempt_id
+    }
+
+    #[tokio::test]
+    async fn test_recording_lifecycle() {
+        let (pool, _tmp) = create_test_pool().await;
+        let attempt_id = seed_attempt(&pool).await;
+        let id = Uuid::new_v4();
+        let recording = TerminalRecording::create(
+            &pool,
+            id,
+            &CreateTerminalRecording {
+                task_attempt_id: attempt_id,
+                session_id: "vk-0123abcd",
+                file_path: "/tmp/recording.cast",
+                cols: 120,
+                rows: 40,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(recording.id, id);
+        assert_eq!((recording.cols, recording.rows), (120, 40));
+        assert!(recording.ended_at.is_none());
+        let active = TerminalRecording::find_active_by_session_id(&pool, "vk-0123abcd")
+            .await
+            .unwrap();
+        assert_eq!(active.map(|r| r.id), Some(id));
+
+        TerminalRecording::mark_ended(&pool, id, 1500)
+            .await
+            .unwrap();
+        TerminalRecording::mark_ended(&pool, id, 9000)
+            .await
+            .unwrap();
+        assert!(
+            TerminalRecording::find_active_by_session_id(&pool, "vk-0123abcd")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let listed = TerminalRecording::find_by_task_attempt_id(&pool, attempt_id)
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].duration_ms, Some(1500));
+        assert!(listed[0].ended_at.is_some());
+
+        assert_eq!(TerminalRecording::delete(&pool, id).await.unwrap(), 1);
+        assert!(
+            TerminalRecording::find_by_id(&pool, id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,580 @@
+//! Token and spend budgets for projects and tasks.
+//!
+//! A budget caps the usage recorded in
+//! [`ExecutionProcessUsage`](super::execution_process_usage::ExecutionProcessUsage) for every
+//! coding agent run in its project or task, optionally counting only the current day or week.
+//! Running executions are stopped once a limit is reached.
+
+use chrono::{DateTime, Utc};
+use executors::executors::BaseCodingAgent;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process_usage::{TokenCounts, UsagePeriod};
+
+/// Completion reason stored on execution processes stopped by a budget
+pub const BUDGET_EXCEEDED_COMPLETION_REASON: &str = "budget_exceeded";
+
+/// Estimated USD per million input, cached input and output tokens for agents that report
+/// their cost only when a turn finishes. Until they do, a run's cost is estimated from its
+/// tokens so cost limits can stop it.
+const ESTIMATED_COST_PER_MILLION_TOKENS: &[(BaseCodingAgent, f64, f64, f64)] = &[
+    (BaseCodingAgent::ClaudeCode, 3.0, 0.3, 15.0),
+    (BaseCodingAgent::Amp, 3.0, 0.3, 15.0),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct UsageBudget {
+    pub id: Uuid,
+    /// Set for project budgets
+    pub project_id: Option<Uuid>,
+    /// Set for task budgets
+    pub task_id: Option<Uuid>,
+    /// Input plus output tokens
+    pub max_tokens: Option<i64>,
+    /// Only enforced for agents that report cost; estimated from tokens for Claude Code and
+    /// Amp runs that have not reported it yet
+    pub max_cost_usd: Option<f64>,
+    /// Count only the current day or week; all usage when unset
+    pub period: Option<UsagePeriod>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetUsageBudget {
+    pub max_tokens: Option<i64>,
+    pub max_cost_usd: Option<f64>,
+    pub period: Option<UsagePeriod>,
+}
+
+impl SetUsageBudget {
+    /// Check that at least one limit is set and that no limit is negative
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.max_tokens.is_none() && self.max_cost_usd.is_none() {
+            return Err("A budget needs max_tokens, max_cost_usd or both");
+        }
+        if self.max_tokens.is_some_and(|tokens| tokens < 0)
+            || self
+                .max_cost_usd
+                .is_some_and(|cost| !cost.is_finite() || cost < 0.0)
+        {
+            return Err("Budget limits must be non-negative numbers");
+        }
+        Ok(())
+    }
+}
+
+/// Usage counted against a budget. `cost_usd` includes estimates for runs whose agent has
+/// not reported a cost yet.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+pub struct BudgetSpend {
+    pub tokens: i64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLimit {
+    Tokens,
+    CostUsd,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct UsageBudgetStatus {
+    pub budget: UsageBudget,
+    pub spend: BudgetSpend,
+    /// The limit that has been reached, if any
+    pub exceeded: Option<BudgetLimit>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BudgetScope {
+    Project(Uuid),
+    Task(Uuid),
+}
+
+impl BudgetScope {
+    fn project_id(self) -> Option<Uuid> {
+        match self {
+            Self::Project(id) => Some(id),
+            Self::Task(_) => None,
+        }
+    }
+
+    fn task_id(self) -> Option<Uuid> {
+        match self {
+            Self::Task(id) => Some(id),
+            Self::Project(_) => None,
+        }
+    }
+}
+
+/// Usage of one executor's runs within a budget's scope
+struct ExecutorSpend {
+    executor: String,
+    tokens: i64,
+    reported_cost_usd: Option<f64>,
+    /// Token counts of the runs that have not reported a cost
+    unreported_input_tokens: i64,
+    unreported_cached_input_tokens: i64,
+    unreported_output_tokens: i64,
+}
+
+impl UsageBudget {
+    fn scope(&self) -> Option<BudgetScope> {
+        match (self.project_id, self.task_id) {
+            (_, Some(task_id)) => Some(BudgetScope::Task(task_id)),
+            (Some(project_id), None) => Some(BudgetScope::Project(project_id)),
+            (None, None) => None,
+        }
+    }
+
+    /// The first limit `spend` has reached
+    pub fn exceeded_limit(&self, spend: &BudgetSpend) -> Option<BudgetLimit> {
+        if self.max_tokens.is_some_and(|max| spend.tokens >= max) {
+            Some(BudgetLimit::Tokens)
+        } else if self.max_cost_usd.is_some_and(|max| spend.cost_usd >= max) {
+            Some(BudgetLimit::CostUsd)
+        } else {
+            None
+        }
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        Self::find(pool, BudgetScope::Project(project_id)).await
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        Self::find(pool, BudgetScope::Task(task_id)).await
+    }
+
+    async fn find(pool: &SqlitePool, scope: BudgetScope) -> Result<Option<Self>, sqlx::Error> {
+        let project_id = scope.project_id();
+        let task_id = scope.task_id();
+        sqlx::query_as!(
+            UsageBudget,
+            r#"SELECT id AS "id!: Uuid",
+                      project_id AS "project_id: Uuid",
+                      task_id AS "task_id: Uuid",
+                      max_tokens,
+                      max_cost_usd,
+                      period AS "period: UsagePeriod",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM usage_budgets
+               WHERE project_id IS $1 AND task_id IS $2"#,
+            project_id,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn set_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &SetUsageBudget,
+    ) -> Result<Self, sqlx::Error> {
+        Self::set(pool, BudgetScope::Project(project_id), data).await
+    }
+
+    pub async fn set_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &SetUsageBudget,
+    ) -> Result<Self, sqlx::Error> {
+        Self::set(pool, BudgetScope::Task(task_id), data).await
+    }
+
+    /// Create or replace the budget of `scope`. The new id never collides, so the only
+    /// possible conflict is with the scope's existing budget.
+    async fn set(
+        pool: &SqlitePool,
+        scope: BudgetScope,
+        data: &SetUsageBudget,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let project_id = scope.project_id();
+        let task_id = scope.task_id();
+        sqlx::query_as!(
+            UsageBudget,
+            r#"INSERT INTO usage_budgets (id, project_id, task_id, max_tokens, max_cost_usd, period)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT DO UPDATE SET
+                   max_tokens = excluded.max_tokens,
+                   max_cost_usd = excluded.max_cost_usd,
+                   period = excluded.period,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id AS "id!: Uuid",
+                         project_id AS "project_id: Uuid",
+                         task_id AS "task_id: Uuid",
+                         max_tokens,
+                         max_cost_usd,
+                         period AS "period: UsagePeriod",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            task_id,
+            data.max_tokens,
+            data.max_cost_usd,
+            data.period
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        Self::delete(pool, BudgetScope::Project(project_id)).await
+    }
+
+    pub async fn delete_for_task(pool: &SqlitePool, task_id: Uuid) -> Result<u64, sqlx::Error> {
+        Self::delete(pool, BudgetScope::Task(task_id)).await
+    }
+
+    async fn delete(pool: &SqlitePool, scope: BudgetScope) -> Result<u64, sqlx::Error> {
+        let project_id = scope.project_id();
+        let task_id = scope.task_id();
+        let result = sqlx::query!(
+            "DELETE FROM usage_budgets WHERE project_id IS $1 AND task_id IS $2",
+            project_id,
+            task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Usage counted against this budget so far
+    pub async fn spend(&self, pool: &SqlitePool) -> Result<BudgetSpend, sqlx::Error> {
+        let Some(scope) = self.scope() else {
+            return Ok(BudgetSpend::default());
+        };
+        let project_id = scope.project_id();
+        let task_id = scope.task_id();
+        // Weeks start on Monday, as in `UsagePeriod::bucket_start_sql`
+        let rows = sqlx::query_as!(
+            ExecutorSpend,
+            r#"SELECT u.executor,
+                      SUM(u.input_tokens + u.output_tokens) AS "tokens!: i64",
+                      SUM(u.total_cost_usd) AS "reported_cost_usd: f64",
+                      COALESCE(SUM(CASE WHEN u.total_cost_usd IS NULL THEN u.input_tokens END), 0) AS "unreported_input_tokens!: i64",
+                      COALESCE(SUM(CASE WHEN u.total_cost_usd IS NULL THEN u.cached_input_tokens END), 0) AS "unreported_cached_input_tokens!: i64",
+                      COALESCE(SUM(CASE WHEN u.total_cost_usd IS NULL THEN u.output_tokens END), 0) AS "unreported_output_tokens!: i64"
+               FROM execution_process_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE ($1 IS NULL OR t.project_id = $1)
+                 AND ($2 IS NULL OR t.id = $2)
+                 AND ($3 IS NULL
+                      OR ($3 = 'day' AND date(ep.started_at) = date('now'))
+                      OR ($3 = 'week'
+                          AND date(ep.started_at, 'weekday 0', '-6 days')
+                              = date('now', 'weekday 0', '-6 days')))
+               GROUP BY u.executor"#,
+            project_id,
+            task_id,
+            self.period
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .fold(BudgetSpend::default(), |mut spend, row| {
+                let unreported = TokenCounts {
+                    input_tokens: row.unreported_input_tokens,
+                    cached_input_tokens: row.unreported_cached_input_tokens,
+                    output_tokens: row.unreported_output_tokens,
+                    reasoning_tokens: 0,
+                };
+                spend.tokens += row.tokens;
+                spend.cost_usd += row.reported_cost_usd.unwrap_or_default()
+                    + estimated_cost_usd(&row.executor, unreported);
+                spend
+            }))
+    }
+
+    pub async fn status(self, pool: &SqlitePool) -> Result<UsageBudgetStatus, sqlx::Error> {
+        let spend = self.spend(pool).await?;
+        Ok(UsageBudgetStatus {
+            exceeded: self.exceeded_limit(&spend),
+            budget: self,
+            spend,
+        })
+    }
+
+    /// The first budget covering an execution process that has been reached, checking the
+    /// task's budget before the project's.
+    pub async fn find_exceeded(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<UsageBudgetStatus>, sqlx::Error> {
+        let budgets = sqlx::query_as!(
+            UsageBudget,
+            r#"SELECT b.id AS "id!: Uuid",
+                      b.project_id AS "project_id: Uuid",
+                      b.task_id AS "task_id: Uuid",
+                      b.max_tokens,
+                      b.max_cost_usd,
+                      b.period AS "period: UsagePeriod",
+                      b.created_at AS "created_at!: DateTime<Utc>",
+                      b.updated_at AS "updated_at!: DateTime<Utc>"
+               FROM execution_processes ep
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               JOIN usage_budgets b ON b.task_id = t.id OR b.project_id = t.project_id
+               WHERE ep.id = $1
+               ORDER BY b.task_id IS NULL"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for budget in budgets {
+            let status = budget.status(pool).await?;
+            if status.exceeded.is_some() {
+                return Ok(Some(status));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Estimated cost of `tokens` used by `executor`; zero for agents without rates
+fn estimated_cost_usd(executor: &str, tokens: TokenCounts) -> f64 {
+    ESTIMATED_COST_PER_MILLION_TOKENS
+        .iter()
+        .find(|(agent, ..)| agent.to_string() == executor)
+        .map(|(_, input, cached, output)| {
+            (tokens.input_tokens as f64 * input
+                + tokens.cached_input_tokens as f64 * cached
+                + tokens.output_tokens as f64 * output)
+                / 1_000_000.0
+        })
+        .unwrap_or_default()
+}
+
+impl UsageBudgetStatus {
+    /// Human-readable description of the exceeded limit, stored as the completion message
+    pub fn describe(&self) -> String {
+        let scope = if self.budget.task_id.is_some() {
+            "Task"
+        } else {
+            "Project"
+        };
+        let period = match self.budget.period {
+            Some(UsagePeriod::Day) => " today",
+            Some(UsagePeriod::Week) => " this week",
+            None => "",
+        };
+        match self.exceeded {
+            Some(BudgetLimit::CostUsd) => format!(
+                "{scope} budget of ${:.2} reached (${:.2} spent{period})",
+                self.budget.max_cost_usd.unwrap_or_default(),
+                self.spend.cost_usd
+            ),
+            _ => format!(
+                "{scope} budget of {} tokens reached ({} tokens used{period})",
+                self.budget.max_tokens.unwrap_or_default(),
+                self.spend.tokens
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::{
+        actions::{
+            ExecutorAction, ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest,
+        },
+        executors::BaseCodingAgent,
+        profile::ExecutorProfileId,
+    };
+
+    use super::*;
+    use crate::{
+        models::{
+            execution_process::{
+                CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason,
+            },
+            execution_process_usage::{ExecutionProcessUsage, TokenCounts},
+            project::{CreateProject, Project},
+            task::{CreateTask, Task},
+            task_attempt::{CreateTaskAttempt, TaskAttempt},
+        },
+        test_utils::create_test_pool,
+    };
+
+    #[tokio::test]
+    async fn test_find_exceeded_prefers_task_budget() {
+        let (pool, _tmp) = create_test_pool().await;
+        let project_id = Uuid::new_v4();
+        Project::create(
+            &pool,
+            &CreateProject {
+                name: "Budget Project".to_string(),
+                git_repo_path: format!("/tmp/test-repo-{project_id}"),
+                use_existing_repo: true,
+                clone_url: None,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+            },
+            project_id,
+        )
+        .await
+        .unwrap();
+        let task_id = Uuid::new_v4();
+        Task::create(
+            &pool,
+            &CreateTask::from_title_description(project_id, "Budget".to_string(), None),
+            task_id,
+        )
+        .await
+        .unwrap();
+        let attempt_id = Uuid::new_v4();
+        TaskAttempt::create(
+            &pool,
+            &CreateTaskAttempt {
+                executor: BaseCodingAgent::Codex,
+                base_branch: "main".to_string(),
+                branch: format!("budget-{attempt_id}"),
+                origin_node_id: None,
+            },
+            attempt_id,
+            task_id,
+        )
+        .await
+        .unwrap();
+        let process_id = Uuid::new_v4();
+        ExecutionProcess::create(
+            &pool,
+            &CreateExecutionProcess {
+                task_attempt_id: attempt_id,
+                executor_action: ExecutorAction::new(
+                    ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                        prompt: "Loop forever".to_string(),
+                        executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::Codex),
+                    }),
+                    None,
+                ),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+            },
+            process_id,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        UsageBudget::set_for_project(
+            &pool,
+            project_id,
+            &SetUsageBudget {
+                max_tokens: Some(500),
+                max_cost_usd: None,
+                period: Some(UsagePeriod::Week),
+            },
+        )
+        .await
+        .unwrap();
+        let first = UsageBudget::set_for_task(
+            &pool,
+            task_id,
+            &SetUsageBudget {
+                max_tokens: Some(10_000),
+                max_cost_usd: None,
+                period: None,
+            },
+        )
+        .await
+        .unwrap();
+        // Setting again replaces the budget rather than adding a second one
+        let task_budget = UsageBudget::set_for_task(
+            &pool,
+            task_id,
+            &SetUsageBudget {
+                max_tokens: Some(1_000),
+                max_cost_usd: None,
+                period: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(task_budget.id, first.id);
+        assert_eq!(task_budget.max_tokens, Some(1_000));
+
+        assert_eq!(
+            UsageBudget::find_exceeded(&pool, process_id).await.unwrap(),
+            None
+        );
+
+        // 400 + 200 tokens reaches the project budget but not the task budget
+        ExecutionProcessUsage::record_tokens(
+            &pool,
+            process_id,
+            TokenCounts {
+                input_tokens: 400,
+                output_tokens: 200,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let exceeded = UsageBudget::find_exceeded(&pool, process_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(exceeded.budget.project_id, Some(project_id));
+        assert_eq!(exceeded.exceeded, Some(BudgetLimit::Tokens));
+        assert_eq!(exceeded.spend.tokens, 600);
+
+        ExecutionProcessUsage::record_tokens(
+            &pool,
+            process_id,
+            TokenCounts {
+                input_tokens: 900,
+                output_tokens: 300,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let exceeded = UsageBudget::find_exceeded(&pool, process_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(exceeded.budget.task_id, Some(task_id));
+        assert_eq!(
+            exceeded.describe(),
+            "Task budget of 1000 tokens reached (1200 tokens used)"
+        );
+
+        assert_eq!(
+            UsageBudget::delete_for_task(&pool, task_id).await.unwrap(),
+            1
+        );
+        assert!(
+            UsageBudget::find_by_task_id(&pool, task_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -2,16 +2,29 @@ use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqlitePool, Type};
 use ts_rs::TS;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum WebhookEventType {
     ApprovalRequest,
     PendingQuestion,
     ExecutorFinish,
+    BudgetExceeded,
+    TaskCreated,
+    TaskStatusChanged,
+    TaskArchived,
+    AttemptCreated,
+    SetupScriptFailed,
+    MergeCompleted,
+    PrOpened,
+    PrMerged,
+    RebaseConflict,
+    NodeOnline,
+    NodeOffline,
 }
 
 impl WebhookEventType {
@@ -20,6 +33,18 @@ impl WebhookEventType {
             Self::ApprovalRequest => "approval_request",
             Self::PendingQuestion => "pending_question",
             Self::ExecutorFinish => "executor_finish",
+            Self::BudgetExceeded => "budget_exceeded",
+            Self::TaskCreated => "task_created",
+            Self::TaskStatusChanged => "task_status_changed",
+            Self::TaskArchived => "task_archived",
+            Self::AttemptCreated => "attempt_created",
+            Self::SetupScriptFailed => "setup_script_failed",
+            Self::MergeCompleted => "merge_completed",
+            Self::PrOpened => "pr_opened",
+            Self::PrMerged => "pr_merged",
+            Self::RebaseConflict => "rebase_conflict",
+            Self::NodeOnline => "node_online",
+            Self::NodeOffline => "node_offline",
         }
     }
 }
@@ -189,9 +214,10 @@ impl Webhook {
     /// Find webhooks applicable for a project+event in a single atomic query.
     /// Project webhooks matching the event are always included if active.
     /// Global webhooks are included unless any matching project webhook has override_global=true.
+    /// Events without a project only match global webhooks.
     pub async fn find_applicable(
         pool: &SqlitePool,
-        project_id: Uuid,
+        project_id: Option<Uuid>,
         event_type: &WebhookEventType,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let event_str = event_type.as_str();
@@ -0,0 +1,326 @@
+//! Delivery log for outbound webhooks.
+//!
+//! Every payload sent to a webhook is recorded with the outcome of its latest attempt, so
+//! failed or dropped deliveries can be inspected and sent again.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::webhook::WebhookEventType;
+
+/// Deliveries kept per webhook; older ones are pruned when new ones are recorded
+pub const DELIVERY_LOG_LIMIT: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    /// Being sent, or waiting for a retry
+    Pending,
+    Succeeded,
+    /// Retries exhausted or the error was not retryable
+    Failed,
+    /// Never sent because too many deliveries were in flight
+    Dropped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event_type: WebhookEventType,
+    /// JSON body as sent to the endpoint
+    pub request_body: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempt_count: i64,
+    /// HTTP status of the latest attempt; unset when no response was received
+    pub response_status: Option<i64>,
+    pub latency_ms: Option<i64>,
+    pub error: Option<String>,
+    /// The delivery this one was manually resent from
+    pub redelivery_of: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct CreateWebhookDelivery<'a> {
+    pub webhook_id: Uuid,
+    pub event_type: WebhookEventType,
+    pub request_body: &'a str,
+    pub status: WebhookDeliveryStatus,
+    pub error: Option<&'a str>,
+    pub redelivery_of: Option<Uuid>,
+}
+
+/// Outcome of a single delivery attempt
+#[derive(Debug, Clone, Default)]
+pub struct DeliveryAttempt {
+    pub response_status: Option<i64>,
+    pub latency_ms: Option<i64>,
+    pub error: Option<String>,
+}
+
+impl WebhookDelivery {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateWebhookDelivery<'_>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let delivery = sqlx::query_as!(
+            WebhookDelivery,
+            r#"INSERT INTO webhook_deliveries
+                   (id, webhook_id, event_type, request_body, status, error, redelivery_of)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id AS "id!: Uuid",
+                         webhook_id AS "webhook_id!: Uuid",
+                         event_type AS "event_type!: WebhookEventType",
+                         request_body,
+                         status AS "status!: WebhookDeliveryStatus",
+                         attempt_count,
+                         response_status,
+                         latency_ms,
+                         error,
+                         redelivery_of AS "redelivery_of: Uuid",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            data.webhook_id,
+            data.event_type,
+            data.request_body,
+            data.status,
+            data.error,
+            data.redelivery_of
+        )
+        .fetch_one(pool)
+        .await?;
+        Self::prune(pool, data.webhook_id, DELIVERY_LOG_LIMIT).await?;
+        Ok(delivery)
+    }
+
+    /// Record the outcome of an attempt. Returns false if the delivery no longer exists, e.g.
+    /// because its webhook was deleted.
+    pub async fn record_attempt(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: WebhookDeliveryStatus,
+        attempt: &DeliveryAttempt,
+    ) -> Result<bool, sqlx::Error> {
+        let error = attempt.error.as_deref();
+        let result = sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = $1, attempt_count = attempt_count + 1, response_status = $2,
+                   latency_ms = $3, error = $4, updated_at = datetime('now', 'subsec')
+               WHERE id = $5"#,
+            status,
+            attempt.response_status,
+            attempt.latency_ms,
+            error,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id AS "id!: Uuid",
+                      webhook_id AS "webhook_id!: Uuid",
+                      event_type AS "event_type!: WebhookEventType",
+                      request_body,
+                      status AS "status!: WebhookDeliveryStatus",
+                      attempt_count,
+                      response_status,
+                      latency_ms,
+                      error,
+                      redelivery_of AS "redelivery_of: Uuid",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Most recent deliveries first
+    pub async fn find_by_webhook_id(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id AS "id!: Uuid",
+                      webhook_id AS "webhook_id!: Uuid",
+                      event_type AS "event_type!: WebhookEventType",
+                      request_body,
+                      status AS "status!: WebhookDeliveryStatus",
+                      attempt_count,
+                      response_status,
+                      latency_ms,
+                      error,
+                      redelivery_of AS "redelivery_of: Uuid",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE webhook_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            webhook_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Deliveries still being sent or waiting for a retry, oldest first
+    pub async fn find_pending(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id AS "id!: Uuid",
+                      webhook_id AS "webhook_id!: Uuid",
+                      event_type AS "event_type!: WebhookEventType",
+                      request_body,
+                      status AS "status!: WebhookDeliveryStatus",
+                      attempt_count,
+                      response_status,
+                      latency_ms,
+                      error,
+                      redelivery_of AS "redelivery_of: Uuid",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE status = 'pending'
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Keep only the `keep` most recent deliveries of a webhook
+    async fn prune(pool: &SqlitePool, webhook_id: Uuid, keep: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM webhook_deliveries
+               WHERE webhook_id = $1
+                 AND id NOT IN (
+                     SELECT id FROM webhook_deliveries
+                     WHERE webhook_id = $1
+                     ORDER BY created_at DESC
+                     LIMIT $2
+                 )"#,
+            webhook_id,
+            keep
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::webhook::{CreateWebhook, Webhook},
+        test_utils::create_test_pool,
+    };
+
+    #[tokio::test]
+    async fn test_record_attempts_and_prune() {
+        let (pool, _tmp) = create_test_pool().await;
+        let webhook = Webhook::create(
+            &pool,
+            None,
+            &CreateWebhook {
+                name: "Deliveries".to_string(),
+                url: "https://example.com/hook".to_string(),
+                events: vec![WebhookEventType::ExecutorFinish],
+                headers: Default::default(),
+                secret: None,
+                payload_template: None,
+                override_global: false,
+                active: true,
+            },
+        )
+        .await
+        .unwrap();
+
+        let delivery = WebhookDelivery::create(
+            &pool,
+            &CreateWebhookDelivery {
+                webhook_id: webhook.id,
+                event_type: WebhookEventType::ExecutorFinish,
+                request_body: "{}",
+                status: WebhookDeliveryStatus::Pending,
+                error: None,
+                redelivery_of: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(delivery.attempt_count, 0);
+
+        let failed = DeliveryAttempt {
+            response_status: Some(503),
+            latency_ms: Some(12),
+            error: Some("HTTP 503".to_string()),
+        };
+        assert!(
+            WebhookDelivery::record_attempt(
+                &pool,
+                delivery.id,
+                WebhookDeliveryStatus::Pending,
+                &failed
+            )
+            .await
+            .unwrap()
+        );
+        // Waiting for its retry, so a restart picks it up
+        let pending = WebhookDelivery::find_pending(&pool).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, delivery.id);
+        assert_eq!(pending[0].attempt_count, 1);
+
+        WebhookDelivery::record_attempt(
+            &pool,
+            delivery.id,
+            WebhookDeliveryStatus::Succeeded,
+            &DeliveryAttempt {
+                response_status: Some(200),
+                latency_ms: Some(8),
+                error: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let delivery = WebhookDelivery::find_by_id(&pool, delivery.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Succeeded);
+        assert_eq!(delivery.attempt_count, 2);
+        assert_eq!(delivery.response_status, Some(200));
+        assert_eq!(delivery.error, None);
+        assert!(
+            WebhookDelivery::find_pending(&pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        WebhookDelivery::prune(&pool, webhook.id, 0).await.unwrap();
+        assert!(
+            WebhookDelivery::find_by_webhook_id(&pool, webhook.id, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -27,7 +27,9 @@ use services::services::{
     github_sync::GitHubSyncService,
     image::{ImageError, ImageService},
     pr_monitor::PrMonitorService,
+    secrets::SecretStore,
     share::{RemoteSync, RemoteSyncHandle, ShareConfig, SharePublisher},
+    task_webhooks::TaskWebhookWatcher,
     worktree_manager::WorktreeError,
 };
 use sqlx::{Error as SqlxError, types::Uuid};
@@ -98,6 +100,8 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn drafts(&self) -> &DraftsService;
 
+    fn secrets(&self) -> &Arc<SecretStore>;
+
     fn auth_context(&self) -> &AuthContext;
 
     fn share_publisher(&self) -> Result<SharePublisher, RemoteClientNotConfigured>;
@@ -128,6 +132,12 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         PrMonitorService::spawn(db, publisher).await
     }
 
+    async fn spawn_task_webhook_watcher(&self) -> tokio::task::JoinHandle<()> {
+        let db = self.db().clone();
+        let msg_store = self.events().msg_store().clone();
+        TaskWebhookWatcher::spawn(db, msg_store).await
+    }
+
     async fn spawn_github_sync_service(&self) -> tokio::task::JoinHandle<()> {
         let db = self.db().clone();
         GitHubSyncService::spawn(db).await
@@ -38,7 +38,7 @@ impl Executable for CodingAgentFollowUpRequest {
         &self,
         current_dir: &Path,
         approvals: Arc<dyn ExecutorApprovalService>,
-        _context: SpawnContext,
+        context: SpawnContext,
     ) -> Result<SpawnedChild, ExecutorError> {
         let executor_profile_id = self.get_executor_profile_id();
         let mut agent = ExecutorConfigs::get_cached()
@@ -48,6 +48,7 @@ impl Executable for CodingAgentFollowUpRequest {
             ))?;
 
         agent.use_approvals(approvals.clone());
+        agent.resolve_env(&context.variables);
 
         agent
             .spawn_follow_up(current_dir, &self.prompt, &self.session_id)
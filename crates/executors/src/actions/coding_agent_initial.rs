@@ -42,6 +42,7 @@ impl Executable for CodingAgentInitialRequest {
             ))?;
 
         agent.use_approvals(approvals.clone());
+        agent.resolve_env(&context.variables);
 
         agent.spawn(current_dir, &self.prompt, context).await
     }
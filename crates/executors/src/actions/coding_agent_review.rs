@@ -67,6 +67,7 @@ impl Executable for CodingAgentReviewRequest {
             ))?;
 
         agent.use_approvals(approvals);
+        agent.resolve_env(&context.variables);
         agent.spawn_review(current_dir, self, context).await
     }
 }
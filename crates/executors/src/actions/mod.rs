@@ -1,4 +1,4 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, fmt, path::Path, sync::Arc};
 
 use async_trait::async_trait;
 use enum_dispatch::enum_dispatch;
@@ -10,7 +10,8 @@ use crate::{
     actions::{
         coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
-        coding_agent_review::CodingAgentReviewRequest, script::ScriptRequest,
+        coding_agent_review::CodingAgentReviewRequest, pipeline::PipelineState,
+        script::ScriptRequest,
     },
     approvals::ExecutorApprovalService,
     executors::{BaseCodingAgent, ExecutorError, SpawnedChild},
@@ -18,13 +19,28 @@ use crate::{
 pub mod coding_agent_follow_up;
 pub mod coding_agent_initial;
 pub mod coding_agent_review;
+pub mod pipeline;
 pub mod script;
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct SpawnContext {
     pub task_attempt_id: Uuid,
     pub task_id: Uuid,
     pub execution_process_id: Uuid,
+    /// Values available to `${VAR}` references in a profile's `env` map (task variables
+    /// and local secrets). Never persisted and redacted from `Debug` output.
+    pub variables: HashMap<String, String>,
+}
+
+impl fmt::Debug for SpawnContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpawnContext")
+            .field("task_attempt_id", &self.task_attempt_id)
+            .field("task_id", &self.task_id)
+            .field("execution_process_id", &self.execution_process_id)
+            .field("variables", &self.variables.keys().collect::<Vec<_>>())
+            .finish()
+    }
 }
 
 #[enum_dispatch]
@@ -41,11 +57,23 @@ pub enum ExecutorActionType {
 pub struct ExecutorAction {
     pub typ: ExecutorActionType,
     pub next_action: Option<Box<ExecutorAction>>,
+    /// Set when this action executes a step of a project pipeline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<PipelineState>,
 }
 
 impl ExecutorAction {
     pub fn new(typ: ExecutorActionType, next_action: Option<Box<ExecutorAction>>) -> Self {
-        Self { typ, next_action }
+        Self {
+            typ,
+            next_action,
+            pipeline: None,
+        }
+    }
+
+    pub fn with_pipeline(mut self, pipeline: PipelineState) -> Self {
+        self.pipeline = Some(pipeline);
+        self
     }
     pub fn append_action(mut self, action: ExecutorAction) -> Self {
         if let Some(next) = self.next_action {
@@ -64,6 +92,10 @@ impl ExecutorAction {
         self.next_action.as_deref()
     }
 
+    pub fn pipeline(&self) -> Option<&PipelineState> {
+        self.pipeline.as_ref()
+    }
+
     pub fn base_executor(&self) -> Option<BaseCodingAgent> {
         match self.typ() {
             ExecutorActionType::CodingAgentInitialRequest(request) => Some(request.base_executor()),
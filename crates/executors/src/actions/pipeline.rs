@@ -0,0 +1,379 @@
+//! Declarative multi-step pipelines.
+//!
+//! A [`Pipeline`] replaces the default setup → agent → cleanup chain for a project with an
+//! ordered list of named steps. Each step branches on its exit status via `on_success` /
+//! `on_failure`, so loops such as "run tests → on failure ask the agent to fix → run tests
+//! again" can be expressed, bounded by `max_runs`. The running state travels with each
+//! [`ExecutorAction`](super::ExecutorAction) as a [`PipelineState`].
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+
+use crate::{actions::script::ScriptRequestLanguage, profile::ExecutorProfileId};
+
+/// Reserved transition target that ends the pipeline.
+pub const PIPELINE_END: &str = "end";
+
+/// Upper bound on executions in a single pipeline run, guarding against unbounded loops.
+pub const MAX_PIPELINE_EXECUTIONS: u32 = 50;
+
+/// Follow-up sent to the agent when a project's verification script fails.
+const VERIFICATION_FIX_PROMPT: &str = "The verification script failed after your changes. \
+Fix the problems reported below so that it passes.\n\n```\n{output}\n```";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PipelineError {
+    #[error("Pipeline must contain at least one step")]
+    Empty,
+    #[error("Step name must not be empty")]
+    EmptyStepName,
+    #[error("Step name '{0}' is reserved")]
+    ReservedStepName(String),
+    #[error("Duplicate step name '{0}'")]
+    DuplicateStep(String),
+    #[error("Step '{step}' references unknown step '{target}'")]
+    UnknownTarget { step: String, target: String },
+    #[error("Step '{0}' has max_runs of 0")]
+    ZeroMaxRuns(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum PipelineStepKind {
+    /// Run the task prompt with the attempt's coding agent
+    CodingAgent,
+    /// Run a script in the worktree, in the project's script language unless `language` is set
+    Script {
+        script: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        language: Option<ScriptRequestLanguage>,
+    },
+    /// Run a shell script in the worktree, labelled as the project's verification script
+    Verification { script: String },
+    /// Send a follow-up to the agent's latest session. `{output}` in the prompt is replaced
+    /// with the tail of the previous step's output.
+    FollowUp { prompt: String },
+    /// Review the attempt's changes against its target branch, or with custom instructions.
+    /// Uses the attempt's profile unless `executor_profile_id` names a review-capable one.
+    Review {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        instructions: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        executor_profile_id: Option<ExecutorProfileId>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct PipelineStep {
+    pub name: String,
+    pub kind: PipelineStepKind,
+    /// Step to run when this one succeeds. Defaults to the next step in the list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_success: Option<String>,
+    /// Step to run when this one fails. Defaults to ending the pipeline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<String>,
+    /// Maximum number of times this step may run in one pipeline run. Unlimited when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_runs: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct Pipeline {
+    pub steps: Vec<PipelineStep>,
+}
+
+impl Pipeline {
+    pub fn validate(&self) -> Result<(), PipelineError> {
+        if self.steps.is_empty() {
+            return Err(PipelineError::Empty);
+        }
+
+        let mut names = HashSet::new();
+        for step in &self.steps {
+            if step.name.trim().is_empty() {
+                return Err(PipelineError::EmptyStepName);
+            }
+            if step.name == PIPELINE_END {
+                return Err(PipelineError::ReservedStepName(step.name.clone()));
+            }
+            if !names.insert(step.name.as_str()) {
+                return Err(PipelineError::DuplicateStep(step.name.clone()));
+            }
+            if step.max_runs == Some(0) {
+                return Err(PipelineError::ZeroMaxRuns(step.name.clone()));
+            }
+        }
+
+        for step in &self.steps {
+            for target in [&step.on_success, &step.on_failure].into_iter().flatten() {
+                if target != PIPELINE_END && !names.contains(target.as_str()) {
+                    return Err(PipelineError::UnknownTarget {
+                        step: step.name.clone(),
+                        target: target.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The verify → fix loop run after a coding agent turn when a project has a verification
+    /// script. A failing run sends its output back to the agent as a follow-up, at most
+    /// `max_retries` times, after which the pipeline ends with the failure.
+    pub fn verification(script: String, max_retries: u32) -> Self {
+        let verify = PipelineStep {
+            name: "verify".to_string(),
+            kind: PipelineStepKind::Verification { script },
+            on_success: Some(PIPELINE_END.to_string()),
+            on_failure: Some(
+                if max_retries == 0 {
+                    PIPELINE_END
+                } else {
+                    "fix"
+                }
+                .to_string(),
+            ),
+            max_runs: None,
+        };
+        let fix = PipelineStep {
+            name: "fix".to_string(),
+            kind: PipelineStepKind::FollowUp {
+                prompt: VERIFICATION_FIX_PROMPT.to_string(),
+            },
+            on_success: Some("verify".to_string()),
+            on_failure: None,
+            max_runs: Some(max_retries.max(1)),
+        };
+        Self {
+            steps: vec![verify, fix],
+        }
+    }
+
+    fn step_index(&self, name: &str) -> Option<usize> {
+        self.steps.iter().position(|step| step.name == name)
+    }
+}
+
+/// Progress of a running pipeline, attached to the action executing its current step.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct PipelineState {
+    pub pipeline: Pipeline,
+    /// Index of the step the carrying action executes
+    pub current_step: usize,
+    /// Number of times each step has run so far, including the current one
+    pub runs: Vec<u32>,
+    /// Profile used for agent steps
+    pub executor_profile_id: ExecutorProfileId,
+    /// Expanded task prompt used by `coding_agent` steps
+    pub prompt: String,
+}
+
+impl PipelineState {
+    /// State for the first step of `pipeline`.
+    pub fn start(
+        pipeline: Pipeline,
+        executor_profile_id: ExecutorProfileId,
+        prompt: String,
+    ) -> Self {
+        let mut runs = vec![0; pipeline.steps.len()];
+        if let Some(first) = runs.first_mut() {
+            *first = 1;
+        }
+        Self {
+            pipeline,
+            current_step: 0,
+            runs,
+            executor_profile_id,
+            prompt,
+        }
+    }
+
+    pub fn step(&self) -> &PipelineStep {
+        &self.pipeline.steps[self.current_step]
+    }
+
+    /// State for the step that follows the current one given its outcome, or `None` when the
+    /// pipeline is finished (explicit `end`, no further step, or a run limit was reached).
+    pub fn advance(&self, succeeded: bool) -> Option<PipelineState> {
+        let step = self.step();
+        let target = if succeeded {
+            step.on_success.as_deref()
+        } else {
+            step.on_failure.as_deref().or(Some(PIPELINE_END))
+        };
+
+        let next = match target {
+            Some(PIPELINE_END) => return None,
+            Some(name) => self.pipeline.step_index(name)?,
+            None => self.current_step + 1,
+        };
+        let next_step = self.pipeline.steps.get(next)?;
+
+        if next_step
+            .max_runs
+            .is_some_and(|max_runs| self.runs[next] >= max_runs)
+        {
+            return None;
+        }
+        if self.runs.iter().sum::<u32>() >= MAX_PIPELINE_EXECUTIONS {
+            return None;
+        }
+
+        let mut runs = self.runs.clone();
+        runs[next] += 1;
+        Some(PipelineState {
+            current_step: next,
+            runs,
+            ..self.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executors::BaseCodingAgent;
+
+    fn step(name: &str, kind: PipelineStepKind) -> PipelineStep {
+        PipelineStep {
+            name: name.to_string(),
+            kind,
+            on_success: None,
+            on_failure: None,
+            max_runs: None,
+        }
+    }
+
+    /// agent → tests → (fail: fix → tests, max 2 fixes) → review
+    fn test_fix_pipeline() -> Pipeline {
+        let mut tests = step(
+            "tests",
+            PipelineStepKind::Script {
+                script: "cargo test".to_string(),
+                language: None,
+            },
+        );
+        tests.on_success = Some("review".to_string());
+        tests.on_failure = Some("fix".to_string());
+
+        let mut fix = step(
+            "fix",
+            PipelineStepKind::FollowUp {
+                prompt: "Tests failed:\n{output}".to_string(),
+            },
+        );
+        fix.on_success = Some("tests".to_string());
+        fix.max_runs = Some(2);
+
+        let mut review = step(
+            "review",
+            PipelineStepKind::Review {
+                instructions: None,
+                executor_profile_id: None,
+            },
+        );
+        review.on_success = Some(PIPELINE_END.to_string());
+
+        Pipeline {
+            steps: vec![
+                step("agent", PipelineStepKind::CodingAgent),
+                tests,
+                review,
+                fix,
+            ],
+        }
+    }
+
+    fn start(pipeline: Pipeline) -> PipelineState {
+        PipelineState::start(
+            pipeline,
+            ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+            "Do the thing".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_targets_and_duplicates() {
+        assert!(test_fix_pipeline().validate().is_ok());
+
+        let mut pipeline = test_fix_pipeline();
+        pipeline.steps[0].on_failure = Some("missing".to_string());
+        assert_eq!(
+            pipeline.validate(),
+            Err(PipelineError::UnknownTarget {
+                step: "agent".to_string(),
+                target: "missing".to_string(),
+            })
+        );
+
+        let mut pipeline = test_fix_pipeline();
+        pipeline.steps[1].name = "agent".to_string();
+        assert_eq!(
+            pipeline.validate(),
+            Err(PipelineError::DuplicateStep("agent".to_string()))
+        );
+
+        assert_eq!(
+            Pipeline { steps: vec![] }.validate(),
+            Err(PipelineError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_advance_follows_branches_until_loop_limit() {
+        let state = start(test_fix_pipeline());
+
+        let tests = state.advance(true).unwrap();
+        assert_eq!(tests.step().name, "tests");
+
+        // Two failing test runs each trigger a fix
+        let fix = tests.advance(false).unwrap();
+        assert_eq!(fix.step().name, "fix");
+        let tests = fix.advance(true).unwrap();
+        let fix = tests.advance(false).unwrap();
+        assert_eq!(fix.runs[3], 2);
+
+        // Third failure exceeds fix.max_runs and ends the pipeline
+        let tests = fix.advance(true).unwrap();
+        assert!(tests.advance(false).is_none());
+
+        // Passing tests go on to review, which ends the pipeline
+        let review = tests.advance(true).unwrap();
+        assert_eq!(review.step().name, "review");
+        assert!(review.advance(true).is_none());
+    }
+
+    #[test]
+    fn test_verification_retries_fixes_until_limit() {
+        let pipeline = Pipeline::verification("cargo test".to_string(), 2);
+        assert!(pipeline.validate().is_ok());
+
+        let verify = start(pipeline);
+        assert!(verify.advance(true).is_none());
+
+        let fix = verify.advance(false).unwrap();
+        assert_eq!(fix.step().name, "fix");
+        let verify = fix.advance(true).unwrap();
+        let fix = verify.advance(false).unwrap();
+        let verify = fix.advance(true).unwrap();
+        assert!(verify.advance(false).is_none());
+
+        // A failed fix turn ends the loop
+        assert!(fix.advance(false).is_none());
+
+        let verify = start(Pipeline::verification("cargo test".to_string(), 0));
+        assert!(verify.advance(false).is_none());
+    }
+
+    #[test]
+    fn test_failure_without_branch_ends_pipeline() {
+        let state = start(test_fix_pipeline());
+        assert!(state.advance(false).is_none());
+    }
+}
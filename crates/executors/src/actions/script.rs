@@ -1,11 +1,21 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::Type;
 use tokio::process::Command;
 use ts_rs::TS;
-use workspace_utils::shell::get_shell_command;
+use workspace_utils::{
+    path::get_vibe_kanban_temp_dir,
+    shell::{get_shell_command, resolve_executable_path},
+};
 
 use crate::{
     actions::{Executable, SpawnContext},
@@ -13,9 +23,70 @@ use crate::{
     executors::{ExecutorError, SpawnedChild},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS, Type)]
+#[sqlx(type_name = "TEXT")]
 pub enum ScriptRequestLanguage {
+    /// The platform shell: the user's shell on Unix, `cmd` on Windows
     Bash,
+    Python,
+    Node,
+    /// PowerShell 7+ (`pwsh`)
+    PowerShell,
+    /// Use the interpreter named on the script's `#!` line, falling back to `Bash`
+    #[default]
+    Shebang,
+}
+
+impl ScriptRequestLanguage {
+    /// Extension of the materialized script file. Some interpreters (notably `pwsh`) refuse
+    /// to run files without the expected extension.
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Bash | Self::Shebang if cfg!(windows) => "cmd",
+            Self::Bash | Self::Shebang => "sh",
+            Self::Python => "py",
+            Self::Node => "js",
+            Self::PowerShell => "ps1",
+        }
+    }
+
+    /// Executables to try, in order of preference
+    fn interpreter_candidates(&self) -> &'static [&'static str] {
+        match self {
+            Self::Python if cfg!(windows) => &["python", "py", "python3"],
+            Self::Python => &["python3", "python"],
+            Self::Node => &["node", "nodejs"],
+            Self::PowerShell if cfg!(windows) => &["pwsh", "powershell"],
+            Self::PowerShell => &["pwsh"],
+            Self::Bash | Self::Shebang => &[],
+        }
+    }
+
+    /// Arguments passed to the interpreter before the script path
+    fn interpreter_args(&self) -> &'static [&'static str] {
+        match self {
+            Self::PowerShell => &["-NoLogo", "-NoProfile", "-NonInteractive", "-File"],
+            Self::Bash | Self::Python | Self::Node | Self::Shebang => &[],
+        }
+    }
+
+    /// Language of an interpreter named on a shebang line
+    fn from_program(program: &str) -> Self {
+        let name = Path::new(program)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let name = name.strip_suffix(".exe").unwrap_or(&name);
+        if name.starts_with("python") || name == "py" {
+            Self::Python
+        } else if name == "node" || name == "nodejs" {
+            Self::Node
+        } else if name == "pwsh" || name == "powershell" {
+            Self::PowerShell
+        } else {
+            Self::Bash
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -24,6 +95,10 @@ pub enum ScriptContext {
     CleanupScript,
     DevServer,
     ToolInstallScript,
+    PipelineScript,
+    VerificationScript,
+    /// A command a coding agent ran, run again from the attempt's command timeline
+    CommandRerun,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -33,6 +108,192 @@ pub struct ScriptRequest {
     pub context: ScriptContext,
 }
 
+/// A resolved interpreter invocation; the script path is appended to `args`.
+#[derive(Debug, Clone, PartialEq)]
+struct Interpreter {
+    language: ScriptRequestLanguage,
+    program: PathBuf,
+    args: Vec<String>,
+}
+
+/// Split a `#!` line into the interpreter and its arguments, unwrapping `/usr/bin/env`
+/// (including `env -S`) so the named program can be resolved on PATH.
+fn parse_shebang(script: &str) -> Option<Vec<String>> {
+    let line = script.lines().next()?.strip_prefix("#!")?;
+    let mut tokens: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+    if tokens
+        .first()
+        .is_some_and(|program| Path::new(program).file_name() == Some(OsStr::new("env")))
+    {
+        tokens.remove(0);
+        if tokens.first().is_some_and(|flag| flag == "-S") {
+            tokens.remove(0);
+        }
+    }
+    (!tokens.is_empty()).then_some(tokens)
+}
+
+/// Resolve `program` on PATH. Absolute shebang paths that don't exist on this machine (e.g.
+/// `/usr/bin/python3` on Windows or NixOS) fall back to a lookup of the bare program name.
+async fn resolve_program(program: &str) -> Result<PathBuf, ExecutorError> {
+    if let Some(path) = resolve_executable_path(program).await {
+        return Ok(path);
+    }
+    if let Some(name) = Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        && name != program
+        && let Some(path) = resolve_executable_path(name).await
+    {
+        return Ok(path);
+    }
+    Err(ExecutorError::ExecutableNotFound {
+        program: program.to_string(),
+    })
+}
+
+async fn resolve_interpreter(
+    language: &ScriptRequestLanguage,
+    script: &str,
+) -> Result<Interpreter, ExecutorError> {
+    if *language == ScriptRequestLanguage::Shebang
+        && let Some(mut tokens) = parse_shebang(script)
+    {
+        let program = tokens.remove(0);
+        return Ok(Interpreter {
+            language: ScriptRequestLanguage::from_program(&program),
+            program: resolve_program(&program).await?,
+            args: tokens,
+        });
+    }
+
+    match language {
+        ScriptRequestLanguage::Bash | ScriptRequestLanguage::Shebang => {
+            let (shell_cmd, shell_arg) = get_shell_command();
+            Ok(Interpreter {
+                language: ScriptRequestLanguage::Bash,
+                program: PathBuf::from(shell_cmd),
+                // `cmd` needs `/C` to run a batch file; Unix shells take the path directly
+                args: if cfg!(windows) {
+                    vec![shell_arg.to_string()]
+                } else {
+                    vec![]
+                },
+            })
+        }
+        _ => {
+            for candidate in language.interpreter_candidates() {
+                if let Some(program) = resolve_executable_path(candidate).await {
+                    return Ok(Interpreter {
+                        language: language.clone(),
+                        program,
+                        args: language
+                            .interpreter_args()
+                            .iter()
+                            .map(|arg| arg.to_string())
+                            .collect(),
+                    });
+                }
+            }
+            Err(ExecutorError::ExecutableNotFound {
+                program: language.interpreter_candidates().join(" or "),
+            })
+        }
+    }
+}
+
+/// Materialized scripts not run for this long are removed from the cache
+const SCRIPT_CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Write `script` to a file the interpreter can run, rather than passing it inline as a
+/// single argument (which breaks on long scripts and on quoting).
+///
+/// Files are named by the hash of their content, so repeated and concurrent runs of the
+/// same script share one file. Each run refreshes the file's modification time; files
+/// older than [`SCRIPT_CACHE_TTL`] are pruned whenever a new script is written.
+async fn materialize_script(script: &str, extension: &str) -> Result<PathBuf, ExecutorError> {
+    materialize_script_in(
+        &get_vibe_kanban_temp_dir().join("scripts"),
+        script,
+        extension,
+    )
+    .await
+}
+
+async fn materialize_script_in(
+    dir: &Path,
+    script: &str,
+    extension: &str,
+) -> Result<PathBuf, ExecutorError> {
+    let digest = Sha256::digest(script.as_bytes());
+    let hex = digest
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+
+    let path = dir.join(format!("{hex}.{extension}"));
+    if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        touch(&path);
+        return Ok(path);
+    }
+
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(ExecutorError::Io)?;
+    // Write under a unique name and rename into place so a concurrent run never sees a
+    // partially written file
+    let staging = dir.join(format!("{hex}.{}.tmp", uuid::Uuid::new_v4()));
+    tokio::fs::write(&staging, script)
+        .await
+        .map_err(ExecutorError::Io)?;
+    if let Err(e) = tokio::fs::rename(&staging, &path).await {
+        let _ = tokio::fs::remove_file(&staging).await;
+        // Another run may have won the race (rename does not replace files on Windows)
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Err(ExecutorError::Io(e));
+        }
+    }
+    prune_script_cache(dir, SCRIPT_CACHE_TTL).await;
+    Ok(path)
+}
+
+/// Mark a cached script as used, so pruning keeps it
+fn touch(path: &Path) {
+    if let Err(e) = std::fs::File::options()
+        .append(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+    {
+        tracing::debug!("Failed to refresh cached script {}: {}", path.display(), e);
+    }
+}
+
+/// Remove cached scripts (and abandoned staging files) last used more than `ttl` ago.
+/// Best effort: a file a running process still has open may fail to delete on Windows and
+/// is retried on the next prune.
+async fn prune_script_cache(dir: &Path, ttl: Duration) {
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return;
+    };
+    let Some(cutoff) = SystemTime::now().checked_sub(ttl) else {
+        return;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let stale = entry
+            .metadata()
+            .await
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified < cutoff);
+        if stale && let Err(e) = tokio::fs::remove_file(entry.path()).await {
+            tracing::debug!(
+                "Failed to remove stale script {}: {}",
+                entry.path().display(),
+                e
+            );
+        }
+    }
+}
+
 #[async_trait]
 impl Executable for ScriptRequest {
     async fn spawn(
@@ -41,15 +302,18 @@ impl Executable for ScriptRequest {
         _approvals: Arc<dyn ExecutorApprovalService>,
         _context: SpawnContext,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+        let interpreter = resolve_interpreter(&self.language, &self.script).await?;
+        let script_path =
+            materialize_script(&self.script, interpreter.language.extension()).await?;
+
+        let mut command = Command::new(&interpreter.program);
         command
             .kill_on_drop(true)
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .arg(shell_arg)
-            .arg(&self.script)
+            .args(&interpreter.args)
+            .arg(&script_path)
             .current_dir(current_dir);
 
         let child = command.group_spawn()?;
@@ -57,3 +321,87 @@ impl Executable for ScriptRequest {
         Ok(child.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shebang_unwraps_env() {
+        assert_eq!(
+            parse_shebang("#!/usr/bin/env python3\nprint('hi')"),
+            Some(vec!["python3".to_string()])
+        );
+        assert_eq!(
+            parse_shebang("#!/usr/bin/env -S node --no-warnings\n"),
+            Some(vec!["node".to_string(), "--no-warnings".to_string()])
+        );
+        assert_eq!(
+            parse_shebang("#!/bin/bash -e\necho hi"),
+            Some(vec!["/bin/bash".to_string(), "-e".to_string()])
+        );
+        assert_eq!(parse_shebang("echo hi\n#!/bin/bash"), None);
+        assert_eq!(parse_shebang("#!\n"), None);
+    }
+
+    #[test]
+    fn test_language_from_shebang_program() {
+        assert_eq!(
+            ScriptRequestLanguage::from_program("/usr/bin/python3.12"),
+            ScriptRequestLanguage::Python
+        );
+        assert_eq!(
+            ScriptRequestLanguage::from_program("node"),
+            ScriptRequestLanguage::Node
+        );
+        assert_eq!(
+            ScriptRequestLanguage::from_program("pwsh.exe"),
+            ScriptRequestLanguage::PowerShell
+        );
+        assert_eq!(
+            ScriptRequestLanguage::from_program("/bin/zsh"),
+            ScriptRequestLanguage::Bash
+        );
+    }
+
+    #[tokio::test]
+    async fn test_materialize_script_is_content_addressed() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = format!("echo {}", uuid::Uuid::new_v4());
+        let first = materialize_script_in(dir.path(), &script, "sh")
+            .await
+            .unwrap();
+        let second = materialize_script_in(dir.path(), &script, "sh")
+            .await
+            .unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.extension(), Some(OsStr::new("sh")));
+        assert_eq!(tokio::fs::read_to_string(&first).await.unwrap(), script);
+
+        let other = materialize_script_in(dir.path(), "echo other", "sh")
+            .await
+            .unwrap();
+        assert_ne!(first, other);
+    }
+
+    #[tokio::test]
+    async fn test_prune_script_cache_removes_stale_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = materialize_script_in(dir.path(), "echo old", "sh")
+            .await
+            .unwrap();
+        std::fs::File::options()
+            .append(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3600))
+            .unwrap();
+        let fresh = materialize_script_in(dir.path(), "echo fresh", "sh")
+            .await
+            .unwrap();
+
+        prune_script_cache(dir.path(), Duration::from_secs(60)).await;
+        assert!(!old.exists());
+        assert!(fresh.exists());
+    }
+}
@@ -1,8 +1,9 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::shell::resolve_executable_path;
 
@@ -29,6 +30,11 @@ impl CommandParts {
         Self { program, args }
     }
 
+    /// The unresolved program name (first token of the command line)
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
     pub async fn into_resolved(self) -> Result<(PathBuf, Vec<String>), ExecutorError> {
         let CommandParts { program, args } = self;
         let executable = resolve_executable_path(&program)
@@ -52,6 +58,88 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub additional_params: Option<Vec<String>>,
+    #[schemars(
+        title = "Environment Variables",
+        description = "Environment variables set on the agent process. Values may reference task variables or local secrets with ${VAR}"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+}
+
+impl CmdOverrides {
+    /// Expand `${VAR}` references in the `env` map in place.
+    ///
+    /// Names are looked up in `variables` (task variables and local secrets) and then in
+    /// the server's own environment. Unknown references are left untouched. Only names are
+    /// ever logged, never values.
+    pub fn resolve_env(&mut self, variables: &HashMap<String, String>) {
+        let Some(env) = self.env.as_mut() else {
+            return;
+        };
+        for (key, value) in env.iter_mut() {
+            let (expanded, missing) = expand_env_references(value, variables);
+            if !missing.is_empty() {
+                tracing::warn!(
+                    env_var = %key,
+                    missing = ?missing,
+                    "Profile env value references undefined variables"
+                );
+            }
+            *value = expanded;
+        }
+    }
+}
+
+/// Replace `${NAME}` references in `input`, returning the expanded string and the names
+/// that could not be resolved.
+fn expand_env_references(
+    input: &str,
+    variables: &HashMap<String, String>,
+) -> (String, Vec<String>) {
+    let mut output = String::with_capacity(input.len());
+    let mut missing = Vec::new();
+    let mut rest = input;
+
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            output.push_str(&rest[start..]);
+            return (output, missing);
+        };
+        let name = &after[..end];
+        let is_valid_name = !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+        let value = if is_valid_name {
+            variables
+                .get(name)
+                .cloned()
+                .or_else(|| std::env::var(name).ok())
+        } else {
+            None
+        };
+        match value {
+            Some(value) => output.push_str(&value),
+            None => {
+                if is_valid_name {
+                    missing.push(name.to_string());
+                }
+                output.push_str(&rest[start..start + end + 3]);
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    output.push_str(rest);
+    (output, missing)
+}
+
+/// Set the profile's `env` map on a command about to be spawned.
+pub fn apply_env(command: &mut Command, overrides: &CmdOverrides) {
+    if let Some(env) = &overrides.env {
+        command.envs(env);
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -156,3 +244,37 @@ pub fn apply_overrides(builder: CommandBuilder, overrides: &CmdOverrides) -> Com
         builder
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_env_expands_known_variables() {
+        let mut overrides = CmdOverrides {
+            env: Some(HashMap::from([(
+                "ANTHROPIC_BASE_URL".to_string(),
+                "https://${GATEWAY_HOST}/v1".to_string(),
+            )])),
+            ..Default::default()
+        };
+        let variables = HashMap::from([("GATEWAY_HOST".to_string(), "gw.internal".to_string())]);
+
+        overrides.resolve_env(&variables);
+
+        assert_eq!(
+            overrides.env.unwrap()["ANTHROPIC_BASE_URL"],
+            "https://gw.internal/v1"
+        );
+    }
+
+    #[test]
+    fn test_expand_env_references_keeps_unknown_and_malformed() {
+        let variables = HashMap::new();
+        let (expanded, missing) =
+            expand_env_references("${VK_SURELY_UNDEFINED_VAR}-${1BAD}-${open", &variables);
+
+        assert_eq!(expanded, "${VK_SURELY_UNDEFINED_VAR}-${1BAD}-${open");
+        assert_eq!(missing, vec!["VK_SURELY_UNDEFINED_VAR".to_string()]);
+    }
+}
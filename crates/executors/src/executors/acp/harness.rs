@@ -18,7 +18,7 @@ use workspace_utils::stream_lines::LinesStreamExt;
 
 use super::{AcpClient, SessionManager};
 use crate::{
-    command::CommandParts,
+    command::{CmdOverrides, CommandParts, apply_env},
     executors::{ExecutorError, ExecutorExitResult, SpawnContext, SpawnedChild, acp::AcpEvent},
 };
 
@@ -61,6 +61,7 @@ impl AcpAgentHarness {
         prompt: String,
         command_parts: CommandParts,
         context: SpawnContext,
+        overrides: &CmdOverrides,
     ) -> Result<SpawnedChild, ExecutorError> {
         let (program_path, args) = command_parts.into_resolved().await?;
         let mut command = Command::new(program_path);
@@ -87,6 +88,8 @@ impl AcpAgentHarness {
                 context.execution_process_id.to_string(),
             );
 
+        apply_env(&mut command, overrides);
+
         let mut child = command.group_spawn()?;
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<ExecutorExitResult>();
@@ -104,6 +107,7 @@ impl AcpAgentHarness {
             child,
             exit_signal: Some(exit_rx),
             protocol_peer: None,
+            temp_files: Vec::new(),
         })
     }
 
@@ -113,6 +117,7 @@ impl AcpAgentHarness {
         prompt: String,
         session_id: &str,
         command_parts: CommandParts,
+        overrides: &CmdOverrides,
     ) -> Result<SpawnedChild, ExecutorError> {
         // Note: VK environment variables (VK_ATTEMPT_ID, VK_TASK_ID, VK_EXECUTION_PROCESS_ID)
         // are inherited from the parent process that spawned this follow-up
@@ -133,6 +138,8 @@ impl AcpAgentHarness {
         command.env_remove("npm_config_verify_deps_before_run");
         command.env_remove("npm_config_globalconfig");
 
+        apply_env(&mut command, overrides);
+
         let mut child = command.group_spawn()?;
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<ExecutorExitResult>();
@@ -150,6 +157,7 @@ impl AcpAgentHarness {
             child,
             exit_signal: Some(exit_rx),
             protocol_peer: None,
+            temp_files: Vec::new(),
         })
     }
 
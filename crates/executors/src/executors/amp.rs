@@ -9,7 +9,7 @@ use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
 use crate::{
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_env, apply_overrides},
     executors::{
         AppendPrompt, ExecutorError, SpawnContext, SpawnedChild, StandardCodingAgentExecutor,
         claude::{ClaudeLogProcessor, HistoryStrategy},
@@ -80,6 +80,8 @@ impl StandardCodingAgentExecutor for Amp {
                 context.execution_process_id.to_string(),
             );
 
+        apply_env(&mut command, &self.cmd);
+
         let mut child = command.group_spawn()?;
 
         // Feed the prompt in, then close the pipe so amp sees EOF
@@ -121,6 +123,8 @@ impl StandardCodingAgentExecutor for Amp {
         fork_command.env_remove("npm_config_verify_deps_before_run");
         fork_command.env_remove("npm_config_globalconfig");
 
+        apply_env(&mut fork_command, &self.cmd);
+
         let fork_output = fork_command.output().await?;
         let stdout_str = String::from_utf8_lossy(&fork_output.stdout);
         let new_thread_id = stdout_str
@@ -162,6 +166,8 @@ impl StandardCodingAgentExecutor for Amp {
         command.env_remove("npm_config_verify_deps_before_run");
         command.env_remove("npm_config_globalconfig");
 
+        apply_env(&mut command, &self.cmd);
+
         let mut child = command.group_spawn()?;
 
         // Feed the prompt in, then close the pipe so amp sees EOF
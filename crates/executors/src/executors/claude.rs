@@ -26,7 +26,7 @@ use self::{
 use crate::{
     actions::SpawnContext,
     approvals::ExecutorApprovalService,
-    command::{CmdOverrides, CommandBuilder, CommandParts, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, CommandParts, apply_env, apply_overrides},
     executors::{
         AppendPrompt, AvailabilityInfo, ExecutorError, ProtocolPeer as ExecutorProtocolPeer,
         SpawnedChild, StandardCodingAgentExecutor, codex::client::LogWriter, session_index,
@@ -275,6 +275,7 @@ impl StandardCodingAgentExecutor for ClaudeCode {
             task_attempt_id: Uuid::nil(),
             task_id: Uuid::nil(),
             execution_process_id: Uuid::nil(),
+            variables: Default::default(),
         };
 
         self.spawn_internal(current_dir, prompt, command_parts, placeholder_context)
@@ -377,6 +378,8 @@ impl ClaudeCode {
                 context.execution_process_id.to_string(),
             );
 
+        apply_env(&mut command, &self.cmd);
+
         let mut child = command.group_spawn()?;
         let child_stdout = child.inner().stdout.take().ok_or_else(|| {
             ExecutorError::Io(std::io::Error::other("Claude Code missing stdout"))
@@ -448,6 +451,7 @@ impl ClaudeCode {
                 Some(exit_signal_rx)
             },
             protocol_peer: Some(Arc::new(ExecutorProtocolPeer::Claude(protocol_peer))),
+            temp_files: Vec::new(),
         })
     }
 }
@@ -2630,6 +2634,7 @@ mod tests {
             cmd: crate::command::CmdOverrides {
                 base_command_override: None,
                 additional_params: None,
+                env: None,
             },
             approvals_service: None,
             disable_api_key: None,
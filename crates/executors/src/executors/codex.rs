@@ -40,7 +40,7 @@ use self::{
 use crate::{
     actions::{SpawnContext, coding_agent_review::CodingAgentReviewRequest},
     approvals::ExecutorApprovalService,
-    command::{CmdOverrides, CommandBuilder, CommandParts, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, CommandParts, apply_env, apply_overrides},
     executors::{
         AppendPrompt, AvailabilityInfo, ExecutorError, ExecutorExitResult, ProtocolPeer,
         SpawnedChild, StandardCodingAgentExecutor,
@@ -238,6 +238,7 @@ impl StandardCodingAgentExecutor for Codex {
             task_attempt_id: Uuid::nil(),
             task_id: Uuid::nil(),
             execution_process_id: Uuid::nil(),
+            variables: Default::default(),
         };
 
         self.spawn_internal(
@@ -443,6 +444,8 @@ impl Codex {
                 context.execution_process_id.to_string(),
             );
 
+        apply_env(&mut process, &self.cmd);
+
         let mut child = process.group_spawn()?;
 
         let child_stdout = child.inner().stdout.take().ok_or_else(|| {
@@ -534,6 +537,7 @@ impl Codex {
             child,
             exit_signal: Some(exit_signal_rx),
             protocol_peer: Some(protocol_peer),
+            temp_files: Vec::new(),
         })
     }
 
@@ -696,6 +700,8 @@ impl Codex {
         Ok(())
     }
 
+    /// Ask the app server which models and modes it offers. Resolve the profile's `env`
+    /// (`CodingAgent::resolve_env`) first, as for a run.
     pub async fn discover_runtime_capabilities(
         &self,
         current_dir: &Path,
@@ -719,6 +725,9 @@ impl Codex {
         process.env_remove("npm_config_verify_deps_before_run");
         process.env_remove("npm_config_globalconfig");
 
+        // Same profile env as a real run, so discovery sees the same account and config
+        apply_env(&mut process, &self.cmd);
+
         let mut child = process.group_spawn()?;
         let child_stdout = child.inner().stdout.take().ok_or_else(|| {
             ExecutorError::Io(std::io::Error::other("Codex app server missing stdout"))
@@ -820,6 +829,8 @@ impl Codex {
         process.env_remove("npm_config_verify_deps_before_run");
         process.env_remove("npm_config_globalconfig");
 
+        apply_env(&mut process, &self.cmd);
+
         let mut child = process.group_spawn()?;
         let child_stdout = child.inner().stdout.take().ok_or_else(|| {
             ExecutorError::Io(std::io::Error::other("Codex app server missing stdout"))
@@ -877,6 +888,7 @@ impl Codex {
             child,
             exit_signal: Some(exit_signal_rx),
             protocol_peer: Some(protocol_peer),
+            temp_files: Vec::new(),
         })
     }
 }
@@ -139,6 +139,23 @@ impl SessionHandler {
         Ok((destination, new_session_id))
     }
 
+    /// Make an imported rollout file resumable by copying it into the sessions directory,
+    /// unless a rollout for `session_id` is already there. Returns the rollout's path.
+    pub fn install_rollout_file(source: &Path, session_id: &str) -> Result<PathBuf, SessionError> {
+        if let Ok(existing) = Self::find_rollout_file_path(session_id) {
+            return Ok(existing);
+        }
+        let destination = Self::create_new_rollout_path(session_id)?;
+        std::fs::copy(source, &destination).map_err(|e| {
+            SessionError::Io(format!(
+                "Failed to copy rollout file {} to {}: {e}",
+                source.display(),
+                destination.display()
+            ))
+        })?;
+        Ok(destination)
+    }
+
     pub(crate) fn replace_session_id(
         session_meta: &mut Value,
         new_id: &str,
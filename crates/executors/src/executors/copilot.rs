@@ -23,7 +23,7 @@ use workspace_utils::{msg_store::MsgStore, path::get_vibe_kanban_temp_dir};
 
 use crate::{
     actions::SpawnContext,
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_env, apply_overrides},
     executors::{
         AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
     },
@@ -137,6 +137,8 @@ impl StandardCodingAgentExecutor for Copilot {
         command.env_remove("npm_config_verify_deps_before_run");
         command.env_remove("npm_config_globalconfig");
 
+        apply_env(&mut command, &self.cmd);
+
         let mut child = command.group_spawn()?;
 
         // Write prompt to stdin
@@ -183,6 +185,8 @@ impl StandardCodingAgentExecutor for Copilot {
         command.env_remove("npm_config_verify_deps_before_run");
         command.env_remove("npm_config_globalconfig");
 
+        apply_env(&mut command, &self.cmd);
+
         let mut child = command.group_spawn()?;
 
         // Write comprehensive prompt to stdin
@@ -16,7 +16,7 @@ use workspace_utils::{
 };
 
 use crate::{
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_env, apply_overrides},
     executors::{
         AppendPrompt, AvailabilityInfo, ExecutorError, SpawnContext, SpawnedChild,
         StandardCodingAgentExecutor,
@@ -102,6 +102,8 @@ impl StandardCodingAgentExecutor for CursorAgent {
                 context.execution_process_id.to_string(),
             );
 
+        apply_env(&mut command, &self.cmd);
+
         let mut child = command.group_spawn()?;
 
         if let Some(mut stdin) = child.inner().stdin.take() {
@@ -139,6 +141,8 @@ impl StandardCodingAgentExecutor for CursorAgent {
             .current_dir(current_dir)
             .args(&args);
 
+        apply_env(&mut command, &self.cmd);
+
         let mut child = command.group_spawn()?;
 
         if let Some(mut stdin) = child.inner().stdin.take() {
@@ -0,0 +1,682 @@
+//! Generic executor for CLI agents that are described entirely by profile config.
+//!
+//! Lets teams onboard in-house or niche agents by declaring how to launch them, how the
+//! prompt is delivered, how to resume a session and how to read their output, instead of
+//! writing a dedicated `CodingAgent` variant.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use futures::StreamExt;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{io::AsyncWriteExt, process::Command};
+use ts_rs::TS;
+use uuid::Uuid;
+use workspace_utils::{
+    msg_store::MsgStore, path::get_vibe_kanban_temp_dir, shell::resolve_executable_path_blocking,
+};
+
+use crate::{
+    actions::SpawnContext,
+    command::{CmdOverrides, CommandBuilder, apply_env, apply_overrides},
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
+    logs::{
+        ActionType, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, ToolStatus,
+        plain_text_processor::PlainTextLogProcessor,
+        stderr_processor::normalize_stderr_logs,
+        utils::{ConversationPatch, EntryIndexProvider},
+    },
+};
+
+const PROMPT_PLACEHOLDER: &str = "{prompt}";
+const PROMPT_FILE_PLACEHOLDER: &str = "{prompt_file}";
+const SESSION_ID_PLACEHOLDER: &str = "{session_id}";
+
+/// How the prompt is handed to the agent process
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PromptDelivery {
+    /// Write the prompt to stdin, then close it
+    #[default]
+    Stdin,
+    /// Pass the prompt as a command-line argument (`{prompt}` in `prompt_args`)
+    Argv,
+    /// Write the prompt to a temp file and pass its path (`{prompt_file}` in `prompt_args`)
+    File,
+}
+
+/// Shape of the agent's stdout
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum CustomLogFormat {
+    /// Free-form text, rendered as assistant messages
+    #[default]
+    PlainText,
+    /// One JSON object per line, mapped to entries via `jsonl_mapping`
+    Jsonl,
+}
+
+/// Maps fields of a JSONL output line onto a normalized entry.
+///
+/// Every field is a JSON pointer (e.g. `/message/text`) into the parsed line.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct JsonlFieldMapping {
+    /// Pointer to the entry's text
+    #[serde(default = "default_content_pointer")]
+    pub content: String,
+    /// Pointer to a field that discriminates the kind of line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// `kind` values rendered as thinking
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thinking_kinds: Vec<String>,
+    /// `kind` values rendered as tool calls
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_kinds: Vec<String>,
+    /// `kind` values rendered as errors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub error_kinds: Vec<String>,
+    /// `kind` values rendered as system messages
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub system_kinds: Vec<String>,
+    /// `kind` values that are dropped from the conversation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored_kinds: Vec<String>,
+    /// Pointer to the tool name for tool lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// Pointer to the tool arguments for tool lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_arguments: Option<String>,
+    /// Pointer to the session id, checked on every line until one is found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+fn default_content_pointer() -> String {
+    "/content".to_string()
+}
+
+impl Default for JsonlFieldMapping {
+    fn default() -> Self {
+        Self {
+            content: default_content_pointer(),
+            kind: None,
+            thinking_kinds: Vec::new(),
+            tool_kinds: Vec::new(),
+            error_kinds: Vec::new(),
+            system_kinds: Vec::new(),
+            ignored_kinds: Vec::new(),
+            tool_name: None,
+            tool_arguments: None,
+            session_id: None,
+        }
+    }
+}
+
+impl JsonlFieldMapping {
+    fn string_at(value: &Value, pointer: &str) -> Option<String> {
+        match value.pointer(pointer)? {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    /// Extract the session id from a parsed line, if the mapping declares where it lives
+    pub fn extract_session_id(&self, value: &Value) -> Option<String> {
+        self.session_id
+            .as_deref()
+            .and_then(|pointer| Self::string_at(value, pointer))
+            .filter(|id| !id.is_empty())
+    }
+
+    /// Map a parsed line onto a normalized entry. Returns `None` for ignored or empty lines.
+    pub fn to_entry(&self, value: &Value) -> Option<NormalizedEntry> {
+        let kind = self
+            .kind
+            .as_deref()
+            .and_then(|pointer| Self::string_at(value, pointer));
+        let is_kind = |kinds: &[String]| kind.as_ref().is_some_and(|k| kinds.contains(k));
+
+        if is_kind(&self.ignored_kinds) {
+            return None;
+        }
+
+        let content = Self::string_at(value, &self.content).unwrap_or_default();
+
+        let entry_type = if is_kind(&self.tool_kinds) {
+            let tool_name = self
+                .tool_name
+                .as_deref()
+                .and_then(|pointer| Self::string_at(value, pointer))
+                .unwrap_or_else(|| "tool".to_string());
+            let arguments = self
+                .tool_arguments
+                .as_deref()
+                .and_then(|pointer| value.pointer(pointer))
+                .cloned();
+            NormalizedEntryType::ToolUse {
+                tool_name: tool_name.clone(),
+                action_type: ActionType::Tool {
+                    tool_name,
+                    arguments,
+                    result: None,
+                },
+                status: ToolStatus::Success,
+            }
+        } else if content.is_empty() {
+            return None;
+        } else if is_kind(&self.thinking_kinds) {
+            NormalizedEntryType::Thinking
+        } else if is_kind(&self.error_kinds) {
+            NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::classify(&content),
+            }
+        } else if is_kind(&self.system_kinds) {
+            NormalizedEntryType::SystemMessage
+        } else {
+            NormalizedEntryType::AssistantMessage
+        };
+
+        Some(NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content,
+            metadata: None,
+        })
+    }
+}
+
+/// Executor for an arbitrary CLI agent, configured entirely through its profile
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct CustomAgent {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_context: Option<bool>,
+    #[serde(default)]
+    #[schemars(
+        title = "Command",
+        description = "Command line that launches the agent, e.g. `my-agent run --yes`"
+    )]
+    pub command: String,
+    #[serde(default)]
+    #[schemars(
+        title = "Prompt Delivery",
+        description = "How the prompt is handed to the agent: stdin, argv or a temp file"
+    )]
+    pub prompt_delivery: PromptDelivery,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Prompt Arguments",
+        description = "Arguments carrying the prompt. `{prompt}` and `{prompt_file}` are substituted. Defaults to `[\"{prompt}\"]` for argv and `[\"{prompt_file}\"]` for file delivery"
+    )]
+    pub prompt_args: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Session ID Regex",
+        description = "Regex matched against stdout and stderr lines. The `session_id` named group, or else the first group, is used as the session id"
+    )]
+    pub session_id_regex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Follow-up Arguments",
+        description = "Arguments that resume a session, with `{session_id}` substituted, e.g. `[\"--resume\", \"{session_id}\"]`. Follow-ups are unsupported when unset"
+    )]
+    pub follow_up_args: Option<Vec<String>>,
+    #[serde(default)]
+    #[schemars(title = "Log Format", description = "Format of the agent's stdout")]
+    pub log_format: CustomLogFormat,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "JSONL Mapping",
+        description = "JSON pointers mapping JSONL output lines onto conversation entries"
+    )]
+    pub jsonl_mapping: Option<JsonlFieldMapping>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "MCP Config Path",
+        description = "Path to the agent's MCP config file. `~` expands to the home directory"
+    )]
+    pub mcp_config_path: Option<String>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+}
+
+/// Replace every placeholder in `template` with its value
+fn expand_template(template: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |acc, (placeholder, value)| {
+            acc.replace(placeholder, value)
+        })
+}
+
+/// Extract a session id from a line using the configured regex
+fn capture_session_id(regex: &Regex, line: &str) -> Option<String> {
+    let caps = regex.captures(line)?;
+    caps.name("session_id")
+        .or_else(|| caps.get(1))
+        .or_else(|| caps.get(0))
+        .map(|m| m.as_str().trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+impl CustomAgent {
+    fn build_command_builder(&self) -> CommandBuilder {
+        apply_overrides(CommandBuilder::new(self.command.clone()), &self.cmd)
+    }
+
+    /// Arguments carrying the prompt, after placeholder substitution
+    fn prompt_arguments(&self, prompt: &str, prompt_file: Option<&Path>) -> Vec<String> {
+        let defaults = match self.prompt_delivery {
+            PromptDelivery::Stdin => vec![],
+            PromptDelivery::Argv => vec![PROMPT_PLACEHOLDER.to_string()],
+            PromptDelivery::File => vec![PROMPT_FILE_PLACEHOLDER.to_string()],
+        };
+        let prompt_file = prompt_file
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.prompt_args
+            .as_ref()
+            .unwrap_or(&defaults)
+            .iter()
+            .map(|arg| {
+                expand_template(
+                    arg,
+                    &[
+                        (PROMPT_PLACEHOLDER, prompt),
+                        (PROMPT_FILE_PLACEHOLDER, &prompt_file),
+                    ],
+                )
+            })
+            .collect()
+    }
+
+    /// Arguments that resume `session_id`, or `None` if follow-ups are not configured
+    fn follow_up_arguments(&self, session_id: &str) -> Option<Vec<String>> {
+        self.follow_up_args.as_ref().map(|args| {
+            args.iter()
+                .map(|arg| expand_template(arg, &[(SESSION_ID_PLACEHOLDER, session_id)]))
+                .collect()
+        })
+    }
+
+    async fn write_prompt_file(prompt: &str) -> Result<PathBuf, ExecutorError> {
+        let dir = get_vibe_kanban_temp_dir().join("custom_agent_prompts");
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(ExecutorError::Io)?;
+        let path = dir.join(format!("{}.md", Uuid::new_v4()));
+        tokio::fs::write(&path, prompt)
+            .await
+            .map_err(ExecutorError::Io)?;
+        Ok(path)
+    }
+
+    async fn spawn_internal(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        leading_args: Vec<String>,
+        context: Option<SpawnContext>,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+
+        let prompt_file = match self.prompt_delivery {
+            PromptDelivery::File => Some(Self::write_prompt_file(&combined_prompt).await?),
+            PromptDelivery::Stdin | PromptDelivery::Argv => None,
+        };
+
+        let result = self
+            .spawn_with_prompt(
+                current_dir,
+                &combined_prompt,
+                prompt_file.as_deref(),
+                leading_args,
+                context,
+            )
+            .await;
+
+        match result {
+            Ok(mut spawned) => {
+                // The agent may read the file at any point while it runs
+                spawned.temp_files.extend(prompt_file);
+                Ok(spawned)
+            }
+            Err(e) => {
+                if let Some(path) = prompt_file {
+                    let _ = tokio::fs::remove_file(path).await;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn spawn_with_prompt(
+        &self,
+        current_dir: &Path,
+        combined_prompt: &str,
+        prompt_file: Option<&Path>,
+        leading_args: Vec<String>,
+        context: Option<SpawnContext>,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let mut args = leading_args;
+        args.extend(self.prompt_arguments(combined_prompt, prompt_file));
+
+        let command_parts = self.build_command_builder().build_follow_up(&args)?;
+        let (program_path, args) = command_parts.into_resolved().await?;
+
+        let mut command = Command::new(program_path);
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .args(&args);
+
+        // Set VK context environment variables for MCP tools
+        if let Some(context) = context {
+            command
+                .env("VK_ATTEMPT_ID", context.task_attempt_id.to_string())
+                .env("VK_TASK_ID", context.task_id.to_string())
+                .env(
+                    "VK_EXECUTION_PROCESS_ID",
+                    context.execution_process_id.to_string(),
+                );
+        }
+
+        apply_env(&mut command, &self.cmd);
+
+        let mut child = command.group_spawn()?;
+
+        // Close stdin either way so agents waiting for EOF don't hang
+        if let Some(mut stdin) = child.inner().stdin.take() {
+            if self.prompt_delivery == PromptDelivery::Stdin {
+                stdin.write_all(combined_prompt.as_bytes()).await?;
+            }
+            stdin.shutdown().await?;
+        }
+
+        Ok(child.into())
+    }
+
+    fn compile_session_regex(&self) -> Option<Regex> {
+        let pattern = self.session_id_regex.as_deref()?;
+        match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                tracing::warn!(
+                    "Invalid session_id_regex '{}' for custom agent: {}",
+                    pattern,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    fn create_plain_text_normalizer(index_provider: EntryIndexProvider) -> PlainTextLogProcessor {
+        PlainTextLogProcessor::builder()
+            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content,
+                metadata: None,
+            }))
+            .transform_lines(Box::new(|lines| {
+                lines.iter_mut().for_each(|line| {
+                    *line = strip_ansi_escapes::strip_str(&line);
+                })
+            }))
+            .index_provider(index_provider)
+            .build()
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for CustomAgent {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        context: SpawnContext,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_internal(current_dir, prompt, vec![], Some(context))
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        // Note: VK environment variables (VK_ATTEMPT_ID, VK_TASK_ID, VK_EXECUTION_PROCESS_ID)
+        // are inherited from the parent process that spawned this follow-up
+        let follow_up_args = self.follow_up_arguments(session_id).ok_or_else(|| {
+            ExecutorError::FollowUpNotSupported(
+                "custom agent profile does not declare follow_up_args".to_string(),
+            )
+        })?;
+        self.spawn_internal(current_dir, prompt, follow_up_args, None)
+            .await
+    }
+
+    /// Normalizes stdout as plain text or mapped JSONL, stderr as error messages, and
+    /// extracts the session id from either stream.
+    fn normalize_logs(
+        &self,
+        msg_store: Arc<MsgStore>,
+        _worktree_path: &Path,
+        entry_index_provider: EntryIndexProvider,
+    ) -> tokio::task::JoinHandle<()> {
+        let stderr_handle = normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+        let session_regex = self.compile_session_regex();
+
+        // Session ids printed on stderr (stdout is checked inline below)
+        let stderr_session_handle = session_regex.clone().map(|regex| {
+            let msg_store = msg_store.clone();
+            tokio::spawn(async move {
+                let mut stderr_lines = msg_store.stderr_lines_stream();
+                while let Some(Ok(line)) = stderr_lines.next().await {
+                    let line = strip_ansi_escapes::strip_str(&line);
+                    if let Some(session_id) = capture_session_id(&regex, &line) {
+                        msg_store.push_session_id(session_id);
+                        break;
+                    }
+                }
+            })
+        });
+
+        let log_format = self.log_format;
+        let mapping = self.jsonl_mapping.clone().unwrap_or_default();
+        let stdout_handle = tokio::spawn(async move {
+            let mut stdout_lines = msg_store.stdout_lines_stream();
+            let mut processor = Self::create_plain_text_normalizer(entry_index_provider.clone());
+            let mut session_found = false;
+
+            while let Some(Ok(line)) = stdout_lines.next().await {
+                if !session_found
+                    && let Some(regex) = session_regex.as_ref()
+                    && let Some(session_id) =
+                        capture_session_id(regex, &strip_ansi_escapes::strip_str(&line))
+                {
+                    msg_store.push_session_id(session_id);
+                    session_found = true;
+                }
+
+                if log_format == CustomLogFormat::Jsonl
+                    && let Ok(value) = serde_json::from_str::<Value>(line.trim())
+                {
+                    if !session_found && let Some(session_id) = mapping.extract_session_id(&value) {
+                        msg_store.push_session_id(session_id);
+                        session_found = true;
+                    }
+                    if let Some(entry) = mapping.to_entry(&value) {
+                        msg_store.push_patch(ConversationPatch::add_normalized_entry(
+                            entry_index_provider.next(),
+                            entry,
+                        ));
+                    }
+                    continue;
+                }
+
+                // Plain text output, or a non-JSON line in a JSONL stream
+                for patch in processor.process(line + "\n") {
+                    msg_store.push_patch(patch);
+                }
+            }
+        });
+
+        tokio::spawn(async move {
+            let _ = stderr_handle.await;
+            let _ = stdout_handle.await;
+            if let Some(handle) = stderr_session_handle {
+                handle.abort();
+            }
+        })
+    }
+
+    fn default_mcp_config_path(&self) -> Option<PathBuf> {
+        let raw = self.mcp_config_path.as_deref()?;
+        match raw.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+            None => Some(PathBuf::from(raw)),
+        }
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let program = self
+            .build_command_builder()
+            .build_initial()
+            .ok()
+            .map(|parts| parts.program().to_string());
+        match program {
+            Some(program) if resolve_executable_path_blocking(&program).is_some() => {
+                AvailabilityInfo::InstallationFound
+            }
+            _ => AvailabilityInfo::NotFound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(json: serde_json::Value) -> CustomAgent {
+        serde_json::from_value(json).expect("valid custom agent config")
+    }
+
+    #[test]
+    fn deserializes_minimal_profile_with_defaults() {
+        let agent = agent(serde_json::json!({ "command": "my-agent run" }));
+        assert_eq!(agent.prompt_delivery, PromptDelivery::Stdin);
+        assert_eq!(agent.log_format, CustomLogFormat::PlainText);
+        assert!(agent.follow_up_args.is_none());
+    }
+
+    #[test]
+    fn argv_delivery_defaults_to_bare_prompt_argument() {
+        let agent = agent(serde_json::json!({
+            "command": "my-agent",
+            "prompt_delivery": "argv"
+        }));
+        assert_eq!(agent.prompt_arguments("do it", None), vec!["do it"]);
+    }
+
+    #[test]
+    fn prompt_args_templates_are_expanded() {
+        let agent = agent(serde_json::json!({
+            "command": "my-agent",
+            "prompt_delivery": "file",
+            "prompt_args": ["--instructions", "{prompt_file}"]
+        }));
+        let args = agent.prompt_arguments("ignored", Some(Path::new("/tmp/p.md")));
+        assert_eq!(args, vec!["--instructions", "/tmp/p.md"]);
+    }
+
+    #[test]
+    fn follow_up_args_substitute_session_id() {
+        let agent = agent(serde_json::json!({
+            "command": "my-agent",
+            "follow_up_args": ["--resume={session_id}"]
+        }));
+        assert_eq!(
+            agent.follow_up_arguments("abc-123"),
+            Some(vec!["--resume=abc-123".to_string()])
+        );
+    }
+
+    #[test]
+    fn session_regex_prefers_named_group() {
+        let regex = Regex::new(r"run (?P<prefix>\w+) session=(?P<session_id>[\w-]+)").unwrap();
+        assert_eq!(
+            capture_session_id(&regex, "run x session=s-42"),
+            Some("s-42".to_string())
+        );
+        let regex = Regex::new(r"session: (\S+)").unwrap();
+        assert_eq!(
+            capture_session_id(&regex, "session: 9f1c"),
+            Some("9f1c".to_string())
+        );
+    }
+
+    #[test]
+    fn jsonl_mapping_classifies_lines() {
+        let mapping = JsonlFieldMapping {
+            content: "/text".to_string(),
+            kind: Some("/type".to_string()),
+            thinking_kinds: vec!["reasoning".to_string()],
+            tool_kinds: vec!["tool".to_string()],
+            ignored_kinds: vec!["heartbeat".to_string()],
+            tool_name: Some("/name".to_string()),
+            session_id: Some("/session".to_string()),
+            ..Default::default()
+        };
+
+        let entry = mapping
+            .to_entry(&serde_json::json!({ "type": "reasoning", "text": "hmm" }))
+            .unwrap();
+        assert!(matches!(entry.entry_type, NormalizedEntryType::Thinking));
+
+        let entry = mapping
+            .to_entry(&serde_json::json!({ "type": "tool", "name": "grep" }))
+            .unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::ToolUse { ref tool_name, .. } if tool_name == "grep"
+        ));
+
+        let entry = mapping
+            .to_entry(&serde_json::json!({ "type": "message", "text": "done" }))
+            .unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+        assert_eq!(entry.content, "done");
+
+        assert!(
+            mapping
+                .to_entry(&serde_json::json!({ "type": "heartbeat" }))
+                .is_none()
+        );
+        assert_eq!(
+            mapping.extract_session_id(&serde_json::json!({ "session": "s1" })),
+            Some("s1".to_string())
+        );
+    }
+}
@@ -10,7 +10,7 @@ use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
 use crate::{
-    command::CommandParts,
+    command::{CmdOverrides, CommandParts, apply_env},
     executors::{
         AppendPrompt, ExecutorError, SpawnContext, SpawnedChild, StandardCodingAgentExecutor,
     },
@@ -83,7 +83,7 @@ pub struct Droid {
     pub reasoning_effort: Option<ReasoningEffortLevel>,
 
     #[serde(flatten)]
-    pub cmd: crate::command::CmdOverrides,
+    pub cmd: CmdOverrides,
 }
 
 impl Droid {
@@ -114,6 +114,7 @@ async fn spawn(
     prompt: &String,
     current_dir: &Path,
     context: SpawnContext,
+    overrides: &CmdOverrides,
 ) -> Result<SpawnedChild, ExecutorError> {
     let (program_path, args) = command_parts.into_resolved().await?;
 
@@ -135,6 +136,8 @@ async fn spawn(
             context.execution_process_id.to_string(),
         );
 
+    apply_env(&mut command, overrides);
+
     let mut child = command.group_spawn()?;
 
     if let Some(mut stdin) = child.inner().stdin.take() {
@@ -156,7 +159,14 @@ impl StandardCodingAgentExecutor for Droid {
         let droid_command = self.build_command_builder().build_initial()?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        spawn(droid_command, &combined_prompt, current_dir, context).await
+        spawn(
+            droid_command,
+            &combined_prompt,
+            current_dir,
+            context,
+            &self.cmd,
+        )
+        .await
     }
 
     async fn spawn_follow_up(
@@ -190,6 +200,8 @@ impl StandardCodingAgentExecutor for Droid {
             .args(args);
 
         // Note: Environment variables are inherited from parent process
+        apply_env(&mut command, &self.cmd);
+
         let mut child = command.group_spawn()?;
 
         if let Some(mut stdin) = child.inner().stdin.take() {
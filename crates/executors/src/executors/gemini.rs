@@ -11,7 +11,7 @@ use workspace_utils::msg_store::MsgStore;
 pub use super::acp::AcpAgentHarness;
 use crate::{
     actions::SpawnContext,
-    command::{CmdOverrides, CommandBuilder, CommandParts, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, CommandParts, apply_env, apply_overrides},
     executors::{
         AppendPrompt, AvailabilityInfo, ExecutorError, ExecutorExitResult, SpawnedChild,
         StandardCodingAgentExecutor,
@@ -90,6 +90,8 @@ impl Gemini {
                 context.execution_process_id.to_string(),
             );
 
+        apply_env(&mut command, &self.cmd);
+
         let mut child = command.group_spawn()?;
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<ExecutorExitResult>();
@@ -107,6 +109,7 @@ impl Gemini {
             child,
             exit_signal: Some(exit_rx),
             protocol_peer: None,
+            temp_files: Vec::new(),
         })
     }
 }
@@ -137,7 +140,13 @@ impl StandardCodingAgentExecutor for Gemini {
         // Follow-up sessions inherit the parent's environment variables,
         // so VK_* env vars will already be set from the initial spawn
         harness
-            .spawn_follow_up_with_command(current_dir, combined_prompt, session_id, gemini_command)
+            .spawn_follow_up_with_command(
+                current_dir,
+                combined_prompt,
+                session_id,
+                gemini_command,
+                &self.cmd,
+            )
             .await
     }
 
@@ -1,4 +1,8 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use tokio::task::JoinHandle;
 
@@ -19,13 +23,14 @@ use crate::logs::utils::EntryIndexProvider;
 use crate::{
     actions::{ExecutorAction, SpawnContext, coding_agent_review::CodingAgentReviewRequest},
     approvals::ExecutorApprovalService,
-    command::CommandBuildError,
+    command::{CmdOverrides, CommandBuildError},
     executors::{
         amp::Amp,
         claude::{ClaudeCode, protocol::ProtocolPeer as ClaudeProtocolPeer},
         codex::{Codex, client::AppServerClient},
         copilot::Copilot,
         cursor::CursorAgent,
+        custom::CustomAgent,
         droid::Droid,
         gemini::Gemini,
         opencode::Opencode,
@@ -41,11 +46,13 @@ pub mod claude;
 pub mod codex;
 pub mod copilot;
 pub mod cursor;
+pub mod custom;
 pub mod droid;
 pub mod gemini;
 pub mod opencode;
 pub mod qa_mock;
 pub mod qwen;
+pub mod session_import;
 pub mod session_index;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
@@ -114,6 +121,7 @@ pub enum CodingAgent {
     Copilot,
     Droid,
     QaMock,
+    CustomAgent,
 }
 
 impl CodingAgent {
@@ -179,7 +187,9 @@ impl CodingAgent {
                 BaseAgentCapability::SetupHelper,
             ],
             Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
-            Self::Opencode(_) | Self::Copilot(_) | Self::QaMock(_) => vec![],
+            Self::Opencode(_) | Self::Copilot(_) | Self::QaMock(_) | Self::CustomAgent(_) => {
+                vec![]
+            }
         }
     }
 
@@ -197,6 +207,31 @@ impl CodingAgent {
             Self::Copilot(c) => c.no_context.unwrap_or(false),
             Self::Droid(c) => c.no_context.unwrap_or(false),
             Self::QaMock(_) => false,
+            Self::CustomAgent(c) => c.no_context.unwrap_or(false),
+        }
+    }
+
+    /// The profile's command overrides, if this executor supports them
+    pub fn cmd_overrides_mut(&mut self) -> Option<&mut CmdOverrides> {
+        match self {
+            Self::ClaudeCode(c) => Some(&mut c.cmd),
+            Self::Amp(c) => Some(&mut c.cmd),
+            Self::Gemini(c) => Some(&mut c.cmd),
+            Self::Codex(c) => Some(&mut c.cmd),
+            Self::Opencode(c) => Some(&mut c.cmd),
+            Self::CursorAgent(c) => Some(&mut c.cmd),
+            Self::QwenCode(c) => Some(&mut c.cmd),
+            Self::Copilot(c) => Some(&mut c.cmd),
+            Self::Droid(c) => Some(&mut c.cmd),
+            Self::QaMock(_) => None,
+            Self::CustomAgent(c) => Some(&mut c.cmd),
+        }
+    }
+
+    /// Resolve `${VAR}` references in the profile's `env` map before spawning
+    pub fn resolve_env(&mut self, variables: &HashMap<String, String>) {
+        if let Some(cmd) = self.cmd_overrides_mut() {
+            cmd.resolve_env(variables);
         }
     }
 
@@ -213,6 +248,7 @@ impl CodingAgent {
             Self::Copilot(c) => c.model.as_deref(),
             Self::Droid(c) => c.model.as_deref(),
             Self::QaMock(_) => None,
+            Self::CustomAgent(_) => None,
         }
     }
 }
@@ -415,6 +451,8 @@ pub struct SpawnedChild {
     pub exit_signal: Option<ExecutorExitSignal>,
     /// Protocol peer for live message injection and agent-aware control.
     pub protocol_peer: Option<Arc<ProtocolPeer>>,
+    /// Files written only for this process, removed once it exits.
+    pub temp_files: Vec<PathBuf>,
 }
 
 impl From<AsyncGroupChild> for SpawnedChild {
@@ -423,6 +461,7 @@ impl From<AsyncGroupChild> for SpawnedChild {
             child,
             exit_signal: None,
             protocol_peer: None,
+            temp_files: Vec::new(),
         }
     }
 }
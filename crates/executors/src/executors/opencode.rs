@@ -17,7 +17,7 @@ use workspace_utils::msg_store::MsgStore;
 
 use crate::{
     actions::SpawnContext,
-    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    command::{CmdOverrides, CommandBuilder, apply_env, apply_overrides},
     executors::{
         AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
     },
@@ -193,6 +193,8 @@ impl StandardCodingAgentExecutor for Opencode {
                 context.execution_process_id.to_string(),
             );
 
+        apply_env(&mut command, &self.cmd);
+
         let mut child = command.group_spawn().map_err(ExecutorError::SpawnError)?;
 
         // Write prompt to stdin
@@ -237,6 +239,8 @@ impl StandardCodingAgentExecutor for Opencode {
         command.env_remove("npm_config_verify_deps_before_run");
         command.env_remove("npm_config_globalconfig");
 
+        apply_env(&mut command, &self.cmd);
+
         let mut child = command.group_spawn().map_err(ExecutorError::SpawnError)?;
 
         // Write prompt to stdin
@@ -87,6 +87,7 @@ impl StandardCodingAgentExecutor for QaMock {
             task_attempt_id: uuid::Uuid::new_v4(),
             task_id: uuid::Uuid::new_v4(),
             execution_process_id: uuid::Uuid::new_v4(),
+            variables: Default::default(),
         };
         self.spawn(current_dir, prompt, context).await
     }
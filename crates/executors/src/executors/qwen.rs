@@ -51,7 +51,13 @@ impl StandardCodingAgentExecutor for QwenCode {
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
         let harness = AcpAgentHarness::with_session_namespace("qwen_sessions");
         harness
-            .spawn_with_command(current_dir, combined_prompt, qwen_command, context)
+            .spawn_with_command(
+                current_dir,
+                combined_prompt,
+                qwen_command,
+                context,
+                &self.cmd,
+            )
             .await
     }
 
@@ -65,7 +71,13 @@ impl StandardCodingAgentExecutor for QwenCode {
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
         let harness = AcpAgentHarness::with_session_namespace("qwen_sessions");
         harness
-            .spawn_follow_up_with_command(current_dir, combined_prompt, session_id, qwen_command)
+            .spawn_follow_up_with_command(
+                current_dir,
+                combined_prompt,
+                session_id,
+                qwen_command,
+                &self.cmd,
+            )
             .await
     }
 
@@ -0,0 +1,810 @@
+//! Import of agent sessions that were started outside the app.
+//!
+//! A session is read from the agent's own on-disk history, split into one turn per user prompt
+//! and normalized into the JSON patches a live run would have produced. Installing the session
+//! puts its history file where the agent looks for it from the attempt's worktree, so
+//! follow-ups resume it through `spawn_follow_up`.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use json_patch::Patch;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use thiserror::Error;
+use ts_rs::TS;
+use workspace_utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+use crate::{
+    executors::{
+        BaseCodingAgent,
+        claude::{ClaudeLogProcessor, HistoryStrategy},
+        codex::session::{SessionError, SessionHandler},
+        session_index::{get_claude_project_dir_with_home, repair_sessions_index},
+    },
+    logs::{
+        ActionType, CommandExitStatus, CommandRunResult, NormalizedEntry, NormalizedEntryError,
+        NormalizedEntryType, ToolResult, ToolStatus,
+        utils::{ConversationPatch, EntryIndexProvider},
+    },
+};
+
+/// User texts Claude Code records for slash commands and interruptions rather than prompts
+const CLAUDE_NON_PROMPT_PREFIXES: &[&str] =
+    &["<command-", "<local-command-", "[Request interrupted"];
+
+/// Content item types the Claude log processor understands
+const CLAUDE_CONTENT_TYPES: &[&str] = &["text", "thinking", "tool_use", "tool_result"];
+
+/// Codex tools whose calls run a shell command
+const CODEX_SHELL_TOOLS: &[&str] = &[
+    "shell",
+    "shell_command",
+    "container.exec",
+    "exec_command",
+    "local_shell",
+];
+
+/// Where to read the session from
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum SessionImportSource {
+    /// A session id, looked up in the agent's session history
+    SessionId { session_id: String },
+    /// The absolute path of a session JSONL file
+    File { path: String },
+}
+
+#[derive(Debug, Error)]
+pub enum SessionImportError {
+    #[error("Importing sessions is not supported for {0}")]
+    Unsupported(BaseCodingAgent),
+    #[error("Session not found: {0}")]
+    NotFound(String),
+    #[error("Session format error: {0}")]
+    Format(String),
+    #[error("Session I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<SessionError> for SessionImportError {
+    fn from(err: SessionError) -> Self {
+        match err {
+            SessionError::NotFound(msg) => Self::NotFound(msg),
+            SessionError::Format(msg) => Self::Format(msg),
+            SessionError::Io(msg) => Self::Io(std::io::Error::other(msg)),
+        }
+    }
+}
+
+/// One user prompt and the agent's work in response to it
+#[derive(Debug, Clone)]
+pub struct ImportedTurn {
+    pub prompt: String,
+    /// Normalized conversation of the turn, with entries indexed from 0
+    pub patches: Vec<Patch>,
+}
+
+/// A session read from the agent's history
+#[derive(Debug, Clone)]
+pub struct ImportedSession {
+    pub agent: BaseCodingAgent,
+    pub session_id: String,
+    /// The session's history file
+    pub path: PathBuf,
+    /// Directory the session ran in, when its history records it
+    pub cwd: Option<PathBuf>,
+    pub turns: Vec<ImportedTurn>,
+}
+
+impl ImportedSession {
+    /// Read and normalize a session. Only Claude Code and Codex sessions can be imported.
+    pub async fn load(
+        agent: BaseCodingAgent,
+        source: &SessionImportSource,
+    ) -> Result<Self, SessionImportError> {
+        let path = match (agent, source) {
+            (BaseCodingAgent::ClaudeCode, SessionImportSource::SessionId { session_id }) => {
+                validate_session_id(session_id)?;
+                find_claude_session(session_id)?
+            }
+            (BaseCodingAgent::Codex, SessionImportSource::SessionId { session_id }) => {
+                validate_session_id(session_id)?;
+                SessionHandler::find_rollout_file_path(session_id)?
+            }
+            (
+                BaseCodingAgent::ClaudeCode | BaseCodingAgent::Codex,
+                SessionImportSource::File { path },
+            ) => {
+                let path = PathBuf::from(path);
+                if !path.is_absolute() {
+                    return Err(SessionImportError::Format(
+                        "Session file path must be absolute".to_string(),
+                    ));
+                }
+                path
+            }
+            (agent, _) => return Err(SessionImportError::Unsupported(agent)),
+        };
+
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => {
+                    SessionImportError::NotFound(path.display().to_string())
+                }
+                _ => e.into(),
+            })?;
+
+        let (session_id, cwd, turns) = if agent == BaseCodingAgent::ClaudeCode {
+            let session = parse_claude_session(&content);
+            let cwd = session.cwd.map(PathBuf::from);
+            let log_cwd = cwd.clone().unwrap_or_default();
+            let mut turns = Vec::with_capacity(session.turns.len());
+            for (prompt, lines) in session.turns {
+                turns.push(ImportedTurn {
+                    patches: normalize_claude_turn(&lines, &log_cwd).await,
+                    prompt,
+                });
+            }
+            let session_id = session
+                .session_id
+                .or_else(|| path.file_stem().map(|s| s.to_string_lossy().to_string()));
+            (session_id, cwd, turns)
+        } else {
+            let session = parse_codex_rollout(&content);
+            let session_id = match session.session_id {
+                Some(id) => Some(id),
+                None => Some(SessionHandler::extract_session_id_from_rollout_path(
+                    path.clone(),
+                )?),
+            };
+            (session_id, session.cwd.map(PathBuf::from), session.turns)
+        };
+
+        let session_id = session_id.ok_or_else(|| {
+            SessionImportError::Format(format!("No session id in {}", path.display()))
+        })?;
+        validate_session_id(&session_id)?;
+        if turns.is_empty() {
+            return Err(SessionImportError::Format(format!(
+                "No prompts found in {}",
+                path.display()
+            )));
+        }
+
+        Ok(Self {
+            agent,
+            session_id,
+            path,
+            cwd,
+            turns,
+        })
+    }
+
+    /// Put the session's history file where the agent finds it when resuming from
+    /// `worktree_path`.
+    pub async fn install(&self, worktree_path: &Path) -> Result<(), SessionImportError> {
+        match self.agent {
+            BaseCodingAgent::ClaudeCode => {
+                let home = dirs::home_dir()
+                    .ok_or_else(|| std::io::Error::other("Could not determine home directory"))?;
+                let project_dir = get_claude_project_dir_with_home(worktree_path, home);
+                tokio::fs::create_dir_all(&project_dir).await?;
+                let destination = project_dir.join(format!("{}.jsonl", self.session_id));
+                if destination != self.path {
+                    tokio::fs::copy(&self.path, &destination).await?;
+                }
+                repair_sessions_index(worktree_path).await?;
+            }
+            BaseCodingAgent::Codex => {
+                SessionHandler::install_rollout_file(&self.path, &self.session_id)?;
+            }
+            agent => return Err(SessionImportError::Unsupported(agent)),
+        }
+        Ok(())
+    }
+}
+
+/// Session ids end up in file names, so only accept what the agents generate
+fn validate_session_id(session_id: &str) -> Result<(), SessionImportError> {
+    if session_id.is_empty()
+        || !session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(SessionImportError::Format(format!(
+            "Invalid session id: {session_id}"
+        )));
+    }
+    Ok(())
+}
+
+/// Find a Claude Code session file in any project directory
+fn find_claude_session(session_id: &str) -> Result<PathBuf, SessionImportError> {
+    let projects_dir = dirs::home_dir()
+        .ok_or_else(|| std::io::Error::other("Could not determine home directory"))?
+        .join(".claude")
+        .join("projects");
+    let file_name = format!("{session_id}.jsonl");
+    if projects_dir.is_dir() {
+        for entry in std::fs::read_dir(&projects_dir)? {
+            let candidate = entry?.path().join(&file_name);
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
+        }
+    }
+    Err(SessionImportError::NotFound(format!(
+        "Could not find Claude Code session {session_id} in {}",
+        projects_dir.display()
+    )))
+}
+
+/// A Claude Code session file grouped into turns of stream-json lines
+#[derive(Debug, Default)]
+struct ClaudeSession {
+    session_id: Option<String>,
+    cwd: Option<String>,
+    turns: Vec<(String, Vec<String>)>,
+}
+
+/// Split a Claude Code session file at the user's prompts, converting the messages in between
+/// to the stream-json lines `ClaudeLogProcessor` reads from a live run. Sidechain (subagent)
+/// and meta messages are dropped, as Claude Code does when resuming.
+fn parse_claude_session(content: &str) -> ClaudeSession {
+    let mut session = ClaudeSession::default();
+    for line in content.lines() {
+        let Ok(Value::Object(record)) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let flag = |key: &str| record.get(key).and_then(Value::as_bool).unwrap_or(false);
+        let typ = record
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if !matches!(typ, "user" | "assistant") || flag("isSidechain") || flag("isMeta") {
+            continue;
+        }
+        let Some(message) = record.get("message") else {
+            continue;
+        };
+        let session_id = record.get("sessionId").and_then(Value::as_str);
+        if session.session_id.is_none() {
+            session.session_id = session_id.map(str::to_string);
+        }
+        if session.cwd.is_none() {
+            session.cwd = record
+                .get("cwd")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+        }
+
+        let compact_summary = flag("isCompactSummary");
+        if typ == "user"
+            && !compact_summary
+            && let Some(prompt) = claude_prompt(message)
+        {
+            session.turns.push((prompt, Vec::new()));
+            continue;
+        }
+        let Some((_, lines)) = session.turns.last_mut() else {
+            continue;
+        };
+
+        let mut message = message.clone();
+        if let Some(Value::Array(items)) = message.get_mut("content") {
+            items.retain(|item| {
+                item.get("type")
+                    .and_then(Value::as_str)
+                    .is_some_and(|t| CLAUDE_CONTENT_TYPES.contains(&t))
+            });
+        }
+        let mut stream_line = json!({
+            "type": typ,
+            "message": message,
+            "session_id": session_id,
+        });
+        if compact_summary {
+            stream_line["isSynthetic"] = Value::Bool(true);
+        }
+        lines.push(stream_line.to_string());
+    }
+    session
+}
+
+/// The text the user typed, if this user message is a prompt rather than tool results
+fn claude_prompt(message: &Value) -> Option<String> {
+    let text = match message.get("content")? {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => {
+            let item_type = |item: &Value| item.get("type").and_then(Value::as_str);
+            if items
+                .iter()
+                .any(|item| item_type(item) == Some("tool_result"))
+            {
+                return None;
+            }
+            items
+                .iter()
+                .filter(|item| item_type(item) == Some("text"))
+                .filter_map(|item| item.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        _ => return None,
+    };
+    let text = text.trim();
+    (!text.is_empty()
+        && !CLAUDE_NON_PROMPT_PREFIXES
+            .iter()
+            .any(|prefix| text.starts_with(prefix)))
+    .then(|| text.to_string())
+}
+
+/// Run a turn's stream-json lines through the Claude log processor
+async fn normalize_claude_turn(lines: &[String], cwd: &Path) -> Vec<Patch> {
+    let msg_store = Arc::new(MsgStore::new());
+    for line in lines {
+        msg_store.push_stdout(format!("{line}\n"));
+    }
+    msg_store.push_finished();
+
+    let processor = ClaudeLogProcessor::process_logs(
+        msg_store.clone(),
+        cwd,
+        EntryIndexProvider::start_from(&msg_store),
+        HistoryStrategy::Default,
+    );
+    if let Err(e) = processor.await {
+        tracing::warn!("Claude log processor failed during session import: {e}");
+    }
+
+    msg_store
+        .get_history()
+        .into_iter()
+        .filter_map(|msg| match msg {
+            LogMsg::JsonPatch(patch) => Some(patch),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A Codex rollout file normalized into turns
+#[derive(Debug, Default)]
+struct CodexRollout {
+    session_id: Option<String>,
+    cwd: Option<String>,
+    turns: Vec<ImportedTurn>,
+    next_index: usize,
+    /// Tool calls waiting for their output, by call id
+    calls: HashMap<String, (usize, NormalizedEntry)>,
+    token_usage_index: Option<usize>,
+}
+
+/// Normalize a Codex rollout file, starting a turn at each user message. Rollouts record both
+/// events and model response items; messages and reasoning come from the events and tool calls
+/// from the response items, so nothing is shown twice.
+fn parse_codex_rollout(content: &str) -> CodexRollout {
+    let mut rollout = CodexRollout::default();
+    for line in content.lines() {
+        if let Ok(line) = serde_json::from_str::<Value>(line) {
+            rollout.push_line(&line);
+        }
+    }
+    rollout
+}
+
+impl CodexRollout {
+    fn push_line(&mut self, line: &Value) {
+        let str_field =
+            |value: &Value, key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+        let timestamp = str_field(line, "timestamp");
+        let Some(payload) = line.get("payload") else {
+            return;
+        };
+        let line_type = line.get("type").and_then(Value::as_str).unwrap_or_default();
+        let payload_type = payload
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        match (line_type, payload_type) {
+            ("session_meta", _) => {
+                if self.session_id.is_none() {
+                    self.session_id = str_field(payload, "id");
+                    self.cwd = str_field(payload, "cwd");
+                }
+            }
+            ("event_msg", "user_message") => {
+                // Older rollouts also record injected instructions and context as user messages
+                if payload
+                    .get("kind")
+                    .and_then(Value::as_str)
+                    .is_some_and(|kind| kind != "plain")
+                {
+                    return;
+                }
+                if let Some(message) = str_field(payload, "message")
+                    && !message.trim().is_empty()
+                {
+                    self.turns.push(ImportedTurn {
+                        prompt: message.trim().to_string(),
+                        patches: Vec::new(),
+                    });
+                    self.next_index = 0;
+                    self.calls.clear();
+                    self.token_usage_index = None;
+                }
+            }
+            ("event_msg", "agent_message") => {
+                if let Some(message) = str_field(payload, "message") {
+                    self.add_entry(NormalizedEntryType::AssistantMessage, message, timestamp);
+                }
+            }
+            ("event_msg", "agent_reasoning") => {
+                if let Some(text) = str_field(payload, "text") {
+                    self.add_entry(NormalizedEntryType::Thinking, text, timestamp);
+                }
+            }
+            ("event_msg", "error") => {
+                if let Some(message) = str_field(payload, "message") {
+                    let error_type = NormalizedEntryError::classify(&message);
+                    self.add_entry(
+                        NormalizedEntryType::ErrorMessage { error_type },
+                        message,
+                        timestamp,
+                    );
+                }
+            }
+            ("event_msg", "token_count") => self.token_usage(payload, timestamp),
+            ("response_item", "function_call") => {
+                let name = str_field(payload, "name").unwrap_or_default();
+                let arguments = match payload.get("arguments") {
+                    Some(Value::String(raw)) => {
+                        serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.clone()))
+                    }
+                    Some(value) => value.clone(),
+                    None => Value::Null,
+                };
+                self.start_tool(str_field(payload, "call_id"), &name, arguments, timestamp);
+            }
+            ("response_item", "custom_tool_call") => {
+                let name = str_field(payload, "name").unwrap_or_default();
+                let input = payload.get("input").cloned().unwrap_or(Value::Null);
+                self.start_tool(str_field(payload, "call_id"), &name, input, timestamp);
+            }
+            ("response_item", "local_shell_call") => {
+                let command = payload
+                    .get("action")
+                    .and_then(|action| action.get("command"))
+                    .cloned()
+                    .unwrap_or(Value::Null);
+                self.start_tool(
+                    str_field(payload, "call_id"),
+                    "local_shell",
+                    json!({ "command": command }),
+                    timestamp,
+                );
+            }
+            ("response_item", "function_call_output" | "custom_tool_call_output") => {
+                if let Some(call_id) = str_field(payload, "call_id") {
+                    self.finish_tool(&call_id, payload.get("output"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Add an entry to the current turn, returning its index. Entries before the first user
+    /// message are dropped.
+    fn push_entry(&mut self, entry: NormalizedEntry) -> Option<usize> {
+        let turn = self.turns.last_mut()?;
+        let index = self.next_index;
+        self.next_index += 1;
+        turn.patches
+            .push(ConversationPatch::add_normalized_entry(index, entry));
+        Some(index)
+    }
+
+    fn add_entry(
+        &mut self,
+        entry_type: NormalizedEntryType,
+        content: String,
+        timestamp: Option<String>,
+    ) {
+        self.push_entry(NormalizedEntry {
+            timestamp,
+            entry_type,
+            content,
+            metadata: None,
+        });
+    }
+
+    fn start_tool(
+        &mut self,
+        call_id: Option<String>,
+        name: &str,
+        arguments: Value,
+        timestamp: Option<String>,
+    ) {
+        let command = CODEX_SHELL_TOOLS
+            .contains(&name)
+            .then(|| codex_command(&arguments))
+            .flatten();
+        let (action_type, content) = match command {
+            Some(command) => (
+                ActionType::CommandRun {
+                    command: command.clone(),
+                    result: None,
+                },
+                command,
+            ),
+            None => (
+                ActionType::Tool {
+                    tool_name: name.to_string(),
+                    arguments: Some(arguments),
+                    result: None,
+                },
+                name.to_string(),
+            ),
+        };
+        let entry = NormalizedEntry {
+            timestamp,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: name.to_string(),
+                action_type,
+                status: ToolStatus::Created,
+            },
+            content,
+            metadata: None,
+        };
+        if let Some(index) = self.push_entry(entry.clone())
+            && let Some(call_id) = call_id
+        {
+            self.calls.insert(call_id, (index, entry));
+        }
+    }
+
+    fn finish_tool(&mut self, call_id: &str, output: Option<&Value>) {
+        let Some((index, mut entry)) = self.calls.remove(call_id) else {
+            return;
+        };
+        let (output, exit_code) = codex_tool_output(output);
+        if let NormalizedEntryType::ToolUse {
+            action_type,
+            status,
+            ..
+        } = &mut entry.entry_type
+        {
+            match action_type {
+                ActionType::CommandRun { result, .. } => {
+                    *status = if exit_code.unwrap_or(0) == 0 {
+                        ToolStatus::Success
+                    } else {
+                        ToolStatus::Failed
+                    };
+                    *result = Some(CommandRunResult {
+                        exit_status: exit_code.map(|code| CommandExitStatus::ExitCode { code }),
+                        output: Some(output),
+                    });
+                }
+                ActionType::Tool { result, .. } => {
+                    *status = ToolStatus::Success;
+                    *result = Some(ToolResult::markdown(output));
+                }
+                _ => {}
+            }
+        }
+        if let Some(turn) = self.turns.last_mut() {
+            turn.patches.push(ConversationPatch::replace(index, entry));
+        }
+    }
+
+    /// Keep a single token usage entry per turn, updated with each count
+    fn token_usage(&mut self, payload: &Value, timestamp: Option<String>) {
+        let Some(info) = payload.get("info").filter(|info| !info.is_null()) else {
+            return;
+        };
+        let tokens = |usage: &str, key: &str| {
+            info.get(usage)
+                .and_then(|usage| usage.get(key))
+                .and_then(Value::as_i64)
+                .unwrap_or(0)
+        };
+        let entry = NormalizedEntry {
+            timestamp,
+            entry_type: NormalizedEntryType::TokenUsage {
+                input_tokens: tokens("total_token_usage", "input_tokens"),
+                cached_input_tokens: tokens("total_token_usage", "cached_input_tokens"),
+                output_tokens: tokens("total_token_usage", "output_tokens"),
+                reasoning_tokens: tokens("total_token_usage", "reasoning_output_tokens"),
+                last_total_tokens: tokens("last_token_usage", "total_tokens"),
+                context_window: info.get("model_context_window").and_then(Value::as_i64),
+            },
+            content: String::new(),
+            metadata: None,
+        };
+        match self.token_usage_index {
+            Some(index) => {
+                if let Some(turn) = self.turns.last_mut() {
+                    turn.patches.push(ConversationPatch::replace(index, entry));
+                }
+            }
+            None => self.token_usage_index = self.push_entry(entry),
+        }
+    }
+}
+
+/// The command line of a shell tool call, unwrapping `bash -lc <script>`
+fn codex_command(arguments: &Value) -> Option<String> {
+    match arguments.get("command").or_else(|| arguments.get("cmd"))? {
+        Value::String(command) => Some(command.clone()),
+        Value::Array(parts) => {
+            let parts: Vec<&str> = parts.iter().filter_map(Value::as_str).collect();
+            match parts.as_slice() {
+                [shell, flag, script] if shell.ends_with("sh") && flag.starts_with('-') => {
+                    Some(script.to_string())
+                }
+                _ => Some(parts.join(" ")),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The text and exit code of a tool call output. Shell outputs are recorded as a JSON string
+/// holding the output and its metadata.
+fn codex_tool_output(output: Option<&Value>) -> (String, Option<i32>) {
+    match output {
+        Some(Value::String(raw)) => match serde_json::from_str::<Value>(raw) {
+            Ok(parsed) if parsed.get("output").is_some_and(Value::is_string) => {
+                let exit_code = parsed
+                    .get("metadata")
+                    .and_then(|metadata| metadata.get("exit_code"))
+                    .and_then(Value::as_i64)
+                    .map(|code| code as i32);
+                (
+                    parsed["output"].as_str().unwrap_or_default().to_string(),
+                    exit_code,
+                )
+            }
+            _ => (raw.clone(), None),
+        },
+        Some(Value::Object(object)) => match object.get("content") {
+            Some(Value::String(content)) => (content.clone(), None),
+            _ => (Value::Object(object.clone()).to_string(), None),
+        },
+        Some(other) => (other.to_string(), None),
+        None => (String::new(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::utils::patch::extract_normalized_entry_from_patch;
+
+    /// The entries of a turn after applying its patches in order
+    fn entries(patches: &[Patch]) -> Vec<NormalizedEntry> {
+        let mut entries: Vec<NormalizedEntry> = Vec::new();
+        for patch in patches {
+            if let Some((index, entry)) = extract_normalized_entry_from_patch(patch) {
+                if index < entries.len() {
+                    entries[index] = entry;
+                } else {
+                    entries.push(entry);
+                }
+            }
+        }
+        entries
+    }
+
+    #[test]
+    fn test_claude_session_split_at_prompts() {
+        let content = [
+            r#"{"type":"summary","summary":"Earlier work","leafUuid":"x"}"#,
+            r#"{"type":"user","sessionId":"abc-123","cwd":"/repo","isMeta":true,"message":{"role":"user","content":"Caveat: local commands"}}"#,
+            r#"{"type":"user","sessionId":"abc-123","cwd":"/repo","message":{"role":"user","content":"Fix the build"}}"#,
+            r#"{"type":"assistant","sessionId":"abc-123","message":{"id":"m1","role":"assistant","content":[{"type":"text","text":"Looking"},{"type":"image","source":{}}]}}"#,
+            r#"{"type":"user","sessionId":"abc-123","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}"#,
+            r#"{"type":"assistant","sessionId":"abc-123","isSidechain":true,"message":{"role":"assistant","content":"subagent"}}"#,
+            r#"{"type":"user","sessionId":"abc-123","message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"user","sessionId":"abc-123","message":{"role":"user","content":[{"type":"text","text":"Now add tests"}]}}"#,
+        ]
+        .join("\n");
+
+        let session = parse_claude_session(&content);
+        assert_eq!(session.session_id.as_deref(), Some("abc-123"));
+        assert_eq!(session.cwd.as_deref(), Some("/repo"));
+        assert_eq!(session.turns.len(), 2);
+        assert_eq!(session.turns[0].0, "Fix the build");
+        assert_eq!(session.turns[1].0, "Now add tests");
+
+        // The assistant message, tool result and slash command stay with the first turn
+        let lines = &session.turns[0].1;
+        assert_eq!(lines.len(), 3);
+        let assistant: Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(assistant["session_id"], "abc-123");
+        assert_eq!(assistant["message"]["content"].as_array().unwrap().len(), 1);
+        assert!(session.turns[1].1.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_claude_turn_normalized() {
+        let lines = vec![
+            r#"{"type":"assistant","message":{"id":"m1","role":"assistant","content":[{"type":"text","text":"Build fixed"}]},"session_id":"abc-123"}"#.to_string(),
+        ];
+        let entries = entries(&normalize_claude_turn(&lines, Path::new("/repo")).await);
+        assert!(entries.iter().any(|entry| {
+            matches!(entry.entry_type, NormalizedEntryType::AssistantMessage)
+                && entry.content == "Build fixed"
+        }));
+    }
+
+    #[test]
+    fn test_codex_rollout_turns() {
+        let content = [
+            r#"{"timestamp":"2025-09-01T10:00:00Z","type":"session_meta","payload":{"id":"0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b","cwd":"/repo"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:01Z","type":"event_msg","payload":{"type":"user_message","message":"Run the tests","kind":"plain"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:02Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"bash\",\"-lc\",\"cargo test\"]}","call_id":"call_1"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:03Z","type":"response_item","payload":{"type":"function_call_output","call_id":"call_1","output":"{\"output\":\"1 failed\",\"metadata\":{\"exit_code\":101}}"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:04Z","type":"event_msg","payload":{"type":"agent_message","message":"One test fails"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:05Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":100,"output_tokens":20},"last_token_usage":{"total_tokens":120}}}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:06Z","type":"event_msg","payload":{"type":"user_message","message":"Fix it"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:07Z","type":"event_msg","payload":{"type":"agent_message","message":"Fixed"}}"#,
+        ]
+        .join("\n");
+
+        let rollout = parse_codex_rollout(&content);
+        assert_eq!(
+            rollout.session_id.as_deref(),
+            Some("0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b")
+        );
+        assert_eq!(rollout.cwd.as_deref(), Some("/repo"));
+        assert_eq!(rollout.turns.len(), 2);
+        assert_eq!(rollout.turns[0].prompt, "Run the tests");
+
+        let first = entries(&rollout.turns[0].patches);
+        assert_eq!(first.len(), 3);
+        match &first[0].entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { command, result },
+                status,
+                ..
+            } => {
+                assert_eq!(command, "cargo test");
+                assert!(matches!(status, ToolStatus::Failed));
+                let result = result.as_ref().unwrap();
+                assert_eq!(result.output.as_deref(), Some("1 failed"));
+                assert!(matches!(
+                    result.exit_status,
+                    Some(CommandExitStatus::ExitCode { code: 101 })
+                ));
+            }
+            other => panic!("expected a command run, got {other:?}"),
+        }
+        assert!(matches!(
+            first[2].entry_type,
+            NormalizedEntryType::TokenUsage {
+                input_tokens: 100,
+                ..
+            }
+        ));
+
+        let second = entries(&rollout.turns[1].patches);
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].content, "Fixed");
+    }
+
+    #[test]
+    fn test_invalid_session_id_rejected() {
+        assert!(validate_session_id("../../etc/passwd").is_err());
+        assert!(validate_session_id("").is_err());
+        assert!(validate_session_id("0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b").is_ok());
+    }
+}
@@ -109,7 +109,7 @@ fn get_claude_project_dir(worktree_path: &Path) -> PathBuf {
     get_claude_project_dir_with_home(worktree_path, home)
 }
 
-fn get_claude_project_dir_with_home(worktree_path: &Path, home: PathBuf) -> PathBuf {
+pub(crate) fn get_claude_project_dir_with_home(worktree_path: &Path, home: PathBuf) -> PathBuf {
     let escaped = worktree_path
         .to_string_lossy()
         .replace('/', "-")
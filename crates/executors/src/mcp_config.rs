@@ -299,7 +299,7 @@ impl CodingAgent {
             CodingAgent::Codex(_) => Codex,
             CodingAgent::Opencode(_) => Opencode,
             CodingAgent::Copilot(..) => Copilot,
-            CodingAgent::QaMock(_) => Passthrough,
+            CodingAgent::QaMock(_) | CodingAgent::CustomAgent(_) => Passthrough,
         };
 
         let canonical = PRECONFIGURED_MCP_SERVERS.clone();
@@ -24,6 +24,8 @@ use db::{
         project::Project,
         task::{Task, TaskStatus},
         task_attempt::TaskAttempt,
+        task_variable::TaskVariable,
+        usage_budget::BUDGET_EXCEEDED_COMPLETION_REASON,
     },
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
@@ -42,6 +44,7 @@ use executors::{
 use futures::{FutureExt, StreamExt, TryStreamExt, stream::select};
 use services::services::{
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
+    budget::{BudgetBreach, BudgetEnforcerHandle, fire_budget_webhook},
     config::Config,
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
@@ -50,7 +53,9 @@ use services::services::{
     log_batcher::{LogBatcher, LogBatcherHandle},
     log_migration,
     normalization_metrics::NormalizationMetrics,
+    secrets::SecretStore,
     share::SharePublisher,
+    webhook::{WebhookEventPayload, WebhookService},
     worktree_manager::{WorktreeCleanup, WorktreeManager},
 };
 use tokio::{sync::RwLock, task::JoinHandle};
@@ -94,7 +99,11 @@ pub struct LocalContainerService {
     image_service: ImageService,
     approvals: Approvals,
     publisher: Result<SharePublisher, RemoteClientNotConfigured>,
+    /// Local secrets available to `${VAR}` references in profile `env` maps.
+    secrets: Arc<SecretStore>,
     log_batcher: LogBatcherHandle,
+    /// Receives budget breaches reported while logs stream in
+    budget_enforcer: BudgetEnforcerHandle,
     message_queue: crate::message_queue::MessageQueueStore,
     /// Normalization task handles keyed by execution_process_id.
     /// Used to await normalization completion before signaling finished.
@@ -109,6 +118,7 @@ pub struct LocalContainerService {
 }
 
 impl LocalContainerService {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         db: DBService,
         msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
@@ -117,6 +127,7 @@ impl LocalContainerService {
         image_service: ImageService,
         approvals: Approvals,
         publisher: Result<SharePublisher, RemoteClientNotConfigured>,
+        secrets: Arc<SecretStore>,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let protocol_peers = Arc::new(RwLock::new(HashMap::new()));
@@ -125,6 +136,9 @@ impl LocalContainerService {
         // Initialize log batcher for batched database writes
         let log_batcher = LogBatcher::spawn(&db);
 
+        // Stops executions that reach a project or task usage budget
+        let (budget_enforcer, budget_breaches) = BudgetEnforcerHandle::new();
+
         // Initialize database-backed message queue store
         let message_queue = crate::message_queue::MessageQueueStore::new(db.pool.clone());
 
@@ -150,7 +164,9 @@ impl LocalContainerService {
             image_service,
             approvals,
             publisher,
+            secrets,
             log_batcher,
+            budget_enforcer,
             message_queue,
             normalization_handles,
             normalization_metrics,
@@ -160,6 +176,7 @@ impl LocalContainerService {
         };
 
         container.spawn_worktree_cleanup();
+        container.spawn_budget_enforcer(budget_breaches);
 
         container
     }
@@ -179,6 +196,9 @@ impl LocalContainerService {
         let image_service = ImageService::new(pool.clone()).expect("image service for test");
         let approvals = Approvals::new(msg_stores.clone());
         let publisher = Err(RemoteClientNotConfigured);
+        let secrets = Arc::new(SecretStore::new(
+            std::env::temp_dir().join(format!("vk-test-secrets-{}.json", Uuid::new_v4())),
+        ));
         Self::new(
             db,
             msg_stores,
@@ -187,6 +207,7 @@ impl LocalContainerService {
             image_service,
             approvals,
             publisher,
+            secrets,
         )
         .await
     }
@@ -200,6 +221,28 @@ impl LocalContainerService {
         self
     }
 
+    /// Values for `${VAR}` references in profile `env` maps: the task's variables
+    /// (including inherited and system ones) overlaid with local secrets.
+    async fn env_variables(&self, task_id: Uuid) -> HashMap<String, String> {
+        let mut variables: HashMap<String, String> =
+            match TaskVariable::get_variable_map_with_system(&self.db.pool, task_id).await {
+                Ok(map) => map
+                    .into_iter()
+                    .map(|(name, (value, _))| (name, value))
+                    .collect(),
+                Err(e) => {
+                    tracing::warn!(
+                        task_id = %task_id,
+                        error = ?e,
+                        "Failed to fetch task variables for profile env"
+                    );
+                    HashMap::new()
+                }
+            };
+        variables.extend(self.secrets.values().await);
+        variables
+    }
+
     /// Get the normalization metrics collector.
     pub fn normalization_metrics(&self) -> &NormalizationMetrics {
         &self.normalization_metrics
@@ -506,12 +549,249 @@ impl LocalContainerService {
         });
     }
 
+    /// Stop a running execution, recording why it was stopped. Manual stops go through
+    /// [`ContainerService::stop_execution`]; budget enforcement passes its own reason and message.
+    pub async fn stop_execution_with_reason(
+        &self,
+        execution_process: &ExecutionProcess,
+        status: ExecutionProcessStatus,
+        completion_reason: Option<&str>,
+        completion_message: Option<&str>,
+    ) -> Result<(), ContainerError> {
+        let child = self
+            .get_child_from_store(&execution_process.id)
+            .await
+            .ok_or_else(|| {
+                ContainerError::Other(anyhow!("Child process not found for execution"))
+            })?;
+        let exit_code = if status == ExecutionProcessStatus::Completed {
+            Some(0)
+        } else {
+            None
+        };
+
+        ExecutionProcess::update_completion(
+            &self.db.pool,
+            execution_process.id,
+            status,
+            exit_code,
+            completion_reason,
+            completion_message,
+        )
+        .await?;
+
+        // Ask the agent to interrupt first if it supports a protocol-native stop.
+        // We still fall back to killing the process group below.
+        if let Some(peer) = self.get_protocol_peer(&execution_process.id).await {
+            match peer.interrupt().await {
+                Ok(true) => {
+                    tracing::debug!(
+                        execution_process_id = %execution_process.id,
+                        "Sent protocol interrupt before process termination"
+                    );
+                    tokio::time::sleep(Duration::from_millis(250)).await;
+                }
+                Ok(false) => {
+                    tracing::trace!(
+                        execution_process_id = %execution_process.id,
+                        "Protocol peer does not support interrupt"
+                    );
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        execution_process_id = %execution_process.id,
+                        error = %err,
+                        "Protocol interrupt failed; falling back to process termination"
+                    );
+                }
+            }
+        }
+
+        // Kill the child process and remove from the store
+        const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+        {
+            let child_guard = match tokio::time::timeout(LOCK_TIMEOUT, child.write()).await {
+                Ok(guard) => guard,
+                Err(_) => {
+                    tracing::warn!(
+                        execution_process_id = %execution_process.id,
+                        timeout_secs = LOCK_TIMEOUT.as_secs(),
+                        "stop_execution: child lock acquisition timed out"
+                    );
+                    return Err(ContainerError::Other(anyhow!(
+                        "Failed to acquire child lock within {}s",
+                        LOCK_TIMEOUT.as_secs()
+                    )));
+                }
+            };
+            let mut child_guard = child_guard;
+            if let Err(e) = command::kill_process_group(&mut child_guard).await {
+                tracing::error!(
+                    "Failed to stop execution process {}: {}",
+                    execution_process.id,
+                    e
+                );
+                return Err(e);
+            }
+        }
+        self.remove_child_from_store(&execution_process.id).await;
+        self.remove_protocol_peer(&execution_process.id).await;
+        self.remove_entry_index_provider(&execution_process.id)
+            .await;
+
+        // Flush any remaining buffered logs before signaling finished
+        if let Some(log_batcher) = self.log_batcher() {
+            log_batcher.finish(execution_process.id).await;
+        }
+
+        // Cancel pending approvals before removing the MsgStore so the background
+        // timeout watchers see Denied (not TimedOut) and skip the MsgStore update.
+        self.approvals.cancel_for_process(execution_process.id);
+
+        // Signal the normalizer that no more log lines are coming, then await completion.
+        // push_finished() MUST come before awaiting the normalization handle: the normalizer's
+        // stdout_lines_stream() blocks indefinitely until push_finished() closes the channel,
+        // so waiting first causes a guaranteed deadlock that always fires the timeout.
+        if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
+            msg.push_finished();
+        }
+
+        let timeout_secs = normalization_timeout_secs();
+        if let Some(norm_handle) = self.take_normalization_handle(&execution_process.id).await {
+            let span = tracing::info_span!(
+                "normalization_await",
+                exec_id = %execution_process.id,
+                timeout_secs = timeout_secs
+            );
+            let _guard = span.enter();
+
+            let start = std::time::Instant::now();
+            let result = tokio::time::timeout(Duration::from_secs(timeout_secs), norm_handle).await;
+
+            match result {
+                Ok(_) => {
+                    let duration = start.elapsed();
+                    self.normalization_metrics.record_completion(duration);
+                    tracing::debug!(
+                        exec_id = %execution_process.id,
+                        duration_ms = duration.as_millis() as u64,
+                        "Normalization completed"
+                    );
+                }
+                Err(_) => {
+                    self.normalization_metrics.record_timeout();
+                    tracing::warn!(
+                        execution_process_id = %execution_process.id,
+                        timeout_secs = timeout_secs,
+                        "Normalization timed out. Raw logs preserved in execution_process_logs."
+                    );
+                }
+            }
+        }
+
+        // Run normalization to populate log_entries with JsonPatch entries for REST pagination
+        if let Err(e) =
+            log_migration::migrate_execution_logs(&self.db.pool, execution_process.id).await
+        {
+            tracing::warn!(
+                execution_process_id = %execution_process.id,
+                error = %e,
+                "Failed to normalize logs for stopped execution"
+            );
+        }
+
+        // Update task status to InReview when execution is stopped
+        if let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
+            && !matches!(
+                ctx.execution_process.run_reason,
+                ExecutionProcessRunReason::DevServer
+            )
+        {
+            match Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await {
+                Ok(_) => {
+                    if let Some(publisher) = self.share_publisher()
+                        && let Err(err) = publisher.update_shared_task_by_id(ctx.task.id).await
+                    {
+                        tracing::warn!(
+                            ?err,
+                            "Failed to propagate shared task update for {}",
+                            ctx.task.id
+                        );
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to update task status to InReview: {e}");
+                }
+            }
+        }
+
+        tracing::debug!(
+            "Execution process {} stopped successfully",
+            execution_process.id
+        );
+
+        // Record after-head commit OID (best-effort)
+        if let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await {
+            let worktree = self.task_attempt_to_current_dir(&ctx.task_attempt);
+            if let Ok(head) = self.git().get_head_info(&worktree) {
+                let _ = ExecutionProcess::update_after_head_commit(
+                    &self.db.pool,
+                    execution_process.id,
+                    &head.oid,
+                )
+                .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Spawn a background task that stops executions once they reach a usage budget.
+    fn spawn_budget_enforcer(
+        &self,
+        mut breaches: tokio::sync::mpsc::UnboundedReceiver<BudgetBreach>,
+    ) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            while let Some(breach) = breaches.recv().await {
+                let exec_id = breach.execution_process_id;
+                let process = match ExecutionProcess::find_by_id(&container.db.pool, exec_id).await
+                {
+                    Ok(Some(process)) if process.status == ExecutionProcessStatus::Running => {
+                        process
+                    }
+                    Ok(_) => continue,
+                    Err(e) => {
+                        tracing::error!(exec_id = %exec_id, error = %e, "Failed to load over-budget execution");
+                        continue;
+                    }
+                };
+                let message = breach.status.describe();
+                tracing::warn!(exec_id = %exec_id, "Stopping execution: {}", message);
+                if let Err(e) = container
+                    .stop_execution_with_reason(
+                        &process,
+                        ExecutionProcessStatus::Killed,
+                        Some(BUDGET_EXCEEDED_COMPLETION_REASON),
+                        Some(&message),
+                    )
+                    .await
+                {
+                    tracing::error!(exec_id = %exec_id, error = %e, "Failed to stop over-budget execution");
+                    continue;
+                }
+                fire_budget_webhook(&container.db.pool, &breach);
+            }
+        });
+    }
+
     /// Spawn a background task that polls the child process for completion and
-    /// cleans up the execution entry when it exits.
+    /// cleans up the execution entry and the process's temp files when it exits.
     pub fn spawn_exit_monitor(
         &self,
         exec_id: &Uuid,
         exit_signal: Option<ExecutorExitSignal>,
+        temp_files: Vec<PathBuf>,
     ) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
@@ -576,6 +856,19 @@ impl LocalContainerService {
                 }
             }
 
+            for path in temp_files {
+                if let Err(e) = tokio::fs::remove_file(&path).await
+                    && e.kind() != std::io::ErrorKind::NotFound
+                {
+                    tracing::warn!(
+                        exec_id = %exec_id,
+                        path = %path.display(),
+                        error = %e,
+                        "Failed to remove execution temp file"
+                    );
+                }
+            }
+
             let (exit_code, status) = match status_result {
                 Ok(exit_status) => {
                     let code = exit_status.code().unwrap_or(-1) as i64;
@@ -605,7 +898,8 @@ impl LocalContainerService {
                 None => (None, None),
             };
 
-            if !ExecutionProcess::was_stopped(&db.pool, exec_id).await
+            let was_stopped = ExecutionProcess::was_stopped(&db.pool, exec_id).await;
+            if !was_stopped
                 && let Err(e) = ExecutionProcess::update_completion(
                     &db.pool,
                     exec_id,
@@ -641,10 +935,16 @@ impl LocalContainerService {
 
             // Fire webhooks for executor_finish (fire-and-forget, non-blocking)
             {
-                use services::services::webhook::WebhookService;
                 let pool_clone = db.pool.clone();
-                let status_str = format!("{status:?}").to_lowercase();
-                let reason_owned = reason_str.map(|s| s.to_string());
+                let mut status_str = format!("{status:?}").to_lowercase();
+                let mut reason_owned = reason_str.map(|s| s.to_string());
+                // A stopped process keeps the status and reason recorded by whoever stopped it
+                if was_stopped
+                    && let Ok(Some(stored)) = ExecutionProcess::find_by_id(&db.pool, exec_id).await
+                {
+                    status_str = format!("{:?}", stored.status).to_lowercase();
+                    reason_owned = stored.completion_reason;
+                }
                 let exit_code_clone = exit_code;
                 let exec_id_clone = exec_id;
                 tokio::spawn(async move {
@@ -657,8 +957,7 @@ impl LocalContainerService {
                     )
                     .await
                     {
-                        let project_id = ctx.project_id;
-                        WebhookService::fire(&pool_clone, project_id, ctx).await;
+                        WebhookService::fire(&pool_clone, ctx).await;
                     } else {
                         tracing::debug!(
                             exec_id = %exec_id_clone,
@@ -724,6 +1023,12 @@ impl LocalContainerService {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
+                if let Some(event) =
+                    WebhookEventPayload::setup_script_failed(&ctx.execution_process, exit_code)
+                {
+                    WebhookService::spawn_execution_event(&db.pool, exec_id, event);
+                }
+
                 let success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
@@ -732,16 +1037,26 @@ impl LocalContainerService {
                 let cleanup_done = matches!(
                     ctx.execution_process.run_reason,
                     ExecutionProcessRunReason::CleanupScript
+                        | ExecutionProcessRunReason::PipelineStep
                 ) && !matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Running
                 );
 
-                if success || cleanup_done {
+                // Pipeline steps may branch on failure, so a failed step can still have a
+                // next step to start
+                let pipeline_continues = container.next_pipeline_state(&ctx).is_some();
+
+                // Set when the project's verification script takes over from a finished
+                // agent turn; the task is finalized once verification completes instead
+                let mut verification_started = false;
+
+                if success || cleanup_done || pipeline_continues {
                     tracing::info!(
                         exec_id = %exec_id,
                         success = success,
                         cleanup_done = cleanup_done,
+                        pipeline_continues = pipeline_continues,
                         "Exit monitor: Process eligible for commit/next-action (success={} OR cleanup_done={})",
                         success, cleanup_done
                     );
@@ -766,12 +1081,23 @@ impl LocalContainerService {
                     // Always proceed to next action (cleanup script) regardless of whether
                     // changes were committed. The agent may have done valuable work that
                     // doesn't involve file changes (e.g., browser testing, running tests).
-                    if let Err(e) = container.try_start_next_action(&ctx).await {
+                    // A configured verification script runs first and starts the next action
+                    // once it passes.
+                    verification_started = match container.try_start_verification(&ctx).await {
+                        Ok(started) => started,
+                        Err(e) => {
+                            tracing::error!("Failed to start verification script: {}", e);
+                            false
+                        }
+                    };
+                    if !verification_started
+                        && let Err(e) = container.try_start_next_action(&ctx).await
+                    {
                         tracing::error!("Failed to start next action after completion: {}", e);
                     }
                 }
 
-                if container.should_finalize(&ctx) {
+                if !verification_started && container.should_finalize(&ctx) {
                     container
                         .finalize_task(&container.config, publisher.as_ref().ok(), &ctx)
                         .await;
@@ -1161,11 +1487,10 @@ impl LocalContainerService {
         };
 
         // Prepare cleanup action
-        let cleanup_action = ctx
-            .task
-            .parent_project(&self.db.pool)
-            .await?
-            .and_then(|project| self.cleanup_action(project.cleanup_script));
+        let cleanup_action = match ctx.task.parent_project(&self.db.pool).await? {
+            Some(project) => self.cleanup_action(&project).await?,
+            None => None,
+        };
 
         // Handle images: associate, copy to worktree, canonicalize prompt
         let mut prompt = draft.prompt.clone();
@@ -1284,10 +1609,10 @@ impl LocalContainerService {
         };
 
         // Prepare cleanup action
-        let cleanup_action = task
-            .parent_project(&self.db.pool)
-            .await?
-            .and_then(|project| self.cleanup_action(project.cleanup_script));
+        let cleanup_action = match task.parent_project(&self.db.pool).await? {
+            Some(project) => self.cleanup_action(&project).await?,
+            None => None,
+        };
 
         // Handle images: copy to worktree and canonicalize prompt
         let worktree_path = std::path::PathBuf::from(&container_ref);
@@ -1571,6 +1896,10 @@ impl ContainerService for LocalContainerService {
         Some(&self.log_batcher)
     }
 
+    fn budget_enforcer(&self) -> Option<&BudgetEnforcerHandle> {
+        Some(&self.budget_enforcer)
+    }
+
     fn normalization_metrics(&self) -> &NormalizationMetrics {
         &self.normalization_metrics
     }
@@ -1803,6 +2132,7 @@ impl ContainerService for LocalContainerService {
             task_attempt_id: task_attempt.id,
             task_id: task_attempt.task_id,
             execution_process_id: execution_process.id,
+            variables: self.env_variables(task_attempt.task_id).await,
         };
 
         // Create the child and stream, add to execution tracker with timeout
@@ -1851,7 +2181,11 @@ impl ContainerService for LocalContainerService {
         }
 
         // Spawn unified exit monitor: watches OS exit and optional executor signal
-        let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
+        let _hn = self.spawn_exit_monitor(
+            &execution_process.id,
+            spawned.exit_signal,
+            spawned.temp_files,
+        );
 
         Ok(())
     }
@@ -1861,199 +2195,14 @@ impl ContainerService for LocalContainerService {
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
-        let child = self
-            .get_child_from_store(&execution_process.id)
-            .await
-            .ok_or_else(|| {
-                ContainerError::Other(anyhow!("Child process not found for execution"))
-            })?;
-        let exit_code = if status == ExecutionProcessStatus::Completed {
-            Some(0)
-        } else {
-            None
-        };
-
         // User manually stopped the execution
         let completion_reason = if status == ExecutionProcessStatus::Killed {
             Some("killed")
         } else {
             None
         };
-
-        ExecutionProcess::update_completion(
-            &self.db.pool,
-            execution_process.id,
-            status,
-            exit_code,
-            completion_reason,
-            None, // No completion message for manual stops
-        )
-        .await?;
-
-        // Ask the agent to interrupt first if it supports a protocol-native stop.
-        // We still fall back to killing the process group below.
-        if let Some(peer) = self.get_protocol_peer(&execution_process.id).await {
-            match peer.interrupt().await {
-                Ok(true) => {
-                    tracing::debug!(
-                        execution_process_id = %execution_process.id,
-                        "Sent protocol interrupt before process termination"
-                    );
-                    tokio::time::sleep(Duration::from_millis(250)).await;
-                }
-                Ok(false) => {
-                    tracing::trace!(
-                        execution_process_id = %execution_process.id,
-                        "Protocol peer does not support interrupt"
-                    );
-                }
-                Err(err) => {
-                    tracing::warn!(
-                        execution_process_id = %execution_process.id,
-                        error = %err,
-                        "Protocol interrupt failed; falling back to process termination"
-                    );
-                }
-            }
-        }
-
-        // Kill the child process and remove from the store
-        const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
-        {
-            let child_guard = match tokio::time::timeout(LOCK_TIMEOUT, child.write()).await {
-                Ok(guard) => guard,
-                Err(_) => {
-                    tracing::warn!(
-                        execution_process_id = %execution_process.id,
-                        timeout_secs = LOCK_TIMEOUT.as_secs(),
-                        "stop_execution: child lock acquisition timed out"
-                    );
-                    return Err(ContainerError::Other(anyhow!(
-                        "Failed to acquire child lock within {}s",
-                        LOCK_TIMEOUT.as_secs()
-                    )));
-                }
-            };
-            let mut child_guard = child_guard;
-            if let Err(e) = command::kill_process_group(&mut child_guard).await {
-                tracing::error!(
-                    "Failed to stop execution process {}: {}",
-                    execution_process.id,
-                    e
-                );
-                return Err(e);
-            }
-        }
-        self.remove_child_from_store(&execution_process.id).await;
-        self.remove_protocol_peer(&execution_process.id).await;
-        self.remove_entry_index_provider(&execution_process.id)
-            .await;
-
-        // Flush any remaining buffered logs before signaling finished
-        if let Some(log_batcher) = self.log_batcher() {
-            log_batcher.finish(execution_process.id).await;
-        }
-
-        // Cancel pending approvals before removing the MsgStore so the background
-        // timeout watchers see Denied (not TimedOut) and skip the MsgStore update.
-        self.approvals.cancel_for_process(execution_process.id);
-
-        // Signal the normalizer that no more log lines are coming, then await completion.
-        // push_finished() MUST come before awaiting the normalization handle: the normalizer's
-        // stdout_lines_stream() blocks indefinitely until push_finished() closes the channel,
-        // so waiting first causes a guaranteed deadlock that always fires the timeout.
-        if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
-            msg.push_finished();
-        }
-
-        let timeout_secs = normalization_timeout_secs();
-        if let Some(norm_handle) = self.take_normalization_handle(&execution_process.id).await {
-            let span = tracing::info_span!(
-                "normalization_await",
-                exec_id = %execution_process.id,
-                timeout_secs = timeout_secs
-            );
-            let _guard = span.enter();
-
-            let start = std::time::Instant::now();
-            let result = tokio::time::timeout(Duration::from_secs(timeout_secs), norm_handle).await;
-
-            match result {
-                Ok(_) => {
-                    let duration = start.elapsed();
-                    self.normalization_metrics.record_completion(duration);
-                    tracing::debug!(
-                        exec_id = %execution_process.id,
-                        duration_ms = duration.as_millis() as u64,
-                        "Normalization completed"
-                    );
-                }
-                Err(_) => {
-                    self.normalization_metrics.record_timeout();
-                    tracing::warn!(
-                        execution_process_id = %execution_process.id,
-                        timeout_secs = timeout_secs,
-                        "Normalization timed out. Raw logs preserved in execution_process_logs."
-                    );
-                }
-            }
-        }
-
-        // Run normalization to populate log_entries with JsonPatch entries for REST pagination
-        if let Err(e) =
-            log_migration::migrate_execution_logs(&self.db.pool, execution_process.id).await
-        {
-            tracing::warn!(
-                execution_process_id = %execution_process.id,
-                error = %e,
-                "Failed to normalize logs for stopped execution"
-            );
-        }
-
-        // Update task status to InReview when execution is stopped
-        if let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
-            && !matches!(
-                ctx.execution_process.run_reason,
-                ExecutionProcessRunReason::DevServer
-            )
-        {
-            match Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await {
-                Ok(_) => {
-                    if let Some(publisher) = self.share_publisher()
-                        && let Err(err) = publisher.update_shared_task_by_id(ctx.task.id).await
-                    {
-                        tracing::warn!(
-                            ?err,
-                            "Failed to propagate shared task update for {}",
-                            ctx.task.id
-                        );
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Failed to update task status to InReview: {e}");
-                }
-            }
-        }
-
-        tracing::debug!(
-            "Execution process {} stopped successfully",
-            execution_process.id
-        );
-
-        // Record after-head commit OID (best-effort)
-        if let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await {
-            let worktree = self.task_attempt_to_current_dir(&ctx.task_attempt);
-            if let Ok(head) = self.git().get_head_info(&worktree) {
-                let _ = ExecutionProcess::update_after_head_commit(
-                    &self.db.pool,
-                    execution_process.id,
-                    &head.oid,
-                )
-                .await;
-            }
-        }
-
-        Ok(())
+        self.stop_execution_with_reason(execution_process, status, completion_reason, None)
+            .await
     }
 
     async fn stream_diff(
@@ -2103,7 +2252,9 @@ impl ContainerService for LocalContainerService {
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
         if !matches!(
             ctx.execution_process.run_reason,
-            ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::CleanupScript,
+            ExecutionProcessRunReason::CodingAgent
+                | ExecutionProcessRunReason::CleanupScript
+                | ExecutionProcessRunReason::PipelineStep,
         ) {
             return Ok(false);
         }
@@ -2148,6 +2299,12 @@ impl ContainerService for LocalContainerService {
                     ctx.task_attempt.id
                 )
             }
+            ExecutionProcessRunReason::PipelineStep => {
+                format!(
+                    "Pipeline step changes for task attempt {}",
+                    ctx.task_attempt.id
+                )
+            }
             _ => Err(ContainerError::Other(anyhow::anyhow!(
                 "Invalid run reason for commit"
             )))?,
@@ -21,12 +21,14 @@ use services::services::{
     node_runner::{NodeRunnerConfig, NodeRunnerContext, spawn_node_runner},
     oauth_credentials::OAuthCredentials,
     remote_client::{RemoteClient, RemoteClientError},
+    secrets::SecretStore,
     share::{RemoteSyncHandle, ShareConfig, SharePublisher},
+    webhook::WebhookService,
 };
 use tokio::sync::{Mutex, RwLock};
 use utils::{
     api::oauth::LoginStatus,
-    assets::{backup_dir, config_path, credentials_path, database_path},
+    assets::{backup_dir, config_path, credentials_path, database_path, secrets_path},
     msg_store::MsgStore,
 };
 use uuid::Uuid;
@@ -49,6 +51,7 @@ pub struct LocalDeployment {
     file_search_cache: Arc<FileSearchCache>,
     approvals: Approvals,
     drafts: DraftsService,
+    secrets: Arc<SecretStore>,
     share_publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     share_sync_handle: Arc<Mutex<Option<RemoteSyncHandle>>>,
     share_config: Option<ShareConfig>,
@@ -176,8 +179,20 @@ impl Deployment for LocalDeployment {
             });
         }
 
+        // Pick up webhook deliveries a previous run was still sending or retrying
+        match WebhookService::resume_pending(&db.pool).await {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Resumed {} pending webhook deliveries", n),
+            Err(e) => tracing::error!("Failed to resume pending webhook deliveries: {}", e),
+        }
+
         let approvals = Approvals::new(msg_stores.clone());
 
+        let secrets = Arc::new(SecretStore::new(secrets_path()));
+        if let Err(e) = secrets.load().await {
+            tracing::warn!(?e, "failed to load local secrets");
+        }
+
         let share_config = ShareConfig::from_env();
 
         // oauth_credentials already loaded in parallel at startup
@@ -247,6 +262,7 @@ impl Deployment for LocalDeployment {
             image.clone(),
             approvals.clone(),
             share_publisher.clone(),
+            secrets.clone(),
         )
         .await;
 
@@ -293,6 +309,7 @@ impl Deployment for LocalDeployment {
                         db.clone(),
                         Some(container.clone()),
                         node_auth_client.clone(),
+                        approvals.clone(),
                     ),
                     validator,
                     proxy_client,
@@ -333,6 +350,7 @@ impl Deployment for LocalDeployment {
             file_search_cache,
             approvals,
             drafts,
+            secrets,
             share_publisher,
             share_sync_handle: share_sync_handle.clone(),
             share_config: share_config.clone(),
@@ -431,6 +449,10 @@ impl Deployment for LocalDeployment {
         &self.drafts
     }
 
+    fn secrets(&self) -> &Arc<SecretStore> {
+        &self.secrets
+    }
+
     fn share_publisher(&self) -> Result<SharePublisher, RemoteClientNotConfigured> {
         self.share_publisher.clone()
     }
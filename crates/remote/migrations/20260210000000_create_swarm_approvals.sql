@@ -0,0 +1,51 @@
+-- Approval requests forwarded by nodes so any organization member can answer them, from the
+-- Hive or from another node. The node that raised a request keeps the agent waiting; the Hive
+-- relays the answer back over the node's WebSocket.
+
+CREATE TABLE swarm_approvals (
+    id                          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id             UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    node_id                     UUID NOT NULL REFERENCES nodes(id) ON DELETE CASCADE,
+    -- The approval's id on the node
+    approval_id                 TEXT NOT NULL,
+    shared_task_id              UUID REFERENCES shared_tasks(id) ON DELETE SET NULL,
+    local_attempt_id            UUID,
+    local_execution_process_id  UUID NOT NULL,
+    task_title                  TEXT NOT NULL,
+    tool_name                   TEXT NOT NULL,
+    tool_input                  JSONB NOT NULL DEFAULT '{}'::jsonb,
+    -- Set for questions the agent asks the user
+    questions                   JSONB,
+    status                      TEXT NOT NULL DEFAULT 'pending'
+                                CHECK (status IN ('pending', 'approved', 'denied', 'timed_out')),
+    denial_reason               TEXT,
+    answers                     JSONB,
+    -- NULL when the request was answered on its node or by the escalation default
+    responded_by                UUID REFERENCES users(id) ON DELETE SET NULL,
+    responded_at                TIMESTAMPTZ,
+    -- When the answer reached the node; answers for offline nodes are sent on reconnect
+    delivered_at                TIMESTAMPTZ,
+    escalated_at                TIMESTAMPTZ,
+    created_at                  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    timeout_at                  TIMESTAMPTZ NOT NULL,
+    UNIQUE (node_id, approval_id)
+);
+
+CREATE INDEX idx_swarm_approvals_org_status ON swarm_approvals(organization_id, status, created_at);
+CREATE INDEX idx_swarm_approvals_undelivered ON swarm_approvals(node_id)
+    WHERE status <> 'pending' AND delivered_at IS NULL;
+
+CREATE TYPE approval_escalation_decision AS ENUM ('approve', 'deny');
+
+-- What happens to approvals nobody answers in time, per organization
+CREATE TABLE approval_escalation_settings (
+    organization_id     UUID PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    -- Minutes a request may wait before it is escalated
+    timeout_minutes     INTEGER NOT NULL CHECK (timeout_minutes > 0),
+    -- Member notified when a request escalates
+    escalate_to         UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- Decision applied when a request escalates, or when it is still unanswered one more
+    -- timeout after the escalation notice. NULL leaves the request to the node's own timeout.
+    default_decision    approval_escalation_decision,
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
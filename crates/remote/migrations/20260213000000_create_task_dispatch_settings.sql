@@ -0,0 +1,12 @@
+-- How the Hive schedules new tasks onto nodes, per organization
+CREATE TABLE task_dispatch_settings (
+    organization_id       UUID PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    -- Dispatch every task created on the Hive to an eligible node, not only ones created
+    -- with start_attempt. Tasks synced from a node stay with that node.
+    auto_dispatch         BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Executor a task runs with when the dispatch request does not name one
+    default_executor      TEXT NOT NULL DEFAULT 'CLAUDE_CODE',
+    -- Node labels every node must carry to receive tasks that do not name their own
+    required_node_labels  TEXT[] NOT NULL DEFAULT '{}',
+    updated_at            TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
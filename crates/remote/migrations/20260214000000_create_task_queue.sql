@@ -0,0 +1,31 @@
+-- Hive tasks marked ready, waiting for a node with room to run them. A task leaves the queue
+-- when it is dispatched, or when it can never be (deleted, or owned by the node that made it).
+CREATE TABLE task_queue (
+    task_id           UUID PRIMARY KEY REFERENCES shared_tasks(id) ON DELETE CASCADE,
+    organization_id   UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    swarm_project_id  UUID NOT NULL REFERENCES swarm_projects(id) ON DELETE CASCADE,
+    -- Higher goes first
+    priority          INTEGER NOT NULL DEFAULT 0,
+    -- Among tasks of equal priority, earlier deadlines go first
+    deadline          TIMESTAMPTZ,
+    -- What the task needs from its node, resolved against the dispatch settings when queued
+    requirements      JSONB NOT NULL,
+    enqueued_by       UUID REFERENCES users(id) ON DELETE SET NULL,
+    enqueued_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Why the last attempt to hand the task out failed
+    last_error        TEXT,
+    last_attempt_at   TIMESTAMPTZ
+);
+
+CREATE INDEX idx_task_queue_org_order
+    ON task_queue(organization_id, priority DESC, deadline ASC NULLS LAST, enqueued_at);
+
+-- Per-project queue limits, and each project's turn in the round-robin between projects
+CREATE TABLE task_queue_projects (
+    swarm_project_id      UUID PRIMARY KEY REFERENCES swarm_projects(id) ON DELETE CASCADE,
+    -- Most of the project's tasks that may run at once; NULL means no limit
+    max_concurrent_tasks  INTEGER CHECK (max_concurrent_tasks > 0),
+    -- When the queue last handed out one of the project's tasks
+    last_dispatched_at    TIMESTAMPTZ,
+    updated_at            TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
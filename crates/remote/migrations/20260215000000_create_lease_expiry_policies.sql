@@ -0,0 +1,42 @@
+-- What the Hive does with a task whose node let its lease expire, per swarm project. Projects
+-- without a policy keep the old behaviour: the lease is freed and the task waits for a claim.
+-- 'retry' queues the task for another node until max_retries is used up, then fails it;
+-- 'fail' fails it straight away.
+CREATE TYPE lease_expiry_action AS ENUM ('retry', 'fail');
+
+CREATE TABLE lease_expiry_policies (
+    swarm_project_id       UUID PRIMARY KEY REFERENCES swarm_projects(id) ON DELETE CASCADE,
+    on_expiry              lease_expiry_action NOT NULL,
+    max_retries            INTEGER NOT NULL DEFAULT 3 CHECK (max_retries >= 0),
+    -- Wait before the first retry; doubled for every retry after it
+    retry_backoff_seconds  INTEGER NOT NULL DEFAULT 30 CHECK (retry_backoff_seconds >= 0),
+    updated_at             TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TYPE hop_resolution AS ENUM ('retried', 'failed');
+
+-- Every node a task was dispatched to, and what became of hops whose lease expired
+CREATE TABLE task_assignment_hops (
+    id                UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    task_id           UUID NOT NULL REFERENCES shared_tasks(id) ON DELETE CASCADE,
+    assignment_id     UUID REFERENCES node_task_assignments(id) ON DELETE SET NULL,
+    node_id           UUID REFERENCES nodes(id) ON DELETE SET NULL,
+    -- What the task needed from its node; NULL for assignments made before hops were recorded
+    requirements      JSONB,
+    -- The task's priority and deadline in the queue, kept when it is retried; NULL for tasks
+    -- dispatched without queueing
+    priority          INTEGER,
+    deadline          TIMESTAMPTZ,
+    dispatched_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    lease_expired_at  TIMESTAMPTZ,
+    -- What the project's policy did about the expired lease
+    resolution        hop_resolution,
+    -- When the retry may be dispatched, for hops that were retried
+    retry_at          TIMESTAMPTZ
+);
+
+CREATE INDEX idx_task_assignment_hops_task ON task_assignment_hops(task_id, dispatched_at);
+CREATE INDEX idx_task_assignment_hops_assignment ON task_assignment_hops(assignment_id);
+
+-- Queued tasks are not handed out before this time, so retries can back off
+ALTER TABLE task_queue ADD COLUMN not_before TIMESTAMPTZ;
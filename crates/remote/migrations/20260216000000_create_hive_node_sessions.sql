@@ -0,0 +1,46 @@
+-- Which Hive replica holds each node's WebSocket, so replicas can reach nodes connected to
+-- another replica. The replica also holds a session-level advisory lock on lock_key from the
+-- backend lock_pid; a row whose lock is no longer held (its replica died) does not count.
+CREATE TABLE hive_node_sessions (
+    node_id          UUID PRIMARY KEY REFERENCES nodes(id) ON DELETE CASCADE,
+    organization_id  UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    replica_id       UUID NOT NULL,
+    lock_key         BIGINT NOT NULL,
+    lock_pid         INTEGER NOT NULL,
+    -- Live status and load from the node's heartbeats, for dispatch on other replicas
+    status           node_status NOT NULL DEFAULT 'online',
+    active_tasks     INTEGER NOT NULL DEFAULT 0,
+    connected_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at       TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_hive_node_sessions_organization ON hive_node_sessions(organization_id);
+
+-- Messages for nodes connected to another replica. The NOTIFY only carries the id, since
+-- messages can outgrow a NOTIFY payload; the owning replica deletes the row as it delivers it.
+CREATE TABLE hive_routed_messages (
+    id          BIGSERIAL PRIMARY KEY,
+    replica_id  UUID NOT NULL,
+    node_ids    UUID[] NOT NULL,
+    payload     JSONB NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_hive_routed_messages_replica ON hive_routed_messages(replica_id);
+CREATE INDEX idx_hive_routed_messages_created_at ON hive_routed_messages(created_at);
+
+-- Whether the backend lock_pid still holds the session-level advisory lock on lock_key
+CREATE FUNCTION hive_session_lock_held(lock_key BIGINT, lock_pid INTEGER) RETURNS BOOLEAN AS $$
+    SELECT EXISTS (
+        SELECT 1
+        FROM pg_locks l
+        WHERE l.locktype = 'advisory'
+          AND l.granted
+          AND l.pid = lock_pid
+          AND l.database = (SELECT oid FROM pg_database WHERE datname = current_database())
+          -- A bigint key shows its high half as classid and its low half as objid
+          AND l.objsubid = 1
+          AND l.classid::bigint = (lock_key >> 32) & 4294967295
+          AND l.objid::bigint = lock_key & 4294967295
+    )
+$$ LANGUAGE sql STABLE;
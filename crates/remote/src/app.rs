@@ -13,9 +13,12 @@ use crate::{
     config::RemoteServerConfig,
     db,
     mail::LoopsMailer,
-    nodes::{BackfillConfig, BackfillService, ConnectionManager},
+    nodes::{BackfillConfig, BackfillService, ConnectionManager, HiveCluster},
     routes,
-    services::{spawn_lease_sweep_service, spawn_stale_cleanup_service},
+    services::{
+        LogRelay, spawn_approval_escalation_service, spawn_hive_cluster_service,
+        spawn_lease_sweep_service, spawn_stale_cleanup_service, spawn_task_queue_service,
+    },
 };
 
 pub struct Server;
@@ -40,9 +43,6 @@ impl Server {
         // Spawn stale node local projects cleanup service
         spawn_stale_cleanup_service(pool.clone(), None);
 
-        // Spawn Hive lease-expiry sweep service (reclaim expired leases, bumping fencing token)
-        spawn_lease_sweep_service(pool.clone(), None);
-
         let broker = ActivityBroker::new(
             config.activity_broadcast_shards,
             config.activity_broadcast_capacity,
@@ -84,7 +84,19 @@ impl Server {
 
         let api_key = std::env::var("LOOPS_EMAIL_API_KEY")
             .context("LOOPS_EMAIL_API_KEY environment variable is required")?;
-        let mailer = Arc::new(LoopsMailer::new(api_key));
+        let mailer = Arc::new(
+            LoopsMailer::new(api_key)
+                .with_approval_escalation_template(
+                    std::env::var("LOOPS_APPROVAL_ESCALATION_TEMPLATE_ID").ok(),
+                )
+                .with_task_lease_failure_template(
+                    std::env::var("LOOPS_TASK_LEASE_FAILURE_TEMPLATE_ID").ok(),
+                ),
+        );
+
+        // Spawn Hive lease-expiry sweep service (reclaim expired leases, bumping fencing token,
+        // then retry or fail the task as its project's lease expiry policy says)
+        spawn_lease_sweep_service(pool.clone(), mailer.clone(), None);
 
         let server_public_base_url = config.server_public_base_url.clone().ok_or_else(|| {
             anyhow::anyhow!(
@@ -92,7 +104,33 @@ impl Server {
             )
         })?;
 
-        let node_connections = ConnectionManager::new();
+        // Node connections are shared with the other replicas on this database: each node's
+        // WebSocket is held by one replica, and messages for it are routed there over NOTIFY
+        let cluster = Arc::new(
+            HiveCluster::connect(pool.clone(), config.node_routing_channel.clone())
+                .await
+                .context("failed to join the Hive cluster")?,
+        );
+        tracing::info!(replica_id = %cluster.replica_id(), "joined Hive cluster");
+        let node_connections = ConnectionManager::new().with_cluster(Arc::clone(&cluster));
+        spawn_hive_cluster_service(Arc::clone(&cluster), node_connections.clone(), None);
+        tokio::spawn(db::RoutedMessageListener::new(cluster, node_connections.clone()).run());
+
+        // Spawn escalation of unanswered swarm approvals
+        spawn_approval_escalation_service(
+            pool.clone(),
+            node_connections.clone(),
+            mailer.clone(),
+            server_public_base_url.clone(),
+            None,
+        );
+
+        // Spawn the sweep that hands queued tasks to nodes with room for them
+        spawn_task_queue_service(pool.clone(), node_connections.clone(), None);
+
+        // Fans node logs out to relay viewers on this replica; other replicas hear about them
+        // over NOTIFY
+        let log_relay = LogRelay::new(config.log_relay_channel.clone(), config.log_relay_capacity);
 
         // Use the same JWT secret for connection tokens
         let connection_token = Arc::new(ConnectionTokenService::new(
@@ -137,6 +175,7 @@ impl Server {
             server_public_base_url,
             node_connections,
             connection_token,
+            log_relay.clone(),
             http_client,
             backfill,
         );
@@ -144,6 +183,7 @@ impl Server {
         let listener =
             db::ActivityListener::new(pool.clone(), broker, config.activity_channel.clone());
         tokio::spawn(listener.run());
+        tokio::spawn(db::LogRelayListener::new(pool.clone(), log_relay).run());
 
         let router = routes::router(state);
         let addr: SocketAddr = config
@@ -11,6 +11,7 @@ const DEFAULT_ACTIVITY_MAX_LIMIT: i64 = 500;
 const DEFAULT_ACTIVITY_BROADCAST_SHARDS: usize = 16;
 const DEFAULT_ACTIVITY_BROADCAST_CAPACITY: usize = 512;
 const DEFAULT_ACTIVITY_CATCHUP_BATCH_SIZE: i64 = 100;
+const DEFAULT_LOG_RELAY_CAPACITY: usize = 1024;
 
 #[derive(Debug, Clone)]
 pub struct RemoteServerConfig {
@@ -23,6 +24,13 @@ pub struct RemoteServerConfig {
     pub activity_broadcast_shards: usize,
     pub activity_broadcast_capacity: usize,
     pub activity_catchup_batch_size: i64,
+    /// NOTIFY channel Hive replicas announce ingested node logs on
+    pub log_relay_channel: String,
+    /// Log entries buffered per watched assignment before a slow viewer catches up from the
+    /// database instead
+    pub log_relay_capacity: usize,
+    /// NOTIFY channel Hive replicas route messages for each other's nodes on
+    pub node_routing_channel: String,
     pub auth: AuthConfig,
     /// URL of the Electric sync service (e.g., "http://localhost:3001")
     pub electric_url: Option<String>,
@@ -75,6 +83,15 @@ impl RemoteServerConfig {
         )?
         .max(1);
 
+        let log_relay_channel =
+            env::var("SERVER_LOG_RELAY_CHANNEL").unwrap_or_else(|_| "task_output_logs".to_string());
+
+        let log_relay_capacity =
+            get_numeric_env_var("SERVER_LOG_RELAY_CAPACITY", DEFAULT_LOG_RELAY_CAPACITY)?.max(1);
+
+        let node_routing_channel = env::var("SERVER_NODE_ROUTING_CHANNEL")
+            .unwrap_or_else(|_| "hive_node_messages".to_string());
+
         let auth = AuthConfig::from_env()?;
 
         // Electric sync service configuration (optional)
@@ -93,6 +110,9 @@ impl RemoteServerConfig {
             activity_broadcast_shards,
             activity_broadcast_capacity,
             activity_catchup_batch_size,
+            log_relay_channel,
+            log_relay_capacity,
+            node_routing_channel,
             auth,
             electric_url,
             electric_secret,
@@ -0,0 +1,251 @@
+//! The nodes a Hive task was dispatched to, one hop per dispatch.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, types::Json};
+use uuid::Uuid;
+
+use crate::nodes::DispatchRequirements;
+
+/// What a project's lease expiry policy did with a hop whose lease expired
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "hop_resolution", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum HopResolution {
+    /// The task was queued for another node
+    Retried,
+    /// The task was given up on
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignmentHop {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub assignment_id: Option<Uuid>,
+    pub node_id: Option<Uuid>,
+    pub node_name: Option<String>,
+    /// `None` for assignments made before hops were recorded
+    pub requirements: Option<Json<DispatchRequirements>>,
+    /// The task's priority in the queue; `None` when it was dispatched without queueing
+    pub priority: Option<i32>,
+    pub deadline: Option<DateTime<Utc>>,
+    pub dispatched_at: DateTime<Utc>,
+    pub lease_expired_at: Option<DateTime<Utc>>,
+    pub resolution: Option<HopResolution>,
+    /// When the retry may be dispatched, for hops that were retried
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+/// Where a task stood in the queue when it was dispatched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueuePlace {
+    pub priority: i32,
+    pub deadline: Option<DateTime<Utc>>,
+}
+
+/// Who to tell when a task is given up on
+#[derive(Debug, Clone)]
+pub struct FailureContact {
+    pub organization_name: String,
+    pub email: String,
+    pub task_title: String,
+}
+
+pub struct AssignmentHopRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> AssignmentHopRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a dispatch, keeping the task's place in the queue when it was dispatched from there
+    pub async fn record_dispatch(
+        &self,
+        task_id: Uuid,
+        assignment_id: Uuid,
+        node_id: Uuid,
+        requirements: &DispatchRequirements,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO task_assignment_hops
+                (task_id, assignment_id, node_id, requirements, priority, deadline)
+            SELECT $1, $2, $3, $4, q.priority, q.deadline
+            FROM (SELECT 1) AS one
+            LEFT JOIN task_queue q ON q.task_id = $1
+            "#,
+            task_id,
+            assignment_id,
+            node_id,
+            Json(requirements) as _
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record that the lease of an assignment expired and what was done about it. Assignments
+    /// made before hops were recorded get a hop of their own.
+    pub async fn record_expiry(
+        &self,
+        task_id: Uuid,
+        assignment_id: Uuid,
+        node_id: Uuid,
+        resolution: HopResolution,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            WITH updated AS (
+                UPDATE task_assignment_hops
+                SET lease_expired_at = NOW(), resolution = $4, retry_at = $5
+                WHERE id = (
+                    SELECT id FROM task_assignment_hops
+                    WHERE assignment_id = $2 AND lease_expired_at IS NULL
+                    ORDER BY dispatched_at DESC
+                    LIMIT 1
+                )
+                RETURNING id
+            )
+            INSERT INTO task_assignment_hops
+                (task_id, assignment_id, node_id, dispatched_at, lease_expired_at, resolution,
+                 retry_at)
+            SELECT $1, $2, $3,
+                   COALESCE(
+                       (SELECT assigned_at FROM node_task_assignments WHERE id = $2),
+                       NOW()
+                   ),
+                   NOW(), $4, $5
+            WHERE NOT EXISTS (SELECT 1 FROM updated)
+            "#,
+            task_id,
+            assignment_id,
+            node_id,
+            resolution as HopResolution,
+            retry_at
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// A task's hops, oldest first
+    pub async fn list_for_task(&self, task_id: Uuid) -> Result<Vec<AssignmentHop>, sqlx::Error> {
+        sqlx::query_as!(
+            AssignmentHop,
+            r#"
+            SELECT
+                h.id                AS "id!",
+                h.task_id           AS "task_id!",
+                h.assignment_id     AS "assignment_id?",
+                h.node_id           AS "node_id?",
+                n.name              AS "node_name?",
+                h.requirements      AS "requirements?: Json<DispatchRequirements>",
+                h.priority          AS "priority?",
+                h.deadline          AS "deadline?",
+                h.dispatched_at     AS "dispatched_at!",
+                h.lease_expired_at  AS "lease_expired_at?",
+                h.resolution        AS "resolution?: HopResolution",
+                h.retry_at          AS "retry_at?"
+            FROM task_assignment_hops h
+            LEFT JOIN nodes n ON n.id = h.node_id
+            WHERE h.task_id = $1
+            ORDER BY h.dispatched_at ASC
+            "#,
+            task_id
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// How many times the task was already moved to another node after a lease expired
+    pub async fn count_retries(&self, task_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM task_assignment_hops
+            WHERE task_id = $1 AND resolution = 'retried'
+            "#,
+            task_id
+        )
+        .fetch_one(self.pool)
+        .await
+    }
+
+    /// Nodes whose lease on the task expired
+    pub async fn expired_nodes(&self, task_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT node_id AS "node_id!"
+            FROM task_assignment_hops
+            WHERE task_id = $1
+              AND lease_expired_at IS NOT NULL
+              AND node_id IS NOT NULL
+            "#,
+            task_id
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// What the task needed from its node the last time it was dispatched
+    pub async fn last_requirements(
+        &self,
+        task_id: Uuid,
+    ) -> Result<Option<DispatchRequirements>, sqlx::Error> {
+        let requirements = sqlx::query_scalar!(
+            r#"
+            SELECT requirements AS "requirements!: Json<DispatchRequirements>"
+            FROM task_assignment_hops
+            WHERE task_id = $1 AND requirements IS NOT NULL
+            ORDER BY dispatched_at DESC
+            LIMIT 1
+            "#,
+            task_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+        Ok(requirements.map(|Json(requirements)| requirements))
+    }
+
+    /// The task's place in the queue the last time it was dispatched from there
+    pub async fn last_queue_place(&self, task_id: Uuid) -> Result<Option<QueuePlace>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuePlace,
+            r#"
+            SELECT priority AS "priority!", deadline
+            FROM task_assignment_hops
+            WHERE task_id = $1 AND priority IS NOT NULL
+            ORDER BY dispatched_at DESC
+            LIMIT 1
+            "#,
+            task_id
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    /// The task's assignee, or its creator when nobody is assigned
+    pub async fn failure_contact(
+        &self,
+        task_id: Uuid,
+    ) -> Result<Option<FailureContact>, sqlx::Error> {
+        sqlx::query_as!(
+            FailureContact,
+            r#"
+            SELECT o.name AS organization_name, u.email,
+                   st.title AS task_title
+            FROM shared_tasks st
+            JOIN organizations o ON o.id = st.organization_id
+            JOIN users u ON u.id = COALESCE(st.assignee_user_id, st.creator_user_id)
+            WHERE st.id = $1
+            "#,
+            task_id
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+}
@@ -0,0 +1,97 @@
+//! Per-project policies for tasks whose node let its lease expire.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// What happens to a task once its node's lease on it expires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "lease_expiry_action", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum LeaseExpiryAction {
+    /// Queue the task for another node, failing it once the retries are used up
+    Retry,
+    /// Fail the task and notify
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaseExpiryPolicy {
+    pub swarm_project_id: Uuid,
+    pub on_expiry: LeaseExpiryAction,
+    /// Times a task may move to another node before it fails
+    pub max_retries: i32,
+    /// Wait before the first retry; doubled for every retry after it
+    pub retry_backoff_seconds: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct LeaseExpiryPolicyRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> LeaseExpiryPolicyRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find(
+        &self,
+        swarm_project_id: Uuid,
+    ) -> Result<Option<LeaseExpiryPolicy>, sqlx::Error> {
+        sqlx::query_as!(
+            LeaseExpiryPolicy,
+            r#"
+            SELECT swarm_project_id, on_expiry AS "on_expiry: LeaseExpiryAction", max_retries,
+                   retry_backoff_seconds, updated_at
+            FROM lease_expiry_policies
+            WHERE swarm_project_id = $1
+            "#,
+            swarm_project_id
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    pub async fn upsert(
+        &self,
+        swarm_project_id: Uuid,
+        on_expiry: LeaseExpiryAction,
+        max_retries: i32,
+        retry_backoff_seconds: i32,
+    ) -> Result<LeaseExpiryPolicy, sqlx::Error> {
+        sqlx::query_as!(
+            LeaseExpiryPolicy,
+            r#"
+            INSERT INTO lease_expiry_policies
+                (swarm_project_id, on_expiry, max_retries, retry_backoff_seconds)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (swarm_project_id) DO UPDATE SET
+                on_expiry = EXCLUDED.on_expiry,
+                max_retries = EXCLUDED.max_retries,
+                retry_backoff_seconds = EXCLUDED.retry_backoff_seconds,
+                updated_at = NOW()
+            RETURNING swarm_project_id, on_expiry AS "on_expiry: LeaseExpiryAction", max_retries,
+                      retry_backoff_seconds, updated_at
+            "#,
+            swarm_project_id,
+            on_expiry as LeaseExpiryAction,
+            max_retries,
+            retry_backoff_seconds
+        )
+        .fetch_one(self.pool)
+        .await
+    }
+
+    /// Remove a project's policy, returning whether it had one
+    pub async fn delete(&self, swarm_project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM lease_expiry_policies WHERE swarm_project_id = $1",
+            swarm_project_id
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use anyhow::Context;
 use serde::Deserialize;
@@ -7,7 +7,15 @@ use tokio::time::sleep;
 use tracing::instrument;
 use uuid::Uuid;
 
-use crate::{activity::ActivityBroker, db::activity::ActivityRepository};
+use crate::{
+    activity::ActivityBroker,
+    db::{
+        activity::ActivityRepository, node_sessions::NodeSessionRepository,
+        task_output_logs::TaskOutputLogRepository,
+    },
+    nodes::{ConnectionManager, HiveCluster},
+    services::{LogRelay, log_relay::LogRelayNotification},
+};
 
 pub struct ActivityListener {
     pool: PgPool,
@@ -106,3 +114,210 @@ struct NotificationEnvelope {
     seq: i64,
     project_id: String,
 }
+
+/// Relays node output logs ingested by other Hive replicas to this replica's viewers.
+pub struct LogRelayListener {
+    pool: PgPool,
+    relay: LogRelay,
+}
+
+impl LogRelayListener {
+    pub fn new(pool: PgPool, relay: LogRelay) -> Self {
+        Self { pool, relay }
+    }
+
+    #[instrument(
+        name = "log_relay.listener",
+        skip(self),
+        fields(channel = %self.relay.channel())
+    )]
+    pub async fn run(self) {
+        let mut backoff = Duration::from_secs(1);
+        let max_backoff = Duration::from_secs(30);
+
+        loop {
+            match log_relay_loop(&self.pool, &self.relay).await {
+                Ok(_) => {
+                    backoff = Duration::from_secs(1);
+                }
+                Err(error) => {
+                    tracing::error!(?error, ?backoff, "log relay listener error; retrying");
+                    sleep(backoff).await;
+                    backoff = (backoff * 2).min(max_backoff);
+                }
+            }
+        }
+    }
+}
+
+#[instrument(
+    name = "log_relay.listen_loop",
+    skip(pool, relay),
+    fields(channel = %relay.channel())
+)]
+async fn log_relay_loop(pool: &PgPool, relay: &LogRelay) -> anyhow::Result<()> {
+    let mut listener = PgListener::connect_with(pool)
+        .await
+        .context("failed to create LISTEN connection")?;
+    listener
+        .listen(relay.channel())
+        .await
+        .with_context(|| format!("failed to LISTEN on channel {}", relay.channel()))?;
+    // Logs announced while we were not listening are only in the database
+    relay.resync();
+
+    loop {
+        let Some(notification) = listener
+            .try_recv()
+            .await
+            .context("failed to receive LISTEN notification")?
+        else {
+            tracing::warn!("log relay LISTEN connection lost; reconnecting");
+            return Ok(());
+        };
+
+        let payload: LogRelayNotification = match serde_json::from_str(notification.payload()) {
+            Ok(payload) => payload,
+            Err(error) => {
+                tracing::warn!(
+                    ?error,
+                    payload = notification.payload(),
+                    "invalid log relay notification"
+                );
+                continue;
+            }
+        };
+
+        // Logs ingested here were fanned out already, and nobody here watches the rest
+        if payload.origin == relay.instance_id() || !relay.has_subscribers(payload.assignment_id) {
+            continue;
+        }
+
+        match TaskOutputLogRepository::new(pool)
+            .list_range(payload.assignment_id, payload.first_id, payload.last_id)
+            .await
+        {
+            Ok(logs) => relay.fan_out(logs),
+            Err(error) => {
+                tracing::error!(?error, assignment_id = %payload.assignment_id, "failed to fetch relayed logs");
+            }
+        }
+    }
+}
+
+/// Delivers messages other Hive replicas routed to nodes connected to this replica.
+pub struct RoutedMessageListener {
+    cluster: Arc<HiveCluster>,
+    connections: ConnectionManager,
+}
+
+impl RoutedMessageListener {
+    pub fn new(cluster: Arc<HiveCluster>, connections: ConnectionManager) -> Self {
+        Self {
+            cluster,
+            connections,
+        }
+    }
+
+    #[instrument(
+        name = "node_routing.listener",
+        skip(self),
+        fields(channel = %self.cluster.channel(), replica_id = %self.cluster.replica_id())
+    )]
+    pub async fn run(self) {
+        let mut backoff = Duration::from_secs(1);
+        let max_backoff = Duration::from_secs(30);
+
+        loop {
+            match routed_message_loop(&self.cluster, &self.connections).await {
+                Ok(_) => {
+                    backoff = Duration::from_secs(1);
+                }
+                Err(error) => {
+                    tracing::error!(?error, ?backoff, "node routing listener error; retrying");
+                    sleep(backoff).await;
+                    backoff = (backoff * 2).min(max_backoff);
+                }
+            }
+        }
+    }
+}
+
+#[instrument(
+    name = "node_routing.listen_loop",
+    skip(cluster, connections),
+    fields(channel = %cluster.channel())
+)]
+async fn routed_message_loop(
+    cluster: &HiveCluster,
+    connections: &ConnectionManager,
+) -> anyhow::Result<()> {
+    let mut listener = PgListener::connect_with(cluster.pool())
+        .await
+        .context("failed to create LISTEN connection")?;
+    listener
+        .listen(cluster.channel())
+        .await
+        .with_context(|| format!("failed to LISTEN on channel {}", cluster.channel()))?;
+
+    // Messages announced while we were not listening are only in the table
+    let pending = NodeSessionRepository::new(cluster.pool())
+        .take_all_routed(cluster.replica_id())
+        .await
+        .context("failed to fetch pending routed messages")?;
+    if !pending.is_empty() {
+        tracing::info!(
+            count = pending.len(),
+            "delivering routed messages missed while not listening"
+        );
+    }
+    for (node_ids, routed) in pending {
+        connections.deliver_routed(&node_ids, routed).await;
+    }
+
+    loop {
+        let Some(notification) = listener
+            .try_recv()
+            .await
+            .context("failed to receive LISTEN notification")?
+        else {
+            tracing::warn!("node routing LISTEN connection lost; reconnecting");
+            return Ok(());
+        };
+
+        let payload: RoutedNotification = match serde_json::from_str(notification.payload()) {
+            Ok(payload) => payload,
+            Err(error) => {
+                tracing::warn!(
+                    ?error,
+                    payload = notification.payload(),
+                    "invalid node routing notification"
+                );
+                continue;
+            }
+        };
+
+        if payload.replica_id != cluster.replica_id() {
+            continue;
+        }
+
+        match NodeSessionRepository::new(cluster.pool())
+            .take_routed(payload.id)
+            .await
+        {
+            Ok(Some((node_ids, routed))) => connections.deliver_routed(&node_ids, routed).await,
+            Ok(None) => {
+                tracing::warn!(id = payload.id, "routed message missing for notification");
+            }
+            Err(error) => {
+                tracing::error!(?error, id = payload.id, "failed to fetch routed message");
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RoutedNotification {
+    id: i64,
+    replica_id: Uuid,
+}
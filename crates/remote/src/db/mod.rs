@@ -1,28 +1,34 @@
 pub mod activity;
+pub mod assignment_hops;
 pub mod auth;
 pub mod identity_errors;
 pub mod invitations;
 pub mod labels;
+pub mod lease_expiry_policies;
 pub mod listener;
 pub mod maintenance;
 pub mod node_api_keys;
 pub mod node_execution_processes;
 pub mod node_local_projects;
+pub mod node_sessions;
 pub mod node_task_attempts;
 pub mod nodes;
 pub mod oauth;
 pub mod oauth_accounts;
 pub mod organization_members;
 pub mod organizations;
+pub mod swarm_approvals;
 pub mod swarm_projects;
 pub mod swarm_templates;
 pub mod task_assignments;
+pub mod task_dispatch_settings;
 pub mod task_output_logs;
 pub mod task_progress_events;
+pub mod task_queue;
 pub mod tasks;
 pub mod users;
 
-pub use listener::ActivityListener;
+pub use listener::{ActivityListener, LogRelayListener, RoutedMessageListener};
 use sqlx::{PgPool, Postgres, Transaction, migrate::MigrateError, postgres::PgPoolOptions};
 
 pub(crate) type Tx<'a> = Transaction<'a, Postgres>;
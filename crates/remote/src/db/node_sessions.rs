@@ -0,0 +1,252 @@
+//! Which Hive replica each connected node's WebSocket lives on, and messages routed to nodes
+//! connected to another replica.
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, types::Json};
+use uuid::Uuid;
+
+use crate::nodes::{NodeStatus, RoutedPayload};
+
+/// A node's WebSocket on some replica, whose lock that replica still holds
+#[derive(Debug, Clone)]
+pub struct NodeSession {
+    pub node_id: Uuid,
+    pub organization_id: Uuid,
+    pub replica_id: Uuid,
+    pub status: NodeStatus,
+    pub active_tasks: i32,
+    pub connected_at: DateTime<Utc>,
+}
+
+pub struct NodeSessionRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> NodeSessionRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record that a replica took a node's WebSocket, replacing whatever session it had before
+    pub async fn upsert(
+        &self,
+        node_id: Uuid,
+        organization_id: Uuid,
+        replica_id: Uuid,
+        lock_key: i64,
+        lock_pid: i32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO hive_node_sessions
+                (node_id, organization_id, replica_id, lock_key, lock_pid)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (node_id) DO UPDATE SET
+                organization_id = EXCLUDED.organization_id,
+                replica_id = EXCLUDED.replica_id,
+                lock_key = EXCLUDED.lock_key,
+                lock_pid = EXCLUDED.lock_pid,
+                status = 'online',
+                active_tasks = 0,
+                connected_at = NOW(),
+                updated_at = NOW()
+            "#,
+            node_id,
+            organization_id,
+            replica_id,
+            lock_key,
+            lock_pid
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Point a replica's sessions at the backend that now holds its locks
+    pub async fn move_locks(
+        &self,
+        replica_id: Uuid,
+        node_ids: &[Uuid],
+        lock_pid: i32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE hive_node_sessions
+            SET lock_pid = $3, updated_at = NOW()
+            WHERE replica_id = $1 AND node_id = ANY($2)
+            "#,
+            replica_id,
+            node_ids,
+            lock_pid
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, node_id: Uuid, replica_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM hive_node_sessions WHERE node_id = $1 AND replica_id = $2",
+            node_id,
+            replica_id
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the status and load a node reported in its heartbeat
+    pub async fn update_load(
+        &self,
+        node_id: Uuid,
+        replica_id: Uuid,
+        status: NodeStatus,
+        active_tasks: i32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE hive_node_sessions
+            SET status = $3, active_tasks = $4, updated_at = NOW()
+            WHERE node_id = $1 AND replica_id = $2
+            "#,
+            node_id,
+            replica_id,
+            status as NodeStatus,
+            active_tasks
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_live(&self, node_id: Uuid) -> Result<Option<NodeSession>, sqlx::Error> {
+        sqlx::query_as!(
+            NodeSession,
+            r#"
+            SELECT node_id, organization_id, replica_id, status AS "status: NodeStatus",
+                   active_tasks, connected_at
+            FROM hive_node_sessions
+            WHERE node_id = $1
+              AND hive_session_lock_held(lock_key, lock_pid)
+            "#,
+            node_id
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    pub async fn list_live_for_organization(
+        &self,
+        organization_id: Uuid,
+    ) -> Result<Vec<NodeSession>, sqlx::Error> {
+        sqlx::query_as!(
+            NodeSession,
+            r#"
+            SELECT node_id, organization_id, replica_id, status AS "status: NodeStatus",
+                   active_tasks, connected_at
+            FROM hive_node_sessions
+            WHERE organization_id = $1
+              AND hive_session_lock_held(lock_key, lock_pid)
+            "#,
+            organization_id
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    pub async fn list_live(&self, node_ids: &[Uuid]) -> Result<Vec<NodeSession>, sqlx::Error> {
+        sqlx::query_as!(
+            NodeSession,
+            r#"
+            SELECT node_id, organization_id, replica_id, status AS "status: NodeStatus",
+                   active_tasks, connected_at
+            FROM hive_node_sessions
+            WHERE node_id = ANY($1)
+              AND hive_session_lock_held(lock_key, lock_pid)
+            "#,
+            node_ids
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// Queue a message for nodes on another replica and wake that replica up
+    pub async fn route(
+        &self,
+        channel: &str,
+        replica_id: Uuid,
+        node_ids: &[Uuid],
+        payload: &RoutedPayload,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            WITH routed AS (
+                INSERT INTO hive_routed_messages (replica_id, node_ids, payload)
+                VALUES ($2, $3, $4)
+                RETURNING id
+            )
+            SELECT pg_notify($1, json_build_object('id', id, 'replica_id', $2)::text)
+            FROM routed
+            "#,
+            channel,
+            replica_id,
+            node_ids,
+            Json(payload) as _
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Take a routed message off the queue for delivery
+    pub async fn take_routed(
+        &self,
+        id: i64,
+    ) -> Result<Option<(Vec<Uuid>, RoutedPayload)>, sqlx::Error> {
+        let routed = sqlx::query!(
+            r#"
+            DELETE FROM hive_routed_messages
+            WHERE id = $1
+            RETURNING node_ids, payload AS "payload: Json<RoutedPayload>"
+            "#,
+            id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+        Ok(routed.map(|routed| (routed.node_ids, routed.payload.0)))
+    }
+
+    /// Take every routed message waiting for a replica off the queue, oldest first, e.g. those
+    /// announced while it was not listening
+    pub async fn take_all_routed(
+        &self,
+        replica_id: Uuid,
+    ) -> Result<Vec<(Vec<Uuid>, RoutedPayload)>, sqlx::Error> {
+        let mut routed = sqlx::query!(
+            r#"
+            DELETE FROM hive_routed_messages
+            WHERE replica_id = $1
+            RETURNING id, node_ids, payload AS "payload: Json<RoutedPayload>"
+            "#,
+            replica_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+        routed.sort_unstable_by_key(|routed| routed.id);
+        Ok(routed
+            .into_iter()
+            .map(|routed| (routed.node_ids, routed.payload.0))
+            .collect())
+    }
+
+    /// Drop routed messages nobody took, e.g. because their replica went away
+    pub async fn prune_routed(&self, older_than: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM hive_routed_messages WHERE created_at < $1",
+            older_than
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,615 @@
+//! Approval requests forwarded by nodes, and the per-organization escalation settings for them.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use thiserror::Error;
+use utils::approvals::{ApprovalStatus, Question};
+use uuid::Uuid;
+
+use crate::nodes::ws::message::ApprovalRequestMessage;
+
+#[derive(Debug, Error)]
+pub enum SwarmApprovalError {
+    #[error("approval not found")]
+    NotFound,
+    #[error("approval already answered or timed out")]
+    AlreadyResolved,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// An approval request raised by an agent on a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwarmApproval {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub node_id: Uuid,
+    pub node_name: String,
+    /// The approval's id on the node
+    pub approval_id: String,
+    pub shared_task_id: Option<Uuid>,
+    pub local_attempt_id: Option<Uuid>,
+    pub local_execution_process_id: Uuid,
+    pub task_title: String,
+    pub tool_name: String,
+    pub tool_input: serde_json::Value,
+    pub questions: Option<Vec<Question>>,
+    pub status: ApprovalStatus,
+    pub answers: Option<HashMap<String, String>>,
+    pub responded_by: Option<Uuid>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub escalated_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub timeout_at: DateTime<Utc>,
+}
+
+// Internal struct for raw database row
+#[derive(Debug)]
+struct SwarmApprovalRow {
+    id: Uuid,
+    organization_id: Uuid,
+    node_id: Uuid,
+    node_name: String,
+    approval_id: String,
+    shared_task_id: Option<Uuid>,
+    local_attempt_id: Option<Uuid>,
+    local_execution_process_id: Uuid,
+    task_title: String,
+    tool_name: String,
+    tool_input: serde_json::Value,
+    questions: Option<serde_json::Value>,
+    status: String,
+    denial_reason: Option<String>,
+    answers: Option<serde_json::Value>,
+    responded_by: Option<Uuid>,
+    responded_at: Option<DateTime<Utc>>,
+    delivered_at: Option<DateTime<Utc>>,
+    escalated_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    timeout_at: DateTime<Utc>,
+}
+
+impl From<SwarmApprovalRow> for SwarmApproval {
+    fn from(row: SwarmApprovalRow) -> Self {
+        let status = match row.status.as_str() {
+            "approved" => ApprovalStatus::Approved,
+            "denied" => ApprovalStatus::Denied {
+                reason: row.denial_reason,
+            },
+            "timed_out" => ApprovalStatus::TimedOut,
+            _ => ApprovalStatus::Pending,
+        };
+        SwarmApproval {
+            id: row.id,
+            organization_id: row.organization_id,
+            node_id: row.node_id,
+            node_name: row.node_name,
+            approval_id: row.approval_id,
+            shared_task_id: row.shared_task_id,
+            local_attempt_id: row.local_attempt_id,
+            local_execution_process_id: row.local_execution_process_id,
+            task_title: row.task_title,
+            tool_name: row.tool_name,
+            tool_input: row.tool_input,
+            questions: row.questions.and_then(|q| serde_json::from_value(q).ok()),
+            status,
+            answers: row.answers.and_then(|a| serde_json::from_value(a).ok()),
+            responded_by: row.responded_by,
+            responded_at: row.responded_at,
+            delivered_at: row.delivered_at,
+            escalated_at: row.escalated_at,
+            created_at: row.created_at,
+            timeout_at: row.timeout_at,
+        }
+    }
+}
+
+/// Split a status into its `status` and `denial_reason` columns
+fn status_columns(status: &ApprovalStatus) -> (&'static str, Option<&str>) {
+    match status {
+        ApprovalStatus::Pending => ("pending", None),
+        ApprovalStatus::Approved => ("approved", None),
+        ApprovalStatus::Denied { reason } => ("denied", reason.as_deref()),
+        ApprovalStatus::TimedOut => ("timed_out", None),
+    }
+}
+
+/// Decision applied to approvals nobody answers before they escalate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "approval_escalation_decision", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum EscalationDecision {
+    Approve,
+    Deny,
+}
+
+impl EscalationDecision {
+    pub fn to_status(self) -> ApprovalStatus {
+        match self {
+            EscalationDecision::Approve => ApprovalStatus::Approved,
+            EscalationDecision::Deny => ApprovalStatus::Denied {
+                reason: Some("Denied automatically after the approval escalated".to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalEscalationSettings {
+    pub organization_id: Uuid,
+    /// Minutes a request may wait before it escalates
+    pub timeout_minutes: i32,
+    /// Member notified when a request escalates
+    pub escalate_to: Option<Uuid>,
+    /// Decision applied when a request escalates, or one more timeout after the escalation
+    /// notice when someone is notified
+    pub default_decision: Option<EscalationDecision>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A pending approval whose escalation timeout has passed
+#[derive(Debug, Clone)]
+pub struct DueEscalation {
+    pub approval: SwarmApproval,
+    pub organization_name: String,
+    pub escalate_to_email: String,
+}
+
+pub struct SwarmApprovalRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> SwarmApprovalRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a request forwarded by a node. Requests forwarded again after a reconnect only
+    /// refresh their task details; their status is kept.
+    pub async fn upsert_request(
+        &self,
+        organization_id: Uuid,
+        node_id: Uuid,
+        request: &ApprovalRequestMessage,
+    ) -> Result<SwarmApproval, SwarmApprovalError> {
+        let questions = request
+            .questions
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO swarm_approvals (
+                organization_id, node_id, approval_id, shared_task_id, local_attempt_id,
+                local_execution_process_id, task_title, tool_name, tool_input, questions,
+                created_at, timeout_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (node_id, approval_id) DO UPDATE SET
+                shared_task_id = COALESCE(EXCLUDED.shared_task_id, swarm_approvals.shared_task_id),
+                task_title = EXCLUDED.task_title,
+                timeout_at = EXCLUDED.timeout_at
+            RETURNING id
+            "#,
+            organization_id,
+            node_id,
+            request.approval_id,
+            request.shared_task_id,
+            request.local_attempt_id,
+            request.local_execution_process_id,
+            request.task_title,
+            request.tool_name,
+            request.tool_input,
+            questions,
+            request.created_at,
+            request.timeout_at
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or(SwarmApprovalError::NotFound)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<SwarmApproval>, SwarmApprovalError> {
+        let row = sqlx::query_as!(
+            SwarmApprovalRow,
+            r#"
+            SELECT
+                a.id,
+                a.organization_id,
+                a.node_id,
+                n.name AS node_name,
+                a.approval_id,
+                a.shared_task_id,
+                a.local_attempt_id,
+                a.local_execution_process_id,
+                a.task_title,
+                a.tool_name,
+                a.tool_input,
+                a.questions,
+                a.status,
+                a.denial_reason,
+                a.answers,
+                a.responded_by,
+                a.responded_at,
+                a.delivered_at,
+                a.escalated_at,
+                a.created_at,
+                a.timeout_at
+            FROM swarm_approvals a
+            JOIN nodes n ON n.id = a.node_id
+            WHERE a.id = $1
+            "#,
+            id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    /// Approvals of an organization, newest first. `pending_only` leaves out answered ones.
+    pub async fn list_by_organization(
+        &self,
+        organization_id: Uuid,
+        pending_only: bool,
+        limit: i64,
+    ) -> Result<Vec<SwarmApproval>, SwarmApprovalError> {
+        let rows = sqlx::query_as!(
+            SwarmApprovalRow,
+            r#"
+            SELECT
+                a.id,
+                a.organization_id,
+                a.node_id,
+                n.name AS node_name,
+                a.approval_id,
+                a.shared_task_id,
+                a.local_attempt_id,
+                a.local_execution_process_id,
+                a.task_title,
+                a.tool_name,
+                a.tool_input,
+                a.questions,
+                a.status,
+                a.denial_reason,
+                a.answers,
+                a.responded_by,
+                a.responded_at,
+                a.delivered_at,
+                a.escalated_at,
+                a.created_at,
+                a.timeout_at
+            FROM swarm_approvals a
+            JOIN nodes n ON n.id = a.node_id
+            WHERE a.organization_id = $1
+              AND (NOT $2 OR (a.status = 'pending' AND a.timeout_at > NOW()))
+            ORDER BY a.created_at DESC
+            LIMIT $3
+            "#,
+            organization_id,
+            pending_only,
+            limit
+        )
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Answer a pending approval. The first answer wins; later ones get `AlreadyResolved`.
+    pub async fn respond(
+        &self,
+        id: Uuid,
+        status: &ApprovalStatus,
+        answers: Option<&HashMap<String, String>>,
+        responded_by: Option<Uuid>,
+    ) -> Result<SwarmApproval, SwarmApprovalError> {
+        let (status, denial_reason) = status_columns(status);
+        let answers = answers
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+        let updated = sqlx::query!(
+            r#"
+            UPDATE swarm_approvals
+            SET status = $2,
+                denial_reason = $3,
+                answers = $4,
+                responded_by = $5,
+                responded_at = NOW()
+            WHERE id = $1
+              AND status = 'pending'
+              AND timeout_at > NOW()
+            "#,
+            id,
+            status,
+            denial_reason,
+            answers,
+            responded_by
+        )
+        .execute(self.pool)
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return match self.find_by_id(id).await? {
+                Some(_) => Err(SwarmApprovalError::AlreadyResolved),
+                None => Err(SwarmApprovalError::NotFound),
+            };
+        }
+        self.find_by_id(id)
+            .await?
+            .ok_or(SwarmApprovalError::NotFound)
+    }
+
+    /// Record that an approval was settled on its own node, which needs no delivery
+    pub async fn resolve_on_node(
+        &self,
+        node_id: Uuid,
+        approval_id: &str,
+        status: &ApprovalStatus,
+    ) -> Result<bool, SwarmApprovalError> {
+        let (status, denial_reason) = status_columns(status);
+        let result = sqlx::query!(
+            r#"
+            UPDATE swarm_approvals
+            SET status = $3,
+                denial_reason = $4,
+                responded_at = NOW(),
+                delivered_at = NOW()
+            WHERE node_id = $1
+              AND approval_id = $2
+              AND status = 'pending'
+            "#,
+            node_id,
+            approval_id,
+            status,
+            denial_reason
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn mark_delivered(&self, id: Uuid) -> Result<(), SwarmApprovalError> {
+        sqlx::query!(
+            "UPDATE swarm_approvals SET delivered_at = NOW() WHERE id = $1",
+            id
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Answers given while the node was offline, still to be sent to it
+    pub async fn find_undelivered(
+        &self,
+        node_id: Uuid,
+    ) -> Result<Vec<SwarmApproval>, SwarmApprovalError> {
+        let rows = sqlx::query_as!(
+            SwarmApprovalRow,
+            r#"
+            SELECT
+                a.id,
+                a.organization_id,
+                a.node_id,
+                n.name AS node_name,
+                a.approval_id,
+                a.shared_task_id,
+                a.local_attempt_id,
+                a.local_execution_process_id,
+                a.task_title,
+                a.tool_name,
+                a.tool_input,
+                a.questions,
+                a.status,
+                a.denial_reason,
+                a.answers,
+                a.responded_by,
+                a.responded_at,
+                a.delivered_at,
+                a.escalated_at,
+                a.created_at,
+                a.timeout_at
+            FROM swarm_approvals a
+            JOIN nodes n ON n.id = a.node_id
+            WHERE a.node_id = $1
+              AND a.status <> 'pending'
+              AND a.delivered_at IS NULL
+              AND a.timeout_at > NOW()
+            ORDER BY a.responded_at ASC
+            "#,
+            node_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Mark pending approvals past their node's timeout as timed out. The node has already
+    /// stopped waiting, so nothing is delivered.
+    pub async fn expire_timed_out(&self) -> Result<u64, SwarmApprovalError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE swarm_approvals
+            SET status = 'timed_out', responded_at = NOW(), delivered_at = NOW()
+            WHERE status = 'pending' AND timeout_at <= NOW()
+            "#
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Pending approvals that have waited past their organization's timeout and have someone to
+    /// notify
+    pub async fn find_due_for_escalation(&self) -> Result<Vec<DueEscalation>, SwarmApprovalError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT a.id, o.name AS organization_name, u.email AS escalate_to_email
+            FROM swarm_approvals a
+            JOIN approval_escalation_settings s ON s.organization_id = a.organization_id
+            JOIN organizations o ON o.id = a.organization_id
+            JOIN users u ON u.id = s.escalate_to
+            WHERE a.status = 'pending'
+              AND a.escalated_at IS NULL
+              AND a.timeout_at > NOW()
+              AND a.created_at + make_interval(mins => s.timeout_minutes) <= NOW()
+            "#
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let mut due = Vec::with_capacity(rows.len());
+        for row in rows {
+            if let Some(approval) = self.find_by_id(row.id).await? {
+                due.push(DueEscalation {
+                    approval,
+                    organization_name: row.organization_name,
+                    escalate_to_email: row.escalate_to_email,
+                });
+            }
+        }
+        Ok(due)
+    }
+
+    pub async fn mark_escalated(&self, id: Uuid) -> Result<(), SwarmApprovalError> {
+        sqlx::query!(
+            "UPDATE swarm_approvals SET escalated_at = NOW() WHERE id = $1",
+            id
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Pending approvals whose organization's default decision is due: at the escalation
+    /// timeout when nobody is notified, one more timeout after the notice otherwise
+    pub async fn find_due_for_default(
+        &self,
+    ) -> Result<Vec<(Uuid, EscalationDecision)>, SwarmApprovalError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT a.id, s.default_decision AS "default_decision!: EscalationDecision"
+            FROM swarm_approvals a
+            JOIN approval_escalation_settings s ON s.organization_id = a.organization_id
+            WHERE a.status = 'pending'
+              AND a.timeout_at > NOW()
+              AND s.default_decision IS NOT NULL
+              AND (
+                  (s.escalate_to IS NULL
+                   AND a.created_at + make_interval(mins => s.timeout_minutes) <= NOW())
+                  OR (a.escalated_at IS NOT NULL
+                      AND a.escalated_at + make_interval(mins => s.timeout_minutes) <= NOW())
+              )
+            "#
+        )
+        .fetch_all(self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.id, row.default_decision))
+            .collect())
+    }
+
+    pub async fn find_escalation_settings(
+        &self,
+        organization_id: Uuid,
+    ) -> Result<Option<ApprovalEscalationSettings>, SwarmApprovalError> {
+        let settings = sqlx::query_as!(
+            ApprovalEscalationSettings,
+            r#"
+            SELECT
+                organization_id,
+                timeout_minutes,
+                escalate_to,
+                default_decision AS "default_decision: EscalationDecision",
+                updated_at
+            FROM approval_escalation_settings
+            WHERE organization_id = $1
+            "#,
+            organization_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+        Ok(settings)
+    }
+
+    pub async fn upsert_escalation_settings(
+        &self,
+        organization_id: Uuid,
+        timeout_minutes: i32,
+        escalate_to: Option<Uuid>,
+        default_decision: Option<EscalationDecision>,
+    ) -> Result<ApprovalEscalationSettings, SwarmApprovalError> {
+        let settings = sqlx::query_as!(
+            ApprovalEscalationSettings,
+            r#"
+            INSERT INTO approval_escalation_settings
+                (organization_id, timeout_minutes, escalate_to, default_decision)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (organization_id) DO UPDATE SET
+                timeout_minutes = EXCLUDED.timeout_minutes,
+                escalate_to = EXCLUDED.escalate_to,
+                default_decision = EXCLUDED.default_decision,
+                updated_at = NOW()
+            RETURNING
+                organization_id,
+                timeout_minutes,
+                escalate_to,
+                default_decision AS "default_decision: EscalationDecision",
+                updated_at
+            "#,
+            organization_id,
+            timeout_minutes,
+            escalate_to,
+            default_decision as Option<EscalationDecision>
+        )
+        .fetch_one(self.pool)
+        .await?;
+        Ok(settings)
+    }
+
+    pub async fn delete_escalation_settings(
+        &self,
+        organization_id: Uuid,
+    ) -> Result<(), SwarmApprovalError> {
+        sqlx::query!(
+            "DELETE FROM approval_escalation_settings WHERE organization_id = $1",
+            organization_id
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_columns_round_trip() {
+        let denied = ApprovalStatus::Denied {
+            reason: Some("not on main".to_string()),
+        };
+        assert_eq!(status_columns(&denied), ("denied", Some("not on main")));
+        assert_eq!(
+            status_columns(&ApprovalStatus::TimedOut),
+            ("timed_out", None)
+        );
+        assert!(matches!(
+            EscalationDecision::Deny.to_status(),
+            ApprovalStatus::Denied { reason: Some(_) }
+        ));
+        assert!(matches!(
+            EscalationDecision::Approve.to_status(),
+            ApprovalStatus::Approved
+        ));
+    }
+}
@@ -569,6 +569,33 @@ impl<'a> TaskAssignmentRepository<'a> {
             .collect())
     }
 
+    /// Fail an assignment reclaimed by the expiry sweep so the task can go to another node.
+    /// Only succeeds while the assignment still carries the token the sweep gave it, i.e. no node
+    /// claimed it since; returns whether it was failed.
+    pub async fn fail_reclaimed(
+        &self,
+        assignment_id: Uuid,
+        fencing_token: i64,
+    ) -> Result<bool, TaskAssignmentError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE node_task_assignments
+            SET execution_status = 'failed',
+                completed_at = now()
+            WHERE id = $1
+              AND fencing_token = $2
+              AND completed_at IS NULL
+              AND lease_expires_at IS NULL
+            "#,
+            assignment_id,
+            fencing_token
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Create a synthetic assignment for locally-started tasks.
     ///
     /// These are tasks that were started on the node without Hive dispatch.
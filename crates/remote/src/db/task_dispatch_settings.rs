@@ -0,0 +1,79 @@
+//! Per-organization settings for scheduling Hive tasks onto nodes.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Executor used when neither the dispatch request nor the organization names one
+pub const DEFAULT_EXECUTOR: &str = "CLAUDE_CODE";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskDispatchSettings {
+    pub organization_id: Uuid,
+    /// Dispatch every task created on the Hive, not only ones created with `start_attempt`
+    pub auto_dispatch: bool,
+    /// Executor a task runs with when the dispatch request does not name one
+    pub default_executor: String,
+    /// Labels a node must carry to receive tasks that do not name their own
+    pub required_node_labels: Vec<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct TaskDispatchSettingsRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> TaskDispatchSettingsRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find(
+        &self,
+        organization_id: Uuid,
+    ) -> Result<Option<TaskDispatchSettings>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskDispatchSettings,
+            r#"
+            SELECT organization_id, auto_dispatch, default_executor, required_node_labels,
+                   updated_at
+            FROM task_dispatch_settings
+            WHERE organization_id = $1
+            "#,
+            organization_id
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    pub async fn upsert(
+        &self,
+        organization_id: Uuid,
+        auto_dispatch: bool,
+        default_executor: &str,
+        required_node_labels: &[String],
+    ) -> Result<TaskDispatchSettings, sqlx::Error> {
+        sqlx::query_as!(
+            TaskDispatchSettings,
+            r#"
+            INSERT INTO task_dispatch_settings
+                (organization_id, auto_dispatch, default_executor, required_node_labels)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (organization_id) DO UPDATE SET
+                auto_dispatch = EXCLUDED.auto_dispatch,
+                default_executor = EXCLUDED.default_executor,
+                required_node_labels = EXCLUDED.required_node_labels,
+                updated_at = NOW()
+            RETURNING organization_id, auto_dispatch, default_executor, required_node_labels,
+                      updated_at
+            "#,
+            organization_id,
+            auto_dispatch,
+            default_executor,
+            required_node_labels
+        )
+        .fetch_one(self.pool)
+        .await
+    }
+}
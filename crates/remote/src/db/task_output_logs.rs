@@ -171,6 +171,31 @@ impl<'a> TaskOutputLogRepository<'a> {
         Ok(logs)
     }
 
+    /// Get the output logs of an assignment with `first_id <= id <= last_id`, oldest first.
+    pub async fn list_range(
+        &self,
+        assignment_id: Uuid,
+        first_id: i64,
+        last_id: i64,
+    ) -> Result<Vec<TaskOutputLog>, TaskOutputLogError> {
+        let logs = sqlx::query_as::<_, TaskOutputLog>(
+            r#"
+            SELECT id, assignment_id, output_type, content, timestamp, created_at
+            FROM node_task_output_logs
+            WHERE assignment_id = $1
+              AND id BETWEEN $2 AND $3
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(assignment_id)
+        .bind(first_id)
+        .bind(last_id)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(logs)
+    }
+
     /// Get the latest output logs (most recent first) for an assignment.
     pub async fn list_latest_by_assignment(
         &self,
@@ -0,0 +1,298 @@
+//! The Hive's queue of tasks waiting for a node, and its per-project limits.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, types::Json};
+use uuid::Uuid;
+
+use crate::nodes::DispatchRequirements;
+
+/// A task waiting in the queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedTask {
+    pub task_id: Uuid,
+    pub organization_id: Uuid,
+    pub swarm_project_id: Uuid,
+    pub title: String,
+    /// Higher goes first
+    pub priority: i32,
+    /// Among tasks of equal priority, earlier deadlines go first
+    pub deadline: Option<DateTime<Utc>>,
+    pub requirements: Json<DispatchRequirements>,
+    pub enqueued_by: Option<Uuid>,
+    pub enqueued_at: DateTime<Utc>,
+    /// Why the last attempt to hand the task out failed
+    pub last_error: Option<String>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    /// Not handed out before this time, while a retry backs off
+    pub not_before: Option<DateTime<Utc>>,
+}
+
+/// A project's share of the queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueProject {
+    pub swarm_project_id: Uuid,
+    pub name: String,
+    /// Most of the project's tasks that may run at once; `None` means no limit
+    pub max_concurrent_tasks: Option<i32>,
+    /// When the queue last handed out one of the project's tasks
+    pub last_dispatched_at: Option<DateTime<Utc>>,
+    /// Tasks of the project a node is working on, however they were dispatched
+    pub running: i64,
+    pub queued: i64,
+}
+
+pub struct TaskQueueRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> TaskQueueRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queue a task, or change the priority, deadline and requirements of a queued one. A task
+    /// that is queued again keeps its place among tasks of the same priority.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn enqueue(
+        &self,
+        task_id: Uuid,
+        organization_id: Uuid,
+        swarm_project_id: Uuid,
+        priority: i32,
+        deadline: Option<DateTime<Utc>>,
+        requirements: &DispatchRequirements,
+        enqueued_by: Option<Uuid>,
+    ) -> Result<QueuedTask, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedTask,
+            r#"
+            WITH q AS (
+                INSERT INTO task_queue
+                    (task_id, organization_id, swarm_project_id, priority, deadline,
+                     requirements, enqueued_by)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (task_id) DO UPDATE SET
+                    priority = EXCLUDED.priority,
+                    deadline = EXCLUDED.deadline,
+                    requirements = EXCLUDED.requirements
+                RETURNING *
+            )
+            SELECT
+                q.task_id,
+                q.organization_id,
+                q.swarm_project_id,
+                st.title,
+                q.priority,
+                q.deadline,
+                q.requirements AS "requirements!: Json<DispatchRequirements>",
+                q.enqueued_by,
+                q.enqueued_at,
+                q.last_error,
+                q.last_attempt_at,
+                q.not_before
+            FROM q
+            JOIN shared_tasks st ON st.id = q.task_id
+            "#,
+            task_id,
+            organization_id,
+            swarm_project_id,
+            priority,
+            deadline,
+            Json(requirements) as _,
+            enqueued_by
+        )
+        .fetch_one(self.pool)
+        .await
+    }
+
+    /// Queue a task for another node after its lease expired, held back until `not_before`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn enqueue_retry(
+        &self,
+        task_id: Uuid,
+        organization_id: Uuid,
+        swarm_project_id: Uuid,
+        priority: i32,
+        deadline: Option<DateTime<Utc>>,
+        requirements: &DispatchRequirements,
+        not_before: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO task_queue
+                (task_id, organization_id, swarm_project_id, priority, deadline, requirements,
+                 not_before)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (task_id) DO UPDATE SET
+                priority = EXCLUDED.priority,
+                deadline = EXCLUDED.deadline,
+                requirements = EXCLUDED.requirements,
+                not_before = EXCLUDED.not_before
+            "#,
+            task_id,
+            organization_id,
+            swarm_project_id,
+            priority,
+            deadline,
+            Json(requirements) as _,
+            not_before
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Take a task out of the queue, returning whether it was queued
+    pub async fn remove(&self, task_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_queue WHERE task_id = $1", task_id)
+            .execute(self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Queued tasks of an organization, highest priority and earliest deadline first.
+    /// Tasks deleted while queued are left out.
+    pub async fn list(&self, organization_id: Uuid) -> Result<Vec<QueuedTask>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedTask,
+            r#"
+            SELECT
+                q.task_id,
+                q.organization_id,
+                q.swarm_project_id,
+                st.title,
+                q.priority,
+                q.deadline,
+                q.requirements AS "requirements!: Json<DispatchRequirements>",
+                q.enqueued_by,
+                q.enqueued_at,
+                q.last_error,
+                q.last_attempt_at,
+                q.not_before
+            FROM task_queue q
+            JOIN shared_tasks st ON st.id = q.task_id
+            WHERE q.organization_id = $1
+              AND st.deleted_at IS NULL
+            ORDER BY q.priority DESC, q.deadline ASC NULLS LAST, q.enqueued_at ASC
+            "#,
+            organization_id
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// Drop queued tasks that were deleted since they were queued
+    pub async fn remove_deleted(&self, organization_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM task_queue q
+            USING shared_tasks st
+            WHERE st.id = q.task_id
+              AND q.organization_id = $1
+              AND st.deleted_at IS NOT NULL
+            "#,
+            organization_id
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Organizations with at least one queued task
+    pub async fn organizations_with_queued_tasks(&self) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!("SELECT DISTINCT organization_id FROM task_queue")
+            .fetch_all(self.pool)
+            .await
+    }
+
+    pub async fn has_queued_tasks(&self, organization_id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM task_queue WHERE organization_id = $1) AS "exists!""#,
+            organization_id
+        )
+        .fetch_one(self.pool)
+        .await
+    }
+
+    pub async fn record_failure(&self, task_id: Uuid, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE task_queue
+            SET last_error = $2, last_attempt_at = NOW()
+            WHERE task_id = $1
+            "#,
+            task_id,
+            error
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Every project of an organization with its limit, running and queued task counts
+    pub async fn projects(&self, organization_id: Uuid) -> Result<Vec<QueueProject>, sqlx::Error> {
+        sqlx::query_as!(
+            QueueProject,
+            r#"
+            SELECT
+                sp.id AS swarm_project_id,
+                sp.name,
+                qp.max_concurrent_tasks AS "max_concurrent_tasks?",
+                qp.last_dispatched_at AS "last_dispatched_at?",
+                (
+                    SELECT COUNT(*)
+                    FROM node_task_assignments nta
+                    JOIN shared_tasks st ON st.id = nta.task_id
+                    WHERE nta.completed_at IS NULL
+                      AND COALESCE(st.swarm_project_id, st.project_id) = sp.id
+                ) AS "running!",
+                (SELECT COUNT(*) FROM task_queue q WHERE q.swarm_project_id = sp.id) AS "queued!"
+            FROM swarm_projects sp
+            LEFT JOIN task_queue_projects qp ON qp.swarm_project_id = sp.id
+            WHERE sp.organization_id = $1
+            ORDER BY sp.name ASC
+            "#,
+            organization_id
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// Set how many of a project's tasks may run at once; `None` removes the limit
+    pub async fn set_project_limit(
+        &self,
+        swarm_project_id: Uuid,
+        max_concurrent_tasks: Option<i32>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO task_queue_projects (swarm_project_id, max_concurrent_tasks)
+            VALUES ($1, $2)
+            ON CONFLICT (swarm_project_id) DO UPDATE SET
+                max_concurrent_tasks = EXCLUDED.max_concurrent_tasks,
+                updated_at = NOW()
+            "#,
+            swarm_project_id,
+            max_concurrent_tasks
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Move a project to the back of the round-robin after one of its tasks was handed out
+    pub async fn mark_dispatched(&self, swarm_project_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO task_queue_projects (swarm_project_id, last_dispatched_at)
+            VALUES ($1, NOW())
+            ON CONFLICT (swarm_project_id) DO UPDATE SET last_dispatched_at = NOW()
+            "#,
+            swarm_project_id
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+}
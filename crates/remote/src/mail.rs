@@ -17,11 +17,34 @@ pub trait Mailer: Send + Sync {
         role: MemberRole,
         invited_by: Option<&str>,
     );
+
+    /// Tell a member that an approval request has waited past the escalation timeout
+    async fn send_approval_escalation(
+        &self,
+        org_name: &str,
+        email: &str,
+        task_title: &str,
+        tool_name: &str,
+        node_name: &str,
+        approvals_url: &str,
+    );
+
+    /// Tell a member that a task was given up on after its node's lease on it expired
+    async fn send_task_lease_failure(
+        &self,
+        org_name: &str,
+        email: &str,
+        task_title: &str,
+        node_name: &str,
+        retries: i64,
+    );
 }
 
 pub struct LoopsMailer {
     client: reqwest::Client,
     api_key: String,
+    approval_escalation_template_id: Option<String>,
+    task_lease_failure_template_id: Option<String>,
 }
 
 impl LoopsMailer {
@@ -31,7 +54,48 @@ impl LoopsMailer {
             .build()
             .expect("failed to build reqwest client");
 
-        Self { client, api_key }
+        Self {
+            client,
+            api_key,
+            approval_escalation_template_id: None,
+            task_lease_failure_template_id: None,
+        }
+    }
+
+    /// Set the Loops template for approval escalation emails; without one they are only logged
+    pub fn with_approval_escalation_template(mut self, template_id: Option<String>) -> Self {
+        self.approval_escalation_template_id = template_id;
+        self
+    }
+
+    /// Set the Loops template for failed task emails; without one they are only logged
+    pub fn with_task_lease_failure_template(mut self, template_id: Option<String>) -> Self {
+        self.task_lease_failure_template_id = template_id;
+        self
+    }
+
+    async fn send_transactional(&self, payload: serde_json::Value, email: &str) {
+        let res = self
+            .client
+            .post("https://app.loops.so/api/v1/transactional")
+            .bearer_auth(&self.api_key)
+            .json(&payload)
+            .send()
+            .await;
+
+        match res {
+            Ok(resp) if resp.status().is_success() => {
+                tracing::debug!("Email sent via Loops to {email}");
+            }
+            Ok(resp) => {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                tracing::warn!(status = %status, body = %body, "Loops send failed");
+            }
+            Err(err) => {
+                tracing::error!(error = ?err, "Loops request error");
+            }
+        }
     }
 }
 
@@ -71,26 +135,74 @@ impl Mailer for LoopsMailer {
             }
         });
 
-        let res = self
-            .client
-            .post("https://app.loops.so/api/v1/transactional")
-            .bearer_auth(&self.api_key)
-            .json(&payload)
-            .send()
-            .await;
+        self.send_transactional(payload, email).await;
+    }
 
-        match res {
-            Ok(resp) if resp.status().is_success() => {
-                tracing::debug!("Invitation email sent via Loops to {email}");
-            }
-            Ok(resp) => {
-                let status = resp.status();
-                let body = resp.text().await.unwrap_or_default();
-                tracing::warn!(status = %status, body = %body, "Loops send failed");
+    async fn send_approval_escalation(
+        &self,
+        org_name: &str,
+        email: &str,
+        task_title: &str,
+        tool_name: &str,
+        node_name: &str,
+        approvals_url: &str,
+    ) {
+        let Some(template_id) = self.approval_escalation_template_id.as_deref() else {
+            tracing::info!(
+                "Approval escalated to {email} (no email template configured)\n\
+                 Organization: {org_name}\n\
+                 Task: {task_title}\n\
+                 Tool: {tool_name} on {node_name}\n\
+                 Approvals URL: {approvals_url}"
+            );
+            return;
+        };
+
+        let payload = json!({
+            "transactionalId": template_id,
+            "email": email,
+            "dataVariables": {
+                "org_name": org_name,
+                "task_title": task_title,
+                "tool_name": tool_name,
+                "node_name": node_name,
+                "approvals_url": approvals_url,
             }
-            Err(err) => {
-                tracing::error!(error = ?err, "Loops request error");
+        });
+
+        self.send_transactional(payload, email).await;
+    }
+
+    async fn send_task_lease_failure(
+        &self,
+        org_name: &str,
+        email: &str,
+        task_title: &str,
+        node_name: &str,
+        retries: i64,
+    ) {
+        let Some(template_id) = self.task_lease_failure_template_id.as_deref() else {
+            tracing::info!(
+                "Task failure sent to {email} (no email template configured)\n\
+                 Organization: {org_name}\n\
+                 Task: {task_title}\n\
+                 Lease expired on: {node_name}\n\
+                 Retries: {retries}"
+            );
+            return;
+        };
+
+        let payload = json!({
+            "transactionalId": template_id,
+            "email": email,
+            "dataVariables": {
+                "org_name": org_name,
+                "task_title": task_title,
+                "node_name": node_name,
+                "retries": retries,
             }
-        }
+        });
+
+        self.send_transactional(payload, email).await;
     }
 }
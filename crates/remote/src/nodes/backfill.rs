@@ -178,10 +178,13 @@ impl BackfillService {
             logs_after: None,
         };
 
-        // Track the request in memory for response correlation
-        self.tracker
-            .track(request_id, node_id, vec![attempt_id])
-            .await;
+        // Track the request in memory for response correlation. Only the replica holding the
+        // node gets the response; the others rely on the backfill_request_id stored above.
+        if self.connections.is_local(node_id).await {
+            self.tracker
+                .track(request_id, node_id, vec![attempt_id])
+                .await;
+        }
 
         if let Err(e) = self
             .connections
@@ -247,10 +250,13 @@ impl BackfillService {
             logs_after: None,
         };
 
-        // Track the request in memory for response correlation
-        self.tracker
-            .track(request_id, node_id, attempt_ids.clone())
-            .await;
+        // Track the request in memory for response correlation. Only the replica holding the
+        // node gets the response; the others rely on the backfill_request_id stored above.
+        if self.connections.is_local(node_id).await {
+            self.tracker
+                .track(request_id, node_id, attempt_ids.clone())
+                .await;
+        }
 
         if let Err(e) = self
             .connections
@@ -311,17 +317,7 @@ impl BackfillService {
     /// This finds incomplete attempts where the node is online and requests backfill.
     /// Uses pagination to process incomplete attempts in batches.
     async fn run_periodic_reconciliation(&self) -> Result<u32, BackfillError> {
-        let repo = NodeTaskAttemptRepository::new(&self.pool);
-
-        // Reset stale pending_backfill states first
-        let reset = repo
-            .reset_stale_pending_backfill(self.config.backfill_timeout_minutes)
-            .await?;
-        if reset > 0 {
-            tracing::info!(count = reset, "reset stale pending_backfill states");
-        }
-
-        // Cleanup stale tracked requests
+        // Cleanup stale tracked requests; every replica tracks the requests it sent
         let stale_ids = self
             .tracker
             .cleanup_stale(self.config.backfill_timeout_minutes as i64)
@@ -333,6 +329,23 @@ impl BackfillService {
             );
         }
 
+        // With several replicas, one of them reconciles for all
+        if let Some(cluster) = self.connections.cluster()
+            && !cluster.leads("backfill_reconciliation").await
+        {
+            return Ok(0);
+        }
+
+        let repo = NodeTaskAttemptRepository::new(&self.pool);
+
+        // Reset stale pending_backfill states
+        let reset = repo
+            .reset_stale_pending_backfill(self.config.backfill_timeout_minutes)
+            .await?;
+        if reset > 0 {
+            tracing::info!(count = reset, "reset stale pending_backfill states");
+        }
+
         let mut total_requested = 0u32;
         let page_size: i64 = 100;
         let mut offset: i64 = 0;
@@ -59,6 +59,9 @@ pub struct NodeCapabilities {
     /// Git branch of the build
     #[serde(default)]
     pub git_branch: String,
+    /// Free-form labels for routing tasks to this node (e.g., ["gpu", "eu"])
+    #[serde(default)]
+    pub labels: Vec<String>,
 }
 
 fn default_max_concurrent() -> i32 {
@@ -240,6 +243,7 @@ mod tests {
         let caps: NodeCapabilities = serde_json::from_str("{}").unwrap();
         assert_eq!(caps.max_concurrent_tasks, 1);
         assert!(caps.executors.is_empty());
+        assert!(caps.labels.is_empty());
     }
 
     #[test]
@@ -13,5 +13,7 @@ pub use domain::{
 pub use heartbeat::HeartbeatMonitor;
 pub use service::{MergeNodesResult, NodeError, NodeService, NodeServiceImpl, RegisterNode};
 pub use ws::{
-    AssignResult, ConnectionManager, DispatchError, NodeConnectionInfo, SendError, TaskDispatcher,
+    AssignResult, ConnectionHandle, ConnectionManager, DispatchError, DispatchRequirements,
+    DispatchResult, HiveCluster, Ineligibility, NodeConnectionInfo, NodeRejection, RegisterError,
+    RoutedPayload, SendError, TaskDispatcher,
 };
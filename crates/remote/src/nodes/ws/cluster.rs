@@ -0,0 +1,310 @@
+//! Coordination between Hive replicas that share one database.
+//!
+//! Each replica keeps one dedicated Postgres connection for session-level
+//! advisory locks:
+//! - one per connected node, so a node's WebSocket lives on exactly one
+//!   replica. The replica records itself in `hive_node_sessions` so the others
+//!   know where to route messages for the node.
+//! - one per singleton job (such as backfill reconciliation), so only one
+//!   replica runs it.
+//!
+//! Locks go away with their connection, so a replica that dies gives up its
+//! nodes and jobs without any cleanup. Messages for a node on another replica
+//! are queued in `hive_routed_messages` and announced with NOTIFY; the owning
+//! replica delivers them (see `db::RoutedMessageListener`).
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, PgConnection, PgPool};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use super::message::HiveMessage;
+use crate::db::node_sessions::NodeSessionRepository;
+
+/// What a replica asks the replica holding some nodes to do with them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum RoutedPayload {
+    /// Send the message to the nodes
+    Deliver(Box<HiveMessage>),
+    /// Close the nodes' sessions, because they connected to another replica
+    Evict,
+}
+
+/// This replica's view of the Hive cluster.
+pub struct HiveCluster {
+    replica_id: Uuid,
+    pool: PgPool,
+    channel: String,
+    locks: Mutex<Locks>,
+}
+
+/// The lock connection and the locks taken on it
+struct Locks {
+    /// `None` after the connection broke, until maintenance reconnects
+    connection: Option<PgConnection>,
+    pid: i32,
+    /// Node -> (organization, sessions on this replica)
+    nodes: HashMap<Uuid, (Uuid, usize)>,
+    jobs: HashSet<i64>,
+}
+
+impl HiveCluster {
+    /// Join the cluster as a new replica, opening the lock connection.
+    pub async fn connect(pool: PgPool, channel: String) -> Result<Self, sqlx::Error> {
+        let (connection, pid) = open_lock_connection(&pool).await?;
+
+        Ok(Self {
+            replica_id: Uuid::new_v4(),
+            pool,
+            channel,
+            locks: Mutex::new(Locks {
+                connection: Some(connection),
+                pid,
+                nodes: HashMap::new(),
+                jobs: HashSet::new(),
+            }),
+        })
+    }
+
+    pub fn replica_id(&self) -> Uuid {
+        self.replica_id
+    }
+
+    /// The NOTIFY channel routed messages are announced on.
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Take a node's WebSocket for this replica. `false` when another replica holds it.
+    ///
+    /// Every successful call must be paired with a [`HiveCluster::release_node`].
+    pub async fn acquire_node(
+        &self,
+        node_id: Uuid,
+        organization_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let mut locks = self.locks.lock().await;
+        if let Some((_, sessions)) = locks.nodes.get_mut(&node_id) {
+            *sessions += 1;
+            return Ok(true);
+        }
+
+        let key = node_lock_key(node_id);
+        if !locks.try_lock(key).await? {
+            return Ok(false);
+        }
+
+        if let Err(error) = NodeSessionRepository::new(&self.pool)
+            .upsert(node_id, organization_id, self.replica_id, key, locks.pid)
+            .await
+        {
+            locks.unlock(key).await;
+            return Err(error);
+        }
+        locks.nodes.insert(node_id, (organization_id, 1));
+
+        Ok(true)
+    }
+
+    /// Give up one session's hold on a node; the last one lets other replicas take it.
+    pub async fn release_node(&self, node_id: Uuid) {
+        let mut locks = self.locks.lock().await;
+        let Some((_, sessions)) = locks.nodes.get_mut(&node_id) else {
+            // Lost with the lock connection already
+            return;
+        };
+        *sessions -= 1;
+        if *sessions > 0 {
+            return;
+        }
+        locks.nodes.remove(&node_id);
+
+        if let Err(error) = NodeSessionRepository::new(&self.pool)
+            .delete(node_id, self.replica_id)
+            .await
+        {
+            tracing::warn!(?error, %node_id, "failed to remove node session");
+        }
+        locks.unlock(node_lock_key(node_id)).await;
+    }
+
+    /// Whether this replica runs a singleton job, taking the job over when no replica does.
+    pub async fn leads(&self, job: &str) -> bool {
+        let key = job_lock_key(job);
+        let mut locks = self.locks.lock().await;
+        if locks.jobs.contains(&key) {
+            return true;
+        }
+
+        match locks.try_lock(key).await {
+            Ok(true) => {
+                tracing::info!(job, replica_id = %self.replica_id, "took over singleton job");
+                locks.jobs.insert(key);
+                true
+            }
+            Ok(false) => false,
+            Err(error) => {
+                tracing::warn!(?error, job, "failed to take singleton job lock");
+                false
+            }
+        }
+    }
+
+    /// Check the lock connection. After it broke, reconnect and take back the locks of this
+    /// replica's nodes; returns the nodes another replica took in the meantime.
+    pub async fn check_locks(&self) -> Result<Vec<Uuid>, sqlx::Error> {
+        let mut locks = self.locks.lock().await;
+        if let Some(connection) = locks.connection.as_mut() {
+            match sqlx::query_scalar!("SELECT 1 AS alive")
+                .fetch_one(&mut *connection)
+                .await
+            {
+                Ok(_) => return Ok(Vec::new()),
+                Err(error) => {
+                    tracing::warn!(?error, "Hive cluster lock connection broke; reconnecting");
+                    locks.connection = None;
+                }
+            }
+        }
+
+        let (connection, pid) = open_lock_connection(&self.pool).await?;
+        locks.connection = Some(connection);
+        locks.pid = pid;
+        // Jobs are taken back on their next run
+        locks.jobs.clear();
+
+        let mut kept = Vec::new();
+        let mut lost = Vec::new();
+        let node_ids: Vec<Uuid> = locks.nodes.keys().copied().collect();
+        for node_id in node_ids {
+            if locks.try_lock(node_lock_key(node_id)).await? {
+                kept.push(node_id);
+            } else {
+                locks.nodes.remove(&node_id);
+                lost.push(node_id);
+            }
+        }
+
+        NodeSessionRepository::new(&self.pool)
+            .move_locks(self.replica_id, &kept, pid)
+            .await?;
+        tracing::info!(
+            kept = kept.len(),
+            lost = lost.len(),
+            "reopened Hive cluster lock connection"
+        );
+
+        Ok(lost)
+    }
+
+    /// Hand a payload to the replica holding `node_ids`.
+    pub async fn route(
+        &self,
+        replica_id: Uuid,
+        node_ids: &[Uuid],
+        payload: &RoutedPayload,
+    ) -> Result<(), sqlx::Error> {
+        NodeSessionRepository::new(&self.pool)
+            .route(&self.channel, replica_id, node_ids, payload)
+            .await
+    }
+}
+
+impl Locks {
+    async fn try_lock(&mut self, key: i64) -> Result<bool, sqlx::Error> {
+        let Some(connection) = self.connection.as_mut() else {
+            return Err(sqlx::Error::PoolClosed);
+        };
+        let result = sqlx::query_scalar!(r#"SELECT pg_try_advisory_lock($1) AS "locked!""#, key)
+            .fetch_one(connection)
+            .await;
+        if result.is_err() {
+            // Maintenance reconnects and takes the node locks back
+            self.connection = None;
+        }
+        result
+    }
+
+    async fn unlock(&mut self, key: i64) {
+        let Some(connection) = self.connection.as_mut() else {
+            return;
+        };
+        if let Err(error) = sqlx::query_scalar!("SELECT pg_advisory_unlock($1)", key)
+            .fetch_one(connection)
+            .await
+        {
+            tracing::warn!(?error, "failed to release Hive cluster lock");
+            self.connection = None;
+        }
+    }
+}
+
+async fn open_lock_connection(pool: &PgPool) -> Result<(PgConnection, i32), sqlx::Error> {
+    let mut connection = PgConnection::connect_with(&pool.connect_options()).await?;
+    let pid = sqlx::query_scalar!(r#"SELECT pg_backend_pid() AS "pid!""#)
+        .fetch_one(&mut connection)
+        .await?;
+    Ok((connection, pid))
+}
+
+/// Advisory lock key of a node; the same on every replica
+pub fn node_lock_key(node_id: Uuid) -> i64 {
+    let (high, low) = node_id.as_u64_pair();
+    (high ^ low) as i64
+}
+
+/// Advisory lock key of a singleton job; the same on every replica and every build
+pub fn job_lock_key(job: &str) -> i64 {
+    let digest = Sha256::digest(format!("hive_job:{job}").as_bytes());
+    let mut key = [0u8; 8];
+    key.copy_from_slice(&digest[..8]);
+    i64::from_be_bytes(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_keys_are_stable() {
+        let node_id = Uuid::from_u128(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210);
+        assert_eq!(node_lock_key(node_id), node_lock_key(node_id));
+        assert_eq!(
+            node_lock_key(node_id),
+            (0x0123_4567_89ab_cdef_u64 ^ 0xfedc_ba98_7654_3210_u64) as i64
+        );
+
+        assert_eq!(
+            job_lock_key("backfill_reconciliation"),
+            job_lock_key("backfill_reconciliation")
+        );
+        assert_ne!(
+            job_lock_key("backfill_reconciliation"),
+            job_lock_key("approval_escalation")
+        );
+    }
+
+    #[test]
+    fn test_routed_payload_round_trips() {
+        let payload = RoutedPayload::Deliver(Box::new(HiveMessage::Close {
+            reason: "bye".to_string(),
+        }));
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["kind"], "deliver");
+        assert!(matches!(
+            serde_json::from_value(json).unwrap(),
+            RoutedPayload::Deliver(message) if matches!(*message, HiveMessage::Close { .. })
+        ));
+
+        let json = serde_json::to_value(RoutedPayload::Evict).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "evict" }));
+    }
+}
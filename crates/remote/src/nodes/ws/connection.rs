@@ -23,15 +23,31 @@
 //! is the regression fence: a new task-state-push variant breaks that test's exhaustive match or its
 //! assertion. If you genuinely need cross-node task fan-out, that is a spec change (escalate), not a
 //! new send call here.
+//!
+//! # Multiple replicas
+//!
+//! With a [`HiveCluster`] attached, a node's WebSocket lives on exactly one replica. The send
+//! primitives deliver to the nodes connected here and route the rest, by node, to the replica
+//! that holds them; lookups fall back to the sessions the other replicas recorded.
 
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use chrono::{DateTime, Utc};
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::{Notify, RwLock, mpsc};
 use uuid::Uuid;
 
-use super::message::HiveMessage;
-use crate::nodes::domain::NodeStatus;
+use super::{
+    cluster::{HiveCluster, RoutedPayload},
+    message::HiveMessage,
+};
+use crate::{
+    db::node_sessions::{NodeSession, NodeSessionRepository},
+    nodes::domain::NodeStatus,
+};
+
+/// How long a node waits for the replica it was connected to to let go of it
+const CLAIM_TIMEOUT: Duration = Duration::from_secs(5);
+const CLAIM_POLL_INTERVAL: Duration = Duration::from_millis(250);
 
 /// Handle for sending messages to a connected node.
 #[derive(Debug, Clone)]
@@ -48,12 +64,25 @@ pub struct NodeConnection {
     pub status: NodeStatus,
     /// Number of active tasks
     pub active_tasks: u32,
+    /// Tells apart this connection from a later one of the same node
+    pub connection_id: Uuid,
+    /// Wakes the session when the node connects again elsewhere
+    pub evicted: Arc<Notify>,
+}
+
+/// A registered session's side of its connection.
+#[derive(Debug)]
+pub struct ConnectionHandle {
+    pub connection_id: Uuid,
+    /// Notified when the session must close because the node connected again
+    pub evicted: Arc<Notify>,
 }
 
 /// Manager for all connected nodes.
-#[derive(Debug, Clone)]
+#[derive(Clone, Default)]
 pub struct ConnectionManager {
     inner: Arc<RwLock<ConnectionManagerInner>>,
+    cluster: Option<Arc<HiveCluster>>,
 }
 
 #[derive(Debug, Default)]
@@ -64,27 +93,43 @@ struct ConnectionManagerInner {
     org_nodes: HashMap<Uuid, Vec<Uuid>>,
 }
 
-impl Default for ConnectionManager {
-    fn default() -> Self {
-        Self::new()
+impl std::fmt::Debug for ConnectionManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionManager")
+            .field("replica_id", &self.cluster.as_ref().map(|c| c.replica_id()))
+            .finish_non_exhaustive()
     }
 }
 
 impl ConnectionManager {
     /// Create a new connection manager.
     pub fn new() -> Self {
-        Self {
-            inner: Arc::new(RwLock::new(ConnectionManagerInner::default())),
-        }
+        Self::default()
+    }
+
+    /// Share node connections with the other replicas of the cluster.
+    pub fn with_cluster(mut self, cluster: Arc<HiveCluster>) -> Self {
+        self.cluster = Some(cluster);
+        self
+    }
+
+    pub fn cluster(&self) -> Option<&Arc<HiveCluster>> {
+        self.cluster.as_ref()
     }
 
     /// Register a new node connection.
+    ///
+    /// A previous connection of the node is evicted, whether on this replica or another one.
     pub async fn register(
         &self,
         node_id: Uuid,
         organization_id: Uuid,
         sender: mpsc::Sender<HiveMessage>,
-    ) {
+    ) -> Result<ConnectionHandle, RegisterError> {
+        if let Some(cluster) = &self.cluster {
+            claim_node(cluster, node_id, organization_id).await?;
+        }
+
         let mut inner = self.inner.write().await;
 
         let connection = NodeConnection {
@@ -94,9 +139,20 @@ impl ConnectionManager {
             connected_at: Utc::now(),
             status: NodeStatus::Online,
             active_tasks: 0,
+            connection_id: Uuid::new_v4(),
+            evicted: Arc::new(Notify::new()),
+        };
+        let registration = ConnectionHandle {
+            connection_id: connection.connection_id,
+            evicted: Arc::clone(&connection.evicted),
         };
 
-        inner.connections.insert(node_id, connection);
+        if let Some(previous) = inner.connections.insert(node_id, connection) {
+            previous.evicted.notify_one();
+            if let Some(nodes) = inner.org_nodes.get_mut(&previous.organization_id) {
+                nodes.retain(|id| *id != node_id);
+            }
+        }
         inner
             .org_nodes
             .entry(organization_id)
@@ -108,69 +164,153 @@ impl ConnectionManager {
             organization_id = %organization_id,
             "node connected"
         );
+
+        Ok(registration)
     }
 
     /// Unregister a node connection.
-    pub async fn unregister(&self, node_id: Uuid) {
-        let mut inner = self.inner.write().await;
-
-        if let Some(conn) = inner.connections.remove(&node_id) {
-            if let Some(nodes) = inner.org_nodes.get_mut(&conn.organization_id) {
-                nodes.retain(|id| *id != node_id);
-                if nodes.is_empty() {
-                    inner.org_nodes.remove(&conn.organization_id);
+    ///
+    /// Returns `false` when the node has connected again since, and the newer connection
+    /// stays registered.
+    pub async fn unregister(&self, node_id: Uuid, connection_id: Uuid) -> bool {
+        let removed = {
+            let mut inner = self.inner.write().await;
+
+            let current = inner
+                .connections
+                .get(&node_id)
+                .is_some_and(|conn| conn.connection_id == connection_id);
+            let conn = if current {
+                inner.connections.remove(&node_id)
+            } else {
+                None
+            };
+
+            if let Some(conn) = &conn {
+                if let Some(nodes) = inner.org_nodes.get_mut(&conn.organization_id) {
+                    nodes.retain(|id| *id != node_id);
+                    if nodes.is_empty() {
+                        inner.org_nodes.remove(&conn.organization_id);
+                    }
                 }
+
+                tracing::info!(
+                    node_id = %node_id,
+                    organization_id = %conn.organization_id,
+                    "node disconnected"
+                );
             }
+            conn.is_some()
+        };
 
-            tracing::info!(
-                node_id = %node_id,
-                organization_id = %conn.organization_id,
-                "node disconnected"
-            );
+        // Every registration holds the node once
+        if let Some(cluster) = &self.cluster {
+            cluster.release_node(node_id).await;
         }
+
+        removed
     }
 
-    /// Update a node's status.
-    pub async fn update_status(&self, node_id: Uuid, status: NodeStatus, active_tasks: u32) {
-        let mut inner = self.inner.write().await;
+    /// Close the sessions of nodes connected to this replica, e.g. because they connected
+    /// to another one.
+    pub async fn evict_local(&self, node_ids: &[Uuid]) {
+        let inner = self.inner.read().await;
 
-        if let Some(conn) = inner.connections.get_mut(&node_id) {
+        for node_id in node_ids {
+            if let Some(conn) = inner.connections.get(node_id) {
+                tracing::info!(node_id = %node_id, "evicting node session");
+                conn.evicted.notify_one();
+            }
+        }
+    }
+
+    /// Carry out a payload another replica routed to nodes connected here.
+    pub async fn deliver_routed(&self, node_ids: &[Uuid], payload: RoutedPayload) {
+        match payload {
+            RoutedPayload::Deliver(message) => {
+                let failed = self.send_local(node_ids, &message).await;
+                if !failed.is_empty() {
+                    tracing::warn!(?failed, "failed to deliver routed message");
+                }
+            }
+            RoutedPayload::Evict => self.evict_local(node_ids).await,
+        }
+    }
+
+    /// Update a node's status. Returns whether its status or load changed.
+    pub async fn update_status(
+        &self,
+        node_id: Uuid,
+        status: NodeStatus,
+        active_tasks: u32,
+    ) -> bool {
+        {
+            let mut inner = self.inner.write().await;
+
+            let Some(conn) = inner.connections.get_mut(&node_id) else {
+                return false;
+            };
+            if conn.status == status && conn.active_tasks == active_tasks {
+                return false;
+            }
             conn.status = status;
             conn.active_tasks = active_tasks;
         }
+
+        // Other replicas dispatch on this too
+        if let Some(cluster) = &self.cluster
+            && let Err(error) = NodeSessionRepository::new(cluster.pool())
+                .update_load(
+                    node_id,
+                    cluster.replica_id(),
+                    status,
+                    i32::try_from(active_tasks).unwrap_or(i32::MAX),
+                )
+                .await
+        {
+            tracing::warn!(?error, %node_id, "failed to record node load");
+        }
+        true
     }
 
     /// Send a message to a specific node.
     pub async fn send_to_node(&self, node_id: Uuid, message: HiveMessage) -> Result<(), SendError> {
-        let inner = self.inner.read().await;
-
-        let conn = inner
-            .connections
-            .get(&node_id)
-            .ok_or(SendError::NotConnected)?;
+        {
+            let inner = self.inner.read().await;
+
+            if let Some(conn) = inner.connections.get(&node_id) {
+                return conn
+                    .sender
+                    .send(message)
+                    .await
+                    .map_err(|_| SendError::ChannelClosed);
+            }
+        }
 
-        conn.sender
-            .send(message)
+        let Some(cluster) = &self.cluster else {
+            return Err(SendError::NotConnected);
+        };
+        let session = NodeSessionRepository::new(cluster.pool())
+            .find_live(node_id)
             .await
-            .map_err(|_| SendError::ChannelClosed)
+            .map_err(|error| SendError::Routing(error.to_string()))?;
+        match session {
+            Some(session) if session.replica_id != cluster.replica_id() => cluster
+                .route(
+                    session.replica_id,
+                    &[node_id],
+                    &RoutedPayload::Deliver(Box::new(message)),
+                )
+                .await
+                .map_err(|error| SendError::Routing(error.to_string())),
+            _ => Err(SendError::NotConnected),
+        }
     }
 
     /// Send a message to all nodes in an organization.
     pub async fn broadcast_to_org(&self, organization_id: Uuid, message: HiveMessage) -> Vec<Uuid> {
-        let inner = self.inner.read().await;
-        let mut failed = Vec::new();
-
-        if let Some(node_ids) = inner.org_nodes.get(&organization_id) {
-            for node_id in node_ids {
-                if let Some(conn) = inner.connections.get(node_id)
-                    && conn.sender.send(message.clone()).await.is_err()
-                {
-                    failed.push(*node_id);
-                }
-            }
-        }
-
-        failed
+        self.broadcast_to_org_filtered(organization_id, None, message)
+            .await
     }
 
     /// Send a message to all nodes in an organization except one.
@@ -183,19 +323,43 @@ impl ConnectionManager {
         except_node_id: Uuid,
         message: HiveMessage,
     ) -> Vec<Uuid> {
-        let inner = self.inner.read().await;
-        let mut failed = Vec::new();
+        self.broadcast_to_org_filtered(organization_id, Some(except_node_id), message)
+            .await
+    }
 
-        if let Some(node_ids) = inner.org_nodes.get(&organization_id) {
-            for node_id in node_ids {
+    async fn broadcast_to_org_filtered(
+        &self,
+        organization_id: Uuid,
+        except_node_id: Option<Uuid>,
+        message: HiveMessage,
+    ) -> Vec<Uuid> {
+        let local: Vec<Uuid> = {
+            let inner = self.inner.read().await;
+            inner
+                .org_nodes
+                .get(&organization_id)
+                .into_iter()
+                .flatten()
                 // Skip the excluded node
-                if *node_id == except_node_id {
-                    continue;
+                .filter(|node_id| Some(**node_id) != except_node_id)
+                .copied()
+                .collect()
+        };
+        let mut failed = self.send_local(&local, &message).await;
+
+        if let Some(cluster) = &self.cluster {
+            match NodeSessionRepository::new(cluster.pool())
+                .list_live_for_organization(organization_id)
+                .await
+            {
+                Ok(sessions) => {
+                    let remote = sessions
+                        .into_iter()
+                        .filter(|session| Some(session.node_id) != except_node_id);
+                    failed.extend(route_to_replicas(cluster, remote, &message).await);
                 }
-                if let Some(conn) = inner.connections.get(node_id)
-                    && conn.sender.send(message.clone()).await.is_err()
-                {
-                    failed.push(*node_id);
+                Err(error) => {
+                    tracing::warn!(?error, %organization_id, "failed to look up remote nodes");
                 }
             }
         }
@@ -208,6 +372,39 @@ impl ConnectionManager {
     /// This is useful for targeted broadcasts (e.g., only to nodes linked to a project).
     /// Returns a list of node IDs that failed to receive the message.
     pub async fn send_to_nodes(&self, node_ids: &[Uuid], message: HiveMessage) -> Vec<Uuid> {
+        let remote: Vec<Uuid> = {
+            let inner = self.inner.read().await;
+            node_ids
+                .iter()
+                .filter(|node_id| !inner.connections.contains_key(node_id))
+                .copied()
+                .collect()
+        };
+        // Note: We don't add to failed if node isn't connected - that's expected
+        let mut failed = self.send_local(node_ids, &message).await;
+
+        if let Some(cluster) = &self.cluster
+            && !remote.is_empty()
+        {
+            match NodeSessionRepository::new(cluster.pool())
+                .list_live(&remote)
+                .await
+            {
+                Ok(sessions) => {
+                    failed.extend(route_to_replicas(cluster, sessions, &message).await);
+                }
+                Err(error) => {
+                    tracing::warn!(?error, "failed to look up remote nodes");
+                    failed.extend(remote);
+                }
+            }
+        }
+
+        failed
+    }
+
+    /// Send to those of `node_ids` connected to this replica; returns the ones that failed.
+    async fn send_local(&self, node_ids: &[Uuid], message: &HiveMessage) -> Vec<Uuid> {
         let inner = self.inner.read().await;
         let mut failed = Vec::new();
 
@@ -217,7 +414,6 @@ impl ConnectionManager {
             {
                 failed.push(*node_id);
             }
-            // Note: We don't add to failed if node isn't connected - that's expected
         }
 
         failed
@@ -225,76 +421,146 @@ impl ConnectionManager {
 
     /// Get a node's connection info.
     pub async fn get_connection(&self, node_id: Uuid) -> Option<NodeConnectionInfo> {
-        let inner = self.inner.read().await;
+        {
+            let inner = self.inner.read().await;
 
-        inner
-            .connections
-            .get(&node_id)
-            .map(|conn| NodeConnectionInfo {
-                node_id: conn.node_id,
-                organization_id: conn.organization_id,
-                connected_at: conn.connected_at,
-                status: conn.status,
-                active_tasks: conn.active_tasks,
-            })
+            if let Some(conn) = inner.connections.get(&node_id) {
+                return Some(NodeConnectionInfo::from(conn));
+            }
+        }
+
+        let cluster = self.cluster.as_ref()?;
+        match NodeSessionRepository::new(cluster.pool())
+            .find_live(node_id)
+            .await
+        {
+            Ok(session) => session
+                .filter(|session| session.replica_id != cluster.replica_id())
+                .map(NodeConnectionInfo::from),
+            Err(error) => {
+                tracing::warn!(?error, %node_id, "failed to look up node session");
+                None
+            }
+        }
     }
 
     /// Get all connected nodes for an organization.
     pub async fn get_org_nodes(&self, organization_id: Uuid) -> Vec<NodeConnectionInfo> {
-        let inner = self.inner.read().await;
+        let mut nodes: Vec<NodeConnectionInfo> = {
+            let inner = self.inner.read().await;
+
+            inner
+                .org_nodes
+                .get(&organization_id)
+                .map(|node_ids| {
+                    node_ids
+                        .iter()
+                        .filter_map(|id| inner.connections.get(id).map(NodeConnectionInfo::from))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
 
-        inner
-            .org_nodes
-            .get(&organization_id)
-            .map(|node_ids| {
-                node_ids
-                    .iter()
-                    .filter_map(|id| {
-                        inner.connections.get(id).map(|conn| NodeConnectionInfo {
-                            node_id: conn.node_id,
-                            organization_id: conn.organization_id,
-                            connected_at: conn.connected_at,
-                            status: conn.status,
-                            active_tasks: conn.active_tasks,
-                        })
-                    })
-                    .collect()
-            })
-            .unwrap_or_default()
+        if let Some(cluster) = &self.cluster {
+            match NodeSessionRepository::new(cluster.pool())
+                .list_live_for_organization(organization_id)
+                .await
+            {
+                Ok(sessions) => nodes.extend(
+                    sessions
+                        .into_iter()
+                        .filter(|session| session.replica_id != cluster.replica_id())
+                        .map(NodeConnectionInfo::from),
+                ),
+                Err(error) => {
+                    tracing::warn!(?error, %organization_id, "failed to look up remote nodes");
+                }
+            }
+        }
+
+        nodes
     }
 
-    /// Check if a node is connected.
+    /// Check if a node is connected, to this replica or another one.
     pub async fn is_connected(&self, node_id: Uuid) -> bool {
+        self.get_connection(node_id).await.is_some()
+    }
+
+    /// Check if a node is connected to this replica.
+    pub async fn is_local(&self, node_id: Uuid) -> bool {
         let inner = self.inner.read().await;
         inner.connections.contains_key(&node_id)
     }
 
-    /// Get the total number of connected nodes.
+    /// Get the number of nodes connected to this replica.
     pub async fn connection_count(&self) -> usize {
         let inner = self.inner.read().await;
         inner.connections.len()
     }
+}
 
-    /// Find an available node for task execution in an organization.
-    /// Returns the node with the lowest active task count.
-    pub async fn find_available_node(&self, organization_id: Uuid) -> Option<NodeConnectionInfo> {
-        let inner = self.inner.read().await;
+/// Take the node's lock for this replica, asking the replica holding it to let go first.
+async fn claim_node(
+    cluster: &HiveCluster,
+    node_id: Uuid,
+    organization_id: Uuid,
+) -> Result<(), RegisterError> {
+    if cluster.acquire_node(node_id, organization_id).await? {
+        return Ok(());
+    }
 
-        inner
-            .org_nodes
-            .get(&organization_id)?
-            .iter()
-            .filter_map(|id| inner.connections.get(id))
-            .filter(|conn| matches!(conn.status, NodeStatus::Online))
-            .min_by_key(|conn| conn.active_tasks)
-            .map(|conn| NodeConnectionInfo {
-                node_id: conn.node_id,
-                organization_id: conn.organization_id,
-                connected_at: conn.connected_at,
-                status: conn.status,
-                active_tasks: conn.active_tasks,
-            })
+    if let Some(session) = NodeSessionRepository::new(cluster.pool())
+        .find_live(node_id)
+        .await?
+    {
+        tracing::info!(
+            node_id = %node_id,
+            replica_id = %session.replica_id,
+            "node reconnected; evicting its session on another replica"
+        );
+        cluster
+            .route(session.replica_id, &[node_id], &RoutedPayload::Evict)
+            .await?;
+    }
+
+    let deadline = tokio::time::Instant::now() + CLAIM_TIMEOUT;
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(CLAIM_POLL_INTERVAL).await;
+        if cluster.acquire_node(node_id, organization_id).await? {
+            return Ok(());
+        }
+    }
+
+    Err(RegisterError::HeldElsewhere)
+}
+
+/// Route a message to other replicas' nodes, one routed message per replica. Returns the
+/// nodes it could not be routed to.
+async fn route_to_replicas(
+    cluster: &HiveCluster,
+    sessions: impl IntoIterator<Item = NodeSession>,
+    message: &HiveMessage,
+) -> Vec<Uuid> {
+    let mut by_replica: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for session in sessions {
+        if session.replica_id != cluster.replica_id() {
+            by_replica
+                .entry(session.replica_id)
+                .or_default()
+                .push(session.node_id);
+        }
+    }
+
+    let payload = RoutedPayload::Deliver(Box::new(message.clone()));
+    let mut failed = Vec::new();
+    for (replica_id, node_ids) in by_replica {
+        if let Err(error) = cluster.route(replica_id, &node_ids, &payload).await {
+            tracing::warn!(?error, %replica_id, "failed to route message to replica");
+            failed.extend(node_ids);
+        }
     }
+
+    failed
 }
 
 /// Public connection info (without the sender channel).
@@ -307,6 +573,30 @@ pub struct NodeConnectionInfo {
     pub active_tasks: u32,
 }
 
+impl From<&NodeConnection> for NodeConnectionInfo {
+    fn from(conn: &NodeConnection) -> Self {
+        Self {
+            node_id: conn.node_id,
+            organization_id: conn.organization_id,
+            connected_at: conn.connected_at,
+            status: conn.status,
+            active_tasks: conn.active_tasks,
+        }
+    }
+}
+
+impl From<NodeSession> for NodeConnectionInfo {
+    fn from(session: NodeSession) -> Self {
+        Self {
+            node_id: session.node_id,
+            organization_id: session.organization_id,
+            connected_at: session.connected_at,
+            status: session.status,
+            active_tasks: u32::try_from(session.active_tasks).unwrap_or(0),
+        }
+    }
+}
+
 /// Error when sending to a node.
 #[derive(Debug, thiserror::Error)]
 pub enum SendError {
@@ -314,4 +604,87 @@ pub enum SendError {
     NotConnected,
     #[error("channel closed")]
     ChannelClosed,
+    #[error("failed to route to the node's replica: {0}")]
+    Routing(String),
+}
+
+/// Error when registering a node connection.
+#[derive(Debug, thiserror::Error)]
+pub enum RegisterError {
+    #[error("node is still connected to another Hive replica")]
+    HeldElsewhere,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reconnect_evicts_the_previous_session() {
+        let connections = ConnectionManager::new();
+        let node_id = Uuid::new_v4();
+        let organization_id = Uuid::new_v4();
+
+        let (old_sender, mut old_receiver) = mpsc::channel(4);
+        let old = connections
+            .register(node_id, organization_id, old_sender)
+            .await
+            .unwrap();
+        let (new_sender, mut new_receiver) = mpsc::channel(4);
+        let new = connections
+            .register(node_id, organization_id, new_sender)
+            .await
+            .unwrap();
+        assert_ne!(old.connection_id, new.connection_id);
+
+        tokio::time::timeout(Duration::from_secs(1), old.evicted.notified())
+            .await
+            .expect("previous session was not evicted");
+        assert_eq!(connections.connection_count().await, 1);
+        assert_eq!(connections.get_org_nodes(organization_id).await.len(), 1);
+
+        connections
+            .send_to_node(
+                node_id,
+                HiveMessage::Close {
+                    reason: "bye".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            new_receiver.try_recv(),
+            Ok(HiveMessage::Close { .. })
+        ));
+        assert!(old_receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stale_unregister_keeps_the_newer_connection() {
+        let connections = ConnectionManager::new();
+        let node_id = Uuid::new_v4();
+        let organization_id = Uuid::new_v4();
+
+        let (sender, _old_receiver) = mpsc::channel(4);
+        let old = connections
+            .register(node_id, organization_id, sender)
+            .await
+            .unwrap();
+        let (sender, _new_receiver) = mpsc::channel(4);
+        let new = connections
+            .register(node_id, organization_id, sender)
+            .await
+            .unwrap();
+
+        // The old session closes after the node reconnected
+        assert!(!connections.unregister(node_id, old.connection_id).await);
+        assert!(connections.is_local(node_id).await);
+        assert_eq!(connections.get_org_nodes(organization_id).await.len(), 1);
+
+        assert!(connections.unregister(node_id, new.connection_id).await);
+        assert!(!connections.is_connected(node_id).await);
+        assert!(connections.get_org_nodes(organization_id).await.is_empty());
+    }
 }
@@ -1,7 +1,11 @@
 //! Task dispatcher for streaming task assignments to nodes.
 //!
-//! This module handles the assignment and dispatch of tasks to connected nodes.
+//! This module handles the assignment and dispatch of tasks to connected nodes, including
+//! choosing which node runs a task when the caller leaves that to the Hive.
 
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -9,8 +13,16 @@ use super::{
     connection::ConnectionManager,
     message::{HiveMessage, TaskAssignMessage, TaskDetails},
 };
-use crate::db::swarm_projects::{SwarmProjectNodeForDispatch, SwarmProjectRepository};
-use crate::nodes::service::{NodeError, NodeServiceImpl};
+use crate::{
+    db::{
+        assignment_hops::AssignmentHopRepository,
+        nodes::NodeRepository,
+        swarm_projects::{SwarmProjectNodeForDispatch, SwarmProjectRepository},
+        task_assignments::TaskAssignmentRepository,
+        tasks::SharedTaskRepository,
+    },
+    nodes::{NodeCapabilities, NodeError, NodeServiceImpl, NodeStatus},
+};
 
 /// Dispatcher for sending tasks to nodes.
 #[derive(Clone)]
@@ -198,83 +210,307 @@ impl TaskDispatcher {
         Ok(())
     }
 
-    /// Finds an available node in the given organization, creates an assignment for `task_id` using the node's first swarm project link, and dispatches the task to that node.
-    ///
-    /// On success returns an `AssignResult` containing the created assignment ID and the node ID. On failure returns an appropriate `DispatchError`.
-    ///
-    /// # Examples
+    /// Dispatches a Hive task to the best node that can run it.
     ///
-    /// ```rust,ignore
-    /// # use uuid::Uuid;
-    /// # use crate::nodes::ws::dispatcher::TaskDispatcher;
-    /// # use crate::nodes::ws::dispatcher::AssignResult;
-    /// # async fn example(dispatcher: &TaskDispatcher) {
-    /// let task_id = Uuid::new_v4();
-    /// let organization_id = Uuid::new_v4();
-    /// let task_details = /* TaskDetails value */;
-    /// let res = dispatcher.dispatch_to_available_node(task_id, organization_id, task_details).await;
-    /// assert!(res.is_ok());
-    /// # }
-    /// ```
-    pub async fn dispatch_to_available_node(
+    /// Candidates are the nodes linked to the task's swarm project. [`select_node`] drops the
+    /// ones that are offline, lack something `requirements` asks for, or are at capacity, and
+    /// picks the least loaded of the rest. Tasks that came from a node stay with that node and
+    /// are never dispatched elsewhere (SC1).
+    pub async fn dispatch_task(
         &self,
         task_id: Uuid,
-        organization_id: Uuid,
-        task_details: TaskDetails,
-    ) -> Result<AssignResult, DispatchError> {
-        // Find an available node in the organization
-        let node_info = self
-            .connections
-            .find_available_node(organization_id)
+        requirements: &DispatchRequirements,
+    ) -> Result<DispatchResult, DispatchError> {
+        let task = SharedTaskRepository::new(&self.pool)
+            .find_by_id(task_id)
             .await
-            .ok_or(DispatchError::NoAvailableNode)?;
+            .map_err(|e| DispatchError::NodeService(NodeError::Database(e.to_string())))?
+            .ok_or(DispatchError::TaskNotFound)?;
 
-        let service = NodeServiceImpl::new(self.pool.clone());
+        if task.source_node_id.is_some() {
+            return Err(DispatchError::TaskOwnedByNode);
+        }
+        let swarm_project_id = task
+            .swarm_project_id
+            .or(task.project_id)
+            .ok_or(DispatchError::NoNodeForProject)?;
+
+        let candidates = self.dispatch_candidates(swarm_project_id).await?;
+        if candidates.is_empty() {
+            return Err(DispatchError::NoNodeForProject);
+        }
+        let node = select_node(&candidates, requirements).map_err(DispatchError::NoEligibleNode)?;
+
+        let task_details = TaskDetails {
+            title: task.title,
+            description: task.description,
+            executor: requirements.executor.clone(),
+            executor_variant: requirements.executor_variant.clone(),
+            base_branch: node.link.default_branch.clone(),
+        };
+        let result = self
+            .assign_task_to_node(task_id, &node.link, task_details)
+            .await?;
+        if let Err(e) = AssignmentHopRepository::new(&self.pool)
+            .record_dispatch(task_id, result.assignment_id, result.node_id, requirements)
+            .await
+        {
+            tracing::warn!(
+                task_id = %task_id,
+                assignment_id = %result.assignment_id,
+                error = ?e,
+                "failed to record assignment hop"
+            );
+        }
+
+        tracing::info!(
+            task_id = %task_id,
+            node_id = %result.node_id,
+            assignment_id = %result.assignment_id,
+            swarm_project_id = %swarm_project_id,
+            executor = %requirements.executor,
+            active_tasks = node.active_tasks,
+            "task dispatched to eligible node"
+        );
+
+        Ok(DispatchResult {
+            assignment_id: result.assignment_id,
+            node_id: result.node_id,
+            node_name: node.link.node_name.clone(),
+        })
+    }
 
-        // Get the node's swarm project links to find a suitable one
-        let projects = SwarmProjectRepository::list_by_node(&self.pool, node_info.node_id)
+    /// Nodes linked to a swarm project, with their capabilities, live status and load.
+    async fn dispatch_candidates(
+        &self,
+        swarm_project_id: Uuid,
+    ) -> Result<Vec<DispatchCandidate>, DispatchError> {
+        let links = SwarmProjectRepository::find_nodes_for_dispatch(&self.pool, swarm_project_id)
             .await
             .map_err(|e| DispatchError::NodeService(e.into()))?;
 
-        if projects.is_empty() {
-            return Err(DispatchError::NoProjectOnNode);
+        let nodes = NodeRepository::new(&self.pool);
+        let assignments = TaskAssignmentRepository::new(&self.pool);
+        let mut candidates = Vec::with_capacity(links.len());
+        for link in links {
+            let Some(node) = nodes
+                .find_by_id(link.node_id)
+                .await
+                .map_err(NodeError::from)?
+            else {
+                continue;
+            };
+            let connection = self.connections.get_connection(link.node_id).await;
+            let assigned = assignments
+                .list_active_by_node(link.node_id)
+                .await
+                .map_err(NodeError::from)?
+                .len() as u32;
+
+            candidates.push(DispatchCandidate {
+                status: connection.as_ref().map(|c| c.status),
+                // Heartbeats also count work the Hive did not assign, such as local attempts
+                active_tasks: assigned.max(connection.map_or(0, |c| c.active_tasks)),
+                capabilities: node.capabilities,
+                link,
+            });
         }
+        Ok(candidates)
+    }
+}
 
-        // Use the first project (in production, we'd match based on requirements)
-        let project_link = &projects[0];
+/// What a task needs from the node that runs it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DispatchRequirements {
+    /// Executor the task runs with (e.g. "CLAUDE_CODE"); the node must have it installed
+    pub executor: String,
+    #[serde(default)]
+    pub executor_variant: Option<String>,
+    /// Operating system the node must run (e.g. "linux")
+    #[serde(default)]
+    pub os: Option<String>,
+    /// CPU architecture the node must have (e.g. "arm64")
+    #[serde(default)]
+    pub arch: Option<String>,
+    /// Oldest Vibe Kanban version the node may run (e.g. "0.0.140")
+    #[serde(default)]
+    pub min_version: Option<String>,
+    /// Labels the node must carry, all of them
+    #[serde(default)]
+    pub node_labels: Vec<String>,
+    /// Nodes that may not take the task, such as ones whose lease on it expired
+    #[serde(default)]
+    pub exclude_node_ids: Vec<Uuid>,
+}
 
-        // Create the assignment (use swarm_project_nodes link_id as node_project_id)
-        let assignment = service
-            .assign_task(task_id, node_info.node_id, project_link.id)
-            .await?;
+/// A node linked to the task's swarm project, as seen by the scheduler.
+#[derive(Debug, Clone)]
+pub struct DispatchCandidate {
+    pub link: SwarmProjectNodeForDispatch,
+    /// Live status, or `None` when the node is not connected to the Hive
+    pub status: Option<NodeStatus>,
+    pub capabilities: NodeCapabilities,
+    /// Tasks the node is working on or has been assigned
+    pub active_tasks: u32,
+}
 
-        // Build the assign message
-        let message = HiveMessage::TaskAssign(TaskAssignMessage {
-            message_id: Uuid::new_v4(),
-            assignment_id: assignment.id,
-            task_id,
-            node_project_id: project_link.id,
-            local_project_id: project_link.local_project_id,
-            task: task_details,
-        });
+impl DispatchCandidate {
+    fn max_tasks(&self) -> u32 {
+        self.capabilities.max_concurrent_tasks.max(1) as u32
+    }
 
-        // Send to the node
-        self.connections
-            .send_to_node(node_info.node_id, message)
-            .await
-            .map_err(|e| DispatchError::SendFailed(e.to_string()))?;
+    fn check(&self, requirements: &DispatchRequirements) -> Result<(), Ineligibility> {
+        let caps = &self.capabilities;
+        if requirements.exclude_node_ids.contains(&self.link.node_id) {
+            return Err(Ineligibility::Excluded);
+        }
+        match self.status {
+            None => return Err(Ineligibility::NotConnected),
+            Some(NodeStatus::Online | NodeStatus::Busy) => {}
+            Some(status) => return Err(Ineligibility::Unavailable(status)),
+        }
+        if !caps
+            .executors
+            .iter()
+            .any(|executor| executor.eq_ignore_ascii_case(&requirements.executor))
+        {
+            return Err(Ineligibility::MissingExecutor(
+                requirements.executor.clone(),
+            ));
+        }
+        if let Some(os) = &requirements.os
+            && !caps.os.eq_ignore_ascii_case(os)
+        {
+            return Err(Ineligibility::OsMismatch(caps.os.clone()));
+        }
+        if let Some(arch) = &requirements.arch
+            && !caps.arch.eq_ignore_ascii_case(arch)
+        {
+            return Err(Ineligibility::ArchMismatch(caps.arch.clone()));
+        }
+        if let Some(min_version) = &requirements.min_version
+            && !version_at_least(&caps.version, min_version)
+        {
+            return Err(Ineligibility::VersionTooOld(caps.version.clone()));
+        }
+        let missing: Vec<String> = requirements
+            .node_labels
+            .iter()
+            .filter(|label| !caps.labels.contains(label))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            return Err(Ineligibility::MissingLabels(missing));
+        }
+        if self.active_tasks >= self.max_tasks() {
+            return Err(Ineligibility::AtCapacity {
+                active: self.active_tasks,
+                max: self.max_tasks(),
+            });
+        }
+        Ok(())
+    }
+}
 
-        tracing::info!(
-            task_id = %task_id,
-            node_id = %node_info.node_id,
-            assignment_id = %assignment.id,
-            "task dispatched to available node"
-        );
+/// Why a node cannot take a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ineligibility {
+    /// The task may not go back to this node
+    Excluded,
+    NotConnected,
+    /// Connected but pending or draining
+    Unavailable(NodeStatus),
+    MissingExecutor(String),
+    /// The node runs a different operating system, the one given
+    OsMismatch(String),
+    /// The node has a different architecture, the one given
+    ArchMismatch(String),
+    /// The node runs an older version, the one given
+    VersionTooOld(String),
+    MissingLabels(Vec<String>),
+    AtCapacity {
+        active: u32,
+        max: u32,
+    },
+}
 
-        Ok(AssignResult {
-            assignment_id: assignment.id,
-            node_id: node_info.node_id,
-        })
+impl fmt::Display for Ineligibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ineligibility::Excluded => write!(f, "excluded"),
+            Ineligibility::NotConnected => write!(f, "not connected"),
+            Ineligibility::Unavailable(status) => write!(f, "{status}"),
+            Ineligibility::MissingExecutor(executor) => write!(f, "{executor} not installed"),
+            Ineligibility::OsMismatch(os) => write!(f, "runs {}", or_unknown(os)),
+            Ineligibility::ArchMismatch(arch) => write!(f, "is {}", or_unknown(arch)),
+            Ineligibility::VersionTooOld(version) => {
+                write!(f, "version {} is too old", or_unknown(version))
+            }
+            Ineligibility::MissingLabels(labels) => {
+                write!(f, "missing labels {}", labels.join(", "))
+            }
+            Ineligibility::AtCapacity { active, max } => write!(f, "at capacity ({active}/{max})"),
+        }
+    }
+}
+
+fn or_unknown(value: &str) -> &str {
+    if value.is_empty() { "unknown" } else { value }
+}
+
+/// A node the scheduler passed over, and why.
+#[derive(Debug, Clone)]
+pub struct NodeRejection {
+    pub node_id: Uuid,
+    pub node_name: String,
+    pub reason: Ineligibility,
+}
+
+/// Picks the node to run a task, or explains why every candidate was passed over.
+///
+/// Eligible nodes are ranked by the share of their `max_concurrent_tasks` in use; ties go to the
+/// node linked to the project first.
+pub fn select_node<'a>(
+    candidates: &'a [DispatchCandidate],
+    requirements: &DispatchRequirements,
+) -> Result<&'a DispatchCandidate, Vec<NodeRejection>> {
+    let mut rejections = Vec::new();
+    let mut best: Option<&DispatchCandidate> = None;
+    for candidate in candidates {
+        if let Err(reason) = candidate.check(requirements) {
+            rejections.push(NodeRejection {
+                node_id: candidate.link.node_id,
+                node_name: candidate.link.node_name.clone(),
+                reason,
+            });
+            continue;
+        }
+        let less_loaded = best.is_none_or(|best| {
+            u64::from(candidate.active_tasks) * u64::from(best.max_tasks())
+                < u64::from(best.active_tasks) * u64::from(candidate.max_tasks())
+        });
+        if less_loaded {
+            best = Some(candidate);
+        }
+    }
+    best.ok_or(rejections)
+}
+
+/// Compares dotted version numbers, ignoring a leading `v` and any pre-release or build suffix.
+/// A version that does not parse is never new enough.
+fn version_at_least(version: &str, minimum: &str) -> bool {
+    fn parse(version: &str) -> Option<Vec<u64>> {
+        let version = version.trim().trim_start_matches('v');
+        let core = version.split(['-', '+']).next()?;
+        core.split('.').map(|part| part.parse().ok()).collect()
+    }
+    match (parse(version), parse(minimum)) {
+        (Some(version), Some(minimum)) => {
+            let len = version.len().max(minimum.len());
+            let pad = |v: Vec<u64>| v.into_iter().chain(std::iter::repeat(0)).take(len);
+            pad(version).cmp(pad(minimum)).is_ge()
+        }
+        _ => false,
     }
 }
 
@@ -287,6 +523,14 @@ pub struct AssignResult {
     pub node_id: Uuid,
 }
 
+/// Result of a successful dispatch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DispatchResult {
+    pub assignment_id: Uuid,
+    pub node_id: Uuid,
+    pub node_name: String,
+}
+
 /// Error when dispatching a task.
 #[derive(Debug, thiserror::Error)]
 pub enum DispatchError {
@@ -294,12 +538,158 @@ pub enum DispatchError {
     NoNodeForProject,
     #[error("the linked node is not connected")]
     NodeNotConnected,
-    #[error("no available node in the organization")]
-    NoAvailableNode,
-    #[error("the node has no linked projects")]
-    NoProjectOnNode,
+    #[error("no linked node can run this task: {}", format_rejections(.0))]
+    NoEligibleNode(Vec<NodeRejection>),
+    #[error("task not found")]
+    TaskNotFound,
+    #[error("the task belongs to the node that created it")]
+    TaskOwnedByNode,
     #[error("failed to send to node: {0}")]
     SendFailed(String),
     #[error("node service error: {0}")]
     NodeService(#[from] NodeError),
 }
+
+fn format_rejections(rejections: &[NodeRejection]) -> String {
+    rejections
+        .iter()
+        .map(|r| format!("{} ({})", r.node_name, r.reason))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(name: &str, active_tasks: u32, max_concurrent_tasks: i32) -> DispatchCandidate {
+        DispatchCandidate {
+            link: SwarmProjectNodeForDispatch {
+                link_id: Uuid::new_v4(),
+                swarm_project_id: Uuid::nil(),
+                node_id: Uuid::new_v4(),
+                node_name: name.to_string(),
+                local_project_id: Uuid::new_v4(),
+                git_repo_path: "/repo".to_string(),
+                default_branch: "main".to_string(),
+            },
+            status: Some(NodeStatus::Online),
+            capabilities: NodeCapabilities {
+                executors: vec!["CLAUDE_CODE".to_string()],
+                max_concurrent_tasks,
+                os: "linux".to_string(),
+                arch: "x86_64".to_string(),
+                version: "0.0.150".to_string(),
+                labels: vec!["gpu".to_string()],
+                ..Default::default()
+            },
+            active_tasks,
+        }
+    }
+
+    fn requirements(executor: &str) -> DispatchRequirements {
+        DispatchRequirements {
+            executor: executor.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_select_node_prefers_least_loaded_share() {
+        let candidates = vec![
+            candidate("first", 1, 2),
+            candidate("second", 1, 4),
+            candidate("third", 1, 4),
+        ];
+        let node = select_node(&candidates, &requirements("claude_code")).unwrap();
+        assert_eq!(node.link.node_name, "second");
+    }
+
+    #[test]
+    fn test_select_node_explains_every_rejection() {
+        let mut offline = candidate("offline", 0, 1);
+        offline.status = None;
+        let mut draining = candidate("draining", 0, 1);
+        draining.status = Some(NodeStatus::Draining);
+        let mut mac = candidate("mac", 0, 1);
+        mac.capabilities.os = "darwin".to_string();
+        let full = candidate("full", 2, 2);
+        let candidates = vec![offline, draining, mac, full];
+
+        let rejections = select_node(
+            &candidates,
+            &DispatchRequirements {
+                os: Some("linux".to_string()),
+                ..requirements("CLAUDE_CODE")
+            },
+        )
+        .unwrap_err();
+        let reasons: Vec<_> = rejections.into_iter().map(|r| r.reason).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                Ineligibility::NotConnected,
+                Ineligibility::Unavailable(NodeStatus::Draining),
+                Ineligibility::OsMismatch("darwin".to_string()),
+                Ineligibility::AtCapacity { active: 2, max: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_node_checks_capabilities() {
+        let candidates = [candidate("gpu", 0, 1)];
+        let mut needs = DispatchRequirements {
+            min_version: Some("v0.0.150".to_string()),
+            node_labels: vec!["gpu".to_string()],
+            ..requirements("CLAUDE_CODE")
+        };
+        assert!(select_node(&candidates, &needs).is_ok());
+
+        needs.node_labels.push("eu".to_string());
+        let rejections = select_node(&candidates, &needs).unwrap_err();
+        assert_eq!(
+            rejections[0].reason,
+            Ineligibility::MissingLabels(vec!["eu".to_string()])
+        );
+
+        let rejections = select_node(
+            &candidates,
+            &DispatchRequirements {
+                min_version: Some("0.0.151".to_string()),
+                ..requirements("CLAUDE_CODE")
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            rejections[0].reason,
+            Ineligibility::VersionTooOld("0.0.150".to_string())
+        );
+
+        let rejections = select_node(&candidates, &requirements("CODEX")).unwrap_err();
+        assert_eq!(
+            rejections[0].reason,
+            Ineligibility::MissingExecutor("CODEX".to_string())
+        );
+
+        let rejections = select_node(
+            &candidates,
+            &DispatchRequirements {
+                exclude_node_ids: vec![candidates[0].link.node_id],
+                ..requirements("CLAUDE_CODE")
+            },
+        )
+        .unwrap_err();
+        assert_eq!(rejections[0].reason, Ineligibility::Excluded);
+    }
+
+    #[test]
+    fn test_version_at_least() {
+        assert!(version_at_least("0.1.0", "0.0.150"));
+        assert!(version_at_least("1.2", "1.2.0"));
+        assert!(version_at_least("v2.0.0-beta.1", "2.0.0"));
+        assert!(!version_at_least("0.0.99", "0.0.150"));
+        assert!(!version_at_least("", "0.0.1"));
+        assert!(!version_at_least("dev", "0.0.1"));
+    }
+}
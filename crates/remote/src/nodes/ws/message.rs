@@ -3,8 +3,11 @@
 //! This module defines the protocol for bidirectional communication between
 //! nodes (local instances) and the hive (remote server).
 
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utils::approvals::{ApprovalStatus, Question};
 use uuid::Uuid;
 
 use crate::nodes::domain::{NodeCapabilities, NodeStatus};
@@ -94,6 +97,14 @@ pub enum NodeMessage {
     /// Anti-entropy digest (SC5, CONTRACT §A). Tracer scope: entity_type "task".
     #[serde(rename = "digest")]
     Digest { entries: Vec<DigestEntry> },
+
+    /// An agent on the node is waiting for a tool approval or an answer to its questions
+    #[serde(rename = "approval_request")]
+    ApprovalRequest(ApprovalRequestMessage),
+
+    /// An approval was answered or timed out on the node itself
+    #[serde(rename = "approval_resolved")]
+    ApprovalResolved(ApprovalResolvedMessage),
 }
 
 /// Messages sent from the hive to a node.
@@ -169,6 +180,10 @@ pub enum HiveMessage {
     /// Reply to a node Digest (SC5, CONTRACT §A).
     #[serde(rename = "digest_result")]
     DigestResult { resend_from_seq: Option<i64>, pull_entities: Vec<Uuid> },
+
+    /// Answer to an approval the node forwarded, given on the Hive or another node
+    #[serde(rename = "approval_response")]
+    ApprovalResponse(ApprovalResponseMessage),
 }
 
 /// Authentication message from node to hive.
@@ -543,7 +558,8 @@ pub struct ExecutionSyncMessage {
     pub execution_id: Uuid,
     /// Attempt ID this process belongs to
     pub attempt_id: Uuid,
-    /// Run reason (setupscript, cleanupscript, codingagent, devserver)
+    /// Run reason (setupscript, cleanupscript, codingagent, devserver, pipelinestep,
+    /// commandrerun)
     pub run_reason: String,
     /// Executor action details (JSON)
     pub executor_action: Option<serde_json::Value>,
@@ -796,5 +812,52 @@ pub struct DigestEntry {
     pub version: i64,
 }
 
+/// Approval request forwarded from a node to the hive.
+///
+/// Sent when an agent starts waiting for approval, and again for every approval still waiting
+/// when the node reconnects. The hive keeps one row per `(node, approval_id)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequestMessage {
+    /// The approval's id on the node
+    pub approval_id: String,
+    /// Execution process on the node the agent runs in
+    pub local_execution_process_id: Uuid,
+    /// Task attempt on the node
+    pub local_attempt_id: Option<Uuid>,
+    /// Shared task ID on the hive, if the task is shared
+    pub shared_task_id: Option<Uuid>,
+    /// Title of the task, for display
+    pub task_title: String,
+    pub tool_name: String,
+    pub tool_input: serde_json::Value,
+    /// Questions asked by the agent, for question requests
+    #[serde(default)]
+    pub questions: Option<Vec<Question>>,
+    pub created_at: DateTime<Utc>,
+    /// When the node stops waiting and times the request out
+    pub timeout_at: DateTime<Utc>,
+}
+
+/// Notice that an approval was settled on the node that raised it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalResolvedMessage {
+    pub approval_id: String,
+    pub status: ApprovalStatus,
+}
+
+/// Answer to a forwarded approval, sent to the node that raised it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalResponseMessage {
+    pub approval_id: String,
+    pub local_execution_process_id: Uuid,
+    pub status: ApprovalStatus,
+    /// Answers to the agent's questions, keyed by question header
+    #[serde(default)]
+    pub answers: Option<HashMap<String, String>>,
+    /// Display name of the member who answered; unset for escalation defaults
+    #[serde(default)]
+    pub responded_by: Option<String>,
+}
+
 /// Current protocol version.
 pub const PROTOCOL_VERSION: u32 = 1;
@@ -14,14 +14,21 @@ use axum::{
 
 use crate::AppState;
 
+mod cluster;
 mod connection;
 mod dispatcher;
 pub mod message;
 mod session;
 mod status_machine;
 
-pub use connection::{ConnectionManager, NodeConnectionInfo, SendError};
-pub use dispatcher::{AssignResult, DispatchError, TaskDispatcher};
+pub use cluster::{HiveCluster, RoutedPayload};
+pub use connection::{
+    ConnectionHandle, ConnectionManager, NodeConnectionInfo, RegisterError, SendError,
+};
+pub use dispatcher::{
+    AssignResult, DispatchError, DispatchRequirements, DispatchResult, Ineligibility, NodeRejection,
+    TaskDispatcher,
+};
 
 /// Create the WebSocket router for node connections.
 pub fn router() -> Router<AppState> {
@@ -33,6 +40,7 @@ async fn upgrade(ws: WebSocketUpgrade, State(state): State<AppState>) -> impl In
     let pool = state.pool().clone();
     let connections = state.node_connections().clone();
     let backfill = Arc::clone(state.backfill());
+    let log_relay = state.log_relay().clone();
 
-    ws.on_upgrade(move |socket| session::handle(socket, pool, connections, backfill))
+    ws.on_upgrade(move |socket| session::handle(socket, pool, connections, backfill, log_relay))
 }
@@ -20,7 +20,8 @@ use uuid::Uuid;
 use super::{
     connection::ConnectionManager,
     message::{
-        AttemptSyncMessage, AuthResultMessage, BackfillResponseMessage, DeregisterMessage,
+        ApprovalRequestMessage, ApprovalResolvedMessage, AttemptSyncMessage, AuthResultMessage,
+        BackfillResponseMessage, DeregisterMessage,
         DigestEntry, ExecutionSyncMessage, HeartbeatMessage, HiveMessage, LinkProjectMessage,
         LinkedProjectInfo, LogsBatchMessage, NodeMessage, NodeRemovedMessage, OutboxOp,
         PROTOCOL_VERSION, ProjectSyncMessage, ProjectsSyncMessage, SwarmLabelInfo,
@@ -29,13 +30,14 @@ use super::{
     },
 };
 use crate::{
-    db::node_task_attempts::NodeTaskAttemptRepository,
+    db::{node_task_attempts::NodeTaskAttemptRepository, swarm_approvals::SwarmApprovalRepository},
     nodes::{
         BackfillService,
         backfill::BackfillRequestTracker,
         domain::NodeStatus,
         service::{NodeServiceImpl, RegisterNode},
     },
+    services::{LogRelay, task_queue},
 };
 
 /// Heartbeat timeout - close connection if no heartbeat received.
@@ -77,7 +79,7 @@ fn extract_project_name(git_repo_path: &str) -> String {
 /// Handle a new node WebSocket connection.
 #[instrument(
     name = "node_ws.session",
-    skip(socket, pool, connections, backfill),
+    skip(socket, pool, connections, backfill, log_relay),
     fields(
         node_id = tracing::field::Empty,
         org_id = tracing::field::Empty,
@@ -89,6 +91,7 @@ pub async fn handle(
     pool: PgPool,
     connections: ConnectionManager,
     backfill: Arc<BackfillService>,
+    log_relay: LogRelay,
 ) {
     let (mut ws_sender, mut ws_receiver) = socket.split();
     let (tx, mut rx) = mpsc::channel::<HiveMessage>(OUTGOING_BUFFER_SIZE);
@@ -130,6 +133,31 @@ pub async fn handle(
         "sending auth success with swarm labels"
     );
 
+    // Register connection before confirming, so a replica still holding the node can refuse
+    let registration = match connections
+        .register(auth_result.node_id, auth_result.organization_id, tx)
+        .await
+    {
+        Ok(registration) => registration,
+        Err(error) => {
+            tracing::warn!(?error, "failed to register node connection");
+            let _ = send_message(
+                &mut ws_sender,
+                &HiveMessage::AuthResult(AuthResultMessage {
+                    success: false,
+                    node_id: None,
+                    organization_id: None,
+                    error: Some(error.to_string()),
+                    protocol_version: PROTOCOL_VERSION,
+                    linked_projects: vec![],
+                    swarm_labels: vec![],
+                }),
+            )
+            .await;
+            return;
+        }
+    };
+
     // Send auth success response
     if send_message(
         &mut ws_sender,
@@ -146,14 +174,12 @@ pub async fn handle(
     .await
     .is_err()
     {
+        connections
+            .unregister(auth_result.node_id, registration.connection_id)
+            .await;
         return;
     }
 
-    // Register connection
-    connections
-        .register(auth_result.node_id, auth_result.organization_id, tx)
-        .await;
-
     // Broadcast this node's projects to other nodes in the organization
     broadcast_node_projects(
         auth_result.node_id,
@@ -166,6 +192,31 @@ pub async fn handle(
     )
     .await;
 
+    // Send answers to approvals given while the node was offline
+    match crate::services::swarm_approvals::deliver_pending_responses(
+        &pool,
+        &connections,
+        auth_result.node_id,
+    )
+    .await
+    {
+        Ok(count) if count > 0 => {
+            tracing::info!(
+                node_id = %auth_result.node_id,
+                count,
+                "delivered approval answers given while node was offline"
+            );
+        }
+        Ok(_) => {}
+        Err(error) => {
+            tracing::warn!(?error, "failed to deliver pending approval answers");
+        }
+    }
+
+    // A node that just connected has room for queued work; later heartbeats only wake the
+    // queue when its load changes
+    task_queue::wake(pool.clone(), connections.clone(), auth_result.organization_id);
+
     // Trigger backfill for incomplete attempts (non-blocking)
     let node_id_for_backfill = auth_result.node_id;
     let backfill_service = Arc::clone(&backfill);
@@ -207,9 +258,28 @@ pub async fn handle(
     // Get tracker for correlating backfill responses
     let tracker = backfill.tracker();
 
+    // Set when the node connected again, here or on another replica
+    let mut evicted = false;
+
     // Main message loop
     loop {
         tokio::select! {
+            // The node's newer connection takes over
+            _ = registration.evicted.notified() => {
+                tracing::info!(
+                    node_id = %auth_result.node_id,
+                    "node connected again; closing this session"
+                );
+                let _ = send_message(
+                    &mut ws_sender,
+                    &HiveMessage::Close {
+                        reason: "node connected again".to_string(),
+                    },
+                ).await;
+                evicted = true;
+                break;
+            }
+
             // Handle outgoing messages from hive
             Some(msg) = rx.recv() => {
                 if send_message(&mut ws_sender, &msg).await.is_err() {
@@ -230,6 +300,7 @@ pub async fn handle(
                                     &auth_result.node_name,
                                     &pool,
                                     &connections,
+                                    &log_relay,
                                     &mut ws_sender,
                                     &mut last_heartbeat,
                                     &tracker,
@@ -284,7 +355,18 @@ pub async fn handle(
     }
 
     // Clean up
-    connections.unregister(auth_result.node_id).await;
+    connections
+        .unregister(auth_result.node_id, registration.connection_id)
+        .await;
+
+    // The newer session owns the node's tracking and status now
+    if evicted {
+        tracing::info!(
+            node_id = %auth_result.node_id,
+            "node session replaced"
+        );
+        return;
+    }
 
     // Clear in-memory backfill tracking for this node.
     // NOTE: We intentionally do NOT reset attempts to partial here. The backfill_request_id
@@ -509,13 +591,14 @@ async fn handle_node_message(
     node_name: &str,
     pool: &PgPool,
     connections: &ConnectionManager,
+    log_relay: &LogRelay,
     ws_sender: &mut futures::stream::SplitSink<WebSocket, Message>,
     last_heartbeat: &mut chrono::DateTime<Utc>,
     tracker: &BackfillRequestTracker,
 ) -> Result<(), HandleError> {
     match msg {
         NodeMessage::Heartbeat(heartbeat) => {
-            handle_heartbeat(
+            if handle_heartbeat(
                 node_id,
                 heartbeat,
                 pool,
@@ -523,12 +606,19 @@ async fn handle_node_message(
                 ws_sender,
                 last_heartbeat,
             )
-            .await
+            .await?
+            {
+                // The node's load may have dropped; hand it queued work if so
+                task_queue::wake(pool.clone(), connections.clone(), organization_id);
+            }
+            Ok(())
         }
         NodeMessage::TaskStatus(status) => {
             handle_task_status(node_id, organization_id, status, pool).await
         }
-        NodeMessage::TaskOutput(output) => handle_task_output(node_id, output, pool).await,
+        NodeMessage::TaskOutput(output) => {
+            handle_task_output(node_id, output, pool, log_relay).await
+        }
         NodeMessage::TaskProgress(progress) => handle_task_progress(node_id, progress, pool).await,
         NodeMessage::LinkProject(link) => {
             handle_link_project(node_id, organization_id, link, pool, connections).await
@@ -543,7 +633,7 @@ async fn handle_node_message(
         NodeMessage::ExecutionSync(execution) => {
             handle_execution_sync(node_id, execution, pool).await
         }
-        NodeMessage::LogsBatch(logs) => handle_logs_batch(node_id, logs, pool).await,
+        NodeMessage::LogsBatch(logs) => handle_logs_batch(node_id, logs, pool, log_relay).await,
         NodeMessage::LabelSync(_label) => {
             // Labels are no longer synced from nodes to hive.
             // Labels are managed centrally on the hive and synced DOWN to nodes.
@@ -583,15 +673,64 @@ async fn handle_node_message(
             handle_op_batch(node_id, organization_id, node_name, ops, pool, ws_sender).await
         }
         NodeMessage::LeaseHeartbeat { assignment_ids } => {
-            handle_lease_heartbeat(node_id, assignment_ids, pool, ws_sender).await
+            handle_lease_heartbeat(node_id, assignment_ids, pool, ws_sender).await?;
+            task_queue::wake(pool.clone(), connections.clone(), organization_id);
+            Ok(())
         }
         NodeMessage::Digest { entries } => {
             handle_digest(node_id, entries, pool, ws_sender).await
         }
+        NodeMessage::ApprovalRequest(request) => {
+            handle_approval_request(node_id, organization_id, request, pool).await
+        }
+        NodeMessage::ApprovalResolved(resolved) => {
+            handle_approval_resolved(node_id, resolved, pool).await
+        }
     }
 }
 
-/// Handle a heartbeat message.
+/// Handle an approval request forwarded by a node, making it visible to the organization.
+async fn handle_approval_request(
+    node_id: Uuid,
+    organization_id: Uuid,
+    request: &ApprovalRequestMessage,
+    pool: &PgPool,
+) -> Result<(), HandleError> {
+    let approval = SwarmApprovalRepository::new(pool)
+        .upsert_request(organization_id, node_id, request)
+        .await
+        .map_err(|e| HandleError::Database(e.to_string()))?;
+
+    tracing::info!(
+        node_id = %node_id,
+        approval_id = %approval.approval_id,
+        tool_name = %approval.tool_name,
+        "approval request forwarded to hive"
+    );
+    Ok(())
+}
+
+/// Handle an approval settled on the node itself, so the hive stops offering it.
+async fn handle_approval_resolved(
+    node_id: Uuid,
+    resolved: &ApprovalResolvedMessage,
+    pool: &PgPool,
+) -> Result<(), HandleError> {
+    let updated = SwarmApprovalRepository::new(pool)
+        .resolve_on_node(node_id, &resolved.approval_id, &resolved.status)
+        .await
+        .map_err(|e| HandleError::Database(e.to_string()))?;
+
+    tracing::debug!(
+        node_id = %node_id,
+        approval_id = %resolved.approval_id,
+        updated,
+        "approval resolved on node"
+    );
+    Ok(())
+}
+
+/// Handle a heartbeat message. Returns whether the node's status or load changed.
 async fn handle_heartbeat(
     node_id: Uuid,
     heartbeat: &HeartbeatMessage,
@@ -599,11 +738,11 @@ async fn handle_heartbeat(
     connections: &ConnectionManager,
     ws_sender: &mut futures::stream::SplitSink<WebSocket, Message>,
     last_heartbeat: &mut chrono::DateTime<Utc>,
-) -> Result<(), HandleError> {
+) -> Result<bool, HandleError> {
     *last_heartbeat = Utc::now();
 
     // Update connection status
-    connections
+    let load_changed = connections
         .update_status(node_id, heartbeat.status, heartbeat.active_tasks)
         .await;
 
@@ -631,7 +770,7 @@ async fn handle_heartbeat(
         "processed heartbeat"
     );
 
-    Ok(())
+    Ok(load_changed)
 }
 
 /// Handle a task status update.
@@ -760,6 +899,7 @@ async fn handle_task_output(
     node_id: Uuid,
     output: &TaskOutputMessage,
     pool: &PgPool,
+    log_relay: &LogRelay,
 ) -> Result<(), HandleError> {
     use crate::db::task_output_logs::{CreateTaskOutputLog, TaskOutputLogRepository};
 
@@ -770,14 +910,17 @@ async fn handle_task_output(
     };
 
     let repo = TaskOutputLogRepository::new(pool);
-    repo.create(CreateTaskOutputLog {
-        assignment_id: output.assignment_id,
-        output_type: output_type.to_string(),
-        content: output.content.clone(),
-        timestamp: output.timestamp,
-    })
-    .await
-    .map_err(|e| HandleError::Database(e.to_string()))?;
+    let log = repo
+        .create(CreateTaskOutputLog {
+            assignment_id: output.assignment_id,
+            output_type: output_type.to_string(),
+            content: output.content.clone(),
+            timestamp: output.timestamp,
+        })
+        .await
+        .map_err(|e| HandleError::Database(e.to_string()))?;
+
+    log_relay.publish(pool, output.assignment_id, vec![log]).await;
 
     tracing::trace!(
         node_id = %node_id,
@@ -1448,6 +1591,7 @@ async fn handle_logs_batch(
     node_id: Uuid,
     logs: &LogsBatchMessage,
     pool: &PgPool,
+    log_relay: &LogRelay,
 ) -> Result<(), HandleError> {
     use crate::db::swarm_projects::SwarmProjectRepository;
     use crate::db::task_assignments::TaskAssignmentRepository;
@@ -1556,6 +1700,7 @@ async fn handle_logs_batch(
 
     // Now insert the logs with the valid assignment_id
     let log_repo = TaskOutputLogRepository::new(pool);
+    let mut stored = Vec::with_capacity(logs.entries.len());
 
     for entry in &logs.entries {
         let output_type = match entry.output_type {
@@ -1565,7 +1710,7 @@ async fn handle_logs_batch(
         };
 
         // Create log with optional execution_process_id
-        log_repo
+        let log = log_repo
             .create_with_execution_process(
                 CreateTaskOutputLog {
                     assignment_id,
@@ -1577,8 +1722,11 @@ async fn handle_logs_batch(
             )
             .await
             .map_err(|e| HandleError::Database(e.to_string()))?;
+        stored.push(log);
     }
 
+    log_relay.publish(pool, assignment_id, stored).await;
+
     tracing::trace!(
         node_id = %node_id,
         assignment_id = %assignment_id,
@@ -26,9 +26,12 @@ pub(crate) mod organization_members;
 mod organizations;
 pub mod projects;
 mod relay;
+pub mod swarm_approvals;
+pub mod swarm_dispatch;
 pub mod swarm_labels;
 pub mod swarm_projects;
 pub mod swarm_templates;
+pub mod task_queue;
 pub mod tasks;
 mod tokens;
 
@@ -85,8 +88,11 @@ pub fn router(state: AppState) -> Router {
         .merge(activity::router())
         .merge(swarm_projects::router())
         .merge(swarm_labels::router())
+        .merge(swarm_approvals::router())
+        .merge(swarm_dispatch::router())
         .merge(swarm_templates::router())
         .merge(tasks::router())
+        .merge(task_queue::router())
         .merge(labels::router())
         .merge(organizations::router())
         .merge(organization_members::protected_router())
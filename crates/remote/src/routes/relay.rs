@@ -2,7 +2,8 @@
 //!
 //! This module provides:
 //! - REST API for fetching logs with cursor-based pagination
-//! - WebSocket endpoint for live log streaming
+//! - WebSocket endpoint for live log streaming, fed by the [`LogRelay`] as
+//!   nodes send logs rather than by polling the database
 //!
 //! Both endpoints allow frontend clients to access logs from task execution
 //! via the Hive when direct node connection is not available.
@@ -17,11 +18,14 @@ use axum::{
     response::{IntoResponse, Response},
     routing::get,
 };
-use futures::{SinkExt, StreamExt};
+use futures::{SinkExt, StreamExt, stream::SplitSink};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::time::Duration;
-use tokio::time::interval;
+use tokio::{
+    sync::broadcast::error::{RecvError, TryRecvError},
+    time::interval,
+};
 use tracing::instrument;
 use utils::unified_log::{Direction, PaginatedLogs};
 use uuid::Uuid;
@@ -30,17 +34,19 @@ use crate::{
     AppState,
     auth::{ConnectionTokenError, RequestContext},
     db::{
-        task_assignments::TaskAssignmentRepository, task_output_logs::TaskOutputLogRepository,
+        task_assignments::TaskAssignmentRepository,
+        task_output_logs::{TaskOutputLog, TaskOutputLogRepository},
         tasks::SharedTaskRepository,
     },
     routes::organization_members::ensure_member_access,
+    services::LogRelay,
 };
 
-/// Polling interval for new logs
-const LOG_POLL_INTERVAL_MS: u64 = 100;
+/// Maximum logs to send to a viewer in one message
+const MAX_LOGS_PER_MESSAGE: usize = 100;
 
-/// Maximum logs to fetch per poll
-const MAX_LOGS_PER_POLL: i64 = 100;
+/// Logs to fetch per query while a viewer catches up from the database
+const CATCH_UP_PAGE_SIZE: i64 = 500;
 
 /// Default number of log entries to return per page.
 const DEFAULT_LIMIT: i64 = 100;
@@ -62,6 +68,10 @@ pub fn router() -> Router<AppState> {
 pub struct LogStreamQuery {
     /// Connection token for authentication
     pub token: Option<String>,
+
+    /// ID of the last entry the viewer already has. Streaming resumes after it;
+    /// without one, it starts from the first entry.
+    pub cursor: Option<i64>,
 }
 
 /// Query parameters for the paginated logs endpoint.
@@ -128,6 +138,17 @@ pub struct LogStreamEntry {
     pub timestamp: String,
 }
 
+impl From<&TaskOutputLog> for LogStreamEntry {
+    fn from(log: &TaskOutputLog) -> Self {
+        Self {
+            id: log.id,
+            output_type: log.output_type.clone(),
+            content: log.content.clone(),
+            timestamp: log.timestamp.to_rfc3339(),
+        }
+    }
+}
+
 /// Messages sent to clients.
 #[derive(Debug, Serialize)]
 #[serde(tag = "type")]
@@ -169,7 +190,10 @@ pub async fn upgrade_log_stream(
         Ok(()) => {
             // Verified access, proceed with WebSocket upgrade
             let pool = pool.clone();
-            ws.on_upgrade(move |socket| handle_log_stream(socket, pool, assignment_id))
+            let relay = state.log_relay().clone();
+            ws.on_upgrade(move |socket| {
+                handle_log_stream(socket, pool, relay, assignment_id, query.cursor)
+            })
         }
         Err(response) => response,
     }
@@ -273,15 +297,30 @@ async fn authenticate(
 }
 
 /// Handle the WebSocket connection for log streaming.
-async fn handle_log_stream(socket: WebSocket, pool: sqlx::PgPool, assignment_id: Uuid) {
+///
+/// Subscribes to the relay before catching up from the database so no entry
+/// falls between the two, then streams entries as they are ingested. A viewer
+/// that falls behind the relay, or whose replica lost its relay listener for a
+/// moment, catches up from the database again.
+async fn handle_log_stream(
+    socket: WebSocket,
+    pool: sqlx::PgPool,
+    relay: LogRelay,
+    assignment_id: Uuid,
+    cursor: Option<i64>,
+) {
     let (mut sender, mut receiver) = socket.split();
+    let mut subscription = relay.subscribe(assignment_id);
 
     // Track the last log ID we've sent
-    let mut last_log_id: i64 = 0;
+    let mut last_log_id: i64 = cursor.unwrap_or(0);
 
-    // Poll interval
-    let mut poll_interval = interval(Duration::from_millis(LOG_POLL_INTERVAL_MS));
-    poll_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    if catch_up(&pool, &mut sender, assignment_id, &mut last_log_id)
+        .await
+        .is_err()
+    {
+        return;
+    }
 
     // Heartbeat interval (30 seconds)
     let mut heartbeat_interval = interval(Duration::from_secs(30));
@@ -289,47 +328,48 @@ async fn handle_log_stream(socket: WebSocket, pool: sqlx::PgPool, assignment_id:
 
     loop {
         tokio::select! {
-            // Poll for new logs
-            _ = poll_interval.tick() => {
-                let log_repo = TaskOutputLogRepository::new(&pool);
-
-                match log_repo.list_by_assignment(assignment_id, Some(MAX_LOGS_PER_POLL), Some(last_log_id)).await {
-                    Ok(logs) => {
-                        if !logs.is_empty() {
-                            // Update the last log ID
-                            if let Some(last) = logs.last() {
-                                last_log_id = last.id;
-                            }
-
-                            // Convert to stream entries
-                            let entries: Vec<LogStreamEntry> = logs.into_iter().map(|log| {
-                                LogStreamEntry {
-                                    id: log.id,
-                                    output_type: log.output_type,
-                                    content: log.content,
-                                    timestamp: log.timestamp.to_rfc3339(),
-                                }
-                            }).collect();
-
-                            // Send to client
-                            let message = LogStreamMessage::Logs { entries };
-                            if let Ok(json) = serde_json::to_string(&message)
-                                && sender.send(Message::Text(json.into())).await.is_err()
-                            {
-                                // Client disconnected
-                                break;
-                            }
-                        }
+            // Forward logs as they are published
+            published = subscription.recv() => {
+                let mut entries = Vec::new();
+                let mut lagged = false;
+                match published {
+                    Ok(log) => entries.push(log),
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!(%assignment_id, skipped, "log viewer lagged; catching up");
+                        lagged = true;
                     }
-                    Err(e) => {
-                        tracing::error!(?e, "failed to fetch logs");
-                        let message = LogStreamMessage::Error { message: "failed to fetch logs".to_string() };
-                        if let Ok(json) = serde_json::to_string(&message) {
-                            let _ = sender.send(Message::Text(json.into())).await;
-                        }
+                    Err(RecvError::Closed) => break,
+                }
+
+                // Send whatever else is already buffered along with it
+                while !lagged && entries.len() < MAX_LOGS_PER_MESSAGE {
+                    match subscription.try_recv() {
+                        Ok(log) => entries.push(log),
+                        Err(TryRecvError::Lagged(_)) => lagged = true,
+                        Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+                    }
+                }
+
+                let entries: Vec<LogStreamEntry> = entries
+                    .into_iter()
+                    .filter(|log| log.id > last_log_id)
+                    .map(|log| LogStreamEntry::from(log.as_ref()))
+                    .collect();
+                if let Some(last) = entries.last() {
+                    last_log_id = last.id;
+                    if send_logs(&mut sender, entries).await.is_err() {
+                        // Client disconnected
                         break;
                     }
                 }
+
+                if lagged
+                    && catch_up(&pool, &mut sender, assignment_id, &mut last_log_id)
+                        .await
+                        .is_err()
+                {
+                    break;
+                }
             }
 
             // Send heartbeat
@@ -365,6 +405,69 @@ async fn handle_log_stream(socket: WebSocket, pool: sqlx::PgPool, assignment_id:
     tracing::debug!(%assignment_id, "log stream closed");
 }
 
+/// Send every stored entry after `last_log_id`, page by page, advancing it as we go.
+///
+/// Errs when the client is gone or the logs could not be fetched; the client
+/// has been told about the latter.
+async fn catch_up(
+    pool: &sqlx::PgPool,
+    sender: &mut SplitSink<WebSocket, Message>,
+    assignment_id: Uuid,
+    last_log_id: &mut i64,
+) -> Result<(), ()> {
+    let log_repo = TaskOutputLogRepository::new(pool);
+
+    loop {
+        let logs = match log_repo
+            .list_by_assignment(assignment_id, Some(CATCH_UP_PAGE_SIZE), Some(*last_log_id))
+            .await
+        {
+            Ok(logs) => logs,
+            Err(e) => {
+                tracing::error!(?e, "failed to fetch logs");
+                let message = LogStreamMessage::Error {
+                    message: "failed to fetch logs".to_string(),
+                };
+                if let Ok(json) = serde_json::to_string(&message) {
+                    let _ = sender.send(Message::Text(json.into())).await;
+                }
+                return Err(());
+            }
+        };
+
+        let Some(last) = logs.last() else {
+            return Ok(());
+        };
+        *last_log_id = last.id;
+        let complete = (logs.len() as i64) < CATCH_UP_PAGE_SIZE;
+
+        let entries = logs.iter().map(LogStreamEntry::from).collect();
+        send_logs(sender, entries).await?;
+
+        if complete {
+            return Ok(());
+        }
+    }
+}
+
+/// Send a batch of entries to the client, erring when it is gone.
+async fn send_logs(
+    sender: &mut SplitSink<WebSocket, Message>,
+    entries: Vec<LogStreamEntry>,
+) -> Result<(), ()> {
+    let message = LogStreamMessage::Logs { entries };
+    match serde_json::to_string(&message) {
+        Ok(json) => sender
+            .send(Message::Text(json.into()))
+            .await
+            .map_err(|_| ()),
+        Err(e) => {
+            tracing::error!(?e, "failed to encode logs");
+            Ok(())
+        }
+    }
+}
+
 /// GET /v1/logs/{assignment_id}
 ///
 /// Returns paginated log entries for the specified assignment.
@@ -395,6 +498,7 @@ pub async fn get_paginated_logs(
     // Convert to LogStreamQuery for authentication
     let auth_query = LogStreamQuery {
         token: query.token.clone(),
+        cursor: None,
     };
 
     // Try to authenticate
@@ -0,0 +1,331 @@
+//! Routes for approval requests forwarded to the Hive by nodes.
+//!
+//! Any member of the organization can list and answer approvals, whichever node raised them.
+//! This module provides endpoints for:
+//! - Listing pending (or all recent) approvals of an organization
+//! - Answering an approval, which relays the answer to the node that raised it
+//! - Reading and changing the organization's escalation settings (admins only)
+
+use std::collections::HashMap;
+
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utils::approvals::ApprovalStatus;
+use uuid::Uuid;
+
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_admin_access, ensure_member_access},
+};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::{
+        organization_members,
+        swarm_approvals::{
+            ApprovalEscalationSettings, EscalationDecision, SwarmApproval, SwarmApprovalError,
+            SwarmApprovalRepository,
+        },
+        users::User,
+    },
+    services::swarm_approvals::deliver_response,
+};
+
+const DEFAULT_LIST_LIMIT: i64 = 100;
+const MAX_LIST_LIMIT: i64 = 500;
+
+// =====================
+// Query & Request Types
+// =====================
+
+#[derive(Debug, Deserialize)]
+pub struct ListSwarmApprovalsQuery {
+    pub organization_id: Uuid,
+    /// Include answered and timed out approvals (default: pending only)
+    #[serde(default)]
+    pub include_resolved: bool,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RespondSwarmApprovalRequest {
+    pub status: ApprovalStatus,
+    /// Answers to the agent's questions, keyed by question header
+    #[serde(default)]
+    pub answers: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EscalationSettingsQuery {
+    pub organization_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateEscalationSettingsRequest {
+    pub organization_id: Uuid,
+    pub timeout_minutes: i32,
+    #[serde(default)]
+    pub escalate_to: Option<Uuid>,
+    #[serde(default)]
+    pub default_decision: Option<EscalationDecision>,
+}
+
+// =====================
+// Response Types
+// =====================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListSwarmApprovalsResponse {
+    pub approvals: Vec<SwarmApproval>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SwarmApprovalResponse {
+    pub approval: SwarmApproval,
+    /// Whether the node that raised the approval received the answer; answers for offline nodes
+    /// are delivered when they reconnect
+    pub delivered: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EscalationSettingsResponse {
+    pub settings: Option<ApprovalEscalationSettings>,
+}
+
+// =====================
+// Router
+// =====================
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/swarm/approvals", get(list_swarm_approvals))
+        .route(
+            "/swarm/approvals/escalation",
+            get(get_escalation_settings)
+                .put(update_escalation_settings)
+                .delete(delete_escalation_settings),
+        )
+        .route(
+            "/swarm/approvals/{approval_id}/respond",
+            post(respond_swarm_approval),
+        )
+}
+
+// =====================
+// Handlers
+// =====================
+
+#[instrument(
+    name = "swarm_approvals.list",
+    skip(state, ctx, params),
+    fields(org_id = %params.organization_id, user_id = %ctx.user.id)
+)]
+async fn list_swarm_approvals(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(params): Query<ListSwarmApprovalsQuery>,
+) -> Result<Json<ListSwarmApprovalsResponse>, ErrorResponse> {
+    ensure_member_access(state.pool(), params.organization_id, ctx.user.id).await?;
+
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_LIST_LIMIT)
+        .clamp(1, MAX_LIST_LIMIT);
+    let approvals = SwarmApprovalRepository::new(state.pool())
+        .list_by_organization(params.organization_id, !params.include_resolved, limit)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to list swarm approvals");
+            ErrorResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to list swarm approvals",
+            )
+        })?;
+
+    Ok(Json(ListSwarmApprovalsResponse { approvals }))
+}
+
+#[instrument(
+    name = "swarm_approvals.respond",
+    skip(state, ctx, payload),
+    fields(approval_id = %approval_id, user_id = %ctx.user.id)
+)]
+async fn respond_swarm_approval(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(approval_id): Path<Uuid>,
+    Json(payload): Json<RespondSwarmApprovalRequest>,
+) -> Result<Json<SwarmApprovalResponse>, ErrorResponse> {
+    if matches!(payload.status, ApprovalStatus::Pending) {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "an approval cannot be answered with a pending status",
+        ));
+    }
+
+    let repo = SwarmApprovalRepository::new(state.pool());
+    let existing = repo
+        .find_by_id(approval_id)
+        .await
+        .map_err(|error| approval_error(error, "failed to get swarm approval"))?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "approval not found"))?;
+
+    ensure_member_access(state.pool(), existing.organization_id, ctx.user.id).await?;
+
+    let approval = repo
+        .respond(
+            approval_id,
+            &payload.status,
+            payload.answers.as_ref(),
+            Some(ctx.user.id),
+        )
+        .await
+        .map_err(|error| approval_error(error, "failed to answer swarm approval"))?;
+
+    let delivered = deliver_response(
+        state.pool(),
+        state.node_connections(),
+        &approval,
+        Some(display_name(&ctx.user)),
+    )
+    .await;
+
+    tracing::info!(
+        approval_id = %approval.approval_id,
+        node_id = %approval.node_id,
+        delivered,
+        "swarm approval answered"
+    );
+
+    Ok(Json(SwarmApprovalResponse {
+        approval,
+        delivered,
+    }))
+}
+
+#[instrument(
+    name = "swarm_approvals.get_escalation",
+    skip(state, ctx, params),
+    fields(org_id = %params.organization_id, user_id = %ctx.user.id)
+)]
+async fn get_escalation_settings(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(params): Query<EscalationSettingsQuery>,
+) -> Result<Json<EscalationSettingsResponse>, ErrorResponse> {
+    ensure_member_access(state.pool(), params.organization_id, ctx.user.id).await?;
+
+    let settings = SwarmApprovalRepository::new(state.pool())
+        .find_escalation_settings(params.organization_id)
+        .await
+        .map_err(|error| approval_error(error, "failed to get escalation settings"))?;
+
+    Ok(Json(EscalationSettingsResponse { settings }))
+}
+
+#[instrument(
+    name = "swarm_approvals.update_escalation",
+    skip(state, ctx, payload),
+    fields(org_id = %payload.organization_id, user_id = %ctx.user.id)
+)]
+async fn update_escalation_settings(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<UpdateEscalationSettingsRequest>,
+) -> Result<Json<EscalationSettingsResponse>, ErrorResponse> {
+    ensure_admin_access(state.pool(), payload.organization_id, ctx.user.id).await?;
+
+    if payload.timeout_minutes <= 0 {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "timeout_minutes must be positive",
+        ));
+    }
+    if payload.escalate_to.is_none() && payload.default_decision.is_none() {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "set a member to notify, a default decision, or both",
+        ));
+    }
+    if let Some(user_id) = payload.escalate_to {
+        let is_member =
+            organization_members::is_member(state.pool(), payload.organization_id, user_id)
+                .await
+                .map_err(|error| {
+                    tracing::error!(?error, "failed to check escalation member");
+                    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "database error")
+                })?;
+        if !is_member {
+            return Err(ErrorResponse::new(
+                StatusCode::BAD_REQUEST,
+                "escalate_to must be a member of the organization",
+            ));
+        }
+    }
+
+    let settings = SwarmApprovalRepository::new(state.pool())
+        .upsert_escalation_settings(
+            payload.organization_id,
+            payload.timeout_minutes,
+            payload.escalate_to,
+            payload.default_decision,
+        )
+        .await
+        .map_err(|error| approval_error(error, "failed to update escalation settings"))?;
+
+    Ok(Json(EscalationSettingsResponse {
+        settings: Some(settings),
+    }))
+}
+
+#[instrument(
+    name = "swarm_approvals.delete_escalation",
+    skip(state, ctx, params),
+    fields(org_id = %params.organization_id, user_id = %ctx.user.id)
+)]
+async fn delete_escalation_settings(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(params): Query<EscalationSettingsQuery>,
+) -> Result<StatusCode, ErrorResponse> {
+    ensure_admin_access(state.pool(), params.organization_id, ctx.user.id).await?;
+
+    SwarmApprovalRepository::new(state.pool())
+        .delete_escalation_settings(params.organization_id)
+        .await
+        .map_err(|error| approval_error(error, "failed to delete escalation settings"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn approval_error(error: SwarmApprovalError, context: &str) -> ErrorResponse {
+    match error {
+        SwarmApprovalError::NotFound => {
+            ErrorResponse::new(StatusCode::NOT_FOUND, "approval not found")
+        }
+        SwarmApprovalError::AlreadyResolved => ErrorResponse::new(
+            StatusCode::CONFLICT,
+            "approval already answered or timed out",
+        ),
+        SwarmApprovalError::Database(_) => {
+            tracing::error!(?error, "{context}");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, context)
+        }
+    }
+}
+
+/// Name shown to the agent's node for the member who answered
+fn display_name(user: &User) -> String {
+    match (&user.first_name, &user.last_name, &user.username) {
+        (Some(first), Some(last), _) => format!("{first} {last}"),
+        (Some(first), None, _) => first.clone(),
+        (_, _, Some(username)) => username.clone(),
+        _ => user.email.clone(),
+    }
+}
@@ -0,0 +1,126 @@
+//! Routes for an organization's task dispatch settings.
+//!
+//! The settings decide whether tasks created on the Hive are dispatched to a node without being
+//! asked, and what a dispatched task needs from its node when the request does not say.
+//! Any member can read them; only admins can change them.
+
+use axum::{
+    Json, Router,
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_admin_access, ensure_member_access},
+};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::task_dispatch_settings::{
+        DEFAULT_EXECUTOR, TaskDispatchSettings, TaskDispatchSettingsRepository,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct DispatchSettingsQuery {
+    pub organization_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateDispatchSettingsRequest {
+    pub organization_id: Uuid,
+    pub auto_dispatch: bool,
+    #[serde(default)]
+    pub default_executor: Option<String>,
+    #[serde(default)]
+    pub required_node_labels: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DispatchSettingsResponse {
+    pub settings: Option<TaskDispatchSettings>,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route(
+        "/swarm/dispatch/settings",
+        get(get_dispatch_settings).put(update_dispatch_settings),
+    )
+}
+
+#[instrument(
+    name = "swarm_dispatch.get_settings",
+    skip(state, ctx, params),
+    fields(org_id = %params.organization_id, user_id = %ctx.user.id)
+)]
+async fn get_dispatch_settings(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(params): Query<DispatchSettingsQuery>,
+) -> Result<Json<DispatchSettingsResponse>, ErrorResponse> {
+    ensure_member_access(state.pool(), params.organization_id, ctx.user.id).await?;
+
+    let settings = TaskDispatchSettingsRepository::new(state.pool())
+        .find(params.organization_id)
+        .await
+        .map_err(|error| settings_error(error, "failed to get dispatch settings"))?;
+
+    Ok(Json(DispatchSettingsResponse { settings }))
+}
+
+#[instrument(
+    name = "swarm_dispatch.update_settings",
+    skip(state, ctx, payload),
+    fields(org_id = %payload.organization_id, user_id = %ctx.user.id)
+)]
+async fn update_dispatch_settings(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Json(payload): Json<UpdateDispatchSettingsRequest>,
+) -> Result<Json<DispatchSettingsResponse>, ErrorResponse> {
+    ensure_admin_access(state.pool(), payload.organization_id, ctx.user.id).await?;
+
+    let default_executor = payload
+        .default_executor
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or(DEFAULT_EXECUTOR);
+    if default_executor.is_empty() {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "default_executor must not be empty",
+        ));
+    }
+    let mut required_node_labels: Vec<String> = payload
+        .required_node_labels
+        .iter()
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+        .collect();
+    required_node_labels.sort();
+    required_node_labels.dedup();
+
+    let settings = TaskDispatchSettingsRepository::new(state.pool())
+        .upsert(
+            payload.organization_id,
+            payload.auto_dispatch,
+            default_executor,
+            &required_node_labels,
+        )
+        .await
+        .map_err(|error| settings_error(error, "failed to update dispatch settings"))?;
+
+    Ok(Json(DispatchSettingsResponse {
+        settings: Some(settings),
+    }))
+}
+
+fn settings_error(error: sqlx::Error, context: &str) -> ErrorResponse {
+    tracing::error!(?error, "{context}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, context)
+}
@@ -14,11 +14,17 @@ use serde_json::Value;
 use tracing::instrument;
 use uuid::Uuid;
 
-use super::{error::ErrorResponse, organization_members::ensure_member_access};
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_admin_access, ensure_member_access, ensure_project_access},
+};
 use crate::{
     AppState,
     auth::RequestContext,
     db::{
+        lease_expiry_policies::{
+            LeaseExpiryAction, LeaseExpiryPolicy, LeaseExpiryPolicyRepository,
+        },
         node_local_projects::NodeLocalProjectRepository,
         swarm_projects::{
             CreateSwarmProjectData, LinkSwarmProjectNodeData, SwarmProject, SwarmProjectError,
@@ -77,6 +83,17 @@ pub struct UnlinkNodeRequest {
     pub node_id: Uuid,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UpdateLeaseExpiryPolicyRequest {
+    pub on_expiry: LeaseExpiryAction,
+    /// Times a task may move to another node before it fails (default: 3)
+    #[serde(default)]
+    pub max_retries: Option<i32>,
+    /// Wait before the first retry, doubled for every retry after it (default: 30)
+    #[serde(default)]
+    pub retry_backoff_seconds: Option<i32>,
+}
+
 // =====================
 // Response Types
 // =====================
@@ -101,6 +118,12 @@ pub struct ListSwarmProjectNodesResponse {
     pub nodes: Vec<SwarmProjectNode>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeaseExpiryPolicyResponse {
+    /// `None` when tasks whose lease expires are left for a node to claim
+    pub policy: Option<LeaseExpiryPolicy>,
+}
+
 // =====================
 // Router
 // =====================
@@ -129,6 +152,12 @@ pub fn router() -> Router<AppState> {
             "/swarm/projects/{project_id}/nodes/{node_id}",
             delete(unlink_node),
         )
+        .route(
+            "/swarm/projects/{project_id}/lease-policy",
+            get(get_lease_expiry_policy)
+                .put(update_lease_expiry_policy)
+                .delete(delete_lease_expiry_policy),
+        )
 }
 
 // =====================
@@ -623,3 +652,97 @@ async fn unlink_node(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+// =====================
+// Lease Expiry Policy Handlers
+// =====================
+
+#[instrument(
+    name = "swarm_projects.get_lease_policy",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn get_lease_expiry_policy(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<LeaseExpiryPolicyResponse>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+
+    let policy = LeaseExpiryPolicyRepository::new(state.pool())
+        .find(project_id)
+        .await
+        .map_err(|error| policy_error(error, "failed to get lease expiry policy"))?;
+
+    Ok(Json(LeaseExpiryPolicyResponse { policy }))
+}
+
+#[instrument(
+    name = "swarm_projects.update_lease_policy",
+    skip(state, ctx, payload),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn update_lease_expiry_policy(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<UpdateLeaseExpiryPolicyRequest>,
+) -> Result<Json<LeaseExpiryPolicyResponse>, ErrorResponse> {
+    let organization_id = ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    ensure_admin_access(state.pool(), organization_id, ctx.user.id).await?;
+
+    let max_retries = payload.max_retries.unwrap_or(3);
+    let retry_backoff_seconds = payload.retry_backoff_seconds.unwrap_or(30);
+    if max_retries < 0 || retry_backoff_seconds < 0 {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "max_retries and retry_backoff_seconds must not be negative",
+        ));
+    }
+
+    let policy = LeaseExpiryPolicyRepository::new(state.pool())
+        .upsert(
+            project_id,
+            payload.on_expiry,
+            max_retries,
+            retry_backoff_seconds,
+        )
+        .await
+        .map_err(|error| policy_error(error, "failed to update lease expiry policy"))?;
+
+    Ok(Json(LeaseExpiryPolicyResponse {
+        policy: Some(policy),
+    }))
+}
+
+#[instrument(
+    name = "swarm_projects.delete_lease_policy",
+    skip(state, ctx),
+    fields(project_id = %project_id, user_id = %ctx.user.id)
+)]
+async fn delete_lease_expiry_policy(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(project_id): Path<Uuid>,
+) -> Result<StatusCode, ErrorResponse> {
+    let organization_id = ensure_project_access(state.pool(), ctx.user.id, project_id).await?;
+    ensure_admin_access(state.pool(), organization_id, ctx.user.id).await?;
+
+    let deleted = LeaseExpiryPolicyRepository::new(state.pool())
+        .delete(project_id)
+        .await
+        .map_err(|error| policy_error(error, "failed to delete lease expiry policy"))?;
+    if !deleted {
+        return Err(ErrorResponse::new(
+            StatusCode::NOT_FOUND,
+            "project has no lease expiry policy",
+        ));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn policy_error(error: sqlx::Error, context: &str) -> ErrorResponse {
+    tracing::error!(?error, "{context}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, context)
+}
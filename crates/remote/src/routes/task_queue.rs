@@ -0,0 +1,252 @@
+//! Routes for the Hive task queue.
+//!
+//! Queued tasks wait in Postgres until a node linked to their project has room, and are handed
+//! out by [`crate::services::task_queue`]. Members can queue tasks and see the queue; only admins
+//! can change how many of a project's tasks may run at once.
+
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{Span, instrument};
+use uuid::Uuid;
+
+use super::{
+    error::ErrorResponse,
+    organization_members::{
+        ensure_admin_access, ensure_member_access, ensure_project_access, ensure_task_access,
+    },
+    tasks::DispatchTaskRequest,
+};
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::{
+        task_assignments::TaskAssignmentRepository,
+        task_dispatch_settings::TaskDispatchSettingsRepository,
+        task_queue::{QueueProject, QueuedTask, TaskQueueRepository},
+        tasks::SharedTaskRepository,
+    },
+    services::task_queue,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct TaskQueueQuery {
+    pub organization_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskQueueResponse {
+    /// In queue order within each project
+    pub tasks: Vec<QueuedTask>,
+    pub projects: Vec<QueueProject>,
+}
+
+/// Queue a task. Queueing a task that is already queued changes its priority, deadline and
+/// requirements.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct EnqueueTaskRequest {
+    /// Higher goes first (default: 0)
+    #[serde(default)]
+    pub priority: i32,
+    pub deadline: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub requirements: DispatchTaskRequest,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateProjectLimitRequest {
+    /// Most of the project's tasks that may run at once; `None` removes the limit
+    pub max_concurrent_tasks: Option<i32>,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/queue", get(get_task_queue))
+        .route(
+            "/queue/projects/{swarm_project_id}",
+            put(update_project_limit),
+        )
+        .route(
+            "/tasks/{task_id}/queue",
+            post(enqueue_task).delete(dequeue_task),
+        )
+}
+
+#[instrument(
+    name = "task_queue.get",
+    skip(state, ctx, params),
+    fields(org_id = %params.organization_id, user_id = %ctx.user.id)
+)]
+async fn get_task_queue(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(params): Query<TaskQueueQuery>,
+) -> Result<Json<TaskQueueResponse>, ErrorResponse> {
+    ensure_member_access(state.pool(), params.organization_id, ctx.user.id).await?;
+
+    let repo = TaskQueueRepository::new(state.pool());
+    let tasks = repo
+        .list(params.organization_id)
+        .await
+        .map_err(|error| queue_error(error, "failed to list queued tasks"))?;
+    let projects = repo
+        .projects(params.organization_id)
+        .await
+        .map_err(|error| queue_error(error, "failed to list queue projects"))?;
+
+    Ok(Json(TaskQueueResponse { tasks, projects }))
+}
+
+#[instrument(
+    name = "task_queue.enqueue",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, task_id = %task_id, org_id = tracing::field::Empty)
+)]
+async fn enqueue_task(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+    payload: Option<Json<EnqueueTaskRequest>>,
+) -> Result<Json<QueuedTask>, ErrorResponse> {
+    let pool = state.pool();
+    let organization_id = ensure_task_access(pool, ctx.user.id, task_id).await?;
+    Span::current().record("org_id", format_args!("{organization_id}"));
+    let payload = payload.map(|Json(request)| request).unwrap_or_default();
+
+    let task = SharedTaskRepository::new(pool)
+        .find_by_id(task_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to load shared task");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to load task")
+        })?
+        .ok_or_else(|| ErrorResponse::new(StatusCode::NOT_FOUND, "task not found"))?;
+    if task.source_node_id.is_some() {
+        return Err(ErrorResponse::new(
+            StatusCode::CONFLICT,
+            "task belongs to the node it was synced from",
+        ));
+    }
+    let Some(swarm_project_id) = task.swarm_project_id.or(task.project_id) else {
+        return Err(ErrorResponse::new(
+            StatusCode::CONFLICT,
+            "task is not in a swarm project",
+        ));
+    };
+    let active = TaskAssignmentRepository::new(pool)
+        .find_active_for_task(task_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to load task assignment");
+            ErrorResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to load assignment",
+            )
+        })?;
+    if active.is_some() {
+        return Err(ErrorResponse::new(
+            StatusCode::CONFLICT,
+            "task is already assigned to a node",
+        ));
+    }
+
+    let settings = TaskDispatchSettingsRepository::new(pool)
+        .find(organization_id)
+        .await
+        .map_err(|error| queue_error(error, "failed to load dispatch settings"))?;
+    let requirements = payload.requirements.into_requirements(settings.as_ref());
+
+    let queued = TaskQueueRepository::new(pool)
+        .enqueue(
+            task_id,
+            organization_id,
+            swarm_project_id,
+            payload.priority,
+            payload.deadline,
+            &requirements,
+            Some(ctx.user.id),
+        )
+        .await
+        .map_err(|error| queue_error(error, "failed to queue task"))?;
+
+    task_queue::wake(
+        pool.clone(),
+        state.node_connections().clone(),
+        organization_id,
+    );
+
+    Ok(Json(queued))
+}
+
+#[instrument(
+    name = "task_queue.dequeue",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, task_id = %task_id)
+)]
+async fn dequeue_task(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+) -> Result<StatusCode, ErrorResponse> {
+    ensure_task_access(state.pool(), ctx.user.id, task_id).await?;
+
+    let removed = TaskQueueRepository::new(state.pool())
+        .remove(task_id)
+        .await
+        .map_err(|error| queue_error(error, "failed to remove queued task"))?;
+    if !removed {
+        return Err(ErrorResponse::new(
+            StatusCode::NOT_FOUND,
+            "task is not queued",
+        ));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[instrument(
+    name = "task_queue.update_project_limit",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, swarm_project_id = %swarm_project_id)
+)]
+async fn update_project_limit(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(swarm_project_id): Path<Uuid>,
+    Json(payload): Json<UpdateProjectLimitRequest>,
+) -> Result<StatusCode, ErrorResponse> {
+    let pool = state.pool();
+    let organization_id = ensure_project_access(pool, ctx.user.id, swarm_project_id).await?;
+    ensure_admin_access(pool, organization_id, ctx.user.id).await?;
+
+    if payload.max_concurrent_tasks.is_some_and(|max| max < 1) {
+        return Err(ErrorResponse::new(
+            StatusCode::BAD_REQUEST,
+            "max_concurrent_tasks must be at least 1",
+        ));
+    }
+
+    TaskQueueRepository::new(pool)
+        .set_project_limit(swarm_project_id, payload.max_concurrent_tasks)
+        .await
+        .map_err(|error| queue_error(error, "failed to update project limit"))?;
+
+    // A raised limit may let queued tasks start
+    task_queue::wake(
+        pool.clone(),
+        state.node_connections().clone(),
+        organization_id,
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn queue_error(error: sqlx::Error, context: &str) -> ErrorResponse {
+    tracing::error!(?error, "{context}");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, context)
+}
@@ -19,8 +19,13 @@ use crate::{
     AppState,
     auth::RequestContext,
     db::{
+        assignment_hops::AssignmentHopRepository,
         organization_members,
         organizations::{MemberRole, OrganizationRepository},
+        task_dispatch_settings::{
+            DEFAULT_EXECUTOR, TaskDispatchSettings, TaskDispatchSettingsRepository,
+        },
+        task_queue::TaskQueueRepository,
         tasks::{
             AssignTaskData, CreateSharedTaskData, DeleteTaskData, SharedTask, SharedTaskError,
             SharedTaskRepository, SharedTaskWithUser, TaskStatus, UpdateSharedTaskData,
@@ -28,7 +33,8 @@ use crate::{
         },
         users::{UserData, UserRepository},
     },
-    nodes::ws::message::TaskDetails,
+    nodes::{DispatchError, DispatchRequirements, NodeError, TaskDispatcher},
+    services::task_queue,
 };
 
 pub fn router() -> Router<AppState> {
@@ -40,6 +46,8 @@ pub fn router() -> Router<AppState> {
         .route("/tasks/{task_id}", patch(update_shared_task))
         .route("/tasks/{task_id}", delete(delete_shared_task))
         .route("/tasks/{task_id}/assign", post(assign_task))
+        .route("/tasks/{task_id}/dispatch", post(dispatch_task))
+        .route("/tasks/{task_id}/hops", get(list_assignment_hops))
         .route("/tasks/{task_id}/executing-node", patch(set_executing_node))
         .route(
             "/tasks/{task_id}/stream-connection-info",
@@ -180,7 +188,7 @@ pub async fn get_shared_task(
 
 /// Create a shared task for a swarm project.
 ///
-/// Validates the request and caller's access, creates and persists a new shared task (or returns an existing one when both `source_task_id` and `source_node_id` match an existing task), optionally sets source-tracking information, and optionally attempts to dispatch an execution attempt when `start_attempt` is true.
+/// Validates the request and caller's access, creates and persists a new shared task (or returns an existing one when both `source_task_id` and `source_node_id` match an existing task), optionally sets source-tracking information, and queues the task for dispatch to an eligible node when `start_attempt` is true or the organization auto-dispatches new tasks.
 ///
 /// # Returns
 ///
@@ -285,8 +293,8 @@ pub async fn create_shared_task(
     let data = CreateSharedTaskData {
         organization_id,
         project_id: Some(swarm_project_id),
-        title: title.clone(),
-        description: description.clone(),
+        title,
+        description,
         status,
         creator_user_id: ctx.user.id,
         assignee_user_id,
@@ -327,52 +335,53 @@ pub async fn create_shared_task(
         }
     }
 
-    // Only dispatch to node if start_attempt flag is true
-    if start_attempt {
-        let dispatcher =
-            crate::nodes::TaskDispatcher::new(pool.clone(), state.node_connections().clone());
-
-        // Find a connected node first to get the correct default_branch
-        match dispatcher.find_connected_node(swarm_project_id).await {
-            Ok(connected_node) => {
-                // Use the connected node's branch to ensure consistency
-                let task_details = TaskDetails {
-                    title,
-                    description,
-                    executor: "CLAUDE_CODE".to_string(), // Default executor
-                    executor_variant: None,
-                    base_branch: connected_node.default_branch.clone(),
-                };
-
-                // Dispatch to the same node we got the branch from to avoid drift
-                match dispatcher
-                    .assign_task_to_node(task.task.id, &connected_node, task_details)
-                    .await
-                {
-                    Ok(result) => {
-                        tracing::info!(
-                            task_id = %task.task.id,
-                            assignment_id = %result.assignment_id,
-                            node_id = %result.node_id,
-                            "task dispatched to node"
-                        );
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            task_id = %task.task.id,
-                            error = %e,
-                            "failed to dispatch task to node - task created but not assigned"
-                        );
-                    }
-                }
+    // Queue for dispatch when asked to, or when the organization dispatches every new task.
+    // Tasks synced from a node stay with that node, so they are never auto-dispatched.
+    if start_attempt || source_node_id.is_none() {
+        let settings = match TaskDispatchSettingsRepository::new(pool)
+            .find(organization_id)
+            .await
+        {
+            Ok(settings) => settings,
+            Err(error) => {
+                tracing::warn!(?error, "failed to load dispatch settings, using defaults");
+                None
             }
-            Err(e) => {
-                tracing::warn!(
-                    task_id = %task.task.id,
-                    swarm_project_id = %swarm_project_id,
-                    error = %e,
-                    "no connected node available for dispatch, task created but not assigned"
-                );
+        };
+        let auto_dispatch = source_node_id.is_none()
+            && settings
+                .as_ref()
+                .is_some_and(|settings| settings.auto_dispatch);
+
+        if start_attempt || auto_dispatch {
+            let requirements = DispatchTaskRequest::default().into_requirements(settings.as_ref());
+            match TaskQueueRepository::new(pool)
+                .enqueue(
+                    task.task.id,
+                    organization_id,
+                    swarm_project_id,
+                    0,
+                    None,
+                    &requirements,
+                    Some(ctx.user.id),
+                )
+                .await
+            {
+                Ok(_) => {
+                    task_queue::wake(
+                        pool.clone(),
+                        state.node_connections().clone(),
+                        organization_id,
+                    );
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        task_id = %task.task.id,
+                        swarm_project_id = %swarm_project_id,
+                        ?error,
+                        "failed to queue task for dispatch - task created but not queued"
+                    );
+                }
             }
         }
     }
@@ -651,6 +660,113 @@ pub async fn set_executing_node(
     }
 }
 
+/// Dispatch a task to the best node linked to its swarm project.
+///
+/// The Hive picks among connected nodes that have the executor installed, match the requested
+/// os, arch, minimum version and labels, and have room under `max_concurrent_tasks`, preferring
+/// the least loaded. Requirements left out of the request come from the organization's dispatch
+/// settings. Tasks synced from a node cannot be dispatched to another node.
+#[instrument(
+    name = "tasks.dispatch_task",
+    skip(state, ctx, payload),
+    fields(user_id = %ctx.user.id, task_id = %task_id, org_id = tracing::field::Empty)
+)]
+pub async fn dispatch_task(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+    payload: Option<Json<DispatchTaskRequest>>,
+) -> Response {
+    let pool = state.pool();
+    let organization_id = match ensure_task_access(pool, ctx.user.id, task_id).await {
+        Ok(org_id) => {
+            Span::current().record("org_id", format_args!("{org_id}"));
+            org_id
+        }
+        Err(error) => return error.into_response(),
+    };
+
+    let settings = match TaskDispatchSettingsRepository::new(pool)
+        .find(organization_id)
+        .await
+    {
+        Ok(settings) => settings,
+        Err(error) => {
+            tracing::error!(?error, "failed to load dispatch settings");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "failed to load dispatch settings" })),
+            )
+                .into_response();
+        }
+    };
+    let requirements = payload
+        .map(|Json(request)| request)
+        .unwrap_or_default()
+        .into_requirements(settings.as_ref());
+
+    let dispatcher = TaskDispatcher::new(pool.clone(), state.node_connections().clone());
+    match dispatcher.dispatch_task(task_id, &requirements).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(error) => dispatch_error_response(error),
+    }
+}
+
+/// Every node the task was dispatched to, oldest first, with what became of hops whose lease
+/// expired.
+#[instrument(
+    name = "tasks.list_assignment_hops",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, task_id = %task_id)
+)]
+pub async fn list_assignment_hops(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(task_id): Path<Uuid>,
+) -> Response {
+    let pool = state.pool();
+    if let Err(error) = ensure_task_access(pool, ctx.user.id, task_id).await {
+        return error.into_response();
+    }
+
+    match AssignmentHopRepository::new(pool)
+        .list_for_task(task_id)
+        .await
+    {
+        Ok(hops) => (StatusCode::OK, Json(hops)).into_response(),
+        Err(error) => {
+            tracing::error!(?error, "failed to list assignment hops");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "failed to list assignment hops" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+fn dispatch_error_response(error: DispatchError) -> Response {
+    let status = match &error {
+        DispatchError::TaskNotFound => StatusCode::NOT_FOUND,
+        DispatchError::TaskOwnedByNode
+        | DispatchError::NoNodeForProject
+        | DispatchError::NoEligibleNode(_)
+        | DispatchError::NodeService(NodeError::TaskAlreadyAssigned) => StatusCode::CONFLICT,
+        DispatchError::NodeNotConnected | DispatchError::SendFailed(_) => {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        DispatchError::NodeService(_) => {
+            tracing::error!(?error, "failed to dispatch task");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "failed to dispatch task" })),
+            )
+                .into_response();
+        }
+    };
+    (status, Json(json!({ "error": error.to_string() }))).into_response()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BulkSharedTasksResponse {
     pub tasks: Vec<crate::db::tasks::SharedTaskActivityPayload>,
@@ -669,8 +785,9 @@ pub struct CreateSharedTaskRequest {
     pub description: Option<String>,
     pub status: Option<TaskStatus>,
     pub assignee_user_id: Option<Uuid>,
-    /// Whether to automatically start a task attempt on the linked node.
-    /// If false or not provided, the task is created but not dispatched.
+    /// Whether to queue the task for an attempt on an eligible linked node right away.
+    /// If false or not provided, the task is only queued when the organization
+    /// auto-dispatches new tasks.
     #[serde(default)]
     pub start_attempt: bool,
     /// Original local task ID from source node, used for re-sync duplicate detection.
@@ -705,6 +822,44 @@ pub struct AssignSharedTaskRequest {
     pub version: Option<i64>,
 }
 
+/// What a dispatched task needs from its node. Unset fields fall back to the organization's
+/// dispatch settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DispatchTaskRequest {
+    /// Executor to run the task with (e.g. "CLAUDE_CODE")
+    pub executor: Option<String>,
+    pub executor_variant: Option<String>,
+    pub os: Option<String>,
+    pub arch: Option<String>,
+    /// Oldest Vibe Kanban version the node may run
+    pub min_version: Option<String>,
+    /// Labels the node must carry. Replaces the organization's required labels when set.
+    pub node_labels: Option<Vec<String>>,
+}
+
+impl DispatchTaskRequest {
+    pub(crate) fn into_requirements(
+        self,
+        settings: Option<&TaskDispatchSettings>,
+    ) -> DispatchRequirements {
+        DispatchRequirements {
+            executor: self
+                .executor
+                .or_else(|| settings.map(|s| s.default_executor.clone()))
+                .unwrap_or_else(|| DEFAULT_EXECUTOR.to_string()),
+            executor_variant: self.executor_variant,
+            os: self.os,
+            arch: self.arch,
+            min_version: self.min_version,
+            node_labels: self
+                .node_labels
+                .or_else(|| settings.map(|s| s.required_node_labels.clone()))
+                .unwrap_or_default(),
+            exclude_node_ids: Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteSharedTaskRequest {
     pub version: Option<i64>,
@@ -0,0 +1,80 @@
+//! Background service keeping this replica's place in the Hive cluster.
+//!
+//! Checks the connection holding the replica's advisory locks, reconnecting and
+//! taking the node locks back when it broke. Nodes another replica took in the
+//! meantime have their sessions here closed. Also drops routed messages that
+//! were never taken, e.g. because their replica went away.
+
+use std::{sync::Arc, time::Duration as StdDuration};
+
+use chrono::{Duration, Utc};
+use tokio::time::{self, MissedTickBehavior};
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    db::node_sessions::NodeSessionRepository,
+    nodes::{ConnectionManager, HiveCluster},
+};
+
+/// Configuration for the Hive cluster service.
+#[derive(Debug, Clone)]
+pub struct HiveClusterConfig {
+    /// How often to check the lock connection (default: 5 seconds)
+    pub check_interval: StdDuration,
+    /// How long a routed message waits for its replica (default: 5 minutes)
+    pub routed_message_ttl: Duration,
+}
+
+impl Default for HiveClusterConfig {
+    fn default() -> Self {
+        Self {
+            check_interval: StdDuration::from_secs(5),
+            routed_message_ttl: Duration::minutes(5),
+        }
+    }
+}
+
+/// Spawn the Hive cluster service as a background task.
+pub fn spawn_hive_cluster_service(
+    cluster: Arc<HiveCluster>,
+    connections: ConnectionManager,
+    config: Option<HiveClusterConfig>,
+) {
+    let config = config.unwrap_or_default();
+
+    tokio::spawn(async move {
+        let mut interval = time::interval(config.check_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+
+            match cluster.check_locks().await {
+                Ok(lost) if !lost.is_empty() => {
+                    warn!(
+                        count = lost.len(),
+                        "Other replicas took nodes while locks were down"
+                    );
+                    connections.evict_local(&lost).await;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    error!(error = ?e, "Failed to restore Hive cluster locks");
+                }
+            }
+
+            match NodeSessionRepository::new(cluster.pool())
+                .prune_routed(Utc::now() - config.routed_message_ttl)
+                .await
+            {
+                Ok(pruned) if pruned > 0 => {
+                    info!(pruned, "Dropped routed messages nobody took");
+                }
+                Ok(_) => debug!("No stale routed messages"),
+                Err(e) => {
+                    error!(error = ?e, "Failed to prune routed messages");
+                }
+            }
+        }
+    });
+}
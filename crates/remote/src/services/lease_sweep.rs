@@ -4,14 +4,44 @@
 //! strictly-higher fencing token (so the prior holder's late writes are stale
 //! per ADR-0009 / SC3) and clears the lease so a subsequent `try_claim` (or a
 //! dispatcher) can take it. The token bump alone is what bounces a partitioned
-//! writer; the lease is freed but NOT reassigned here.
+//! writer.
+//!
+//! The lease is freed but NOT reassigned here, unless the task's swarm project
+//! has a lease expiry policy. Then the reclaimed assignment is failed and the
+//! task either queued for another node, after a backoff and never on a node
+//! whose lease on it expired, or given up on with an email to its assignee. A
+//! task is also given up on once every node linked to its project let a lease
+//! on it expire, as no node is left to retry on.
+//! Each outcome is recorded on the task's assignment hops.
+
+use std::{sync::Arc, time::Duration as StdDuration};
 
+use chrono::{Duration, Utc};
 use sqlx::PgPool;
-use std::time::Duration as StdDuration;
+use thiserror::Error;
 use tokio::time::{self, MissedTickBehavior};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
 
-use crate::db::task_assignments::{TaskAssignmentError, TaskAssignmentRepository};
+use crate::{
+    db::{
+        assignment_hops::{AssignmentHopRepository, HopResolution},
+        lease_expiry_policies::{
+            LeaseExpiryAction, LeaseExpiryPolicy, LeaseExpiryPolicyRepository,
+        },
+        nodes::{NodeDbError, NodeRepository},
+        swarm_projects::{SwarmProjectError, SwarmProjectRepository},
+        task_assignments::{ReclaimedLease, TaskAssignmentError, TaskAssignmentRepository},
+        task_dispatch_settings::{DEFAULT_EXECUTOR, TaskDispatchSettingsRepository},
+        task_queue::TaskQueueRepository,
+        tasks::{SharedTaskError, SharedTaskRepository},
+    },
+    mail::Mailer,
+    nodes::DispatchRequirements,
+};
+
+/// Longest wait before a retry, however many retries came before it
+const MAX_RETRY_BACKOFF_SECONDS: i64 = 60 * 60;
 
 /// Configuration for the lease sweep service.
 #[derive(Debug, Clone)]
@@ -29,12 +59,64 @@ impl Default for LeaseSweepConfig {
     }
 }
 
+#[derive(Debug, Error)]
+enum ExpiryError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Task(#[from] SharedTaskError),
+    #[error(transparent)]
+    Assignment(#[from] TaskAssignmentError),
+    #[error(transparent)]
+    Node(#[from] NodeDbError),
+    #[error(transparent)]
+    Project(#[from] SwarmProjectError),
+}
+
+/// What a project's policy does with a task whose lease expired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryDecision {
+    /// Queue the task for another node once the backoff has passed
+    Retry {
+        backoff: Duration,
+    },
+    Fail,
+}
+
+/// Decide what happens to a task whose lease expired after it was already retried `retries`
+/// times. The backoff doubles with every retry.
+pub fn expiry_decision(policy: &LeaseExpiryPolicy, retries: i64) -> ExpiryDecision {
+    match policy.on_expiry {
+        LeaseExpiryAction::Retry if retries < i64::from(policy.max_retries) => {
+            let seconds = i64::from(policy.retry_backoff_seconds)
+                .saturating_mul(2_i64.saturating_pow(retries.clamp(0, 62) as u32))
+                .min(MAX_RETRY_BACKOFF_SECONDS);
+            ExpiryDecision::Retry {
+                backoff: Duration::seconds(seconds),
+            }
+        }
+        LeaseExpiryAction::Retry | LeaseExpiryAction::Fail => ExpiryDecision::Fail,
+    }
+}
+
+/// Whether every node linked to a project let a lease on the task expire, leaving none to retry
+/// on. A project without nodes has none left either.
+pub fn no_node_left(linked_node_ids: &[Uuid], expired_node_ids: &[Uuid]) -> bool {
+    linked_node_ids
+        .iter()
+        .all(|node_id| expired_node_ids.contains(node_id))
+}
+
 /// Spawn the lease sweep service as a background task.
 ///
 /// Runs periodically and reclaims expired leases, bumping each fencing token
 /// via `nextval('node_fencing_token_seq')` so the prior holder's late writes
-/// are bounced.
-pub fn spawn_lease_sweep_service(pool: PgPool, config: Option<LeaseSweepConfig>) {
+/// are bounced, then applies the lease expiry policy of each task's project.
+pub fn spawn_lease_sweep_service(
+    pool: PgPool,
+    mailer: Arc<dyn Mailer>,
+    config: Option<LeaseSweepConfig>,
+) {
     let config = config.unwrap_or_default();
 
     tokio::spawn(async move {
@@ -44,7 +126,7 @@ pub fn spawn_lease_sweep_service(pool: PgPool, config: Option<LeaseSweepConfig>)
         loop {
             interval.tick().await;
 
-            match sweep_expired(&pool).await {
+            match sweep_expired(&pool, mailer.as_ref()).await {
                 Ok(reclaimed) => {
                     if reclaimed > 0 {
                         info!(reclaimed = reclaimed, "Reclaimed expired Hive leases");
@@ -61,7 +143,7 @@ pub fn spawn_lease_sweep_service(pool: PgPool, config: Option<LeaseSweepConfig>)
 }
 
 /// Reclaim expired leases from the database.
-async fn sweep_expired(pool: &PgPool) -> Result<u64, sqlx::Error> {
+async fn sweep_expired(pool: &PgPool, mailer: &dyn Mailer) -> Result<u64, sqlx::Error> {
     let reclaimed = TaskAssignmentRepository::new(pool)
         .reclaim_expired_leases()
         .await
@@ -71,16 +153,230 @@ async fn sweep_expired(pool: &PgPool) -> Result<u64, sqlx::Error> {
             _ => sqlx::Error::Protocol("unexpected TaskAssignmentError variant".into()),
         })?;
 
+    for lease in &reclaimed {
+        if let Err(e) = apply_expiry_policy(pool, mailer, lease).await {
+            error!(
+                error = ?e,
+                assignment_id = %lease.assignment_id,
+                task_id = %lease.task_id,
+                "Failed to apply lease expiry policy"
+            );
+        }
+    }
+
     Ok(reclaimed.len() as u64)
 }
 
+/// Retry or fail the task of a reclaimed lease, as its project's policy says. Leaves the lease
+/// free for a claim when the project has no policy.
+async fn apply_expiry_policy(
+    pool: &PgPool,
+    mailer: &dyn Mailer,
+    lease: &ReclaimedLease,
+) -> Result<(), ExpiryError> {
+    let Some(task) = SharedTaskRepository::new(pool)
+        .find_by_id(lease.task_id)
+        .await?
+    else {
+        return Ok(());
+    };
+    // Tasks synced from a node stay with that node (SC1)
+    if task.source_node_id.is_some() {
+        return Ok(());
+    }
+    let Some(swarm_project_id) = task.swarm_project_id.or(task.project_id) else {
+        return Ok(());
+    };
+    let Some(policy) = LeaseExpiryPolicyRepository::new(pool)
+        .find(swarm_project_id)
+        .await?
+    else {
+        return Ok(());
+    };
+
+    if !TaskAssignmentRepository::new(pool)
+        .fail_reclaimed(lease.assignment_id, lease.fencing_token)
+        .await?
+    {
+        debug!(
+            assignment_id = %lease.assignment_id,
+            "Reclaimed lease was claimed again before its policy applied"
+        );
+        return Ok(());
+    }
+
+    let hops = AssignmentHopRepository::new(pool);
+    let retries = hops.count_retries(task.id).await?;
+    let mut expired_node_ids = hops.expired_nodes(task.id).await?;
+    if !expired_node_ids.contains(&lease.node_id) {
+        expired_node_ids.push(lease.node_id);
+    }
+    let decision = match expiry_decision(&policy, retries) {
+        ExpiryDecision::Retry { .. }
+            if no_node_left(
+                &SwarmProjectRepository::get_linked_node_ids(pool, swarm_project_id).await?,
+                &expired_node_ids,
+            ) =>
+        {
+            info!(
+                task_id = %task.id,
+                %swarm_project_id,
+                "Every node of the project let a lease on the task expire; not retrying"
+            );
+            ExpiryDecision::Fail
+        }
+        decision => decision,
+    };
+    match decision {
+        ExpiryDecision::Retry { backoff } => {
+            let retry_at = Utc::now() + backoff;
+            hops.record_expiry(
+                task.id,
+                lease.assignment_id,
+                lease.node_id,
+                HopResolution::Retried,
+                Some(retry_at),
+            )
+            .await?;
+
+            let mut requirements = match hops.last_requirements(task.id).await? {
+                Some(requirements) => requirements,
+                None => {
+                    let settings = TaskDispatchSettingsRepository::new(pool)
+                        .find(task.organization_id)
+                        .await?;
+                    DispatchRequirements {
+                        executor: settings
+                            .as_ref()
+                            .map(|s| s.default_executor.clone())
+                            .unwrap_or_else(|| DEFAULT_EXECUTOR.to_string()),
+                        node_labels: settings.map(|s| s.required_node_labels).unwrap_or_default(),
+                        ..Default::default()
+                    }
+                }
+            };
+            requirements.exclude_node_ids = expired_node_ids;
+            // Tasks dispatched without queueing are retried at the queue's default priority
+            let place = hops.last_queue_place(task.id).await?;
+
+            TaskQueueRepository::new(pool)
+                .enqueue_retry(
+                    task.id,
+                    task.organization_id,
+                    swarm_project_id,
+                    place.map_or(0, |place| place.priority),
+                    place.and_then(|place| place.deadline),
+                    &requirements,
+                    retry_at,
+                )
+                .await?;
+            info!(
+                task_id = %task.id,
+                node_id = %lease.node_id,
+                retry = retries + 1,
+                %retry_at,
+                "Queued task for another node after its lease expired"
+            );
+        }
+        ExpiryDecision::Fail => {
+            hops.record_expiry(
+                task.id,
+                lease.assignment_id,
+                lease.node_id,
+                HopResolution::Failed,
+                None,
+            )
+            .await?;
+            warn!(
+                task_id = %task.id,
+                node_id = %lease.node_id,
+                retries,
+                "Gave up on task after its lease expired"
+            );
+
+            let Some(contact) = hops.failure_contact(task.id).await? else {
+                return Ok(());
+            };
+            let node_name = NodeRepository::new(pool)
+                .find_by_id(lease.node_id)
+                .await?
+                .map(|node| node.name)
+                .unwrap_or_else(|| lease.node_id.to_string());
+            mailer
+                .send_task_lease_failure(
+                    &contact.organization_name,
+                    &contact.email,
+                    &contact.task_title,
+                    &node_name,
+                    retries,
+                )
+                .await;
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn policy(on_expiry: LeaseExpiryAction, max_retries: i32) -> LeaseExpiryPolicy {
+        LeaseExpiryPolicy {
+            swarm_project_id: Uuid::nil(),
+            on_expiry,
+            max_retries,
+            retry_backoff_seconds: 30,
+            updated_at: Utc::now(),
+        }
+    }
+
     #[test]
     fn test_default_config() {
         let config = LeaseSweepConfig::default();
         assert_eq!(config.sweep_interval.as_secs(), 10);
     }
+
+    #[test]
+    fn test_expiry_decision_backs_off_then_fails() {
+        let retry = policy(LeaseExpiryAction::Retry, 3);
+        assert_eq!(
+            expiry_decision(&retry, 0),
+            ExpiryDecision::Retry {
+                backoff: Duration::seconds(30)
+            }
+        );
+        assert_eq!(
+            expiry_decision(&retry, 2),
+            ExpiryDecision::Retry {
+                backoff: Duration::seconds(120)
+            }
+        );
+        assert_eq!(expiry_decision(&retry, 3), ExpiryDecision::Fail);
+
+        let patient = policy(LeaseExpiryAction::Retry, 100);
+        assert_eq!(
+            expiry_decision(&patient, 90),
+            ExpiryDecision::Retry {
+                backoff: Duration::seconds(MAX_RETRY_BACKOFF_SECONDS)
+            }
+        );
+
+        assert_eq!(
+            expiry_decision(&policy(LeaseExpiryAction::Fail, 3), 0),
+            ExpiryDecision::Fail
+        );
+    }
+
+    #[test]
+    fn test_no_node_left_once_every_linked_node_expired() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let unlinked = Uuid::new_v4();
+
+        assert!(!no_node_left(&[a, b], &[a]));
+        assert!(!no_node_left(&[a, b], &[a, unlinked]));
+        assert!(no_node_left(&[a, b], &[b, a]));
+        assert!(no_node_left(&[], &[a]));
+    }
 }
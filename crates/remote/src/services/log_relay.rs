@@ -0,0 +1,277 @@
+//! In-process fan-out of node output logs to relay viewers.
+//!
+//! Logs are published once, where they are ingested from the node WebSocket,
+//! and every viewer of the assignment receives them from a bounded broadcast
+//! channel instead of polling `node_task_output_logs`. A viewer that falls
+//! behind gets `RecvError::Lagged` and catches up from the database from its
+//! own cursor, so a slow browser never holds up ingest or other viewers.
+//!
+//! With several Hive replicas, the replica that ingested the logs also sends a
+//! NOTIFY on the relay channel. `db::LogRelayListener` on the other replicas
+//! fetches the announced rows once and fans them out to its own viewers.
+//! Notifications sent while the listener was reconnecting are lost, so every
+//! viewer catches up from the database once it listens again.
+
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::sync::broadcast::{
+    self,
+    error::{RecvError, TryRecvError},
+};
+use uuid::Uuid;
+
+use crate::db::task_output_logs::TaskOutputLog;
+
+/// Announces logs ingested by one replica to the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRelayNotification {
+    /// The replica that ingested the logs and already fanned them out
+    pub origin: Uuid,
+    pub assignment_id: Uuid,
+    pub first_id: i64,
+    pub last_id: i64,
+}
+
+/// Fans out node output logs to relay viewers, one channel per watched assignment.
+#[derive(Clone)]
+pub struct LogRelay {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    instance_id: Uuid,
+    channel: String,
+    capacity: usize,
+    assignments: DashMap<Uuid, broadcast::Sender<Relayed>>,
+}
+
+/// What goes through an assignment's channel
+#[derive(Clone)]
+enum Relayed {
+    Log(Arc<TaskOutputLog>),
+    /// Logs may have been missed; viewers catch up from the database
+    Resync,
+}
+
+impl LogRelay {
+    pub fn new(channel: String, capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                instance_id: Uuid::new_v4(),
+                channel,
+                capacity: capacity.max(1),
+                assignments: DashMap::new(),
+            }),
+        }
+    }
+
+    /// Identifies this replica in relay notifications.
+    pub fn instance_id(&self) -> Uuid {
+        self.inner.instance_id
+    }
+
+    /// The NOTIFY channel replicas announce ingested logs on.
+    pub fn channel(&self) -> &str {
+        &self.inner.channel
+    }
+
+    /// Start receiving the assignment's logs as they are published.
+    pub fn subscribe(&self, assignment_id: Uuid) -> LogSubscription {
+        let receiver = self
+            .inner
+            .assignments
+            .entry(assignment_id)
+            .or_insert_with(|| broadcast::channel(self.inner.capacity).0)
+            .subscribe();
+
+        LogSubscription {
+            assignment_id,
+            receiver,
+            inner: Arc::clone(&self.inner),
+        }
+    }
+
+    /// Whether anyone on this replica is watching the assignment.
+    pub fn has_subscribers(&self, assignment_id: Uuid) -> bool {
+        self.inner
+            .assignments
+            .get(&assignment_id)
+            .is_some_and(|sender| sender.receiver_count() > 0)
+    }
+
+    /// Hand logs to this replica's viewers. `logs` must be in id order.
+    pub fn fan_out(&self, logs: impl IntoIterator<Item = TaskOutputLog>) {
+        for log in logs {
+            let Some(sender) = self.inner.assignments.get(&log.assignment_id) else {
+                continue;
+            };
+            // Only fails when the last viewer just left
+            let _ = sender.send(Relayed::Log(Arc::new(log)));
+        }
+    }
+
+    /// Have every viewer on this replica catch up from the database, after logs from other
+    /// replicas may have been missed.
+    pub fn resync(&self) {
+        for sender in self.inner.assignments.iter() {
+            let _ = sender.send(Relayed::Resync);
+        }
+    }
+
+    /// Hand freshly ingested logs of one assignment to this replica's viewers and announce
+    /// them to the other replicas.
+    pub async fn publish(&self, pool: &PgPool, assignment_id: Uuid, logs: Vec<TaskOutputLog>) {
+        let (Some(first_id), Some(last_id)) = (
+            logs.first().map(|log| log.id),
+            logs.last().map(|log| log.id),
+        ) else {
+            return;
+        };
+
+        self.fan_out(logs);
+
+        let notification = LogRelayNotification {
+            origin: self.inner.instance_id,
+            assignment_id,
+            first_id,
+            last_id,
+        };
+        let payload = match serde_json::to_string(&notification) {
+            Ok(payload) => payload,
+            Err(error) => {
+                tracing::warn!(?error, "failed to encode log relay notification");
+                return;
+            }
+        };
+        if let Err(error) = sqlx::query!("SELECT pg_notify($1, $2)", self.inner.channel, payload)
+            .execute(pool)
+            .await
+        {
+            tracing::warn!(?error, %assignment_id, "failed to announce logs to other replicas");
+        }
+    }
+}
+
+/// A viewer's feed of one assignment's logs. Stops the assignment's channel when the last
+/// viewer leaves.
+pub struct LogSubscription {
+    assignment_id: Uuid,
+    receiver: broadcast::Receiver<Relayed>,
+    inner: Arc<Inner>,
+}
+
+impl LogSubscription {
+    /// The next published entry. `RecvError::Lagged` means entries were dropped because the
+    /// viewer fell behind, or with a count of 0 that entries from other replicas may have been
+    /// missed; catch up from the database before receiving again.
+    pub async fn recv(&mut self) -> Result<Arc<TaskOutputLog>, RecvError> {
+        match self.receiver.recv().await? {
+            Relayed::Log(log) => Ok(log),
+            Relayed::Resync => Err(RecvError::Lagged(0)),
+        }
+    }
+
+    /// An entry that is already buffered, without waiting. Lags like `recv`.
+    pub fn try_recv(&mut self) -> Result<Arc<TaskOutputLog>, TryRecvError> {
+        match self.receiver.try_recv()? {
+            Relayed::Log(log) => Ok(log),
+            Relayed::Resync => Err(TryRecvError::Lagged(0)),
+        }
+    }
+}
+
+impl Drop for LogSubscription {
+    fn drop(&mut self) {
+        // Our own receiver is still alive here
+        self.inner
+            .assignments
+            .remove_if(&self.assignment_id, |_, sender| {
+                sender.receiver_count() <= 1
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn log(assignment_id: Uuid, id: i64) -> TaskOutputLog {
+        TaskOutputLog {
+            id,
+            assignment_id,
+            output_type: "stdout".to_string(),
+            content: format!("line {id}"),
+            timestamp: Utc::now(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_reaches_only_the_assignments_viewers() {
+        let relay = LogRelay::new("task_output_logs".to_string(), 8);
+        let watched = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        let mut first = relay.subscribe(watched);
+        let mut second = relay.subscribe(watched);
+        assert!(!relay.has_subscribers(other));
+
+        relay.fan_out([log(other, 1), log(watched, 2)]);
+
+        assert_eq!(first.recv().await.unwrap().id, 2);
+        assert_eq!(second.recv().await.unwrap().id, 2);
+        assert!(matches!(first.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[tokio::test]
+    async fn test_slow_viewer_lags_without_blocking_others() {
+        let relay = LogRelay::new("task_output_logs".to_string(), 2);
+        let assignment_id = Uuid::new_v4();
+
+        let mut slow = relay.subscribe(assignment_id);
+        let mut fast = relay.subscribe(assignment_id);
+
+        relay.fan_out([log(assignment_id, 1), log(assignment_id, 2)]);
+        assert_eq!(fast.recv().await.unwrap().id, 1);
+        assert_eq!(fast.recv().await.unwrap().id, 2);
+
+        relay.fan_out([log(assignment_id, 3)]);
+        assert_eq!(fast.recv().await.unwrap().id, 3);
+        assert!(matches!(slow.recv().await, Err(RecvError::Lagged(1))));
+        assert_eq!(slow.recv().await.unwrap().id, 2);
+    }
+
+    #[tokio::test]
+    async fn test_resync_has_viewers_catch_up_after_buffered_logs() {
+        let relay = LogRelay::new("task_output_logs".to_string(), 8);
+        let assignment_id = Uuid::new_v4();
+        let mut viewer = relay.subscribe(assignment_id);
+
+        relay.fan_out([log(assignment_id, 1)]);
+        relay.resync();
+
+        assert_eq!(viewer.recv().await.unwrap().id, 1);
+        assert!(matches!(viewer.recv().await, Err(RecvError::Lagged(0))));
+        assert!(matches!(viewer.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn test_last_viewer_leaving_drops_the_channel() {
+        let relay = LogRelay::new("task_output_logs".to_string(), 8);
+        let assignment_id = Uuid::new_v4();
+
+        let first = relay.subscribe(assignment_id);
+        let second = relay.subscribe(assignment_id);
+        drop(first);
+        assert!(relay.has_subscribers(assignment_id));
+
+        drop(second);
+        assert!(!relay.has_subscribers(assignment_id));
+        assert!(relay.inner.assignments.is_empty());
+    }
+}
@@ -1,9 +1,17 @@
 //! Business logic services for the Hive server.
 
+pub mod hive_cluster;
 pub mod lease_sweep;
 pub mod log_cache;
+pub mod log_relay;
 pub mod stale_cleanup;
+pub mod swarm_approvals;
+pub mod task_queue;
 
+pub use hive_cluster::{HiveClusterConfig, spawn_hive_cluster_service};
 pub use lease_sweep::{LeaseSweepConfig, spawn_lease_sweep_service};
 pub use log_cache::LogCache;
+pub use log_relay::{LogRelay, LogSubscription};
 pub use stale_cleanup::{StaleCleanupConfig, spawn_stale_cleanup_service};
+pub use swarm_approvals::{ApprovalEscalationConfig, spawn_approval_escalation_service};
+pub use task_queue::{TaskQueueConfig, spawn_task_queue_service};
@@ -0,0 +1,193 @@
+//! Delivery of approval answers to nodes, and escalation of approvals nobody answers.
+//!
+//! Answers go to the node that raised the approval over its WebSocket. Answers for a node that
+//! is offline stay undelivered and are sent when it reconnects, as long as the node is still
+//! waiting. The escalation sweep notifies the organization's escalation contact once a request
+//! has waited past the configured timeout, and applies the default decision when one is set.
+
+use std::{sync::Arc, time::Duration as StdDuration};
+
+use sqlx::PgPool;
+use tokio::time::{self, MissedTickBehavior};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::swarm_approvals::{SwarmApproval, SwarmApprovalError, SwarmApprovalRepository},
+    mail::Mailer,
+    nodes::ws::{
+        ConnectionManager,
+        message::{ApprovalResponseMessage, HiveMessage},
+    },
+};
+
+/// Configuration for the approval escalation service.
+#[derive(Debug, Clone)]
+pub struct ApprovalEscalationConfig {
+    /// How often to check for approvals to escalate (default: 30 seconds)
+    pub sweep_interval: StdDuration,
+}
+
+impl Default for ApprovalEscalationConfig {
+    fn default() -> Self {
+        Self {
+            sweep_interval: StdDuration::from_secs(30),
+        }
+    }
+}
+
+/// Send an answered approval to the node that raised it. Returns whether the node received it;
+/// answers for offline nodes are delivered on reconnect.
+pub async fn deliver_response(
+    pool: &PgPool,
+    connections: &ConnectionManager,
+    approval: &SwarmApproval,
+    responded_by: Option<String>,
+) -> bool {
+    let message = HiveMessage::ApprovalResponse(ApprovalResponseMessage {
+        approval_id: approval.approval_id.clone(),
+        local_execution_process_id: approval.local_execution_process_id,
+        status: approval.status.clone(),
+        answers: approval.answers.clone(),
+        responded_by,
+    });
+    if let Err(e) = connections.send_to_node(approval.node_id, message).await {
+        debug!(
+            approval_id = %approval.approval_id,
+            node_id = %approval.node_id,
+            error = %e,
+            "approval answer not delivered; will retry on reconnect"
+        );
+        return false;
+    }
+    if let Err(e) = SwarmApprovalRepository::new(pool)
+        .mark_delivered(approval.id)
+        .await
+    {
+        warn!(error = ?e, approval_id = %approval.approval_id, "failed to mark approval delivered");
+    }
+    true
+}
+
+/// Send a reconnected node the answers given while it was offline
+pub async fn deliver_pending_responses(
+    pool: &PgPool,
+    connections: &ConnectionManager,
+    node_id: Uuid,
+) -> Result<usize, SwarmApprovalError> {
+    let undelivered = SwarmApprovalRepository::new(pool)
+        .find_undelivered(node_id)
+        .await?;
+    let mut delivered = 0;
+    for approval in &undelivered {
+        if deliver_response(pool, connections, approval, None).await {
+            delivered += 1;
+        }
+    }
+    Ok(delivered)
+}
+
+/// Spawn the approval escalation service as a background task.
+pub fn spawn_approval_escalation_service(
+    pool: PgPool,
+    connections: ConnectionManager,
+    mailer: Arc<dyn Mailer>,
+    server_public_base_url: String,
+    config: Option<ApprovalEscalationConfig>,
+) {
+    let config = config.unwrap_or_default();
+
+    tokio::spawn(async move {
+        let mut interval = time::interval(config.sweep_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+
+            // With several replicas, one of them escalates, so each approval is emailed once
+            if let Some(cluster) = connections.cluster()
+                && !cluster.leads("approval_escalation").await
+            {
+                continue;
+            }
+
+            if let Err(e) = sweep(
+                &pool,
+                &connections,
+                mailer.as_ref(),
+                &server_public_base_url,
+            )
+            .await
+            {
+                error!(error = ?e, "Failed to sweep swarm approvals");
+            }
+        }
+    });
+}
+
+async fn sweep(
+    pool: &PgPool,
+    connections: &ConnectionManager,
+    mailer: &dyn Mailer,
+    server_public_base_url: &str,
+) -> Result<(), SwarmApprovalError> {
+    let repo = SwarmApprovalRepository::new(pool);
+
+    let expired = repo.expire_timed_out().await?;
+    if expired > 0 {
+        debug!(expired, "Marked swarm approvals past their node timeout");
+    }
+
+    for due in repo.find_due_for_escalation().await? {
+        let approval = &due.approval;
+        let approvals_url = format!(
+            "{server_public_base_url}/approvals?organization_id={}",
+            approval.organization_id
+        );
+        mailer
+            .send_approval_escalation(
+                &due.organization_name,
+                &due.escalate_to_email,
+                &approval.task_title,
+                &approval.tool_name,
+                &approval.node_name,
+                &approvals_url,
+            )
+            .await;
+        repo.mark_escalated(approval.id).await?;
+        info!(
+            approval_id = %approval.approval_id,
+            node_id = %approval.node_id,
+            "Escalated unanswered approval"
+        );
+    }
+
+    for (id, decision) in repo.find_due_for_default().await? {
+        let approval = match repo.respond(id, &decision.to_status(), None, None).await {
+            Ok(approval) => approval,
+            // Answered between the query and the update
+            Err(SwarmApprovalError::AlreadyResolved | SwarmApprovalError::NotFound) => continue,
+            Err(e) => return Err(e),
+        };
+        info!(
+            approval_id = %approval.approval_id,
+            node_id = %approval.node_id,
+            decision = ?decision,
+            "Applied escalation default to unanswered approval"
+        );
+        deliver_response(pool, connections, &approval, None).await;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = ApprovalEscalationConfig::default();
+        assert_eq!(config.sweep_interval.as_secs(), 30);
+    }
+}
@@ -0,0 +1,452 @@
+//! Hands queued Hive tasks out to nodes as they free up capacity.
+//!
+//! A pass over an organization's queue offers tasks to [`TaskDispatcher::dispatch_task`] one at a
+//! time until nothing more fits. Passes run when a node connects, reports a change in load or
+//! renews its leases, when a task is queued, and on a periodic sweep. Wakes arriving together are
+//! coalesced into one pass per organization. Only one pass per organization runs at a time,
+//! across every Hive instance sharing the database.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{LazyLock, Mutex},
+    time::Duration as StdDuration,
+};
+
+use chrono::Utc;
+use sqlx::{PgPool, Postgres, pool::PoolConnection};
+use tokio::time::{self, MissedTickBehavior};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::task_queue::{QueueProject, QueuedTask, TaskQueueRepository},
+    nodes::{ConnectionManager, DispatchError, Ineligibility, NodeError, TaskDispatcher},
+};
+
+/// How long a wake waits for others to join it before its pass runs
+const WAKE_DELAY: StdDuration = StdDuration::from_millis(500);
+
+/// Organizations with a pass from [`wake`] that has not started yet
+static PENDING_WAKES: LazyLock<Mutex<HashSet<Uuid>>> = LazyLock::new(Default::default);
+
+/// Configuration for the task queue service.
+#[derive(Debug, Clone)]
+pub struct TaskQueueConfig {
+    /// How often to run a pass for every organization with queued tasks (default: 15 seconds)
+    pub sweep_interval: StdDuration,
+}
+
+impl Default for TaskQueueConfig {
+    fn default() -> Self {
+        Self {
+            sweep_interval: StdDuration::from_secs(15),
+        }
+    }
+}
+
+/// Spawn the task queue service as a background task.
+///
+/// Node load changes drive most passes through [`wake`]; the sweep catches tasks that could not
+/// be placed at the time, such as ones waiting for a node to connect.
+pub fn spawn_task_queue_service(
+    pool: PgPool,
+    connections: ConnectionManager,
+    config: Option<TaskQueueConfig>,
+) {
+    let config = config.unwrap_or_default();
+
+    tokio::spawn(async move {
+        let mut interval = time::interval(config.sweep_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+
+            let organizations = match TaskQueueRepository::new(&pool)
+                .organizations_with_queued_tasks()
+                .await
+            {
+                Ok(organizations) => organizations,
+                Err(e) => {
+                    error!(error = ?e, "Failed to list organizations with queued tasks");
+                    continue;
+                }
+            };
+            for organization_id in organizations {
+                if let Err(e) = run_queue(&pool, &connections, organization_id).await {
+                    error!(error = ?e, %organization_id, "Failed to run the task queue");
+                }
+            }
+        }
+    });
+}
+
+/// Run a pass over an organization's queue in the background, shortly. Does nothing when a pass
+/// for the organization is already waiting to start, so a burst of heartbeats runs one pass.
+pub fn wake(pool: PgPool, connections: ConnectionManager, organization_id: Uuid) {
+    if !PENDING_WAKES.lock().unwrap().insert(organization_id) {
+        return;
+    }
+    tokio::spawn(async move {
+        time::sleep(WAKE_DELAY).await;
+        PENDING_WAKES.lock().unwrap().remove(&organization_id);
+        if let Err(e) = run_queue(&pool, &connections, organization_id).await {
+            error!(error = ?e, %organization_id, "Failed to run the task queue");
+        }
+    });
+}
+
+/// Hand out an organization's queued tasks until no node or project has room for more.
+///
+/// Returns how many tasks were dispatched. Does nothing when another pass for the organization
+/// is already running.
+pub async fn run_queue(
+    pool: &PgPool,
+    connections: &ConnectionManager,
+    organization_id: Uuid,
+) -> Result<usize, sqlx::Error> {
+    let repo = TaskQueueRepository::new(pool);
+    if !repo.has_queued_tasks(organization_id).await? {
+        return Ok(0);
+    }
+
+    let Some(lock) = QueueLock::try_acquire(pool, organization_id).await? else {
+        debug!(%organization_id, "Task queue pass already running");
+        return Ok(0);
+    };
+    let result = run_pass(pool, connections, organization_id).await;
+    lock.release().await;
+    result
+}
+
+async fn run_pass(
+    pool: &PgPool,
+    connections: &ConnectionManager,
+    organization_id: Uuid,
+) -> Result<usize, sqlx::Error> {
+    let repo = TaskQueueRepository::new(pool);
+    repo.remove_deleted(organization_id).await?;
+    let now = Utc::now();
+    let ready = repo
+        .list(organization_id)
+        .await?
+        .into_iter()
+        .filter(|task| task.not_before.is_none_or(|not_before| not_before <= now))
+        .collect();
+    let mut queue = FairQueue::new(ready, &repo.projects(organization_id).await?);
+    let dispatcher = TaskDispatcher::new(pool.clone(), connections.clone());
+    let mut dispatched = 0;
+
+    while let Some(task) = queue.next_task() {
+        match dispatcher
+            .dispatch_task(task.task_id, &task.requirements)
+            .await
+        {
+            Ok(result) => {
+                repo.remove(task.task_id).await?;
+                repo.mark_dispatched(task.swarm_project_id).await?;
+                queue.dispatched(task.swarm_project_id);
+                dispatched += 1;
+                info!(
+                    task_id = %task.task_id,
+                    node_id = %result.node_id,
+                    assignment_id = %result.assignment_id,
+                    priority = task.priority,
+                    "Dispatched queued task"
+                );
+            }
+            Err(e) if can_never_dispatch(&e) => {
+                repo.remove(task.task_id).await?;
+                warn!(task_id = %task.task_id, error = %e, "Dropped queued task");
+            }
+            Err(e) => {
+                repo.record_failure(task.task_id, &e.to_string()).await?;
+                if leaves_no_room(&e) {
+                    queue.block(task.swarm_project_id);
+                }
+                debug!(task_id = %task.task_id, error = %e, "Queued task not dispatched yet");
+            }
+        }
+    }
+
+    Ok(dispatched)
+}
+
+/// Session-level advisory lock on one organization's queue.
+///
+/// Held on a pooled connection that stays idle while the pass sends tasks to nodes, rather
+/// than in a transaction left open across those sends. A connection whose lock was not
+/// released, because unlocking failed or the pass panicked, is closed instead of going back
+/// to the pool, which releases the lock.
+struct QueueLock {
+    connection: Option<PoolConnection<Postgres>>,
+    key: String,
+}
+
+impl QueueLock {
+    /// `None` when another pass holds the lock
+    async fn try_acquire(
+        pool: &PgPool,
+        organization_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let mut connection = pool.acquire().await?;
+        let key = format!("task_queue:{organization_id}");
+        let locked = sqlx::query_scalar!(
+            r#"SELECT pg_try_advisory_lock(hashtextextended($1, 0)) AS "locked!""#,
+            key
+        )
+        .fetch_one(&mut *connection)
+        .await?;
+        Ok(locked.then(|| Self {
+            connection: Some(connection),
+            key,
+        }))
+    }
+
+    async fn release(mut self) {
+        let Some(mut connection) = self.connection.take() else {
+            return;
+        };
+        if let Err(e) = sqlx::query_scalar!(
+            "SELECT pg_advisory_unlock(hashtextextended($1, 0))",
+            self.key
+        )
+        .fetch_one(&mut *connection)
+        .await
+        {
+            warn!(error = ?e, "Failed to release the task queue lock; closing its connection");
+            connection.close_on_drop();
+        }
+    }
+}
+
+impl Drop for QueueLock {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.as_mut() {
+            connection.close_on_drop();
+        }
+    }
+}
+
+/// Whether a dispatch error means the task should leave the queue
+fn can_never_dispatch(error: &DispatchError) -> bool {
+    matches!(
+        error,
+        DispatchError::TaskNotFound
+            | DispatchError::TaskOwnedByNode
+            | DispatchError::NodeService(NodeError::TaskAlreadyAssigned)
+    )
+}
+
+/// Whether no other task of the same project could be placed right now either, because every
+/// node linked to it is full or away, whatever the task requires
+fn leaves_no_room(error: &DispatchError) -> bool {
+    match error {
+        DispatchError::NoNodeForProject => true,
+        DispatchError::NoEligibleNode(rejections) => rejections.iter().all(|r| {
+            matches!(
+                r.reason,
+                Ineligibility::NotConnected
+                    | Ineligibility::Unavailable(_)
+                    | Ineligibility::AtCapacity { .. }
+            )
+        }),
+        _ => false,
+    }
+}
+
+/// The order in which one pass offers queued tasks to nodes.
+///
+/// A project's tasks come in queue order: priority, then deadline, then age. Between projects,
+/// the task with the highest priority goes first; at equal priority, the project served longest
+/// ago does, so one busy project cannot starve the others. Projects at their concurrency limit
+/// are skipped.
+pub struct FairQueue {
+    lanes: Vec<ProjectLane>,
+    next_turn: u64,
+}
+
+struct ProjectLane {
+    swarm_project_id: Uuid,
+    tasks: VecDeque<QueuedTask>,
+    /// Tasks the project may still start, or `None` without a limit
+    room: Option<i64>,
+    /// Place in the round-robin; lower goes first
+    turn: u64,
+    blocked: bool,
+}
+
+impl FairQueue {
+    /// `tasks` must be in queue order, as [`TaskQueueRepository::list`] returns them
+    pub fn new(tasks: Vec<QueuedTask>, projects: &[QueueProject]) -> Self {
+        let mut projects: Vec<&QueueProject> = projects.iter().collect();
+        // Never served first, then the one served longest ago
+        projects.sort_by_key(|project| project.last_dispatched_at);
+
+        let mut lanes: Vec<ProjectLane> = projects
+            .iter()
+            .enumerate()
+            .map(|(turn, project)| ProjectLane {
+                swarm_project_id: project.swarm_project_id,
+                tasks: VecDeque::new(),
+                room: project
+                    .max_concurrent_tasks
+                    .map(|max| i64::from(max) - project.running),
+                turn: turn as u64,
+                blocked: false,
+            })
+            .collect();
+        for task in tasks {
+            if let Some(lane) = lanes
+                .iter_mut()
+                .find(|lane| lane.swarm_project_id == task.swarm_project_id)
+            {
+                lane.tasks.push_back(task);
+            }
+        }
+
+        Self {
+            next_turn: lanes.len() as u64,
+            lanes,
+        }
+    }
+
+    /// Take the next task to offer, if any project has one and room to run it
+    pub fn next_task(&mut self) -> Option<QueuedTask> {
+        self.lanes
+            .iter_mut()
+            .filter(|lane| !lane.blocked && lane.room.is_none_or(|room| room > 0))
+            .filter_map(|lane| {
+                let priority = lane.tasks.front()?.priority;
+                Some((priority, lane))
+            })
+            .min_by(|(a_priority, a), (b_priority, b)| {
+                b_priority.cmp(a_priority).then(a.turn.cmp(&b.turn))
+            })
+            .and_then(|(_, lane)| lane.tasks.pop_front())
+    }
+
+    /// A task of the project was handed out: it uses up room and goes to the back of the turn
+    pub fn dispatched(&mut self, swarm_project_id: Uuid) {
+        let turn = self.next_turn;
+        if let Some(lane) = self.lane(swarm_project_id) {
+            lane.room = lane.room.map(|room| room - 1);
+            lane.turn = turn;
+            self.next_turn += 1;
+        }
+    }
+
+    /// Offer no more of the project's tasks in this pass
+    pub fn block(&mut self, swarm_project_id: Uuid) {
+        if let Some(lane) = self.lane(swarm_project_id) {
+            lane.blocked = true;
+        }
+    }
+
+    fn lane(&mut self, swarm_project_id: Uuid) -> Option<&mut ProjectLane> {
+        self.lanes
+            .iter_mut()
+            .find(|lane| lane.swarm_project_id == swarm_project_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::types::Json;
+
+    use super::*;
+    use crate::nodes::DispatchRequirements;
+
+    fn project(
+        name: &str,
+        max: Option<i32>,
+        running: i64,
+        served_ago: Option<i64>,
+    ) -> QueueProject {
+        QueueProject {
+            swarm_project_id: Uuid::new_v4(),
+            name: name.to_string(),
+            max_concurrent_tasks: max,
+            last_dispatched_at: served_ago.map(|minutes| Utc::now() - Duration::minutes(minutes)),
+            running,
+            queued: 0,
+        }
+    }
+
+    fn task(project: &QueueProject, title: &str, priority: i32) -> QueuedTask {
+        QueuedTask {
+            task_id: Uuid::new_v4(),
+            organization_id: Uuid::nil(),
+            swarm_project_id: project.swarm_project_id,
+            title: title.to_string(),
+            priority,
+            deadline: None,
+            requirements: Json(DispatchRequirements::default()),
+            enqueued_by: None,
+            enqueued_at: Utc::now(),
+            last_error: None,
+            last_attempt_at: None,
+            not_before: None,
+        }
+    }
+
+    /// Drain the queue as if every offered task was dispatched
+    fn drain(queue: &mut FairQueue) -> Vec<String> {
+        let mut order = Vec::new();
+        while let Some(task) = queue.next_task() {
+            queue.dispatched(task.swarm_project_id);
+            order.push(task.title);
+        }
+        order
+    }
+
+    #[test]
+    fn test_projects_take_turns_at_equal_priority() {
+        let busy = project("busy", None, 0, Some(1));
+        let quiet = project("quiet", None, 0, Some(10));
+        let tasks = vec![
+            task(&busy, "busy-1", 0),
+            task(&busy, "busy-2", 0),
+            task(&busy, "busy-3", 0),
+            task(&quiet, "quiet-1", 0),
+            task(&quiet, "quiet-2", 0),
+        ];
+        let mut queue = FairQueue::new(tasks, &[busy, quiet]);
+        assert_eq!(
+            drain(&mut queue),
+            ["quiet-1", "busy-1", "quiet-2", "busy-2", "busy-3"]
+        );
+    }
+
+    #[test]
+    fn test_higher_priority_goes_first_across_projects() {
+        let a = project("a", None, 0, None);
+        let b = project("b", None, 0, Some(5));
+        let tasks = vec![
+            task(&a, "a-low", 0),
+            task(&b, "b-urgent", 10),
+            task(&b, "b-low", 0),
+        ];
+        let mut queue = FairQueue::new(tasks, &[a, b]);
+        assert_eq!(drain(&mut queue), ["b-urgent", "a-low", "b-low"]);
+    }
+
+    #[test]
+    fn test_project_limit_and_block() {
+        let capped = project("capped", Some(2), 1, None);
+        let full = project("full", None, 0, None);
+        let open = project("open", None, 0, None);
+        let tasks = vec![
+            task(&capped, "capped-1", 0),
+            task(&capped, "capped-2", 0),
+            task(&full, "full-1", 0),
+            task(&open, "open-1", 0),
+        ];
+        let full_id = full.swarm_project_id;
+        let mut queue = FairQueue::new(tasks, &[capped, full, open]);
+        queue.block(full_id);
+        // One slot left under the cap of two with one already running
+        assert_eq!(drain(&mut queue), ["capped-1", "open-1"]);
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
     config::RemoteServerConfig,
     mail::Mailer,
     nodes::{BackfillService, ConnectionManager, backfill::BackfillRequestTracker},
-    services::LogCache,
+    services::{LogCache, LogRelay},
 };
 
 #[derive(Clone)]
@@ -27,6 +27,7 @@ pub struct AppState {
     pub node_connections: ConnectionManager,
     pub connection_token: Arc<ConnectionTokenService>,
     pub log_cache: LogCache,
+    pub log_relay: LogRelay,
     pub http_client: reqwest::Client,
     pub backfill: Arc<BackfillService>,
 }
@@ -44,6 +45,7 @@ impl AppState {
         server_public_base_url: String,
         node_connections: ConnectionManager,
         connection_token: Arc<ConnectionTokenService>,
+        log_relay: LogRelay,
         http_client: reqwest::Client,
         backfill: Arc<BackfillService>,
     ) -> Self {
@@ -59,6 +61,7 @@ impl AppState {
             node_connections,
             connection_token,
             log_cache: LogCache::new(),
+            log_relay,
             http_client,
             backfill,
         }
@@ -69,6 +72,11 @@ impl AppState {
         &self.log_cache
     }
 
+    /// Get a reference to the relay that fans node logs out to viewers.
+    pub fn log_relay(&self) -> &LogRelay {
+        &self.log_relay
+    }
+
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
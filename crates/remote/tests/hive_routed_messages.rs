@@ -0,0 +1,140 @@
+//! Messages routed to a replica while it was not listening are delivered once it listens.
+//!
+//! A NOTIFY sent while the replica's LISTEN connection is down is lost, so the routed message
+//! would sit in `hive_routed_messages` until it is pruned. The listener drains the replica's
+//! messages every time it starts listening.
+
+use std::{sync::Arc, time::Duration};
+
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use remote::{
+    db::RoutedMessageListener,
+    nodes::{ConnectionManager, HiveCluster, RoutedPayload, ws::message::HiveMessage},
+};
+
+fn database_url() -> Option<String> {
+    std::env::var("DATABASE_URL").ok()
+}
+
+macro_rules! skip_without_db {
+    () => {
+        if database_url().is_none() {
+            eprintln!("Skipping test: DATABASE_URL not set");
+            return;
+        }
+    };
+}
+
+async fn create_pool() -> PgPool {
+    let url = database_url().expect("DATABASE_URL must be set");
+    sqlx::PgPool::connect(&url)
+        .await
+        .expect("Failed to connect to database")
+}
+
+async fn create_test_organization(pool: &PgPool) -> Uuid {
+    let org_id = Uuid::new_v4();
+    let now = chrono::Utc::now();
+
+    sqlx::query(
+        r#"
+        INSERT INTO organizations (id, name, slug, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(org_id)
+    .bind(format!("Test Org {}", org_id))
+    .bind(format!("test-org-{}", org_id))
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .expect("Failed to create test organization");
+
+    org_id
+}
+
+async fn create_test_node(pool: &PgPool, org_id: Uuid) -> Uuid {
+    let node_id = Uuid::new_v4();
+    let now = chrono::Utc::now();
+
+    sqlx::query(
+        r#"
+        INSERT INTO nodes (id, organization_id, name, machine_id, status, capabilities, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, 'online', '{}'::jsonb, $5, $6)
+        "#,
+    )
+    .bind(node_id)
+    .bind(org_id)
+    .bind(format!("node-{}", node_id))
+    .bind(format!("machine-{}", node_id))
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .expect("Failed to create test node");
+
+    node_id
+}
+
+async fn cleanup_org(pool: &PgPool, org_id: Uuid) {
+    let _ = sqlx::query("DELETE FROM organizations WHERE id = $1")
+        .bind(org_id)
+        .execute(pool)
+        .await;
+}
+
+#[tokio::test]
+async fn message_routed_before_listening_is_delivered() {
+    skip_without_db!();
+    let pool = create_pool().await;
+    let org_id = create_test_organization(&pool).await;
+    let node_id = create_test_node(&pool, org_id).await;
+    let channel = format!("hive_routing_test_{}", Uuid::new_v4().simple());
+
+    let here = Arc::new(
+        HiveCluster::connect(pool.clone(), channel.clone())
+            .await
+            .expect("Failed to join cluster"),
+    );
+    let connections = ConnectionManager::new().with_cluster(Arc::clone(&here));
+    let (sender, mut receiver) = mpsc::channel(4);
+    let handle = connections
+        .register(node_id, org_id, sender)
+        .await
+        .expect("Failed to register node");
+
+    // Another replica routes a message here while nobody listens; its NOTIFY is lost
+    let other = HiveCluster::connect(pool.clone(), channel)
+        .await
+        .expect("Failed to join cluster");
+    other
+        .route(
+            here.replica_id(),
+            &[node_id],
+            &RoutedPayload::Deliver(Box::new(HiveMessage::Close {
+                reason: "routed while not listening".to_string(),
+            })),
+        )
+        .await
+        .expect("Failed to route message");
+
+    let listener =
+        tokio::spawn(RoutedMessageListener::new(Arc::clone(&here), connections.clone()).run());
+    let delivered = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await;
+    listener.abort();
+
+    assert!(
+        matches!(
+            delivered,
+            Ok(Some(HiveMessage::Close { ref reason })) if reason == "routed while not listening"
+        ),
+        "routed message was not delivered: {delivered:?}"
+    );
+
+    connections.unregister(node_id, handle.connection_id).await;
+    cleanup_org(&pool, org_id).await;
+}
@@ -10,10 +10,11 @@
 //! `crates/remote/src/nodes/ws/connection.rs` (task 602).
 
 use remote::nodes::ws::message::{
-    AuthResultMessage, BackfillRequestMessage, BackfillType, HiveMessage, LabelSyncBroadcastMessage,
-    NodeRemovedMessage, ProjectSyncMessage, TaskAssignMessage, TaskCancelMessage, TaskDetails,
-    TaskSyncResponseMessage,
+    ApprovalResponseMessage, AuthResultMessage, BackfillRequestMessage, BackfillType, HiveMessage,
+    LabelSyncBroadcastMessage, NodeRemovedMessage, ProjectSyncMessage, TaskAssignMessage,
+    TaskCancelMessage, TaskDetails, TaskSyncResponseMessage,
 };
+use utils::approvals::ApprovalStatus;
 
 /// How a `HiveMessage` reaches a node — the allowed delivery classes for SC1.
 #[derive(Debug, PartialEq, Eq)]
@@ -61,6 +62,8 @@ fn classify(msg: &HiveMessage) -> Delivery {
         HiveMessage::LeaseGrant { .. } | HiveMessage::LeaseRevoked { .. } => Delivery::OwnAssignment,
         // P5 digest result (this task `depends_on: 501`) — directs the recipient's OWN heal; control.
         HiveMessage::DigestResult { .. } => Delivery::PerNodeControl,
+        // The answer to an approval the recipient itself forwarded; only ever sent to that node.
+        HiveMessage::ApprovalResponse(_) => Delivery::PerNodeControl,
     }
 }
 
@@ -157,6 +160,13 @@ fn one_of_each() -> Vec<HiveMessage> {
         HiveMessage::LeaseRevoked { assignment_id: sample_uuid(), reason: "x".into() },
         // P5 digest result (depends_on 501) — shape per CONTRACT §A.
         HiveMessage::DigestResult { resend_from_seq: None, pull_entities: vec![] },
+        HiveMessage::ApprovalResponse(ApprovalResponseMessage {
+            approval_id: "a".into(),
+            local_execution_process_id: sample_uuid(),
+            status: ApprovalStatus::Approved,
+            answers: None,
+            responded_by: None,
+        }),
     ]
 }
 
@@ -16,6 +16,10 @@ fn generate_types_content() -> String {
         db::models::project::Project::decl(),
         db::models::project::CreateProject::decl(),
         db::models::project::UpdateProject::decl(),
+        db::models::project::ProjectVerification::decl(),
+        db::models::project::UpdateProjectVerification::decl(),
+        db::models::project::ProjectScriptLanguage::decl(),
+        db::models::project::UpdateProjectScriptLanguage::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
         db::models::project::ProjectConfigSuggestion::decl(),
@@ -43,6 +47,12 @@ fn generate_types_content() -> String {
         executors::actions::script::ScriptContext::decl(),
         executors::actions::script::ScriptRequest::decl(),
         executors::actions::script::ScriptRequestLanguage::decl(),
+        executors::actions::pipeline::Pipeline::decl(),
+        executors::actions::pipeline::PipelineStep::decl(),
+        executors::actions::pipeline::PipelineStepKind::decl(),
+        executors::actions::pipeline::PipelineState::decl(),
+        db::models::project_pipeline::ProjectPipeline::decl(),
+        db::models::project_pipeline::UpsertProjectPipeline::decl(),
         executors::executors::BaseCodingAgent::decl(),
         executors::executors::CodingAgent::decl(),
         db::models::template::Template::decl(),
@@ -60,10 +70,18 @@ fn generate_types_content() -> String {
         db::models::webhook::WebhookEventType::decl(),
         db::models::webhook::CreateWebhook::decl(),
         db::models::webhook::UpdateWebhook::decl(),
+        db::models::webhook_delivery::WebhookDeliveryStatus::decl(),
+        db::models::webhook_delivery::WebhookDelivery::decl(),
+        db::models::mcp_client_token::McpToolAccess::decl(),
+        db::models::mcp_client_token::McpClientToken::decl(),
+        db::models::mcp_client_token::CreateMcpClientToken::decl(),
+        server::routes::mcp_tokens::IssuedMcpClientToken::decl(),
         db::models::task_variable::TaskVariable::decl(),
         db::models::task_variable::CreateTaskVariable::decl(),
         db::models::task_variable::UpdateTaskVariable::decl(),
         db::models::task_variable::ResolvedVariable::decl(),
+        services::services::secrets::SecretInfo::decl(),
+        server::routes::secrets::SetSecretRequest::decl(),
         db::models::task::TaskStatus::decl(),
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
@@ -176,6 +194,10 @@ fn generate_types_content() -> String {
         executors::executors::qwen::QwenCode::decl(),
         executors::executors::droid::Droid::decl(),
         executors::executors::qa_mock::QaMock::decl(),
+        executors::executors::custom::CustomAgent::decl(),
+        executors::executors::custom::PromptDelivery::decl(),
+        executors::executors::custom::CustomLogFormat::decl(),
+        executors::executors::custom::JsonlFieldMapping::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
         executors::executors::AppendPrompt::decl(),
@@ -183,7 +205,9 @@ fn generate_types_content() -> String {
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
         executors::actions::coding_agent_review::CodingAgentReviewRequest::decl(),
         executors::actions::coding_agent_review::CodingAgentReviewTarget::decl(),
+        executors::executors::session_import::SessionImportSource::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
+        server::routes::task_attempts::ImportSessionBody::decl(),
         server::routes::task_attempts::CreateReviewAttempt::decl(),
         server::routes::task_attempts::RunAgentSetupRequest::decl(),
         server::routes::task_attempts::RunAgentSetupResponse::decl(),
@@ -199,6 +223,38 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::attempt_group::AttemptGroup::decl(),
+        db::models::attempt_group::AttemptGroupMember::decl(),
+        services::services::attempt_group::AttemptDiffStats::decl(),
+        services::services::attempt_group::AttemptScriptResult::decl(),
+        services::services::attempt_group::AttemptTokenUsage::decl(),
+        services::services::attempt_group::AttemptComparison::decl(),
+        services::services::attempt_group::AttemptGroupComparison::decl(),
+        server::routes::attempt_groups::CreateAttemptGroupRequest::decl(),
+        server::routes::attempt_groups::PromoteAttemptRequest::decl(),
+        db::models::execution_process_usage::ExecutionProcessUsage::decl(),
+        db::models::execution_process_usage::UsagePeriod::decl(),
+        db::models::execution_process_usage::UsageGroupBy::decl(),
+        db::models::execution_process_usage::UsageRollup::decl(),
+        db::models::log_search::LogSearchEntryType::decl(),
+        db::models::log_search::LogSearchHit::decl(),
+        services::services::transcript::TranscriptFormat::decl(),
+        db::models::executor_command::CommandRunStatus::decl(),
+        services::services::command_timeline::TimelineCommand::decl(),
+        server::routes::task_attempts::RerunCommandRequest::decl(),
+        db::models::usage_budget::UsageBudget::decl(),
+        db::models::usage_budget::SetUsageBudget::decl(),
+        db::models::usage_budget::BudgetSpend::decl(),
+        db::models::usage_budget::BudgetLimit::decl(),
+        db::models::usage_budget::UsageBudgetStatus::decl(),
+        db::models::approval_policy::ApprovalPolicyOutcome::decl(),
+        db::models::approval_policy::ApprovalPolicyAction::decl(),
+        db::models::approval_policy::ApprovalPolicyRule::decl(),
+        db::models::approval_policy::ApprovalPolicyRuleInput::decl(),
+        db::models::approval_policy::SetApprovalPolicy::decl(),
+        db::models::approval_policy::ApprovalPolicyDecision::decl(),
+        services::services::approvals::policy::ApprovalPolicyDryRunEntry::decl(),
+        server::routes::projects::handlers::approval_policy::ApprovalPolicyDryRunRequest::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
@@ -220,6 +276,7 @@ fn generate_types_content() -> String {
         executors::logs::DenialSource::decl(),
         executors::logs::ToolStatus::decl(),
         executors::logs::utils::patch::PatchType::decl(),
+        utils::approvals::ApprovalRequest::decl(),
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::CreateApprovalRequest::decl(),
         utils::approvals::ApprovalResponse::decl(),
@@ -238,8 +295,10 @@ fn generate_types_content() -> String {
         services::services::process_service::KillFailure::decl(),
         // Terminal session types
         services::services::terminal_session::SessionInfo::decl(),
+        services::services::terminal_session::SessionViewers::decl(),
         server::routes::terminal::CreateSessionResponse::decl(),
         server::routes::terminal::TerminalMessage::decl(),
+        db::models::terminal_recording::TerminalRecording::decl(),
         // Worktree path type
         server::routes::task_attempts::WorktreePathResponse::decl(),
         // Stash types
@@ -345,6 +404,10 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "custom_agent",
+            generate_json_schema::<executors::executors::custom::CustomAgent>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
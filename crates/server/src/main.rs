@@ -133,6 +133,17 @@ async fn main() -> Result<(), VibeKanbanError> {
         {
             tracing::warn!("Failed to cleanup orphan executions: {}", e);
         }
+        // Close persisted approvals that no resumed execution can receive; the rest stay
+        // answerable until their agent asks again
+        match deployment_for_orphan_cleanup
+            .approvals()
+            .restore_persisted(&deployment_for_orphan_cleanup.db().pool)
+            .await
+        {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Restored {} pending approvals from before restart", n),
+            Err(e) => tracing::warn!("Failed to restore persisted approvals: {}", e),
+        }
         // Drain persisted queued messages for idle attempts AFTER cleanup completes.
         // Sequenced in the same spawn (not a sibling spawn) so cleanup_orphan_executions
         // finishes first, preventing 304's resume path from racing with the drain.
@@ -154,9 +165,19 @@ async fn main() -> Result<(), VibeKanbanError> {
         {
             tracing::warn!("Failed to backfill before_head_commits: {}", e);
         }
+        match deployment_for_backfill
+            .container()
+            .backfill_log_search_index()
+            .await
+        {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Indexed logs of {} earlier executions for search", n),
+            Err(e) => tracing::warn!("Failed to backfill log search index: {}", e),
+        }
     });
 
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_task_webhook_watcher().await;
     deployment.spawn_github_sync_service().await;
 
     // Spawn periodic normalization metrics logger (logs every 5 minutes if there's activity)
@@ -204,8 +225,6 @@ async fn main() -> Result<(), VibeKanbanError> {
         }
     });
 
-    let app_router = routes::router(deployment.clone()).await;
-
     let port = std::env::var("BACKEND_PORT")
         .or_else(|_| std::env::var("PORT"))
         .ok()
@@ -222,7 +241,48 @@ async fn main() -> Result<(), VibeKanbanError> {
 
     let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
     let listener = tokio::net::TcpListener::bind(format!("{host}:{port}")).await?;
-    let actual_port = listener.local_addr()?.port(); // get → 53427 (example)
+    let local_addr = listener.local_addr()?;
+    let actual_port = local_addr.port(); // get → 53427 (example)
+
+    let app_router = routes::router(deployment.clone()).await;
+
+    // The /mcp endpoint for token-authenticated MCP clients gets its own listener. The API has no
+    // authentication, so MCP_HTTP_HOST can be opened to other machines while HOST stays loopback.
+    if let Some(mcp_http_port) = std::env::var("MCP_HTTP_PORT")
+        .ok()
+        .and_then(|s| s.trim().parse::<u16>().ok())
+    {
+        let mcp_http_host =
+            std::env::var("MCP_HTTP_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let mcp_listener =
+            tokio::net::TcpListener::bind(format!("{mcp_http_host}:{mcp_http_port}")).await?;
+        // The endpoint calls back into this server's API over loopback; HOST may be a wildcard
+        // address that can't be connected to
+        let loopback = if local_addr.is_ipv6() {
+            "[::1]"
+        } else {
+            "127.0.0.1"
+        };
+        let mcp_router = routes::mcp_router(
+            deployment.clone(),
+            format!("http://{loopback}:{actual_port}"),
+        );
+        tracing::info!("MCP endpoint running on http://{mcp_http_host}:{mcp_http_port}/mcp");
+        if !local_addr.ip().is_loopback() {
+            tracing::warn!(
+                "HOST {host} exposes the unauthenticated API; MCP clients that can reach it are \
+                 not limited by their tokens"
+            );
+        }
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(mcp_listener, mcp_router)
+                .with_graceful_shutdown(shutdown_signal())
+                .await
+            {
+                tracing::error!("MCP endpoint stopped: {}", e);
+            }
+        });
+    }
 
     // Determine project root for instance registration
     let project_root = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
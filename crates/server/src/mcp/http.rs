@@ -0,0 +1,161 @@
+//! The MCP task server served by the main server as a streamable HTTP endpoint.
+//!
+//! The endpoint runs on its own listener (`MCP_HTTP_HOST`/`MCP_HTTP_PORT`), never on the one
+//! serving the unauthenticated API, so exposing it doesn't expose token management or the REST
+//! API. Clients authenticate with a bearer token issued through `/api/mcp/tokens`. Each token
+//! gets its own MCP service, so sessions never cross tokens, and its `TaskServer` only exposes
+//! the projects and tools the token allows.
+
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    Extension, Router,
+    body::Body,
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::any,
+};
+use db::models::mcp_client_token::{McpClientToken, McpToolAccess};
+use deployment::Deployment;
+use rand::Rng;
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+};
+use sha2::{Digest, Sha256};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    mcp::task_server::{McpClientScope, TaskServer},
+    middleware::model_loaders::extract_bearer_token,
+};
+
+/// Prefix of issued tokens, so they are recognisable in config files and secret scanners
+const TOKEN_PREFIX: &str = "vkmcp_";
+
+/// Characters of the token kept in the clear to tell tokens apart
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+type McpService = StreamableHttpService<TaskServer, LocalSessionManager>;
+
+/// One MCP service per token, created on the token's first request.
+pub struct McpHttpServer {
+    backend_url: String,
+    services: Mutex<HashMap<Uuid, McpService>>,
+}
+
+impl McpHttpServer {
+    pub fn new(backend_url: String) -> Self {
+        Self {
+            backend_url,
+            services: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn service_for(&self, token: &McpClientToken) -> McpService {
+        let mut services = self.services.lock().unwrap();
+        services
+            .entry(token.id)
+            .or_insert_with(|| {
+                let server = TaskServer::new(&self.backend_url).with_scope(McpClientScope {
+                    project_ids: token.project_ids.clone(),
+                    read_only: token.access == McpToolAccess::ReadOnly,
+                });
+                StreamableHttpService::new(
+                    move || Ok(server.clone()),
+                    LocalSessionManager::default().into(),
+                    StreamableHttpServerConfig::default(),
+                )
+            })
+            .clone()
+    }
+}
+
+/// A new token and its display prefix
+pub(crate) fn generate_token() -> (String, String) {
+    const CHARS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+    let mut rng = rand::rng();
+    let random: String = (0..40)
+        .map(|_| CHARS[rng.random_range(0..CHARS.len())] as char)
+        .collect();
+    let token = format!("{TOKEN_PREFIX}{random}");
+    let prefix = token[..DISPLAY_PREFIX_LEN].to_string();
+    (token, prefix)
+}
+
+pub(crate) fn hash_token(token: &str) -> String {
+    let mut output = String::with_capacity(64);
+    for byte in Sha256::digest(token.as_bytes()) {
+        let _ = write!(output, "{:02x}", byte);
+    }
+    output
+}
+
+/// Remap 401 to 404 for unknown MCP sessions.
+///
+/// rmcp's LocalSessionManager returns 401 Unauthorized when a session ID is not found,
+/// but the MCP Streamable HTTP spec requires 404 so clients know to re-initialize.
+/// Without this, clients treat it as an auth failure and don't reconnect automatically.
+pub async fn remap_session_not_found(req: Request<Body>, next: Next) -> Response {
+    session_not_found_as_404(next.run(req).await)
+}
+
+fn session_not_found_as_404(response: Response) -> Response {
+    if response.status() == StatusCode::UNAUTHORIZED {
+        let (mut parts, body) = response.into_parts();
+        parts.status = StatusCode::NOT_FOUND;
+        Response::from_parts(parts, body)
+    } else {
+        response
+    }
+}
+
+fn unauthorized(message: &'static str) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        message,
+    )
+        .into_response()
+}
+
+async fn handle_mcp_request(
+    State(deployment): State<DeploymentImpl>,
+    Extension(server): Extension<Arc<McpHttpServer>>,
+    request: Request,
+) -> Response {
+    let Some(token) = extract_bearer_token(request.headers()) else {
+        return unauthorized("Missing MCP client token");
+    };
+    let pool = &deployment.db().pool;
+    let client = match McpClientToken::find_active_by_hash(pool, &hash_token(token)).await {
+        Ok(Some(client)) => client,
+        Ok(None) => return unauthorized("Invalid or revoked MCP client token"),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to look up MCP client token");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if let Err(e) = McpClientToken::touch_last_used(pool, client.id).await {
+        tracing::warn!(token_id = %client.id, error = %e, "Failed to record MCP token use");
+    }
+
+    let response = match server.service_for(&client).oneshot(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    };
+    session_not_found_as_404(response.map(Body::new))
+}
+
+pub fn router(backend_url: String) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/mcp", any(handle_mcp_request))
+        .layer(Extension(Arc::new(McpHttpServer::new(backend_url))))
+}
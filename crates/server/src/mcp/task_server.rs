@@ -1,29 +1,49 @@
-use std::{future::Future, path::PathBuf, str::FromStr};
+use std::{collections::HashMap, future::Future, path::PathBuf, str::FromStr};
 
 use db::models::{
-    execution_process::ExecutionProcess,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     label::Label,
     project::Project,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
     task_attempt::{TaskAttempt, TaskAttemptContext},
     task_variable::{ResolvedVariable, TaskVariable},
 };
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use executors::{
+    executors::BaseCodingAgent,
+    logs::{NormalizedEntry, NormalizedEntryType},
+    profile::ExecutorProfileId,
+};
+use local_deployment::message_queue::QueuedMessage;
 use rmcp::{
-    ErrorData, ServerHandler,
-    handler::server::tool::{Parameters, ToolRouter},
+    ErrorData, RoleServer, ServerHandler,
+    handler::server::tool::{Parameters, ToolCallContext, ToolRouter},
     model::{
-        CallToolResult, Content, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo,
+        CallToolRequestParam, CallToolResult, Content, Implementation, JsonObject, ListToolsResult,
+        PaginatedRequestParam, ProtocolVersion, ServerCapabilities, ServerInfo,
     },
-    schemars, tool, tool_handler, tool_router,
+    schemars,
+    service::RequestContext,
+    tool, tool_router,
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json;
+use services::services::transcript::diff_text;
+use utils::{
+    approvals::{ApprovalRequest, ApprovalResponse, ApprovalStatus},
+    diff::{Diff, compute_line_change_counts},
+};
 use uuid::Uuid;
 
 use remote::routes::projects::{ListProjectNodesResponse, ProjectNodeInfo};
 
-use crate::routes::{containers::ContainerQuery, task_attempts::CreateTaskAttemptBody};
+use crate::routes::{
+    containers::ContainerQuery,
+    execution_processes::NormalizedEntriesResponse,
+    task_attempts::{
+        CreateFollowUpAttempt, CreateGitHubPrRequest, CreateTaskAttemptBody,
+        RebaseTaskAttemptRequest,
+    },
+};
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CreateTaskRequest {
@@ -378,6 +398,190 @@ pub struct ListTaskAttemptsResponse {
     pub count: usize,
 }
 
+// ===== Attempt Review and Steering MCP Types =====
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetAttemptDiffRequest {
+    #[schemars(description = "Attempt ID")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Only list changed files with line counts, without the diff text")]
+    #[serde(default)]
+    pub stats_only: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DiffFileSummary {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub change: String,
+    pub additions: Option<usize>,
+    pub deletions: Option<usize>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetAttemptDiffResponse {
+    pub attempt_id: String,
+    pub files: Vec<DiffFileSummary>,
+    pub count: usize,
+    pub diff: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetAttemptLogsRequest {
+    #[schemars(description = "Attempt ID")]
+    pub attempt_id: Uuid,
+    #[schemars(
+        description = "Execution process to read. Defaults to the attempt's latest coding agent run."
+    )]
+    #[serde(default)]
+    pub execution_process_id: Option<Uuid>,
+    #[schemars(description = "Number of most recent entries to return (default: 20)")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct LogEntrySummary {
+    pub index: usize,
+    pub entry_type: String,
+    pub content: String,
+    pub tool_name: Option<String>,
+    pub status: Option<String>,
+    pub timestamp: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetAttemptLogsResponse {
+    pub attempt_id: String,
+    pub execution_process_id: String,
+    pub process_status: String,
+    pub total: usize,
+    pub entries: Vec<LogEntrySummary>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SendFollowUpRequest {
+    #[schemars(description = "Attempt ID")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Follow-up prompt for the coding agent")]
+    pub prompt: String,
+    #[schemars(description = "Executor variant (optional)")]
+    #[serde(default)]
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SendFollowUpResponse {
+    pub attempt_id: String,
+    pub execution_process_id: String,
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct QueueMessageRequest {
+    #[schemars(description = "Attempt ID")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Message to send as a follow-up when the current run finishes")]
+    pub content: String,
+    #[schemars(description = "Executor variant (optional)")]
+    #[serde(default)]
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct QueueMessageResponse {
+    pub attempt_id: String,
+    pub message_id: String,
+    pub position: usize,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListPendingApprovalsRequest {
+    #[schemars(description = "Only list approvals raised by this execution process")]
+    #[serde(default)]
+    pub execution_process_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct PendingApprovalSummary {
+    pub approval_id: String,
+    pub execution_process_id: String,
+    pub tool_name: String,
+    pub tool_input: serde_json::Value,
+    pub timeout_at: String,
+    pub questions: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListPendingApprovalsResponse {
+    pub approvals: Vec<PendingApprovalSummary>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RespondToApprovalRequest {
+    #[schemars(description = "Approval ID from list_pending_approvals")]
+    pub approval_id: String,
+    #[schemars(description = "Execution process that raised the approval")]
+    pub execution_process_id: Uuid,
+    #[schemars(description = "True to approve, false to deny")]
+    pub approve: bool,
+    #[schemars(description = "Reason given to the agent when denying")]
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[schemars(description = "Answers to a question, keyed by question header")]
+    #[serde(default)]
+    pub answers: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct RespondToApprovalResponse {
+    pub approval_id: String,
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RebaseTaskAttemptMcpRequest {
+    #[schemars(description = "Attempt ID")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Branch to rebase onto. Defaults to the attempt's target branch.")]
+    #[serde(default)]
+    pub new_base_branch: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct MergeTaskAttemptRequest {
+    #[schemars(description = "Attempt ID")]
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GitOperationResponse {
+    pub attempt_id: String,
+    pub operation: String,
+    pub success: bool,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreatePrRequest {
+    #[schemars(description = "Attempt ID")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Pull request title")]
+    pub title: String,
+    #[schemars(description = "Pull request body")]
+    #[serde(default)]
+    pub body: Option<String>,
+    #[schemars(description = "Branch to merge into. Defaults to the attempt's target branch.")]
+    #[serde(default)]
+    pub target_branch: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct CreatePrResponse {
+    pub attempt_id: String,
+    pub pr_url: String,
+}
+
 // ===== Label MCP Types =====
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -479,8 +683,48 @@ pub struct TaskServer {
     client: reqwest::Client,
     base_url: String,
     tool_router: ToolRouter<TaskServer>,
+    scope: Option<McpClientScope>,
+}
+
+/// What an HTTP client may do, from the token it connected with. Stdio clients are unscoped.
+#[derive(Debug, Clone)]
+pub struct McpClientScope {
+    /// Projects the client may access; `None` allows every project
+    pub project_ids: Option<Vec<Uuid>>,
+    pub read_only: bool,
 }
 
+/// Tools available to read-only clients
+const READ_ONLY_TOOLS: &[&str] = &[
+    "get_context",
+    "get_task_id",
+    "get_project_id",
+    "list_projects",
+    "list_tasks",
+    "get_task",
+    "get_task_variables",
+    "get_task_attempt_status",
+    "list_task_attempts",
+    "get_attempt_diff",
+    "get_attempt_logs",
+    "list_pending_approvals",
+    "get_task_labels",
+    "list_labels",
+    "list_nodes",
+];
+
+/// Tool arguments that point at something inside a project; `label_ids` holds a list
+const SCOPED_ARGUMENTS: &[&str] = &[
+    "project_id",
+    "task_id",
+    "parent_task_id",
+    "attempt_id",
+    "execution_process_id",
+    "approval_id",
+    "cwd",
+    "label_ids",
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
 pub struct McpContext {
     pub project_id: Uuid,
@@ -498,7 +742,21 @@ impl TaskServer {
             client: reqwest::Client::new(),
             base_url: base_url.to_string(),
             tool_router: Self::tool_router(),
+            scope: None,
+        }
+    }
+
+    /// Limit the server to what an HTTP client's token allows.
+    pub fn with_scope(mut self, scope: McpClientScope) -> Self {
+        if scope.read_only {
+            for tool in self.tool_router.list_all() {
+                if !READ_ONLY_TOOLS.contains(&tool.name.as_ref()) {
+                    self.tool_router.remove_route(&tool.name);
+                }
+            }
         }
+        self.scope = Some(scope);
+        self
     }
 
     /// Initialize the server.
@@ -634,6 +892,8 @@ impl TaskServer {
 struct ApiResponseEnvelope<T> {
     success: bool,
     data: Option<T>,
+    #[serde(default)]
+    error_data: Option<serde_json::Value>,
     message: Option<String>,
 }
 
@@ -660,10 +920,10 @@ impl TaskServer {
         Self::err_value(v)
     }
 
-    async fn send_json<T: DeserializeOwned>(
+    async fn send_envelope<T: DeserializeOwned>(
         &self,
         rb: reqwest::RequestBuilder,
-    ) -> Result<T, CallToolResult> {
+    ) -> Result<Option<T>, CallToolResult> {
         let resp = rb
             .send()
             .await
@@ -671,8 +931,14 @@ impl TaskServer {
 
         if !resp.status().is_success() {
             let status = resp.status();
+            // Error responses still carry the API's message, which says what went wrong
+            let message = resp
+                .json::<ApiResponseEnvelope<serde_json::Value>>()
+                .await
+                .ok()
+                .and_then(|r| r.message);
             return Err(
-                Self::err(format!("VK API returned error status: {}", status), None).unwrap(),
+                Self::err(format!("VK API returned error status: {}", status), message).unwrap(),
             );
         }
 
@@ -682,14 +948,151 @@ impl TaskServer {
 
         if !api_response.success {
             let msg = api_response.message.as_deref().unwrap_or("Unknown error");
-            return Err(Self::err("VK API returned error", Some(msg)).unwrap());
+            let mut v = serde_json::json!({
+                "success": false,
+                "error": "VK API returned error",
+                "details": msg,
+            });
+            // Structured errors, e.g. merge conflicts during a rebase
+            if let Some(error_data) = api_response.error_data {
+                v["error_data"] = error_data;
+            }
+            return Err(Self::err_value(v).unwrap());
         }
 
-        api_response
-            .data
+        Ok(api_response.data)
+    }
+
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        rb: reqwest::RequestBuilder,
+    ) -> Result<T, CallToolResult> {
+        self.send_envelope(rb)
+            .await?
             .ok_or_else(|| Self::err("VK API response missing data field", None).unwrap())
     }
 
+    /// For endpoints that return no data on success.
+    async fn send_ok(&self, rb: reqwest::RequestBuilder) -> Result<(), CallToolResult> {
+        self.send_envelope::<serde_json::Value>(rb)
+            .await
+            .map(|_| ())
+    }
+
+    /// A task or attempt ID from the environment of a locally spawned server. Always `None` for
+    /// HTTP clients: the environment is the server's, not theirs, so their context comes from the
+    /// scope-checked `cwd` instead.
+    fn env_id(&self, var: &str) -> Option<Uuid> {
+        if self.scope.is_some() {
+            return None;
+        }
+        std::env::var(var)
+            .ok()
+            .and_then(|id| Uuid::parse_str(&id).ok())
+    }
+
+    fn allows_project(&self, project_id: Uuid) -> bool {
+        self.scope
+            .as_ref()
+            .and_then(|scope| scope.project_ids.as_ref())
+            .is_none_or(|ids| ids.contains(&project_id))
+    }
+
+    /// Reject a tool call whose arguments point outside the client's projects.
+    async fn check_project_scope(
+        &self,
+        arguments: Option<&JsonObject>,
+    ) -> Result<(), CallToolResult> {
+        let scoped = self
+            .scope
+            .as_ref()
+            .is_some_and(|scope| scope.project_ids.is_some());
+        let Some(arguments) = arguments.filter(|_| scoped) else {
+            return Ok(());
+        };
+
+        for key in SCOPED_ARGUMENTS {
+            let values: Vec<&str> = match arguments.get(*key) {
+                Some(serde_json::Value::String(value)) => vec![value.as_str()],
+                Some(serde_json::Value::Array(values)) => values
+                    .iter()
+                    .map(|value| value.as_str().unwrap_or_default())
+                    .collect(),
+                _ => continue,
+            };
+            for value in values {
+                let project_id = match *key {
+                    "cwd" => self
+                        .fetch_context_for_path(value)
+                        .await
+                        .map(|ctx| ctx.project_id),
+                    "approval_id" => self.project_for_approval(value).await,
+                    _ => match Uuid::parse_str(value) {
+                        Ok(id) => match *key {
+                            "project_id" => Some(id),
+                            "task_id" | "parent_task_id" => self.project_for_task(id).await,
+                            "attempt_id" => self.project_for_attempt(id).await,
+                            "label_ids" => match self.project_for_label(id).await {
+                                // Global labels belong to no project
+                                Some(None) => continue,
+                                Some(project_id) => project_id,
+                                None => None,
+                            },
+                            _ => self.project_for_execution_process(id).await,
+                        },
+                        Err(_) => None,
+                    },
+                };
+                if !project_id.is_some_and(|id| self.allows_project(id)) {
+                    return Err(Self::err(
+                        format!("This client is not allowed to access the given {}", key),
+                        None,
+                    )
+                    .unwrap());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn project_for_task(&self, task_id: Uuid) -> Option<Uuid> {
+        let url = self.url(&format!("/api/tasks/{}", task_id));
+        let task: Task = self.send_json(self.client.get(&url)).await.ok()?;
+        Some(task.project_id)
+    }
+
+    /// `Some(None)` for a global label
+    async fn project_for_label(&self, label_id: Uuid) -> Option<Option<Uuid>> {
+        let url = self.url(&format!("/api/labels/{}", label_id));
+        let label: Label = self.send_json(self.client.get(&url)).await.ok()?;
+        Some(label.project_id)
+    }
+
+    async fn project_for_attempt(&self, attempt_id: Uuid) -> Option<Uuid> {
+        let url = self.url(&format!("/api/task-attempts/{}", attempt_id));
+        let attempt: TaskAttempt = self.send_json(self.client.get(&url)).await.ok()?;
+        self.project_for_task(attempt.task_id).await
+    }
+
+    async fn project_for_execution_process(&self, execution_process_id: Uuid) -> Option<Uuid> {
+        let url = self.url(&format!(
+            "/api/execution-processes/{}",
+            execution_process_id
+        ));
+        let process: ExecutionProcess = self.send_json(self.client.get(&url)).await.ok()?;
+        self.project_for_attempt(process.task_attempt_id).await
+    }
+
+    /// Resolved through the stored approval, since the execution process a client passes
+    /// alongside the approval ID is not checked against it.
+    async fn project_for_approval(&self, approval_id: &str) -> Option<Uuid> {
+        let url = self.url("/api/approvals/pending");
+        let pending: Vec<ApprovalRequest> = self.send_json(self.client.get(&url)).await.ok()?;
+        let approval = pending.into_iter().find(|a| a.id == approval_id)?;
+        self.project_for_execution_process(approval.execution_process_id)
+            .await
+    }
+
     fn url(&self, path: &str) -> String {
         format!(
             "{}/{}",
@@ -699,6 +1102,32 @@ impl TaskServer {
     }
 }
 
+/// Compact form of a log entry for MCP clients; tool arguments and results are left out.
+fn summarize_log_entry(index: usize, entry: NormalizedEntry) -> LogEntrySummary {
+    let (tool_name, status) = match &entry.entry_type {
+        NormalizedEntryType::ToolUse {
+            tool_name, status, ..
+        } => (
+            Some(tool_name.clone()),
+            serde_json::to_value(status)
+                .ok()
+                .and_then(|v| v["status"].as_str().map(str::to_string)),
+        ),
+        _ => (None, None),
+    };
+    LogEntrySummary {
+        index,
+        entry_type: serde_json::to_value(&entry.entry_type)
+            .ok()
+            .and_then(|v| v["type"].as_str().map(str::to_string))
+            .unwrap_or_default(),
+        content: entry.content,
+        tool_name,
+        status,
+        timestamp: entry.timestamp,
+    }
+}
+
 #[tool_router]
 impl TaskServer {
     #[tool(description = "Get current task attempt context.")]
@@ -707,8 +1136,7 @@ impl TaskServer {
         Parameters(GetContextRequest { cwd }): Parameters<GetContextRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         // Layer 1: Direct lookup if env var available
-        if let Ok(attempt_id_str) = std::env::var("VK_ATTEMPT_ID")
-            && let Ok(attempt_id) = Uuid::parse_str(&attempt_id_str)
+        if let Some(attempt_id) = self.env_id("VK_ATTEMPT_ID")
             && let Some(ctx) = self.fetch_context_by_attempt_id(attempt_id).await
         {
             return TaskServer::success(&ctx);
@@ -734,9 +1162,7 @@ impl TaskServer {
         Parameters(GetContextRequest { cwd }): Parameters<GetContextRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         // Layer 1: Direct return if env var available
-        if let Ok(task_id_str) = std::env::var("VK_TASK_ID")
-            && let Ok(task_id) = Uuid::parse_str(&task_id_str)
-        {
+        if let Some(task_id) = self.env_id("VK_TASK_ID") {
             return TaskServer::success(&TaskIdResponse {
                 task_id: task_id.to_string(),
             });
@@ -764,8 +1190,7 @@ impl TaskServer {
         Parameters(GetContextRequest { cwd }): Parameters<GetContextRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         // Layer 1: Direct lookup if env var available
-        if let Ok(attempt_id_str) = std::env::var("VK_ATTEMPT_ID")
-            && let Ok(attempt_id) = Uuid::parse_str(&attempt_id_str)
+        if let Some(attempt_id) = self.env_id("VK_ATTEMPT_ID")
             && let Some(ctx) = self.fetch_context_by_attempt_id(attempt_id).await
         {
             return TaskServer::success(&ProjectIdResponse {
@@ -849,6 +1274,7 @@ impl TaskServer {
 
         let project_summaries: Vec<ProjectSummary> = projects
             .into_iter()
+            .filter(|p| self.allows_project(p.id))
             .map(ProjectSummary::from_project)
             .collect();
 
@@ -1205,10 +1631,7 @@ impl TaskServer {
         let url = self.url(&format!("/api/task-attempts/{}/stop", attempt_id));
 
         // POST to stop endpoint - it returns () on success
-        if let Err(e) = self
-            .send_json::<serde_json::Value>(self.client.post(&url))
-            .await
-        {
+        if let Err(e) = self.send_ok(self.client.post(&url)).await {
             return Ok(e);
         }
 
@@ -1302,6 +1725,356 @@ impl TaskServer {
         TaskServer::success(&response)
     }
 
+    // ===== Attempt Review and Steering MCP Tools =====
+
+    #[tool(description = "Get attempt diff against its target branch, incl. uncommitted changes.")]
+    async fn get_attempt_diff(
+        &self,
+        Parameters(GetAttemptDiffRequest {
+            attempt_id,
+            stats_only,
+        }): Parameters<GetAttemptDiffRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let stats_only = stats_only.unwrap_or(false);
+        let url = self.url(&format!(
+            "/api/task-attempts/{}/diff?stats_only={}",
+            attempt_id, stats_only
+        ));
+        let diffs: Vec<Diff> = match self.send_json(self.client.get(&url)).await {
+            Ok(d) => d,
+            Err(e) => return Ok(e),
+        };
+
+        let files: Vec<DiffFileSummary> = diffs
+            .iter()
+            .map(|d| {
+                let (additions, deletions) = match (d.additions, d.deletions) {
+                    (Some(a), Some(r)) => (Some(a), Some(r)),
+                    _ if d.content_omitted => (None, None),
+                    _ => {
+                        let (a, r) = compute_line_change_counts(
+                            d.old_content.as_deref().unwrap_or_default(),
+                            d.new_content.as_deref().unwrap_or_default(),
+                        );
+                        (Some(a), Some(r))
+                    }
+                };
+                DiffFileSummary {
+                    path: d
+                        .new_path
+                        .clone()
+                        .or_else(|| d.old_path.clone())
+                        .unwrap_or_default(),
+                    old_path: d
+                        .old_path
+                        .clone()
+                        .filter(|old| d.new_path.as_ref() != Some(old)),
+                    change: format!("{:?}", d.change).to_lowercase(),
+                    additions,
+                    deletions,
+                }
+            })
+            .collect();
+
+        let response = GetAttemptDiffResponse {
+            attempt_id: attempt_id.to_string(),
+            count: files.len(),
+            files,
+            diff: (!stats_only).then(|| diff_text(&diffs)),
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(description = "Get the last N normalized log entries of an attempt's agent run.")]
+    async fn get_attempt_logs(
+        &self,
+        Parameters(GetAttemptLogsRequest {
+            attempt_id,
+            execution_process_id,
+            limit,
+        }): Parameters<GetAttemptLogsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let processes_url = self.url(&format!(
+            "/api/execution-processes?task_attempt_id={}",
+            attempt_id
+        ));
+        let processes: Vec<ExecutionProcess> =
+            match self.send_json(self.client.get(&processes_url)).await {
+                Ok(p) => p,
+                Err(e) => return Ok(e),
+            };
+
+        let process = match execution_process_id {
+            Some(id) => processes.into_iter().find(|p| p.id == id),
+            None => processes
+                .into_iter()
+                .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+                .max_by_key(|p| p.created_at),
+        };
+        let Some(process) = process else {
+            return Self::err(
+                "No matching execution process for this attempt".to_string(),
+                Some(attempt_id.to_string()),
+            );
+        };
+
+        let url = self.url(&format!(
+            "/api/execution-processes/{}/normalized-entries?limit={}",
+            process.id,
+            limit.unwrap_or(20)
+        ));
+        let NormalizedEntriesResponse { total, entries } =
+            match self.send_json(self.client.get(&url)).await {
+                Ok(r) => r,
+                Err(e) => return Ok(e),
+            };
+
+        let first_index = total.saturating_sub(entries.len());
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| summarize_log_entry(first_index + i, entry))
+            .collect();
+
+        let response = GetAttemptLogsResponse {
+            attempt_id: attempt_id.to_string(),
+            execution_process_id: process.id.to_string(),
+            process_status: format!("{:?}", process.status).to_lowercase(),
+            total,
+            entries,
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(description = "Send a follow-up prompt to an attempt. Starts a new agent run.")]
+    async fn send_follow_up(
+        &self,
+        Parameters(SendFollowUpRequest {
+            attempt_id,
+            prompt,
+            variant,
+        }): Parameters<SendFollowUpRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = CreateFollowUpAttempt {
+            prompt,
+            variant,
+            image_ids: None,
+            retry_process_id: None,
+            force_when_dirty: None,
+            perform_git_reset: None,
+        };
+        let url = self.url(&format!("/api/task-attempts/{}/follow-up", attempt_id));
+        let process: ExecutionProcess =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(p) => p,
+                Err(e) => return Ok(e),
+            };
+
+        let response = SendFollowUpResponse {
+            attempt_id: attempt_id.to_string(),
+            execution_process_id: process.id.to_string(),
+            status: format!("{:?}", process.status).to_lowercase(),
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(description = "Queue a message to send as a follow-up when the current run finishes.")]
+    async fn queue_message(
+        &self,
+        Parameters(QueueMessageRequest {
+            attempt_id,
+            content,
+            variant,
+        }): Parameters<QueueMessageRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = serde_json::json!({
+            "content": content,
+            "variant": variant,
+        });
+        let url = self.url(&format!("/api/task-attempts/{}/message-queue", attempt_id));
+        let message: QueuedMessage =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(m) => m,
+                Err(e) => return Ok(e),
+            };
+
+        let response = QueueMessageResponse {
+            attempt_id: attempt_id.to_string(),
+            message_id: message.id.to_string(),
+            position: message.position,
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(description = "List approvals and questions waiting for an answer.")]
+    async fn list_pending_approvals(
+        &self,
+        Parameters(ListPendingApprovalsRequest {
+            execution_process_id,
+        }): Parameters<ListPendingApprovalsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = match execution_process_id {
+            Some(id) => self.url(&format!(
+                "/api/approvals/pending?execution_process_id={}",
+                id
+            )),
+            None => self.url("/api/approvals/pending"),
+        };
+        let pending: Vec<ApprovalRequest> = match self.send_json(self.client.get(&url)).await {
+            Ok(p) => p,
+            Err(e) => return Ok(e),
+        };
+
+        let mut visible = Vec::with_capacity(pending.len());
+        for approval in pending {
+            if self.scope.is_none()
+                || self
+                    .project_for_execution_process(approval.execution_process_id)
+                    .await
+                    .is_some_and(|id| self.allows_project(id))
+            {
+                visible.push(approval);
+            }
+        }
+
+        let approvals: Vec<PendingApprovalSummary> = visible
+            .into_iter()
+            .map(|a| PendingApprovalSummary {
+                approval_id: a.id,
+                execution_process_id: a.execution_process_id.to_string(),
+                tool_name: a.tool_name,
+                tool_input: a.tool_input,
+                timeout_at: a.timeout_at.to_rfc3339(),
+                questions: a
+                    .questions
+                    .and_then(|questions| serde_json::to_value(questions).ok()),
+            })
+            .collect();
+
+        let response = ListPendingApprovalsResponse {
+            count: approvals.len(),
+            approvals,
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(description = "Approve or deny a pending approval, or answer a question.")]
+    async fn respond_to_approval(
+        &self,
+        Parameters(RespondToApprovalRequest {
+            approval_id,
+            execution_process_id,
+            approve,
+            reason,
+            answers,
+        }): Parameters<RespondToApprovalRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = ApprovalResponse {
+            execution_process_id,
+            status: if approve {
+                ApprovalStatus::Approved
+            } else {
+                ApprovalStatus::Denied { reason }
+            },
+            answers,
+        };
+        let url = self.url(&format!("/api/approvals/{}/respond", approval_id));
+        let status: ApprovalStatus =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(s) => s,
+                Err(e) => return Ok(e),
+            };
+
+        let response = RespondToApprovalResponse {
+            approval_id,
+            status: serde_json::to_value(&status)
+                .ok()
+                .and_then(|v| v["status"].as_str().map(str::to_string))
+                .unwrap_or_default(),
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(description = "Rebase attempt branch onto its target branch or a new base branch.")]
+    async fn rebase_task_attempt(
+        &self,
+        Parameters(RebaseTaskAttemptMcpRequest {
+            attempt_id,
+            new_base_branch,
+        }): Parameters<RebaseTaskAttemptMcpRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = RebaseTaskAttemptRequest {
+            old_base_branch: None,
+            new_base_branch,
+        };
+        let url = self.url(&format!("/api/task-attempts/{}/rebase", attempt_id));
+        if let Err(e) = self.send_ok(self.client.post(&url).json(&payload)).await {
+            return Ok(e);
+        }
+
+        let response = GitOperationResponse {
+            attempt_id: attempt_id.to_string(),
+            operation: "rebase".to_string(),
+            success: true,
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(description = "Merge attempt branch into its target branch.")]
+    async fn merge_task_attempt(
+        &self,
+        Parameters(MergeTaskAttemptRequest { attempt_id }): Parameters<MergeTaskAttemptRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/task-attempts/{}/merge", attempt_id));
+        if let Err(e) = self.send_ok(self.client.post(&url)).await {
+            return Ok(e);
+        }
+
+        let response = GitOperationResponse {
+            attempt_id: attempt_id.to_string(),
+            operation: "merge".to_string(),
+            success: true,
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(description = "Push attempt branch and open a GitHub pull request.")]
+    async fn create_pr(
+        &self,
+        Parameters(CreatePrRequest {
+            attempt_id,
+            title,
+            body,
+            target_branch,
+        }): Parameters<CreatePrRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = CreateGitHubPrRequest {
+            title,
+            body,
+            target_branch,
+        };
+        let url = self.url(&format!("/api/task-attempts/{}/pr", attempt_id));
+        let pr_url: String = match self.send_json(self.client.post(&url).json(&payload)).await {
+            Ok(u) => u,
+            Err(e) => return Ok(e),
+        };
+
+        let response = CreatePrResponse {
+            attempt_id: attempt_id.to_string(),
+            pr_url,
+        };
+
+        TaskServer::success(&response)
+    }
+
     // ===== Label MCP Tools =====
 
     #[tool(description = "Get task labels.")]
@@ -1410,10 +2183,33 @@ impl TaskServer {
     }
 }
 
-#[tool_handler]
 impl ServerHandler for TaskServer {
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(denied) = self.check_project_scope(request.arguments.as_ref()).await {
+            return Ok(denied);
+        }
+        self.tool_router
+            .call(ToolCallContext::new(self, request, context))
+            .await
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        Ok(ListToolsResult {
+            tools: self.tool_router.list_all(),
+            next_cursor: None,
+        })
+    }
+
     fn get_info(&self) -> ServerInfo {
-        let instruction = "Use 'get_context' with your working directory (cwd) to fetch project/task/attempt metadata for the active Vibe Kanban attempt. A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids. Task variables: Use 'get_task_variables', 'set_task_variable', and 'delete_task_variable' to manage variables that are expanded in task descriptions using $VAR or ${VAR} syntax. Task attempts: Use 'stop_task_attempt', 'get_task_attempt_status', and 'list_task_attempts' to control and monitor task execution. Labels: Use 'get_task_labels', 'set_task_labels', and 'list_labels' to manage task labels for categorization. Nodes: Use 'list_nodes' to find swarm nodes available for a task's project.".to_string();
+        let instruction = "Use 'get_context' with your working directory (cwd) to fetch project/task/attempt metadata for the active Vibe Kanban attempt. A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids. Task variables: Use 'get_task_variables', 'set_task_variable', and 'delete_task_variable' to manage variables that are expanded in task descriptions using $VAR or ${VAR} syntax. Task attempts: Use 'stop_task_attempt', 'get_task_attempt_status', and 'list_task_attempts' to control and monitor task execution. Review and steering: Use 'get_attempt_diff' and 'get_attempt_logs' to see what an attempt changed and what its agent is doing, 'send_follow_up' to start another run or 'queue_message' to send one when the current run finishes, 'list_pending_approvals' and 'respond_to_approval' to answer approvals, and 'rebase_task_attempt', 'merge_task_attempt' and 'create_pr' to land the work. Labels: Use 'get_task_labels', 'set_task_labels', and 'list_labels' to manage task labels for categorization. Nodes: Use 'list_nodes' to find swarm nodes available for a task's project.".to_string();
 
         ServerInfo {
             protocol_version: ProtocolVersion::V_2025_03_26,
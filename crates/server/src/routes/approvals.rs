@@ -1,14 +1,20 @@
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::Json as ResponseJson,
-    routing::post,
+    routing::{get, post},
 };
+use db::models::approval_request::StoredApproval;
 use deployment::Deployment;
+use remote::routes::swarm_approvals::{
+    ListSwarmApprovalsResponse, RespondSwarmApprovalRequest, SwarmApprovalResponse,
+};
+use serde::Deserialize;
 use utils::{
-    approvals::{ApprovalResponse, ApprovalStatus},
+    approvals::{ApprovalRequest, ApprovalResponse, ApprovalStatus},
     response::ApiResponse,
 };
+use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
 
@@ -32,6 +38,65 @@ pub async fn respond_to_approval(
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct PendingApprovalsQuery {
+    pub execution_process_id: Option<Uuid>,
+}
+
+/// List approvals and questions still waiting for the user, including ones persisted before a
+/// server restart that no longer appear in the conversation
+pub async fn list_pending_approvals(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<PendingApprovalsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalRequest>>>, ApiError> {
+    let pending =
+        StoredApproval::find_pending(&deployment.db().pool, query.execution_process_id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        pending.into_iter().map(|stored| stored.request).collect(),
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SwarmApprovalsQuery {
+    pub organization_id: Uuid,
+    #[serde(default)]
+    pub include_resolved: bool,
+}
+
+/// List approvals raised on any node of the organization, as forwarded to the hive
+pub async fn list_swarm_approvals(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SwarmApprovalsQuery>,
+) -> Result<ResponseJson<ApiResponse<ListSwarmApprovalsResponse>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let response = client
+        .list_swarm_approvals(query.organization_id, query.include_resolved)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+/// Answer an approval raised on another node; the hive relays the answer to that node
+pub async fn respond_to_swarm_approval(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<RespondSwarmApprovalRequest>,
+) -> Result<ResponseJson<ApiResponse<SwarmApprovalResponse>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let response = client.respond_swarm_approval(id, &request).await?;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
 pub fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/approvals/{id}/respond", post(respond_to_approval))
+    Router::new()
+        .route("/approvals/pending", get(list_pending_approvals))
+        .route("/approvals/{id}/respond", post(respond_to_approval))
+        .route("/approvals/swarm", get(list_swarm_approvals))
+        .route(
+            "/approvals/swarm/{id}/respond",
+            post(respond_to_swarm_approval),
+        )
 }
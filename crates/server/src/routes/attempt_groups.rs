@@ -0,0 +1,220 @@
+//! Race mode: run the same task on several executor profiles at once and keep the best result.
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    attempt_group::AttemptGroup,
+    task::Task,
+    task_attempt::{CreateTaskAttempt, TaskAttempt},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::Deserialize;
+use services::services::{
+    attempt_group::{AttemptGroupComparison, compare_attempts},
+    container::ContainerService,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Upper bound on attempts in one race, each of which gets its own worktree and agent process
+const MAX_RACE_ATTEMPTS: usize = 8;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateAttemptGroupRequest {
+    pub task_id: Uuid,
+    pub base_branch: String,
+    /// One attempt is started per profile
+    pub executor_profile_ids: Vec<ExecutorProfileId>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PromoteAttemptRequest {
+    pub task_attempt_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttemptGroupQuery {
+    pub task_id: Uuid,
+}
+
+/// GET /api/attempt-groups?task_id= — list a task's races, newest first
+pub async fn list_attempt_groups(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AttemptGroupQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptGroup>>>, ApiError> {
+    let groups = AttemptGroup::find_by_task_id(&deployment.db().pool, query.task_id).await?;
+    Ok(ResponseJson(ApiResponse::success(groups)))
+}
+
+/// POST /api/attempt-groups — start one attempt per executor profile, each in its own worktree
+pub async fn create_attempt_group(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateAttemptGroupRequest>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupComparison>>, ApiError> {
+    if payload.executor_profile_ids.len() < 2 {
+        return Err(ApiError::BadRequest(
+            "A race needs at least two executor profiles".to_string(),
+        ));
+    }
+    if payload.executor_profile_ids.len() > MAX_RACE_ATTEMPTS {
+        return Err(ApiError::BadRequest(format!(
+            "A race can run at most {MAX_RACE_ATTEMPTS} attempts"
+        )));
+    }
+
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Task::unarchive_if_archived(pool, task.id).await?;
+
+    let origin_node_id = if let Some(ctx) = deployment.node_runner_context() {
+        ctx.node_id().await
+    } else {
+        None
+    };
+
+    let group = AttemptGroup::create(pool, Uuid::new_v4(), task.id).await?;
+    let mut started = 0;
+    for executor_profile_id in &payload.executor_profile_ids {
+        let attempt_id = Uuid::new_v4();
+        let branch = deployment
+            .container()
+            .git_branch_from_task_attempt(&attempt_id, &task.title)
+            .await;
+        let task_attempt = TaskAttempt::create(
+            pool,
+            &CreateTaskAttempt {
+                executor: executor_profile_id.executor,
+                base_branch: payload.base_branch.clone(),
+                branch,
+                origin_node_id,
+            },
+            attempt_id,
+            task.id,
+        )
+        .await?;
+        AttemptGroup::add_member(pool, group.id, task_attempt.id, executor_profile_id).await?;
+
+        // A profile that fails to start drops out of the race without stopping the others
+        if let Err(err) = deployment
+            .container()
+            .start_attempt(&task_attempt, executor_profile_id.clone(), false)
+            .await
+        {
+            tracing::error!(
+                task_id = %task.id,
+                attempt_id = %task_attempt.id,
+                executor = %executor_profile_id,
+                error = %err,
+                "Failed to start race attempt"
+            );
+            if let Err(delete_err) = TaskAttempt::delete(pool, task_attempt.id).await {
+                tracing::error!(
+                    attempt_id = %task_attempt.id,
+                    error = %delete_err,
+                    "Failed to clean up broken race attempt"
+                );
+            }
+            continue;
+        }
+        started += 1;
+    }
+
+    if started == 0 {
+        AttemptGroup::delete(pool, group.id).await?;
+        return Err(ApiError::BadRequest(
+            "None of the race attempts could be started".to_string(),
+        ));
+    }
+    tracing::info!(task_id = %task.id, group_id = %group.id, started, "Started race");
+
+    let comparison = compare_attempts(pool, deployment.git(), group).await?;
+    Ok(ResponseJson(ApiResponse::success(comparison)))
+}
+
+/// GET /api/attempt-groups/:id — per-attempt diff stats, script results, duration and tokens
+pub async fn get_attempt_group_comparison(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupComparison>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let group = AttemptGroup::find_by_id(pool, group_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    let comparison = compare_attempts(pool, deployment.git(), group).await?;
+    Ok(ResponseJson(ApiResponse::success(comparison)))
+}
+
+/// POST /api/attempt-groups/:id/promote — keep one attempt, stop the others and delete their
+/// worktrees. Their branches and history are kept.
+pub async fn promote_attempt(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+    Json(payload): Json<PromoteAttemptRequest>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupComparison>>, ApiError> {
+    let pool = &deployment.db().pool;
+    AttemptGroup::find_by_id(pool, group_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    let members = AttemptGroup::members(pool, group_id).await?;
+    if !members
+        .iter()
+        .any(|member| member.task_attempt_id == payload.task_attempt_id)
+    {
+        return Err(ApiError::BadRequest(
+            "Attempt is not part of this race".to_string(),
+        ));
+    }
+
+    let group = AttemptGroup::promote(pool, group_id, payload.task_attempt_id).await?;
+
+    for member in members {
+        if member.task_attempt_id == payload.task_attempt_id || member.discarded {
+            continue;
+        }
+        let Some(task_attempt) = TaskAttempt::find_by_id(pool, member.task_attempt_id).await?
+        else {
+            continue;
+        };
+        if task_attempt.worktree_deleted {
+            continue;
+        }
+        // Stops running processes, then removes the worktree
+        if let Err(e) = deployment.container().delete(&task_attempt).await {
+            tracing::warn!(
+                attempt_id = %task_attempt.id,
+                error = %e,
+                "Failed to clean up losing race attempt"
+            );
+            continue;
+        }
+        TaskAttempt::mark_worktree_deleted(pool, task_attempt.id).await?;
+    }
+    tracing::info!(
+        group_id = %group.id,
+        winner_attempt_id = %payload.task_attempt_id,
+        "Promoted race winner"
+    );
+
+    let comparison = compare_attempts(pool, deployment.git(), group).await?;
+    Ok(ResponseJson(ApiResponse::success(comparison)))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/attempt-groups",
+            get(list_attempt_groups).post(create_attempt_group),
+        )
+        .route("/attempt-groups/{id}", get(get_attempt_group_comparison))
+        .route("/attempt-groups/{id}/promote", post(promote_attempt))
+}
@@ -155,13 +155,15 @@ async fn get_user_system_info(
 }
 
 async fn get_agent_runtime_capabilities(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
     Query(query): Query<McpServerQuery>,
 ) -> ResponseJson<ApiResponse<AgentRuntimeCapabilities>> {
     let profiles = ExecutorConfigs::get_cached();
-    let Some(agent) = profiles.get_coding_agent(&ExecutorProfileId::new(query.executor)) else {
+    let Some(mut agent) = profiles.get_coding_agent(&ExecutorProfileId::new(query.executor)) else {
         return ResponseJson(ApiResponse::error("Executor not found"));
     };
+    // Discover with the env the agent runs with; there are no task variables outside a task
+    agent.resolve_env(&deployment.secrets().values().await);
 
     let current_dir = match std::env::current_dir() {
         Ok(dir) => dir,
@@ -13,8 +13,9 @@ use db::models::execution_process::{
     ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus,
 };
 use deployment::Deployment;
+use executors::logs::NormalizedEntry;
 use futures_util::TryStreamExt;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use services::services::container::ContainerService;
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
@@ -48,6 +49,34 @@ pub struct InjectMessageRequest {
     pub content: String,
 }
 
+/// Query parameters for the normalized entries endpoint.
+#[derive(Debug, Deserialize)]
+pub struct NormalizedEntriesQuery {
+    /// Only return the last `limit` entries
+    pub limit: Option<usize>,
+}
+
+/// A slice of a process's normalized log. `entries` are the last entries of the log,
+/// the first of them at index `total - entries.len()`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NormalizedEntriesResponse {
+    pub total: usize,
+    pub entries: Vec<NormalizedEntry>,
+}
+
+pub async fn get_execution_processes(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExecutionProcessQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionProcess>>>, ApiError> {
+    let processes = ExecutionProcess::find_by_task_attempt_id(
+        &deployment.db().pool,
+        query.task_attempt_id,
+        query.show_soft_deleted.unwrap_or(false),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(processes)))
+}
+
 pub async fn get_execution_process_by_id(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(_deployment): State<DeploymentImpl>,
@@ -189,6 +218,25 @@ async fn handle_normalized_logs_ws(
     run_ws_stream(socket, stream, WsKeepAlive::for_execution_streams()).await
 }
 
+/// Get the normalized log entries of a process, optionally only the most recent ones.
+pub async fn get_normalized_entries(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<NormalizedEntriesQuery>,
+) -> Result<ResponseJson<ApiResponse<NormalizedEntriesResponse>>, ApiError> {
+    let mut entries = deployment
+        .container()
+        .normalized_entries(&execution_process.id)
+        .await;
+    let total = entries.len();
+    if let Some(limit) = query.limit {
+        entries.drain(..total.saturating_sub(limit));
+    }
+    Ok(ResponseJson(ApiResponse::success(
+        NormalizedEntriesResponse { total, entries },
+    )))
+}
+
 pub async fn stop_execution_process(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(deployment): State<DeploymentImpl>,
@@ -278,12 +326,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/inject-message", post(inject_message))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
+        .route("/normalized-entries", get(get_normalized_entries))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_execution_process_middleware,
         ));
 
     let task_attempts_router = Router::new()
+        .route("/", get(get_execution_processes))
         .route("/stream/ws", get(stream_execution_processes_ws))
         .nest("/{id}", task_attempt_id_router);
 
@@ -3,6 +3,7 @@
 //! This module provides:
 //! - REST API for fetching log entries with cursor-based pagination
 //! - WebSocket endpoint for live-only log streaming
+//! - Full-text search across the normalized logs of finished local executions
 //!
 //! Both endpoints work for local and remote executions.
 //!
@@ -18,7 +19,11 @@ use axum::{
     response::{IntoResponse, Json as ResponseJson},
     routing::get,
 };
-use db::models::execution_process::ExecutionProcessError;
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::ExecutionProcessError,
+    log_search::{LogSearch, LogSearchEntryType, LogSearchFilter, LogSearchHit},
+};
 use deployment::Deployment;
 use futures_util::TryStreamExt;
 use serde::Deserialize;
@@ -261,10 +266,77 @@ pub async fn get_logs_for_attempt(
     Ok(ResponseJson(ApiResponse::success(paginated)))
 }
 
+/// Default number of search hits to return per page.
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+
+/// Maximum number of search hits to return per page.
+const MAX_SEARCH_LIMIT: i64 = 200;
+
+/// Query parameters for the log search endpoint.
+#[derive(Debug, Deserialize)]
+pub struct LogSearchParams {
+    /// Words to search for; every word must match
+    pub q: String,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub task_attempt_id: Option<Uuid>,
+    /// Base coding agent, e.g. `CLAUDE_CODE`
+    pub executor: Option<String>,
+    pub entry_type: Option<LogSearchEntryType>,
+    /// Only executions started at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only executions started before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Maximum hits to return. Defaults to 50, max 200.
+    pub limit: Option<i64>,
+    /// Number of hits to skip
+    pub offset: Option<i64>,
+}
+
+/// GET /api/logs/search?q=...
+///
+/// Searches the assistant messages, tool calls (commands, output, file paths and URLs) and
+/// errors of finished local executions, best matches first. Each hit carries the execution id
+/// and the entry's index in its normalized conversation.
+pub async fn search_logs(
+    State(deployment): State<DeploymentImpl>,
+    Query(params): Query<LogSearchParams>,
+) -> Result<ResponseJson<ApiResponse<Vec<LogSearchHit>>>, ApiError> {
+    if params.q.trim().is_empty() {
+        return Err(ApiError::BadRequest("`q` must not be empty".to_string()));
+    }
+    if let (Some(since), Some(until)) = (params.since, params.until)
+        && since >= until
+    {
+        return Err(ApiError::BadRequest(
+            "`since` must be earlier than `until`".to_string(),
+        ));
+    }
+
+    let filter = LogSearchFilter {
+        project_id: params.project_id,
+        task_id: params.task_id,
+        task_attempt_id: params.task_attempt_id,
+        executor: params.executor,
+        entry_type: params.entry_type,
+        since: params.since,
+        until: params.until,
+    };
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let offset = params.offset.unwrap_or(0).max(0);
+    let hits = LogSearch::search(&deployment.db().pool, &params.q, &filter, limit, offset).await?;
+
+    Ok(ResponseJson(ApiResponse::success(hits)))
+}
+
 /// Create the router for log endpoints.
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let _ = deployment; // Reserved for future middleware
     Router::new()
+        .route("/logs/search", get(search_logs))
         .route("/logs/{execution_id}", get(get_logs))
         .route("/logs/{execution_id}/live", get(stream_live_logs_ws))
         .route("/logs/attempt/{attempt_id}", get(get_logs_for_attempt))
@@ -0,0 +1,92 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::{
+    mcp_client_token::{CreateMcpClientToken, McpClientToken},
+    project::Project,
+};
+use deployment::Deployment;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    mcp::http::{generate_token, hash_token},
+};
+
+/// A newly issued token. `token` is only returned here; just its hash is stored.
+#[derive(Debug, Serialize, TS)]
+pub struct IssuedMcpClientToken {
+    pub client: McpClientToken,
+    pub token: String,
+}
+
+/// GET /api/mcp/tokens — list issued tokens, including revoked ones
+pub async fn list_mcp_tokens(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<McpClientToken>>>, ApiError> {
+    let tokens = McpClientToken::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(tokens)))
+}
+
+/// POST /api/mcp/tokens — issue a token for an MCP client
+pub async fn create_mcp_token(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateMcpClientToken>,
+) -> Result<ResponseJson<ApiResponse<IssuedMcpClientToken>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest("Token name cannot be empty".into()));
+    }
+    if let Some(project_ids) = &payload.project_ids {
+        if project_ids.is_empty() {
+            return Err(ApiError::BadRequest(
+                "Select at least one project, or omit project_ids to allow every project".into(),
+            ));
+        }
+        for project_id in project_ids {
+            if Project::find_by_id(pool, *project_id).await?.is_none() {
+                return Err(ApiError::BadRequest(format!(
+                    "Project not found: {project_id}"
+                )));
+            }
+        }
+    }
+
+    let (token, prefix) = generate_token();
+    let client = McpClientToken::create(pool, &payload, &hash_token(&token), &prefix).await?;
+    tracing::info!(
+        token_id = %client.id,
+        name = %client.name,
+        access = ?client.access,
+        "Issued MCP client token"
+    );
+    Ok(ResponseJson(ApiResponse::success(IssuedMcpClientToken {
+        client,
+        token,
+    })))
+}
+
+/// DELETE /api/mcp/tokens/:id — revoke a token. Its client is rejected from the next request on.
+pub async fn revoke_mcp_token(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if !McpClientToken::revoke(&deployment.db().pool, id).await? {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    tracing::info!(token_id = %id, "Revoked MCP client token");
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/mcp/tokens", get(list_mcp_tokens).post(create_mcp_token))
+        .route("/mcp/tokens/{id}", delete(revoke_mcp_token))
+}
@@ -3,10 +3,11 @@ use axum::{
     routing::{IntoMakeService, get},
 };
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, mcp};
 
 pub mod all_tasks;
 pub mod approvals;
+pub mod attempt_groups;
 pub mod backups;
 pub mod config;
 pub mod containers;
@@ -23,16 +24,20 @@ pub mod health;
 pub mod images;
 pub mod labels;
 pub mod logs;
+pub mod mcp_tokens;
 pub mod message_queue;
 pub mod oauth;
 pub mod organizations;
+pub mod pipelines;
 pub mod processes;
 pub mod projects;
+pub mod secrets;
 pub mod task_attempts;
 pub mod task_variables;
 pub mod tasks;
 pub mod templates;
 pub mod terminal;
+pub mod usage;
 pub mod webhooks;
 
 pub async fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
@@ -51,6 +56,8 @@ pub async fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(tasks::router(&deployment))
         .merge(all_tasks::router(&deployment))
         .merge(task_attempts::router(&deployment))
+        .merge(attempt_groups::router(&deployment))
+        .merge(usage::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(processes::router(&deployment))
         .merge(templates::router(&deployment))
@@ -65,11 +72,14 @@ pub async fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(database::router())
         .merge(diagnostics::router(&deployment))
         .merge(logs::router(&deployment))
+        .merge(mcp_tokens::router(&deployment))
         .merge(message_queue::router(&deployment))
         .merge(webhooks::router(&deployment))
+        .merge(pipelines::router(&deployment))
+        .merge(secrets::router(&deployment))
         .merge(terminal_router)
         .nest("/images", images::routes())
-        .with_state(deployment);
+        .with_state(deployment.clone());
 
     Router::new()
         .nest("/api", base_routes)
@@ -77,3 +87,12 @@ pub async fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .route("/{*path}", get(frontend::serve_frontend))
         .into_make_service()
 }
+
+/// The token-authenticated MCP endpoint. It is served on its own listener, never next to the
+/// unauthenticated API, so it can be exposed to other machines while the API is not.
+/// `backend_url` is where it reaches this server's API.
+pub fn mcp_router(deployment: DeploymentImpl, backend_url: String) -> IntoMakeService<Router> {
+    mcp::http::router(backend_url)
+        .with_state(deployment)
+        .into_make_service()
+}
@@ -0,0 +1,65 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::{
+    project::Project,
+    project_pipeline::{ProjectPipeline, UpsertProjectPipeline},
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// GET /api/projects/:project_id/pipeline — get the project's pipeline, if configured
+pub async fn get_project_pipeline(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectPipeline>>>, ApiError> {
+    let pipeline = ProjectPipeline::find_by_project_id(&deployment.db().pool, project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(pipeline)))
+}
+
+/// PUT /api/projects/:project_id/pipeline — create or replace the project's pipeline
+pub async fn upsert_project_pipeline(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<UpsertProjectPipeline>,
+) -> Result<ResponseJson<ApiResponse<ProjectPipeline>>, ApiError> {
+    if Project::find_by_id(&deployment.db().pool, project_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    payload
+        .pipeline
+        .validate()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let pipeline = ProjectPipeline::upsert(&deployment.db().pool, project_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(pipeline)))
+}
+
+/// DELETE /api/projects/:project_id/pipeline — remove the pipeline, restoring the default chain
+pub async fn delete_project_pipeline(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows = ProjectPipeline::delete(&deployment.db().pool, project_id).await?;
+    if rows == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().route(
+        "/projects/{project_id}/pipeline",
+        get(get_project_pipeline)
+            .put(upsert_project_pipeline)
+            .delete(delete_project_pipeline),
+    )
+}
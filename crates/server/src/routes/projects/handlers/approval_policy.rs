@@ -0,0 +1,154 @@
+//! Approval policy handlers for projects.
+//!
+//! This module contains handlers for the project's approval policy:
+//! - get_approval_policy: Get the project's ordered approval rules
+//! - set_approval_policy: Replace the approval rules
+//! - get_approval_policy_decisions: List recent decisions made while agents ran
+//! - dry_run_approval_policy: Evaluate rules against the tool calls of a past execution
+
+use std::collections::BTreeMap;
+
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    approval_policy::{
+        ApprovalPolicyDecision, ApprovalPolicyRule, ApprovalPolicyRuleInput, SetApprovalPolicy,
+    },
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    project::Project,
+};
+use deployment::Deployment;
+use executors::logs::utils::patch::extract_normalized_entry_from_patch;
+use futures_util::StreamExt;
+use serde::Deserialize;
+use services::services::{
+    approvals::policy::{self, ApprovalPolicyDryRunEntry},
+    container::ContainerService,
+};
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const DEFAULT_DECISION_LIMIT: i64 = 50;
+const MAX_DECISION_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct ApprovalPolicyDecisionsQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ApprovalPolicyDryRunRequest {
+    pub execution_process_id: Uuid,
+    /// Rules to test; the project's saved rules are used when unset
+    pub rules: Option<Vec<ApprovalPolicyRuleInput>>,
+}
+
+/// Get the project's approval rules in evaluation order
+pub async fn get_approval_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalPolicyRule>>>, ApiError> {
+    let rules = ApprovalPolicyRule::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+/// Replace the project's approval rules. An empty list removes the policy, so every tool call
+/// is left to the user again.
+pub async fn set_approval_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetApprovalPolicy>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalPolicyRule>>>, ApiError> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let rules =
+        ApprovalPolicyRule::replace_for_project(&deployment.db().pool, project.id, &payload.rules)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+/// List the project's most recent approval decisions, newest first
+pub async fn get_approval_policy_decisions(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ApprovalPolicyDecisionsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalPolicyDecision>>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DECISION_LIMIT)
+        .clamp(1, MAX_DECISION_LIMIT);
+    let decisions =
+        ApprovalPolicyDecision::find_by_project_id(&deployment.db().pool, project.id, limit)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(decisions)))
+}
+
+/// Show how the given rules, or the saved ones, would have decided each tool call of a finished
+/// execution in the project
+pub async fn dry_run_approval_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ApprovalPolicyDryRunRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalPolicyDryRunEntry>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let ctx = ExecutionProcess::load_context(pool, payload.execution_process_id).await?;
+    if ctx.task.project_id != project.id {
+        return Err(ApiError::NotFound(
+            "Execution process not found in this project".to_string(),
+        ));
+    }
+    // A running execution's log stream doesn't end until the process does
+    if ctx.execution_process.status == ExecutionProcessStatus::Running {
+        return Err(ApiError::BadRequest(
+            "Dry runs need an execution that has finished".to_string(),
+        ));
+    }
+
+    let rules = match payload.rules {
+        Some(rules) => {
+            let policy = SetApprovalPolicy { rules };
+            policy
+                .validate()
+                .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            policy
+                .rules
+                .iter()
+                .enumerate()
+                .map(|(position, input)| input.to_rule(project.id, position))
+                .collect()
+        }
+        None => ApprovalPolicyRule::find_by_project_id(pool, project.id).await?,
+    };
+
+    let Some(mut stream) = deployment
+        .container()
+        .stream_normalized_logs(&payload.execution_process_id)
+        .await
+    else {
+        return Ok(ResponseJson(ApiResponse::success(Vec::new())));
+    };
+    // Entries are replaced as tool calls progress; keep the latest version of each
+    let mut entries = BTreeMap::new();
+    while let Some(Ok(msg)) = stream.next().await {
+        match msg {
+            LogMsg::JsonPatch(patch) => {
+                if let Some((index, entry)) = extract_normalized_entry_from_patch(&patch) {
+                    entries.insert(index, entry);
+                }
+            }
+            LogMsg::Finished => break,
+            _ => {}
+        }
+    }
+
+    Ok(ResponseJson(ApiResponse::success(policy::dry_run(
+        &rules, entries,
+    ))))
+}
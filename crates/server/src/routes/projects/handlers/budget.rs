@@ -0,0 +1,59 @@
+//! Usage budget handlers for projects.
+//!
+//! This module contains handlers for the project's usage budget:
+//! - get_project_budget: Get the budget and what has been spent against it
+//! - set_project_budget: Create or replace the budget
+//! - delete_project_budget: Remove the budget
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    project::Project,
+    usage_budget::{SetUsageBudget, UsageBudget, UsageBudgetStatus},
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Get the project's usage budget with its current spend, or null when none is set
+pub async fn get_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<UsageBudgetStatus>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let status = match UsageBudget::find_by_project_id(pool, project.id).await? {
+        Some(budget) => Some(budget.status(pool).await?),
+        None => None,
+    };
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Create or replace the project's usage budget. Coding agent runs in the project are stopped
+/// once either limit is reached.
+pub async fn set_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetUsageBudget>,
+) -> Result<ResponseJson<ApiResponse<UsageBudgetStatus>>, ApiError> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let pool = &deployment.db().pool;
+    let budget = UsageBudget::set_for_project(pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        budget.status(pool).await?,
+    )))
+}
+
+/// Remove the project's usage budget
+pub async fn delete_project_budget(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if UsageBudget::delete_for_project(&deployment.db().pool, project.id).await? == 0 {
+        return Err(ApiError::NotFound(
+            "Project has no usage budget".to_string(),
+        ));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
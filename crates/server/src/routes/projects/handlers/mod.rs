@@ -6,14 +6,27 @@
 //! - `files`: File browser, search, and file content
 //! - `linking`: Remote project linking and members
 //! - `github`: GitHub integration (enable, counts, sync)
+//! - `verification`: Verification script settings
+//! - `script_language`: Language the project's scripts run in
+//! - `budget`: Usage budget settings
+//! - `approval_policy`: Approval rules, their decisions and dry runs
 
+pub mod approval_policy;
+pub mod budget;
 pub mod core;
 pub mod files;
 pub mod github;
 pub mod linking;
+pub mod script_language;
 pub mod swarm;
+pub mod verification;
 
 // Re-export all handlers for convenient access from the router
+pub use approval_policy::{
+    dry_run_approval_policy, get_approval_policy, get_approval_policy_decisions,
+    set_approval_policy,
+};
+pub use budget::{delete_project_budget, get_project_budget, set_project_budget};
 pub use core::{
     apply_remote_project_link, create_project, delete_orphaned_projects, delete_project,
     get_project, get_project_branches, get_project_sync_health, get_projects,
@@ -25,4 +38,6 @@ pub use files::{
 };
 pub use github::{get_github_counts, set_github_enabled, sync_github_counts};
 pub use linking::{get_project_remote_members, get_remote_project_by_id, link_to_local_folder};
+pub use script_language::{get_project_script_language, update_project_script_language};
 pub use swarm::{force_resync_tasks, unlink_from_swarm};
+pub use verification::{get_project_verification, update_project_verification};
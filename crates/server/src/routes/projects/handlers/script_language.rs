@@ -0,0 +1,37 @@
+//! Script language handlers for projects.
+//!
+//! This module contains handlers for the language the project's scripts run in:
+//! - get_project_script_language: Get the script language
+//! - update_project_script_language: Set the script language
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::project::{
+    Project, ProjectError, ProjectScriptLanguage, UpdateProjectScriptLanguage,
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Get the script language for a project
+pub async fn get_project_script_language(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectScriptLanguage>>, ApiError> {
+    let script_language = Project::find_script_language(&deployment.db().pool, project.id)
+        .await?
+        .ok_or(ProjectError::ProjectNotFound)?;
+    Ok(ResponseJson(ApiResponse::success(script_language)))
+}
+
+/// Set the language the project's setup, dev, cleanup, verification and pipeline scripts
+/// run in
+pub async fn update_project_script_language(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectScriptLanguage>,
+) -> Result<ResponseJson<ApiResponse<ProjectScriptLanguage>>, ApiError> {
+    let script_language =
+        Project::update_script_language(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(script_language)))
+}
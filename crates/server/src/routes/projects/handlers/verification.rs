@@ -0,0 +1,35 @@
+//! Verification script handlers for projects.
+//!
+//! This module contains handlers for the project's verification settings:
+//! - get_project_verification: Get the verification script and retry limit
+//! - update_project_verification: Set or clear the verification script
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::project::{Project, ProjectError, ProjectVerification, UpdateProjectVerification};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Get the verification settings for a project
+pub async fn get_project_verification(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectVerification>>, ApiError> {
+    let verification = Project::find_verification(&deployment.db().pool, project.id)
+        .await?
+        .ok_or(ProjectError::ProjectNotFound)?;
+    Ok(ResponseJson(ApiResponse::success(verification)))
+}
+
+/// Set the verification script and retry limit for a project. A blank or missing script
+/// disables verification.
+pub async fn update_project_verification(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectVerification>,
+) -> Result<ResponseJson<ApiResponse<ProjectVerification>>, ApiError> {
+    let verification =
+        Project::update_verification(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(verification)))
+}
@@ -29,15 +29,26 @@ use handlers::{
     create_project,
     delete_orphaned_projects,
     delete_project,
+    // Budget handlers
+    delete_project_budget,
+    // Approval policy handlers
+    dry_run_approval_policy,
     // Swarm handlers
     force_resync_tasks,
+    get_approval_policy,
+    get_approval_policy_decisions,
     // GitHub handlers
     get_github_counts,
     get_project,
     get_project_branches,
+    get_project_budget,
     // Linking handlers
     get_project_remote_members,
+    // Script language handlers
+    get_project_script_language,
     get_project_sync_health,
+    // Verification handlers
+    get_project_verification,
     get_projects,
     link_to_local_folder,
     list_orphaned_projects,
@@ -48,10 +59,14 @@ use handlers::{
     read_project_file_by_remote_id,
     scan_project_config,
     search_project_files,
+    set_approval_policy,
     set_github_enabled,
+    set_project_budget,
     sync_github_counts,
     unlink_from_swarm,
     update_project,
+    update_project_script_language,
+    update_project_verification,
 };
 
 /// Builds the axum router containing all project-related HTTP routes.
@@ -91,6 +106,33 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/sync-health", get(get_project_sync_health))
         .route("/unlink-swarm", post(unlink_from_swarm))
         .route("/force-resync-tasks", post(force_resync_tasks))
+        // Verification script endpoints
+        .route(
+            "/verification",
+            get(get_project_verification).put(update_project_verification),
+        )
+        // Script language endpoints
+        .route(
+            "/script-language",
+            get(get_project_script_language).put(update_project_script_language),
+        )
+        // Usage budget endpoints
+        .route(
+            "/budget",
+            get(get_project_budget)
+                .put(set_project_budget)
+                .delete(delete_project_budget),
+        )
+        // Approval policy endpoints
+        .route(
+            "/approval-policy",
+            get(get_approval_policy).put(set_approval_policy),
+        )
+        .route(
+            "/approval-policy/decisions",
+            get(get_approval_policy_decisions),
+        )
+        .route("/approval-policy/dry-run", post(dry_run_approval_policy))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
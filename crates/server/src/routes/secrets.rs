@@ -0,0 +1,73 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::secrets::{SecretInfo, SecretStoreError};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetSecretRequest {
+    pub value: String,
+}
+
+fn secret_error(e: SecretStoreError) -> ApiError {
+    match e {
+        SecretStoreError::InvalidName(_) => ApiError::BadRequest(e.to_string()),
+        SecretStoreError::Io(e) => ApiError::Io(e),
+        SecretStoreError::Serde(e) => ApiError::Io(std::io::Error::other(e)),
+    }
+}
+
+/// List stored secret names. Values are never returned.
+pub async fn list_secrets(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<SecretInfo>>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        deployment.secrets().list().await,
+    )))
+}
+
+/// Create or replace a secret
+pub async fn set_secret(
+    State(deployment): State<DeploymentImpl>,
+    Path(name): Path<String>,
+    Json(payload): Json<SetSecretRequest>,
+) -> Result<ResponseJson<ApiResponse<SecretInfo>>, ApiError> {
+    deployment
+        .secrets()
+        .set(&name, payload.value)
+        .await
+        .map_err(secret_error)?;
+    Ok(ResponseJson(ApiResponse::success(SecretInfo { name })))
+}
+
+pub async fn delete_secret(
+    State(deployment): State<DeploymentImpl>,
+    Path(name): Path<String>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let removed = deployment
+        .secrets()
+        .remove(&name)
+        .await
+        .map_err(secret_error)?;
+    if !removed {
+        return Err(ApiError::NotFound(format!("Secret '{name}' not found")));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().nest(
+        "/secrets",
+        Router::new()
+            .route("/", get(list_secrets))
+            .route("/{name}", put(set_secret).delete(delete_secret)),
+    )
+}
@@ -0,0 +1,85 @@
+//! Command timeline handlers.
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    task_attempt::TaskAttempt,
+};
+use deployment::Deployment;
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use services::services::{command_timeline::TimelineCommand, container::ContainerService};
+use utils::response::ApiResponse;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::RemoteTaskAttemptContext,
+    routes::task_attempts::{types::RerunCommandRequest, util::ensure_worktree_path},
+};
+
+fn reject_remote(remote_ctx: Option<&Extension<RemoteTaskAttemptContext>>) -> Result<(), ApiError> {
+    if remote_ctx.is_some() {
+        return Err(ApiError::BadRequest(
+            "Commands are only available on the node that ran the attempt".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// GET /task-attempts/:id/commands — every shell command the attempt's agents ran, oldest first
+pub async fn list_commands(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    remote_ctx: Option<Extension<RemoteTaskAttemptContext>>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TimelineCommand>>>, ApiError> {
+    reject_remote(remote_ctx.as_ref())?;
+    let commands = deployment
+        .container()
+        .command_timeline(&task_attempt)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(commands)))
+}
+
+/// POST /task-attempts/:id/commands/rerun — run a command from the timeline again as a script
+/// in the attempt's worktree
+pub async fn rerun_command(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    remote_ctx: Option<Extension<RemoteTaskAttemptContext>>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RerunCommandRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    reject_remote(remote_ctx.as_ref())?;
+    let command = deployment
+        .container()
+        .command_timeline(&task_attempt)
+        .await?
+        .into_iter()
+        .find(|command| {
+            command.execution_process_id == payload.execution_process_id
+                && command.entry_index == payload.entry_index
+        })
+        .ok_or_else(|| ApiError::NotFound("Command not found in this attempt".to_string()))?;
+
+    let _ = ensure_worktree_path(&deployment, &task_attempt).await?;
+
+    let executor_action = ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            script: command.command,
+            language: ScriptRequestLanguage::Bash,
+            context: ScriptContext::CommandRerun,
+        }),
+        None,
+    );
+    let execution_process = deployment
+        .container()
+        .start_execution(
+            &task_attempt,
+            &executor_action,
+            &ExecutionProcessRunReason::CommandRerun,
+        )
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
@@ -17,7 +17,7 @@ use deployment::Deployment;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
-        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+        script::{ScriptContext, ScriptRequest},
     },
     executors::{CodingAgent, ExecutorError},
     profile::ExecutorConfigs,
@@ -907,11 +907,11 @@ pub async fn start_dev_server(
     }
 
     if let Some(dev_server) = project.dev_script {
-        // TODO: Derive script language from system config
+        let language = Project::script_language(pool, project.id).await?;
         let executor_action = ExecutorAction::new(
             ExecutorActionType::ScriptRequest(ScriptRequest {
                 script: dev_server,
-                language: ScriptRequestLanguage::Bash,
+                language,
                 context: ScriptContext::DevServer,
             }),
             None,
@@ -319,9 +319,7 @@ pub async fn follow_up(
         }
     };
 
-    let cleanup_action = deployment
-        .container()
-        .cleanup_action(project.cleanup_script);
+    let cleanup_action = deployment.container().cleanup_action(&project).await?;
 
     let action_type = if let Some(session_id) = latest_session_id {
         ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
@@ -15,6 +15,7 @@ use services::services::{
     container::ContainerService,
     git::{ConflictOp, GitCliError, GitServiceError},
     github::GitHubService,
+    webhook::{WebhookEventPayload, WebhookService},
 };
 use utils::response::ApiResponse;
 
@@ -97,6 +98,14 @@ pub async fn merge_task_attempt(
     )
     .await?;
     Task::update_status(pool, ctx.task.id, TaskStatus::Done).await?;
+    WebhookService::spawn_attempt_event(
+        pool,
+        task_attempt.id,
+        WebhookEventPayload::MergeCompleted {
+            merge_commit: merge_commit_id.clone(),
+            target_branch: ctx.task_attempt.target_branch.clone(),
+        },
+    );
 
     // Stop any running dev servers for this task attempt
     let dev_servers =
@@ -298,15 +307,29 @@ pub async fn rebase_task_attempt(
     );
     if let Err(e) = result {
         return match e {
-            GitServiceError::MergeConflicts(msg) => Ok(ResponseJson(ApiResponse::<
-                (),
-                GitOperationError,
-            >::error_with_data(
-                GitOperationError::MergeConflicts {
-                    message: msg,
-                    op: ConflictOp::Rebase,
-                },
-            ))),
+            GitServiceError::MergeConflicts(msg) => {
+                let conflicted_files = deployment
+                    .git()
+                    .get_conflicted_files(worktree_path)
+                    .unwrap_or_default();
+                WebhookService::spawn_attempt_event(
+                    pool,
+                    task_attempt.id,
+                    WebhookEventPayload::RebaseConflict {
+                        onto_branch: new_base_branch.clone(),
+                        conflicted_files,
+                        message: msg.clone(),
+                    },
+                );
+                Ok(ResponseJson(
+                    ApiResponse::<(), GitOperationError>::error_with_data(
+                        GitOperationError::MergeConflicts {
+                            message: msg,
+                            op: ConflictOp::Rebase,
+                        },
+                    ),
+                ))
+            }
             GitServiceError::RebaseInProgress => Ok(ResponseJson(ApiResponse::<
                 (),
                 GitOperationError,
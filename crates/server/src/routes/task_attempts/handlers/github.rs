@@ -13,6 +13,7 @@ use git2::BranchType;
 use services::services::{
     git::{GitCliError, GitServiceError},
     github::{CreatePrRequest, GitHubService, GitHubServiceError},
+    webhook::{WebhookEventPayload, WebhookService},
 };
 use utils::response::ApiResponse;
 
@@ -156,7 +157,7 @@ pub async fn create_github_pr(
     match github_service.create_pr(&repo_info, &pr_request).await {
         Ok(pr_info) => {
             // Update the task attempt with PR information
-            if let Err(e) = Merge::create_pr(
+            match Merge::create_pr(
                 pool,
                 task_attempt.id,
                 &norm_target_branch_name,
@@ -165,7 +166,16 @@ pub async fn create_github_pr(
             )
             .await
             {
-                tracing::error!("Failed to update task attempt PR status: {}", e);
+                Ok(_) => WebhookService::spawn_attempt_event(
+                    pool,
+                    task_attempt.id,
+                    WebhookEventPayload::PrOpened {
+                        url: pr_info.url.clone(),
+                        number: pr_info.number,
+                        target_branch: norm_target_branch_name.clone(),
+                    },
+                ),
+                Err(e) => tracing::error!("Failed to update task attempt PR status: {}", e),
             }
 
             // Auto-open PR in browser
@@ -274,6 +284,30 @@ pub async fn attach_existing_pr(
             .await?;
         }
 
+        match pr_info.status {
+            MergeStatus::Open => WebhookService::spawn_attempt_event(
+                pool,
+                task_attempt.id,
+                WebhookEventPayload::PrOpened {
+                    url: pr_info.url.clone(),
+                    number: pr_info.number,
+                    target_branch: task_attempt.target_branch.clone(),
+                },
+            ),
+            MergeStatus::Merged => WebhookService::spawn_attempt_event(
+                pool,
+                task_attempt.id,
+                WebhookEventPayload::PrMerged {
+                    url: pr_info.url.clone(),
+                    number: pr_info.number,
+                    target_branch: task_attempt.target_branch.clone(),
+                    merge_commit_sha: pr_info.merge_commit_sha.clone(),
+                    merged_at: pr_info.merged_at,
+                },
+            ),
+            _ => {}
+        }
+
         // If PR is merged, mark task as done
         if matches!(pr_info.status, MergeStatus::Merged) {
             Task::update_status(pool, task.id, TaskStatus::Done).await?;
@@ -0,0 +1,174 @@
+//! Session import handler.
+
+use std::path::Path;
+
+use axum::{Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    project::Project,
+    task::Task,
+    task_attempt::{CreateTaskAttempt, TaskAttempt},
+};
+use deployment::Deployment;
+use executors::executors::session_import::ImportedSession;
+use services::services::{
+    container::{ContainerError, ContainerService},
+    git::GitServiceError,
+};
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, routes::task_attempts::types::ImportSessionBody};
+
+/// Create a task attempt from a Claude Code or Codex session started outside the app
+pub async fn import_session(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ImportSessionBody>,
+) -> Result<ResponseJson<ApiResponse<TaskAttempt>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let project = Project::find_by_id(pool, task.project_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    // Read the session before creating anything, so a bad id or file leaves no attempt behind
+    let session = ImportedSession::load(payload.executor_profile_id.executor, &payload.source)
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    // Start the worktree from what the session left in its working directory, uncommitted
+    // changes included, so follow-ups see the code the conversation talks about
+    let start_commit = match session.cwd.as_deref().filter(|cwd| cwd.exists()) {
+        Some(cwd) => Some(
+            deployment
+                .git()
+                .snapshot_checkout(&project.git_repo_path, cwd)
+                .map_err(|e| {
+                    ApiError::BadRequest(format!(
+                        "Cannot import the session's working directory {}: {e}",
+                        cwd.display()
+                    ))
+                })?,
+        ),
+        None => None,
+    };
+
+    let origin_node_id = if let Some(ctx) = deployment.node_runner_context() {
+        ctx.node_id().await
+    } else {
+        None
+    };
+
+    let attempt_id = Uuid::new_v4();
+    let branch = deployment
+        .container()
+        .git_branch_from_task_attempt(&attempt_id, &task.title)
+        .await;
+    let task_attempt = TaskAttempt::create(
+        pool,
+        &CreateTaskAttempt {
+            executor: payload.executor_profile_id.executor,
+            base_branch: payload.base_branch.clone(),
+            branch,
+            origin_node_id,
+        },
+        attempt_id,
+        task.id,
+    )
+    .await?;
+
+    let container = deployment.container();
+    let imported: Result<_, ContainerError> = async {
+        container.create(&task_attempt).await?;
+        let task_attempt = TaskAttempt::find_by_id(pool, task_attempt.id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        if let Some(commit) = &start_commit {
+            let worktree_path = container.task_attempt_to_current_dir(&task_attempt);
+            deployment
+                .git()
+                .reset_worktree_to_commit(&worktree_path, commit, true)?;
+        }
+        container
+            .import_session(&task_attempt, payload.executor_profile_id.clone(), &session)
+            .await
+    }
+    .await;
+
+    if let Err(err) = imported {
+        tracing::error!(
+            task_id = %task.id,
+            attempt_id = %task_attempt.id,
+            error = %err,
+            "Failed to import session"
+        );
+        cleanup_failed_import(&deployment, &project.git_repo_path, &task_attempt).await;
+        if let Err(delete_err) = TaskAttempt::delete(pool, task_attempt.id).await {
+            tracing::error!(
+                task_id = %task.id,
+                attempt_id = %task_attempt.id,
+                error = %delete_err,
+                "Failed to clean up task attempt after failed import"
+            );
+        }
+        return Err(match err {
+            ContainerError::SessionImport(e) => ApiError::BadRequest(e.to_string()),
+            err => ApiError::Container(err),
+        });
+    }
+    tracing::info!(
+        task_id = %task.id,
+        attempt_id = %task_attempt.id,
+        session_id = %session.session_id,
+        turns = session.turns.len(),
+        "Imported session into task attempt"
+    );
+
+    let task_attempt = TaskAttempt::find_by_id(pool, task_attempt.id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    Ok(ResponseJson(ApiResponse::success(task_attempt)))
+}
+
+/// Remove the worktree and branch created for an attempt whose import failed
+async fn cleanup_failed_import(
+    deployment: &DeploymentImpl,
+    repo_path: &Path,
+    task_attempt: &TaskAttempt,
+) {
+    match TaskAttempt::find_by_id(&deployment.db().pool, task_attempt.id).await {
+        Ok(Some(created)) if created.container_ref.is_some() => {
+            if let Err(e) = deployment.container().delete(&created).await {
+                tracing::error!(
+                    attempt_id = %task_attempt.id,
+                    error = %e,
+                    "Failed to remove worktree after failed import"
+                );
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!(
+                attempt_id = %task_attempt.id,
+                error = %e,
+                "Failed to load task attempt after failed import"
+            );
+        }
+    }
+    match deployment
+        .git()
+        .delete_local_branch(repo_path, &task_attempt.branch)
+    {
+        Ok(()) | Err(GitServiceError::BranchNotFound(_)) => {}
+        Err(e) => {
+            tracing::error!(
+                attempt_id = %task_attempt.id,
+                branch = %task_attempt.branch,
+                error = %e,
+                "Failed to delete branch after failed import"
+            );
+        }
+    }
+}
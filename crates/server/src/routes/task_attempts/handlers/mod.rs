@@ -6,16 +6,23 @@
 //! - `review`: Native review execution for supported agents
 //! - `git_ops`: Git operations (merge, rebase, push, stash, branch)
 //! - `github`: PR creation, attachment, gh CLI setup
+//! - `import`: Importing agent sessions started outside the app
+//! - `transcript`: Conversation export as Markdown, JSON or HTML
+//! - `commands`: Timeline of the shell commands agents ran, and re-running them
 //! - `worktree`: File browser, cleanup, worktree path access
 
+pub mod commands;
 pub mod core;
 pub mod follow_up;
 pub mod git_ops;
 pub mod github;
+pub mod import;
 pub mod review;
+pub mod transcript;
 pub mod worktree;
 
 // Re-export all handlers for convenient access from the router
+pub use commands::{list_commands, rerun_command};
 pub use core::{
     compare_commit_to_head, create_task_attempt, create_task_attempt_by_task_id, fix_sessions,
     get_commit_info, get_task_attempt, get_task_attempt_children, get_task_attempts,
@@ -29,8 +36,10 @@ pub use git_ops::{
     push_task_attempt_branch, rebase_task_attempt, rename_branch, stash_changes,
 };
 pub use github::{attach_existing_pr, create_github_pr, gh_cli_setup_handler};
+pub use import::import_session;
 pub use review::review_attempt;
+pub use transcript::export_transcript;
 pub use worktree::{
-    cleanup_worktree, get_worktree_path, list_worktree_files, purge_build_artifacts,
-    read_worktree_file, stream_task_attempt_diff_ws,
+    cleanup_worktree, get_task_attempt_diff, get_worktree_path, list_worktree_files,
+    purge_build_artifacts, read_worktree_file, stream_task_attempt_diff_ws,
 };
@@ -65,9 +65,7 @@ pub async fn review_attempt(
         .parent_project(&deployment.db().pool)
         .await?
         .ok_or(sqlx::Error::RowNotFound)?;
-    let cleanup_action = deployment
-        .container()
-        .cleanup_action(project.cleanup_script);
+    let cleanup_action = deployment.container().cleanup_action(&project).await?;
 
     let executor_configs = ExecutorConfigs::get_cached();
     let coding_agent = executor_configs.get_coding_agent_or_default(&executor_profile_id);
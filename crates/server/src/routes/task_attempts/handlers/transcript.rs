@@ -0,0 +1,49 @@
+//! Transcript export handler.
+
+use axum::{
+    Extension,
+    body::Body,
+    extract::{Query, State},
+    http::{Response, header},
+};
+use db::models::task_attempt::TaskAttempt;
+use deployment::Deployment;
+use services::services::container::ContainerService;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::RemoteTaskAttemptContext,
+    routes::task_attempts::types::TranscriptQuery,
+};
+
+/// Download the attempt's conversation as a Markdown, JSON or HTML transcript
+pub async fn export_transcript(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    remote_ctx: Option<Extension<RemoteTaskAttemptContext>>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TranscriptQuery>,
+) -> Result<Response<Body>, ApiError> {
+    if remote_ctx.is_some() {
+        return Err(ApiError::BadRequest(
+            "Transcripts can only be exported from the node that ran the attempt".to_string(),
+        ));
+    }
+
+    let transcript = deployment
+        .container()
+        .export_transcript(&task_attempt, query.include_diff)
+        .await?;
+    let filename = format!(
+        "transcript-{}.{}",
+        task_attempt.id,
+        query.format.extension()
+    );
+
+    Ok(Response::builder()
+        .header(header::CONTENT_TYPE, query.format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{filename}\""),
+        )
+        .body(Body::from(transcript.render(query.format)))
+        .unwrap())
+}
@@ -20,10 +20,12 @@ use db::models::{
 use deployment::Deployment;
 use services::services::{
     container::ContainerService,
+    diff_stream::omit_diff_contents,
     filesystem::{DirectoryListResponse, FileContentResponse, FilesystemError},
     worktree_manager::{PurgeResult, WorktreeCleanup, WorktreeManager},
 };
 use sqlx::Error as SqlxError;
+use utils::diff::Diff;
 use utils::response::ApiResponse;
 use utils::unified_log::OutputType;
 
@@ -76,6 +78,40 @@ async fn handle_task_attempt_diff_ws(
     run_ws_stream(socket, stream, WsKeepAlive::for_execution_streams()).await
 }
 
+/// Get the attempt's diff against its target branch, including uncommitted changes
+pub async fn get_task_attempt_diff(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    remote_ctx: Option<Extension<RemoteTaskAttemptContext>>,
+    State(deployment): State<DeploymentImpl>,
+    Query(params): Query<DiffStreamQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Diff>>>, ApiError> {
+    // Check if this is a remote task attempt that should be proxied
+    if let Some(proxy_info) = check_remote_task_attempt_proxy(remote_ctx.as_ref().map(|e| &e.0))? {
+        tracing::debug!(
+            node_id = %proxy_info.node_id,
+            shared_task_id = %proxy_info.target_id,
+            "Proxying get_task_attempt_diff to remote node"
+        );
+
+        let path = format!(
+            "/task-attempts/by-task-id/{}/diff?stats_only={}",
+            proxy_info.target_id, params.stats_only
+        );
+        let response: ApiResponse<Vec<Diff>> = deployment
+            .node_proxy_client()
+            .proxy_get(&proxy_info.node_url, &path, proxy_info.node_id)
+            .await?;
+
+        return Ok(ResponseJson(response));
+    }
+
+    let mut diffs = deployment.container().attempt_diffs(&task_attempt).await?;
+    if params.stats_only {
+        diffs.iter_mut().for_each(omit_diff_contents);
+    }
+    Ok(ResponseJson(ApiResponse::success(diffs)))
+}
+
 // ============================================================================
 // File Browser Endpoints
 // ============================================================================
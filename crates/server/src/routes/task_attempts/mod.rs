@@ -14,10 +14,11 @@ pub use types::{
     AttachPrResponse, BranchStatus, ChangeTargetBranchRequest, ChangeTargetBranchResponse,
     CommitCompareResult, CommitInfo, CreateFollowUpAttempt, CreateGitHubPrRequest, CreatePrError,
     CreateReviewAttempt, CreateTaskAttemptBody, CreateTaskAttemptByTaskIdBody, DiffStreamQuery,
-    DirtyFilesResponse, FixSessionsResponse, GitOperationError, ListFilesQuery, OpenEditorRequest,
-    OpenEditorResponse, PushError, RebaseTaskAttemptRequest, RenameBranchRequest,
-    RenameBranchResponse, RunAgentSetupRequest, RunAgentSetupResponse, StashChangesRequest,
-    StashChangesResponse, TaskAttemptQuery, WorktreePathResponse,
+    DirtyFilesResponse, FixSessionsResponse, GitOperationError, ImportSessionBody, ListFilesQuery,
+    OpenEditorRequest, OpenEditorResponse, PushError, RebaseTaskAttemptRequest,
+    RenameBranchRequest, RenameBranchResponse, RerunCommandRequest, RunAgentSetupRequest,
+    RunAgentSetupResponse,
+    StashChangesRequest, StashChangesResponse, TaskAttemptQuery, WorktreePathResponse,
 };
 
 use axum::{
@@ -47,6 +48,8 @@ use handlers::{
     // Core handlers
     create_task_attempt,
     create_task_attempt_by_task_id,
+    // Transcript handler
+    export_transcript,
     fix_sessions,
     // Follow-up handler
     follow_up,
@@ -56,10 +59,15 @@ use handlers::{
     get_task_attempt,
     get_task_attempt_branch_status,
     get_task_attempt_children,
+    get_task_attempt_diff,
     get_task_attempts,
     get_worktree_path,
     gh_cli_setup_handler,
     has_session_error,
+    // Import handler
+    import_session,
+    // Command timeline handlers
+    list_commands,
     list_worktree_files,
     // Git ops handlers
     merge_task_attempt,
@@ -70,6 +78,7 @@ use handlers::{
     read_worktree_file,
     rebase_task_attempt,
     rename_branch,
+    rerun_command,
     review_attempt,
     run_agent_setup,
     start_dev_server,
@@ -97,6 +106,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/commit-compare", get(compare_commit_to_head))
         .route("/start-dev-server", post(start_dev_server))
         .route("/branch-status", get(get_task_attempt_branch_status))
+        .route("/diff", get(get_task_attempt_diff))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
         .route("/merge", post(merge_task_attempt))
         .route("/push", post(push_task_attempt_branch))
@@ -111,6 +121,9 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/pr/attach", post(attach_existing_pr))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/children", get(get_task_attempt_children))
+        .route("/transcript", get(export_transcript))
+        .route("/commands", get(list_commands))
+        .route("/commands/rerun", post(rerun_command))
         .route("/stop", post(stop_task_attempt_execution))
         .route("/change-target-branch", post(change_target_branch))
         .route("/rename-branch", post(rename_branch))
@@ -169,6 +182,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         )
         .route("/draft/queue", post(drafts::set_draft_queue))
         .route("/files", get(list_worktree_files))
+        .route("/diff", get(get_task_attempt_diff))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
         // These routes were added for node-to-node proxy support
         .route("/children", get(get_task_attempt_children))
@@ -204,6 +218,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_attempts_router = Router::new()
         .route("/", get(get_task_attempts).post(create_task_attempt))
+        .route("/import", post(import_session))
         .nest("/{id}", task_attempt_id_router)
         .merge(task_attempt_files_router)
         .nest("/by-task-id/{task_id}", by_task_id_router)
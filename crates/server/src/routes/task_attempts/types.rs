@@ -2,10 +2,11 @@
 
 use db::models::merge::{Merge, MergeStatus};
 use executors::{
-    actions::coding_agent_review::CodingAgentReviewTarget, profile::ExecutorProfileId,
+    actions::coding_agent_review::CodingAgentReviewTarget,
+    executors::session_import::SessionImportSource, profile::ExecutorProfileId,
 };
 use serde::{Deserialize, Serialize};
-use services::services::git::ConflictOp;
+use services::services::{git::ConflictOp, transcript::TranscriptFormat};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -24,6 +25,15 @@ pub struct DiffStreamQuery {
     pub stats_only: bool,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TranscriptQuery {
+    #[serde(default)]
+    pub format: TranscriptFormat,
+    /// Append the attempt's diff against its target branch
+    #[serde(default)]
+    pub include_diff: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ListFilesQuery {
     /// Relative path within the worktree (optional, defaults to root)
@@ -70,6 +80,23 @@ pub struct CreateTaskAttemptByTaskIdBody {
     pub use_parent_worktree: Option<bool>,
 }
 
+/// Request body for importing a session started outside the app into a new task attempt
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ImportSessionBody {
+    pub task_id: Uuid,
+    /// Executor profile follow-ups will run with; its executor must match the session's agent
+    pub executor_profile_id: ExecutorProfileId,
+    pub base_branch: String,
+    pub source: SessionImportSource,
+}
+
+/// A command from the attempt's command timeline to run again in its worktree
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct RerunCommandRequest {
+    pub execution_process_id: Uuid,
+    pub entry_index: i64,
+}
+
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct RunAgentSetupRequest {
     pub executor_profile_id: ExecutorProfileId,
@@ -0,0 +1,56 @@
+//! Usage budget handlers: get_task_budget, set_task_budget, delete_task_budget.
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    task::Task,
+    usage_budget::{SetUsageBudget, UsageBudget, UsageBudgetStatus},
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// GET /api/tasks/{id}/budget - Get the task's usage budget with its current spend
+///
+/// Returns null when the task has no budget of its own; the project budget may still apply.
+pub async fn get_task_budget(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<UsageBudgetStatus>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let status = match UsageBudget::find_by_task_id(pool, task.id).await? {
+        Some(budget) => Some(budget.status(pool).await?),
+        None => None,
+    };
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// PUT /api/tasks/{id}/budget - Create or replace the task's usage budget
+///
+/// The task budget is checked before the project budget, so a run is stopped by whichever is
+/// reached first.
+pub async fn set_task_budget(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetUsageBudget>,
+) -> Result<ResponseJson<ApiResponse<UsageBudgetStatus>>, ApiError> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let pool = &deployment.db().pool;
+    let budget = UsageBudget::set_for_task(pool, task.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        budget.status(pool).await?,
+    )))
+}
+
+/// DELETE /api/tasks/{id}/budget - Remove the task's usage budget
+pub async fn delete_task_budget(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if UsageBudget::delete_for_task(&deployment.db().pool, task.id).await? == 0 {
+        return Err(ApiError::NotFound("Task has no usage budget".to_string()));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
@@ -4,10 +4,12 @@
 //! - `core`: CRUD operations (create, read, update, delete, create-and-start)
 //! - `status`: Archive, unarchive, assign, get children
 //! - `labels`: Get labels, set labels
+//! - `budget`: Get, set and delete the task's usage budget
 //! - `remote`: Remote/Hive task helpers (create, update, delete, resync)
 //! - `streams`: WebSocket and streaming (task streams, available nodes, connection info)
 //! - `sync`: Hive sync operations (backfill archive status)
 
+pub mod budget;
 pub mod core;
 pub mod labels;
 pub mod remote;
@@ -16,6 +18,7 @@ pub mod streams;
 pub mod sync;
 
 // Re-export all handlers for convenient access from the router
+pub use budget::{delete_task_budget, get_task_budget, set_task_budget};
 pub use core::{create_task, create_task_and_start, delete_task, get_task, get_tasks, update_task};
 pub use labels::{get_task_labels, set_task_labels};
 pub use status::{archive_task, assign_task, get_task_children, unarchive_task};
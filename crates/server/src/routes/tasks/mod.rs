@@ -33,6 +33,9 @@ pub use types::{
 /// - `GET /tasks/{task_id}/children` - Get task children (subtasks)
 /// - `GET /tasks/{task_id}/labels` - Get task labels
 /// - `PUT /tasks/{task_id}/labels` - Set task labels
+/// - `GET /tasks/{task_id}/budget` - Get the task's usage budget
+/// - `PUT /tasks/{task_id}/budget` - Set the task's usage budget
+/// - `DELETE /tasks/{task_id}/budget` - Remove the task's usage budget
 /// - `GET /tasks/{task_id}/available-nodes` - Get nodes where task's project exists
 /// - `GET /tasks/{task_id}/stream-connection-info` - Get stream connection info for remote task
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
@@ -48,6 +51,12 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/labels",
             get(handlers::get_task_labels).put(handlers::set_task_labels),
         )
+        .route(
+            "/budget",
+            get(handlers::get_task_budget)
+                .put(handlers::set_task_budget)
+                .delete(handlers::delete_task_budget),
+        )
         .route("/available-nodes", get(handlers::get_available_nodes))
         .route(
             "/stream-connection-info",
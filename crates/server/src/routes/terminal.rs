@@ -2,25 +2,41 @@
 //!
 //! This module provides:
 //! - REST API for creating and listing terminal sessions
-//! - WebSocket endpoint for bidirectional terminal I/O
+//! - WebSocket endpoint for bidirectional terminal I/O, shared by any number of viewers
+//! - REST API for recording sessions of a task attempt and replaying the recordings
 
 use std::sync::Arc;
 
 use axum::{
     Router,
     extract::{
-        Path, State,
+        Path, Query, State,
         ws::{Message, WebSocket, WebSocketUpgrade},
     },
-    response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    http::header,
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::{delete, get, post},
+};
+use db::models::{
+    task_attempt::TaskAttempt,
+    terminal_recording::{CreateTerminalRecording, TerminalRecording},
+};
+use deployment::Deployment;
+use futures_util::{
+    SinkExt, StreamExt,
+    stream::{SplitSink, SplitStream},
 };
-use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use services::services::terminal_session::{SessionInfo, TerminalError, TerminalSessionManager};
+use services::services::{
+    terminal_recording::ASCIICAST_CONTENT_TYPE,
+    terminal_session::{
+        SessionInfo, SessionViewers, TerminalError, TerminalSessionManager, ViewerAttachment,
+    },
+};
 use tokio::sync::RwLock;
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{assets::asset_dir, response::ApiResponse};
+use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, ws_util::WsKeepAlive};
 
@@ -29,6 +45,19 @@ use crate::{DeploymentImpl, error::ApiError, ws_util::WsKeepAlive};
 pub struct CreateSessionRequest {
     /// The working directory for the terminal session.
     pub working_dir: String,
+    /// The task attempt the session is for. Required when `record` is set, and the working
+    /// directory must then be in the attempt's worktree.
+    #[serde(default)]
+    pub task_attempt_id: Option<Uuid>,
+    /// Record the session, unless it is already being recorded.
+    #[serde(default)]
+    pub record: bool,
+}
+
+/// Query for listing the recordings of a task attempt.
+#[derive(Debug, Deserialize)]
+pub struct RecordingsQuery {
+    pub task_attempt_id: Uuid,
 }
 
 /// Response for creating a terminal session.
@@ -41,6 +70,8 @@ pub struct CreateSessionResponse {
     pub session: SessionInfo,
     /// Whether this was a reconnection to an existing session.
     pub is_reconnect: bool,
+    /// The session's running recording, if it is being recorded.
+    pub recording: Option<TerminalRecording>,
 }
 
 /// WebSocket message types for terminal I/O.
@@ -58,23 +89,155 @@ pub enum TerminalMessage {
     Exit { code: Option<i32> },
     /// Error message.
     Error { message: String },
+    /// Ask to become the writer. Granted only when no other viewer has control.
+    RequestControl,
+    /// Give up control so another viewer can take it.
+    ReleaseControl,
+    /// Hand control to another viewer. Only the writer may do this.
+    GrantControl { viewer_id: String },
+    /// Viewers of the session and who controls it, sent on connect and on every change.
+    /// `viewer_id` identifies the receiving client.
+    Viewers {
+        viewer_id: String,
+        writer_id: Option<String>,
+        viewer_ids: Vec<String>,
+    },
+}
+
+impl TerminalMessage {
+    fn viewers(viewer_id: &str, viewers: &SessionViewers) -> Self {
+        TerminalMessage::Viewers {
+            viewer_id: viewer_id.to_string(),
+            writer_id: viewers.writer_id.clone(),
+            viewer_ids: viewers.viewer_ids.clone(),
+        }
+    }
 }
 
 /// Shared state for terminal sessions.
 pub struct TerminalState {
     pub manager: Arc<RwLock<TerminalSessionManager>>,
+    pub deployment: DeploymentImpl,
 }
 
 impl TerminalState {
-    pub async fn new() -> Self {
+    pub async fn new(deployment: DeploymentImpl) -> Self {
         let mut manager = TerminalSessionManager::new();
         manager.init().await;
+
+        // Sessions don't outlive the server, so neither do their recordings
+        match TerminalRecording::end_orphaned(&deployment.db().pool).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!(count, "Ended terminal recordings left running"),
+            Err(e) => tracing::warn!(error = %e, "Failed to end orphaned terminal recordings"),
+        }
+
         Self {
             manager: Arc::new(RwLock::new(manager)),
+            deployment,
         }
     }
 }
 
+/// Directory recordings of a task attempt are written to.
+fn recordings_dir(task_attempt_id: Uuid) -> std::path::PathBuf {
+    asset_dir()
+        .join("terminal-recordings")
+        .join(task_attempt_id.to_string())
+}
+
+/// Whether `working_dir` is the worktree at `container_ref` or inside it.
+fn is_in_worktree(working_dir: &std::path::Path, container_ref: Option<&str>) -> bool {
+    let Some(container_ref) = container_ref else {
+        return false;
+    };
+    // Resolve `..` and symlinks, so a path can't point out of the worktree
+    match (
+        std::fs::canonicalize(working_dir),
+        std::fs::canonicalize(container_ref),
+    ) {
+        (Ok(working_dir), Ok(worktree)) => working_dir.starts_with(worktree),
+        _ => false,
+    }
+}
+
+/// Return the session's running recording, starting one for the task attempt if there is none.
+/// The session must work in the attempt's worktree, so it can't be filed under another attempt.
+async fn ensure_recording(
+    terminal_state: &TerminalState,
+    manager: &TerminalSessionManager,
+    session: &SessionInfo,
+    task_attempt_id: Uuid,
+) -> Result<TerminalRecording, ApiError> {
+    let pool = &terminal_state.deployment.db().pool;
+    let task_attempt = TaskAttempt::find_by_id(pool, task_attempt_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Task attempt not found: {task_attempt_id}")))?;
+    if !is_in_worktree(
+        std::path::Path::new(&session.working_dir),
+        task_attempt.container_ref.as_deref(),
+    ) {
+        return Err(ApiError::BadRequest(
+            "Only sessions in the task attempt's worktree can be recorded for it".to_string(),
+        ));
+    }
+
+    if manager.is_recording(&session.id).await
+        && let Some(recording) =
+            TerminalRecording::find_active_by_session_id(pool, &session.id).await?
+    {
+        return Ok(recording);
+    }
+
+    let id = Uuid::new_v4();
+    let path = recordings_dir(task_attempt.id).join(format!("{id}.cast"));
+    let file_path = path.to_string_lossy();
+    let recording = TerminalRecording::create(
+        pool,
+        id,
+        &CreateTerminalRecording {
+            task_attempt_id: task_attempt.id,
+            session_id: &session.id,
+            file_path: &file_path,
+            cols: session.cols,
+            rows: session.rows,
+        },
+    )
+    .await?;
+
+    let done = match manager
+        .start_recording(&session.id, &path, Some(task_attempt.branch.clone()))
+        .await
+    {
+        Ok(done) => done,
+        Err(e) => {
+            TerminalRecording::delete(pool, id).await?;
+            return Err(ApiError::BadRequest(e.to_string()));
+        }
+    };
+
+    let pool = pool.clone();
+    let started_at = recording.started_at;
+    tokio::spawn(async move {
+        let duration_ms = match done.await {
+            Ok(summary) => summary.duration.as_millis() as i64,
+            // The recording could not be finished cleanly; keep what was written
+            Err(_) => (chrono::Utc::now() - started_at).num_milliseconds(),
+        };
+        if let Err(e) = TerminalRecording::mark_ended(&pool, id, duration_ms).await {
+            tracing::error!(recording_id = %id, error = %e, "Failed to mark terminal recording ended");
+        }
+    });
+
+    tracing::info!(
+        recording_id = %id,
+        session_id = %session.id,
+        task_attempt_id = %task_attempt.id,
+        "Recording terminal session"
+    );
+    Ok(recording)
+}
+
 /// POST /api/terminal/sessions
 ///
 /// Create a new terminal session in the specified working directory.
@@ -119,10 +282,30 @@ pub async fn create_session(
         ApiError::BadRequest("Failed to get session info after creation".to_string())
     })?;
 
+    let recording = if request.record {
+        let task_attempt_id = request.task_attempt_id.ok_or_else(|| {
+            ApiError::BadRequest("task_attempt_id is required to record a session".to_string())
+        })?;
+        Some(ensure_recording(&terminal_state, &manager, &session, task_attempt_id).await?)
+    } else if session.recording {
+        TerminalRecording::find_active_by_session_id(
+            &terminal_state.deployment.db().pool,
+            &session_id,
+        )
+        .await?
+    } else {
+        None
+    };
+    let session = SessionInfo {
+        recording: recording.is_some() || session.recording,
+        ..session
+    };
+
     let response = CreateSessionResponse {
         session_id,
         session,
         is_reconnect,
+        recording,
     };
 
     Ok(ResponseJson(ApiResponse::success(response)))
@@ -173,9 +356,107 @@ pub async fn delete_session(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// DELETE /api/terminal/sessions/{session_id}/recording
+///
+/// Stop recording a terminal session. The session keeps running.
+pub async fn stop_recording(
+    State(terminal_state): State<Arc<TerminalState>>,
+    Path(session_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let manager = terminal_state.manager.read().await;
+
+    manager
+        .stop_recording(&session_id)
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// GET /api/terminal/recordings?task_attempt_id=
+///
+/// List the terminal recordings of a task attempt, newest first.
+pub async fn list_recordings(
+    State(terminal_state): State<Arc<TerminalState>>,
+    Query(query): Query<RecordingsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TerminalRecording>>>, ApiError> {
+    let recordings = TerminalRecording::find_by_task_attempt_id(
+        &terminal_state.deployment.db().pool,
+        query.task_attempt_id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(recordings)))
+}
+
+async fn find_recording(
+    terminal_state: &TerminalState,
+    recording_id: Uuid,
+) -> Result<TerminalRecording, ApiError> {
+    TerminalRecording::find_by_id(&terminal_state.deployment.db().pool, recording_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Recording not found: {recording_id}")))
+}
+
+/// GET /api/terminal/recordings/{recording_id}/cast
+///
+/// Replay a recording: returns its asciicast v2 file, for asciinema-compatible players.
+/// A running recording is returned up to its latest output.
+pub async fn get_recording_cast(
+    State(terminal_state): State<Arc<TerminalState>>,
+    Path(recording_id): Path<Uuid>,
+) -> Result<Response, ApiError> {
+    let recording = find_recording(&terminal_state, recording_id).await?;
+    let cast = match tokio::fs::read(&recording.file_path).await {
+        Ok(cast) => cast,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(ApiError::NotFound(format!(
+                "Recording file is missing: {recording_id}"
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, ASCIICAST_CONTENT_TYPE.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{recording_id}.cast\""),
+            ),
+        ],
+        cast,
+    )
+        .into_response())
+}
+
+/// DELETE /api/terminal/recordings/{recording_id}
+///
+/// Delete a recording and its file. Running recordings must be stopped first.
+pub async fn delete_recording(
+    State(terminal_state): State<Arc<TerminalState>>,
+    Path(recording_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let recording = find_recording(&terminal_state, recording_id).await?;
+    if recording.ended_at.is_none() {
+        return Err(ApiError::Conflict(
+            "Stop the recording before deleting it".to_string(),
+        ));
+    }
+
+    match tokio::fs::remove_file(&recording.file_path).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    TerminalRecording::delete(&terminal_state.deployment.db().pool, recording_id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 /// WS /api/terminal/ws/{session_id}
 ///
-/// WebSocket endpoint for bidirectional terminal I/O.
+/// WebSocket endpoint for bidirectional terminal I/O. Every connection attaches as a viewer;
+/// only the viewer in control may send input or resize the terminal.
 pub async fn terminal_websocket(
     ws: WebSocketUpgrade,
     State(terminal_state): State<Arc<TerminalState>>,
@@ -207,14 +488,65 @@ async fn handle_terminal_ws(
 ) -> anyhow::Result<()> {
     let (mut sender, mut receiver) = socket.split();
 
-    // Subscribe to terminal output
-    let output_rx = {
+    // Attach as a viewer, which also subscribes to terminal output
+    let attachment = {
         let manager = terminal_state.manager.read().await;
         manager
-            .subscribe(&session_id)
+            .attach_viewer(&session_id)
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to subscribe to session output: {}", e))?
+            .map_err(|e| anyhow::anyhow!("Failed to attach to session: {}", e))?
     };
+    let viewer_id = attachment.viewer_id.clone();
+
+    let result = run_terminal_ws(
+        &mut sender,
+        &mut receiver,
+        &terminal_state,
+        &session_id,
+        attachment,
+    )
+    .await;
+
+    terminal_state
+        .manager
+        .read()
+        .await
+        .detach_viewer(&session_id, &viewer_id)
+        .await;
+
+    // Attempt graceful close
+    let _ = sender.send(Message::Close(None)).await;
+
+    result
+}
+
+/// Relay a viewer's terminal I/O until either side closes.
+async fn run_terminal_ws(
+    sender: &mut SplitSink<WebSocket, Message>,
+    receiver: &mut SplitStream<WebSocket>,
+    terminal_state: &Arc<TerminalState>,
+    session_id: &str,
+    attachment: ViewerAttachment,
+) -> anyhow::Result<()> {
+    let ViewerAttachment {
+        viewer_id,
+        scrollback,
+        output: output_rx,
+        viewers: mut viewers_rx,
+    } = attachment;
+    let viewer_id = viewer_id.as_str();
+
+    // Catch up on recent output, then tell the viewer who controls the session
+    if !scrollback.is_empty() {
+        let msg = TerminalMessage::Output { data: scrollback };
+        sender
+            .send(Message::Text(serde_json::to_string(&msg)?.into()))
+            .await?;
+    }
+    let msg = TerminalMessage::viewers(viewer_id, &viewers_rx.borrow_and_update());
+    sender
+        .send(Message::Text(serde_json::to_string(&msg)?.into()))
+        .await?;
 
     let keep_alive = WsKeepAlive::for_execution_streams();
 
@@ -255,6 +587,22 @@ async fn handle_terminal_ws(
                 }
             }
 
+            // Tell the viewer when viewers come and go or control changes hands
+            changed = viewers_rx.changed() => {
+                if changed.is_err() {
+                    tracing::debug!(session_id = %session_id, "session closed");
+                    let msg = TerminalMessage::Exit { code: None };
+                    let json = serde_json::to_string(&msg)?;
+                    let _ = sender.send(Message::Text(json.into())).await;
+                    break;
+                }
+                let msg = TerminalMessage::viewers(viewer_id, &viewers_rx.borrow_and_update());
+                let json = serde_json::to_string(&msg)?;
+                if sender.send(Message::Text(json.into())).await.is_err() {
+                    break;
+                }
+            }
+
             // Handle incoming WebSocket messages
             msg = receiver.next() => {
                 match msg {
@@ -262,8 +610,9 @@ async fn handle_terminal_ws(
                         match serde_json::from_str::<TerminalMessage>(&text) {
                             Ok(terminal_msg) => {
                                 if let Err(e) = handle_client_message(
-                                    &terminal_state,
-                                    &session_id,
+                                    terminal_state,
+                                    session_id,
+                                    viewer_id,
                                     terminal_msg,
                                 ).await {
                                     tracing::warn!(
@@ -331,9 +680,6 @@ async fn handle_terminal_ws(
         }
     }
 
-    // Attempt graceful close
-    let _ = sender.send(Message::Close(None)).await;
-
     Ok(())
 }
 
@@ -341,20 +687,36 @@ async fn handle_terminal_ws(
 async fn handle_client_message(
     terminal_state: &Arc<TerminalState>,
     session_id: &str,
+    viewer_id: &str,
     msg: TerminalMessage,
 ) -> Result<(), TerminalError> {
     let manager = terminal_state.manager.read().await;
 
     match msg {
         TerminalMessage::Input { data } => {
+            manager.ensure_writer(session_id, viewer_id).await?;
             manager
                 .write_to_session(session_id, data.as_bytes())
                 .await?;
         }
         TerminalMessage::Resize { cols, rows } => {
+            manager.ensure_writer(session_id, viewer_id).await?;
             manager.resize_session(session_id, cols, rows).await?;
         }
-        // Output, Exit, and Error are server-to-client messages
+        TerminalMessage::RequestControl => {
+            manager.request_control(session_id, viewer_id).await?;
+        }
+        TerminalMessage::ReleaseControl => {
+            manager.release_control(session_id, viewer_id).await?;
+        }
+        TerminalMessage::GrantControl {
+            viewer_id: to_viewer_id,
+        } => {
+            manager
+                .grant_control(session_id, viewer_id, &to_viewer_id)
+                .await?;
+        }
+        // Output, Exit, Error, and Viewers are server-to-client messages
         _ => {}
     }
 
@@ -370,9 +732,19 @@ pub fn router() -> Router<Arc<TerminalState>> {
         .route("/terminal/sessions", post(create_session))
         .route("/terminal/sessions", get(list_sessions))
         .route("/terminal/sessions/{session_id}", get(get_session))
+        .route("/terminal/sessions/{session_id}", delete(delete_session))
         .route(
-            "/terminal/sessions/{session_id}",
-            axum::routing::delete(delete_session),
+            "/terminal/sessions/{session_id}/recording",
+            delete(stop_recording),
+        )
+        .route("/terminal/recordings", get(list_recordings))
+        .route(
+            "/terminal/recordings/{recording_id}",
+            delete(delete_recording),
+        )
+        .route(
+            "/terminal/recordings/{recording_id}/cast",
+            get(get_recording_cast),
         )
         .route("/terminal/ws/{session_id}", get(terminal_websocket))
 }
@@ -381,25 +753,40 @@ pub fn router() -> Router<Arc<TerminalState>> {
 ///
 /// This is a convenience function that creates the terminal state and
 /// returns a router that can be merged into the main app router.
-pub async fn router_with_state(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
-    let terminal_state = Arc::new(TerminalState::new().await);
+pub async fn router_with_state(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let terminal_state = Arc::new(TerminalState::new(deployment.clone()).await);
 
-    Router::new()
-        .route("/terminal/sessions", post(create_session))
-        .route("/terminal/sessions", get(list_sessions))
-        .route("/terminal/sessions/{session_id}", get(get_session))
-        .route(
-            "/terminal/sessions/{session_id}",
-            axum::routing::delete(delete_session),
-        )
-        .route("/terminal/ws/{session_id}", get(terminal_websocket))
-        .with_state(terminal_state)
+    router().with_state(terminal_state)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_in_worktree() {
+        let root = tempfile::tempdir().unwrap();
+        let worktree = root.path().join("worktree");
+        let other = root.path().join("other");
+        std::fs::create_dir_all(worktree.join("src")).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+        let container_ref = worktree.to_string_lossy();
+
+        assert!(is_in_worktree(&worktree, Some(&container_ref)));
+        assert!(is_in_worktree(&worktree.join("src"), Some(&container_ref)));
+        assert!(!is_in_worktree(&other, Some(&container_ref)));
+        assert!(!is_in_worktree(
+            &worktree.join("..").join("other"),
+            Some(&container_ref)
+        ));
+        // A sibling whose name merely starts with the worktree's
+        let sibling = root.path().join("worktree-2");
+        std::fs::create_dir_all(&sibling).unwrap();
+        assert!(!is_in_worktree(&sibling, Some(&container_ref)));
+        // Attempts without a worktree have nothing to record
+        assert!(!is_in_worktree(&worktree, None));
+    }
+
     #[test]
     fn test_terminal_message_serialization() {
         // Test Input message
@@ -463,6 +850,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_viewer_control_messages() {
+        let msg: TerminalMessage = serde_json::from_str(r#"{"type":"request_control"}"#).unwrap();
+        assert!(matches!(msg, TerminalMessage::RequestControl));
+
+        let json = r#"{"type":"grant_control","viewer_id":"viewer-2"}"#;
+        let msg: TerminalMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            TerminalMessage::GrantControl { viewer_id } => assert_eq!(viewer_id, "viewer-2"),
+            _ => panic!("Expected GrantControl message"),
+        }
+
+        let msg = TerminalMessage::viewers(
+            "viewer-2",
+            &SessionViewers {
+                writer_id: Some("viewer-1".to_string()),
+                viewer_ids: vec!["viewer-1".to_string(), "viewer-2".to_string()],
+            },
+        );
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"viewers\""));
+        assert!(json.contains("\"viewer_id\":\"viewer-2\""));
+        assert!(json.contains("\"writer_id\":\"viewer-1\""));
+    }
+
     #[test]
     fn test_create_session_response_serialization() {
         let response = CreateSessionResponse {
@@ -474,8 +886,11 @@ mod tests {
                 cols: 80,
                 rows: 24,
                 active: true,
+                recording: false,
+                viewers: 1,
             },
             is_reconnect: false,
+            recording: None,
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -495,8 +910,11 @@ mod tests {
                 cols: 80,
                 rows: 24,
                 active: true,
+                recording: false,
+                viewers: 1,
             },
             is_reconnect: true,
+            recording: None,
         };
 
         let json = serde_json::to_string(&response).unwrap();
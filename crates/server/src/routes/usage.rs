@@ -0,0 +1,64 @@
+//! Token usage and cost reported by coding agents, rolled up per task, project or executor.
+
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::execution_process_usage::{
+    ExecutionProcessUsage, UsageFilter, UsageGroupBy, UsagePeriod, UsageRollup,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// Bucket by day or week; all-time totals when omitted
+    pub period: Option<UsagePeriod>,
+    pub group_by: UsageGroupBy,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub executor: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Only count tasks that have been merged
+    #[serde(default)]
+    pub merged_only: bool,
+}
+
+/// GET /api/usage?group_by=executor|project|task[&period=day|week] — summed token usage and cost
+pub async fn get_usage(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<UsageRollup>>>, ApiError> {
+    if let (Some(since), Some(until)) = (query.since, query.until)
+        && since >= until
+    {
+        return Err(ApiError::BadRequest(
+            "`since` must be earlier than `until`".to_string(),
+        ));
+    }
+
+    let filter = UsageFilter {
+        project_id: query.project_id,
+        task_id: query.task_id,
+        executor: query.executor,
+        since: query.since,
+        until: query.until,
+        merged_only: query.merged_only,
+    };
+    let rollups =
+        ExecutionProcessUsage::rollup(&deployment.db().pool, query.period, query.group_by, &filter)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(rollups)))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().route("/usage", get(get_usage))
+}
@@ -1,14 +1,16 @@
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::Json as ResponseJson,
     routing::{get, post},
 };
 use db::models::{
     project::Project,
     webhook::{CreateWebhook, UpdateWebhook, Webhook, WebhookResponse},
+    webhook_delivery::{DELIVERY_LOG_LIMIT, WebhookDelivery, WebhookDeliveryStatus},
 };
 use deployment::Deployment;
+use serde::Deserialize;
 use services::services::webhook::WebhookService;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -262,6 +264,49 @@ pub async fn test_webhook(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQuery {
+    /// Defaults to 50
+    pub limit: Option<i64>,
+}
+
+/// GET /api/webhooks/:id/deliveries — most recent deliveries first
+pub async fn list_webhook_deliveries(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<DeliveriesQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookDelivery>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if Webhook::find_by_id(pool, id).await?.is_none() {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    let limit = query.limit.unwrap_or(50).clamp(1, DELIVERY_LOG_LIMIT);
+    let deliveries = WebhookDelivery::find_by_webhook_id(pool, id, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(deliveries)))
+}
+
+/// POST /api/webhooks/:id/deliveries/:delivery_id/redeliver — send a delivery's body again
+pub async fn redeliver_webhook_delivery(
+    State(deployment): State<DeploymentImpl>,
+    Path((id, delivery_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<WebhookDelivery>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let webhook = Webhook::find_by_id(pool, id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    let delivery = WebhookDelivery::find_by_id(pool, delivery_id)
+        .await?
+        .filter(|d| d.webhook_id == webhook.id)
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    if delivery.status == WebhookDeliveryStatus::Pending {
+        return Err(ApiError::Conflict(
+            "Delivery is still being sent.".to_string(),
+        ));
+    }
+    let redelivery = WebhookService::redeliver(pool, webhook, &delivery).await?;
+    Ok(ResponseJson(ApiResponse::success(redelivery)))
+}
+
 pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         .nest(
@@ -272,7 +317,12 @@ pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                     "/{id}",
                     get(get_webhook).put(update_webhook).delete(delete_webhook),
                 )
-                .route("/{id}/test", post(test_webhook)),
+                .route("/{id}/test", post(test_webhook))
+                .route("/{id}/deliveries", get(list_webhook_deliveries))
+                .route(
+                    "/{id}/deliveries/{delivery_id}/redeliver",
+                    post(redeliver_webhook_delivery),
+                ),
         )
         .nest(
             "/projects/{project_id}/webhooks",
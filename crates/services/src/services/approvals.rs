@@ -1,23 +1,29 @@
 pub mod executor_approvals;
+pub mod policy;
 
-use std::{collections::HashMap, sync::Arc, time::Duration as StdDuration};
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+    time::Duration as StdDuration,
+};
 
 use dashmap::DashMap;
 use db::models::{
+    approval_request::StoredApproval,
     execution_process::ExecutionProcess,
     task::{Task, TaskStatus},
 };
 use executors::{
     approvals::ToolCallMetadata,
     logs::{
-        ActionType, NormalizedEntry, NormalizedEntryType, ToolStatus,
+        ActionType, DenialSource, NormalizedEntry, NormalizedEntryType, ToolStatus,
         utils::patch::{ConversationPatch, extract_normalized_entry_from_patch},
     },
 };
 use futures::future::{BoxFuture, FutureExt, Shared};
 use sqlx::{Error as SqlxError, SqlitePool};
 use thiserror::Error;
-use tokio::sync::{RwLock, oneshot};
+use tokio::sync::{RwLock, mpsc, oneshot};
 use utils::{
     approvals::{ApprovalRequest, ApprovalResponse, ApprovalStatus, Question},
     log_msg::LogMsg,
@@ -25,6 +31,8 @@ use utils::{
 };
 use uuid::Uuid;
 
+use crate::services::hive_client::{ApprovalRequestMessage, ApprovalResolvedMessage, NodeMessage};
+
 /// Response data from an approval/question response
 #[derive(Debug, Clone)]
 pub struct ApprovalResponseData {
@@ -66,6 +74,8 @@ pub struct Approvals {
     pending: Arc<DashMap<String, PendingApproval>>,
     completed: Arc<DashMap<String, ApprovalStatus>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+    /// Channel to the hive, set when this server runs as a swarm node
+    hive_tx: Arc<OnceLock<mpsc::Sender<NodeMessage>>>,
 }
 
 #[derive(Debug, Error)]
@@ -90,9 +100,68 @@ impl Approvals {
             pending: Arc::new(DashMap::new()),
             completed: Arc::new(DashMap::new()),
             msg_stores,
+            hive_tx: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Forward approvals to the hive from now on, so organization members can answer them from
+    /// the Hive or another node
+    pub fn relay_to_hive(&self, command_tx: mpsc::Sender<NodeMessage>) {
+        if self.hive_tx.set(command_tx).is_err() {
+            tracing::warn!("Approvals are already relayed to the hive");
         }
     }
 
+    /// Forward a request an agent is waiting on to the hive, if connected to one
+    pub async fn forward_to_hive(&self, pool: &SqlitePool, request: &ApprovalRequest) {
+        let Some(hive_tx) = self.hive_tx.get() else {
+            return;
+        };
+        let ctx = ExecutionProcess::load_context(pool, request.execution_process_id)
+            .await
+            .ok();
+        let message = NodeMessage::ApprovalRequest(ApprovalRequestMessage {
+            approval_id: request.id.clone(),
+            local_execution_process_id: request.execution_process_id,
+            local_attempt_id: ctx.as_ref().map(|ctx| ctx.task_attempt.id),
+            shared_task_id: ctx.as_ref().and_then(|ctx| ctx.task.shared_task_id),
+            task_title: ctx.map(|ctx| ctx.task.title).unwrap_or_default(),
+            tool_name: request.tool_name.clone(),
+            tool_input: request.tool_input.clone(),
+            questions: request.questions.clone(),
+            created_at: request.created_at,
+            timeout_at: request.timeout_at,
+        });
+        // Never hold up the agent on the hive connection; pending requests are forwarded again
+        // on every reconnect
+        if let Err(e) = hive_tx.try_send(message) {
+            tracing::debug!(approval_id = %request.id, "Approval not forwarded to hive: {}", e);
+        }
+    }
+
+    /// Tell the hive an approval it was offered has been settled on this node
+    pub fn forward_resolution_to_hive(&self, approval_id: &str, status: &ApprovalStatus) {
+        let Some(hive_tx) = self.hive_tx.get() else {
+            return;
+        };
+        let message = NodeMessage::ApprovalResolved(ApprovalResolvedMessage {
+            approval_id: approval_id.to_string(),
+            status: status.clone(),
+        });
+        if let Err(e) = hive_tx.try_send(message) {
+            tracing::debug!(approval_id = %approval_id, "Approval resolution not sent to hive: {}", e);
+        }
+    }
+
+    /// Forward every approval still waiting for an answer, after (re)connecting to the hive
+    pub async fn forward_pending_to_hive(&self, pool: &SqlitePool) -> Result<usize, ApprovalError> {
+        let pending = StoredApproval::find_pending(pool, None).await?;
+        for stored in &pending {
+            self.forward_to_hive(pool, &stored.request).await;
+        }
+        Ok(pending.len())
+    }
+
     pub async fn create_with_waiter(
         &self,
         request: ApprovalRequest,
@@ -113,37 +182,7 @@ impl Approvals {
         );
 
         if let Some(store) = self.msg_store_by_id(&request.execution_process_id).await {
-            // Subscribe to notifications BEFORE the first check to avoid a race
-            // where the log processor pushes between check and subscribe.
-            // `notify_one()` stores a permit if no one is waiting, so the next
-            // `.notified()` call will return immediately.
-            let notify = store.subscribe_notify();
-            let mut notified = std::pin::pin!(notify.notified());
-
-            let mut matching_tool = find_matching_tool_use(store.clone(), &request.tool_call_id);
-
-            if matching_tool.is_none() {
-                let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
-
-                loop {
-                    tokio::select! {
-                        _ = tokio::time::sleep_until(deadline) => {
-                            tracing::warn!(
-                                tool_call_id = %request.tool_call_id,
-                                "Timed out waiting for tool-use entry in msg_store"
-                            );
-                            break;
-                        }
-                        _ = &mut notified => {
-                            notified.set(notify.notified());
-                            matching_tool = find_matching_tool_use(store.clone(), &request.tool_call_id);
-                            if matching_tool.is_some() {
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
+            let matching_tool = wait_for_tool_use(&store, &request.tool_call_id).await;
 
             if let Some((idx, matching_tool)) = matching_tool {
                 tracing::info!(
@@ -334,28 +373,70 @@ impl Approvals {
                 tool_name: p.tool_name,
                 execution_process_id: p.execution_process_id,
             };
-
-            // If approved or denied, and task is still InReview, move back to InProgress
-            if matches!(
-                req.status,
-                ApprovalStatus::Approved | ApprovalStatus::Denied { .. }
-            ) && let Ok(ctx) =
-                ExecutionProcess::load_context(pool, tool_ctx.execution_process_id).await
-                && ctx.task.status == TaskStatus::InReview
-                && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InProgress).await
-            {
-                tracing::warn!(
-                    "Failed to update task status to InProgress after approval response: {}",
-                    e
-                );
-            }
+            move_task_back_to_in_progress(pool, &req.status, tool_ctx.execution_process_id).await;
 
             Ok((req.status, tool_ctx))
         } else if self.completed.contains_key(id) {
             Err(ApprovalError::AlreadyCompleted)
         } else {
-            Err(ApprovalError::NotFound)
+            self.respond_to_stored(pool, id, req).await
+        }
+    }
+
+    /// Answer an approval persisted before a server restart, which no agent is waiting on. The
+    /// response is kept until the resumed agent repeats the tool call.
+    async fn respond_to_stored(
+        &self,
+        pool: &SqlitePool,
+        id: &str,
+        req: ApprovalResponse,
+    ) -> Result<(ApprovalStatus, ToolContext), ApprovalError> {
+        let stored = StoredApproval::find_by_id(pool, id)
+            .await?
+            .ok_or(ApprovalError::NotFound)?;
+        if stored.request.timeout_at <= chrono::Utc::now()
+            || !StoredApproval::record_response(pool, id, &req.status, req.answers.as_ref(), false)
+                .await?
+        {
+            return Err(ApprovalError::AlreadyCompleted);
+        }
+        tracing::info!(
+            approval_id = %id,
+            execution_process_id = %stored.request.execution_process_id,
+            "Stored response to approval persisted before restart"
+        );
+        self.forward_resolution_to_hive(id, &req.status);
+
+        let tool_ctx = ToolContext {
+            tool_name: stored.request.tool_name,
+            execution_process_id: stored.request.execution_process_id,
+        };
+        move_task_back_to_in_progress(pool, &req.status, tool_ctx.execution_process_id).await;
+        Ok((req.status, tool_ctx))
+    }
+
+    /// Whether an agent is waiting on the approval in this server
+    pub fn is_pending(&self, id: &str) -> bool {
+        self.pending.contains_key(id)
+    }
+
+    /// Close persisted approvals left over from before a restart that no agent can receive
+    /// anymore, and return the number still waiting for the user. Call after orphaned
+    /// executions have been resumed or marked failed.
+    pub async fn restore_persisted(&self, pool: &SqlitePool) -> Result<usize, ApprovalError> {
+        let closed = StoredApproval::close_stale(pool).await?;
+        if closed > 0 {
+            tracing::info!(
+                "Closed {} approvals of exited or timed out executions",
+                closed
+            );
         }
+        let pending = StoredApproval::find_pending(pool, None)
+            .await?
+            .into_iter()
+            .filter(|stored| !self.is_pending(&stored.request.id))
+            .count();
+        Ok(pending)
     }
 
     #[tracing::instrument(skip(self, id, timeout_at, waiter))]
@@ -456,6 +537,37 @@ impl Approvals {
         }
     }
 
+    /// Find the tool-use entry of a tool call that is waiting for approval, waiting briefly for
+    /// the log processor to emit it
+    pub async fn find_tool_use(
+        &self,
+        execution_process_id: Uuid,
+        tool_call_id: &str,
+    ) -> Option<(usize, NormalizedEntry)> {
+        let store = self.msg_store_by_id(&execution_process_id).await?;
+        wait_for_tool_use(&store, tool_call_id).await
+    }
+
+    /// Mark a tool call as denied by an approval policy rule, without creating a pending approval
+    pub async fn deny_by_policy(
+        &self,
+        execution_process_id: Uuid,
+        entry_index: usize,
+        entry: NormalizedEntry,
+        reason: String,
+    ) {
+        let Some(store) = self.msg_store_by_id(&execution_process_id).await else {
+            return;
+        };
+        let status = ToolStatus::Denied {
+            reason: Some(reason),
+            source: DenialSource::Policy,
+        };
+        if let Some(updated_entry) = entry.with_tool_status(status) {
+            store.push_patch(ConversationPatch::replace(entry_index, updated_entry));
+        }
+    }
+
     async fn msg_store_by_id(&self, execution_process_id: &Uuid) -> Option<Arc<MsgStore>> {
         let map = self.msg_stores.read().await;
         map.get(execution_process_id).cloned()
@@ -474,6 +586,67 @@ pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_i
     }
 }
 
+/// Move the task back to InProgress once an approval is answered, if it is still InReview
+async fn move_task_back_to_in_progress(
+    pool: &SqlitePool,
+    status: &ApprovalStatus,
+    execution_process_id: Uuid,
+) {
+    if matches!(
+        status,
+        ApprovalStatus::Approved | ApprovalStatus::Denied { .. }
+    ) && let Ok(ctx) = ExecutionProcess::load_context(pool, execution_process_id).await
+        && ctx.task.status == TaskStatus::InReview
+        && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InProgress).await
+    {
+        tracing::warn!(
+            "Failed to update task status to InProgress after approval response: {}",
+            e
+        );
+    }
+}
+
+/// Find the tool use entry for `tool_call_id`, waiting up to 5 seconds for the log processor
+/// to push it
+async fn wait_for_tool_use(
+    store: &Arc<MsgStore>,
+    tool_call_id: &str,
+) -> Option<(usize, NormalizedEntry)> {
+    // Subscribe to notifications BEFORE the first check to avoid a race
+    // where the log processor pushes between check and subscribe.
+    // `notify_one()` stores a permit if no one is waiting, so the next
+    // `.notified()` call will return immediately.
+    let notify = store.subscribe_notify();
+    let mut notified = std::pin::pin!(notify.notified());
+
+    let mut matching_tool = find_matching_tool_use(store.clone(), tool_call_id);
+
+    if matching_tool.is_none() {
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => {
+                    tracing::warn!(
+                        tool_call_id = %tool_call_id,
+                        "Timed out waiting for tool-use entry in msg_store"
+                    );
+                    break;
+                }
+                _ = &mut notified => {
+                    notified.set(notify.notified());
+                    matching_tool = find_matching_tool_use(store.clone(), tool_call_id);
+                    if matching_tool.is_some() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    matching_tool
+}
+
 /// Find a matching tool use entry that hasn't been assigned to an approval yet
 /// Matches by tool call id from tool metadata
 fn find_matching_tool_use(
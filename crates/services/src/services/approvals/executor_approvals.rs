@@ -1,13 +1,38 @@
 use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
-use db::DBService;
-use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
+use db::{
+    DBService,
+    models::{
+        approval_policy::{
+            ApprovalPolicyDecision, ApprovalPolicyOutcome, ApprovalPolicyRule,
+            CreateApprovalPolicyDecision,
+        },
+        approval_request::StoredApproval,
+        execution_process::ExecutionProcess,
+    },
+};
+use executors::{
+    approvals::{ExecutorApprovalError, ExecutorApprovalService},
+    logs::NormalizedEntryType,
+};
 use serde_json::Value;
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest, Question};
 use uuid::Uuid;
 
-use crate::services::approvals::Approvals;
+use crate::services::approvals::{
+    ApprovalResponseData, Approvals,
+    policy::{self, PolicySubject},
+};
+
+/// What was persisted for a tool call before the current request
+enum Restored {
+    /// Answered after a restart while no agent was waiting
+    Answered(ApprovalResponseData),
+    /// Still waiting for the user; the request takes over its id
+    Pending,
+    New,
+}
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
@@ -23,6 +48,155 @@ impl ExecutorApprovalBridge {
             execution_process_id,
         })
     }
+
+    /// Persist the request, or pick up one persisted before a restart for the same tool call so a
+    /// resumed agent receives the answer given, or still to be given, to the original request
+    async fn persist_or_restore(&self, request: &mut ApprovalRequest) -> Restored {
+        let pool = &self.db.pool;
+        let stored = match StoredApproval::find_undelivered(
+            pool,
+            request.execution_process_id,
+            &request.tool_name,
+            &request.tool_input,
+        )
+        .await
+        {
+            Ok(stored) => stored,
+            Err(e) => {
+                tracing::warn!("Failed to look up persisted approvals: {}", e);
+                None
+            }
+        };
+
+        match stored {
+            // A request some agent in this server is already waiting on is not taken over
+            Some(stored) if self.approvals.is_pending(&stored.request.id) => {}
+            Some(stored) if matches!(stored.status, ApprovalStatus::Pending) => {
+                tracing::info!(
+                    approval_id = %stored.request.id,
+                    tool_call_id = %request.tool_call_id,
+                    "Restoring approval persisted before restart"
+                );
+                request.id = stored.request.id;
+                request.created_at = stored.request.created_at;
+                request.timeout_at = stored.request.timeout_at;
+                if let Err(e) =
+                    StoredApproval::update_tool_call_id(pool, &request.id, &request.tool_call_id)
+                        .await
+                {
+                    tracing::warn!("Failed to update persisted approval: {}", e);
+                }
+                return Restored::Pending;
+            }
+            Some(stored) => {
+                tracing::info!(
+                    approval_id = %stored.request.id,
+                    "Delivering approval answered after restart"
+                );
+                if let Err(e) = StoredApproval::mark_delivered(pool, &stored.request.id).await {
+                    tracing::warn!("Failed to mark persisted approval delivered: {}", e);
+                }
+                return Restored::Answered(ApprovalResponseData {
+                    status: stored.status,
+                    answers: stored.answers,
+                });
+            }
+            None => {}
+        }
+
+        if let Err(e) = StoredApproval::create(pool, request).await {
+            tracing::warn!("Failed to persist approval request: {}", e);
+        }
+        Restored::New
+    }
+
+    /// Record the response the agent received, and withdraw the request from the hive
+    async fn record_response(&self, approval_id: &str, response: &ApprovalResponseData) {
+        self.approvals
+            .forward_resolution_to_hive(approval_id, &response.status);
+        if let Err(e) = StoredApproval::record_response(
+            &self.db.pool,
+            approval_id,
+            &response.status,
+            response.answers.as_ref(),
+            true,
+        )
+        .await
+        {
+            tracing::warn!("Failed to record approval response: {}", e);
+        }
+    }
+
+    /// Decide a tool call with the project's approval policy. Returns `None` when the user
+    /// should be asked, either because no rule matched or because the matching rule says so.
+    async fn apply_policy(&self, tool_name: &str, tool_call_id: &str) -> Option<ApprovalStatus> {
+        let pool = &self.db.pool;
+        let ctx = ExecutionProcess::load_context(pool, self.execution_process_id)
+            .await
+            .ok()?;
+        let rules = match ApprovalPolicyRule::find_by_project_id(pool, ctx.task.project_id).await {
+            Ok(rules) if !rules.is_empty() => rules,
+            Ok(_) => return None,
+            Err(e) => {
+                tracing::warn!("Failed to load approval policy rules: {}", e);
+                return None;
+            }
+        };
+
+        let tool_use = self
+            .approvals
+            .find_tool_use(self.execution_process_id, tool_call_id)
+            .await;
+        // Without the tool-use entry only rules that don't look at the action can match
+        let subject = tool_use
+            .as_ref()
+            .and_then(|(_, entry)| match &entry.entry_type {
+                NormalizedEntryType::ToolUse { action_type, .. } => {
+                    Some(PolicySubject::from_action_type(action_type))
+                }
+                _ => None,
+            })
+            .unwrap_or_default();
+        let decision = policy::evaluate(&rules, tool_name, &subject);
+
+        if let Err(e) = ApprovalPolicyDecision::create(
+            pool,
+            &CreateApprovalPolicyDecision {
+                execution_process_id: self.execution_process_id,
+                rule: decision.rule,
+                tool_name,
+                tool_call_id,
+                action: subject.action,
+                subject: subject.subject.as_deref(),
+                outcome: decision.outcome,
+            },
+        )
+        .await
+        {
+            tracing::warn!("Failed to record approval policy decision: {}", e);
+        }
+
+        match (decision.outcome, decision.rule) {
+            (ApprovalPolicyOutcome::Allow, _) => Some(ApprovalStatus::Approved),
+            (ApprovalPolicyOutcome::Deny, Some(rule)) => {
+                let reason = format!("Denied by approval rule '{}'", rule.name);
+                if let Some((entry_index, entry)) = tool_use {
+                    self.approvals
+                        .deny_by_policy(
+                            self.execution_process_id,
+                            entry_index,
+                            entry,
+                            reason.clone(),
+                        )
+                        .await;
+                }
+                Some(ApprovalStatus::Denied {
+                    reason: Some(reason),
+                })
+            }
+            _ => None,
+        }
+    }
 }
 
 #[async_trait]
@@ -33,9 +207,11 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_input: Value,
         tool_call_id: &str,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
-        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
+        if let Some(status) = self.apply_policy(tool_name, tool_call_id).await {
+            return Ok(status);
+        }
 
-        let request = ApprovalRequest::from_create(
+        let mut request = ApprovalRequest::from_create(
             CreateApprovalRequest {
                 tool_name: tool_name.to_string(),
                 tool_input,
@@ -43,6 +219,13 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             },
             self.execution_process_id,
         );
+        let is_new = match self.persist_or_restore(&mut request).await {
+            Restored::Answered(response) => return Ok(response.status),
+            Restored::Pending => false,
+            Restored::New => true,
+        };
+
+        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
 
         let (request_ref, waiter) = self
             .approvals
@@ -50,8 +233,12 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             .await
             .map_err(ExecutorApprovalError::request_failed)?;
 
-        // Fire webhook for approval_request (non-blocking)
-        {
+        // Fire webhook for approval_request (non-blocking) and offer the request to the rest of
+        // the swarm; restored requests were announced before the restart
+        if is_new {
+            self.approvals
+                .forward_to_hive(&self.db.pool, &request_ref)
+                .await;
             use crate::services::webhook::{WebhookEventPayload, WebhookService};
             let pool = self.db.pool.clone();
             let exec_id = self.execution_process_id;
@@ -67,10 +254,9 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
                     timeout_at,
                 };
                 if let Some(ctx) =
-                    WebhookService::build_approval_context(&pool, exec_id, event).await
+                    WebhookService::build_execution_context(&pool, exec_id, event).await
                 {
-                    let project_id = ctx.project_id;
-                    WebhookService::fire(&pool, project_id, ctx).await;
+                    WebhookService::fire(&pool, ctx).await;
                 } else {
                     tracing::debug!(
                         exec_id = %exec_id,
@@ -81,6 +267,7 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         }
 
         let response_data = waiter.clone().await;
+        self.record_response(&request_ref.id, &response_data).await;
 
         if matches!(response_data.status, ApprovalStatus::Pending) {
             return Err(ExecutorApprovalError::request_failed(
@@ -96,14 +283,19 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         questions: &[Question],
         tool_call_id: &str,
     ) -> Result<(ApprovalStatus, Option<HashMap<String, String>>), ExecutorApprovalError> {
-        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
-
         // Create an approval request with questions
-        let request = ApprovalRequest::from_questions(
+        let mut request = ApprovalRequest::from_questions(
             questions.to_vec(),
             tool_call_id.to_string(),
             self.execution_process_id,
         );
+        let is_new = match self.persist_or_restore(&mut request).await {
+            Restored::Answered(response) => return Ok((response.status, response.answers)),
+            Restored::Pending => false,
+            Restored::New => true,
+        };
+
+        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
 
         let (request_ref, waiter) = self
             .approvals
@@ -111,8 +303,12 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             .await
             .map_err(ExecutorApprovalError::request_failed)?;
 
-        // Fire webhook for pending_question (non-blocking)
-        {
+        // Fire webhook for pending_question (non-blocking) and offer the question to the rest of
+        // the swarm; restored questions were announced before the restart
+        if is_new {
+            self.approvals
+                .forward_to_hive(&self.db.pool, &request_ref)
+                .await;
             use crate::services::webhook::{WebhookEventPayload, WebhookService};
             let pool = self.db.pool.clone();
             let exec_id = self.execution_process_id;
@@ -126,10 +322,9 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
                     timeout_at,
                 };
                 if let Some(ctx) =
-                    WebhookService::build_approval_context(&pool, exec_id, event).await
+                    WebhookService::build_execution_context(&pool, exec_id, event).await
                 {
-                    let project_id = ctx.project_id;
-                    WebhookService::fire(&pool, project_id, ctx).await;
+                    WebhookService::fire(&pool, ctx).await;
                 } else {
                     tracing::debug!(
                         exec_id = %exec_id,
@@ -140,6 +335,7 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         }
 
         let response_data = waiter.clone().await;
+        self.record_response(&request_ref.id, &response_data).await;
 
         if matches!(response_data.status, ApprovalStatus::Pending) {
             return Err(ExecutorApprovalError::request_failed(
@@ -291,6 +291,7 @@ fn parse_executor(executor: &str) -> Result<BaseCodingAgent, AssignmentError> {
         "QWEN_CODE" => Ok(BaseCodingAgent::QwenCode),
         "COPILOT" => Ok(BaseCodingAgent::Copilot),
         "DROID" => Ok(BaseCodingAgent::Droid),
+        "CUSTOM_AGENT" => Ok(BaseCodingAgent::CustomAgent),
         _ => Err(AssignmentError::UnknownExecutor(executor.to_string())),
     }
 }
@@ -0,0 +1,233 @@
+//! Side-by-side comparison of race mode attempts.
+//!
+//! Collects, for each member of an [`AttemptGroup`], the figures needed to pick a winner: diff
+//! size, the outcome of the scripts that ran after the agent, wall-clock duration and the token
+//! usage reported by the agent.
+
+use std::path::PathBuf;
+
+use chrono::Utc;
+use db::models::{
+    attempt_group::{AttemptGroup, AttemptGroupMember},
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_process_usage::ExecutionProcessUsage,
+    project::Project,
+    task::Task,
+    task_attempt::TaskAttempt,
+};
+use executors::{
+    actions::{
+        ExecutorActionType,
+        script::{ScriptContext, ScriptRequest},
+    },
+    profile::ExecutorProfileId,
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::diff::compute_line_change_counts;
+
+use crate::services::git::GitService;
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct AttemptDiffStats {
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Latest run of a script that executes after the agent (cleanup, verification or pipeline)
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptScriptResult {
+    pub context: ScriptContext,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+}
+
+/// Token usage summed over the attempt's coding agent runs, as reported by the agent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+pub struct AttemptTokenUsage {
+    pub input_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub output_tokens: i64,
+    /// Only reported by some agents
+    pub total_cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptComparison {
+    pub task_attempt: TaskAttempt,
+    pub executor_profile_id: ExecutorProfileId,
+    pub discarded: bool,
+    pub is_winner: bool,
+    /// Status of the latest coding agent run
+    pub agent_status: Option<ExecutionProcessStatus>,
+    /// Changes against the target branch; `None` when they could not be computed
+    pub diff_stats: Option<AttemptDiffStats>,
+    pub script_results: Vec<AttemptScriptResult>,
+    /// Total run time of the attempt's processes, counting running ones up to now
+    pub duration_seconds: i64,
+    pub token_usage: Option<AttemptTokenUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptGroupComparison {
+    pub group: AttemptGroup,
+    pub attempts: Vec<AttemptComparison>,
+}
+
+pub async fn compare_attempts(
+    pool: &SqlitePool,
+    git: &GitService,
+    group: AttemptGroup,
+) -> Result<AttemptGroupComparison, sqlx::Error> {
+    let project = match Task::find_by_id(pool, group.task_id).await? {
+        Some(task) => Project::find_by_id(pool, task.project_id).await?,
+        None => None,
+    };
+
+    let mut attempts = Vec::new();
+    for member in AttemptGroup::members(pool, group.id).await? {
+        let Some(task_attempt) = TaskAttempt::find_by_id(pool, member.task_attempt_id).await?
+        else {
+            continue;
+        };
+        let diff_stats = match &project {
+            Some(project) => {
+                diff_stats(git, PathBuf::from(&project.git_repo_path), &task_attempt).await
+            }
+            None => None,
+        };
+        attempts.push(compare_attempt(pool, &group, member, task_attempt, diff_stats).await?);
+    }
+
+    Ok(AttemptGroupComparison { group, attempts })
+}
+
+async fn compare_attempt(
+    pool: &SqlitePool,
+    group: &AttemptGroup,
+    member: AttemptGroupMember,
+    task_attempt: TaskAttempt,
+    diff_stats: Option<AttemptDiffStats>,
+) -> Result<AttemptComparison, sqlx::Error> {
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id, false).await?;
+
+    let mut agent_status = None;
+    let mut script_results: Vec<AttemptScriptResult> = Vec::new();
+    let mut duration_seconds = 0;
+    let mut token_usage: Option<AttemptTokenUsage> = None;
+
+    for process in &processes {
+        if matches!(process.run_reason, ExecutionProcessRunReason::DevServer) {
+            continue;
+        }
+        let ended_at = process.completed_at.unwrap_or_else(Utc::now);
+        duration_seconds += (ended_at - process.started_at).num_seconds().max(0);
+
+        let Ok(action) = process.executor_action() else {
+            continue;
+        };
+        match action.typ() {
+            ExecutorActionType::ScriptRequest(ScriptRequest { context, .. })
+                if matches!(
+                    context,
+                    ScriptContext::CleanupScript
+                        | ScriptContext::VerificationScript
+                        | ScriptContext::PipelineScript
+                ) =>
+            {
+                // Processes are ordered oldest first, so later runs replace earlier ones
+                script_results.retain(|result| result.context != *context);
+                script_results.push(AttemptScriptResult {
+                    context: context.clone(),
+                    status: process.status.clone(),
+                    exit_code: process.exit_code,
+                });
+            }
+            ExecutorActionType::ScriptRequest(_) => {}
+            _ => {
+                agent_status = Some(process.status.clone());
+                if let Some(usage) =
+                    ExecutionProcessUsage::find_by_execution_process_id(pool, process.id).await?
+                {
+                    let total = token_usage.get_or_insert_with(Default::default);
+                    total.input_tokens += usage.input_tokens;
+                    total.cached_input_tokens += usage.cached_input_tokens;
+                    total.output_tokens += usage.output_tokens;
+                    if let Some(cost) = usage.total_cost_usd {
+                        *total.total_cost_usd.get_or_insert(0.0) += cost;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(AttemptComparison {
+        is_winner: group.winner_attempt_id == Some(task_attempt.id),
+        task_attempt,
+        executor_profile_id: member.executor_profile_id,
+        discarded: member.discarded,
+        agent_status,
+        diff_stats,
+        script_results,
+        duration_seconds,
+        token_usage,
+    })
+}
+
+/// Diff stats of the attempt against its target branch: the live worktree when it still
+/// exists, otherwise the committed branch.
+async fn diff_stats(
+    git: &GitService,
+    repo_path: PathBuf,
+    task_attempt: &TaskAttempt,
+) -> Option<AttemptDiffStats> {
+    let git = git.clone();
+    let worktree_path = task_attempt
+        .container_ref
+        .as_ref()
+        .filter(|_| !task_attempt.worktree_deleted)
+        .map(PathBuf::from)
+        .filter(|path| path.exists());
+    let branch = task_attempt.branch.clone();
+    let target_branch = task_attempt.target_branch.clone();
+    let attempt_id = task_attempt.id;
+
+    let result = tokio::task::spawn_blocking(move || {
+        let diffs = git.get_branch_diffs(
+            &repo_path,
+            worktree_path.as_deref(),
+            &branch,
+            &target_branch,
+        )?;
+
+        let mut stats = AttemptDiffStats::default();
+        for diff in diffs {
+            let (additions, deletions) = match (diff.additions, diff.deletions) {
+                (Some(additions), Some(deletions)) => (additions, deletions),
+                _ => compute_line_change_counts(
+                    diff.old_content.as_deref().unwrap_or(""),
+                    diff.new_content.as_deref().unwrap_or(""),
+                ),
+            };
+            stats.files_changed += 1;
+            stats.additions += additions;
+            stats.deletions += deletions;
+        }
+        Ok::<_, crate::services::git::GitServiceError>(stats)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(stats)) => Some(stats),
+        Ok(Err(e)) => {
+            tracing::warn!(task_attempt_id = %attempt_id, error = %e, "Failed to compute diff stats");
+            None
+        }
+        Err(e) => {
+            tracing::warn!(task_attempt_id = %attempt_id, error = %e, "Diff stats task failed");
+            None
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Live enforcement of project and task usage budgets.
+//!
+//! Budgets are checked each time new usage is recorded for a running execution. A breach is
+//! reported once per process to the deployment's enforcer, which stops the process and fires
+//! the `budget_exceeded` webhook.
+
+use db::models::{
+    execution_process_usage::UsagePeriod,
+    usage_budget::{BudgetLimit, UsageBudget, UsageBudgetStatus},
+};
+use sqlx::SqlitePool;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::services::webhook::{WebhookEventPayload, WebhookService};
+
+/// A running execution whose project or task budget has been reached
+#[derive(Debug, Clone)]
+pub struct BudgetBreach {
+    pub execution_process_id: Uuid,
+    pub status: UsageBudgetStatus,
+}
+
+/// Sends budget breaches to the task that stops over-budget executions
+#[derive(Clone)]
+pub struct BudgetEnforcerHandle {
+    tx: mpsc::UnboundedSender<BudgetBreach>,
+}
+
+impl BudgetEnforcerHandle {
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<BudgetBreach>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { tx }, rx)
+    }
+
+    /// Check the budgets covering `execution_process_id` and report a breach if one has been
+    /// reached. Returns true once a breach was reported.
+    pub async fn check(&self, pool: &SqlitePool, execution_process_id: Uuid) -> bool {
+        let status = match UsageBudget::find_exceeded(pool, execution_process_id).await {
+            Ok(Some(status)) => status,
+            Ok(None) => return false,
+            Err(e) => {
+                tracing::error!(
+                    execution_process_id = %execution_process_id,
+                    error = %e,
+                    "Failed to check usage budgets"
+                );
+                return false;
+            }
+        };
+        tracing::warn!(
+            execution_process_id = %execution_process_id,
+            budget_id = %status.budget.id,
+            "{}",
+            status.describe()
+        );
+        if self
+            .tx
+            .send(BudgetBreach {
+                execution_process_id,
+                status,
+            })
+            .is_err()
+        {
+            tracing::error!("Budget enforcer is not running; breach dropped");
+        }
+        true
+    }
+}
+
+/// Fire the `budget_exceeded` webhook for a breach (fire-and-forget).
+pub fn fire_budget_webhook(pool: &SqlitePool, breach: &BudgetBreach) {
+    let pool = pool.clone();
+    let exec_id = breach.execution_process_id;
+    let status = &breach.status;
+    let event = WebhookEventPayload::BudgetExceeded {
+        scope: if status.budget.task_id.is_some() {
+            "task"
+        } else {
+            "project"
+        }
+        .to_string(),
+        limit: match status.exceeded {
+            Some(BudgetLimit::CostUsd) => "cost_usd",
+            _ => "tokens",
+        }
+        .to_string(),
+        max_tokens: status.budget.max_tokens,
+        max_cost_usd: status.budget.max_cost_usd,
+        period: status.budget.period.map(|period| {
+            match period {
+                UsagePeriod::Day => "day",
+                UsagePeriod::Week => "week",
+            }
+            .to_string()
+        }),
+        spent_tokens: status.spend.tokens,
+        spent_cost_usd: status.spend.cost_usd,
+        message: status.describe(),
+    };
+    tokio::spawn(async move {
+        if let Some(ctx) = WebhookService::build_execution_context(&pool, exec_id, event).await {
+            WebhookService::fire(&pool, ctx).await;
+        } else {
+            tracing::debug!(
+                exec_id = %exec_id,
+                "webhook: could not build budget context, event not delivered"
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::{
+        execution_process::{CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason},
+        project::{CreateProject, Project},
+        task::{CreateTask, Task},
+        task_attempt::{CreateTaskAttempt, TaskAttempt},
+        usage_budget::SetUsageBudget,
+    };
+    use executors::{
+        actions::{
+            ExecutorAction, ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest,
+        },
+        executors::BaseCodingAgent,
+        logs::{NormalizedEntry, NormalizedEntryType, utils::patch::ConversationPatch},
+        profile::ExecutorProfileId,
+    };
+
+    use super::*;
+    use crate::services::usage::UsageRecorder;
+
+    fn message_usage(index: usize, input_tokens: i64, output_tokens: i64) -> json_patch::Patch {
+        ConversationPatch::add_normalized_entry(
+            index,
+            NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::TokenUsage {
+                    input_tokens,
+                    cached_input_tokens: 0,
+                    output_tokens,
+                    reasoning_tokens: 0,
+                    last_total_tokens: input_tokens + output_tokens,
+                    context_window: None,
+                },
+                content: String::new(),
+                metadata: None,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_claude_run_is_stopped_before_its_result() {
+        let (pool, _tmp) = db::test_utils::create_test_pool().await;
+        let project_id = Uuid::new_v4();
+        Project::create(
+            &pool,
+            &CreateProject {
+                name: "Budget Project".to_string(),
+                git_repo_path: format!("/tmp/test-repo-{project_id}"),
+                use_existing_repo: true,
+                clone_url: None,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+            },
+            project_id,
+        )
+        .await
+        .unwrap();
+        let task_id = Uuid::new_v4();
+        Task::create(
+            &pool,
+            &CreateTask::from_title_description(project_id, "Budget".to_string(), None),
+            task_id,
+        )
+        .await
+        .unwrap();
+        let attempt_id = Uuid::new_v4();
+        TaskAttempt::create(
+            &pool,
+            &CreateTaskAttempt {
+                executor: BaseCodingAgent::ClaudeCode,
+                base_branch: "main".to_string(),
+                branch: format!("budget-{attempt_id}"),
+                origin_node_id: None,
+            },
+            attempt_id,
+            task_id,
+        )
+        .await
+        .unwrap();
+        let process_id = Uuid::new_v4();
+        ExecutionProcess::create(
+            &pool,
+            &CreateExecutionProcess {
+                task_attempt_id: attempt_id,
+                executor_action: ExecutorAction::new(
+                    ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                        prompt: "Loop forever".to_string(),
+                        executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+                    }),
+                    None,
+                ),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+            },
+            process_id,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        UsageBudget::set_for_task(
+            &pool,
+            task_id,
+            &SetUsageBudget {
+                max_tokens: Some(1_000),
+                max_cost_usd: None,
+                period: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let (enforcer, mut breaches) = BudgetEnforcerHandle::new();
+        let mut recorder = UsageRecorder::new(process_id, Some(BaseCodingAgent::ClaudeCode));
+
+        // Each message reports only its own usage; no result message arrives
+        recorder
+            .observe(&pool, &message_usage(0, 400, 200))
+            .await
+            .unwrap();
+        assert!(!enforcer.check(&pool, process_id).await);
+        recorder
+            .observe(&pool, &message_usage(1, 400, 200))
+            .await
+            .unwrap();
+        assert!(enforcer.check(&pool, process_id).await);
+
+        let breach = breaches.try_recv().unwrap();
+        assert_eq!(breach.execution_process_id, process_id);
+        assert_eq!(breach.status.exceeded, Some(BudgetLimit::Tokens));
+        assert_eq!(breach.status.spend.tokens, 1_200);
+        // Claude has not reported a cost yet, so it is estimated from the tokens
+        assert!((breach.status.spend.cost_usd - 0.0084).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,258 @@
+//! The shell commands coding agents ran during an attempt, as a timeline.
+//!
+//! Every executor's normalizer reports shell commands as [`ActionType::CommandRun`] tool uses.
+//! [`CommandTimelineRecorder`] watches the patches of one execution process and writes each
+//! command to [`ExecutorCommand`] as it starts and finishes, which is what gives commands their
+//! start times and durations. Processes that ran before commands were recorded still show their
+//! commands, extracted from the stored log, without timing.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use db::models::executor_command::{CommandRunStatus, ExecutorCommand, ExecutorCommandUpdate};
+use executors::logs::{
+    ActionType, CommandExitStatus, NormalizedEntry, NormalizedEntryType, ToolStatus,
+    utils::patch::extract_normalized_entry_from_patch,
+};
+use json_patch::Patch;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Bytes of command output kept; longer output keeps its tail, where errors and summaries are
+pub const MAX_OUTPUT_BYTES: usize = 16 * 1024;
+
+/// A command in an attempt's timeline
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct TimelineCommand {
+    pub execution_process_id: Uuid,
+    /// Index of the command's entry in the process's normalized conversation
+    pub entry_index: i64,
+    pub command: String,
+    pub status: CommandRunStatus,
+    /// Only set when the agent reported an exit code rather than just success or failure
+    pub exit_code: Option<i64>,
+    /// Tail of the output, cut to a fixed size
+    pub output: Option<String>,
+    pub output_truncated: bool,
+    /// Unset for commands of imported processes or ones that ran before commands were timed
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+}
+
+impl From<ExecutorCommand> for TimelineCommand {
+    fn from(command: ExecutorCommand) -> Self {
+        Self {
+            duration_ms: command.duration_ms(),
+            execution_process_id: command.execution_process_id,
+            entry_index: command.entry_index,
+            command: command.command,
+            status: command.status,
+            exit_code: command.exit_code,
+            output: command.output,
+            output_truncated: command.output_truncated,
+            started_at: Some(command.started_at),
+            completed_at: command.completed_at,
+        }
+    }
+}
+
+impl TimelineCommand {
+    /// A command extracted from a stored log, which has no timing
+    pub fn untimed(execution_process_id: Uuid, update: ExecutorCommandUpdate) -> Self {
+        Self {
+            execution_process_id,
+            entry_index: update.entry_index,
+            command: update.command,
+            status: update.status,
+            exit_code: update.exit_code,
+            output: update.output,
+            output_truncated: update.output_truncated,
+            started_at: None,
+            completed_at: None,
+            duration_ms: None,
+        }
+    }
+}
+
+/// The command carried by a normalized entry, if it is a command run
+pub fn command_from_entry(index: usize, entry: &NormalizedEntry) -> Option<ExecutorCommandUpdate> {
+    let NormalizedEntryType::ToolUse {
+        action_type: ActionType::CommandRun { command, result },
+        status,
+        ..
+    } = &entry.entry_type
+    else {
+        return None;
+    };
+
+    let exit_status = result.as_ref().and_then(|r| r.exit_status.as_ref());
+    let status = match (status, exit_status) {
+        (ToolStatus::Denied { .. } | ToolStatus::TimedOut { .. }, _) => CommandRunStatus::Denied,
+        (ToolStatus::PendingApproval { .. } | ToolStatus::PendingQuestion { .. }, _) => {
+            CommandRunStatus::AwaitingApproval
+        }
+        (_, Some(CommandExitStatus::ExitCode { code: 0 }))
+        | (_, Some(CommandExitStatus::Success { success: true })) => CommandRunStatus::Succeeded,
+        (_, Some(_)) | (ToolStatus::Failed, None) => CommandRunStatus::Failed,
+        (ToolStatus::Success, None) => CommandRunStatus::Succeeded,
+        _ => CommandRunStatus::Running,
+    };
+    let exit_code = match exit_status {
+        Some(CommandExitStatus::ExitCode { code }) => Some(i64::from(*code)),
+        _ => None,
+    };
+    let (output, output_truncated) = match result.as_ref().and_then(|r| r.output.as_deref()) {
+        Some(output) => {
+            let (output, truncated) = truncate_output(output);
+            (Some(output), truncated)
+        }
+        None => (None, false),
+    };
+
+    Some(ExecutorCommandUpdate {
+        entry_index: index as i64,
+        command: command.clone(),
+        status,
+        exit_code,
+        output,
+        output_truncated,
+    })
+}
+
+/// Commands in a conversation replayed from a stored log
+pub fn commands_from_entries(
+    execution_process_id: Uuid,
+    entries: &[NormalizedEntry],
+) -> Vec<TimelineCommand> {
+    entries
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| command_from_entry(index, entry))
+        .map(|update| TimelineCommand::untimed(execution_process_id, update))
+        .collect()
+}
+
+fn truncate_output(output: &str) -> (String, bool) {
+    if output.len() <= MAX_OUTPUT_BYTES {
+        return (output.to_string(), false);
+    }
+    let mut start = output.len() - MAX_OUTPUT_BYTES;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    (output[start..].to_string(), true)
+}
+
+/// Records the commands of one execution process, skipping patches that leave a command as it was.
+pub struct CommandTimelineRecorder {
+    execution_process_id: Uuid,
+    last: HashMap<i64, ExecutorCommandUpdate>,
+}
+
+impl CommandTimelineRecorder {
+    pub fn new(execution_process_id: Uuid) -> Self {
+        Self {
+            execution_process_id,
+            last: HashMap::new(),
+        }
+    }
+
+    /// Persist the command carried by `patch`, returning whether anything was written.
+    pub async fn observe(&mut self, pool: &SqlitePool, patch: &Patch) -> Result<bool, sqlx::Error> {
+        let Some(update) = extract_normalized_entry_from_patch(patch)
+            .and_then(|(index, entry)| command_from_entry(index, &entry))
+        else {
+            return Ok(false);
+        };
+        if self.last.get(&update.entry_index) == Some(&update) {
+            return Ok(false);
+        }
+        ExecutorCommand::record(pool, self.execution_process_id, &update).await?;
+        self.last.insert(update.entry_index, update);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::CommandRunResult;
+
+    use super::*;
+
+    fn command_entry(status: ToolStatus, result: Option<CommandRunResult>) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "Bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "npm test".to_string(),
+                    result,
+                },
+                status,
+            },
+            content: "npm test".to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_command_status_follows_exit_status_and_approval() {
+        let running = command_from_entry(2, &command_entry(ToolStatus::Created, None)).unwrap();
+        assert_eq!(running.entry_index, 2);
+        assert_eq!(running.status, CommandRunStatus::Running);
+
+        let failed = command_from_entry(
+            2,
+            &command_entry(
+                ToolStatus::Success,
+                Some(CommandRunResult {
+                    exit_status: Some(CommandExitStatus::ExitCode { code: 1 }),
+                    output: Some("1 failing".to_string()),
+                }),
+            ),
+        )
+        .unwrap();
+        assert_eq!(failed.status, CommandRunStatus::Failed);
+        assert_eq!(failed.exit_code, Some(1));
+        assert_eq!(failed.output.as_deref(), Some("1 failing"));
+
+        let passed = command_from_entry(
+            2,
+            &command_entry(
+                ToolStatus::Created,
+                Some(CommandRunResult {
+                    exit_status: Some(CommandExitStatus::Success { success: true }),
+                    output: None,
+                }),
+            ),
+        )
+        .unwrap();
+        assert_eq!(passed.status, CommandRunStatus::Succeeded);
+        assert_eq!(passed.exit_code, None);
+
+        let denied = command_from_entry(
+            2,
+            &command_entry(
+                ToolStatus::TimedOut {
+                    waited_seconds: Some(60),
+                },
+                None,
+            ),
+        )
+        .unwrap();
+        assert_eq!(denied.status, CommandRunStatus::Denied);
+    }
+
+    #[test]
+    fn test_long_output_keeps_its_tail() {
+        let output = format!("{}é{}", "x".repeat(MAX_OUTPUT_BYTES), "done");
+        let (kept, truncated) = truncate_output(&output);
+        assert!(truncated);
+        assert!(kept.len() <= MAX_OUTPUT_BYTES);
+        assert!(kept.ends_with("édone"));
+        assert_eq!(truncate_output("ok"), ("ok".to_string(), false));
+    }
+}
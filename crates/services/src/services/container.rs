@@ -14,7 +14,12 @@ use db::{
             ExecutionProcessStatus,
         },
         execution_process_logs::ExecutionProcessLogs,
+        execution_process_usage::ExecutionProcessUsage,
+        executor_command::ExecutorCommand,
         executor_session::{CreateExecutorSession, ExecutorSession},
+        log_search::LogSearch,
+        project::Project,
+        project_pipeline::ProjectPipeline,
         task::{Task, TaskStatus},
         task_attempt::{TaskAttempt, TaskAttemptError},
         task_variable::TaskVariable,
@@ -25,9 +30,14 @@ use executors::{
         ExecutorAction, ExecutorActionType,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
+        coding_agent_review::{CodingAgentReviewRequest, CodingAgentReviewTarget},
+        pipeline::{Pipeline, PipelineState, PipelineStepKind},
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
-    executors::{ExecutorError, StandardCodingAgentExecutor},
+    executors::{
+        ExecutorError, StandardCodingAgentExecutor,
+        session_import::{ImportedSession, SessionImportError},
+    },
     logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
@@ -36,6 +46,7 @@ use sqlx::Error as SqlxError;
 use thiserror::Error;
 use tokio::{sync::RwLock, task::JoinHandle, time::Duration};
 use utils::{
+    diff::Diff,
     log_msg::LogMsg,
     msg_store::MsgStore,
     text::{git_branch_id, short_uuid},
@@ -43,16 +54,22 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
+    budget::BudgetEnforcerHandle,
+    command_timeline::{CommandTimelineRecorder, TimelineCommand, commands_from_entries},
     config::Config,
     git::{GitService, GitServiceError},
     image::ImageService,
     log_batcher::LogBatcherHandle,
+    log_search::LogSearchIndexer,
     normalization_metrics::NormalizationMetrics,
     notification::NotificationService,
     process_fence::{self, FenceOutcome},
     process_inspector::{ProcessInspector, SysinfoProcessInspector},
     share::SharePublisher,
+    transcript::{Transcript, TranscriptProcess, diff_text, entries_from_stream},
+    usage::UsageRecorder,
     variable_expander,
+    webhook::{WebhookEventPayload, WebhookService},
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
@@ -74,13 +91,15 @@ pub enum ContainerError {
     #[error(transparent)]
     TaskAttemptError(#[from] TaskAttemptError),
     #[error(transparent)]
+    SessionImport(#[from] SessionImportError),
+    #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
 
 /// Build a resume action from a stored coding-agent action.
 ///
 /// Constructs a new `CodingAgentFollowUpRequest` using the provided session ID and prompt,
-/// preserving the executor profile and `next_action` from the stored action.
+/// preserving the executor profile, `next_action` and pipeline state from the stored action.
 ///
 /// Handles both `CodingAgentInitialRequest` (first-turn crash) and
 /// `CodingAgentFollowUpRequest` (multi-turn crash), covering the full resume surface.
@@ -100,10 +119,11 @@ pub fn build_resume_action(
                 session_id,
                 executor_profile_id: req.executor_profile_id.clone(),
             };
-            let action = ExecutorAction::new(
+            let mut action = ExecutorAction::new(
                 ExecutorActionType::CodingAgentFollowUpRequest(follow_up),
                 stored.next_action().map(|next| Box::new(next.clone())),
             );
+            action.pipeline = stored.pipeline.clone();
             Some(action)
         }
         ExecutorActionType::CodingAgentFollowUpRequest(req) => {
@@ -114,10 +134,11 @@ pub fn build_resume_action(
                 session_id,
                 executor_profile_id: req.executor_profile_id.clone(),
             };
-            let action = ExecutorAction::new(
+            let mut action = ExecutorAction::new(
                 ExecutorActionType::CodingAgentFollowUpRequest(follow_up),
                 stored.next_action().map(|next| Box::new(next.clone())),
             );
+            action.pipeline = stored.pipeline.clone();
             Some(action)
         }
         _ => None,
@@ -128,6 +149,34 @@ pub fn build_resume_action(
 /// operator-facing escalation warning for a process stuck in D-state. See ADR-0005.
 const FENCE_ESCALATION_THRESHOLD: i64 = 5;
 
+/// Maximum number of characters of a step's output substituted for `{output}` in a pipeline
+/// follow-up prompt. The tail is kept, since failures are usually reported last.
+const PIPELINE_OUTPUT_TAIL_CHARS: usize = 8000;
+
+/// Run reason for an action executing a pipeline step.
+fn pipeline_run_reason(action: &ExecutorAction) -> ExecutionProcessRunReason {
+    match action.typ() {
+        ExecutorActionType::ScriptRequest(_) => ExecutionProcessRunReason::PipelineStep,
+        _ => ExecutionProcessRunReason::CodingAgent,
+    }
+}
+
+/// The last `PIPELINE_OUTPUT_TAIL_CHARS` characters of a process's stdout and stderr.
+fn output_tail(history: &[LogMsg]) -> String {
+    let output: String = history
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::Stdout(s) | LogMsg::Stderr(s) => Some(s.as_str()),
+            _ => None,
+        })
+        .collect();
+    let skip = output
+        .chars()
+        .count()
+        .saturating_sub(PIPELINE_OUTPUT_TAIL_CHARS);
+    output.chars().skip(skip).collect()
+}
+
 #[async_trait]
 pub trait ContainerService {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>;
@@ -142,6 +191,12 @@ pub trait ContainerService {
     /// Returns None if batching is disabled (falls back to direct writes).
     fn log_batcher(&self) -> Option<&LogBatcherHandle>;
 
+    /// Get the handle that stops executions once a usage budget is reached.
+    /// Returns None if this deployment does not enforce budgets.
+    fn budget_enforcer(&self) -> Option<&BudgetEnforcerHandle> {
+        None
+    }
+
     /// Get normalization metrics for tracking completion times and timeouts.
     fn normalization_metrics(&self) -> &NormalizationMetrics;
 
@@ -230,19 +285,26 @@ pub trait ContainerService {
 
     /// A context is finalized when
     /// - Always when the execution process has failed or been killed
-    /// - Never when the run reason is DevServer
+    /// - Never when the run reason is DevServer or CommandRerun
     /// - Never when the run reason is SetupScript with no next_action (parallel mode)
+    /// - Never for pipeline steps while the pipeline has a further step to run
     /// - The next action is None (no follow-up actions)
     fn should_finalize(&self, ctx: &ExecutionContext) -> bool {
         if matches!(
             ctx.execution_process.run_reason,
-            ExecutionProcessRunReason::DevServer
+            ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::CommandRerun
         ) {
             return false;
         }
 
         let action = ctx.execution_process.executor_action().unwrap();
 
+        // Pipeline steps never finalize while the pipeline has a further step to run. Script
+        // steps run as `PipelineStep` but agent steps as `CodingAgent`, so check the action.
+        if action.pipeline().is_some() && self.next_pipeline_state(ctx).is_some() {
+            return false;
+        }
+
         // Never finalize setup scripts without next_action (parallel mode)
         // In parallel mode, the setup script runs independently and shouldn't trigger finalization
         if matches!(
@@ -570,6 +632,7 @@ pub trait ContainerService {
             ExecutionProcessRunReason::CodingAgent
                 | ExecutionProcessRunReason::SetupScript
                 | ExecutionProcessRunReason::CleanupScript
+                | ExecutionProcessRunReason::PipelineStep
         ) && let Ok(Some(task)) = task_attempt.parent_task(pool).await
         {
             match Task::update_status(pool, task.id, TaskStatus::InReview).await {
@@ -676,17 +739,384 @@ pub trait ContainerService {
         Ok(())
     }
 
-    fn cleanup_action(&self, cleanup_script: Option<String>) -> Option<Box<ExecutorAction>> {
-        cleanup_script.map(|script| {
-            Box::new(ExecutorAction::new(
-                ExecutorActionType::ScriptRequest(ScriptRequest {
-                    script,
-                    language: ScriptRequestLanguage::Bash,
-                    context: ScriptContext::CleanupScript,
-                }),
-                None,
-            ))
+    /// Index coding agent runs that finished before full-text log search existed, or before a
+    /// restart cut their indexing short
+    async fn backfill_log_search_index(&self) -> Result<usize, ContainerError> {
+        const BATCH_SIZE: i64 = 50;
+        let pool = &self.db().pool;
+        let mut indexed = 0;
+        loop {
+            let ids = LogSearch::find_unindexed_executions(pool, BATCH_SIZE).await?;
+            if ids.is_empty() {
+                return Ok(indexed);
+            }
+            for id in ids {
+                match self.stream_normalized_logs(&id).await {
+                    Some(stream) => {
+                        LogSearchIndexer::index_stream(pool, id, stream).await?;
+                    }
+                    // No stored logs; record it as indexed so it isn't retried
+                    None => LogSearch::replace_for_execution(pool, id, &[]).await?,
+                }
+                indexed += 1;
+            }
+        }
+    }
+
+    /// The normalized conversation of an execution. Running executions return the entries
+    /// produced so far.
+    async fn normalized_entries(&self, id: &Uuid) -> Vec<NormalizedEntry> {
+        if let Some(store) = self.get_msg_store_by_id(id).await {
+            let history = futures::stream::iter(store.get_history().into_iter().map(Ok));
+            return entries_from_stream(history).await;
+        }
+        match self.stream_normalized_logs(id).await {
+            Some(stream) => entries_from_stream(stream).await,
+            None => Vec::new(),
+        }
+    }
+
+    /// The attempt's diff against its target branch. While the worktree exists the diff
+    /// includes uncommitted changes.
+    async fn attempt_diffs(&self, task_attempt: &TaskAttempt) -> Result<Vec<Diff>, ContainerError> {
+        let pool = &self.db().pool;
+        let task = task_attempt
+            .parent_task(pool)
+            .await?
+            .ok_or(TaskAttemptError::TaskNotFound)?;
+        let project = Project::find_by_id(pool, task.project_id)
+            .await?
+            .ok_or(TaskAttemptError::ProjectNotFound)?;
+
+        let git = self.git().clone();
+        let worktree_path = task_attempt
+            .container_ref
+            .as_ref()
+            .filter(|_| !task_attempt.worktree_deleted)
+            .map(PathBuf::from)
+            .filter(|path| path.exists());
+        let branch = task_attempt.branch.clone();
+        let target_branch = task_attempt.target_branch.clone();
+        let diffs = tokio::task::spawn_blocking(move || {
+            git.get_branch_diffs(
+                &project.git_repo_path,
+                worktree_path.as_deref(),
+                &branch,
+                &target_branch,
+            )
         })
+        .await
+        .map_err(|e| anyhow!("Diff task failed: {e}"))??;
+        Ok(diffs)
+    }
+
+    /// Gather the conversation of every execution in the attempt's history for export,
+    /// optionally with the attempt's diff against its target branch.
+    async fn export_transcript(
+        &self,
+        task_attempt: &TaskAttempt,
+        include_diff: bool,
+    ) -> Result<Transcript, ContainerError> {
+        let pool = &self.db().pool;
+        let task = task_attempt
+            .parent_task(pool)
+            .await?
+            .ok_or(TaskAttemptError::TaskNotFound)?;
+        let project = Project::find_by_id(pool, task.project_id)
+            .await?
+            .ok_or(TaskAttemptError::ProjectNotFound)?;
+
+        let history =
+            ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id, false).await?;
+        let mut processes = Vec::with_capacity(history.len());
+        for process in history {
+            let (prompt, script) = match process.executor_action().map(|action| action.typ()) {
+                Ok(ExecutorActionType::CodingAgentInitialRequest(request)) => {
+                    (Some(request.prompt.clone()), None)
+                }
+                Ok(ExecutorActionType::CodingAgentFollowUpRequest(request)) => {
+                    (Some(request.prompt.clone()), None)
+                }
+                Ok(ExecutorActionType::ScriptRequest(request)) => {
+                    (None, Some(request.script.clone()))
+                }
+                _ => (None, None),
+            };
+            processes.push(TranscriptProcess {
+                entries: self.normalized_entries(&process.id).await,
+                usage: ExecutionProcessUsage::find_by_execution_process_id(pool, process.id)
+                    .await?,
+                id: process.id,
+                run_reason: process.run_reason,
+                status: process.status,
+                exit_code: process.exit_code,
+                started_at: process.started_at,
+                completed_at: process.completed_at,
+                prompt,
+                script,
+            });
+        }
+
+        let diff = if include_diff {
+            Some(diff_text(&self.attempt_diffs(task_attempt).await?))
+        } else {
+            None
+        };
+
+        Ok(Transcript {
+            task_attempt_id: task_attempt.id,
+            task_id: task.id,
+            task_title: task.title,
+            task_description: task.description,
+            project_name: project.name,
+            executor: task_attempt.executor.clone(),
+            branch: task_attempt.branch.clone(),
+            target_branch: task_attempt.target_branch.clone(),
+            exported_at: chrono::Utc::now(),
+            processes,
+            diff,
+        })
+    }
+
+    /// The shell commands coding agents ran in the attempt, oldest first. Dropped processes are
+    /// left out.
+    async fn command_timeline(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<Vec<TimelineCommand>, ContainerError> {
+        let pool = &self.db().pool;
+        let history =
+            ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id, false).await?;
+        let mut commands = Vec::new();
+        for process in history {
+            if process.run_reason != ExecutionProcessRunReason::CodingAgent {
+                continue;
+            }
+            let recorded = ExecutorCommand::find_by_execution_process_id(pool, process.id).await?;
+            if recorded.is_empty() {
+                // Ran before commands were recorded, or was imported; take them from the log
+                let entries = self.normalized_entries(&process.id).await;
+                commands.extend(commands_from_entries(process.id, &entries));
+            } else {
+                commands.extend(recorded.into_iter().map(TimelineCommand::from));
+            }
+        }
+        Ok(commands)
+    }
+
+    /// Record a session started outside the app as the history of a freshly created attempt:
+    /// one completed coding agent run per prompt, with the session installed in the worktree
+    /// so follow-ups resume it.
+    async fn import_session(
+        &self,
+        task_attempt: &TaskAttempt,
+        executor_profile_id: ExecutorProfileId,
+        session: &ImportedSession,
+    ) -> Result<Vec<ExecutionProcess>, ContainerError> {
+        let pool = &self.db().pool;
+        let task = task_attempt
+            .parent_task(pool)
+            .await?
+            .ok_or(TaskAttemptError::TaskNotFound)?;
+        let worktree_path = task_attempt
+            .container_ref
+            .as_ref()
+            .map(PathBuf::from)
+            .ok_or_else(|| ContainerError::Other(anyhow!("Container ref not found")))?;
+        session.install(&worktree_path).await?;
+
+        let mut processes = Vec::with_capacity(session.turns.len());
+        for (turn_index, turn) in session.turns.iter().enumerate() {
+            let typ = if turn_index == 0 {
+                ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                    prompt: turn.prompt.clone(),
+                    executor_profile_id: executor_profile_id.clone(),
+                })
+            } else {
+                ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                    prompt: turn.prompt.clone(),
+                    session_id: session.session_id.clone(),
+                    executor_profile_id: executor_profile_id.clone(),
+                })
+            };
+            let execution_process = ExecutionProcess::create(
+                pool,
+                &CreateExecutionProcess {
+                    task_attempt_id: task_attempt.id,
+                    executor_action: ExecutorAction::new(typ, None),
+                    run_reason: ExecutionProcessRunReason::CodingAgent,
+                },
+                Uuid::new_v4(),
+                None,
+                Some(self.instance_id()),
+            )
+            .await?;
+            ExecutorSession::create(
+                pool,
+                &CreateExecutorSession {
+                    task_attempt_id: task_attempt.id,
+                    execution_process_id: execution_process.id,
+                    prompt: Some(turn.prompt.clone()),
+                },
+                Uuid::new_v4(),
+            )
+            .await?;
+            ExecutorSession::update_session_id(pool, execution_process.id, &session.session_id)
+                .await?;
+
+            // Persist the turn the way a live run's log stream does
+            let mut usage =
+                UsageRecorder::new(execution_process.id, Some(executor_profile_id.executor));
+            let mut search_index = LogSearchIndexer::new(execution_process.id);
+            for patch in &turn.patches {
+                search_index.observe(patch);
+                usage.observe(pool, patch).await?;
+                let json_line = serde_json::to_string(&LogMsg::JsonPatch(patch.clone()))
+                    .map_err(|e| anyhow!("Failed to serialize JsonPatch: {e}"))?;
+                ExecutionProcessLogs::append_log_line(
+                    pool,
+                    execution_process.id,
+                    &format!("{json_line}\n"),
+                )
+                .await?;
+            }
+            search_index.finish(pool).await?;
+
+            ExecutionProcess::update_completion(
+                pool,
+                execution_process.id,
+                ExecutionProcessStatus::Completed,
+                Some(0),
+                Some("imported"),
+                None,
+            )
+            .await?;
+            processes.push(
+                ExecutionProcess::find_by_id(pool, execution_process.id)
+                    .await?
+                    .ok_or(SqlxError::RowNotFound)?,
+            );
+        }
+
+        Task::update_status(pool, task.id, TaskStatus::InReview).await?;
+        WebhookService::spawn_attempt_event(
+            pool,
+            task_attempt.id,
+            WebhookEventPayload::AttemptCreated,
+        );
+
+        Ok(processes)
+    }
+
+    async fn cleanup_action(
+        &self,
+        project: &Project,
+    ) -> Result<Option<Box<ExecutorAction>>, ContainerError> {
+        let Some(script) = project.cleanup_script.clone() else {
+            return Ok(None);
+        };
+        let language = Project::script_language(&self.db().pool, project.id).await?;
+        Ok(Some(Box::new(ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script,
+                language,
+                context: ScriptContext::CleanupScript,
+            }),
+            None,
+        ))))
+    }
+
+    /// The pipeline step to run after this execution, if it belongs to a pipeline that
+    /// continues. Killed executions always end their pipeline.
+    fn next_pipeline_state(&self, ctx: &ExecutionContext) -> Option<PipelineState> {
+        let succeeded = match ctx.execution_process.status {
+            ExecutionProcessStatus::Completed => ctx.execution_process.exit_code == Some(0),
+            ExecutionProcessStatus::Failed => false,
+            ExecutionProcessStatus::Running | ExecutionProcessStatus::Killed => return None,
+        };
+        let action = ctx.execution_process.executor_action().ok()?;
+        action.pipeline()?.advance(succeeded)
+    }
+
+    /// Build the action executing the current step of `state`.
+    ///
+    /// `previous_output` replaces `{output}` in follow-up prompts. Follow-ups fall back to a
+    /// fresh agent run when the attempt has no session to resume. Scripts run in the project's
+    /// script language unless the step names its own.
+    async fn pipeline_step_action(
+        &self,
+        task_attempt: &TaskAttempt,
+        project_id: Uuid,
+        state: PipelineState,
+        previous_output: Option<&str>,
+    ) -> Result<ExecutorAction, ContainerError> {
+        let executor_profile_id = state.executor_profile_id.clone();
+        let typ = match &state.step().kind {
+            PipelineStepKind::CodingAgent => {
+                ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                    prompt: state.prompt.clone(),
+                    executor_profile_id,
+                })
+            }
+            PipelineStepKind::Script { script, language } => {
+                let language = match language {
+                    Some(language) => language.clone(),
+                    None => Project::script_language(&self.db().pool, project_id).await?,
+                };
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: script.clone(),
+                    language,
+                    context: ScriptContext::PipelineScript,
+                })
+            }
+            PipelineStepKind::Verification { script } => {
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: script.clone(),
+                    language: Project::script_language(&self.db().pool, project_id).await?,
+                    context: ScriptContext::VerificationScript,
+                })
+            }
+            PipelineStepKind::FollowUp { prompt } => {
+                let prompt = prompt.replace("{output}", previous_output.unwrap_or_default());
+                match ExecutionProcess::find_latest_session_id_by_task_attempt(
+                    &self.db().pool,
+                    task_attempt.id,
+                )
+                .await?
+                {
+                    Some(session_id) => {
+                        ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                            prompt,
+                            session_id,
+                            executor_profile_id,
+                        })
+                    }
+                    None => {
+                        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                            prompt,
+                            executor_profile_id,
+                        })
+                    }
+                }
+            }
+            PipelineStepKind::Review {
+                instructions,
+                executor_profile_id: review_profile_id,
+            } => ExecutorActionType::CodingAgentReviewRequest(CodingAgentReviewRequest {
+                target: match instructions {
+                    Some(instructions) => CodingAgentReviewTarget::Custom {
+                        instructions: instructions.clone(),
+                    },
+                    None => CodingAgentReviewTarget::BaseBranch {
+                        branch: task_attempt.target_branch.clone(),
+                    },
+                },
+                append_to_original_thread: false,
+                session_id: None,
+                executor_profile_id: review_profile_id.clone().unwrap_or(executor_profile_id),
+            }),
+        };
+        Ok(ExecutorAction::new(typ, None).with_pipeline(state))
     }
 
     async fn try_stop(&self, task_attempt: &TaskAttempt) {
@@ -1046,11 +1476,16 @@ pub trait ContainerService {
         }
     }
 
-    fn spawn_stream_raw_logs_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
-        let execution_id = *execution_id;
+    fn spawn_stream_raw_logs_to_db(&self, execution_process: &ExecutionProcess) -> JoinHandle<()> {
+        let execution_id = execution_process.id;
+        let executor = execution_process
+            .executor_action()
+            .ok()
+            .and_then(|action| action.base_executor());
         let msg_stores = self.msg_stores().clone();
         let db = self.db().clone();
         let log_batcher = self.log_batcher().cloned();
+        let budget_enforcer = self.budget_enforcer().cloned();
 
         tokio::spawn(async move {
             // Get the message store for this execution
@@ -1061,6 +1496,10 @@ pub trait ContainerService {
 
             if let Some(store) = store {
                 let mut stream = store.history_plus_stream();
+                let mut usage = UsageRecorder::new(execution_id, executor);
+                let mut search_index = LogSearchIndexer::new(execution_id);
+                let mut commands = CommandTimelineRecorder::new(execution_id);
+                let mut budget_reported = false;
 
                 while let Some(Ok(msg)) = stream.next().await {
                     match &msg {
@@ -1120,9 +1559,40 @@ pub trait ContainerService {
                             if let Some(ref batcher) = log_batcher {
                                 batcher.finish(execution_id).await;
                             }
+                            if let Err(e) = search_index.finish(&db.pool).await {
+                                tracing::error!(
+                                    "Failed to index logs for search for execution {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
                             break;
                         }
-                        LogMsg::JsonPatch(_) => {
+                        LogMsg::JsonPatch(patch) => {
+                            search_index.observe(patch);
+                            match usage.observe(&db.pool, patch).await {
+                                Ok(Some(_)) if !budget_reported => {
+                                    if let Some(ref enforcer) = budget_enforcer {
+                                        budget_reported =
+                                            enforcer.check(&db.pool, execution_id).await;
+                                    }
+                                }
+                                Ok(_) => {}
+                                Err(e) => {
+                                    tracing::error!(
+                                        "Failed to record token usage for execution {}: {}",
+                                        execution_id,
+                                        e
+                                    );
+                                }
+                            }
+                            if let Err(e) = commands.observe(&db.pool, patch).await {
+                                tracing::error!(
+                                    "Failed to record command for execution {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
                             // Persist JsonPatch to database via log batcher
                             if let Some(ref batcher) = log_batcher {
                                 batcher.add_log(execution_id, msg.clone()).await;
@@ -1218,6 +1688,12 @@ pub trait ContainerService {
             .await?
             .ok_or(SqlxError::RowNotFound)?;
 
+        WebhookService::spawn_attempt_event(
+            &self.db().pool,
+            task_attempt.id,
+            WebhookEventPayload::AttemptCreated,
+        );
+
         // TODO: this implementation will not work in cloud
         let worktree_path = PathBuf::from(
             task_attempt
@@ -1269,16 +1745,44 @@ pub trait ContainerService {
             }
         };
 
-        let cleanup_action = self.cleanup_action(project.cleanup_script);
+        // The agent-side chain: the project's pipeline when configured, otherwise the
+        // coding agent followed by the cleanup script
+        let pipeline = ProjectPipeline::find_enabled(&self.db().pool, project.id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(project_id = %project.id, error = ?e, "Failed to load project pipeline");
+                None
+            });
+        let (agent_action, agent_run_reason) = match pipeline {
+            Some(pipeline) => {
+                let state = PipelineState::start(pipeline, executor_profile_id.clone(), prompt);
+                let action = self
+                    .pipeline_step_action(&task_attempt, project.id, state, None)
+                    .await?;
+                let run_reason = pipeline_run_reason(&action);
+                (action, run_reason)
+            }
+            None => (
+                ExecutorAction::new(
+                    ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                        prompt,
+                        executor_profile_id: executor_profile_id.clone(),
+                    }),
+                    self.cleanup_action(&project).await?,
+                ),
+                ExecutionProcessRunReason::CodingAgent,
+            ),
+        };
 
         // Choose whether to execute the setup_script or coding agent first
         let execution_process = if let Some(setup_script) = project.setup_script {
+            let language = Project::script_language(&self.db().pool, project.id).await?;
             if project.parallel_setup_script {
                 // Parallel mode: start setup script independently (no next_action)
                 let setup_action = ExecutorAction::new(
                     ExecutorActionType::ScriptRequest(ScriptRequest {
                         script: setup_script,
-                        language: ScriptRequestLanguage::Bash,
+                        language,
                         context: ScriptContext::SetupScript,
                     }),
                     None, // No chaining - runs independently
@@ -1295,36 +1799,18 @@ pub trait ContainerService {
                 }
 
                 // Start coding agent immediately (don't wait for setup script)
-                let coding_action = ExecutorAction::new(
-                    ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                        prompt,
-                        executor_profile_id: executor_profile_id.clone(),
-                    }),
-                    cleanup_action,
-                );
-
-                self.start_execution(
-                    &task_attempt,
-                    &coding_action,
-                    &ExecutionProcessRunReason::CodingAgent,
-                )
-                .await?
+                self.start_execution(&task_attempt, &agent_action, &agent_run_reason)
+                    .await?
             } else {
                 // Sequential mode (existing behavior): chain setup → coding agent
                 let executor_action = ExecutorAction::new(
                     ExecutorActionType::ScriptRequest(ScriptRequest {
                         script: setup_script,
-                        language: ScriptRequestLanguage::Bash,
+                        language,
                         context: ScriptContext::SetupScript,
                     }),
                     // once the setup script is done, run the initial coding agent request
-                    Some(Box::new(ExecutorAction::new(
-                        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                            prompt,
-                            executor_profile_id: executor_profile_id.clone(),
-                        }),
-                        cleanup_action,
-                    ))),
+                    Some(Box::new(agent_action)),
                 );
 
                 self.start_execution(
@@ -1335,20 +1821,8 @@ pub trait ContainerService {
                 .await?
             }
         } else {
-            let executor_action = ExecutorAction::new(
-                ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                    prompt,
-                    executor_profile_id: executor_profile_id.clone(),
-                }),
-                cleanup_action,
-            );
-
-            self.start_execution(
-                &task_attempt,
-                &executor_action,
-                &ExecutionProcessRunReason::CodingAgent,
-            )
-            .await?
+            self.start_execution(&task_attempt, &agent_action, &agent_run_reason)
+                .await?
         };
         Ok(execution_process)
     }
@@ -1365,7 +1839,10 @@ pub trait ContainerService {
             .await?
             .ok_or(SqlxError::RowNotFound)?;
         if task.status != TaskStatus::InProgress
-            && run_reason != &ExecutionProcessRunReason::DevServer
+            && !matches!(
+                run_reason,
+                ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::CommandRerun
+            )
         {
             Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
 
@@ -1532,12 +2009,26 @@ pub trait ContainerService {
             }
         }
 
-        self.spawn_stream_raw_logs_to_db(&execution_process.id);
+        self.spawn_stream_raw_logs_to_db(&execution_process);
         Ok(execution_process)
     }
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
         let action = ctx.execution_process.executor_action()?;
+        if action.pipeline().is_some() {
+            if self.next_pipeline_state(ctx).is_some() {
+                return self.try_start_next_pipeline_step(ctx).await;
+            }
+            // A finished pipeline continues with its next action (e.g. the cleanup script
+            // after verification) only when its last step succeeded
+            if !matches!(
+                ctx.execution_process.status,
+                ExecutionProcessStatus::Completed
+            ) {
+                tracing::debug!("Pipeline finished with a failed step");
+                return Ok(());
+            }
+        }
         let next_action = if let Some(next_action) = action.next_action() {
             next_action
         } else {
@@ -1547,6 +2038,14 @@ pub trait ContainerService {
 
         // Determine the run reason of the next action
         let next_run_reason = match (action.typ(), next_action.typ()) {
+            _ if next_action.pipeline().is_some() => pipeline_run_reason(next_action),
+            (
+                _,
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    context: ScriptContext::CleanupScript,
+                    ..
+                }),
+            ) => ExecutionProcessRunReason::CleanupScript,
             (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(_)) => {
                 ExecutionProcessRunReason::SetupScript
             }
@@ -1570,6 +2069,128 @@ pub trait ContainerService {
         tracing::debug!("Started next action: {:?}", next_action);
         Ok(())
     }
+
+    /// Start the pipeline step selected by the outcome of this execution, if any.
+    ///
+    /// When the step cannot be started the pipeline ends and the task moves to InReview, so
+    /// the attempt is never left waiting on a step that will not run.
+    async fn try_start_next_pipeline_step(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<(), ContainerError> {
+        let Some(state) = self.next_pipeline_state(ctx) else {
+            tracing::debug!("Pipeline finished");
+            return Ok(());
+        };
+        let continuation = ctx.execution_process.executor_action()?.next_action.clone();
+
+        let previous_output = match self.get_msg_store_by_id(&ctx.execution_process.id).await {
+            Some(msg_store) => output_tail(&msg_store.get_history()),
+            None => String::new(),
+        };
+
+        tracing::info!(
+            task_attempt_id = %ctx.task_attempt.id,
+            step = %state.step().name,
+            "Starting pipeline step"
+        );
+
+        let result = match self
+            .pipeline_step_action(
+                &ctx.task_attempt,
+                ctx.task.project_id,
+                state,
+                Some(&previous_output),
+            )
+            .await
+        {
+            Ok(mut next_action) => {
+                next_action.next_action = continuation;
+                self.start_execution(
+                    &ctx.task_attempt,
+                    &next_action,
+                    &pipeline_run_reason(&next_action),
+                )
+                .await
+                .map(|_| ())
+            }
+            Err(e) => Err(e),
+        };
+
+        if result.is_err() {
+            Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await?;
+        }
+        result
+    }
+
+    /// Start the project's verification script after a successful coding agent turn.
+    ///
+    /// Verification runs as an implicit verify → fix pipeline that carries the agent's
+    /// `next_action` (the cleanup script), so cleanup runs once verification passes. Returns
+    /// whether verification was started; when it was not, the caller continues with the
+    /// regular next action. Projects with an enabled pipeline are skipped, since the
+    /// pipeline decides what runs after each step.
+    async fn try_start_verification(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        if !matches!(
+            ctx.execution_process.status,
+            ExecutionProcessStatus::Completed
+        ) || ctx.execution_process.exit_code != Some(0)
+        {
+            return Ok(false);
+        }
+        let action = ctx.execution_process.executor_action()?;
+        if action.pipeline().is_some() {
+            return Ok(false);
+        }
+        let executor_profile_id = match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                request.executor_profile_id.clone()
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                request.get_executor_profile_id()
+            }
+            _ => return Ok(false),
+        };
+
+        // Projects with a pipeline express verification as pipeline steps
+        if ProjectPipeline::find_enabled(&self.db().pool, ctx.task.project_id)
+            .await?
+            .is_some()
+        {
+            return Ok(false);
+        }
+        let Some(verification) =
+            Project::find_verification(&self.db().pool, ctx.task.project_id).await?
+        else {
+            return Ok(false);
+        };
+        let Some(script) = verification.script() else {
+            return Ok(false);
+        };
+
+        tracing::info!(
+            task_attempt_id = %ctx.task_attempt.id,
+            max_retries = verification.verification_max_retries,
+            "Starting verification script"
+        );
+
+        let state = PipelineState::start(
+            Pipeline::verification(script.to_string(), verification.verification_max_retries),
+            executor_profile_id,
+            String::new(),
+        );
+        let mut verify_action = self
+            .pipeline_step_action(&ctx.task_attempt, ctx.task.project_id, state, None)
+            .await?;
+        verify_action.next_action = action.next_action.clone();
+        self.start_execution(
+            &ctx.task_attempt,
+            &verify_action,
+            &pipeline_run_reason(&verify_action),
+        )
+        .await?;
+        Ok(true)
+    }
 }
 
 #[cfg(test)]
@@ -1612,7 +2233,9 @@ mod tests {
         }
 
         fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>> {
-            unimplemented!()
+            static MSG_STORES: std::sync::LazyLock<Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>> =
+                std::sync::LazyLock::new(Default::default);
+            &MSG_STORES
         }
         fn git(&self) -> &GitService {
             unimplemented!()
@@ -1737,6 +2360,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_build_resume_action_preserves_pipeline_state() {
+        use executors::actions::pipeline::{Pipeline, PipelineStep};
+
+        let pipeline = Pipeline {
+            steps: vec![PipelineStep {
+                name: "agent".to_string(),
+                kind: PipelineStepKind::CodingAgent,
+                on_success: None,
+                on_failure: None,
+                max_runs: None,
+            }],
+        };
+        let state = PipelineState::start(
+            pipeline,
+            ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+            "Initial prompt".to_string(),
+        );
+        let stored = sample_coding_agent_action_claude().with_pipeline(state.clone());
+
+        let action = build_resume_action(&stored, "sess-abc".to_string(), "continue".to_string())
+            .expect("resumable");
+
+        assert_eq!(action.pipeline(), Some(&state));
+    }
+
     #[test]
     fn test_build_resume_action_follow_up_preserves_profile_and_updates_session() {
         // A crash during a multi-turn follow-up must be resumable (SC1 coverage gap fix).
@@ -2148,6 +2797,93 @@ mod tests {
         assert_eq!(state, Some("resumed".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_failed_command_rerun_leaves_task_status_alone() {
+        use db::DbMetrics;
+        use db::test_utils::create_test_pool;
+        use executors::actions::script::{ScriptContext, ScriptRequest, ScriptRequestLanguage};
+
+        use crate::services::webhook::WebhookEventPayload;
+
+        let (pool, _tmp) = create_test_pool().await;
+
+        let project_id = uuid::Uuid::new_v4();
+        sqlx::query("INSERT INTO projects (id, name, git_repo_path) VALUES ($1, 'p', '/tmp/p')")
+            .bind(project_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let task_id = uuid::Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO tasks (id, project_id, title, status) VALUES ($1, $2, 't', 'inreview')",
+        )
+        .bind(task_id)
+        .bind(project_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let attempt_id = uuid::Uuid::new_v4();
+        sqlx::query("INSERT INTO task_attempts (id, task_id, executor, branch, target_branch) VALUES ($1, $2, 'CLAUDE_CODE', 'b', 'main')")
+            .bind(attempt_id).bind(task_id).execute(&pool).await.unwrap();
+        let task_attempt = TaskAttempt::find_by_id(&pool, attempt_id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let service = TestContainerService {
+            db: DBService {
+                pool: pool.clone(),
+                metrics: DbMetrics::new(),
+            },
+            instance_id: "test-instance".to_string(),
+            inspector: MockProcessInspector::new(),
+            captured_action: Arc::new(Mutex::new(None)),
+        };
+
+        let action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: "cargo test".to_string(),
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::CommandRerun,
+            }),
+            None,
+        );
+        let process = service
+            .start_execution(
+                &task_attempt,
+                &action,
+                &ExecutionProcessRunReason::CommandRerun,
+            )
+            .await
+            .unwrap();
+        let task = Task::find_by_id(&pool, task_id).await.unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::InReview);
+
+        ExecutionProcess::update_completion(
+            &pool,
+            process.id,
+            ExecutionProcessStatus::Failed,
+            Some(101),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let ctx = ExecutionProcess::load_context(&pool, process.id)
+            .await
+            .unwrap();
+        assert!(!service.should_finalize(&ctx));
+        assert!(
+            WebhookEventPayload::setup_script_failed(&ctx.execution_process, Some(101)).is_none()
+        );
+
+        service
+            .mark_process_failed_with_task_update(&pool, &ctx.execution_process, &task_attempt)
+            .await;
+        let task = Task::find_by_id(&pool, task_id).await.unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::InReview);
+    }
+
     #[tokio::test]
     #[traced_test]
     async fn test_cleanup_orphan_executions_stubborn_pid_escalation() {
@@ -329,7 +329,8 @@ pub fn apply_stream_omit_policy(diff: &mut Diff, sent_bytes: &Arc<AtomicUsize>,
     }
 }
 
-fn omit_diff_contents(diff: &mut Diff) {
+/// Drop a diff's file contents, keeping line counts so stats still render.
+pub fn omit_diff_contents(diff: &mut Diff) {
     if diff.additions.is_none()
         && diff.deletions.is_none()
         && (diff.old_content.is_some() || diff.new_content.is_some())
@@ -2,8 +2,8 @@ use std::{collections::HashMap, path::Path};
 
 use chrono::{DateTime, Utc};
 use git2::{
-    BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, Reference, Remote,
-    Repository, Sort,
+    BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, IndexAddOption, Reference,
+    Remote, Repository, Sort,
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -294,6 +294,38 @@ impl GitService {
         Ok(true)
     }
 
+    /// Get the diffs of an attempt branch against its target branch: the live worktree
+    /// (including uncommitted changes) when `worktree_path` is given, otherwise the committed
+    /// branch.
+    pub fn get_branch_diffs(
+        &self,
+        repo_path: &Path,
+        worktree_path: Option<&Path>,
+        branch: &str,
+        target_branch: &str,
+    ) -> Result<Vec<Diff>, GitServiceError> {
+        match worktree_path {
+            Some(worktree_path) => {
+                let base_commit = self.get_base_commit(repo_path, branch, target_branch)?;
+                self.get_diffs(
+                    DiffTarget::Worktree {
+                        worktree_path,
+                        base_commit: &base_commit,
+                    },
+                    None,
+                )
+            }
+            None => self.get_diffs(
+                DiffTarget::Branch {
+                    repo_path,
+                    branch_name: branch,
+                    base_branch: target_branch,
+                },
+                None,
+            ),
+        }
+    }
+
     /// Get diffs between branches or worktree changes
     pub fn get_diffs(
         &self,
@@ -1466,6 +1498,20 @@ impl GitService {
         Ok(())
     }
 
+    /// Delete a local branch
+    pub fn delete_local_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut branch = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?;
+        branch.delete()?;
+        Ok(())
+    }
+
     /// Return true if a rebase is currently in progress in this worktree.
     pub fn is_rebase_in_progress(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
         let git = GitCli::new();
@@ -1909,6 +1955,56 @@ impl GitService {
         Ok(())
     }
 
+    /// Commit the state of the checkout containing `path`: its HEAD plus any uncommitted
+    /// changes, including untracked files that aren't ignored. The checkout's index and working
+    /// tree are left untouched and no ref is moved. Returns HEAD itself when nothing changed.
+    ///
+    /// Returns `InvalidRepository` if the checkout is not of the repository at `repo_path`.
+    pub fn snapshot_checkout(
+        &self,
+        repo_path: &Path,
+        path: &Path,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let checkout = Repository::discover(path)?;
+        let same_repository = match (
+            common_dir(&repo).canonicalize(),
+            common_dir(&checkout).canonicalize(),
+        ) {
+            (Ok(repo_dir), Ok(checkout_dir)) => repo_dir == checkout_dir,
+            _ => false,
+        };
+        if !same_repository {
+            return Err(GitServiceError::InvalidRepository(format!(
+                "{} is not a checkout of {}",
+                path.display(),
+                repo_path.display()
+            )));
+        }
+
+        let head = checkout.head()?.peel_to_commit()?;
+        // Stage everything in memory; only the tree objects get written
+        let mut index = checkout.index()?;
+        index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+        index.update_all(["*"], None)?;
+        let tree_id = index.write_tree()?;
+        if tree_id == head.tree_id() {
+            return Ok(head.id().to_string());
+        }
+
+        let tree = checkout.find_tree(tree_id)?;
+        let signature = self.signature_with_fallback(&checkout)?;
+        let commit = checkout.commit(
+            None,
+            &signature,
+            &signature,
+            "Uncommitted changes",
+            &tree,
+            &[&head],
+        )?;
+        Ok(commit.to_string())
+    }
+
     /// Get a list of files with uncommitted changes (dirty files).
     ///
     /// This is useful for displaying to users which files have changes
@@ -1962,3 +2058,12 @@ impl GitService {
         Ok(())
     }
 }
+
+/// The git directory shared by all worktrees of `repo`
+fn common_dir(repo: &Repository) -> std::path::PathBuf {
+    let git_dir = repo.path();
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common_dir) => git_dir.join(common_dir.trim()),
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
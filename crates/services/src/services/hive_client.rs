@@ -3,9 +3,13 @@
 //! This module provides a WebSocket-based client for nodes to connect to the
 //! central hive server, register, send heartbeats, and receive task assignments.
 
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use chrono::Utc;
+use executors::{
+    executors::{BaseCodingAgent, StandardCodingAgentExecutor},
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
 use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::{
@@ -14,6 +18,7 @@ use tokio::{
 };
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use url::Url;
+use utils::approvals::{ApprovalStatus, Question};
 use uuid::Uuid;
 
 /// Heartbeat interval - how often to send heartbeats to the hive.
@@ -53,6 +58,8 @@ pub struct NodeCapabilities {
     pub git_commit: String,
     #[serde(default)]
     pub git_branch: String,
+    #[serde(default)]
+    pub labels: Vec<String>,
 }
 
 fn default_max_concurrent() -> i32 {
@@ -125,6 +132,12 @@ pub enum NodeMessage {
     /// detect silent divergence the ack cursor misses (SC5, CONTRACT §A). Tracer scope: entity_type "task".
     #[serde(rename = "digest")]
     Digest { entries: Vec<DigestEntry> },
+    /// An agent is waiting for approval; forwarded so organization members can answer it
+    #[serde(rename = "approval_request")]
+    ApprovalRequest(ApprovalRequestMessage),
+    /// An approval was answered or timed out on this node
+    #[serde(rename = "approval_resolved")]
+    ApprovalResolved(ApprovalResolvedMessage),
 }
 
 /// Messages sent from hive to node.
@@ -176,6 +189,9 @@ pub enum HiveMessage {
     /// has that the node lacks (hive-has/node-lacks heal via the bulk-snapshot reconcile leg).
     #[serde(rename = "digest_result")]
     DigestResult { resend_from_seq: Option<i64>, pull_entities: Vec<Uuid> },
+    /// Answer to a forwarded approval, given on the hive or another node
+    #[serde(rename = "approval_response")]
+    ApprovalResponse(ApprovalResponseMessage),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -703,6 +719,42 @@ pub struct DigestEntry {
     pub version: i64,
 }
 
+/// Approval request forwarded from this node to the hive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequestMessage {
+    pub approval_id: String,
+    pub local_execution_process_id: Uuid,
+    pub local_attempt_id: Option<Uuid>,
+    pub shared_task_id: Option<Uuid>,
+    pub task_title: String,
+    pub tool_name: String,
+    pub tool_input: serde_json::Value,
+    #[serde(default)]
+    pub questions: Option<Vec<Question>>,
+    pub created_at: chrono::DateTime<Utc>,
+    pub timeout_at: chrono::DateTime<Utc>,
+}
+
+/// Notice that an approval was settled on this node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalResolvedMessage {
+    pub approval_id: String,
+    pub status: ApprovalStatus,
+}
+
+/// Answer to a forwarded approval from the hive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalResponseMessage {
+    pub approval_id: String,
+    pub local_execution_process_id: Uuid,
+    pub status: ApprovalStatus,
+    #[serde(default)]
+    pub answers: Option<HashMap<String, String>>,
+    /// Display name of the member who answered; unset for escalation defaults
+    #[serde(default)]
+    pub responded_by: Option<String>,
+}
+
 /// Protocol version
 const PROTOCOL_VERSION: u32 = 1;
 
@@ -751,6 +803,8 @@ pub enum HiveEvent {
         resend_from_seq: Option<i64>,
         pull_entities: Vec<Uuid>,
     },
+    /// Answer to an approval this node forwarded
+    ApprovalResponse(ApprovalResponseMessage),
 }
 
 /// State of the hive connection.
@@ -1188,6 +1242,17 @@ impl HiveClient {
                     );
                 }
             }
+            HiveMessage::ApprovalResponse(response) => {
+                tracing::info!(
+                    approval_id = %response.approval_id,
+                    responded_by = ?response.responded_by,
+                    "received approval response from hive"
+                );
+                let _ = self
+                    .event_tx
+                    .send(HiveEvent::ApprovalResponse(response))
+                    .await;
+            }
             _ => {
                 tracing::debug!(?hive_msg, "ignoring unhandled hive message");
             }
@@ -1289,18 +1354,71 @@ pub enum HiveClientError {
 }
 
 /// Detect node capabilities from the system.
+///
+/// The hive only dispatches tasks to nodes whose capabilities match, so these can be overridden:
+/// - `VK_NODE_EXECUTORS`: comma-separated executors to accept (defaults to the installed ones)
+/// - `VK_NODE_MAX_CONCURRENT_TASKS`: tasks the hive may run here at once (defaults to 1)
+/// - `VK_NODE_LABELS`: comma-separated labels tasks can require (e.g. `gpu,eu`)
 pub fn detect_capabilities() -> NodeCapabilities {
     use utils::build_info::BUILD_INFO;
 
+    let max_concurrent_tasks = match std::env::var("VK_NODE_MAX_CONCURRENT_TASKS") {
+        Ok(value) => value
+            .trim()
+            .parse::<i32>()
+            .ok()
+            .filter(|max| *max > 0)
+            .unwrap_or_else(|| {
+                tracing::warn!(
+                    value = %value,
+                    "VK_NODE_MAX_CONCURRENT_TASKS must be a positive number, using the default"
+                );
+                default_max_concurrent()
+            }),
+        Err(_) => default_max_concurrent(),
+    };
+
     NodeCapabilities {
-        executors: vec!["CLAUDE_CODE".to_string()], // Default supported executor
-        max_concurrent_tasks: 1,
+        executors: env_list("VK_NODE_EXECUTORS").unwrap_or_else(installed_executors),
+        max_concurrent_tasks,
         os: std::env::consts::OS.to_string(),
         arch: std::env::consts::ARCH.to_string(),
         version: BUILD_INFO.version.to_string(),
         git_commit: BUILD_INFO.git_commit.to_string(),
         git_branch: BUILD_INFO.git_branch.to_string(),
+        labels: env_list("VK_NODE_LABELS").unwrap_or_default(),
+    }
+}
+
+/// Comma-separated values of an environment variable, if it has any
+fn env_list(name: &str) -> Option<Vec<String>> {
+    let value = std::env::var(name).ok()?;
+    let items: Vec<String> = value
+        .split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect();
+    (!items.is_empty()).then_some(items)
+}
+
+/// Executors that are installed and signed in on this machine, or Claude Code if none are found.
+fn installed_executors() -> Vec<String> {
+    let configs = ExecutorConfigs::get_cached();
+    let mut executors: Vec<String> = configs
+        .executors
+        .keys()
+        .filter(|agent| {
+            configs
+                .get_coding_agent(&ExecutorProfileId::new(**agent))
+                .is_some_and(|coding_agent| coding_agent.get_availability_info().is_available())
+        })
+        .map(|agent| agent.to_string())
+        .collect();
+    if executors.is_empty() {
+        executors.push(BaseCodingAgent::ClaudeCode.to_string());
     }
+    executors.sort();
+    executors
 }
 
 /// Generate a stable machine ID.
@@ -0,0 +1,112 @@
+//! Feeds finished executions into the full-text log search index.
+//!
+//! [`LogSearchIndexer`] watches the normalized patches of one execution process, keeping the
+//! latest searchable text of each entry as tool calls progress, and writes them to
+//! [`LogSearch`] when the execution finishes. Executions that finished before the index existed
+//! are indexed at startup from their stored logs.
+
+use std::collections::BTreeMap;
+
+use db::models::log_search::{LogSearch, LogSearchDocument};
+use executors::logs::utils::patch::extract_normalized_entry_from_patch;
+use futures::{Stream, StreamExt};
+use json_patch::Patch;
+use sqlx::SqlitePool;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+/// Collects the searchable entries of one execution process
+pub struct LogSearchIndexer {
+    execution_process_id: Uuid,
+    documents: BTreeMap<usize, LogSearchDocument>,
+}
+
+impl LogSearchIndexer {
+    pub fn new(execution_process_id: Uuid) -> Self {
+        Self {
+            execution_process_id,
+            documents: BTreeMap::new(),
+        }
+    }
+
+    /// Track the entry carried by `patch`, replacing an earlier version of it
+    pub fn observe(&mut self, patch: &Patch) {
+        let Some((index, entry)) = extract_normalized_entry_from_patch(patch) else {
+            return;
+        };
+        match LogSearchDocument::from_entry(index, &entry) {
+            Some(document) => {
+                self.documents.insert(index, document);
+            }
+            None => {
+                self.documents.remove(&index);
+            }
+        }
+    }
+
+    /// Write the collected entries, replacing any indexed earlier, and return how many there are
+    pub async fn finish(self, pool: &SqlitePool) -> Result<usize, sqlx::Error> {
+        let documents: Vec<_> = self.documents.into_values().collect();
+        LogSearch::replace_for_execution(pool, self.execution_process_id, &documents).await?;
+        Ok(documents.len())
+    }
+
+    /// Index an execution from its normalized log stream
+    pub async fn index_stream<S>(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        mut stream: S,
+    ) -> Result<usize, sqlx::Error>
+    where
+        S: Stream<Item = Result<LogMsg, std::io::Error>> + Unpin,
+    {
+        let mut indexer = Self::new(execution_process_id);
+        while let Some(Ok(msg)) = stream.next().await {
+            match msg {
+                LogMsg::JsonPatch(patch) => indexer.observe(&patch),
+                LogMsg::Finished => break,
+                _ => {}
+            }
+        }
+        indexer.finish(pool).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::log_search::LogSearchEntryType;
+    use executors::logs::{NormalizedEntry, NormalizedEntryType, utils::patch::ConversationPatch};
+
+    use super::*;
+
+    fn message(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_observe_keeps_latest_version_of_each_entry() {
+        let mut indexer = LogSearchIndexer::new(Uuid::new_v4());
+        indexer.observe(&ConversationPatch::add_normalized_entry(
+            0,
+            message(NormalizedEntryType::AssistantMessage, "Working on it"),
+        ));
+        indexer.observe(&ConversationPatch::replace(
+            0,
+            message(NormalizedEntryType::AssistantMessage, "Done"),
+        ));
+        indexer.observe(&ConversationPatch::add_normalized_entry(
+            1,
+            message(NormalizedEntryType::Thinking, "Hmm"),
+        ));
+
+        assert_eq!(indexer.documents.len(), 1);
+        let document = &indexer.documents[&0];
+        assert_eq!(document.entry_type, LogSearchEntryType::AssistantMessage);
+        assert_eq!(document.content, "Done");
+    }
+}
@@ -12,7 +12,10 @@
 
 pub mod approvals;
 pub mod assignment_handler;
+pub mod attempt_group;
 pub mod auth;
+pub mod budget;
+pub mod command_timeline;
 pub mod config;
 pub mod connection_token;
 pub mod container;
@@ -30,6 +33,7 @@ pub mod hive_client;
 pub mod hive_sync;
 pub mod image;
 pub mod log_batcher;
+pub mod log_search;
 pub mod node_proxy_client;
 pub mod node_runner;
 pub mod normalization_metrics;
@@ -41,8 +45,13 @@ pub mod process_inspector;
 pub mod process_service;
 pub mod project_detector;
 pub mod remote_client;
+pub mod secrets;
+pub mod task_webhooks;
+pub mod terminal_recording;
 pub mod terminal_session;
+pub mod transcript;
 pub mod unified_logs;
+pub mod usage;
 pub mod variable_expander;
 pub mod webhook;
 pub mod worktree_manager;
@@ -46,7 +46,10 @@ use tokio::time::{self, MissedTickBehavior};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-use super::remote_client::{RemoteClient, RemoteClientError};
+use super::{
+    remote_client::{RemoteClient, RemoteClientError},
+    webhook::{WebhookEventPayload, WebhookNode, WebhookService},
+};
 
 /// Default sync interval (5 minutes)
 const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(300);
@@ -175,6 +178,13 @@ impl<'a> NodeCacheSyncer<'a> {
             let node_id = node.id;
             synced_node_ids.push(node_id);
 
+            // Status before this sync, to detect nodes coming online or going offline
+            let previous_status = CachedNode::find_by_id(self.pool, node_id)
+                .await
+                .ok()
+                .flatten()
+                .map(|cached| cached.status);
+
             // Convert and upsert the node
             let input = self.node_to_input(&node);
             match CachedNode::upsert(self.pool, input).await {
@@ -185,6 +195,9 @@ impl<'a> NodeCacheSyncer<'a> {
                         "successfully cached node"
                     );
                     stats.nodes_synced += 1;
+                    if let Some(previous_status) = previous_status {
+                        self.fire_connectivity_webhook(&cached, previous_status);
+                    }
                 }
                 Err(e) => {
                     tracing::error!(
@@ -240,6 +253,33 @@ impl<'a> NodeCacheSyncer<'a> {
         Ok((0, 0))
     }
 
+    /// Fire node_online/node_offline webhooks when a node's connectivity flipped since the
+    /// last sync. Busy and draining nodes count as online; pending nodes are neither.
+    fn fire_connectivity_webhook(&self, node: &CachedNode, previous_status: CachedNodeStatus) {
+        let is_online = |status: CachedNodeStatus| {
+            matches!(
+                status,
+                CachedNodeStatus::Online | CachedNodeStatus::Busy | CachedNodeStatus::Draining
+            )
+        };
+        let webhook_node = || WebhookNode {
+            id: node.id,
+            name: node.name.clone(),
+            organization_id: node.organization_id,
+            public_url: node.public_url.clone(),
+            status: node.status.to_string(),
+            previous_status: previous_status.to_string(),
+        };
+        let event = if is_online(node.status) && !is_online(previous_status) {
+            WebhookEventPayload::NodeOnline(webhook_node())
+        } else if node.status == CachedNodeStatus::Offline && is_online(previous_status) {
+            WebhookEventPayload::NodeOffline(webhook_node())
+        } else {
+            return;
+        };
+        WebhookService::spawn_node_event(self.pool, event);
+    }
+
     /// Convert a remote Node to a CachedNodeInput
     fn node_to_input(&self, node: &Node) -> CachedNodeInput {
         CachedNodeInput {
@@ -254,6 +294,7 @@ impl<'a> NodeCacheSyncer<'a> {
                 os: node.capabilities.os.clone(),
                 arch: node.capabilities.arch.clone(),
                 version: node.capabilities.version.clone(),
+                labels: node.capabilities.labels.clone(),
             },
             public_url: node.public_url.clone(),
             last_heartbeat_at: node.last_heartbeat_at,
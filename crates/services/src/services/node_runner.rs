@@ -537,6 +537,14 @@ impl NodeRunnerHandle {
                 );
                 // Heal (re-stream + reconcile) happens in run_node_runner where pool+remote_client live.
             }
+            HiveEvent::ApprovalResponse(response) => {
+                tracing::debug!(
+                    approval_id = %response.approval_id,
+                    status = ?response.status,
+                    "approval answered on the hive"
+                );
+                // The answer is applied in run_node_runner where the approvals service lives.
+            }
         }
 
         Some(event)
@@ -677,6 +685,7 @@ fn spawn_hive_connection(config: NodeRunnerConfig) -> NodeRunnerHandle {
     }
 }
 
+use super::approvals::{ApprovalError, Approvals};
 use super::assignment_handler::AssignmentHandler;
 use super::container::ContainerService;
 use super::hive_sync::spawn_hive_sync_service;
@@ -689,6 +698,7 @@ use super::hive_sync::spawn_hive_sync_service;
 /// 2. Processes incoming events (task assignments, cancellations, etc.)
 /// 3. Creates local tasks and attempts for incoming assignments
 /// 4. Syncs remote projects and tasks on connection (if remote_client provided)
+/// 5. Forwards pending approvals to the hive and applies answers given there
 ///
 /// Returns a `NodeRunnerContext` that can be used to interact with the hive.
 ///
@@ -699,11 +709,15 @@ pub fn spawn_node_runner<C: ContainerService + Sync + Send + 'static>(
     db: DBService,
     container: Option<C>,
     remote_client: Option<RemoteClient>,
+    approvals: Approvals,
 ) -> Option<NodeRunnerContext> {
     let mut handle = spawn_hive_connection(config);
     let state = handle.state.clone();
     let command_tx = handle.command_tx.clone();
 
+    // Approvals raised from now on are forwarded so any org member can answer them
+    approvals.relay_to_hive(command_tx.clone());
+
     // Create the context to return
     let context = NodeRunnerContext {
         state: state.clone(),
@@ -859,6 +873,17 @@ pub fn spawn_node_runner<C: ContainerService + Sync + Send + 'static>(
                     {
                         tracing::warn!(error = ?e, "Failed to sync node statuses from hive");
                     }
+
+                    // Re-forward approvals raised while disconnected; the hive upserts them
+                    match approvals.forward_pending_to_hive(&db.pool).await {
+                        Ok(count) if count > 0 => {
+                            tracing::info!(count, "Forwarded pending approvals to hive");
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            tracing::warn!(error = ?e, "Failed to forward pending approvals to hive");
+                        }
+                    }
                 }
                 Some(HiveEvent::TaskAssigned(assignment)) => {
                     tracing::info!(
@@ -1159,6 +1184,37 @@ pub fn spawn_node_runner<C: ContainerService + Sync + Send + 'static>(
                         }
                     }
                 }
+                Some(HiveEvent::ApprovalResponse(response)) => {
+                    let approval_id = response.approval_id.clone();
+                    let answer = utils::approvals::ApprovalResponse {
+                        execution_process_id: response.local_execution_process_id,
+                        status: response.status,
+                        answers: response.answers,
+                    };
+                    match approvals.respond(&db.pool, &approval_id, answer).await {
+                        Ok(_) => {
+                            tracing::info!(
+                                approval_id = %approval_id,
+                                responded_by = ?response.responded_by,
+                                "Applied approval answer from hive"
+                            );
+                        }
+                        // Answered locally or timed out before the hive's answer arrived
+                        Err(ApprovalError::AlreadyCompleted | ApprovalError::NotFound) => {
+                            tracing::debug!(
+                                approval_id = %approval_id,
+                                "Ignoring hive answer for approval that is no longer pending"
+                            );
+                        }
+                        Err(e) => {
+                            tracing::warn!(
+                                error = ?e,
+                                approval_id = %approval_id,
+                                "Failed to apply approval answer from hive"
+                            );
+                        }
+                    }
+                }
                 Some(_) => {
                     // Other events are handled in process_event
                 }
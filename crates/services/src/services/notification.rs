@@ -1,6 +1,9 @@
 use std::sync::OnceLock;
 
-use db::models::execution_process::{ExecutionContext, ExecutionProcessStatus};
+use db::models::{
+    execution_process::{ExecutionContext, ExecutionProcessStatus},
+    usage_budget::BUDGET_EXCEEDED_COMPLETION_REASON,
+};
 use utils;
 
 use crate::services::config::SoundFile;
@@ -15,13 +18,28 @@ static WSL_ROOT_PATH_CACHE: OnceLock<Option<String>> = OnceLock::new();
 
 impl NotificationService {
     pub async fn notify_execution_halted(mut config: NotificationConfig, ctx: &ExecutionContext) {
+        let budget_exceeded = ctx.execution_process.completion_reason.as_deref()
+            == Some(BUDGET_EXCEEDED_COMPLETION_REASON);
+
         // If the process was intentionally killed by user, suppress sound
-        if matches!(ctx.execution_process.status, ExecutionProcessStatus::Killed) {
+        if matches!(ctx.execution_process.status, ExecutionProcessStatus::Killed)
+            && !budget_exceeded
+        {
             config.sound_enabled = false;
         }
 
         let title = format!("Task Complete: {}", ctx.task.title);
         let message = match ctx.execution_process.status {
+            ExecutionProcessStatus::Killed if budget_exceeded => format!(
+                "💸 '{}' execution stopped: {}\nBranch: {:?}\nExecutor: {}",
+                ctx.task.title,
+                ctx.execution_process
+                    .completion_message
+                    .as_deref()
+                    .unwrap_or("usage budget reached"),
+                ctx.task_attempt.branch,
+                ctx.task_attempt.executor
+            ),
             ExecutionProcessStatus::Completed => format!(
                 "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {}",
                 ctx.task.title, ctx.task_attempt.branch, ctx.task_attempt.executor
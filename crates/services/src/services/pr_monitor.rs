@@ -17,6 +17,7 @@ use uuid::Uuid;
 use crate::services::{
     github::{GitHubRepoInfo, GitHubService, GitHubServiceError},
     share::SharePublisher,
+    webhook::{WebhookEventPayload, WebhookService},
 };
 
 #[derive(Debug, Error)]
@@ -162,6 +163,17 @@ impl PrMonitorService {
             &pr.url,
         )
         .await?;
+        if matches!(pr.status, MergeStatus::Open) {
+            WebhookService::spawn_attempt_event(
+                &self.db.pool,
+                attempt_id,
+                WebhookEventPayload::PrOpened {
+                    url: pr.url.clone(),
+                    number: pr.number,
+                    target_branch: attempt.target_branch.clone(),
+                },
+            );
+        }
 
         // If the PR is already merged, update the status immediately
         if matches!(pr.status, MergeStatus::Merged) {
@@ -176,6 +188,17 @@ impl PrMonitorService {
                     pr.merge_commit_sha.clone(),
                 )
                 .await?;
+                WebhookService::spawn_attempt_event(
+                    &self.db.pool,
+                    attempt_id,
+                    WebhookEventPayload::PrMerged {
+                        url: pr.url.clone(),
+                        number: pr.number,
+                        target_branch: attempt.target_branch.clone(),
+                        merge_commit_sha: pr.merge_commit_sha.clone(),
+                        merged_at: pr.merged_at,
+                    },
+                );
 
                 // Also update the task to done if it was merged
                 info!(
@@ -243,10 +266,24 @@ impl PrMonitorService {
                 &self.db.pool,
                 pr_merge.id,
                 pr_status.status.clone(),
-                pr_status.merge_commit_sha,
+                pr_status.merge_commit_sha.clone(),
             )
             .await?;
 
+            if matches!(&pr_status.status, MergeStatus::Merged) {
+                WebhookService::spawn_attempt_event(
+                    &self.db.pool,
+                    pr_merge.task_attempt_id,
+                    WebhookEventPayload::PrMerged {
+                        url: pr_merge.pr_info.url.clone(),
+                        number: pr_merge.pr_info.number,
+                        target_branch: pr_merge.target_branch_name.clone(),
+                        merge_commit_sha: pr_status.merge_commit_sha.clone(),
+                        merged_at: pr_status.merged_at,
+                    },
+                );
+            }
+
             // If the PR was merged, update the task status to done
             if matches!(&pr_status.status, MergeStatus::Merged)
                 && let Some(task_attempt) =
@@ -15,6 +15,9 @@ use remote::{
             ListTaskAttemptsBySharedTaskResponse,
         },
         projects::ListProjectNodesResponse,
+        swarm_approvals::{
+            ListSwarmApprovalsResponse, RespondSwarmApprovalRequest, SwarmApprovalResponse,
+        },
         swarm_labels::{ListSwarmLabelsResponse, MergeLabelsResult, SwarmLabelResponse},
         swarm_projects::{
             ListSwarmProjectNodesResponse, ListSwarmProjectsResponse, SwarmProjectNodeResponse,
@@ -1379,6 +1382,35 @@ impl RemoteClient {
         )
         .await
     }
+
+    // =====================
+    // Swarm Approval APIs
+    // =====================
+
+    /// Lists approval requests forwarded to the hive by the organization's nodes.
+    pub async fn list_swarm_approvals(
+        &self,
+        organization_id: Uuid,
+        include_resolved: bool,
+    ) -> Result<ListSwarmApprovalsResponse, RemoteClientError> {
+        self.get_authed(&format!(
+            "/v1/swarm/approvals?organization_id={organization_id}&include_resolved={include_resolved}"
+        ))
+        .await
+    }
+
+    /// Answers an approval request raised on any node of the organization.
+    pub async fn respond_swarm_approval(
+        &self,
+        approval_id: Uuid,
+        request: &RespondSwarmApprovalRequest,
+    ) -> Result<SwarmApprovalResponse, RemoteClientError> {
+        self.post_authed(
+            &format!("/v1/swarm/approvals/{approval_id}/respond"),
+            Some(request),
+        )
+        .await
+    }
 }
 
 /// Request payload for creating a node API key
@@ -0,0 +1,167 @@
+//! Local secret store for values referenced from executor profile `env` maps.
+//!
+//! Secrets are kept in `secrets.json` in the asset directory (mode 0600 on unix). They are
+//! only resolved into the environment of spawned agent processes: values are never logged,
+//! never persisted in execution actions and never synced to the Hive.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::RwLock;
+use ts_rs::TS;
+
+#[derive(Debug, Error)]
+pub enum SecretStoreError {
+    #[error(
+        "Invalid secret name '{0}': use letters, digits and underscores, not starting with a digit"
+    )]
+    InvalidName(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+}
+
+/// Secret metadata returned by the API. The value itself is never exposed.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SecretInfo {
+    pub name: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredSecrets {
+    #[serde(default)]
+    secrets: HashMap<String, String>,
+}
+
+/// File-backed store of named secrets, loaded into memory on startup.
+pub struct SecretStore {
+    path: PathBuf,
+    inner: RwLock<HashMap<String, String>>,
+}
+
+impl SecretStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            inner: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn load(&self) -> Result<(), SecretStoreError> {
+        if !self.path.exists() {
+            return Ok(());
+        }
+        let bytes = tokio::fs::read(&self.path).await?;
+        let stored: StoredSecrets = serde_json::from_slice(&bytes)?;
+        *self.inner.write().await = stored.secrets;
+        Ok(())
+    }
+
+    /// Names of all stored secrets, sorted
+    pub async fn list(&self) -> Vec<SecretInfo> {
+        let mut names: Vec<String> = self.inner.read().await.keys().cloned().collect();
+        names.sort();
+        names.into_iter().map(|name| SecretInfo { name }).collect()
+    }
+
+    pub async fn set(&self, name: &str, value: String) -> Result<(), SecretStoreError> {
+        if !is_valid_secret_name(name) {
+            return Err(SecretStoreError::InvalidName(name.to_string()));
+        }
+        let mut secrets = self.inner.write().await;
+        let mut updated = secrets.clone();
+        updated.insert(name.to_string(), value);
+        self.persist(&updated)?;
+        *secrets = updated;
+        Ok(())
+    }
+
+    /// Remove a secret, returning whether it existed
+    pub async fn remove(&self, name: &str) -> Result<bool, SecretStoreError> {
+        let mut secrets = self.inner.write().await;
+        if !secrets.contains_key(name) {
+            return Ok(false);
+        }
+        let mut updated = secrets.clone();
+        updated.remove(name);
+        self.persist(&updated)?;
+        *secrets = updated;
+        Ok(true)
+    }
+
+    /// All secrets by name, for resolving `${VAR}` references at spawn time
+    pub async fn values(&self) -> HashMap<String, String> {
+        self.inner.read().await.clone()
+    }
+
+    fn persist(&self, secrets: &HashMap<String, String>) -> Result<(), SecretStoreError> {
+        let tmp = self.path.with_extension("tmp");
+
+        let file = {
+            let mut opts = std::fs::OpenOptions::new();
+            opts.create(true).truncate(true).write(true);
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                opts.mode(0o600);
+            }
+
+            opts.open(&tmp)?
+        };
+
+        serde_json::to_writer_pretty(
+            &file,
+            &StoredSecrets {
+                secrets: secrets.clone(),
+            },
+        )?;
+        file.sync_all()?;
+        drop(file);
+
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+fn is_valid_secret_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_set_persists_and_reloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.json");
+
+        let store = SecretStore::new(path.clone());
+        store
+            .set("GATEWAY_TOKEN", "s3cret".to_string())
+            .await
+            .unwrap();
+
+        let reloaded = SecretStore::new(path);
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.values().await["GATEWAY_TOKEN"], "s3cret");
+        assert_eq!(reloaded.list().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SecretStore::new(dir.path().join("secrets.json"));
+
+        assert!(matches!(
+            store.set("1BAD", "x".to_string()).await,
+            Err(SecretStoreError::InvalidName(_))
+        ));
+        assert!(!store.remove("MISSING").await.unwrap());
+    }
+}
@@ -0,0 +1,146 @@
+//! Task lifecycle webhooks.
+//!
+//! Task status changes come from many places (route handlers, the exit monitor, the PR
+//! monitor, Hive sync), so instead of firing from each of them this watcher follows the task
+//! patches the event service already streams. It remembers each task's status and archived
+//! state and fires `task_created`, `task_status_changed` and `task_archived` when they change.
+
+use std::{collections::HashMap, sync::Arc};
+
+use db::{
+    DBService,
+    models::{
+        project::Project,
+        task::{Task, TaskStatus},
+    },
+};
+use json_patch::PatchOperation;
+use tokio::{sync::broadcast, task::JoinHandle};
+use tracing::{debug, info, warn};
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use crate::services::webhook::{WebhookEventPayload, WebhookService};
+
+#[derive(Debug, Clone, PartialEq)]
+struct TaskState {
+    status: TaskStatus,
+    archived: bool,
+}
+
+pub struct TaskWebhookWatcher {
+    db: DBService,
+    known: HashMap<Uuid, TaskState>,
+}
+
+impl TaskWebhookWatcher {
+    pub async fn spawn(db: DBService, msg_store: Arc<MsgStore>) -> JoinHandle<()> {
+        // Subscribe before seeding so changes made while seeding are not missed
+        let rx = msg_store.get_receiver();
+        let known = match Task::find_lifecycle_states(&db.pool).await {
+            Ok(rows) => rows
+                .into_iter()
+                .map(|row| {
+                    (
+                        row.id,
+                        TaskState {
+                            status: row.status,
+                            archived: row.archived,
+                        },
+                    )
+                })
+                .collect(),
+            Err(e) => {
+                warn!("Failed to load task states for lifecycle webhooks: {}", e);
+                HashMap::new()
+            }
+        };
+        info!(
+            "Starting task lifecycle webhook watcher ({} tasks)",
+            known.len()
+        );
+        let watcher = Self { db, known };
+        tokio::spawn(watcher.run(rx))
+    }
+
+    async fn run(mut self, mut rx: broadcast::Receiver<LogMsg>) {
+        loop {
+            match rx.recv().await {
+                Ok(LogMsg::JsonPatch(patch)) => {
+                    for op in &patch.0 {
+                        self.handle_op(op).await;
+                    }
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(
+                        skipped,
+                        "Task lifecycle webhook watcher lagged; some task events were missed"
+                    );
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+
+    async fn handle_op(&mut self, op: &PatchOperation) {
+        let Some(task_id) = op
+            .path()
+            .strip_prefix("/tasks/")
+            .and_then(|id| Uuid::parse_str(id).ok())
+        else {
+            return;
+        };
+        let (value, created) = match op {
+            PatchOperation::Add(op) => (&op.value, true),
+            PatchOperation::Replace(op) => (&op.value, false),
+            PatchOperation::Remove(_) => {
+                self.known.remove(&task_id);
+                return;
+            }
+            _ => return,
+        };
+        // Task patches carry a TaskWithAttemptStatus, which flattens the task
+        let Ok(task) = serde_json::from_value::<Task>(value.clone()) else {
+            return;
+        };
+        let state = TaskState {
+            status: task.status.clone(),
+            archived: task.archived_at.is_some(),
+        };
+        let previous = self.known.insert(task_id, state.clone());
+
+        let mut events = Vec::new();
+        match previous {
+            None if created => events.push(WebhookEventPayload::TaskCreated),
+            // First sighting of a task that existed before the watcher started tracking it
+            None => return,
+            Some(previous) => {
+                if previous.status != state.status {
+                    events.push(WebhookEventPayload::TaskStatusChanged {
+                        from: format!("{:?}", previous.status),
+                        to: format!("{:?}", state.status),
+                    });
+                }
+                if !previous.archived && state.archived {
+                    events.push(WebhookEventPayload::TaskArchived);
+                }
+            }
+        }
+        if events.is_empty() {
+            return;
+        }
+
+        // Tasks of remote projects belong to other nodes, which fire their own webhooks
+        match Project::find_by_id(&self.db.pool, task.project_id).await {
+            Ok(Some(project)) if !project.is_remote => {}
+            _ => {
+                debug!(task_id = %task_id, "skipping lifecycle webhooks for non-local task");
+                return;
+            }
+        }
+        for event in events {
+            WebhookService::spawn_task_event(&self.db.pool, task_id, event);
+        }
+    }
+}
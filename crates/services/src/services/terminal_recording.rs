@@ -0,0 +1,138 @@
+//! Asciicast v2 recording of terminal sessions.
+//!
+//! A recording is a newline-delimited JSON file: a header object followed by one
+//! `[elapsed_seconds, code, data]` array per event. Output is recorded as `"o"` events and
+//! resizes as `"r"` events with `"COLSxROWS"` data, so the file plays back in asciinema
+//! and compatible players. See <https://docs.asciinema.org/manual/asciicast/v2/>.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Content type of asciicast files
+pub const ASCIICAST_CONTENT_TYPE: &str = "application/x-asciicast";
+
+/// First line of an asciicast v2 file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AsciicastHeader {
+    /// Always 2
+    pub version: u8,
+    pub width: u16,
+    pub height: u16,
+    /// Unix time the recording started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl AsciicastHeader {
+    pub fn new(width: u16, height: u16, title: Option<String>) -> Self {
+        Self {
+            version: 2,
+            width,
+            height,
+            timestamp: Some(chrono::Utc::now().timestamp()),
+            title,
+        }
+    }
+}
+
+/// What a finished recording produced.
+#[derive(Debug, Clone)]
+pub struct RecordingSummary {
+    pub path: PathBuf,
+    pub duration: Duration,
+}
+
+/// Writes events to an asciicast file as they happen.
+///
+/// Every event is flushed, so a recording that is still running can already be replayed up to
+/// its latest output.
+pub struct AsciicastWriter {
+    path: PathBuf,
+    out: BufWriter<File>,
+    started: Instant,
+}
+
+impl AsciicastWriter {
+    /// Create the file, including missing parent directories, and write the header.
+    pub fn create(path: &Path, header: &AsciicastHeader) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut out, header)?;
+        out.write_all(b"\n")?;
+        out.flush()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            out,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn output(&mut self, data: &str) -> io::Result<()> {
+        self.event("o", data)
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) -> io::Result<()> {
+        self.event("r", &format!("{cols}x{rows}"))
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> io::Result<()> {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        serde_json::to_writer(&mut self.out, &(elapsed, code, data))?;
+        self.out.write_all(b"\n")?;
+        self.out.flush()
+    }
+
+    pub fn finish(mut self) -> io::Result<RecordingSummary> {
+        self.out.flush()?;
+        Ok(RecordingSummary {
+            path: self.path,
+            duration: self.started.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_writes_header_and_events() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("attempt").join("session.cast");
+        let mut writer = AsciicastWriter::create(
+            &path,
+            &AsciicastHeader::new(80, 24, Some("feature-branch".to_string())),
+        )
+        .unwrap();
+        writer.output("$ ls\r\n").unwrap();
+        writer.resize(120, 40).unwrap();
+        let summary = writer.finish().unwrap();
+        assert_eq!(summary.path, path);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let mut lines = contents.lines();
+        let header: AsciicastHeader = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!(header.version, 2);
+        assert_eq!((header.width, header.height), (80, 24));
+        assert_eq!(header.title.as_deref(), Some("feature-branch"));
+
+        let output: (f64, String, String) = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!((output.1.as_str(), output.2.as_str()), ("o", "$ ls\r\n"));
+        let resize: (f64, String, String) = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!((resize.1.as_str(), resize.2.as_str()), ("r", "120x40"));
+        assert!(resize.0 >= output.0);
+        assert!(lines.next().is_none());
+    }
+}
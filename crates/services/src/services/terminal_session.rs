@@ -3,6 +3,10 @@
 //! This service manages interactive terminal sessions using tmux (preferred) or
 //! portable-pty as a fallback. Sessions can be created, attached to, and managed
 //! through a WebSocket interface.
+//!
+//! Several viewers can attach to one session. They all see its output, but only one of
+//! them, the writer, may send input or resize it. A session can also be recorded to an
+//! asciicast file while it runs.
 
 use std::{
     collections::HashSet,
@@ -21,10 +25,16 @@ use thiserror::Error;
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
     process::Command,
-    sync::{RwLock, broadcast, mpsc},
+    sync::{RwLock, broadcast, mpsc, oneshot, watch},
 };
 use tracing::{debug, error, info, warn};
 use ts_rs::TS;
+use uuid::Uuid;
+
+use super::terminal_recording::{AsciicastHeader, AsciicastWriter, RecordingSummary};
+
+/// Output kept per session for viewers that attach after it was produced
+const SCROLLBACK_LIMIT: usize = 64 * 1024;
 
 /// Error types for terminal session operations.
 #[derive(Debug, Error)]
@@ -52,6 +62,21 @@ pub enum TerminalError {
 
     #[error("Session closed")]
     SessionClosed,
+
+    #[error("Session is already being recorded: {0}")]
+    AlreadyRecording(String),
+
+    #[error("Session is not being recorded: {0}")]
+    NotRecording(String),
+
+    #[error("Another viewer controls session {0}")]
+    ControlHeld(String),
+
+    #[error("Viewer does not control session {0}")]
+    NotWriter(String),
+
+    #[error("Viewer is not attached to session {0}")]
+    ViewerNotFound(String),
 }
 
 /// Information about a terminal session.
@@ -69,6 +94,29 @@ pub struct SessionInfo {
     pub rows: u16,
     /// Whether the session is still active
     pub active: bool,
+    /// Whether the session is being recorded
+    pub recording: bool,
+    /// Number of attached viewers
+    pub viewers: u32,
+}
+
+/// Viewers attached to a session and which of them may write to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SessionViewers {
+    /// Viewer allowed to send input and resize the terminal; `None` when nobody has control
+    pub writer_id: Option<String>,
+    /// Attached viewers, in the order they attached
+    pub viewer_ids: Vec<String>,
+}
+
+/// A viewer's handle on a session, returned when it attaches.
+pub struct ViewerAttachment {
+    pub viewer_id: String,
+    /// Recent output, to draw the terminal before live output arrives
+    pub scrollback: String,
+    pub output: broadcast::Receiver<TerminalOutput>,
+    pub viewers: watch::Receiver<SessionViewers>,
 }
 
 /// Terminal output event sent to listeners.
@@ -97,6 +145,64 @@ enum SessionBackend {
     },
 }
 
+/// A recording in progress.
+struct ActiveRecording {
+    writer: AsciicastWriter,
+    /// Receives the summary once the recording is stopped or its session ends
+    done_tx: oneshot::Sender<RecordingSummary>,
+}
+
+/// Output history of a session, fed by a task that follows the output channel.
+#[derive(Default)]
+struct SessionHistory {
+    scrollback: String,
+    recording: Option<ActiveRecording>,
+}
+
+impl SessionHistory {
+    fn push_output(&mut self, session_id: &str, data: &str) {
+        self.scrollback.push_str(data);
+        if self.scrollback.len() > SCROLLBACK_LIMIT {
+            let mut cut = self.scrollback.len() - SCROLLBACK_LIMIT;
+            while !self.scrollback.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.scrollback.drain(..cut);
+        }
+
+        let failed = self
+            .recording
+            .as_mut()
+            .is_some_and(|recording| recording.writer.output(data).is_err());
+        if failed {
+            warn!(session_id = %session_id, "Failed to write terminal recording, stopping it");
+            self.finish_recording(session_id);
+        }
+    }
+
+    /// Finish the active recording, if any, and report its summary.
+    fn finish_recording(&mut self, session_id: &str) -> bool {
+        let Some(recording) = self.recording.take() else {
+            return false;
+        };
+        match recording.writer.finish() {
+            Ok(summary) => {
+                info!(
+                    session_id = %session_id,
+                    path = %summary.path.display(),
+                    duration_secs = summary.duration.as_secs_f64(),
+                    "Finished terminal recording"
+                );
+                let _ = recording.done_tx.send(summary);
+            }
+            Err(e) => {
+                warn!(session_id = %session_id, error = ?e, "Failed to finish terminal recording");
+            }
+        }
+        true
+    }
+}
+
 /// A terminal session.
 struct TerminalSession {
     id: String,
@@ -106,9 +212,32 @@ struct TerminalSession {
     backend: SessionBackend,
     /// Broadcast channel for output
     output_tx: broadcast::Sender<TerminalOutput>,
+    history: Arc<std::sync::Mutex<SessionHistory>>,
+    viewers: watch::Sender<SessionViewers>,
 }
 
 impl TerminalSession {
+    fn new(
+        id: String,
+        working_dir: PathBuf,
+        cols: u16,
+        rows: u16,
+        backend: SessionBackend,
+        output_tx: broadcast::Sender<TerminalOutput>,
+        history: Arc<std::sync::Mutex<SessionHistory>>,
+    ) -> Self {
+        Self {
+            id,
+            working_dir,
+            cols,
+            rows,
+            backend,
+            output_tx,
+            history,
+            viewers: watch::Sender::new(SessionViewers::default()),
+        }
+    }
+
     fn info(&self) -> SessionInfo {
         SessionInfo {
             id: self.id.clone(),
@@ -117,10 +246,52 @@ impl TerminalSession {
             cols: self.cols,
             rows: self.rows,
             active: true,
+            recording: self.history.lock().unwrap().recording.is_some(),
+            viewers: self.viewers.borrow().viewer_ids.len() as u32,
+        }
+    }
+
+    fn ensure_attached(&self, viewer_id: &str) -> Result<(), TerminalError> {
+        if self
+            .viewers
+            .borrow()
+            .viewer_ids
+            .iter()
+            .any(|id| id == viewer_id)
+        {
+            Ok(())
+        } else {
+            Err(TerminalError::ViewerNotFound(self.id.clone()))
         }
     }
 }
 
+/// Follow a session's output into its scrollback and active recording.
+///
+/// The receiver is subscribed before the backend starts, so the first prompt is kept too.
+/// When the output channel closes the session has ended, and any recording is finished.
+fn spawn_history_task(
+    session_id: String,
+    mut output_rx: broadcast::Receiver<TerminalOutput>,
+    history: Arc<std::sync::Mutex<SessionHistory>>,
+) {
+    tokio::spawn(async move {
+        loop {
+            match output_rx.recv().await {
+                Ok(output) => history
+                    .lock()
+                    .unwrap()
+                    .push_output(&session_id, &output.data),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(session_id = %session_id, skipped, "Terminal history fell behind output");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+        history.lock().unwrap().finish_recording(&session_id);
+    });
+}
+
 /// Manager for terminal sessions.
 ///
 /// This is the main entry point for creating and managing terminal sessions.
@@ -258,7 +429,8 @@ impl TerminalSessionManager {
         working_dir: &Path,
         session_id: &str,
     ) -> Result<String, TerminalError> {
-        let (output_tx, _) = broadcast::channel(1024);
+        let (output_tx, history_rx) = broadcast::channel(1024);
+        let history = Arc::new(std::sync::Mutex::new(SessionHistory::default()));
         let cols = 80;
         let rows = 24;
 
@@ -270,14 +442,16 @@ impl TerminalSessionManager {
                 .await?
         };
 
-        let session = TerminalSession {
-            id: session_id.to_string(),
-            working_dir: working_dir.to_path_buf(),
+        spawn_history_task(session_id.to_string(), history_rx, Arc::clone(&history));
+        let session = TerminalSession::new(
+            session_id.to_string(),
+            working_dir.to_path_buf(),
             cols,
             rows,
             backend,
             output_tx,
-        };
+            history,
+        );
 
         self.sessions
             .insert(session_id.to_string(), Arc::new(RwLock::new(session)));
@@ -564,6 +738,17 @@ impl TerminalSessionManager {
         let mut session = session.write().await;
         session.cols = cols;
         session.rows = rows;
+        {
+            let mut history = session.history.lock().unwrap();
+            let failed = history
+                .recording
+                .as_mut()
+                .is_some_and(|recording| recording.writer.resize(cols, rows).is_err());
+            if failed {
+                warn!(session_id = %session_id, "Failed to write terminal recording, stopping it");
+                history.finish_recording(session_id);
+            }
+        }
 
         match &session.backend {
             SessionBackend::Tmux { session_name, .. } => {
@@ -625,6 +810,7 @@ impl TerminalSessionManager {
             .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
 
         let session = session.1.write().await;
+        session.history.lock().unwrap().finish_recording(session_id);
 
         match &session.backend {
             SessionBackend::Tmux {
@@ -752,6 +938,213 @@ impl TerminalSessionManager {
         self.use_tmux
     }
 
+    /// Start recording a session's output to an asciicast file at `path`.
+    ///
+    /// The returned receiver gets the recording's summary once it is stopped, or once the
+    /// session ends or is killed.
+    pub async fn start_recording(
+        &self,
+        session_id: &str,
+        path: &Path,
+        title: Option<String>,
+    ) -> Result<oneshot::Receiver<RecordingSummary>, TerminalError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        let session = session.read().await;
+
+        let mut history = session.history.lock().unwrap();
+        if history.recording.is_some() {
+            return Err(TerminalError::AlreadyRecording(session_id.to_string()));
+        }
+        let header = AsciicastHeader::new(session.cols, session.rows, title);
+        let writer = AsciicastWriter::create(path, &header)?;
+        let (done_tx, done_rx) = oneshot::channel();
+        history.recording = Some(ActiveRecording { writer, done_tx });
+
+        info!(session_id = %session_id, path = %path.display(), "Started terminal recording");
+        Ok(done_rx)
+    }
+
+    /// Stop recording a session. Its summary goes to the receiver from `start_recording`.
+    pub async fn stop_recording(&self, session_id: &str) -> Result<(), TerminalError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        let session = session.read().await;
+
+        if session.history.lock().unwrap().finish_recording(session_id) {
+            Ok(())
+        } else {
+            Err(TerminalError::NotRecording(session_id.to_string()))
+        }
+    }
+
+    /// Check if a session is being recorded.
+    pub async fn is_recording(&self, session_id: &str) -> bool {
+        let Some(session) = self.sessions.get(session_id) else {
+            return false;
+        };
+        let session = session.read().await;
+        session.history.lock().unwrap().recording.is_some()
+    }
+
+    /// Attach a new viewer to a session.
+    ///
+    /// The first viewer to attach while nobody has control becomes the writer.
+    pub async fn attach_viewer(&self, session_id: &str) -> Result<ViewerAttachment, TerminalError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        let session = session.read().await;
+
+        let viewer_id = Uuid::new_v4().to_string();
+        let (scrollback, output) = {
+            let history = session.history.lock().unwrap();
+            (history.scrollback.clone(), session.output_tx.subscribe())
+        };
+        session.viewers.send_modify(|viewers| {
+            viewers.viewer_ids.push(viewer_id.clone());
+            if viewers.writer_id.is_none() {
+                viewers.writer_id = Some(viewer_id.clone());
+            }
+        });
+        debug!(session_id = %session_id, viewer_id = %viewer_id, "Viewer attached");
+
+        Ok(ViewerAttachment {
+            viewer_id,
+            scrollback,
+            output,
+            viewers: session.viewers.subscribe(),
+        })
+    }
+
+    /// Detach a viewer. If it was the writer, nobody has control until a viewer asks for it.
+    pub async fn detach_viewer(&self, session_id: &str, viewer_id: &str) {
+        let Some(session) = self.sessions.get(session_id) else {
+            return;
+        };
+        let session = session.read().await;
+        session.viewers.send_modify(|viewers| {
+            viewers.viewer_ids.retain(|id| id != viewer_id);
+            if viewers.writer_id.as_deref() == Some(viewer_id) {
+                viewers.writer_id = None;
+            }
+        });
+        debug!(session_id = %session_id, viewer_id = %viewer_id, "Viewer detached");
+    }
+
+    /// Give a viewer control of a session, if nobody else has it.
+    pub async fn request_control(
+        &self,
+        session_id: &str,
+        viewer_id: &str,
+    ) -> Result<(), TerminalError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        let session = session.read().await;
+        session.ensure_attached(viewer_id)?;
+
+        let mut granted = false;
+        session
+            .viewers
+            .send_if_modified(|viewers| match viewers.writer_id.as_deref() {
+                None => {
+                    viewers.writer_id = Some(viewer_id.to_string());
+                    granted = true;
+                    true
+                }
+                Some(writer_id) => {
+                    granted = writer_id == viewer_id;
+                    false
+                }
+            });
+        if granted {
+            Ok(())
+        } else {
+            Err(TerminalError::ControlHeld(session_id.to_string()))
+        }
+    }
+
+    /// Give up control of a session.
+    pub async fn release_control(
+        &self,
+        session_id: &str,
+        viewer_id: &str,
+    ) -> Result<(), TerminalError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        let session = session.read().await;
+
+        let mut released = false;
+        session.viewers.send_if_modified(|viewers| {
+            released = viewers.writer_id.as_deref() == Some(viewer_id);
+            if released {
+                viewers.writer_id = None;
+            }
+            released
+        });
+        if released {
+            Ok(())
+        } else {
+            Err(TerminalError::NotWriter(session_id.to_string()))
+        }
+    }
+
+    /// Hand control of a session from its writer to another attached viewer.
+    pub async fn grant_control(
+        &self,
+        session_id: &str,
+        viewer_id: &str,
+        to_viewer_id: &str,
+    ) -> Result<(), TerminalError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        let session = session.read().await;
+        session.ensure_attached(to_viewer_id)?;
+
+        let mut granted = false;
+        session.viewers.send_if_modified(|viewers| {
+            granted = viewers.writer_id.as_deref() == Some(viewer_id);
+            if granted {
+                viewers.writer_id = Some(to_viewer_id.to_string());
+            }
+            granted && viewer_id != to_viewer_id
+        });
+        if granted {
+            Ok(())
+        } else {
+            Err(TerminalError::NotWriter(session_id.to_string()))
+        }
+    }
+
+    /// Check that a viewer is the session's writer.
+    pub async fn ensure_writer(
+        &self,
+        session_id: &str,
+        viewer_id: &str,
+    ) -> Result<(), TerminalError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        let session = session.read().await;
+        if session.viewers.borrow().writer_id.as_deref() == Some(viewer_id) {
+            Ok(())
+        } else {
+            Err(TerminalError::NotWriter(session_id.to_string()))
+        }
+    }
+
     /// Attach to an existing tmux session (reconnection support).
     pub async fn attach_session(&self, session_id: &str) -> Result<(), TerminalError> {
         if !self.session_exists(session_id) {
@@ -768,21 +1161,24 @@ impl TerminalSessionManager {
 
                 if check.success() {
                     // Session exists in tmux, recreate our tracking
-                    let (output_tx, _) = broadcast::channel(1024);
+                    let (output_tx, history_rx) = broadcast::channel(1024);
+                    let history = Arc::new(std::sync::Mutex::new(SessionHistory::default()));
+                    spawn_history_task(session_id.to_string(), history_rx, Arc::clone(&history));
                     let reader_handle =
                         self.start_tmux_reader(session_id.to_string(), output_tx.clone());
 
-                    let session = TerminalSession {
-                        id: session_id.to_string(),
-                        working_dir: PathBuf::from("."), // Unknown, but session exists
-                        cols: 80,
-                        rows: 24,
-                        backend: SessionBackend::Tmux {
+                    let session = TerminalSession::new(
+                        session_id.to_string(),
+                        PathBuf::from("."), // Unknown, but session exists
+                        80,
+                        24,
+                        SessionBackend::Tmux {
                             session_name: session_id.to_string(),
                             reader_handle: Some(reader_handle),
                         },
                         output_tx,
-                    };
+                        history,
+                    );
 
                     self.sessions
                         .insert(session_id.to_string(), Arc::new(RwLock::new(session)));
@@ -1027,6 +1423,8 @@ mod tests {
             cols: 80,
             rows: 24,
             active: true,
+            recording: false,
+            viewers: 0,
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -1035,6 +1433,113 @@ mod tests {
         assert!(json.contains("\"cols\":80"));
     }
 
+    /// Insert a session without a live backend, for tests that don't need a PTY.
+    fn insert_detached_session(manager: &TerminalSessionManager, session_id: &str) {
+        let (output_tx, _) = broadcast::channel(16);
+        let session = TerminalSession::new(
+            session_id.to_string(),
+            PathBuf::from("/tmp"),
+            80,
+            24,
+            SessionBackend::Tmux {
+                session_name: session_id.to_string(),
+                reader_handle: None,
+            },
+            output_tx,
+            Arc::new(std::sync::Mutex::new(SessionHistory::default())),
+        );
+        manager
+            .sessions
+            .insert(session_id.to_string(), Arc::new(RwLock::new(session)));
+    }
+
+    #[test]
+    fn test_scrollback_keeps_latest_output() {
+        let mut history = SessionHistory::default();
+        history.push_output("vk-test", &"a".repeat(SCROLLBACK_LIMIT));
+        history.push_output("vk-test", "é-tail");
+        assert!(history.scrollback.len() <= SCROLLBACK_LIMIT);
+        assert!(history.scrollback.ends_with("é-tail"));
+    }
+
+    #[tokio::test]
+    async fn test_single_writer_among_viewers() {
+        let manager = TerminalSessionManager::new();
+        insert_detached_session(&manager, "vk-shared");
+
+        let first = manager.attach_viewer("vk-shared").await.unwrap();
+        let second = manager.attach_viewer("vk-shared").await.unwrap();
+        let (first, second) = (first.viewer_id, second.viewer_id);
+        assert!(manager.ensure_writer("vk-shared", &first).await.is_ok());
+        assert!(matches!(
+            manager.ensure_writer("vk-shared", &second).await,
+            Err(TerminalError::NotWriter(_))
+        ));
+        assert!(matches!(
+            manager.request_control("vk-shared", &second).await,
+            Err(TerminalError::ControlHeld(_))
+        ));
+        assert_eq!(manager.get_session("vk-shared").await.unwrap().viewers, 2);
+
+        manager
+            .grant_control("vk-shared", &first, &second)
+            .await
+            .unwrap();
+        assert!(manager.ensure_writer("vk-shared", &second).await.is_ok());
+        assert!(manager.ensure_writer("vk-shared", &first).await.is_err());
+
+        manager.detach_viewer("vk-shared", &second).await;
+        manager.request_control("vk-shared", &first).await.unwrap();
+        assert!(manager.ensure_writer("vk-shared", &first).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_recording_writes_asciicast_until_stopped() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("recording.cast");
+        let manager = TerminalSessionManager::new();
+        insert_detached_session(&manager, "vk-record");
+
+        let done = manager
+            .start_recording("vk-record", &path, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.start_recording("vk-record", &path, None).await,
+            Err(TerminalError::AlreadyRecording(_))
+        ));
+        assert!(manager.get_session("vk-record").await.unwrap().recording);
+        {
+            let session = manager.sessions.get("vk-record").unwrap();
+            let session = session.read().await;
+            session
+                .history
+                .lock()
+                .unwrap()
+                .push_output("vk-record", "hello\r\n");
+        }
+        // The detached session has no tmux to resize, but the new size is recorded first
+        assert!(matches!(
+            manager.resize_session("vk-record", 100, 30).await,
+            Err(TerminalError::TmuxNotAvailable)
+        ));
+
+        manager.stop_recording("vk-record").await.unwrap();
+        let summary = done.await.unwrap();
+        assert_eq!(summary.path, path);
+        assert!(!manager.is_recording("vk-record").await);
+        assert!(matches!(
+            manager.stop_recording("vk-record").await,
+            Err(TerminalError::NotRecording(_))
+        ));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].contains(r#""o","hello\r\n""#));
+        assert!(lines[2].contains(r#""r","100x30""#));
+    }
+
     #[test]
     fn test_manager_clone() {
         let manager1 = TerminalSessionManager::new();
@@ -0,0 +1,732 @@
+//! Exports a task attempt's conversation as a standalone transcript.
+//!
+//! A [`Transcript`] holds the normalized entries of every execution process still in the
+//! attempt's history (dropped processes are left out), in the order they ran, and optionally the
+//! attempt's final diff. It renders to Markdown, JSON or a single HTML file with inline styles,
+//! so it can be attached to pull requests and post-mortems as is.
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_process_usage::ExecutionProcessUsage,
+};
+use executors::logs::{
+    ActionType, CommandExitStatus, FileChange, NormalizedEntry, NormalizedEntryType,
+    ToolResultValueType, ToolStatus,
+};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use ts_rs::TS;
+use utils::{
+    diff::{Diff, create_unified_diff},
+    log_msg::LogMsg,
+};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    #[default]
+    Markdown,
+    Json,
+    Html,
+}
+
+impl TranscriptFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Json => "application/json",
+            Self::Html => "text/html; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+            Self::Html => "html",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Transcript {
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub task_description: Option<String>,
+    pub project_name: String,
+    /// Base coding agent of the attempt, e.g. `CLAUDE_CODE`
+    pub executor: String,
+    pub branch: String,
+    pub target_branch: String,
+    pub exported_at: DateTime<Utc>,
+    pub processes: Vec<TranscriptProcess>,
+    /// Unified diff of the attempt against its target branch, when requested
+    pub diff: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptProcess {
+    pub id: Uuid,
+    pub run_reason: ExecutionProcessRunReason,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Prompt sent to the coding agent
+    pub prompt: Option<String>,
+    /// Script run by setup, cleanup and dev server processes
+    pub script: Option<String>,
+    pub usage: Option<ExecutionProcessUsage>,
+    pub entries: Vec<NormalizedEntry>,
+}
+
+/// Replay the patches of a normalized log stream into the conversation they build, stopping at
+/// `Finished`. Patches are applied in order, so entries removed or replaced later are gone.
+pub async fn entries_from_stream<S>(mut stream: S) -> Vec<NormalizedEntry>
+where
+    S: Stream<Item = Result<LogMsg, std::io::Error>> + Unpin,
+{
+    let mut conversation = json!({ "entries": [] });
+    while let Some(Ok(msg)) = stream.next().await {
+        match msg {
+            LogMsg::JsonPatch(patch) => {
+                if let Err(e) = json_patch::patch(&mut conversation, &patch.0) {
+                    tracing::debug!("Skipping conversation patch that does not apply: {e}");
+                }
+            }
+            LogMsg::Finished => break,
+            _ => {}
+        }
+    }
+
+    let Value::Array(entries) = conversation["entries"].take() else {
+        return Vec::new();
+    };
+    entries
+        .into_iter()
+        .filter(|entry| entry["type"] == "NORMALIZED_ENTRY")
+        .filter_map(|mut entry| serde_json::from_value(entry["content"].take()).ok())
+        .collect()
+}
+
+/// Concatenate file diffs into one unified diff
+pub fn diff_text(diffs: &[Diff]) -> String {
+    let mut text = String::new();
+    for diff in diffs {
+        let old_path = diff.old_path.as_deref();
+        let path = diff.new_path.as_deref().or(old_path).unwrap_or_default();
+        if let Some(old_path) = old_path.filter(|old| *old != path) {
+            text.push_str(&format!("rename from {old_path}\nrename to {path}\n"));
+        }
+        if diff.content_omitted {
+            let counts = match (diff.additions, diff.deletions) {
+                (Some(additions), Some(deletions)) => format!(" (+{additions} -{deletions})"),
+                _ => String::new(),
+            };
+            text.push_str(&format!(
+                "--- a/{path}\n+++ b/{path}\n# contents omitted{counts}\n"
+            ));
+            continue;
+        }
+        text.push_str(&create_unified_diff(
+            path,
+            diff.old_content.as_deref().unwrap_or_default(),
+            diff.new_content.as_deref().unwrap_or_default(),
+        ));
+    }
+    text
+}
+
+/// A piece of a transcript, independent of the output format
+#[derive(Debug)]
+enum Block {
+    /// Who is speaking, or which tool was called
+    Heading(String),
+    /// Text as the user or agent wrote it, usually Markdown
+    Text(String),
+    /// Plain line of metadata
+    Note(String),
+    /// Labelled literal value such as a file path or URL
+    Field { label: &'static str, value: String },
+    Code {
+        language: &'static str,
+        text: String,
+    },
+}
+
+impl Transcript {
+    pub fn render(&self, format: TranscriptFormat) -> String {
+        match format {
+            TranscriptFormat::Markdown => self.render_markdown(),
+            TranscriptFormat::Json => {
+                serde_json::to_string_pretty(self).expect("transcript serializes to JSON")
+            }
+            TranscriptFormat::Html => self.render_html(),
+        }
+    }
+
+    fn header_fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Project", self.project_name.clone()),
+            ("Attempt", self.task_attempt_id.to_string()),
+            ("Agent", self.executor.clone()),
+            (
+                "Branch",
+                format!("{} → {}", self.branch, self.target_branch),
+            ),
+            ("Exported", self.exported_at.to_rfc3339()),
+        ]
+    }
+
+    fn render_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.task_title);
+        for (label, value) in self.header_fields() {
+            out.push_str(&format!("- **{label}:** {}\n", md_code_span(&value)));
+        }
+        out.push('\n');
+        if let Some(description) = self.task_description.as_deref().filter(|d| !d.is_empty()) {
+            out.push_str(&format!("{}\n\n", description.trim_end()));
+        }
+
+        for process in &self.processes {
+            out.push_str(&format!("## {}\n\n", process.title()));
+            for block in process.blocks() {
+                out.push_str(&block.to_markdown());
+            }
+        }
+
+        if let Some(diff) = &self.diff {
+            out.push_str("## Final diff\n\n");
+            out.push_str(&md_fence("diff", diff));
+        }
+        out
+    }
+
+    fn render_html(&self) -> String {
+        let mut body = format!("<h1>{}</h1>\n<dl>\n", html_escape(&self.task_title));
+        for (label, value) in self.header_fields() {
+            body.push_str(&format!(
+                "<dt>{label}</dt><dd><code>{}</code></dd>\n",
+                html_escape(&value)
+            ));
+        }
+        body.push_str("</dl>\n");
+        if let Some(description) = self.task_description.as_deref().filter(|d| !d.is_empty()) {
+            body.push_str(&format!(
+                "<div class=\"text\">{}</div>\n",
+                html_escape(description.trim_end())
+            ));
+        }
+
+        for process in &self.processes {
+            body.push_str(&format!(
+                "<section>\n<h2>{}</h2>\n",
+                html_escape(&process.title())
+            ));
+            for block in process.blocks() {
+                body.push_str(&block.to_html());
+            }
+            body.push_str("</section>\n");
+        }
+
+        if let Some(diff) = &self.diff {
+            body.push_str("<section>\n<h2>Final diff</h2>\n");
+            body.push_str(&html_diff(diff));
+            body.push_str("</section>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<main>\n{body}</main>\n\
+             </body>\n</html>\n",
+            html_escape(&self.task_title)
+        )
+    }
+}
+
+impl TranscriptProcess {
+    fn title(&self) -> String {
+        let kind = match self.run_reason {
+            ExecutionProcessRunReason::CodingAgent => "Agent run",
+            ExecutionProcessRunReason::SetupScript => "Setup script",
+            ExecutionProcessRunReason::CleanupScript => "Cleanup script",
+            ExecutionProcessRunReason::DevServer => "Dev server",
+            ExecutionProcessRunReason::PipelineStep => "Pipeline step",
+            ExecutionProcessRunReason::CommandRerun => "Command re-run",
+        };
+        let status = match self.status {
+            ExecutionProcessStatus::Running => "running",
+            ExecutionProcessStatus::Completed => "completed",
+            ExecutionProcessStatus::Failed => "failed",
+            ExecutionProcessStatus::Killed => "killed",
+        };
+        format!(
+            "{kind} ({status}), {}",
+            self.started_at.format("%Y-%m-%d %H:%M:%S UTC")
+        )
+    }
+
+    fn blocks(&self) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        if let Some(exit_code) = self.exit_code.filter(|code| *code != 0) {
+            blocks.push(Block::Note(format!("Exited with code {exit_code}")));
+        }
+        if let Some(usage) = &self.usage {
+            blocks.push(Block::Note(usage_note(
+                usage.input_tokens,
+                usage.cached_input_tokens,
+                usage.output_tokens,
+                usage.reasoning_tokens,
+                usage.total_cost_usd,
+            )));
+        }
+        if let Some(script) = &self.script {
+            blocks.push(Block::Code {
+                language: "sh",
+                text: script.clone(),
+            });
+        }
+        // Some agents echo the prompt as their first entry
+        let prompt_logged = self.entries.iter().any(|entry| {
+            matches!(entry.entry_type, NormalizedEntryType::UserMessage)
+                && Some(entry.content.trim()) == self.prompt.as_deref().map(str::trim)
+        });
+        if let Some(prompt) = self.prompt.as_ref().filter(|_| !prompt_logged) {
+            blocks.push(Block::Heading("User".to_string()));
+            blocks.push(Block::Text(prompt.clone()));
+        }
+        blocks.extend(self.entries.iter().flat_map(entry_blocks));
+        blocks
+    }
+}
+
+fn entry_blocks(entry: &NormalizedEntry) -> Vec<Block> {
+    let heading = |title: &str| Block::Heading(title.to_string());
+    let text = || Block::Text(entry.content.clone());
+    match &entry.entry_type {
+        NormalizedEntryType::UserMessage => vec![heading("User"), text()],
+        NormalizedEntryType::UserFeedback { denied_tool } => {
+            vec![heading(&format!("User feedback on {denied_tool}")), text()]
+        }
+        NormalizedEntryType::AssistantMessage => vec![heading("Assistant"), text()],
+        NormalizedEntryType::Thinking => vec![heading("Thinking"), text()],
+        NormalizedEntryType::SystemMessage => vec![Block::Note(entry.content.clone())],
+        NormalizedEntryType::ErrorMessage { .. } => vec![
+            heading("Error"),
+            Block::Code {
+                language: "text",
+                text: entry.content.clone(),
+            },
+        ],
+        NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+            status,
+        } => tool_blocks(tool_name, action_type, status),
+        NormalizedEntryType::ResultMessage {
+            subtype,
+            duration_ms,
+            num_turns,
+            total_cost_usd,
+            ..
+        } => {
+            let mut note = format!(
+                "Result: {subtype}, {num_turns} turns in {:.1}s",
+                *duration_ms as f64 / 1000.0
+            );
+            if let Some(cost) = total_cost_usd {
+                note.push_str(&format!(", ${cost:.4}"));
+            }
+            vec![Block::Note(note)]
+        }
+        NormalizedEntryType::TokenUsage {
+            input_tokens,
+            cached_input_tokens,
+            output_tokens,
+            reasoning_tokens,
+            ..
+        } => vec![Block::Note(usage_note(
+            *input_tokens,
+            *cached_input_tokens,
+            *output_tokens,
+            *reasoning_tokens,
+            None,
+        ))],
+        NormalizedEntryType::Loading
+        | NormalizedEntryType::NextAction { .. }
+        | NormalizedEntryType::ExecutionStart { .. }
+        | NormalizedEntryType::ExecutionEnd { .. } => Vec::new(),
+    }
+}
+
+fn tool_blocks(tool_name: &str, action: &ActionType, status: &ToolStatus) -> Vec<Block> {
+    let status_label = match status {
+        ToolStatus::Created => "started",
+        ToolStatus::Success => "succeeded",
+        ToolStatus::Failed => "failed",
+        ToolStatus::Denied { .. } => "denied",
+        ToolStatus::PendingApproval { .. } => "awaiting approval",
+        ToolStatus::TimedOut { .. } => "approval timed out",
+        ToolStatus::PendingQuestion { .. } => "awaiting answer",
+        ToolStatus::Answered { .. } => "answered",
+    };
+    let mut blocks = vec![Block::Heading(format!(
+        "Tool: {tool_name} ({status_label})"
+    ))];
+    let field = |label, value: &str| Block::Field {
+        label,
+        value: value.to_string(),
+    };
+
+    match action {
+        ActionType::FileRead { path } => blocks.push(field("Read", path)),
+        ActionType::FileEdit { path, changes } => {
+            for change in changes {
+                match change {
+                    FileChange::Write { content } => {
+                        blocks.push(field("Wrote", path));
+                        blocks.push(Block::Code {
+                            language: "",
+                            text: content.clone(),
+                        });
+                    }
+                    FileChange::Delete => blocks.push(field("Deleted", path)),
+                    FileChange::Rename { new_path } => {
+                        blocks.push(field("Renamed", path));
+                        blocks.push(field("To", new_path));
+                    }
+                    FileChange::Edit { unified_diff, .. } => {
+                        blocks.push(field("Edited", path));
+                        blocks.push(Block::Code {
+                            language: "diff",
+                            text: unified_diff.clone(),
+                        });
+                    }
+                }
+            }
+            if changes.is_empty() {
+                blocks.push(field("Edited", path));
+            }
+        }
+        ActionType::CommandRun { command, result } => {
+            blocks.push(Block::Code {
+                language: "sh",
+                text: command.clone(),
+            });
+            if let Some(result) = result {
+                if let Some(output) = result.output.as_deref().filter(|o| !o.is_empty()) {
+                    blocks.push(Block::Code {
+                        language: "text",
+                        text: output.to_string(),
+                    });
+                }
+                match result.exit_status {
+                    Some(CommandExitStatus::ExitCode { code }) if code != 0 => {
+                        blocks.push(Block::Note(format!("Exited with code {code}")))
+                    }
+                    Some(CommandExitStatus::Success { success: false }) => {
+                        blocks.push(Block::Note("Command failed".to_string()))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        ActionType::Search { query } => blocks.push(field("Searched for", query)),
+        ActionType::WebFetch { url } => blocks.push(field("Fetched", url)),
+        ActionType::Tool {
+            arguments, result, ..
+        } => {
+            if let Some(arguments) = arguments {
+                blocks.push(json_block(arguments));
+            }
+            if let Some(result) = result {
+                blocks.push(match (&result.r#type, &result.value) {
+                    (ToolResultValueType::Markdown, Value::String(markdown)) => {
+                        Block::Text(markdown.clone())
+                    }
+                    (_, value) => json_block(value),
+                });
+            }
+        }
+        ActionType::TaskCreate { description } | ActionType::Other { description } => {
+            blocks.push(Block::Text(description.clone()))
+        }
+        ActionType::PlanPresentation { plan } => blocks.push(Block::Text(plan.clone())),
+        ActionType::TodoManagement { todos, .. } => {
+            let list = todos
+                .iter()
+                .map(|todo| {
+                    let mark = if todo.status == "completed" { "x" } else { " " };
+                    format!("- [{mark}] {}", todo.content)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            blocks.push(Block::Text(list));
+        }
+    }
+
+    if let ToolStatus::Denied {
+        reason: Some(reason),
+        ..
+    } = status
+    {
+        blocks.push(Block::Note(format!("Denied: {reason}")));
+    }
+    blocks
+}
+
+fn json_block(value: &Value) -> Block {
+    Block::Code {
+        language: "json",
+        text: serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string()),
+    }
+}
+
+fn usage_note(
+    input_tokens: i64,
+    cached_input_tokens: i64,
+    output_tokens: i64,
+    reasoning_tokens: i64,
+    total_cost_usd: Option<f64>,
+) -> String {
+    let mut note = format!(
+        "Tokens: {input_tokens} input ({cached_input_tokens} cached), {output_tokens} output \
+         ({reasoning_tokens} reasoning)"
+    );
+    if let Some(cost) = total_cost_usd {
+        note.push_str(&format!(", ${cost:.4}"));
+    }
+    note
+}
+
+impl Block {
+    fn to_markdown(&self) -> String {
+        match self {
+            Block::Heading(title) => format!("### {title}\n\n"),
+            Block::Text(text) | Block::Note(text) => format!("{}\n\n", text.trim_end()),
+            Block::Field { label, value } => format!("**{label}:** {}\n\n", md_code_span(value)),
+            Block::Code { language, text } => md_fence(language, text),
+        }
+    }
+
+    fn to_html(&self) -> String {
+        match self {
+            Block::Heading(title) => format!("<h3>{}</h3>\n", html_escape(title)),
+            Block::Text(text) => format!(
+                "<div class=\"text\">{}</div>\n",
+                html_escape(text.trim_end())
+            ),
+            Block::Note(text) => format!("<p class=\"note\">{}</p>\n", html_escape(text)),
+            Block::Field { label, value } => format!(
+                "<p><strong>{label}:</strong> <code>{}</code></p>\n",
+                html_escape(value)
+            ),
+            Block::Code {
+                language: "diff",
+                text,
+            } => html_diff(text),
+            Block::Code { text, .. } => {
+                format!("<pre><code>{}</code></pre>\n", html_escape(text.trim_end()))
+            }
+        }
+    }
+}
+
+/// Longest run of backticks in `text`, to pick a fence or code span delimiter it can't close
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+fn md_fence(language: &str, text: &str) -> String {
+    let fence = "`".repeat((longest_backtick_run(text) + 1).max(3));
+    format!(
+        "{fence}{language}\n{}\n{fence}\n\n",
+        text.trim_end_matches('\n')
+    )
+}
+
+fn md_code_span(value: &str) -> String {
+    let ticks = "`".repeat(longest_backtick_run(value) + 1);
+    if value.starts_with('`') || value.ends_with('`') {
+        format!("{ticks} {value} {ticks}")
+    } else {
+        format!("{ticks}{value}{ticks}")
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A unified diff with added, removed and hunk header lines coloured
+fn html_diff(diff: &str) -> String {
+    let lines: Vec<String> = diff
+        .trim_end_matches('\n')
+        .lines()
+        .map(|line| {
+            let class = if line.starts_with("+++") || line.starts_with("---") {
+                "file"
+            } else if line.starts_with('+') {
+                "add"
+            } else if line.starts_with('-') {
+                "del"
+            } else if line.starts_with("@@") {
+                "hunk"
+            } else {
+                return html_escape(line);
+            };
+            format!("<span class=\"{class}\">{}</span>", html_escape(line))
+        })
+        .collect();
+    format!(
+        "<pre class=\"diff\"><code>{}</code></pre>\n",
+        lines.join("\n")
+    )
+}
+
+const HTML_STYLE: &str = "\
+body{margin:0;background:#fafafa;color:#1f2328;\
+font:15px/1.5 -apple-system,BlinkMacSystemFont,'Segoe UI',Helvetica,Arial,sans-serif}\
+main{max-width:960px;margin:0 auto;padding:32px 24px}\
+h1{font-size:26px;margin:0 0 16px}\
+h2{font-size:19px;margin:40px 0 12px;padding-bottom:6px;border-bottom:1px solid #d0d7de}\
+h3{font-size:15px;margin:20px 0 6px;color:#57606a}\
+dl{display:grid;grid-template-columns:max-content 1fr;gap:4px 16px;margin:0 0 16px}\
+dt{font-weight:600}dd{margin:0}\
+.text{white-space:pre-wrap;overflow-wrap:anywhere}\
+.note{color:#57606a;font-size:13px}\
+code,pre{font:13px/1.45 ui-monospace,SFMono-Regular,Menlo,Consolas,monospace}\
+pre{background:#f0f2f4;border-radius:6px;padding:12px;overflow-x:auto}\
+.diff .add{color:#116329;background:#dafbe1}\
+.diff .del{color:#82071e;background:#ffebe9}\
+.diff .hunk{color:#0550ae}\
+.diff .file{font-weight:600}";
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{CommandRunResult, utils::patch::ConversationPatch};
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn transcript(entries: Vec<NormalizedEntry>) -> Transcript {
+        Transcript {
+            task_attempt_id: Uuid::nil(),
+            task_id: Uuid::nil(),
+            task_title: "Fix <login> bug".to_string(),
+            task_description: None,
+            project_name: "web".to_string(),
+            executor: "CLAUDE_CODE".to_string(),
+            branch: "vk/fix-login".to_string(),
+            target_branch: "main".to_string(),
+            exported_at: Utc::now(),
+            processes: vec![TranscriptProcess {
+                id: Uuid::nil(),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+                status: ExecutionProcessStatus::Completed,
+                exit_code: Some(0),
+                started_at: Utc::now(),
+                completed_at: None,
+                prompt: Some("Fix the login bug".to_string()),
+                script: None,
+                usage: None,
+                entries,
+            }],
+            diff: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entries_from_stream_applies_removals() {
+        let patches = vec![
+            ConversationPatch::add_normalized_entry(
+                0,
+                entry(NormalizedEntryType::AssistantMessage, "Draft"),
+            ),
+            ConversationPatch::remove(0),
+            ConversationPatch::add_normalized_entry(
+                0,
+                entry(NormalizedEntryType::UserMessage, "Fix the login bug"),
+            ),
+            ConversationPatch::add_normalized_entry(
+                1,
+                entry(NormalizedEntryType::AssistantMessage, "Working"),
+            ),
+            ConversationPatch::replace(1, entry(NormalizedEntryType::AssistantMessage, "Done")),
+        ];
+        let stream = futures::stream::iter(
+            patches
+                .into_iter()
+                .map(LogMsg::JsonPatch)
+                .chain([LogMsg::Finished])
+                .map(Ok),
+        );
+
+        let entries = entries_from_stream(stream).await;
+        let contents: Vec<_> = entries.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, ["Fix the login bug", "Done"]);
+    }
+
+    #[test]
+    fn test_markdown_renders_tool_calls_and_skips_echoed_prompt() {
+        let command = NormalizedEntryType::ToolUse {
+            tool_name: "Bash".to_string(),
+            action_type: ActionType::CommandRun {
+                command: "npm test".to_string(),
+                result: Some(CommandRunResult {
+                    exit_status: Some(CommandExitStatus::ExitCode { code: 1 }),
+                    output: Some("```\n1 failing".to_string()),
+                }),
+            },
+            status: ToolStatus::Failed,
+        };
+        let markdown = transcript(vec![
+            entry(NormalizedEntryType::UserMessage, "Fix the login bug"),
+            entry(command, "npm test"),
+        ])
+        .render(TranscriptFormat::Markdown);
+
+        assert_eq!(markdown.matches("Fix the login bug").count(), 1);
+        assert!(markdown.contains("### Tool: Bash (failed)"));
+        assert!(markdown.contains("```sh\nnpm test\n```"));
+        assert!(markdown.contains("````text\n```\n1 failing\n````"));
+        assert!(markdown.contains("Exited with code 1"));
+    }
+
+    #[test]
+    fn test_html_escapes_content() {
+        let html = transcript(vec![entry(
+            NormalizedEntryType::AssistantMessage,
+            "<script>alert(1)</script>",
+        )])
+        .render(TranscriptFormat::Html);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Fix &lt;login&gt; bug</title>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!html.contains("<script>"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Persists the token usage and cost coding agents report in their normalized log stream.
+//!
+//! Agents emit [`NormalizedEntryType::TokenUsage`] snapshots as they go and, for some agents, a
+//! [`NormalizedEntryType::ResultMessage`] with the run's total cost. [`UsageRecorder`] watches
+//! the patches of one execution process and writes the process's usage so far to
+//! [`ExecutionProcessUsage`].
+
+use db::models::execution_process_usage::{ExecutionProcessUsage, TokenCounts};
+use executors::{
+    executors::BaseCodingAgent,
+    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+};
+use json_patch::Patch;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Usage reported by a single normalized log patch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UsageUpdate {
+    Tokens(TokenCounts),
+    Cost(f64),
+}
+
+impl UsageUpdate {
+    pub fn from_patch(patch: &Patch) -> Option<Self> {
+        let (_, entry) = extract_normalized_entry_from_patch(patch)?;
+        Self::from_entry_type(&entry.entry_type)
+    }
+
+    fn from_entry_type(entry_type: &NormalizedEntryType) -> Option<Self> {
+        match *entry_type {
+            NormalizedEntryType::TokenUsage {
+                input_tokens,
+                cached_input_tokens,
+                output_tokens,
+                reasoning_tokens,
+                ..
+            } => Some(Self::Tokens(TokenCounts {
+                input_tokens,
+                cached_input_tokens,
+                output_tokens,
+                reasoning_tokens,
+            })),
+            NormalizedEntryType::ResultMessage {
+                total_cost_usd: Some(cost),
+                ..
+            } => Some(Self::Cost(cost)),
+            _ => None,
+        }
+    }
+}
+
+/// Whether an agent's token snapshots cover a single message rather than the run so far.
+///
+/// Claude Code (and Amp, which shares its log format) reports each message's usage as it
+/// streams, then the turn's total right after the turn's result message.
+fn reports_usage_per_message(executor: BaseCodingAgent) -> bool {
+    matches!(executor, BaseCodingAgent::ClaudeCode | BaseCodingAgent::Amp)
+}
+
+/// Sums per-message token snapshots into usage for the run so far
+#[derive(Debug, Default)]
+struct MessageUsage {
+    /// Totals of the turns that have reported a result
+    finished_turns: TokenCounts,
+    /// Messages of the current turn
+    turn: TokenCounts,
+    /// The next snapshot is the total of the turn that just reported its result
+    turn_total_next: bool,
+}
+
+impl MessageUsage {
+    fn result_reported(&mut self) {
+        self.turn_total_next = true;
+    }
+
+    fn add(&mut self, reported: TokenCounts) -> TokenCounts {
+        if std::mem::take(&mut self.turn_total_next) {
+            // The turn total replaces the messages it covers
+            self.finished_turns += reported;
+            self.turn = TokenCounts::default();
+        } else {
+            self.turn += reported;
+        }
+        self.finished_turns + self.turn
+    }
+}
+
+/// Records usage for one execution process, skipping snapshots that repeat the last one written.
+pub struct UsageRecorder {
+    execution_process_id: Uuid,
+    /// Set for agents that report usage per message
+    message_usage: Option<MessageUsage>,
+    last_tokens: Option<TokenCounts>,
+    last_cost: Option<f64>,
+}
+
+impl UsageRecorder {
+    /// `executor` is the agent running the process, deciding how its snapshots add up.
+    pub fn new(execution_process_id: Uuid, executor: Option<BaseCodingAgent>) -> Self {
+        Self {
+            execution_process_id,
+            message_usage: executor
+                .filter(|executor| reports_usage_per_message(*executor))
+                .map(|_| MessageUsage::default()),
+            last_tokens: None,
+            last_cost: None,
+        }
+    }
+
+    /// Persist any usage carried by `patch`, returning the update that was written.
+    ///
+    /// Token updates carry the process's usage so far, not just what the patch reported.
+    pub async fn observe(
+        &mut self,
+        pool: &SqlitePool,
+        patch: &Patch,
+    ) -> Result<Option<UsageUpdate>, sqlx::Error> {
+        let Some((_, entry)) = extract_normalized_entry_from_patch(patch) else {
+            return Ok(None);
+        };
+        if let Some(message_usage) = self.message_usage.as_mut()
+            && matches!(entry.entry_type, NormalizedEntryType::ResultMessage { .. })
+        {
+            message_usage.result_reported();
+        }
+        let Some(update) = UsageUpdate::from_entry_type(&entry.entry_type) else {
+            return Ok(None);
+        };
+        let update = match update {
+            UsageUpdate::Tokens(reported) => {
+                let tokens = match self.message_usage.as_mut() {
+                    Some(message_usage) => message_usage.add(reported),
+                    None => reported,
+                };
+                if self.last_tokens == Some(tokens) {
+                    return Ok(None);
+                }
+                ExecutionProcessUsage::record_tokens(pool, self.execution_process_id, tokens)
+                    .await?;
+                self.last_tokens = Some(tokens);
+                UsageUpdate::Tokens(tokens)
+            }
+            UsageUpdate::Cost(cost) => {
+                if self.last_cost == Some(cost) {
+                    return Ok(None);
+                }
+                ExecutionProcessUsage::record_cost(pool, self.execution_process_id, cost).await?;
+                self.last_cost = Some(cost);
+                update
+            }
+        };
+        Ok(Some(update))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(input_tokens: i64, output_tokens: i64) -> TokenCounts {
+        TokenCounts {
+            input_tokens,
+            output_tokens,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_message_usage_sums_messages_until_turn_total() {
+        let mut usage = MessageUsage::default();
+        assert_eq!(usage.add(tokens(100, 10)), tokens(100, 10));
+        assert_eq!(usage.add(tokens(150, 20)), tokens(250, 30));
+
+        // The turn total replaces the messages it covers
+        usage.result_reported();
+        assert_eq!(usage.add(tokens(260, 35)), tokens(260, 35));
+
+        // A second turn in the same process adds to the first
+        assert_eq!(usage.add(tokens(40, 5)), tokens(300, 40));
+        usage.result_reported();
+        assert_eq!(usage.add(tokens(45, 6)), tokens(305, 41));
+    }
+
+    #[test]
+    fn test_only_claude_format_agents_report_per_message() {
+        assert!(reports_usage_per_message(BaseCodingAgent::ClaudeCode));
+        assert!(reports_usage_per_message(BaseCodingAgent::Amp));
+        assert!(!reports_usage_per_message(BaseCodingAgent::Codex));
+    }
+}
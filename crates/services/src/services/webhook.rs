@@ -2,14 +2,19 @@ use std::{collections::HashMap, net::IpAddr, sync::Arc, time::Duration};
 
 use chrono::{DateTime, Utc};
 use db::models::{
-    execution_process::ExecutionProcess,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     label::Label,
     merge::Merge,
     project::Project,
+    task::Task,
+    task_attempt::TaskAttempt,
     webhook::{Webhook, WebhookEventType},
+    webhook_delivery::{
+        CreateWebhookDelivery, DeliveryAttempt, WebhookDelivery, WebhookDeliveryStatus,
+    },
 };
 use sqlx::SqlitePool;
-use tokio::sync::Semaphore;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tracing::warn;
 use utils::approvals::Question;
 use uuid::Uuid;
@@ -18,6 +23,12 @@ use uuid::Uuid;
 static DISPATCH_SEM: std::sync::LazyLock<Arc<Semaphore>> =
     std::sync::LazyLock::new(|| Arc::new(Semaphore::new(20)));
 
+/// Attempts per delivery before it is marked failed
+const MAX_DELIVERY_ATTEMPTS: u32 = 4;
+
+/// Delay before the first retry, doubled for each retry after it
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
 /// Hop-by-hop and virtual-host headers that must not be forwarded.
 const RESERVED_HEADERS: &[&str] = &[
     "host",
@@ -32,27 +43,49 @@ const RESERVED_HEADERS: &[&str] = &[
     "upgrade",
 ];
 
+/// Everything a webhook payload can describe. Sections are unset when the event isn't about
+/// them: task events have no attempt or process, and node events only reach global webhooks.
 #[derive(Clone)]
 pub struct WebhookContext {
-    pub project_id: Uuid,
-    pub project_name: String,
-    pub project_git_repo_path: String,
-    pub project_github_owner: Option<String>,
-    pub project_github_repo: Option<String>,
-    pub task_id: Uuid,
-    pub task_title: String,
-    pub task_description: Option<String>,
-    pub task_status: String,
-    pub task_labels: Vec<String>,
-    pub task_attempt_id: Uuid,
-    pub task_attempt_executor: String,
-    pub task_attempt_branch: String,
-    pub task_attempt_worktree_path: Option<String>,
-    pub execution_process_id: Uuid,
-    pub execution_process_run_reason: String,
+    pub project: Option<WebhookProject>,
+    pub task: Option<WebhookTask>,
+    pub task_attempt: Option<WebhookTaskAttempt>,
+    pub execution_process: Option<WebhookExecutionProcess>,
     pub event: WebhookEventPayload,
 }
 
+#[derive(Clone)]
+pub struct WebhookProject {
+    pub id: Uuid,
+    pub name: String,
+    pub git_repo_path: String,
+    pub github_owner: Option<String>,
+    pub github_repo: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct WebhookTask {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+    pub labels: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct WebhookTaskAttempt {
+    pub id: Uuid,
+    pub executor: String,
+    pub branch: String,
+    pub worktree_path: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct WebhookExecutionProcess {
+    pub id: Uuid,
+    pub run_reason: String,
+}
+
 #[derive(Clone)]
 pub enum WebhookEventPayload {
     ApprovalRequest {
@@ -76,6 +109,62 @@ pub enum WebhookEventPayload {
         pr_url: Option<String>,
         pr_number: Option<i64>,
     },
+    BudgetExceeded {
+        /// "project" or "task"
+        scope: String,
+        /// "tokens" or "cost_usd"
+        limit: String,
+        max_tokens: Option<i64>,
+        max_cost_usd: Option<f64>,
+        period: Option<String>,
+        spent_tokens: i64,
+        spent_cost_usd: f64,
+        message: String,
+    },
+    TaskCreated,
+    TaskStatusChanged {
+        from: String,
+        to: String,
+    },
+    TaskArchived,
+    AttemptCreated,
+    SetupScriptFailed {
+        exit_code: Option<i64>,
+        message: Option<String>,
+    },
+    MergeCompleted {
+        merge_commit: String,
+        target_branch: String,
+    },
+    PrOpened {
+        url: String,
+        number: i64,
+        target_branch: String,
+    },
+    PrMerged {
+        url: String,
+        number: i64,
+        target_branch: String,
+        merge_commit_sha: Option<String>,
+        merged_at: Option<DateTime<Utc>>,
+    },
+    RebaseConflict {
+        onto_branch: String,
+        conflicted_files: Vec<String>,
+        message: String,
+    },
+    NodeOnline(WebhookNode),
+    NodeOffline(WebhookNode),
+}
+
+#[derive(Clone)]
+pub struct WebhookNode {
+    pub id: Uuid,
+    pub name: String,
+    pub organization_id: Uuid,
+    pub public_url: Option<String>,
+    pub status: String,
+    pub previous_status: String,
 }
 
 impl WebhookEventPayload {
@@ -84,15 +173,119 @@ impl WebhookEventPayload {
             Self::ApprovalRequest { .. } => WebhookEventType::ApprovalRequest,
             Self::PendingQuestion { .. } => WebhookEventType::PendingQuestion,
             Self::ExecutorFinish { .. } => WebhookEventType::ExecutorFinish,
+            Self::BudgetExceeded { .. } => WebhookEventType::BudgetExceeded,
+            Self::TaskCreated => WebhookEventType::TaskCreated,
+            Self::TaskStatusChanged { .. } => WebhookEventType::TaskStatusChanged,
+            Self::TaskArchived => WebhookEventType::TaskArchived,
+            Self::AttemptCreated => WebhookEventType::AttemptCreated,
+            Self::SetupScriptFailed { .. } => WebhookEventType::SetupScriptFailed,
+            Self::MergeCompleted { .. } => WebhookEventType::MergeCompleted,
+            Self::PrOpened { .. } => WebhookEventType::PrOpened,
+            Self::PrMerged { .. } => WebhookEventType::PrMerged,
+            Self::RebaseConflict { .. } => WebhookEventType::RebaseConflict,
+            Self::NodeOnline(_) => WebhookEventType::NodeOnline,
+            Self::NodeOffline(_) => WebhookEventType::NodeOffline,
         }
     }
+
+    /// The `setup_script_failed` event for a finished process, when it is a failed setup script.
+    /// Other scripts, such as command re-runs, never fire it.
+    pub fn setup_script_failed(process: &ExecutionProcess, exit_code: Option<i64>) -> Option<Self> {
+        (process.run_reason == ExecutionProcessRunReason::SetupScript
+            && process.status == ExecutionProcessStatus::Failed)
+            .then(|| Self::SetupScriptFailed {
+                exit_code,
+                message: process.completion_message.clone(),
+            })
+    }
+
+    /// Event-specific payload section for lifecycle events, keyed the same in the default JSON
+    /// payload and in template variables (`{{section.key}}`). Task, archive and attempt events
+    /// carry no section of their own; everything they describe is in the context.
+    fn lifecycle_section(&self) -> Option<(&'static str, serde_json::Value)> {
+        let section = match self {
+            Self::TaskStatusChanged { from, to } => (
+                "status_change",
+                serde_json::json!({ "from": from, "to": to }),
+            ),
+            Self::SetupScriptFailed { exit_code, message } => (
+                "setup_script",
+                serde_json::json!({ "exit_code": exit_code, "message": message }),
+            ),
+            Self::MergeCompleted {
+                merge_commit,
+                target_branch,
+            } => (
+                "merge",
+                serde_json::json!({ "commit": merge_commit, "target_branch": target_branch }),
+            ),
+            Self::PrOpened {
+                url,
+                number,
+                target_branch,
+            } => (
+                "pr",
+                serde_json::json!({
+                    "url": url,
+                    "number": number,
+                    "target_branch": target_branch,
+                    "merge_commit_sha": null,
+                    "merged_at": null,
+                }),
+            ),
+            Self::PrMerged {
+                url,
+                number,
+                target_branch,
+                merge_commit_sha,
+                merged_at,
+            } => (
+                "pr",
+                serde_json::json!({
+                    "url": url,
+                    "number": number,
+                    "target_branch": target_branch,
+                    "merge_commit_sha": merge_commit_sha,
+                    "merged_at": merged_at,
+                }),
+            ),
+            Self::RebaseConflict {
+                onto_branch,
+                conflicted_files,
+                message,
+            } => (
+                "rebase",
+                serde_json::json!({
+                    "onto_branch": onto_branch,
+                    "conflicted_files": conflicted_files,
+                    "message": message,
+                }),
+            ),
+            Self::NodeOnline(node) | Self::NodeOffline(node) => (
+                "node",
+                serde_json::json!({
+                    "id": node.id.to_string(),
+                    "name": node.name,
+                    "organization_id": node.organization_id.to_string(),
+                    "public_url": node.public_url,
+                    "status": node.status,
+                    "previous_status": node.previous_status,
+                }),
+            ),
+            _ => return None,
+        };
+        Some(section)
+    }
 }
 
 pub struct WebhookService;
 
 impl WebhookService {
-    pub async fn fire(pool: &SqlitePool, project_id: Uuid, ctx: WebhookContext) {
+    /// Deliver an event to every applicable webhook. Events without a project only reach global
+    /// webhooks.
+    pub async fn fire(pool: &SqlitePool, ctx: WebhookContext) {
         let event_type = ctx.event.event_type();
+        let project_id = ctx.project.as_ref().map(|p| p.id);
         let webhooks = match Webhook::find_applicable(pool, project_id, &event_type).await {
             Ok(w) => w,
             Err(e) => {
@@ -101,57 +294,199 @@ impl WebhookService {
             }
         };
         for webhook in webhooks {
-            let ctx2 = ctx.clone();
-            let sem = Arc::clone(&DISPATCH_SEM);
-            // Step 4 (H5): acquire semaphore BEFORE spawning to bound concurrent dispatches.
-            match sem.try_acquire_owned() {
-                Ok(permit) => {
-                    tokio::spawn(async move {
-                        Self::dispatch(&webhook, &ctx2).await;
-                        drop(permit);
-                    });
+            let payload = Self::build_payload(&webhook, &ctx, Utc::now());
+            let body = match serde_json::to_string(&payload) {
+                Ok(b) => b,
+                Err(e) => {
+                    warn!(webhook_id = %webhook.id, error = ?e, "Failed to serialize webhook payload");
+                    continue;
                 }
-                Err(_) => {
-                    warn!(webhook_id = %webhook.id, "Dispatch semaphore full, dropping webhook delivery");
+            };
+            if let Err(e) = Self::enqueue(pool, webhook, event_type.clone(), body, None).await {
+                warn!(error = ?e, "Failed to record webhook delivery");
+            }
+        }
+    }
+
+    /// Send a previous delivery's body to its webhook again, recorded as a new delivery.
+    pub async fn redeliver(
+        pool: &SqlitePool,
+        webhook: Webhook,
+        delivery: &WebhookDelivery,
+    ) -> Result<WebhookDelivery, sqlx::Error> {
+        Self::enqueue(
+            pool,
+            webhook,
+            delivery.event_type.clone(),
+            delivery.request_body.clone(),
+            Some(delivery.id),
+        )
+        .await
+    }
+
+    /// Record a delivery and send it in the background, or record it as dropped when too many
+    /// deliveries are already in flight.
+    async fn enqueue(
+        pool: &SqlitePool,
+        webhook: Webhook,
+        event_type: WebhookEventType,
+        body: String,
+        redelivery_of: Option<Uuid>,
+    ) -> Result<WebhookDelivery, sqlx::Error> {
+        // Step 4 (H5): acquire semaphore BEFORE spawning to bound concurrent dispatches.
+        let permit = Arc::clone(&DISPATCH_SEM).try_acquire_owned();
+        let (status, error) = match permit {
+            Ok(_) => (WebhookDeliveryStatus::Pending, None),
+            Err(_) => {
+                warn!(webhook_id = %webhook.id, "Dispatch semaphore full, dropping webhook delivery");
+                (
+                    WebhookDeliveryStatus::Dropped,
+                    Some("Too many webhook deliveries in flight"),
+                )
+            }
+        };
+        let delivery = WebhookDelivery::create(
+            pool,
+            &CreateWebhookDelivery {
+                webhook_id: webhook.id,
+                event_type: event_type.clone(),
+                request_body: &body,
+                status,
+                error,
+                redelivery_of,
+            },
+        )
+        .await?;
+        if let Ok(permit) = permit {
+            let pool = pool.clone();
+            let delivery_id = delivery.id;
+            tokio::spawn(async move {
+                Self::deliver(
+                    &pool,
+                    &webhook,
+                    delivery_id,
+                    &event_type,
+                    &body,
+                    1,
+                    Some(permit),
+                )
+                .await;
+            });
+        }
+        Ok(delivery)
+    }
+
+    /// Carry on with deliveries a previous run left pending, continuing from their next
+    /// attempt. Returns how many were resumed.
+    pub async fn resume_pending(pool: &SqlitePool) -> Result<usize, sqlx::Error> {
+        let deliveries = WebhookDelivery::find_pending(pool).await?;
+        let mut resumed = 0;
+        for delivery in deliveries {
+            let Some(webhook) = Webhook::find_by_id(pool, delivery.webhook_id).await? else {
+                continue;
+            };
+            let first_attempt = (delivery.attempt_count as u32).min(MAX_DELIVERY_ATTEMPTS - 1) + 1;
+            let pool = pool.clone();
+            tokio::spawn(async move {
+                Self::deliver(
+                    &pool,
+                    &webhook,
+                    delivery.id,
+                    &delivery.event_type,
+                    &delivery.request_body,
+                    first_attempt,
+                    None,
+                )
+                .await;
+            });
+            resumed += 1;
+        }
+        Ok(resumed)
+    }
+
+    /// Send a delivery, retrying 5xx responses and transport errors with exponential backoff.
+    ///
+    /// A dispatch slot is held only while an attempt is being sent; `permit` is the slot for
+    /// the first attempt, if one was already taken.
+    async fn deliver(
+        pool: &SqlitePool,
+        webhook: &Webhook,
+        delivery_id: Uuid,
+        event_type: &WebhookEventType,
+        body: &str,
+        first_attempt: u32,
+        mut permit: Option<OwnedSemaphorePermit>,
+    ) {
+        for attempt_number in first_attempt..=MAX_DELIVERY_ATTEMPTS {
+            let slot = match permit.take() {
+                Some(slot) => slot,
+                None => match Arc::clone(&DISPATCH_SEM).acquire_owned().await {
+                    Ok(slot) => slot,
+                    // The semaphore is never closed
+                    Err(_) => return,
+                },
+            };
+            let (attempt, retryable) = Self::dispatch(webhook, event_type, body).await;
+            drop(slot);
+            let status = if attempt.error.is_none() {
+                WebhookDeliveryStatus::Succeeded
+            } else if retryable && attempt_number < MAX_DELIVERY_ATTEMPTS {
+                WebhookDeliveryStatus::Pending
+            } else {
+                WebhookDeliveryStatus::Failed
+            };
+            match WebhookDelivery::record_attempt(pool, delivery_id, status, &attempt).await {
+                // The webhook was deleted while the delivery was in flight
+                Ok(false) => return,
+                Ok(true) => {}
+                Err(e) => {
+                    warn!(delivery_id = %delivery_id, error = ?e, "Failed to record webhook delivery attempt");
                 }
             }
+            if status != WebhookDeliveryStatus::Pending {
+                return;
+            }
+            tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt_number - 1)).await;
         }
     }
 
-    async fn dispatch(webhook: &Webhook, ctx: &WebhookContext) {
-        // Capture timestamp once for consistent headers and payload
-        let now = Utc::now();
-        let ts = now.timestamp().to_string();
+    /// Make one delivery attempt. Returns its outcome and whether a failure is worth retrying.
+    async fn dispatch(
+        webhook: &Webhook,
+        event_type: &WebhookEventType,
+        body: &str,
+    ) -> (DeliveryAttempt, bool) {
+        let failed = |error: String| {
+            (
+                DeliveryAttempt {
+                    error: Some(error),
+                    ..Default::default()
+                },
+                false,
+            )
+        };
+        let ts = Utc::now().timestamp().to_string();
 
         // Step 1 (C1): resolve_pinned prevents DNS rebind TOCTOU.
         let parsed_url = match url::Url::parse(&webhook.url) {
             Ok(u) => u,
             Err(e) => {
                 warn!(webhook_id = %webhook.id, error = ?e, "Webhook URL is invalid, skipping dispatch");
-                return;
+                return failed(format!("Invalid URL: {e}"));
             }
         };
         let host = match parsed_url.host_str() {
             Some(h) => h.to_string(),
             None => {
                 warn!(webhook_id = %webhook.id, "Webhook URL has no host, skipping dispatch");
-                return;
+                return failed("URL has no host".to_string());
             }
         };
         let resolved_addr = match Self::resolve_pinned(&webhook.url).await {
             Ok(addr) => addr,
             Err(e) => {
                 warn!(webhook_id = %webhook.id, url = %webhook.url, error = %e, "Webhook URL SSRF check failed, skipping dispatch");
-                return;
-            }
-        };
-
-        let payload = Self::build_payload(webhook, ctx, now);
-        let body = match serde_json::to_string(&payload) {
-            Ok(b) => b,
-            Err(e) => {
-                warn!(webhook_id = %webhook.id, error = ?e, "Failed to serialize webhook payload");
-                return;
+                return failed(e);
             }
         };
 
@@ -164,11 +499,10 @@ impl WebhookService {
             Ok(c) => c,
             Err(e) => {
                 warn!(error = ?e, "Failed to build reqwest client");
-                return;
+                return failed("Failed to build HTTP client".to_string());
             }
         };
 
-        let event_type = ctx.event.event_type();
         let mut builder = client.post(&webhook.url);
 
         // Apply user headers first, filtering reserved names (H3 + H6 expanded blocklist)
@@ -209,7 +543,10 @@ impl WebhookService {
             builder = builder.header("X-VkSwarm-Signature", format!("sha256={sig}"));
         }
 
-        match builder.body(body).send().await {
+        let start = std::time::Instant::now();
+        let result = builder.body(body.to_string()).send().await;
+        let latency_ms = Some(start.elapsed().as_millis() as i64);
+        match result {
             Err(e) => {
                 warn!(
                     webhook_id = %webhook.id,
@@ -217,16 +554,38 @@ impl WebhookService {
                     error = ?e,
                     "Webhook dispatch transport error"
                 );
+                let error = if e.is_timeout() {
+                    "Request timed out"
+                } else {
+                    "Request failed"
+                };
+                (
+                    DeliveryAttempt {
+                        response_status: None,
+                        latency_ms,
+                        error: Some(error.to_string()),
+                    },
+                    true,
+                )
             }
             Ok(resp) => {
-                if !resp.status().is_success() {
+                let status = resp.status();
+                if !status.is_success() {
                     warn!(
                         webhook_id = %webhook.id,
                         url = %webhook.url,
-                        status = %resp.status(),
+                        status = %status,
                         "Webhook dispatch received non-2xx response"
                     );
                 }
+                (
+                    DeliveryAttempt {
+                        response_status: Some(status.as_u16() as i64),
+                        latency_ms,
+                        error: (!status.is_success()).then(|| format!("HTTP {status}")),
+                    },
+                    status.is_server_error(),
+                )
             }
         }
     }
@@ -311,59 +670,75 @@ impl WebhookService {
     ) -> HashMap<String, serde_json::Value> {
         let mut map: HashMap<String, serde_json::Value> = HashMap::new();
 
-        map.insert("project.id".into(), ctx.project_id.to_string().into());
-        map.insert("project.name".into(), ctx.project_name.clone().into());
+        let project = ctx.project.as_ref();
+        map.insert(
+            "project.id".into(),
+            opt_str(project.map(|p| p.id.to_string())),
+        );
+        map.insert(
+            "project.name".into(),
+            opt_str(project.map(|p| p.name.clone())),
+        );
         map.insert(
             "project.git_repo_path".into(),
-            ctx.project_git_repo_path.clone().into(),
+            opt_str(project.map(|p| p.git_repo_path.clone())),
         );
         map.insert(
             "project.github_owner".into(),
-            opt_str(ctx.project_github_owner.clone()),
+            opt_str(project.and_then(|p| p.github_owner.clone())),
         );
         map.insert(
             "project.github_repo".into(),
-            opt_str(ctx.project_github_repo.clone()),
+            opt_str(project.and_then(|p| p.github_repo.clone())),
         );
-        map.insert("task.id".into(), ctx.task_id.to_string().into());
-        map.insert("task.title".into(), ctx.task_title.clone().into());
+        let task = ctx.task.as_ref();
+        map.insert("task.id".into(), opt_str(task.map(|t| t.id.to_string())));
+        map.insert("task.title".into(), opt_str(task.map(|t| t.title.clone())));
         map.insert(
             "task.description".into(),
-            opt_str(ctx.task_description.clone()),
+            opt_str(task.and_then(|t| t.description.clone())),
+        );
+        map.insert(
+            "task.status".into(),
+            opt_str(task.map(|t| t.status.clone())),
         );
-        map.insert("task.status".into(), ctx.task_status.clone().into());
         map.insert(
             "task.labels".into(),
-            serde_json::Value::Array(
-                ctx.task_labels
-                    .iter()
-                    .map(|l| serde_json::Value::String(l.clone()))
-                    .collect(),
-            ),
+            task.map(|t| {
+                serde_json::Value::Array(
+                    t.labels
+                        .iter()
+                        .map(|l| serde_json::Value::String(l.clone()))
+                        .collect(),
+                )
+            })
+            .unwrap_or(serde_json::Value::Null),
         );
+        let attempt = ctx.task_attempt.as_ref();
         map.insert(
             "task_attempt.id".into(),
-            ctx.task_attempt_id.to_string().into(),
+            opt_str(attempt.map(|a| a.id.to_string())),
         );
         map.insert(
             "task_attempt.executor".into(),
-            ctx.task_attempt_executor.clone().into(),
+            opt_str(attempt.map(|a| a.executor.clone())),
         );
         map.insert(
             "task_attempt.branch".into(),
-            ctx.task_attempt_branch.clone().into(),
+            opt_str(attempt.map(|a| a.branch.clone())),
         );
         map.insert(
             "task_attempt.worktree_path".into(),
-            opt_str(ctx.task_attempt_worktree_path.clone()),
+            opt_str(attempt.and_then(|a| a.worktree_path.clone())),
         );
+        let process = ctx.execution_process.as_ref();
         map.insert(
             "execution_process.id".into(),
-            ctx.execution_process_id.to_string().into(),
+            opt_str(process.map(|p| p.id.to_string())),
         );
         map.insert(
             "execution_process.run_reason".into(),
-            ctx.execution_process_run_reason.clone().into(),
+            opt_str(process.map(|p| p.run_reason.clone())),
         );
         map.insert(
             "event.type".into(),
@@ -447,6 +822,45 @@ impl WebhookService {
                         .unwrap_or(serde_json::Value::Null),
                 );
             }
+            WebhookEventPayload::BudgetExceeded {
+                scope,
+                limit,
+                max_tokens,
+                max_cost_usd,
+                period,
+                spent_tokens,
+                spent_cost_usd,
+                message,
+            } => {
+                map.insert("budget.scope".into(), scope.clone().into());
+                map.insert("budget.limit".into(), limit.clone().into());
+                map.insert(
+                    "budget.max_tokens".into(),
+                    max_tokens
+                        .map(|n| serde_json::Value::Number(n.into()))
+                        .unwrap_or(serde_json::Value::Null),
+                );
+                map.insert(
+                    "budget.max_cost_usd".into(),
+                    serde_json::to_value(max_cost_usd).unwrap_or(serde_json::Value::Null),
+                );
+                map.insert("budget.period".into(), opt_str(period.clone()));
+                map.insert("budget.spent_tokens".into(), (*spent_tokens).into());
+                map.insert(
+                    "budget.spent_cost_usd".into(),
+                    serde_json::to_value(spent_cost_usd).unwrap_or(serde_json::Value::Null),
+                );
+                map.insert("budget.message".into(), message.clone().into());
+            }
+            event => {
+                if let Some((section, serde_json::Value::Object(fields))) =
+                    event.lifecycle_section()
+                {
+                    for (key, value) in fields {
+                        map.insert(format!("{section}.{key}"), value);
+                    }
+                }
+            }
         }
         map
     }
@@ -457,30 +871,30 @@ impl WebhookService {
                 "type": ctx.event.event_type().as_str(),
                 "timestamp": now.to_rfc3339(),
             },
-            "project": {
-                "id": ctx.project_id.to_string(),
-                "name": ctx.project_name,
-                "git_repo_path": ctx.project_git_repo_path,
-                "github_owner": ctx.project_github_owner,
-                "github_repo": ctx.project_github_repo,
-            },
-            "task": {
-                "id": ctx.task_id.to_string(),
-                "title": ctx.task_title,
-                "description": ctx.task_description,
-                "status": ctx.task_status,
-                "labels": ctx.task_labels,
-            },
-            "task_attempt": {
-                "id": ctx.task_attempt_id.to_string(),
-                "executor": ctx.task_attempt_executor,
-                "branch": ctx.task_attempt_branch,
-                "worktree_path": ctx.task_attempt_worktree_path,
-            },
-            "execution_process": {
-                "id": ctx.execution_process_id.to_string(),
-                "run_reason": ctx.execution_process_run_reason,
-            },
+            "project": ctx.project.as_ref().map(|p| serde_json::json!({
+                "id": p.id.to_string(),
+                "name": p.name,
+                "git_repo_path": p.git_repo_path,
+                "github_owner": p.github_owner,
+                "github_repo": p.github_repo,
+            })),
+            "task": ctx.task.as_ref().map(|t| serde_json::json!({
+                "id": t.id.to_string(),
+                "title": t.title,
+                "description": t.description,
+                "status": t.status,
+                "labels": t.labels,
+            })),
+            "task_attempt": ctx.task_attempt.as_ref().map(|a| serde_json::json!({
+                "id": a.id.to_string(),
+                "executor": a.executor,
+                "branch": a.branch,
+                "worktree_path": a.worktree_path,
+            })),
+            "execution_process": ctx.execution_process.as_ref().map(|p| serde_json::json!({
+                "id": p.id.to_string(),
+                "run_reason": p.run_reason,
+            })),
         });
 
         match &ctx.event {
@@ -529,6 +943,32 @@ impl WebhookService {
                     "pr_number": pr_number,
                 });
             }
+            WebhookEventPayload::BudgetExceeded {
+                scope,
+                limit,
+                max_tokens,
+                max_cost_usd,
+                period,
+                spent_tokens,
+                spent_cost_usd,
+                message,
+            } => {
+                payload["budget"] = serde_json::json!({
+                    "scope": scope,
+                    "limit": limit,
+                    "max_tokens": max_tokens,
+                    "max_cost_usd": max_cost_usd,
+                    "period": period,
+                    "spent_tokens": spent_tokens,
+                    "spent_cost_usd": spent_cost_usd,
+                    "message": message,
+                });
+            }
+            event => {
+                if let Some((section, fields)) = event.lifecycle_section() {
+                    payload[section] = fields;
+                }
+            }
         }
         payload
     }
@@ -589,45 +1029,19 @@ impl WebhookService {
         completion_reason: Option<String>,
         exit_code: Option<i64>,
     ) -> Option<WebhookContext> {
-        let ctx = ExecutionProcess::load_context(pool, exec_id).await.ok()?;
-        let labels = Label::find_by_task_id(pool, ctx.task.id)
-            .await
-            .unwrap_or_default();
-        let label_names: Vec<String> = labels.into_iter().map(|l| l.name).collect();
-        let project = Project::find_by_id(pool, ctx.task.project_id)
-            .await
-            .ok()??;
-        let started_at = ctx.execution_process.started_at;
-        let completed_at = ctx.execution_process.completed_at;
+        let process = ExecutionProcess::find_by_id(pool, exec_id).await.ok()??;
+        let started_at = process.started_at;
+        let completed_at = process.completed_at;
         let duration_ms = completed_at.map(|c| (c - started_at).num_milliseconds());
         let (pr_url, pr_number) =
-            match Merge::find_latest_by_task_attempt_id(pool, ctx.task_attempt.id).await {
+            match Merge::find_latest_by_task_attempt_id(pool, process.task_attempt_id).await {
                 Ok(Some(Merge::Pr(pr))) => (Some(pr.pr_info.url.clone()), Some(pr.pr_info.number)),
                 _ => (None, None),
             };
-        // Step 8 (M6): use Serialize instead of Debug format for run_reason
-        let run_reason = serde_json::to_value(&ctx.execution_process.run_reason)
-            .ok()
-            .and_then(|v| v.as_str().map(str::to_owned))
-            .unwrap_or_else(|| format!("{:?}", ctx.execution_process.run_reason));
-        Some(WebhookContext {
-            project_id: project.id,
-            project_name: project.name.clone(),
-            project_git_repo_path: project.git_repo_path.to_string_lossy().to_string(),
-            project_github_owner: project.github_owner.clone(),
-            project_github_repo: project.github_repo.clone(),
-            task_id: ctx.task.id,
-            task_title: ctx.task.title.clone(),
-            task_description: ctx.task.description.clone(),
-            task_status: format!("{:?}", ctx.task.status),
-            task_labels: label_names,
-            task_attempt_id: ctx.task_attempt.id,
-            task_attempt_executor: ctx.task_attempt.executor.clone(),
-            task_attempt_branch: ctx.task_attempt.branch.clone(),
-            task_attempt_worktree_path: ctx.task_attempt.container_ref.clone(),
-            execution_process_id: exec_id,
-            execution_process_run_reason: run_reason,
-            event: WebhookEventPayload::ExecutorFinish {
+        Self::build_execution_context(
+            pool,
+            exec_id,
+            WebhookEventPayload::ExecutorFinish {
                 status,
                 completion_reason,
                 exit_code,
@@ -637,49 +1051,155 @@ impl WebhookService {
                 pr_url,
                 pr_number,
             },
-        })
+        )
+        .await
     }
 
-    /// Build a WebhookContext for approval/question events.
-    pub async fn build_approval_context(
+    /// Build a WebhookContext for events raised by an execution process, such as approval,
+    /// question, budget and setup script events.
+    pub async fn build_execution_context(
         pool: &SqlitePool,
         exec_id: Uuid,
         event: WebhookEventPayload,
     ) -> Option<WebhookContext> {
         let ctx = ExecutionProcess::load_context(pool, exec_id).await.ok()?;
-        let labels = Label::find_by_task_id(pool, ctx.task.id)
-            .await
-            .unwrap_or_default();
-        let label_names: Vec<String> = labels.into_iter().map(|l| l.name).collect();
-        let project = Project::find_by_id(pool, ctx.task.project_id)
-            .await
-            .ok()??;
+        let mut webhook_ctx = Self::context_for_task(pool, &ctx.task, event).await?;
+        webhook_ctx.task_attempt = Some(Self::attempt_section(&ctx.task_attempt));
         // Step 8 (M6): use Serialize instead of Debug format for run_reason
         let run_reason = serde_json::to_value(&ctx.execution_process.run_reason)
             .ok()
             .and_then(|v| v.as_str().map(str::to_owned))
             .unwrap_or_else(|| format!("{:?}", ctx.execution_process.run_reason));
+        webhook_ctx.execution_process = Some(WebhookExecutionProcess {
+            id: exec_id,
+            run_reason,
+        });
+        Some(webhook_ctx)
+    }
+
+    /// Build a WebhookContext for attempt events such as attempt creation, merges, pull
+    /// requests and rebase conflicts.
+    pub async fn build_attempt_context(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        event: WebhookEventPayload,
+    ) -> Option<WebhookContext> {
+        let attempt = TaskAttempt::find_by_id(pool, task_attempt_id)
+            .await
+            .ok()??;
+        let task = Task::find_by_id(pool, attempt.task_id).await.ok()??;
+        let mut webhook_ctx = Self::context_for_task(pool, &task, event).await?;
+        webhook_ctx.task_attempt = Some(Self::attempt_section(&attempt));
+        Some(webhook_ctx)
+    }
+
+    /// Build a WebhookContext for task events such as creation, status changes and archiving.
+    pub async fn build_task_context(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        event: WebhookEventPayload,
+    ) -> Option<WebhookContext> {
+        let task = Task::find_by_id(pool, task_id).await.ok()??;
+        Self::context_for_task(pool, &task, event).await
+    }
+
+    /// Build a WebhookContext for node events, which are not tied to a project.
+    pub fn build_node_context(event: WebhookEventPayload) -> WebhookContext {
+        WebhookContext {
+            project: None,
+            task: None,
+            task_attempt: None,
+            execution_process: None,
+            event,
+        }
+    }
+
+    async fn context_for_task(
+        pool: &SqlitePool,
+        task: &Task,
+        event: WebhookEventPayload,
+    ) -> Option<WebhookContext> {
+        let labels = Label::find_by_task_id(pool, task.id)
+            .await
+            .unwrap_or_default();
+        let project = Project::find_by_id(pool, task.project_id).await.ok()??;
         Some(WebhookContext {
-            project_id: project.id,
-            project_name: project.name.clone(),
-            project_git_repo_path: project.git_repo_path.to_string_lossy().to_string(),
-            project_github_owner: project.github_owner.clone(),
-            project_github_repo: project.github_repo.clone(),
-            task_id: ctx.task.id,
-            task_title: ctx.task.title.clone(),
-            task_description: ctx.task.description.clone(),
-            task_status: format!("{:?}", ctx.task.status),
-            task_labels: label_names,
-            task_attempt_id: ctx.task_attempt.id,
-            task_attempt_executor: ctx.task_attempt.executor.clone(),
-            task_attempt_branch: ctx.task_attempt.branch.clone(),
-            task_attempt_worktree_path: ctx.task_attempt.container_ref.clone(),
-            execution_process_id: exec_id,
-            execution_process_run_reason: run_reason,
+            project: Some(WebhookProject {
+                id: project.id,
+                name: project.name.clone(),
+                git_repo_path: project.git_repo_path.to_string_lossy().to_string(),
+                github_owner: project.github_owner.clone(),
+                github_repo: project.github_repo.clone(),
+            }),
+            task: Some(WebhookTask {
+                id: task.id,
+                title: task.title.clone(),
+                description: task.description.clone(),
+                status: format!("{:?}", task.status),
+                labels: labels.into_iter().map(|l| l.name).collect(),
+            }),
+            task_attempt: None,
+            execution_process: None,
             event,
         })
     }
 
+    fn attempt_section(attempt: &TaskAttempt) -> WebhookTaskAttempt {
+        WebhookTaskAttempt {
+            id: attempt.id,
+            executor: attempt.executor.clone(),
+            branch: attempt.branch.clone(),
+            worktree_path: attempt.container_ref.clone(),
+        }
+    }
+
+    /// Fire an execution process event (fire-and-forget, non-blocking).
+    pub fn spawn_execution_event(pool: &SqlitePool, exec_id: Uuid, event: WebhookEventPayload) {
+        Self::spawn_fire(pool, move |pool| async move {
+            Self::build_execution_context(&pool, exec_id, event).await
+        });
+    }
+
+    /// Fire an attempt event (fire-and-forget, non-blocking).
+    pub fn spawn_attempt_event(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        event: WebhookEventPayload,
+    ) {
+        Self::spawn_fire(pool, move |pool| async move {
+            Self::build_attempt_context(&pool, task_attempt_id, event).await
+        });
+    }
+
+    /// Fire a task event (fire-and-forget, non-blocking).
+    pub fn spawn_task_event(pool: &SqlitePool, task_id: Uuid, event: WebhookEventPayload) {
+        Self::spawn_fire(pool, move |pool| async move {
+            Self::build_task_context(&pool, task_id, event).await
+        });
+    }
+
+    /// Fire a node event to global webhooks (fire-and-forget, non-blocking).
+    pub fn spawn_node_event(pool: &SqlitePool, event: WebhookEventPayload) {
+        Self::spawn_fire(pool, move |_| async move {
+            Some(Self::build_node_context(event))
+        });
+    }
+
+    fn spawn_fire<F, Fut>(pool: &SqlitePool, build: F)
+    where
+        F: FnOnce(SqlitePool) -> Fut + Send + 'static,
+        Fut: Future<Output = Option<WebhookContext>> + Send + 'static,
+    {
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            if let Some(ctx) = build(pool.clone()).await {
+                Self::fire(&pool, ctx).await;
+            } else {
+                tracing::debug!("webhook: could not build event context, event not delivered");
+            }
+        });
+    }
+
     /// Resolve hostname once, verify all IPs are public, return a pinned SocketAddr.
     /// This prevents DNS rebind TOCTOU attacks (C1): the same resolved IP is used for
     /// the actual HTTP connection via `.resolve_to_addrs()`, preventing a DNS flip between
@@ -544,3 +544,64 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@vibekanban.com"));
     }
 }
+
+#[test]
+fn snapshot_checkout_captures_uncommitted_changes_without_touching_them() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let worktree_path = td.path().join("wt_session");
+    let s = GitService::new();
+
+    write_file(&repo_path, "kept.txt", "kept\n");
+    write_file(&repo_path, "removed.txt", "removed\n");
+    s.commit(&repo_path, "initial").unwrap();
+    create_branch(&repo_path, "session");
+    s.add_worktree(&repo_path, &worktree_path, "session", false)
+        .unwrap();
+
+    // A clean checkout snapshots to its HEAD
+    let head = s.get_head_info(&worktree_path).unwrap().oid;
+    assert_eq!(
+        s.snapshot_checkout(&repo_path, &worktree_path).unwrap(),
+        head
+    );
+
+    write_file(&worktree_path, "kept.txt", "edited\n");
+    fs::remove_file(worktree_path.join("removed.txt")).unwrap();
+    write_file(&worktree_path, "src/new.txt", "new\n");
+    write_file(&worktree_path, ".gitignore", "ignored.txt\n");
+    write_file(&worktree_path, "ignored.txt", "ignored\n");
+
+    // Any directory inside the checkout works, and the checkout is left as it was
+    let snapshot = s
+        .snapshot_checkout(&repo_path, &worktree_path.join("src"))
+        .unwrap();
+    assert_ne!(snapshot, head);
+    assert_eq!(s.get_head_info(&worktree_path).unwrap().oid, head);
+    let staged = GitCli::new()
+        .git(&worktree_path, ["diff", "--cached", "--name-only"])
+        .unwrap();
+    assert!(staged.trim().is_empty());
+    assert!(!s.is_worktree_clean(&worktree_path).unwrap());
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let commit = repo
+        .find_commit(git2::Oid::from_str(&snapshot).unwrap())
+        .unwrap();
+    assert_eq!(commit.parent_id(0).unwrap().to_string(), head);
+    let tree = commit.tree().unwrap();
+    let blob = |path: &str| {
+        tree.get_path(Path::new(path))
+            .ok()
+            .map(|entry| repo.find_blob(entry.id()).unwrap().content().to_vec())
+    };
+    assert_eq!(blob("kept.txt").as_deref(), Some(&b"edited\n"[..]));
+    assert_eq!(blob("src/new.txt").as_deref(), Some(&b"new\n"[..]));
+    assert_eq!(blob("removed.txt"), None);
+    assert_eq!(blob("ignored.txt"), None);
+
+    // Checkouts of other repositories are refused
+    let other = td.path().join("other");
+    s.initialize_repo_with_main_branch(&other).unwrap();
+    assert!(s.snapshot_checkout(&repo_path, &other).is_err());
+}
@@ -36,6 +36,10 @@ pub fn credentials_path() -> std::path::PathBuf {
     asset_dir().join("credentials.json")
 }
 
+pub fn secrets_path() -> std::path::PathBuf {
+    asset_dir().join("secrets.json")
+}
+
 /// Get the database file path.
 ///
 /// Respects the `VK_DATABASE_PATH` environment variable for custom locations.
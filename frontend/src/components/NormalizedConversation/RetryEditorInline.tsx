@@ -241,7 +241,9 @@ export function RetryEditorInline({
         (p) => p.run_reason === PROCESS_RUN_REASONS.SETUP_SCRIPT
       ).length;
       const laterCleanup = later.filter(
-        (p) => p.run_reason === PROCESS_RUN_REASONS.CLEANUP_SCRIPT
+        (p) =>
+          p.run_reason === PROCESS_RUN_REASONS.CLEANUP_SCRIPT ||
+          p.run_reason === PROCESS_RUN_REASONS.PIPELINE_STEP
       ).length;
 
       // Ask user for confirmation
@@ -9,6 +9,7 @@ import {
   Cog,
   ArrowLeft,
   Unplug,
+  Wallet,
   XCircle,
 } from 'lucide-react';
 import { executionProcessesApi } from '@/lib/api';
@@ -90,6 +91,8 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
         return <Unplug className="h-3 w-3 text-amber-600" />;
       case 'killed':
         return <Square className="h-3 w-3 text-gray-600" />;
+      case 'budget_exceeded':
+        return <Wallet className="h-3 w-3 text-orange-600" />;
       case 'error':
         return <AlertCircle className="h-3 w-3 text-red-600" />;
       default:
@@ -107,6 +110,8 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
         return 'bg-amber-50 border-amber-200 text-amber-700';
       case 'killed':
         return 'bg-gray-50 border-gray-200 text-gray-700';
+      case 'budget_exceeded':
+        return 'bg-orange-50 border-orange-200 text-orange-700';
       case 'error':
         return 'bg-red-50 border-red-200 text-red-700';
       default:
@@ -7,7 +7,7 @@ import {
   ConnectionState,
 } from '@/hooks/useTerminalWebSocket';
 import { getTerminalSettings } from '@/hooks/useTerminalSettings';
-import { Loader2, RefreshCw, AlertCircle } from 'lucide-react';
+import { Loader2, RefreshCw, AlertCircle, Eye } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { cn } from '@/lib/utils';
 
@@ -55,14 +55,22 @@ function TerminalView({
     setLocalConnectionState(state);
   }, []);
 
-  const { sendInput, sendResize, connectionState, reconnect, retryCount } =
-    useTerminalWebSocket({
-      sessionId,
-      onOutput: handleOutput,
-      onExit: handleExit,
-      onError: handleError,
-      onConnectionChange: handleConnectionChange,
-    });
+  const {
+    sendInput,
+    sendResize,
+    connectionState,
+    reconnect,
+    retryCount,
+    viewers,
+    isWriter,
+    requestControl,
+  } = useTerminalWebSocket({
+    sessionId,
+    onOutput: handleOutput,
+    onExit: handleExit,
+    onError: handleError,
+    onConnectionChange: handleConnectionChange,
+  });
 
   // Sync local state with WebSocket state
   useEffect(() => {
@@ -148,6 +156,14 @@ function TerminalView({
     };
   }, [sendInput, sendResize]);
 
+  // Size the session to this terminal whenever it gains control
+  useEffect(() => {
+    const terminal = terminalRef.current;
+    if (isWriter && terminal) {
+      sendResize(terminal.cols, terminal.rows);
+    }
+  }, [isWriter, sendResize]);
+
   // Focus terminal when connected
   useEffect(() => {
     if (localConnectionState === 'connected') {
@@ -230,6 +246,24 @@ function TerminalView({
         </div>
       )}
 
+      {/* Shared session: another viewer controls the terminal */}
+      {localConnectionState === 'connected' && viewers && !isWriter && (
+        <div className="absolute top-2 right-2 z-20 px-3 py-1.5 rounded-md bg-muted/90 text-muted-foreground text-sm flex items-center gap-2">
+          <Eye className="h-4 w-4" />
+          {viewers.writerId ? 'View only' : 'Nobody has control'}
+          {!viewers.writerId && (
+            <Button
+              variant="secondary"
+              size="sm"
+              onClick={requestControl}
+              className="h-6 px-2"
+            >
+              Take control
+            </Button>
+          )}
+        </div>
+      )}
+
       {/* Terminal container */}
       <div
         ref={containerRef}
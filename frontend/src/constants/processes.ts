@@ -6,6 +6,8 @@ export const PROCESS_RUN_REASONS = {
   CLEANUP_SCRIPT: 'cleanupscript' as ExecutionProcessRunReason,
   CODING_AGENT: 'codingagent' as ExecutionProcessRunReason,
   DEV_SERVER: 'devserver' as ExecutionProcessRunReason,
+  PIPELINE_STEP: 'pipelinestep' as ExecutionProcessRunReason,
+  COMMAND_RERUN: 'commandrerun' as ExecutionProcessRunReason,
 } as const;
 
 export const isCodingAgent = (
@@ -193,7 +193,9 @@ export const useConversationHistory = ({
       (ep) =>
         ep.run_reason === 'setupscript' ||
         ep.run_reason === 'cleanupscript' ||
-        ep.run_reason === 'codingagent'
+        ep.run_reason === 'codingagent' ||
+        ep.run_reason === 'pipelinestep' ||
+        ep.run_reason === 'commandrerun'
     );
   }, [executionProcessesRaw]);
 
@@ -342,6 +344,12 @@ export const useConversationHistory = ({
               return 'Cleanup Script';
             case 'ToolInstallScript':
               return 'Tool Install Script';
+            case 'PipelineScript':
+              return 'Pipeline Script';
+            case 'VerificationScript':
+              return 'Verification Script';
+            case 'CommandRerun':
+              return 'Command Rerun';
             default:
               return 'Script';
           }
@@ -509,6 +517,15 @@ export const useConversationHistory = ({
               case 'ToolInstallScript':
                 toolName = 'Tool Install Script';
                 break;
+              case 'PipelineScript':
+                toolName = 'Pipeline Script';
+                break;
+              case 'VerificationScript':
+                toolName = 'Verification Script';
+                break;
+              case 'CommandRerun':
+                toolName = 'Command Rerun';
+                break;
               default:
                 return [];
             }
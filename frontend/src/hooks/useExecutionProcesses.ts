@@ -144,7 +144,9 @@ export const useExecutionProcesses = (
     (process) =>
       (process.run_reason === 'codingagent' ||
         process.run_reason === 'setupscript' ||
-        process.run_reason === 'cleanupscript') &&
+        process.run_reason === 'cleanupscript' ||
+        process.run_reason === 'pipelinestep' ||
+        process.run_reason === 'commandrerun') &&
       process.status === 'running'
   );
 
@@ -68,6 +68,8 @@ export const useNodeLogStream = (
   const retryTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const isIntentionallyClosed = useRef<boolean>(false);
   const connectionInfoRef = useRef<ConnectionInfo | null>(null);
+  // Last entry received from the relay, so a reconnect resumes after it
+  const relayCursorRef = useRef<number | null>(null);
   // Use a ref to store the connect function to avoid circular dependency
   const connectRef = useRef<() => Promise<void>>();
 
@@ -143,16 +145,22 @@ export const useNodeLogStream = (
   );
 
   /**
-   * Connect to the Hive relay.
+   * Connect to the Hive relay, resuming after `cursor` if given.
    */
   const connectToRelay = useCallback(
-    (info: ConnectionInfo): Promise<WebSocket | null> => {
+    (
+      info: ConnectionInfo,
+      cursor: number | null
+    ): Promise<WebSocket | null> => {
       return new Promise((resolve) => {
         try {
           // Build relay WebSocket URL
           const relayUrl = new URL(info.relay_url);
           const wsProtocol = relayUrl.protocol === 'https:' ? 'wss:' : 'ws:';
-          const wsUrl = `${wsProtocol}//${relayUrl.host}${relayUrl.pathname}?token=${encodeURIComponent(info.connection_token)}`;
+          let wsUrl = `${wsProtocol}//${relayUrl.host}${relayUrl.pathname}?token=${encodeURIComponent(info.connection_token)}`;
+          if (cursor !== null) {
+            wsUrl += `&cursor=${cursor}`;
+          }
 
           const ws = new WebSocket(wsUrl);
           const timeout = setTimeout(() => {
@@ -181,50 +189,57 @@ export const useNodeLogStream = (
   /**
    * Handle incoming WebSocket messages.
    */
-  const setupWebSocketHandlers = useCallback((ws: WebSocket) => {
-    ws.onmessage = (event) => {
-      try {
-        const message = JSON.parse(event.data) as LogStreamMessage;
-
-        switch (message.type) {
-          case 'logs':
-            setLogs((prev) => [...prev, ...message.entries]);
-            break;
-          case 'heartbeat':
-            // Keep-alive, no action needed
-            break;
-          case 'error':
-            setError(message.message);
-            break;
+  const setupWebSocketHandlers = useCallback(
+    (ws: WebSocket, isRelay: boolean) => {
+      ws.onmessage = (event) => {
+        try {
+          const message = JSON.parse(event.data) as LogStreamMessage;
+
+          switch (message.type) {
+            case 'logs':
+              if (isRelay && message.entries.length > 0) {
+                relayCursorRef.current =
+                  message.entries[message.entries.length - 1].id;
+              }
+              setLogs((prev) => [...prev, ...message.entries]);
+              break;
+            case 'heartbeat':
+              // Keep-alive, no action needed
+              break;
+            case 'error':
+              setError(message.message);
+              break;
+          }
+        } catch (e) {
+          console.error('Failed to parse WebSocket message:', e);
         }
-      } catch (e) {
-        console.error('Failed to parse WebSocket message:', e);
-      }
-    };
-
-    ws.onerror = () => {
-      setError('WebSocket connection error');
-    };
-
-    ws.onclose = (event) => {
-      if (!isIntentionallyClosed.current && event.code !== 1000) {
-        setConnectionType('disconnected');
-
-        // Retry with exponential backoff
-        const next = retryCountRef.current + 1;
-        retryCountRef.current = next;
-        if (next <= 6) {
-          const delay = Math.min(1500, 250 * 2 ** (next - 1));
-          retryTimerRef.current = setTimeout(() => {
-            // Use ref to call connect to avoid circular dependency
-            void connectRef.current?.();
-          }, delay);
-        } else {
-          setError('Connection lost after multiple retries');
+      };
+
+      ws.onerror = () => {
+        setError('WebSocket connection error');
+      };
+
+      ws.onclose = (event) => {
+        if (!isIntentionallyClosed.current && event.code !== 1000) {
+          setConnectionType('disconnected');
+
+          // Retry with exponential backoff
+          const next = retryCountRef.current + 1;
+          retryCountRef.current = next;
+          if (next <= 6) {
+            const delay = Math.min(1500, 250 * 2 ** (next - 1));
+            retryTimerRef.current = setTimeout(() => {
+              // Use ref to call connect to avoid circular dependency
+              void connectRef.current?.();
+            }, delay);
+          } else {
+            setError('Connection lost after multiple retries');
+          }
         }
-      }
-    };
-  }, []);
+      };
+    },
+    []
+  );
 
   /**
    * Main connection logic.
@@ -245,16 +260,21 @@ export const useNodeLogStream = (
 
     // Try direct connection first
     let ws = await tryDirectConnection(info);
+    const isRelay = !ws;
     if (ws) {
       setConnectionType('direct');
       setLogs([]); // Clear logs on new connection
+      relayCursorRef.current = null;
       retryCountRef.current = 0;
     } else {
-      // Fall back to relay
-      ws = await connectToRelay(info);
+      // Fall back to relay, picking up where the last relay connection left off
+      const cursor = relayCursorRef.current;
+      ws = await connectToRelay(info, cursor);
       if (ws) {
         setConnectionType('relay');
-        setLogs([]); // Clear logs on new connection
+        if (cursor === null) {
+          setLogs([]); // Clear logs on new connection
+        }
         retryCountRef.current = 0;
       }
     }
@@ -267,7 +287,7 @@ export const useNodeLogStream = (
 
     wsRef.current = ws;
     isIntentionallyClosed.current = false;
-    setupWebSocketHandlers(ws);
+    setupWebSocketHandlers(ws, isRelay);
   }, [
     assignmentId,
     fetchConnectionInfo,
@@ -302,6 +322,7 @@ export const useNodeLogStream = (
       return;
     }
 
+    relayCursorRef.current = null;
     void connect();
 
     return () => {
@@ -7,6 +7,14 @@ export type ConnectionState =
   | 'disconnected'
   | 'error';
 
+/** Viewers attached to the session, as seen by this client */
+export interface TerminalViewers {
+  viewerId: string;
+  /** Viewer that may send input and resize; null when nobody has control */
+  writerId: string | null;
+  viewerIds: string[];
+}
+
 export interface UseTerminalWebSocketOptions {
   sessionId: string | null;
   onOutput?: (data: string) => void;
@@ -21,6 +29,12 @@ export interface UseTerminalWebSocketResult {
   connectionState: ConnectionState;
   reconnect: () => void;
   retryCount: number;
+  viewers: TerminalViewers | null;
+  /** Whether this client controls the session */
+  isWriter: boolean;
+  requestControl: () => void;
+  releaseControl: () => void;
+  grantControl: (viewerId: string) => void;
 }
 
 export function useTerminalWebSocket({
@@ -36,6 +50,10 @@ export function useTerminalWebSocket({
   const [retryCount, setRetryCount] = useState<number>(0);
   const retryTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const isIntentionallyClosed = useRef<boolean>(false);
+  const [viewers, setViewers] = useState<TerminalViewers | null>(null);
+  const isWriterRef = useRef<boolean>(false);
+  const isWriter = !!viewers && viewers.writerId === viewers.viewerId;
+  isWriterRef.current = isWriter;
 
   const updateConnectionState = useCallback(
     (state: ConnectionState) => {
@@ -81,8 +99,18 @@ export function useTerminalWebSocket({
           case 'error':
             onError?.(msg.message);
             break;
+          case 'viewers':
+            setViewers({
+              viewerId: msg.viewer_id,
+              writerId: msg.writer_id,
+              viewerIds: msg.viewer_ids,
+            });
+            break;
           case 'input':
           case 'resize':
+          case 'request_control':
+          case 'release_control':
+          case 'grant_control':
             // These are client-to-server messages, ignore if received from server
             break;
         }
@@ -96,6 +124,7 @@ export function useTerminalWebSocket({
     };
 
     ws.onclose = (event) => {
+      setViewers(null);
       if (isIntentionallyClosed.current) {
         updateConnectionState('disconnected');
         return;
@@ -141,19 +170,45 @@ export function useTerminalWebSocket({
     };
   }, [sessionId, connect, disconnect]);
 
-  const sendInput = useCallback((data: string) => {
+  const send = useCallback((msg: TerminalMessage) => {
     if (wsRef.current?.readyState === WebSocket.OPEN) {
-      const msg: TerminalMessage = { type: 'input', data };
       wsRef.current.send(JSON.stringify(msg));
     }
   }, []);
 
-  const sendResize = useCallback((cols: number, rows: number) => {
-    if (wsRef.current?.readyState === WebSocket.OPEN) {
-      const msg: TerminalMessage = { type: 'resize', cols, rows };
-      wsRef.current.send(JSON.stringify(msg));
-    }
-  }, []);
+  // Only the writer may type or resize; other viewers watch
+  const sendInput = useCallback(
+    (data: string) => {
+      if (isWriterRef.current) {
+        send({ type: 'input', data });
+      }
+    },
+    [send]
+  );
+
+  const sendResize = useCallback(
+    (cols: number, rows: number) => {
+      if (isWriterRef.current) {
+        send({ type: 'resize', cols, rows });
+      }
+    },
+    [send]
+  );
+
+  const requestControl = useCallback(
+    () => send({ type: 'request_control' }),
+    [send]
+  );
+
+  const releaseControl = useCallback(
+    () => send({ type: 'release_control' }),
+    [send]
+  );
+
+  const grantControl = useCallback(
+    (viewerId: string) => send({ type: 'grant_control', viewer_id: viewerId }),
+    [send]
+  );
 
   const reconnect = useCallback(() => {
     disconnect();
@@ -167,5 +222,10 @@ export function useTerminalWebSocket({
     connectionState,
     reconnect,
     retryCount,
+    viewers,
+    isWriter,
+    requestControl,
+    releaseControl,
+    grantControl,
   };
 }
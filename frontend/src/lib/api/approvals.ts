@@ -2,7 +2,17 @@
  * Approvals API - Handle execution approvals.
  */
 
-import { ApprovalStatus, ApprovalResponse } from 'shared/types';
+import {
+  ApprovalRequest,
+  ApprovalStatus,
+  ApprovalResponse,
+} from 'shared/types';
+import type {
+  ListSwarmApprovalsResponse,
+  RespondSwarmApprovalRequest,
+  SwarmApproval,
+  SwarmApprovalResponse,
+} from '@/types/swarm';
 
 import { makeRequest, handleApiResponse } from './utils';
 
@@ -10,6 +20,20 @@ import { makeRequest, handleApiResponse } from './utils';
  * Approvals API namespace - Handle execution approvals.
  */
 export const approvalsApi = {
+  /**
+   * Approvals and questions still waiting for an answer, including ones from
+   * before a server restart.
+   */
+  listPending: async (
+    executionProcessId?: string
+  ): Promise<ApprovalRequest[]> => {
+    const query = executionProcessId
+      ? `?execution_process_id=${encodeURIComponent(executionProcessId)}`
+      : '';
+    const res = await makeRequest(`/api/approvals/pending${query}`);
+    return handleApiResponse<ApprovalRequest[]>(res);
+  },
+
   respond: async (
     approvalId: string,
     payload: ApprovalResponse,
@@ -24,4 +48,35 @@ export const approvalsApi = {
 
     return handleApiResponse<ApprovalStatus>(res);
   },
+  /**
+   * Approvals raised on any node of the organization, via the hive.
+   */
+  listSwarm: async (
+    organizationId: string,
+    includeResolved = false
+  ): Promise<SwarmApproval[]> => {
+    const res = await makeRequest(
+      `/api/approvals/swarm?organization_id=${encodeURIComponent(organizationId)}&include_resolved=${includeResolved}`
+    );
+    const result = await handleApiResponse<ListSwarmApprovalsResponse>(res);
+    return result.approvals;
+  },
+
+  /**
+   * Answer an approval raised on another node; the hive relays the answer.
+   */
+  respondSwarm: async (
+    approvalId: string,
+    payload: RespondSwarmApprovalRequest
+  ): Promise<SwarmApprovalResponse> => {
+    const res = await makeRequest(
+      `/api/approvals/swarm/${approvalId}/respond`,
+      {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(payload),
+      }
+    );
+    return handleApiResponse<SwarmApprovalResponse>(res);
+  },
 };
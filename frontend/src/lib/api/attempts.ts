@@ -6,6 +6,7 @@ import type {
   TaskAttempt,
   TaskRelationships,
   CreateTaskAttemptBody,
+  ImportSessionBody,
   CreateFollowUpAttempt,
   CreateReviewAttempt,
   RunAgentSetupRequest,
@@ -33,6 +34,9 @@ import type {
   GitOperationError,
   PushError,
   CreatePrError,
+  TranscriptFormat,
+  TimelineCommand,
+  RerunCommandRequest,
 } from 'shared/types';
 import {
   makeRequest,
@@ -68,6 +72,18 @@ export const attemptsApi = {
     return handleApiResponse<TaskAttempt>(response);
   },
 
+  /**
+   * Create an attempt from a Claude Code or Codex session started outside the app.
+   * Follow-ups on the attempt resume the imported session.
+   */
+  importSession: async (data: ImportSessionBody): Promise<TaskAttempt> => {
+    const response = await makeRequest(`/api/task-attempts/import`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskAttempt>(response);
+  },
+
   stop: async (attemptId: string): Promise<void> => {
     const response = await makeRequest(`/api/task-attempts/${attemptId}/stop`, {
       method: 'POST',
@@ -388,4 +404,39 @@ export const attemptsApi = {
     );
     return handleApiResponse<FixSessionsResponse>(response);
   },
+
+  /**
+   * Get the download URL for the attempt's conversation transcript.
+   * The browser will handle the download when navigated to this URL.
+   */
+  getTranscriptUrl: (
+    attemptId: string,
+    format: TranscriptFormat,
+    includeDiff = false
+  ): string => {
+    return `/api/task-attempts/${attemptId}/transcript?format=${format}&include_diff=${includeDiff}`;
+  },
+
+  /** Every shell command the attempt's agents ran, oldest first. */
+  getCommands: async (attemptId: string): Promise<TimelineCommand[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/commands`
+    );
+    return handleApiResponse<TimelineCommand[]>(response);
+  },
+
+  /** Run a command from the timeline again in the attempt's worktree. */
+  rerunCommand: async (
+    attemptId: string,
+    data: RerunCommandRequest
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/commands/rerun`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionProcess>(response);
+  },
 };
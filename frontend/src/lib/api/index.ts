@@ -62,7 +62,7 @@ export { templatesApi } from './templates';
 export { labelsApi } from './labels';
 
 // MCP Servers API
-export { mcpServersApi } from './mcp';
+export { mcpServersApi, mcpTokensApi } from './mcp';
 
 // Profiles API
 export { profilesApi } from './profiles';
@@ -2,7 +2,12 @@
  * Logs API namespace - Unified log access endpoints.
  */
 
-import type { PaginatedLogs, Direction } from 'shared/types';
+import type {
+  PaginatedLogs,
+  Direction,
+  LogSearchEntryType,
+  LogSearchHit,
+} from 'shared/types';
 import { makeRequest, handleApiResponse } from './utils';
 
 /**
@@ -31,6 +36,23 @@ const buildPaginationQueryString = (params?: LogsPaginationParams): string => {
   return queryParams.toString();
 };
 
+/**
+ * Filters for full-text log search. `since` and `until` bound the
+ * execution start time (ISO 8601).
+ */
+export interface LogSearchParams {
+  q: string;
+  projectId?: string;
+  taskId?: string;
+  taskAttemptId?: string;
+  executor?: string;
+  entryType?: LogSearchEntryType;
+  since?: string;
+  until?: string;
+  limit?: number;
+  offset?: number;
+}
+
 export const logsApi = {
   /**
    * Get paginated logs for an execution process.
@@ -83,4 +105,30 @@ export const logsApi = {
     const response = await makeRequest(url);
     return handleApiResponse<PaginatedLogs>(response);
   },
+
+  /**
+   * Search the normalized logs of finished local executions, best matches
+   * first. Every word in `q` must match.
+   */
+  search: async (params: LogSearchParams): Promise<LogSearchHit[]> => {
+    const queryParams = new URLSearchParams({ q: params.q });
+    const optional: [string, string | number | undefined][] = [
+      ['project_id', params.projectId],
+      ['task_id', params.taskId],
+      ['task_attempt_id', params.taskAttemptId],
+      ['executor', params.executor],
+      ['entry_type', params.entryType],
+      ['since', params.since],
+      ['until', params.until],
+      ['limit', params.limit],
+      ['offset', params.offset],
+    ];
+    for (const [key, value] of optional) {
+      if (value !== undefined) {
+        queryParams.set(key, value.toString());
+      }
+    }
+    const response = await makeRequest(`/api/logs/search?${queryParams}`);
+    return handleApiResponse<LogSearchHit[]>(response);
+  },
 };
@@ -6,6 +6,9 @@ import {
   McpServerQuery,
   UpdateMcpServersBody,
   GetMcpServerResponse,
+  McpClientToken,
+  CreateMcpClientToken,
+  IssuedMcpClientToken,
 } from 'shared/types';
 
 import { makeRequest, handleApiResponse, ApiError } from './utils';
@@ -45,3 +48,33 @@ export const mcpServersApi = {
     }
   },
 };
+
+/**
+ * MCP client tokens API - bearer tokens for clients of the /mcp HTTP endpoint.
+ */
+export const mcpTokensApi = {
+  /** List issued tokens, including revoked ones */
+  list: async (): Promise<McpClientToken[]> => {
+    const response = await makeRequest('/api/mcp/tokens');
+    return handleApiResponse<McpClientToken[]>(response);
+  },
+
+  /** Issue a token. The returned token is not shown again. */
+  create: async (
+    data: CreateMcpClientToken
+  ): Promise<IssuedMcpClientToken> => {
+    const response = await makeRequest('/api/mcp/tokens', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<IssuedMcpClientToken>(response);
+  },
+
+  /** Revoke a token */
+  revoke: async (id: string): Promise<void> => {
+    const response = await makeRequest(`/api/mcp/tokens/${id}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
@@ -2,7 +2,11 @@
  * Terminal API namespace - Terminal session management endpoints.
  */
 
-import type { SessionInfo, CreateSessionResponse } from 'shared/types';
+import type {
+  SessionInfo,
+  CreateSessionResponse,
+  TerminalRecording,
+} from 'shared/types';
 import { makeRequest, handleApiResponse } from './utils';
 
 /**
@@ -10,6 +14,15 @@ import { makeRequest, handleApiResponse } from './utils';
  */
 export interface CreateTerminalSessionRequest {
   working_dir: string;
+  task_attempt_id?: string;
+  record?: boolean;
+}
+
+export interface CreateTerminalSessionOptions {
+  /** Task attempt the session is for; required to record it */
+  taskAttemptId?: string;
+  /** Record the session, unless it is already being recorded */
+  record?: boolean;
 }
 
 export const terminalApi = {
@@ -17,11 +30,20 @@ export const terminalApi = {
    * Create a new terminal session in the specified working directory.
    *
    * @param workingDir - The working directory for the terminal session
+   * @param options - Task attempt to tie the session to, and whether to record it
    */
-  createSession: async (workingDir: string): Promise<CreateSessionResponse> => {
+  createSession: async (
+    workingDir: string,
+    options?: CreateTerminalSessionOptions
+  ): Promise<CreateSessionResponse> => {
+    const body: CreateTerminalSessionRequest = {
+      working_dir: workingDir,
+      task_attempt_id: options?.taskAttemptId,
+      record: options?.record,
+    };
     const response = await makeRequest('/api/terminal/sessions', {
       method: 'POST',
-      body: JSON.stringify({ working_dir: workingDir }),
+      body: JSON.stringify(body),
     });
     return handleApiResponse<CreateSessionResponse>(response);
   },
@@ -56,6 +78,54 @@ export const terminalApi = {
     return handleApiResponse<void>(response);
   },
 
+  /**
+   * Stop recording a terminal session. The session keeps running.
+   *
+   * @param sessionId - The terminal session ID
+   */
+  stopRecording: async (sessionId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/terminal/sessions/${sessionId}/recording`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  /**
+   * List the terminal recordings of a task attempt, newest first.
+   *
+   * @param taskAttemptId - The task attempt ID
+   */
+  listRecordings: async (
+    taskAttemptId: string
+  ): Promise<TerminalRecording[]> => {
+    const response = await makeRequest(
+      `/api/terminal/recordings?task_attempt_id=${encodeURIComponent(taskAttemptId)}`
+    );
+    return handleApiResponse<TerminalRecording[]>(response);
+  },
+
+  /**
+   * Delete a finished recording.
+   *
+   * @param recordingId - The recording ID
+   */
+  deleteRecording: async (recordingId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/terminal/recordings/${recordingId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  /**
+   * Get the URL of a recording's asciicast file, for replay.
+   *
+   * @param recordingId - The recording ID
+   */
+  getRecordingCastUrl: (recordingId: string): string =>
+    `/api/terminal/recordings/${recordingId}/cast`,
+
   /**
    * Get the WebSocket URL for connecting to a terminal session.
    *
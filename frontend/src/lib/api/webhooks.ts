@@ -6,6 +6,7 @@ import type {
   WebhookResponse,
   CreateWebhook,
   UpdateWebhook,
+  WebhookDelivery,
 } from 'shared/types';
 
 import { makeRequest, handleApiResponse } from './utils';
@@ -81,4 +82,22 @@ export const webhooksApi = {
     });
     return handleApiResponse<WebhookTestResult>(response);
   },
+
+  /** List a webhook's most recent deliveries */
+  listDeliveries: async (id: string): Promise<WebhookDelivery[]> => {
+    const response = await makeRequest(`/api/webhooks/${id}/deliveries`);
+    return handleApiResponse<WebhookDelivery[]>(response);
+  },
+
+  /** Send a previous delivery's payload again */
+  redeliver: async (
+    id: string,
+    deliveryId: string
+  ): Promise<WebhookDelivery> => {
+    const response = await makeRequest(
+      `/api/webhooks/${id}/deliveries/${deliveryId}/redeliver`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<WebhookDelivery>(response);
+  },
 };
@@ -37,23 +37,55 @@ import {
   Info,
   Loader2,
   Plus,
+  RotateCcw,
   Trash2,
   Zap,
 } from 'lucide-react';
 import { webhooksApi } from '@/lib/api';
 import { ConfirmDialog } from '@/components/dialogs';
-import type { WebhookResponse, WebhookEventType, CreateWebhook, UpdateWebhook } from 'shared/types';
+import type {
+  WebhookResponse,
+  WebhookEventType,
+  CreateWebhook,
+  UpdateWebhook,
+  WebhookDelivery,
+  WebhookDeliveryStatus,
+} from 'shared/types';
 
 const ALL_EVENTS: WebhookEventType[] = [
   'approval_request',
   'pending_question',
   'executor_finish',
+  'budget_exceeded',
+  'task_created',
+  'task_status_changed',
+  'task_archived',
+  'attempt_created',
+  'setup_script_failed',
+  'merge_completed',
+  'pr_opened',
+  'pr_merged',
+  'rebase_conflict',
+  'node_online',
+  'node_offline',
 ];
 
 const EVENT_LABELS: Record<WebhookEventType, string> = {
   approval_request: 'Approval Request',
   pending_question: 'Pending Question',
   executor_finish: 'Executor Finish',
+  budget_exceeded: 'Budget Exceeded',
+  task_created: 'Task Created',
+  task_status_changed: 'Task Status Changed',
+  task_archived: 'Task Archived',
+  attempt_created: 'Attempt Created',
+  setup_script_failed: 'Setup Script Failed',
+  merge_completed: 'Merge Completed',
+  pr_opened: 'PR Opened',
+  pr_merged: 'PR Merged',
+  rebase_conflict: 'Rebase Conflict',
+  node_online: 'Node Online',
+  node_offline: 'Node Offline',
 };
 
 const VARIABLE_GROUPS = [
@@ -89,6 +121,40 @@ const VARIABLE_GROUPS = [
       'finish.pr_url', 'finish.pr_number',
     ],
   },
+  {
+    label: 'Budget Exceeded',
+    vars: [
+      'budget.scope', 'budget.limit', 'budget.max_tokens', 'budget.max_cost_usd',
+      'budget.period', 'budget.spent_tokens', 'budget.spent_cost_usd', 'budget.message',
+    ],
+  },
+  {
+    label: 'Task Status Changed',
+    vars: ['status_change.from', 'status_change.to'],
+  },
+  {
+    label: 'Setup Script Failed',
+    vars: ['setup_script.exit_code', 'setup_script.message'],
+  },
+  {
+    label: 'Merge Completed',
+    vars: ['merge.commit', 'merge.target_branch'],
+  },
+  {
+    label: 'PR Opened / Merged',
+    vars: ['pr.url', 'pr.number', 'pr.target_branch', 'pr.merge_commit_sha', 'pr.merged_at'],
+  },
+  {
+    label: 'Rebase Conflict',
+    vars: ['rebase.onto_branch', 'rebase.conflicted_files', 'rebase.message'],
+  },
+  {
+    label: 'Node Online / Offline',
+    vars: [
+      'node.id', 'node.name', 'node.organization_id', 'node.public_url',
+      'node.status', 'node.previous_status',
+    ],
+  },
 ];
 
 const TOTAL_VAR_COUNT = VARIABLE_GROUPS.reduce((n, g) => n + g.vars.length, 0);
@@ -101,6 +167,97 @@ interface WebhookTestResult {
   error?: string;
 }
 
+const DELIVERY_STATUS_VARIANT: Record<
+  WebhookDeliveryStatus,
+  'default' | 'secondary' | 'destructive' | 'outline'
+> = {
+  pending: 'secondary',
+  succeeded: 'default',
+  failed: 'destructive',
+  dropped: 'outline',
+};
+
+function WebhookDeliveries({ webhookId }: { webhookId: string }) {
+  const queryClient = useQueryClient();
+  const [open, setOpen] = useState(false);
+
+  const { data: deliveries = [], isLoading } = useQuery({
+    queryKey: ['webhooks', 'deliveries', webhookId],
+    queryFn: () => webhooksApi.listDeliveries(webhookId),
+    enabled: open,
+    // Pending deliveries are retried in the background; poll until they settle
+    refetchInterval: (query) =>
+      query.state.data?.some((d) => d.status === 'pending') ? 2000 : false,
+  });
+
+  const redeliverMutation = useMutation({
+    mutationFn: (delivery: WebhookDelivery) =>
+      webhooksApi.redeliver(webhookId, delivery.id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['webhooks', 'deliveries', webhookId] });
+    },
+    onError: (err) => {
+      console.error('Failed to redeliver webhook:', err);
+    },
+  });
+
+  return (
+    <div className="space-y-2">
+      <button
+        type="button"
+        className="flex items-center gap-1 text-sm font-medium"
+        onClick={() => setOpen((v) => !v)}
+      >
+        {open ? <ChevronDown className="h-4 w-4" /> : <ChevronRight className="h-4 w-4" />}
+        Recent Deliveries
+      </button>
+      {open && (
+        <div className="space-y-1 max-h-64 overflow-y-auto">
+          {isLoading && <Loader2 className="h-4 w-4 animate-spin" />}
+          {!isLoading && deliveries.length === 0 && (
+            <p className="text-xs text-muted-foreground">No deliveries yet.</p>
+          )}
+          {deliveries.map((d) => (
+            <details key={d.id} className="rounded border border-border px-2 py-1">
+              <summary className="flex items-center gap-2 cursor-pointer text-xs">
+                <Badge variant={DELIVERY_STATUS_VARIANT[d.status]} className="text-xs">
+                  {d.status}
+                </Badge>
+                <span>{EVENT_LABELS[d.event_type]}</span>
+                {d.response_status !== null && <span>HTTP {String(d.response_status)}</span>}
+                {d.latency_ms !== null && <span>{String(d.latency_ms)}ms</span>}
+                <span className="text-muted-foreground">
+                  {String(d.attempt_count)} attempt{Number(d.attempt_count) === 1 ? '' : 's'}
+                </span>
+                <span className="ml-auto text-muted-foreground">
+                  {new Date(d.created_at).toLocaleString()}
+                </span>
+                {d.status !== 'pending' && (
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    className="h-6 w-6 p-0"
+                    title="Redeliver"
+                    disabled={redeliverMutation.isPending}
+                    onClick={(e) => {
+                      e.preventDefault();
+                      redeliverMutation.mutate(d);
+                    }}
+                  >
+                    <RotateCcw className="h-3 w-3" />
+                  </Button>
+                )}
+              </summary>
+              {d.error && <p className="text-xs text-destructive mt-1">{d.error}</p>}
+              <pre className="text-xs mt-1 whitespace-pre-wrap break-all">{d.request_body}</pre>
+            </details>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
+
 interface WebhookFormProps {
   initial?: WebhookResponse;
   projectId?: string;
@@ -515,6 +672,7 @@ function WebhookForm({ initial, projectId, onClose, onSaved }: WebhookFormProps)
               </AlertDescription>
             </Alert>
           )}
+          <WebhookDeliveries webhookId={initial.id} />
         </div>
       )}
 
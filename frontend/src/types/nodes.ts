@@ -13,6 +13,7 @@ export interface NodeCapabilities {
   version: string;
   git_commit: string;
   git_branch: string;
+  labels: string[];
 }
 
 export interface Node {
@@ -15,6 +15,7 @@ import { initOAuth } from '@/api'
 const NodesPage = lazy(() => import('./pages/NodesPage').then(m => ({ default: m.NodesPage })))
 const ProcessesPage = lazy(() => import('./pages/ProcessesPage').then(m => ({ default: m.ProcessesPage })))
 const BoardPage = lazy(() => import('./pages/BoardPage').then(m => ({ default: m.BoardPage })))
+const ApprovalsPage = lazy(() => import('./pages/ApprovalsPage').then(m => ({ default: m.ApprovalsPage })))
 
 function RootRedirect() {
   const { isSignedIn, isLoaded } = useProfile()
@@ -250,6 +251,7 @@ export function createRoutes() {
         { path: '/nodes', element: <ErrorBoundary><Suspense fallback={<div className="p-8">Loading nodes...</div>}><NodesPage /></Suspense></ErrorBoundary> },
         { path: '/tasks', element: <ErrorBoundary><Suspense fallback={<div className="p-8">Loading tasks...</div>}><BoardPage /></Suspense></ErrorBoundary> },
         { path: '/processes', element: <ErrorBoundary><Suspense fallback={<div className="p-8">Loading processes...</div>}><ProcessesPage /></Suspense></ErrorBoundary> },
+        { path: '/approvals', element: <ErrorBoundary><Suspense fallback={<div className="p-8">Loading approvals...</div>}><ApprovalsPage /></Suspense></ErrorBoundary> },
         { path: '*', element: <NotFoundPage /> },
       ],
     },
@@ -22,6 +22,9 @@ export { swarmProjectsApi } from './swarmProjects';
 // Swarm Labels API
 export { swarmLabelsApi } from './swarmLabels';
 
+// Swarm Approvals API
+export { swarmApprovalsApi } from './swarmApprovals';
+
 // Templates API (stub for hive)
 export { templatesApi } from './templates';
 
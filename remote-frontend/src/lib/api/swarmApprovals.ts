@@ -0,0 +1,51 @@
+/**
+ * Swarm Approvals API namespace.
+ */
+
+import type {
+  ListSwarmApprovalsResponse,
+  RespondSwarmApprovalRequest,
+  SwarmApproval,
+  SwarmApprovalResponse,
+} from '@/types/swarm';
+
+import { ApiError, makeRequest } from './utils';
+
+export const swarmApprovalsApi = {
+  /**
+   * List approvals forwarded by the organization's nodes, pending only unless
+   * `includeResolved` is set.
+   */
+  list: async (
+    organizationId: string,
+    includeResolved = false
+  ): Promise<SwarmApproval[]> => {
+    const response = await makeRequest(
+      `/v1/swarm/approvals?organization_id=${encodeURIComponent(organizationId)}&include_resolved=${includeResolved}`
+    );
+    if (!response.ok) {
+      const body = await response.text();
+      throw new ApiError(body || 'Request failed', response.status, response);
+    }
+    const result = await response.json() as ListSwarmApprovalsResponse;
+    return result.approvals;
+  },
+
+  /**
+   * Answer an approval; the hive relays the answer to the node that raised it.
+   */
+  respond: async (
+    approvalId: string,
+    data: RespondSwarmApprovalRequest
+  ): Promise<SwarmApprovalResponse> => {
+    const response = await makeRequest(`/v1/swarm/approvals/${approvalId}/respond`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    if (!response.ok) {
+      const body = await response.text();
+      throw new ApiError(body || 'Request failed', response.status, response);
+    }
+    return await response.json() as SwarmApprovalResponse;
+  },
+};
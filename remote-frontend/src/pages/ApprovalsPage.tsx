@@ -0,0 +1,137 @@
+import { useState } from 'react';
+import { useSearchParams } from 'react-router-dom';
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { formatDistanceToNow } from 'date-fns';
+import { organizationsApi } from '@/lib/api/organizations';
+import { swarmApprovalsApi } from '@/lib/api/swarmApprovals';
+import { parseErrorMessage } from '@/lib/errors';
+import { Button } from '@/components/ui/button';
+import { Badge } from '@/components/ui/badge';
+import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card';
+import { ErrorBanner } from '@/components/ui/ErrorBanner';
+import type { RespondSwarmApprovalRequest, SwarmApproval } from '@/types/swarm';
+
+const REFETCH_INTERVAL_MS = 10_000;
+
+/**
+ * Approval requests and agent questions forwarded by the organization's nodes.
+ *
+ * Any member can answer; the hive relays the answer to the node that raised
+ * the request. Escalation emails link here with `?organization_id=`, otherwise
+ * the first org is used (same placeholder selection as `NodesPage`).
+ */
+export function ApprovalsPage() {
+  const [searchParams] = useSearchParams();
+  const orgsQ = useQuery({ queryKey: ['orgs'], queryFn: organizationsApi.list });
+  const orgId = searchParams.get('organization_id') ?? orgsQ.data?.[0]?.id;
+
+  const approvalsQ = useQuery({
+    queryKey: ['swarmApprovals', orgId],
+    queryFn: () => swarmApprovalsApi.list(orgId!),
+    enabled: !!orgId,
+    refetchInterval: REFETCH_INTERVAL_MS,
+  });
+  const approvals = approvalsQ.data ?? [];
+
+  const isError = orgsQ.isError || approvalsQ.isError;
+
+  return (
+    <div className="p-6 space-y-4">
+      {isError && <ErrorBanner message="Failed to load approvals. Check your connection and try again." />}
+      <h1 className="text-lg font-semibold">Approvals</h1>
+      {approvalsQ.isSuccess && approvals.length === 0 && (
+        <p className="text-sm text-muted-foreground">No agent is waiting for an answer.</p>
+      )}
+      {approvals.map((approval) => (
+        <ApprovalCard key={approval.id} approval={approval} />
+      ))}
+    </div>
+  );
+}
+
+function ApprovalCard({ approval }: { approval: SwarmApproval }) {
+  const queryClient = useQueryClient();
+  const [answers, setAnswers] = useState<Record<string, string>>({});
+  const [error, setError] = useState<string | null>(null);
+  const [notice, setNotice] = useState<string | null>(null);
+
+  const respondMutation = useMutation({
+    mutationFn: (data: RespondSwarmApprovalRequest) =>
+      swarmApprovalsApi.respond(approval.id, data),
+    onSuccess: (response) => {
+      setError(null);
+      if (!response.delivered) {
+        setNotice(`${approval.node_name} is offline; it will receive the answer when it reconnects.`);
+      }
+      queryClient.invalidateQueries({ queryKey: ['swarmApprovals', approval.organization_id] });
+    },
+    onError: (err) => setError(parseErrorMessage(err)),
+  });
+
+  const questions = approval.questions ?? [];
+  const allAnswered = questions.every((q) => answers[q.header]);
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="text-base flex items-center gap-2 flex-wrap">
+          <span>{approval.task_title}</span>
+          <Badge variant="secondary">{approval.node_name}</Badge>
+          {approval.escalated_at && <Badge variant="destructive">Escalated</Badge>}
+        </CardTitle>
+        <p className="text-xs text-muted-foreground">
+          {questions.length > 0 ? 'Question' : approval.tool_name} ·{' '}
+          {formatDistanceToNow(new Date(approval.created_at), { addSuffix: true })} · times out{' '}
+          {formatDistanceToNow(new Date(approval.timeout_at), { addSuffix: true })}
+        </p>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {questions.length > 0 ? (
+          questions.map((q) => (
+            <label key={q.header} className="block space-y-1 text-sm">
+              <span>{q.question}</span>
+              <select
+                className="block w-full border rounded p-2 bg-background"
+                value={answers[q.header] ?? ''}
+                onChange={(e) => setAnswers((prev) => ({ ...prev, [q.header]: e.target.value }))}
+              >
+                <option value="" disabled>Choose an answer</option>
+                {q.options.map((o) => (
+                  <option key={o.label} value={o.label}>{o.label}</option>
+                ))}
+              </select>
+            </label>
+          ))
+        ) : (
+          <pre className="text-xs bg-muted rounded p-2 overflow-x-auto">
+            {JSON.stringify(approval.tool_input, null, 2)}
+          </pre>
+        )}
+        {error && <p className="text-sm text-destructive">{error}</p>}
+        {notice && <p className="text-sm text-muted-foreground">{notice}</p>}
+        <div className="flex gap-2">
+          <Button
+            size="sm"
+            disabled={respondMutation.isPending || !allAnswered}
+            onClick={() =>
+              respondMutation.mutate({
+                status: { status: 'approved' },
+                answers: questions.length > 0 ? answers : null,
+              })
+            }
+          >
+            {questions.length > 0 ? 'Send answers' : 'Approve'}
+          </Button>
+          <Button
+            size="sm"
+            variant="destructive"
+            disabled={respondMutation.isPending}
+            onClick={() => respondMutation.mutate({ status: { status: 'denied' } })}
+          >
+            Deny
+          </Button>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -1,7 +1,7 @@
-// Types for Swarm Projects, Labels, and Templates
+// Types for Swarm Projects, Labels, Templates, and Approvals
 // These are organization-wide entities managed through the Hive
 
-import type { JsonValue } from 'shared/types';
+import type { ApprovalStatus, JsonValue, Question } from 'shared/types';
 
 // =====================
 // Swarm Projects
@@ -177,3 +177,44 @@ export interface ListSwarmTemplatesResponse {
 export interface SwarmTemplateResponse {
   template: SwarmTemplate;
 }
+
+// =====================
+// Swarm Approvals
+// =====================
+
+export interface SwarmApproval {
+  id: string;
+  organization_id: string;
+  node_id: string;
+  node_name: string;
+  approval_id: string; // the approval's id on the node that raised it
+  shared_task_id: string | null;
+  local_attempt_id: string | null;
+  local_execution_process_id: string;
+  task_title: string;
+  tool_name: string;
+  tool_input: JsonValue;
+  questions: Question[] | null;
+  status: ApprovalStatus;
+  answers: Record<string, string> | null;
+  responded_by: string | null;
+  responded_at: string | null;
+  delivered_at: string | null;
+  escalated_at: string | null;
+  created_at: string;
+  timeout_at: string;
+}
+
+export interface RespondSwarmApprovalRequest {
+  status: ApprovalStatus;
+  answers?: Record<string, string> | null;
+}
+
+export interface ListSwarmApprovalsResponse {
+  approvals: SwarmApproval[];
+}
+
+export interface SwarmApprovalResponse {
+  approval: SwarmApproval;
+  delivered: boolean; // false while the raising node is offline
+}
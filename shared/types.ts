@@ -68,6 +68,26 @@ clone_url: string | null, setup_script: string | null, dev_script: string | null
 
 export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, };
 
+export type ProjectVerification = { 
+/**
+ * Script run after each coding agent turn; verification is disabled when unset
+ */
+verification_script: string | null, 
+/**
+ * Automatic follow-ups allowed before a failing verification is left for review
+ */
+verification_max_retries: number, };
+
+export type UpdateProjectVerification = { verification_script: string | null, verification_max_retries: number | null, };
+
+export type ProjectScriptLanguage = { 
+/**
+ * Interpreter for the project's scripts; `Shebang` reads it from each script's `#!` line
+ */
+script_language: ScriptRequestLanguage, };
+
+export type UpdateProjectScriptLanguage = { script_language: ScriptRequestLanguage, };
+
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
@@ -236,23 +256,77 @@ arch: string,
 /**
  * Vibe Kanban version running on the node
  */
-version: string, };
+version: string, 
+/**
+ * Labels tasks can require of the node when dispatched by the hive
+ */
+labels: Array<string>, };
 
-export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
+export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, 
+/**
+ * Set when this action executes a step of a project pipeline
+ */
+pipeline?: PipelineState | null, };
 
 export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, preconfigured: JsonValue, is_toml_config: boolean, };
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "CodingAgentReviewRequest" } & CodingAgentReviewRequest | { "type": "ScriptRequest" } & ScriptRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript" | "PipelineScript" | "VerificationScript" | "CommandRerun";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, };
 
-export type ScriptRequestLanguage = "Bash";
+export type ScriptRequestLanguage = "Bash" | "Python" | "Node" | "PowerShell" | "Shebang";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", QA_MOCK = "QA_MOCK" }
+export type Pipeline = { steps: Array<PipelineStep>, };
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "QA_MOCK": QaMock };
+export type PipelineStep = { name: string, kind: PipelineStepKind, 
+/**
+ * Step to run when this one succeeds. Defaults to the next step in the list.
+ */
+on_success?: string | null, 
+/**
+ * Step to run when this one fails. Defaults to ending the pipeline.
+ */
+on_failure?: string | null, 
+/**
+ * Maximum number of times this step may run in one pipeline run. Unlimited when unset.
+ */
+max_runs?: number | null, };
+
+export type PipelineStepKind = { "type": "coding_agent" } | { "type": "script", script: string, language?: ScriptRequestLanguage | null, } | { "type": "verification", script: string, } | { "type": "follow_up", prompt: string, } | { "type": "review", instructions?: string | null, executor_profile_id?: ExecutorProfileId | null, };
+
+/**
+ * Progress of a running pipeline, attached to the action executing its current step.
+ */
+export type PipelineState = { pipeline: Pipeline, 
+/**
+ * Index of the step the carrying action executes
+ */
+current_step: number, 
+/**
+ * Number of times each step has run so far, including the current one
+ */
+runs: Array<number>, 
+/**
+ * Profile used for agent steps
+ */
+executor_profile_id: ExecutorProfileId, 
+/**
+ * Expanded task prompt used by `coding_agent` steps
+ */
+prompt: string, };
+
+/**
+ * A project's declarative execution pipeline
+ */
+export type ProjectPipeline = { project_id: string, pipeline: Pipeline, enabled: boolean, created_at: string, updated_at: string, };
+
+export type UpsertProjectPipeline = { pipeline: Pipeline, enabled: boolean, };
+
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", QA_MOCK = "QA_MOCK", CUSTOM_AGENT = "CUSTOM_AGENT" }
+
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "QA_MOCK": QaMock } | { "CUSTOM_AGENT": CustomAgent };
 
 export type Template = { id: string, template_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -319,7 +393,7 @@ headers: string, secret: string | null, payload_template: string | null, overrid
 
 export type WebhookResponse = { id: string, project_id: string | null, name: string, url: string, events: Array<WebhookEventType>, headers: { [key in string]?: string }, secret_set: boolean, payload_template: string | null, override_global: boolean, active: boolean, created_at: string, updated_at: string, };
 
-export type WebhookEventType = "approval_request" | "pending_question" | "executor_finish";
+export type WebhookEventType = "approval_request" | "pending_question" | "executor_finish" | "budget_exceeded" | "task_created" | "task_status_changed" | "task_archived" | "attempt_created" | "setup_script_failed" | "merge_completed" | "pr_opened" | "pr_merged" | "rebase_conflict" | "node_online" | "node_offline";
 
 export type CreateWebhook = { name: string, url: string, events: Array<WebhookEventType>, headers: { [key in string]?: string }, secret: string | null, payload_template: string | null, override_global: boolean, active: boolean, };
 
@@ -333,6 +407,45 @@ clear_secret: boolean, payload_template: string | null,
  */
 clear_payload_template: boolean, override_global: boolean | null, active: boolean | null, };
 
+export type WebhookDeliveryStatus = "pending" | "succeeded" | "failed" | "dropped";
+
+export type WebhookDelivery = { id: string, webhook_id: string, event_type: WebhookEventType, 
+/**
+ * JSON body as sent to the endpoint
+ */
+request_body: string, status: WebhookDeliveryStatus, attempt_count: bigint, 
+/**
+ * HTTP status of the latest attempt; unset when no response was received
+ */
+response_status: bigint | null, latency_ms: bigint | null, error: string | null, 
+/**
+ * The delivery this one was manually resent from
+ */
+redelivery_of: string | null, created_at: string, updated_at: string, };
+
+export type McpToolAccess = "read_only" | "read_write";
+
+export type McpClientToken = { id: string, name: string, 
+/**
+ * First characters of the token, to tell tokens apart
+ */
+token_prefix: string, 
+/**
+ * Projects the client may access; `None` allows every project
+ */
+project_ids: Array<string> | null, access: McpToolAccess, created_at: string, last_used_at: string | null, revoked_at: string | null, };
+
+export type CreateMcpClientToken = { name: string, 
+/**
+ * Projects the client may access; omit to allow every project
+ */
+project_ids: Array<string> | null, access: McpToolAccess, };
+
+/**
+ * A newly issued token. `token` is only returned here; just its hash is stored.
+ */
+export type IssuedMcpClientToken = { client: McpClientToken, token: string, };
+
 export type TaskVariable = { id: string, task_id: string, name: string, value: string, created_at: string, updated_at: string, };
 
 export type CreateTaskVariable = { name: string, value: string, };
@@ -349,6 +462,13 @@ source_task_id: string,
  */
 inherited: boolean, };
 
+/**
+ * Secret metadata returned by the API. The value itself is never exposed.
+ */
+export type SecretInfo = { name: string, };
+
+export type SetSecretRequest = { value: string, };
+
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, remote_assignee_user_id: string | null, remote_assignee_name: string | null, remote_assignee_username: string | null, remote_version: bigint, remote_last_synced_at: string | null, remote_stream_node_id: string | null, remote_stream_url: string | null, 
@@ -730,7 +850,7 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "QA_MOCK": QaMock } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "QA_MOCK": QaMock } | { "CUSTOM_AGENT": CustomAgent } };
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
@@ -749,13 +869,13 @@ no_exit_on_result?: boolean | null,
  * When disabled, questions fall back to text-based prompts.
  * Defaults to true.
  */
-interactive_questions: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, };
+interactive_questions: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, no_context?: boolean | null, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Gemini = { append_prompt: AppendPrompt, no_context?: boolean | null, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
-export type Amp = { append_prompt: AppendPrompt, no_context?: boolean | null, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Amp = { append_prompt: AppendPrompt, no_context?: boolean | null, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
-export type Codex = { append_prompt: AppendPrompt, no_context?: boolean | null, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, collaboration_mode?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Codex = { append_prompt: AppendPrompt, no_context?: boolean | null, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, collaboration_mode?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -767,18 +887,66 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, no_context?: boolean | null, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, no_context?: boolean | null, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, no_context?: boolean | null, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Copilot = { append_prompt: AppendPrompt, no_context?: boolean | null, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
-export type Opencode = { append_prompt: AppendPrompt, no_context?: boolean | null, model?: string | null, agent?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Opencode = { append_prompt: AppendPrompt, no_context?: boolean | null, model?: string | null, agent?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, no_context?: boolean | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type QwenCode = { append_prompt: AppendPrompt, no_context?: boolean | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
-export type Droid = { append_prompt: AppendPrompt, no_context?: boolean | null, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Droid = { append_prompt: AppendPrompt, no_context?: boolean | null, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
 export type QaMock = { append_prompt: AppendPrompt, };
 
+export type CustomAgent = { append_prompt: AppendPrompt, no_context?: boolean | null, command: string, prompt_delivery: PromptDelivery, prompt_args?: Array<string> | null, session_id_regex?: string | null, follow_up_args?: Array<string> | null, log_format: CustomLogFormat, jsonl_mapping?: JsonlFieldMapping | null, mcp_config_path?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+
+export type PromptDelivery = "stdin" | "argv" | "file";
+
+export type CustomLogFormat = "plain-text" | "jsonl";
+
+export type JsonlFieldMapping = { 
+/**
+ * Pointer to the entry's text
+ */
+content: string, 
+/**
+ * Pointer to a field that discriminates the kind of line
+ */
+kind?: string | null, 
+/**
+ * `kind` values rendered as thinking
+ */
+thinking_kinds?: Array<string>, 
+/**
+ * `kind` values rendered as tool calls
+ */
+tool_kinds?: Array<string>, 
+/**
+ * `kind` values rendered as errors
+ */
+error_kinds?: Array<string>, 
+/**
+ * `kind` values rendered as system messages
+ */
+system_kinds?: Array<string>, 
+/**
+ * `kind` values that are dropped from the conversation
+ */
+ignored_kinds?: Array<string>, 
+/**
+ * Pointer to the tool name for tool lines
+ */
+tool_name?: string | null, 
+/**
+ * Pointer to the tool arguments for tool lines
+ */
+tool_arguments?: string | null, 
+/**
+ * Pointer to the session id, checked on every line until one is found
+ */
+session_id?: string | null, };
+
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
@@ -805,6 +973,8 @@ executor_profile_id: ExecutorProfileId, };
 
 export type CodingAgentReviewTarget = { "type": "uncommitted_changes" } | { "type": "base_branch", branch: string, } | { "type": "commit", sha: string, title: string | null, } | { "type": "custom", instructions: string, };
 
+export type SessionImportSource = { "type": "session_id", session_id: string, } | { "type": "file", path: string, };
+
 export type CreateTaskAttemptBody = { task_id: string, 
 /**
  * Executor profile specification
@@ -821,6 +991,12 @@ target_node_id: string | null,
  */
 use_parent_worktree: boolean | null, };
 
+export type ImportSessionBody = { task_id: string, 
+/**
+ * Executor profile follow-ups will run with; its executor must match the session's agent
+ */
+executor_profile_id: ExecutorProfileId, base_branch: string, source: SessionImportSource, };
+
 export type CreateReviewAttempt = { variant: string | null, target?: CodingAgentReviewTarget, };
 
 export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
@@ -910,7 +1086,227 @@ completion_message?: string, };
 
 export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "pipelinestep" | "commandrerun";
+
+export type AttemptGroup = { id: string, task_id: string, 
+/**
+ * The promoted attempt, once a winner has been chosen
+ */
+winner_attempt_id: string | null, created_at: string, updated_at: string, };
+
+export type AttemptGroupMember = { task_attempt_id: string, attempt_group_id: string, executor_profile_id: ExecutorProfileId, 
+/**
+ * True once the attempt lost and its worktree was cleaned up
+ */
+discarded: boolean, created_at: string, };
+
+export type AttemptDiffStats = { files_changed: number, additions: number, deletions: number, };
+
+export type AttemptScriptResult = { context: ScriptContext, status: ExecutionProcessStatus, exit_code: bigint | null, };
+
+export type AttemptTokenUsage = { input_tokens: bigint, cached_input_tokens: bigint, output_tokens: bigint, 
+/**
+ * Only reported by some agents
+ */
+total_cost_usd: number | null, };
+
+export type AttemptComparison = { task_attempt: TaskAttempt, executor_profile_id: ExecutorProfileId, discarded: boolean, is_winner: boolean, 
+/**
+ * Status of the latest coding agent run
+ */
+agent_status: ExecutionProcessStatus | null, 
+/**
+ * Changes against the target branch; `None` when they could not be computed
+ */
+diff_stats: AttemptDiffStats | null, script_results: Array<AttemptScriptResult>, 
+/**
+ * Total run time of the attempt's processes, counting running ones up to now
+ */
+duration_seconds: bigint, token_usage: AttemptTokenUsage | null, };
+
+export type AttemptGroupComparison = { group: AttemptGroup, attempts: Array<AttemptComparison>, };
+
+export type CreateAttemptGroupRequest = { task_id: string, base_branch: string, 
+/**
+ * One attempt is started per profile
+ */
+executor_profile_ids: Array<ExecutorProfileId>, };
+
+export type PromoteAttemptRequest = { task_attempt_id: string, };
+
+export type ExecutionProcessUsage = { execution_process_id: string, 
+/**
+ * Base coding agent that ran the process, e.g. `CLAUDE_CODE`
+ */
+executor: string, input_tokens: bigint, cached_input_tokens: bigint, output_tokens: bigint, reasoning_tokens: bigint, 
+/**
+ * Only reported by some agents
+ */
+total_cost_usd: number | null, created_at: Date, updated_at: Date, };
+
+export type UsagePeriod = "day" | "week";
+
+export type UsageGroupBy = "executor" | "project" | "task";
+
+/**
+ * Usage summed over one group, optionally within one day or week.
+ *
+ * Only the key matching the requested grouping is set.
+ */
+export type UsageRollup = { 
+/**
+ * First day of the bucket as `YYYY-MM-DD`; unset for all-time totals
+ */
+period_start: string | null, executor: string | null, project_id: string | null, task_id: string | null, process_count: bigint, input_tokens: bigint, cached_input_tokens: bigint, output_tokens: bigint, reasoning_tokens: bigint, 
+/**
+ * Sum over the processes that reported a cost; unset when none did
+ */
+total_cost_usd: number | null, };
+
+export type LogSearchEntryType = "user_message" | "user_feedback" | "assistant_message" | "tool_use" | "error_message";
+
+/**
+ * An entry matching a search, with the task it belongs to
+ */
+export type LogSearchHit = { execution_process_id: string, 
+/**
+ * Index of the entry in the execution's normalized conversation
+ */
+entry_index: bigint, entry_type: LogSearchEntryType, tool_name: string | null, subject: string | null, 
+/**
+ * Excerpt around the match, with matched words wrapped in `**`
+ */
+snippet: string, task_attempt_id: string, task_id: string, task_title: string, project_id: string, 
+/**
+ * Base coding agent of the attempt, e.g. `CLAUDE_CODE`
+ */
+executor: string, execution_status: ExecutionProcessStatus, started_at: Date, };
+
+export type TranscriptFormat = "markdown" | "json" | "html";
+
+export type CommandRunStatus = "running" | "awaiting_approval" | "succeeded" | "failed" | "denied";
+
+/**
+ * A command in an attempt's timeline
+ */
+export type TimelineCommand = { execution_process_id: string, 
+/**
+ * Index of the command's entry in the process's normalized conversation
+ */
+entry_index: bigint, command: string, status: CommandRunStatus, 
+/**
+ * Only set when the agent reported an exit code rather than just success or failure
+ */
+exit_code: bigint | null, 
+/**
+ * Tail of the output, cut to a fixed size
+ */
+output: string | null, output_truncated: boolean, 
+/**
+ * Unset for commands of imported processes or ones that ran before commands were timed
+ */
+started_at: string | null, completed_at: string | null, duration_ms: bigint | null, };
+
+/**
+ * A command from the attempt's command timeline to run again in its worktree
+ */
+export type RerunCommandRequest = { execution_process_id: string, entry_index: bigint, };
+
+export type UsageBudget = { id: string, 
+/**
+ * Set for project budgets
+ */
+project_id: string | null, 
+/**
+ * Set for task budgets
+ */
+task_id: string | null, 
+/**
+ * Input plus output tokens
+ */
+max_tokens: bigint | null, 
+/**
+ * Only enforced for agents that report cost; estimated from tokens for Claude Code and
+ * Amp runs that have not reported it yet
+ */
+max_cost_usd: number | null, 
+/**
+ * Count only the current day or week; all usage when unset
+ */
+period: UsagePeriod | null, created_at: string, updated_at: string, };
+
+export type SetUsageBudget = { max_tokens: bigint | null, max_cost_usd: number | null, period: UsagePeriod | null, };
+
+/**
+ * Usage counted against a budget. `cost_usd` includes estimates for runs whose agent has
+ * not reported a cost yet.
+ */
+export type BudgetSpend = { tokens: bigint, cost_usd: number, };
+
+export type BudgetLimit = "tokens" | "cost_usd";
+
+export type UsageBudgetStatus = { budget: UsageBudget, spend: BudgetSpend, 
+/**
+ * The limit that has been reached, if any
+ */
+exceeded: BudgetLimit | null, };
+
+export type ApprovalPolicyOutcome = "allow" | "deny" | "ask";
+
+/**
+ * Kind of action a tool call performs, as recognised from the agent's logs
+ */
+export type ApprovalPolicyAction = "file_read" | "file_edit" | "command_run" | "web_fetch" | "search" | "tool";
+
+export type ApprovalPolicyRule = { id: string, project_id: string, position: bigint, name: string, 
+/**
+ * Wildcard pattern for the tool name; matches any tool when unset
+ */
+tool_name: string | null, 
+/**
+ * Matches any action when unset
+ */
+action: ApprovalPolicyAction | null, 
+/**
+ * Wildcard pattern for the file path, command or domain of the action
+ */
+pattern: string | null, outcome: ApprovalPolicyOutcome, created_at: string, updated_at: string, };
+
+export type ApprovalPolicyRuleInput = { 
+/**
+ * Id of an existing rule to keep; new rules leave it unset
+ */
+id: string | null, name: string, tool_name: string | null, action: ApprovalPolicyAction | null, pattern: string | null, outcome: ApprovalPolicyOutcome, };
+
+/**
+ * The full, ordered rule list of a project
+ */
+export type SetApprovalPolicy = { rules: Array<ApprovalPolicyRuleInput>, };
+
+export type ApprovalPolicyDecision = { id: string, execution_process_id: string, 
+/**
+ * Rule that decided; unset when no rule matched or the rule was removed since
+ */
+rule_id: string | null, rule_name: string | null, tool_name: string, tool_call_id: string, action: ApprovalPolicyAction | null, 
+/**
+ * File path, command or URL the tool call acted on
+ */
+subject: string | null, outcome: ApprovalPolicyOutcome, created_at: string, };
+
+/**
+ * How a policy would have decided one tool call of a past execution
+ */
+export type ApprovalPolicyDryRunEntry = { 
+/**
+ * Index of the tool call in the execution's conversation
+ */
+entry_index: number, tool_name: string, tool_call_id: string | null, action: ApprovalPolicyAction | null, subject: string | null, outcome: ApprovalPolicyOutcome, rule_id: string | null, rule_name: string | null, };
+
+export type ApprovalPolicyDryRunRequest = { execution_process_id: string, 
+/**
+ * Rules to test; the project's saved rules are used when unset
+ */
+rules: Array<ApprovalPolicyRuleInput> | null, };
 
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
@@ -1014,6 +1410,12 @@ answers: { [key in string]?: string }, };
 
 export type PatchType = { "type": "NORMALIZED_ENTRY", "content": NormalizedEntry } | { "type": "STDOUT", "content": string } | { "type": "STDERR", "content": string } | { "type": "DIFF", "content": Diff };
 
+export type ApprovalRequest = { id: string, tool_name: string, tool_input: JsonValue, tool_call_id: string, execution_process_id: string, created_at: string, timeout_at: string, 
+/**
+ * Optional questions for AskUserQuestion requests
+ */
+questions?: Array<Question>, };
+
 export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
 
 export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue, tool_call_id: string, };
@@ -1196,7 +1598,25 @@ cols: number, rows: number,
 /**
  * Whether the session is still active
  */
-active: boolean, };
+active: boolean, 
+/**
+ * Whether the session is being recorded
+ */
+recording: boolean, 
+/**
+ * Number of attached viewers
+ */
+viewers: number, };
+
+export type SessionViewers = { 
+/**
+ * Viewer allowed to send input and resize the terminal; `None` when nobody has control
+ */
+writer_id: string | null, 
+/**
+ * Attached viewers, in the order they attached
+ */
+viewer_ids: Array<string>, };
 
 export type CreateSessionResponse = { 
 /**
@@ -1210,9 +1630,27 @@ session: SessionInfo,
 /**
  * Whether this was a reconnection to an existing session.
  */
-is_reconnect: boolean, };
+is_reconnect: boolean, 
+/**
+ * The session's running recording, if it is being recorded.
+ */
+recording: TerminalRecording | null, };
+
+export type TerminalMessage = { "type": "input", data: string, } | { "type": "resize", cols: number, rows: number, } | { "type": "output", data: string, } | { "type": "exit", code: number | null, } | { "type": "error", message: string, } | { "type": "request_control" } | { "type": "release_control" } | { "type": "grant_control", viewer_id: string, } | { "type": "viewers", viewer_id: string, writer_id: string | null, viewer_ids: Array<string>, };
 
-export type TerminalMessage = { "type": "input", data: string, } | { "type": "resize", cols: number, rows: number, } | { "type": "output", data: string, } | { "type": "exit", code: number | null, } | { "type": "error", message: string, };
+export type TerminalRecording = { id: string, task_attempt_id: string, 
+/**
+ * Terminal session that was recorded
+ */
+session_id: string, 
+/**
+ * Terminal size when recording started
+ */
+cols: bigint, rows: bigint, 
+/**
+ * Unset while the recording runs, or if the server stopped before it ended
+ */
+duration_ms: bigint | null, started_at: string, ended_at: string | null, };
 
 export type WorktreePathResponse = { 
 /**